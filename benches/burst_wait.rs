@@ -0,0 +1,131 @@
+// benches/burst_wait.rs
+// Средний размер пачки и задержка при адаптивном ожидании после неполной пачки
+//
+// Очередь NIC моделируется расписанием прихода пакетов (пуассоновский поток
+// заданной интенсивности). Опрос стоит фиксированное время плюс время на
+// каждый пакет пачки; задержка пакета - от прихода до конца обработки его
+// пачки. Ожидание и часы - те же, что в рабочем потоке.
+//
+// Запуск: cargo bench --bench burst_wait
+
+#[allow(dead_code)]
+#[path = "../src/time/tsc.rs"]
+mod tsc;
+
+/// Часы рабочего потока (`crate::time` в приложении)
+mod time {
+    pub use super::tsc::now_ns;
+}
+
+#[path = "../src/numa/burst_wait.rs"]
+mod burst_wait;
+
+use burst_wait::AdaptiveBurstWait;
+
+const BURST_SIZE: u16 = 32;
+/// Стоимость вызова rx_burst независимо от числа принятых пакетов
+const POLL_COST_NS: u64 = 200;
+/// Стоимость обработки одного пакета
+const PACKET_COST_NS: u64 = 40;
+/// Длительность прогона одной конфигурации
+const RUN_NS: u64 = 500_000_000;
+
+const RATES_MPPS: [f64; 4] = [0.5, 1.0, 2.0, 4.0];
+const MAX_WAITS_NS: [u64; 4] = [0, 500, 1_000, 2_000];
+
+/// Моменты прихода пакетов (нс от начала прогона) с экспоненциальными интервалами
+fn schedule(rate_mpps: f64, seed: u64) -> Vec<u64> {
+    let mean_ns = 1_000.0 / rate_mpps;
+    let mut state = seed;
+    let mut at = 0.0;
+    let mut arrivals = Vec::new();
+
+    while (at as u64) < RUN_NS {
+        // xorshift64, равномерно в (0, 1]
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let uniform = ((state >> 11) as f64 + 1.0) / (1u64 << 53) as f64;
+
+        at += -uniform.ln() * mean_ns;
+        arrivals.push(at as u64);
+    }
+    arrivals
+}
+
+fn spin_for(ns: u64) {
+    let deadline = time::now_ns() + ns;
+    while time::now_ns() < deadline {
+        std::hint::spin_loop();
+    }
+}
+
+struct Outcome {
+    avg_burst: f64,
+    /// Отсортированные задержки пакетов, нс
+    latencies: Vec<u64>,
+}
+
+impl Outcome {
+    fn percentile(&self, p: f64) -> u64 {
+        let index = ((self.latencies.len() - 1) as f64 * p) as usize;
+        self.latencies[index]
+    }
+}
+
+fn run(arrivals: &[u64], max_wait_ns: u64) -> Outcome {
+    let mut burst_wait = AdaptiveBurstWait::new(max_wait_ns);
+    let mut latencies = Vec::with_capacity(arrivals.len());
+    let mut bursts = 0u64;
+    let mut next = 0;
+    let start = time::now_ns();
+
+    while next < arrivals.len() {
+        burst_wait.wait();
+
+        let poll_ns = time::now_ns();
+        spin_for(POLL_COST_NS);
+        let arrived = arrivals[next..].partition_point(|&at| at <= poll_ns - start);
+        let nb_rx = arrived.min(BURST_SIZE as usize);
+
+        spin_for(PACKET_COST_NS * nb_rx as u64);
+        let done_ns = time::now_ns() - start;
+        latencies.extend(arrivals[next..next + nb_rx].iter().map(|&at| done_ns - at));
+        next += nb_rx;
+        if nb_rx > 0 {
+            bursts += 1;
+        }
+
+        burst_wait.update(nb_rx as u16, BURST_SIZE, poll_ns);
+    }
+
+    latencies.sort_unstable();
+    Outcome {
+        avg_burst: arrivals.len() as f64 / bursts as f64,
+        latencies,
+    }
+}
+
+fn main() {
+    tsc::init();
+
+    println!(
+        "{:>9} {:>11} {:>9} {:>8} {:>8} {:>8}",
+        "rate_mpps", "max_wait_ns", "avg_burst", "p50_ns", "p99_ns", "p999_ns"
+    );
+    for rate_mpps in RATES_MPPS {
+        let arrivals = schedule(rate_mpps, 0x9e37_79b9_7f4a_7c15);
+        for max_wait_ns in MAX_WAITS_NS {
+            let outcome = run(&arrivals, max_wait_ns);
+            println!(
+                "{:>9.1} {:>11} {:>9.2} {:>8} {:>8} {:>8}",
+                rate_mpps,
+                max_wait_ns,
+                outcome.avg_burst,
+                outcome.percentile(0.5),
+                outcome.percentile(0.99),
+                outcome.percentile(0.999)
+            );
+        }
+    }
+}
@@ -0,0 +1,43 @@
+// benches/copy.rs
+// copy_small против ptr::copy_nonoverlapping на длинах коротких сообщений
+//
+// Запуск: cargo bench --bench copy
+// Путь AVX собирается, когда build.rs выставляет feature = "avx" (профиль
+// HFEEC_CPU_PROFILE или флаги CPU сборочной машины).
+
+use std::hint::black_box;
+use std::ptr;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+#[allow(dead_code)]
+#[path = "../src/mem/copy.rs"]
+mod copy;
+
+const LENGTHS: [usize; 8] = [8, 24, 48, 64, 96, 128, 192, 256];
+
+fn bench_copy(c: &mut Criterion) {
+    let src = vec![0xa5u8; copy::SMALL_COPY_MAX];
+    let mut dst = vec![0u8; copy::SMALL_COPY_MAX];
+    let mut group = c.benchmark_group("copy");
+
+    for len in LENGTHS {
+        group.bench_with_input(BenchmarkId::new("copy_small", len), &len, |b, &len| {
+            b.iter(|| unsafe { copy::copy_small(dst.as_mut_ptr(), src.as_ptr(), black_box(len)) })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("copy_nonoverlapping", len),
+            &len,
+            |b, &len| {
+                b.iter(|| unsafe {
+                    ptr::copy_nonoverlapping(src.as_ptr(), dst.as_mut_ptr(), black_box(len))
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_copy);
+criterion_main!(benches);
@@ -13,39 +13,26 @@ fn main() {
 
     println!("cargo:rerun-if-env-changed=ENABLE_PGO");
     println!("cargo:rerun-if-env-changed=PGO_MODE");
+    println!("cargo:rerun-if-env-changed=HFEEC_CPU_PROFILE");
 
-    // Get DPDK paths and flags using pkg-config or fallback to defaults
-    let dpdk_include_path = Command::new("pkg-config")
-        .args(["--cflags", "libdpdk"])
-        .output()
-        .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
-        .unwrap_or_else(|_| "-I/usr/local/include/dpdk".to_string());
-
-    let dpdk_libs = Command::new("pkg-config")
-        .args(["--libs", "libdpdk"])
-        .output()
-        .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
-        .unwrap_or_else(|_| {
-            "-lrte_eal -lrte_mempool -lrte_ring -lrte_mbuf -lrte_net -lrte_ethdev".to_string()
-        });
+    // Target CPU profile: named profiles make CI builds run correctly on production hardware
+    let cpu_profile = select_cpu_profile(is_release);
 
-    println!("DPDK lib flags: {}", dpdk_libs);
+    // DPDK is optional: without the `dpdk` feature nothing is linked or compiled against it
+    let with_dpdk = env::var("CARGO_FEATURE_DPDK").is_ok();
 
-    // Standard library search paths for DPDK
-    println!("cargo:rustc-link-search=native=/usr/lib");
-    println!("cargo:rustc-link-search=native=/usr/local/lib");
-    println!("cargo:rustc-link-search=native=/usr/lib/x86_64-linux-gnu");
-    println!("cargo:rustc-link-search=native=/usr/lib/dpdk");
-    println!("cargo:rustc-link-search=native=/usr/lib/x86_64-linux-gnu/dpdk");
-
-    // Add DPDK libraries
-    for lib in dpdk_libs.split_whitespace() {
-        if lib.starts_with("-l") {
-            println!("cargo:rustc-link-lib={}", &lib[2..]);
-        } else if lib.starts_with("-L") {
-            println!("cargo:rustc-link-search=native={}", &lib[2..]);
-        }
+    // Renamed ethdev/mbuf constants are mapped in src/native/compat.h; Rust code only needs
+    // to know about the rx frame length change in 21.11 (dpdk_pre_21_11)
+    println!("cargo:rustc-check-cfg=cfg(dpdk_pre_21_11)");
+    let dpdk_version = dpdk_version();
+    if with_dpdk {
+        check_dpdk_version(&dpdk_version);
     }
+    let dpdk_include_path = if with_dpdk {
+        link_dpdk()
+    } else {
+        String::new()
+    };
 
     // Check if HugePages are available and enable feature flag if so
     let has_hugepages = check_hugepages_available();
@@ -60,12 +47,36 @@ fn main() {
         println!("cargo:rustc-link-lib=numa");
     }
 
-    // Check hardware capabilities (AVX, AVX2, AVX512)
-    let cpu_features = detect_cpu_features();
-    for feature in cpu_features {
+    // Hardware capabilities (AVX, AVX2, AVX512) of the target profile, or of this host for native
+    let cpu_features = match &cpu_profile {
+        Some(profile) if profile.name != "native" => {
+            profile.features.iter().map(|f| f.to_string()).collect()
+        }
+        _ => detect_cpu_features(),
+    };
+    println!(
+        "cargo:rustc-check-cfg=cfg(feature, values(\"sse4_2\", \"avx\", \"avx2\", \"avx512\", \"aes_ni\", \"rdrand\", \"rdseed\"))"
+    );
+    for feature in &cpu_features {
         println!("cargo:rustc-cfg=feature=\"{}\"", feature);
     }
 
+    // Embed build provenance so the running binary can report exactly how it was built
+    emit_build_info(&BuildInfo {
+        profile: &profile,
+        march: cpu_profile.as_ref().map_or("default", |p| p.march),
+        pgo_mode: if enable_pgo { &pgo_mode } else { "off" },
+        has_hugepages,
+        has_numa,
+        cpu_features: &cpu_features,
+        dpdk_version: &dpdk_version,
+    });
+
+    if !with_dpdk {
+        println!("cargo:rerun-if-changed=build.rs");
+        return;
+    }
+
     // Compile native code
     let mut compiler = cc::Build::new();
     compiler.file("src/native/dpdk.c");
@@ -85,12 +96,14 @@ fn main() {
     // Required DPDK config header
     compiler.flag("-include").flag("rte_config.h");
 
+    // CPU-specific optimizations for the selected profile
+    if let Some(cpu_profile) = &cpu_profile {
+        compiler.flag(format!("-march={}", cpu_profile.march));
+        compiler.flag(format!("-mtune={}", cpu_profile.mtune));
+    }
+
     // Release mode optimizations
     if is_release {
-        // CPU-specific optimizations
-        compiler.flag("-march=native"); // Optimize for the current CPU
-        compiler.flag("-mtune=native"); // Fine-tune for the current CPU
-
         // Aggressive optimization flags
         compiler.flag("-O3"); // Maximum optimization level
         compiler.flag("-flto"); // Link-time optimization
@@ -138,6 +151,10 @@ fn main() {
     // Finally, compile the native code
     compiler.compile("dpdk");
 
+    // Rust bindings for the ethdev structures of the installed DPDK
+    #[cfg(feature = "dpdk")]
+    generate_bindings(&dpdk_include_path);
+
     // Set up linker optimizations for the Rust side
     if is_release {
         println!("cargo:rustc-link-arg=-flto"); // Link-time optimization
@@ -145,9 +162,217 @@ fn main() {
 
     // Trigger rebuild if native source or build script changes
     println!("cargo:rerun-if-changed=src/native/dpdk.c");
+    println!("cargo:rerun-if-changed=src/native/bindings.h");
+    println!("cargo:rerun-if-changed=src/native/compat.h");
     println!("cargo:rerun-if-changed=build.rs");
 }
 
+/// Emit DPDK link flags (pkg-config or fallback defaults) and return its compiler flags
+fn link_dpdk() -> String {
+    // Get DPDK paths and flags using pkg-config or fallback to defaults
+    let dpdk_include_path = Command::new("pkg-config")
+        .args(["--cflags", "libdpdk"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+        .unwrap_or_else(|_| "-I/usr/local/include/dpdk".to_string());
+
+    let dpdk_libs = Command::new("pkg-config")
+        .args(["--libs", "libdpdk"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+        .unwrap_or_else(|_| {
+            "-lrte_eal -lrte_mempool -lrte_ring -lrte_mbuf -lrte_net -lrte_ethdev".to_string()
+        });
+
+    println!("DPDK lib flags: {}", dpdk_libs);
+
+    // Standard library search paths for DPDK
+    println!("cargo:rustc-link-search=native=/usr/lib");
+    println!("cargo:rustc-link-search=native=/usr/local/lib");
+    println!("cargo:rustc-link-search=native=/usr/lib/x86_64-linux-gnu");
+    println!("cargo:rustc-link-search=native=/usr/lib/dpdk");
+    println!("cargo:rustc-link-search=native=/usr/lib/x86_64-linux-gnu/dpdk");
+
+    // Add DPDK libraries
+    for lib in dpdk_libs.split_whitespace() {
+        if lib.starts_with("-l") {
+            println!("cargo:rustc-link-lib={}", &lib[2..]);
+        } else if lib.starts_with("-L") {
+            println!("cargo:rustc-link-search=native={}", &lib[2..]);
+        }
+    }
+
+    dpdk_include_path
+}
+
+/// Generate bindings for the ethdev structures from the DPDK headers found by pkg-config
+///
+/// rte_eth_conf and friends change layout between DPDK releases, so they are generated from
+/// the installed headers (src/native/bindings.h) instead of being mirrored by hand in ffi.rs.
+#[cfg(feature = "dpdk")]
+fn generate_bindings(dpdk_cflags: &str) {
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is not set");
+
+    let bindings = bindgen::Builder::default()
+        .header("src/native/bindings.h")
+        .clang_args(dpdk_cflags.split_whitespace())
+        .clang_arg("-I/usr/include/dpdk")
+        .clang_arg("-I/usr/include/x86_64-linux-gnu/dpdk")
+        .clang_args(["-include", "rte_config.h"])
+        // Flag masks are defined through RTE_BIT64(), which only clang itself can expand
+        .clang_macro_fallback()
+        .parse_callbacks(Box::new(FlagMacros))
+        .allowlist_type("rte_eth_conf")
+        .allowlist_type("rte_eth_rxconf")
+        .allowlist_type("rte_eth_txconf")
+        .allowlist_type("rte_eth_xstat")
+        .allowlist_type("rte_eth_xstat_name")
+        .allowlist_type("rte_ether_addr")
+        .allowlist_type("rte_eth_fc_conf")
+        .allowlist_function("rte_eth_dev_configure")
+        .allowlist_function("rte_eth_macaddr_get")
+        .allowlist_function("rte_eth_dev_flow_ctrl_get")
+        .allowlist_function("rte_eth_dev_flow_ctrl_set")
+        .allowlist_function("rte_eth_xstats_get")
+        .allowlist_function("rte_eth_xstats_get_names")
+        .allowlist_var("RTE_ETH_RSS_.*")
+        .allowlist_var("RTE_ETH_RX_OFFLOAD_.*")
+        .allowlist_var("RTE_ETH_TX_OFFLOAD_.*")
+        .allowlist_var("RTE_MBUF_F_TX_.*")
+        // On 20.11 these are macros from compat.h rather than enum constants
+        .allowlist_var("RTE_ETH_MQ_RX_.*")
+        .allowlist_var("RTE_ETH_FC_.*")
+        .allowlist_var("RTE_ETH_NAME_MAX_LEN")
+        .allowlist_var("RTE_ETH_XSTATS_NAME_SIZE")
+        .allowlist_var("RTE_ETHER_HDR_LEN")
+        .allowlist_var("RTE_ETHER_CRC_LEN")
+        // Enum values keep their C names (RTE_ETH_MQ_RX_RSS, RTE_ETH_FC_FULL)
+        .prepend_enum_name(false)
+        .derive_default(true)
+        .generate()
+        .expect("Failed to generate DPDK bindings");
+
+    bindings
+        .write_to_file(Path::new(&out_dir).join("dpdk_bindings.rs"))
+        .expect("Failed to write DPDK bindings");
+}
+
+/// Types the offload and RSS flag macros as u64, matching the u64 fields they are stored in
+#[cfg(feature = "dpdk")]
+#[derive(Debug)]
+struct FlagMacros;
+
+#[cfg(feature = "dpdk")]
+impl bindgen::callbacks::ParseCallbacks for FlagMacros {
+    fn int_macro(&self, name: &str, _value: i64) -> Option<bindgen::callbacks::IntKind> {
+        const FLAG_PREFIXES: [&str; 4] = [
+            "RTE_ETH_RSS_",
+            "RTE_ETH_RX_OFFLOAD_",
+            "RTE_ETH_TX_OFFLOAD_",
+            "RTE_MBUF_F_",
+        ];
+        FLAG_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+            .then_some(bindgen::callbacks::IntKind::U64)
+    }
+}
+
+/// Installed DPDK version from pkg-config ("unknown" without pkg-config or libdpdk)
+fn dpdk_version() -> String {
+    Command::new("pkg-config")
+        .args(["--modversion", "libdpdk"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Check the DPDK version against the supported LTS releases (20.11 - 23.11)
+///
+/// An unknown version is assumed to be a current release.
+fn check_dpdk_version(version: &str) {
+    let mut parts = version.split('.').map(|part| part.parse::<u32>().ok());
+    let (Some(Some(major)), Some(Some(minor))) = (parts.next(), parts.next()) else {
+        println!(
+            "cargo:warning=DPDK version '{}' not recognized, assuming 21.11 or newer",
+            version
+        );
+        return;
+    };
+
+    if (major, minor) < (20, 11) {
+        println!(
+            "cargo:warning=DPDK {} is older than the oldest supported LTS (20.11)",
+            version
+        );
+    }
+    if (major, minor) < (21, 11) {
+        println!("cargo:rustc-cfg=dpdk_pre_21_11");
+    }
+    println!("DPDK version: {}", version);
+}
+
+/// Build provenance reported through compile-time environment variables
+struct BuildInfo<'a> {
+    profile: &'a str,
+    march: &'a str,
+    /// PGO mode, "off" when PGO is disabled
+    pgo_mode: &'a str,
+    has_hugepages: bool,
+    has_numa: bool,
+    cpu_features: &'a [String],
+    dpdk_version: &'a str,
+}
+
+/// Emit build provenance as compile-time environment variables (read by src/control/build_info.rs)
+fn emit_build_info(info: &BuildInfo) {
+    let git_commit = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let git_dirty = Command::new("git")
+        .args(["status", "--porcelain", "--untracked-files=no"])
+        .output()
+        .map(|output| !output.stdout.is_empty())
+        .unwrap_or(false);
+
+    // Cargo features selected for this build plus the ones enabled by this script
+    let mut features: Vec<String> = env::vars()
+        .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(|f| f.to_lowercase()))
+        .collect();
+    if info.has_hugepages {
+        features.push("hugepages".to_string());
+    }
+    if info.has_numa {
+        features.push("numa".to_string());
+    }
+    features.sort();
+    features.dedup();
+
+    println!(
+        "cargo:rustc-env=HFEEC_GIT_COMMIT={}{}",
+        git_commit,
+        if git_dirty { "-dirty" } else { "" }
+    );
+    println!("cargo:rustc-env=HFEEC_BUILD_PROFILE={}", info.profile);
+    println!("cargo:rustc-env=HFEEC_BUILD_MARCH={}", info.march);
+    println!("cargo:rustc-env=HFEEC_BUILD_PGO={}", info.pgo_mode);
+    println!("cargo:rustc-env=HFEEC_DPDK_VERSION={}", info.dpdk_version);
+    println!("cargo:rustc-env=HFEEC_FEATURES={}", features.join(","));
+    println!(
+        "cargo:rustc-env=HFEEC_CPU_FEATURES={}",
+        info.cpu_features.join(",")
+    );
+
+    println!("cargo:rerun-if-changed=.git/HEAD");
+}
+
 /// Check if HugePages are available on the system
 fn check_hugepages_available() -> bool {
     Path::new("/sys/kernel/mm/hugepages").exists()
@@ -179,7 +404,140 @@ fn check_numa_support() -> bool {
     true
 }
 
-/// Detect CPU features to enable specialized optimization paths
+/// Named target CPU profile: compiler -march/-mtune and the SIMD features it guarantees
+struct CpuProfile {
+    name: &'static str,
+    march: &'static str,
+    mtune: &'static str,
+    features: &'static [&'static str],
+}
+
+const SERVER_AVX512: &[&str] = &[
+    "sse4_2", "avx", "avx2", "avx512", "aes_ni", "rdrand", "rdseed",
+];
+const SERVER_AVX2: &[&str] = &["sse4_2", "avx", "avx2", "aes_ni", "rdrand", "rdseed"];
+
+const CPU_PROFILES: &[CpuProfile] = &[
+    CpuProfile {
+        name: "native",
+        march: "native",
+        mtune: "native",
+        features: &[],
+    },
+    CpuProfile {
+        name: "x86-64",
+        march: "x86-64",
+        mtune: "generic",
+        features: &[],
+    },
+    CpuProfile {
+        name: "x86-64-v2",
+        march: "x86-64-v2",
+        mtune: "generic",
+        features: &["sse4_2"],
+    },
+    CpuProfile {
+        name: "x86-64-v3",
+        march: "x86-64-v3",
+        mtune: "generic",
+        features: &["sse4_2", "avx", "avx2"],
+    },
+    CpuProfile {
+        name: "x86-64-v4",
+        march: "x86-64-v4",
+        mtune: "generic",
+        features: &["sse4_2", "avx", "avx2", "avx512"],
+    },
+    CpuProfile {
+        name: "skylake-avx512",
+        march: "skylake-avx512",
+        mtune: "skylake-avx512",
+        features: SERVER_AVX512,
+    },
+    CpuProfile {
+        name: "cascadelake",
+        march: "cascadelake",
+        mtune: "cascadelake",
+        features: SERVER_AVX512,
+    },
+    CpuProfile {
+        name: "icelake-server",
+        march: "icelake-server",
+        mtune: "icelake-server",
+        features: SERVER_AVX512,
+    },
+    CpuProfile {
+        name: "sapphirerapids",
+        march: "sapphirerapids",
+        mtune: "sapphirerapids",
+        features: SERVER_AVX512,
+    },
+    CpuProfile {
+        name: "znver2",
+        march: "znver2",
+        mtune: "znver2",
+        features: SERVER_AVX2,
+    },
+    CpuProfile {
+        name: "znver3",
+        march: "znver3",
+        mtune: "znver3",
+        features: SERVER_AVX2,
+    },
+    CpuProfile {
+        name: "znver4",
+        march: "znver4",
+        mtune: "znver4",
+        features: SERVER_AVX512,
+    },
+];
+
+/// Select the CPU profile from HFEEC_CPU_PROFILE
+///
+/// Without the variable release builds use `native`, unless cross-compiling, where the
+/// host CPU says nothing about the target and the portable `x86-64` baseline is used.
+/// Debug builds keep the compiler defaults. Rust code is compiled for the CPU given by
+/// `-C target-cpu` in RUSTFLAGS, which a build script cannot set, so a mismatch is reported.
+fn select_cpu_profile(is_release: bool) -> Option<&'static CpuProfile> {
+    let find = |name: &str| CPU_PROFILES.iter().find(|p| p.name == name);
+
+    let profile = match env::var("HFEEC_CPU_PROFILE") {
+        Ok(name) => match find(name.trim()) {
+            Some(profile) => profile,
+            None => {
+                let known: Vec<&str> = CPU_PROFILES.iter().map(|p| p.name).collect();
+                panic!(
+                    "Unknown HFEEC_CPU_PROFILE '{}', expected one of: {}",
+                    name,
+                    known.join(", ")
+                );
+            }
+        },
+        Err(_) if !is_release => return None,
+        Err(_) => {
+            let cross = env::var("TARGET").ok() != env::var("HOST").ok();
+            if cross {
+                println!("cargo:warning=Cross-compiling without HFEEC_CPU_PROFILE, using x86-64 baseline");
+                find("x86-64")?
+            } else {
+                find("native")?
+            }
+        }
+    };
+
+    let rustflags = env::var("CARGO_ENCODED_RUSTFLAGS").unwrap_or_default();
+    let target_cpu = format!("target-cpu={}", profile.march);
+    if profile.name != "native" && !rustflags.contains(&target_cpu) {
+        println!(
+            "cargo:warning=CPU profile {} applies to native code only; set RUSTFLAGS=\"-C {}\" for Rust code",
+            profile.name, target_cpu
+        );
+    }
+
+    println!("CPU profile: {} (-march={})", profile.name, profile.march);
+    Some(profile)
+}
+
 fn detect_cpu_features() -> Vec<String> {
     let mut features = Vec::new();
 
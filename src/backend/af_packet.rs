@@ -0,0 +1,292 @@
+// src/backend/af_packet.rs
+use std::cell::Cell;
+use std::io;
+use std::mem;
+use std::os::raw::{c_int, c_void};
+
+use crate::backend::rx::{Backend, InterfaceSpec, RxQueue, RxQueueInfo};
+use crate::numa::topology::NumaTopology;
+use crate::{log_info, log_warn};
+
+// Определения linux/if_packet.h (часть из них libc не экспортирует)
+const SOL_PACKET: c_int = 263;
+const PACKET_ADD_MEMBERSHIP: c_int = 1;
+const PACKET_STATISTICS: c_int = 6;
+const PACKET_FANOUT: c_int = 18;
+const PACKET_FANOUT_HASH: u32 = 0;
+const PACKET_MR_PROMISC: u16 = 1;
+/// Тип кадра: отправлен с этого хоста
+const PACKET_OUTGOING: u8 = 4;
+const ETH_P_ALL: u16 = 0x0003;
+
+/// Наибольший принимаемый кадр (jumbo frames с запасом на VLAN)
+const MAX_FRAME_SIZE: usize = 9216;
+
+#[repr(C)]
+struct PacketMreq {
+    mr_ifindex: c_int,
+    mr_type: u16,
+    mr_alen: u16,
+    mr_address: [u8; 8],
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct TpacketStats {
+    tp_packets: u32,
+    tp_drops: u32,
+}
+
+/// Интерфейс, открытый для приема через AF_PACKET
+struct PacketInterface {
+    port_id: u16,
+    if_name: String,
+    ifindex: u32,
+    num_queues: u16,
+    numa_node: Option<usize>,
+}
+
+/// Бэкенд приема через сокеты AF_PACKET для разработки и тестов
+///
+/// Не требует DPDK, hugepages, программ XDP и поддержки драйвера: работает
+/// на любом интерфейсе, включая loopback и veth. Каждый кадр копируется
+/// системным вызовом, поэтому бэкенд медленный и не подходит для боевой
+/// нагрузки. Очереди - сокеты одной группы PACKET_FANOUT (распределение по
+/// хешу потока), по умолчанию одна очередь на интерфейс. Интерфейс
+/// переводится в неразборчивый режим на время работы. Номера портов
+/// присваиваются в порядке `--packet-interface`, начиная с 0. Нужны права
+/// CAP_NET_RAW.
+pub struct AfPacketBackend {
+    interfaces: Vec<PacketInterface>,
+}
+
+impl AfPacketBackend {
+    pub fn new(specs: &[InterfaceSpec]) -> Result<Self, String> {
+        if specs.is_empty() {
+            return Err("No interfaces for AF_PACKET backend (use --packet-interface)".to_string());
+        }
+
+        let topology = NumaTopology::new().ok();
+        let mut interfaces = Vec::with_capacity(specs.len());
+
+        for (port_id, spec) in specs.iter().enumerate() {
+            let ifindex = spec.ifindex()?;
+            let num_queues = spec.num_queues.unwrap_or(1);
+            let numa_node = topology
+                .as_ref()
+                .and_then(|topology| topology.get_nic_node(&spec.if_name));
+
+            log_info!(
+                "af_packet",
+                "Interface selected",
+                interface = spec.if_name,
+                port = port_id,
+                queues = num_queues
+            );
+
+            interfaces.push(PacketInterface {
+                port_id: port_id as u16,
+                if_name: spec.if_name.clone(),
+                ifindex,
+                num_queues,
+                numa_node,
+            });
+        }
+
+        Ok(Self { interfaces })
+    }
+}
+
+impl Backend for AfPacketBackend {
+    fn name(&self) -> &'static str {
+        "af_packet"
+    }
+
+    fn rx_queues(&self) -> Vec<RxQueueInfo> {
+        self.interfaces
+            .iter()
+            .flat_map(|interface| {
+                (0..interface.num_queues).map(move |queue_id| RxQueueInfo {
+                    port_id: interface.port_id,
+                    queue_id,
+                    if_name: interface.if_name.clone(),
+                    numa_node: interface.numa_node,
+                })
+            })
+            .collect()
+    }
+
+    fn open_rx(&self, port_id: u16, _queue_id: u16) -> Result<Box<dyn RxQueue>, String> {
+        let interface = self
+            .interfaces
+            .iter()
+            .find(|interface| interface.port_id == port_id)
+            .ok_or_else(|| format!("Unknown AF_PACKET port {}", port_id))?;
+
+        // Группа fanout своя для каждого интерфейса; идентификатор группы
+        // общий для пространства имен сети, поэтому в него входит pid
+        let fanout = (interface.num_queues > 1)
+            .then(|| (std::process::id() as u16).wrapping_add(interface.port_id));
+
+        let socket = PacketSocket::open(&interface.if_name, interface.ifindex, fanout)?;
+        Ok(Box::new(socket))
+    }
+}
+
+/// Неблокирующий сокет AF_PACKET одной очереди
+pub struct PacketSocket {
+    fd: c_int,
+    buffer: Vec<u8>,
+    /// Накопленные потери: PACKET_STATISTICS сбрасывает счетчики при чтении
+    dropped: Cell<u64>,
+}
+
+impl PacketSocket {
+    /// Открывает сокет на интерфейсе `ifindex` (с группой fanout `fanout`)
+    pub fn open(if_name: &str, ifindex: u32, fanout: Option<u16>) -> Result<Self, String> {
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_PACKET,
+                libc::SOCK_RAW | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC,
+                ETH_P_ALL.to_be() as c_int,
+            )
+        };
+        if fd < 0 {
+            return Err(format!(
+                "Failed to create AF_PACKET socket: {}",
+                io::Error::last_os_error()
+            ));
+        }
+
+        // Сокет закрывается в Drop и при ошибке настройки
+        let socket = Self {
+            fd,
+            buffer: vec![0u8; MAX_FRAME_SIZE],
+            dropped: Cell::new(0),
+        };
+
+        let mut addr: libc::sockaddr_ll = unsafe { mem::zeroed() };
+        addr.sll_family = libc::AF_PACKET as u16;
+        addr.sll_protocol = ETH_P_ALL.to_be();
+        addr.sll_ifindex = ifindex as c_int;
+        let bound = unsafe {
+            libc::bind(
+                fd,
+                &addr as *const libc::sockaddr_ll as *const libc::sockaddr,
+                mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
+            )
+        };
+        if bound < 0 {
+            return Err(format!(
+                "Failed to bind AF_PACKET socket to {}: {}",
+                if_name,
+                io::Error::last_os_error()
+            ));
+        }
+
+        let membership = PacketMreq {
+            mr_ifindex: ifindex as c_int,
+            mr_type: PACKET_MR_PROMISC,
+            mr_alen: 0,
+            mr_address: [0; 8],
+        };
+        if let Err(e) = socket.set_option(PACKET_ADD_MEMBERSHIP, &membership) {
+            log_warn!(
+                "af_packet",
+                "Failed to enable promiscuous mode",
+                interface = if_name,
+                error = e
+            );
+        }
+
+        if let Some(group) = fanout {
+            let value = group as u32 | (PACKET_FANOUT_HASH << 16);
+            socket
+                .set_option(PACKET_FANOUT, &value)
+                .map_err(|e| format!("Failed to join fanout group on {}: {}", if_name, e))?;
+        }
+
+        Ok(socket)
+    }
+
+    fn set_option<T>(&self, name: c_int, value: &T) -> io::Result<()> {
+        let result = unsafe {
+            libc::setsockopt(
+                self.fd,
+                SOL_PACKET,
+                name,
+                value as *const T as *const c_void,
+                mem::size_of::<T>() as libc::socklen_t,
+            )
+        };
+        if result < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+impl RxQueue for PacketSocket {
+    fn rx_burst(&mut self, max: usize, on_frame: &mut dyn FnMut(&[u8])) -> usize {
+        let mut received = 0;
+
+        // Исходящие кадры тоже видны сокету: пропускаем их, но не более
+        // `max` попыток за опрос
+        for _ in 0..max {
+            let mut addr: libc::sockaddr_ll = unsafe { mem::zeroed() };
+            let mut addr_len = mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t;
+            let len = unsafe {
+                libc::recvfrom(
+                    self.fd,
+                    self.buffer.as_mut_ptr() as *mut c_void,
+                    self.buffer.len(),
+                    libc::MSG_TRUNC,
+                    &mut addr as *mut libc::sockaddr_ll as *mut libc::sockaddr,
+                    &mut addr_len,
+                )
+            };
+            if len < 0 {
+                // EAGAIN: очередь пуста
+                break;
+            }
+
+            let len = len as usize;
+            if addr.sll_pkttype == PACKET_OUTGOING {
+                continue;
+            }
+            if len > self.buffer.len() {
+                self.dropped.set(self.dropped.get() + 1);
+                continue;
+            }
+
+            on_frame(&self.buffer[..len]);
+            received += 1;
+        }
+
+        received
+    }
+
+    fn dropped(&self) -> u64 {
+        let mut stats = TpacketStats::default();
+        let mut len = mem::size_of::<TpacketStats>() as libc::socklen_t;
+        let result = unsafe {
+            libc::getsockopt(
+                self.fd,
+                SOL_PACKET,
+                PACKET_STATISTICS,
+                &mut stats as *mut TpacketStats as *mut c_void,
+                &mut len,
+            )
+        };
+        if result == 0 {
+            self.dropped.set(self.dropped.get() + stats.tp_drops as u64);
+        }
+        self.dropped.get()
+    }
+}
+
+impl Drop for PacketSocket {
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
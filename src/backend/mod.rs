@@ -0,0 +1,3 @@
+pub mod af_packet;
+pub mod rx;
+pub mod workers;
@@ -0,0 +1,92 @@
+// src/backend/rx.rs
+use std::ffi::CString;
+
+/// Интерфейс ядра, выбранный для приема (`--xdp-interface`, `--packet-interface`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfaceSpec {
+    pub if_name: String,
+    /// Число опрашиваемых очередей (None - значение по умолчанию бэкенда)
+    pub num_queues: Option<u16>,
+}
+
+impl InterfaceSpec {
+    /// Разбирает `<ifname>[,<queues>]`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (if_name, queues) = match spec.split_once(',') {
+            Some((name, queues)) => (name, Some(queues)),
+            None => (spec, None),
+        };
+        if if_name.is_empty() {
+            return Err(format!("Invalid interface '{}': empty name", spec));
+        }
+
+        let num_queues = match queues {
+            Some(value) => match value.parse::<u16>() {
+                Ok(count) if count > 0 => Some(count),
+                _ => return Err(format!("Invalid queue count '{}' for {}", value, if_name)),
+            },
+            None => None,
+        };
+
+        Ok(Self {
+            if_name: if_name.to_string(),
+            num_queues,
+        })
+    }
+
+    /// Индекс интерфейса в ядре
+    pub fn ifindex(&self) -> Result<u32, String> {
+        let name = CString::new(self.if_name.as_str())
+            .map_err(|_| format!("Invalid interface name '{}'", self.if_name))?;
+        match unsafe { libc::if_nametoindex(name.as_ptr()) } {
+            0 => Err(format!("Interface {} not found", self.if_name)),
+            ifindex => Ok(ifindex),
+        }
+    }
+}
+
+/// Очередь приема, которую можно опрашивать из рабочего потока
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RxQueueInfo {
+    /// Номер порта в реестре обработчиков (`HandlerRegistry::with_port`)
+    pub port_id: u16,
+    pub queue_id: u16,
+    pub if_name: String,
+    /// Узел NUMA устройства (None - неизвестен)
+    pub numa_node: Option<usize>,
+}
+
+/// Открытая очередь приема; принадлежит одному рабочему потоку
+pub trait RxQueue: Send {
+    /// Принимает до `max` кадров и передает каждый в `on_frame`
+    ///
+    /// Кадр действителен только во время вызова `on_frame`: после возврата
+    /// из `rx_burst` буфер возвращается сетевой карте. Возвращает число
+    /// принятых кадров.
+    fn rx_burst(&mut self, max: usize, on_frame: &mut dyn FnMut(&[u8])) -> usize;
+
+    /// Кадры, потерянные до очереди (переполнение кольца ядра/NIC)
+    fn dropped(&self) -> u64 {
+        0
+    }
+}
+
+/// Бэкенд ввода-вывода пакетов
+///
+/// Рабочие потоки (`BackendWorkers`) открывают очереди после привязки к
+/// ядру и узлу NUMA, поэтому буферы очереди выделяются на локальном узле.
+pub trait Backend: Send + Sync {
+    /// Имя бэкенда для журнала (`af_xdp`, `af_packet`, `pcap`)
+    fn name(&self) -> &'static str;
+
+    /// Очереди, которые нужно опрашивать
+    fn rx_queues(&self) -> Vec<RxQueueInfo>;
+
+    /// Открывает очередь; вызывается из рабочего потока очереди
+    fn open_rx(&self, port_id: u16, queue_id: u16) -> Result<Box<dyn RxQueue>, String>;
+
+    /// Источник исчерпан: все кадры переданы (воспроизведение записи)
+    fn is_finished(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,370 @@
+// src/backend/workers.rs
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread::{self, JoinHandle};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use core_affinity::CoreId;
+
+use crate::backend::rx::{Backend, RxQueueInfo};
+use crate::cpu::topology::CpuTopology;
+use crate::metrics::counters::{QueueStats, WorkerMetrics, WorkerStats};
+use crate::numa::ffi::NumaAllocator;
+use crate::numa::topology::NumaTopology;
+use crate::packet::handler::SharedHandlers;
+use crate::packet::parser::{parse_frame, ParseError, VXLAN_DEFAULT_PORT};
+use crate::packet::pool::PacketDataPool;
+use crate::time;
+use crate::{log_info, log_warn};
+
+/// Период (в опросах) обновления счетчика потерь очереди
+const DROPPED_POLL_INTERVAL: u64 = 1 << 16;
+
+/// Параметры рабочих потоков бэкенда
+#[derive(Debug, Clone, Copy)]
+pub struct BackendSettings {
+    /// Наибольшее число кадров за один опрос очереди
+    pub burst_size: usize,
+    /// Флаги разбора пакетов (декапсуляция туннелей)
+    pub extract_flags: u32,
+    /// UDP порт VXLAN
+    pub vxlan_port: u16,
+}
+
+impl Default for BackendSettings {
+    fn default() -> Self {
+        Self {
+            burst_size: 64,
+            extract_flags: 0,
+            vxlan_port: VXLAN_DEFAULT_PORT,
+        }
+    }
+}
+
+/// Рабочий поток очереди бэкенда
+struct BackendWorker {
+    thread: Option<JoinHandle<()>>,
+    info: RxQueueInfo,
+    core_id: CoreId,
+    metrics: Arc<WorkerMetrics>,
+    queue_dropped: Arc<AtomicU64>,
+}
+
+/// Рабочие потоки бэкенда без DPDK
+///
+/// Та же модель, что и у узлов NUMA с DPDK: на каждую очередь - поток,
+/// закрепленный за физическим ядром узла устройства (кроме ядра 0), пакеты
+/// разбираются на месте и передаются обработчикам из общего реестра.
+/// Сборка IPv4 фрагментов не выполняется: фрагменты отбрасываются.
+pub struct BackendWorkers {
+    backend: &'static str,
+    running: Arc<AtomicBool>,
+    workers: Vec<BackendWorker>,
+}
+
+impl BackendWorkers {
+    /// Открывает очереди бэкенда и запускает рабочие потоки
+    ///
+    /// Возвращает ошибку, если хотя бы одну очередь не удалось открыть; уже
+    /// запущенные потоки при этом останавливаются.
+    pub fn start(
+        backend: Arc<dyn Backend>,
+        handlers: Arc<SharedHandlers>,
+        settings: BackendSettings,
+    ) -> Result<Self, String> {
+        let queues = backend.rx_queues();
+        if queues.is_empty() {
+            return Err(format!("Backend {} has no RX queues", backend.name()));
+        }
+
+        let cores = assign_cores(&queues)?;
+        let running = Arc::new(AtomicBool::new(true));
+        let mut workers = Self {
+            backend: backend.name(),
+            running: running.clone(),
+            workers: Vec::with_capacity(queues.len()),
+        };
+
+        for (info, core_id) in queues.into_iter().zip(cores) {
+            let (opened_tx, opened_rx) = mpsc::channel();
+            let metrics = Arc::new(WorkerMetrics::default());
+            let queue_dropped = Arc::new(AtomicU64::new(0));
+
+            let thread = {
+                let backend = backend.clone();
+                let handlers = handlers.clone();
+                let running = running.clone();
+                let metrics = metrics.clone();
+                let queue_dropped = queue_dropped.clone();
+                let info = info.clone();
+
+                thread::Builder::new()
+                    .name(format!(
+                        "hfeec-{}-p{}q{}",
+                        backend.name(),
+                        info.port_id,
+                        info.queue_id
+                    ))
+                    .spawn(move || {
+                        core_affinity::set_for_current(core_id);
+                        if let Some(node_id) = info.numa_node {
+                            if NumaAllocator::is_available() {
+                                NumaAllocator::bind_thread_to_node(node_id);
+                            }
+                        }
+
+                        // Очередь открывается после привязки: буферы выделяются на узле потока
+                        let mut queue = match backend.open_rx(info.port_id, info.queue_id) {
+                            Ok(queue) => {
+                                let _ = opened_tx.send(Ok(()));
+                                queue
+                            }
+                            Err(e) => {
+                                let _ = opened_tx.send(Err(e));
+                                return;
+                            }
+                        };
+
+                        let packet_pool = PacketDataPool::new(settings.burst_size, info.numa_node);
+                        let mut dispatch = handlers.resolve(info.port_id, info.queue_id);
+                        let mut dispatch_generation = handlers.generation();
+                        let counters = &metrics.counters;
+
+                        while running.load(Ordering::Relaxed) {
+                            let generation = handlers.generation();
+                            if generation != dispatch_generation {
+                                dispatch = handlers.resolve(info.port_id, info.queue_id);
+                                dispatch_generation = generation;
+                            }
+
+                            // Одна отметка времени на пачку, как и у рабочих потоков DPDK
+                            let rx_timestamp_ns = time::now_ns();
+                            let nb_rx = queue.rx_burst(settings.burst_size, &mut |frame| {
+                                counters.rx_bytes.add(frame.len() as u64);
+
+                                match parse_frame(
+                                    frame,
+                                    settings.extract_flags,
+                                    settings.vxlan_port,
+                                ) {
+                                    Ok(parsed) => {
+                                        let mut packet = packet_pool.acquire();
+
+                                        packet.source_port = parsed.src_port;
+                                        packet.dest_port = parsed.dst_port;
+                                        packet.queue_id = info.queue_id;
+                                        packet.port_id = info.port_id;
+                                        packet.protocol = parsed.protocol;
+                                        packet.source_ip_ptr =
+                                            frame[parsed.src_ip_offset()..].as_ptr();
+                                        packet.source_ip_len = 4;
+                                        packet.dest_ip_ptr =
+                                            frame[parsed.dst_ip_offset()..].as_ptr();
+                                        packet.dest_ip_len = 4;
+                                        packet.data_ptr = frame[parsed.payload_offset..].as_ptr();
+                                        packet.data_len = parsed.payload_len;
+                                        // Кадр лежит в буфере бэкенда, а не в mbuf: удержать его нельзя
+                                        packet.mbuf_ptr = std::ptr::null_mut();
+                                        packet.l2_ptr = frame.as_ptr();
+                                        packet.ether_type = parsed.ether_type;
+                                        packet.vlan_tci = parsed.vlan_tci.unwrap_or(0);
+                                        packet.has_vlan = parsed.vlan_tci.is_some();
+                                        packet.rx_timestamp_ns = rx_timestamp_ns;
+
+                                        dispatch.dispatch_timed(&metrics, info.queue_id, &packet);
+
+                                        packet_pool.release(packet);
+                                    }
+                                    Err(ParseError::Fragment { .. }) => counters.dropped.inc(),
+                                    Err(_) => counters.parse_errors.inc(),
+                                }
+                            });
+
+                            counters.polls.inc();
+                            if nb_rx == 0 {
+                                counters.empty_polls.inc();
+                            }
+                            counters.rx_packets.add(nb_rx as u64);
+                            // Потери очереди могут стоить системного вызова: читаем их редко
+                            if counters.polls.get() % DROPPED_POLL_INTERVAL == 0 {
+                                queue_dropped.store(queue.dropped(), Ordering::Relaxed);
+                            }
+                        }
+                    })
+                    .map_err(|e| format!("Failed to spawn {} worker: {}", workers.backend, e))?
+            };
+
+            let opened = opened_rx
+                .recv()
+                .unwrap_or_else(|_| Err("worker exited before opening the queue".to_string()));
+            workers.workers.push(BackendWorker {
+                thread: Some(thread),
+                info: info.clone(),
+                core_id,
+                metrics,
+                queue_dropped,
+            });
+            if let Err(e) = opened {
+                return Err(format!(
+                    "Failed to open {} port {} queue {}: {}",
+                    info.if_name, info.port_id, info.queue_id, e
+                ));
+            }
+
+            log_info!(
+                "worker",
+                "Backend worker started",
+                backend = backend.name(),
+                interface = info.if_name,
+                port = info.port_id,
+                queue = info.queue_id,
+                core = core_id.id
+            );
+        }
+
+        Ok(workers)
+    }
+
+    /// Счетчики очередей, упорядоченные по порту и очереди
+    pub fn get_stats(&self) -> (Vec<QueueStats>, WorkerStats) {
+        let mut queues: Vec<QueueStats> = self
+            .workers
+            .iter()
+            .map(|worker| QueueStats {
+                node_id: worker.info.numa_node.unwrap_or(0),
+                port_id: worker.info.port_id,
+                queue_id: worker.info.queue_id,
+                stats: worker.metrics.counters.snapshot(),
+            })
+            .collect();
+        queues.sort_by_key(|q| (q.port_id, q.queue_id));
+
+        let mut total = WorkerStats::default();
+        for queue in &queues {
+            total += queue.stats;
+        }
+
+        (queues, total)
+    }
+
+    /// Формирует текстовый отчет по счетчикам очередей
+    pub fn format_stats(&self) -> String {
+        let (queues, total) = self.get_stats();
+
+        let mut out = format!("==== Worker Statistics ({}) ====\n", self.backend);
+        for queue in &queues {
+            out.push_str(&format!(
+                "Node {} port {} queue {}: {} ring_dropped={}\n",
+                queue.node_id,
+                queue.port_id,
+                queue.queue_id,
+                queue.stats,
+                self.queue_dropped(queue.port_id, queue.queue_id)
+            ));
+        }
+        out.push_str(&format!("Total: {}\n", total));
+        out
+    }
+
+    /// Формирует снимок счетчиков очередей в формате JSON (как у узлов DPDK)
+    pub fn format_stats_json(&self) -> String {
+        let (queues, total) = self.get_stats();
+        let timestamp_ns = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+
+        let queues_json: Vec<String> = queues
+            .iter()
+            .map(|queue| {
+                format!(
+                    "{{\"node\":{},\"port\":{},\"queue\":{},\"counters\":{},\"ring_dropped\":{}}}",
+                    queue.node_id,
+                    queue.port_id,
+                    queue.queue_id,
+                    queue.stats.to_json(),
+                    self.queue_dropped(queue.port_id, queue.queue_id)
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\"timestamp_ns\":{},\"backend\":\"{}\",\"queues\":[{}],\"total\":{}}}",
+            timestamp_ns,
+            self.backend,
+            queues_json.join(","),
+            total.to_json()
+        )
+    }
+
+    /// Кадры, потерянные до очереди (по последнему обновлению рабочего потока)
+    fn queue_dropped(&self, port_id: u16, queue_id: u16) -> u64 {
+        self.workers
+            .iter()
+            .find(|w| w.info.port_id == port_id && w.info.queue_id == queue_id)
+            .map_or(0, |w| w.queue_dropped.load(Ordering::Relaxed))
+    }
+
+    /// Ядра рабочих потоков
+    pub fn worker_cores(&self) -> Vec<usize> {
+        self.workers
+            .iter()
+            .map(|worker| worker.core_id.id)
+            .collect()
+    }
+
+    /// Останавливает рабочие потоки; очереди закрываются в своих потоках
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        for mut worker in self.workers.drain(..) {
+            if let Some(thread) = worker.thread.take() {
+                if thread.join().is_err() {
+                    log_warn!(
+                        "worker",
+                        "Backend worker panicked",
+                        port = worker.info.port_id,
+                        queue = worker.info.queue_id
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl Drop for BackendWorkers {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Назначает очередям физические ядра узлов их устройств
+///
+/// Очереди устройства без известного узла получают ядра узла 0.
+fn assign_cores(queues: &[RxQueueInfo]) -> Result<Vec<CoreId>, String> {
+    let cpu_topology =
+        CpuTopology::new().map_err(|e| format!("Failed to read CPU topology: {}", e))?;
+    let numa_topology =
+        NumaTopology::new().map_err(|e| format!("Failed to read NUMA topology: {}", e))?;
+
+    let mut next: HashMap<usize, usize> = HashMap::new();
+    queues
+        .iter()
+        .map(|queue| {
+            let node_id = queue.numa_node.unwrap_or(0);
+            let cores = numa_topology.get_node_core_ids(node_id, &cpu_topology);
+            let idx = next.entry(node_id).or_insert(0);
+            let core = cores.get(*idx).copied().ok_or_else(|| {
+                format!(
+                    "Not enough cores on NUMA node {} for {} queue {} ({} available)",
+                    node_id,
+                    queue.if_name,
+                    queue.queue_id,
+                    cores.len()
+                )
+            })?;
+            *idx += 1;
+            Ok(core)
+        })
+        .collect()
+}
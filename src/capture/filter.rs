@@ -0,0 +1,107 @@
+// src/capture/filter.rs
+use std::net::Ipv4Addr;
+
+use crate::packet::data::PacketView;
+
+const IPPROTO_TCP: u8 = 6;
+const IPPROTO_UDP: u8 = 17;
+
+/// Отбор пакетов для записи
+///
+/// Разбирает список условий через запятую:
+/// `port=<id>`, `queue=<id>`, `proto=udp|tcp|<номер>`, `src=<ip>`, `dst=<ip>`,
+/// `sport=<порт>[-<порт>]`, `dport=<порт>[-<порт>]`. Пакет записывается, если
+/// выполнены все условия; пустая строка и `all` - все пакеты.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CaptureFilter {
+    pub port_id: Option<u16>,
+    pub queue_id: Option<u16>,
+    pub protocol: Option<u8>,
+    pub src_ip: Option<[u8; 4]>,
+    pub dst_ip: Option<[u8; 4]>,
+    pub src_ports: Option<(u16, u16)>,
+    pub dst_ports: Option<(u16, u16)>,
+}
+
+impl CaptureFilter {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut filter = Self::default();
+        let spec = spec.trim();
+        if spec.is_empty() || spec == "all" {
+            return Ok(filter);
+        }
+
+        for condition in spec.split(',') {
+            let (key, value) = condition
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .ok_or_else(|| {
+                    format!("Invalid capture filter condition '{}'", condition.trim())
+                })?;
+
+            match key {
+                "port" => filter.port_id = Some(parse_number(key, value)?),
+                "queue" => filter.queue_id = Some(parse_number(key, value)?),
+                "proto" => {
+                    filter.protocol = Some(match value {
+                        "udp" => IPPROTO_UDP,
+                        "tcp" => IPPROTO_TCP,
+                        _ => parse_number(key, value)?,
+                    })
+                }
+                "src" => filter.src_ip = Some(parse_ip(key, value)?),
+                "dst" => filter.dst_ip = Some(parse_ip(key, value)?),
+                "sport" => filter.src_ports = Some(parse_port_range(key, value)?),
+                "dport" => filter.dst_ports = Some(parse_port_range(key, value)?),
+                _ => return Err(format!("Unknown capture filter key '{}'", key)),
+            }
+        }
+
+        Ok(filter)
+    }
+
+    /// Фильтр без условий (записываются все пакеты)
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    #[inline(always)]
+    pub fn matches(&self, packet: &PacketView) -> bool {
+        let in_range = |port: u16, range: Option<(u16, u16)>| {
+            range.is_none_or(|(first, last)| port >= first && port <= last)
+        };
+
+        self.port_id.is_none_or(|id| packet.port_id() == id)
+            && self.queue_id.is_none_or(|id| packet.queue_id() == id)
+            && self.protocol.is_none_or(|proto| packet.protocol() == proto)
+            && self.src_ip.is_none_or(|ip| packet.source_ip() == ip)
+            && self.dst_ip.is_none_or(|ip| packet.dest_ip() == ip)
+            && in_range(packet.source_port(), self.src_ports)
+            && in_range(packet.dest_port(), self.dst_ports)
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value '{}' for capture filter key '{}'", value, key))
+}
+
+fn parse_ip(key: &str, value: &str) -> Result<[u8; 4], String> {
+    value
+        .parse::<Ipv4Addr>()
+        .map(|addr| addr.octets())
+        .map_err(|_| {
+            format!(
+                "Invalid IPv4 address '{}' for capture filter key '{}'",
+                value, key
+            )
+        })
+}
+
+fn parse_port_range(key: &str, value: &str) -> Result<(u16, u16), String> {
+    let (first, last) = value.split_once('-').unwrap_or((value, value));
+    let first: u16 = parse_number(key, first.trim())?;
+    let last: u16 = parse_number(key, last.trim())?;
+    Ok((first.min(last), first.max(last)))
+}
@@ -0,0 +1,4 @@
+pub mod filter;
+pub mod pcapng;
+pub mod recorder;
+pub mod tee;
@@ -0,0 +1,132 @@
+// src/capture/pcapng.rs
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::time;
+
+const BLOCK_SECTION_HEADER: u32 = 0x0A0D_0D0A;
+const BLOCK_INTERFACE_DESCRIPTION: u32 = 0x0000_0001;
+const BLOCK_ENHANCED_PACKET: u32 = 0x0000_0006;
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+
+const OPT_END: u16 = 0;
+const OPT_COMMENT: u16 = 1;
+const SHB_USERAPPL: u16 = 4;
+const IF_NAME: u16 = 2;
+const IF_TSRESOL: u16 = 9;
+const EPB_QUEUE: u16 = 6;
+
+/// Тип канального уровня: Ethernet
+const LINKTYPE_ETHERNET: u16 = 1;
+/// Отметки времени интерфейсов в наносекундах (10^-9)
+const TSRESOL_NS: u8 = 9;
+
+/// Запись файла pcapng (секция, описания интерфейсов и пакеты)
+///
+/// Блоки пишутся в порядке байтов хоста, как разрешает формат. Интерфейсы
+/// добавляются по мере появления и могут описываться в любом месте секции
+/// до первого пакета интерфейса.
+pub struct PcapngWriter<W: Write> {
+    out: W,
+    interfaces: u32,
+    /// Буфер блока пакета (повторно используется между пакетами)
+    block: Vec<u8>,
+}
+
+impl<W: Write> PcapngWriter<W> {
+    /// Начинает секцию: заголовок с названием приложения
+    pub fn new(mut out: W) -> io::Result<Self> {
+        let mut body = Vec::with_capacity(64);
+        body.extend_from_slice(&BYTE_ORDER_MAGIC.to_ne_bytes());
+        body.extend_from_slice(&1u16.to_ne_bytes()); // версия 1.0
+        body.extend_from_slice(&0u16.to_ne_bytes());
+        body.extend_from_slice(&(-1i64).to_ne_bytes()); // длина секции неизвестна
+        push_option(&mut body, SHB_USERAPPL, b"hfeec");
+        push_option(&mut body, OPT_END, &[]);
+
+        write_block(&mut out, BLOCK_SECTION_HEADER, &body)?;
+        Ok(Self {
+            out,
+            interfaces: 0,
+            block: Vec::with_capacity(2048),
+        })
+    }
+
+    /// Описывает интерфейс и возвращает его номер для `write_packet`
+    pub fn add_interface(
+        &mut self,
+        name: &str,
+        comment: Option<&str>,
+        snaplen: u32,
+    ) -> io::Result<u32> {
+        let mut body = Vec::with_capacity(64);
+        body.extend_from_slice(&LINKTYPE_ETHERNET.to_ne_bytes());
+        body.extend_from_slice(&0u16.to_ne_bytes());
+        body.extend_from_slice(&snaplen.to_ne_bytes());
+        push_option(&mut body, IF_NAME, name.as_bytes());
+        if let Some(comment) = comment {
+            push_option(&mut body, OPT_COMMENT, comment.as_bytes());
+        }
+        push_option(&mut body, IF_TSRESOL, &[TSRESOL_NS]);
+        push_option(&mut body, OPT_END, &[]);
+
+        write_block(&mut self.out, BLOCK_INTERFACE_DESCRIPTION, &body)?;
+        self.interfaces += 1;
+        Ok(self.interfaces - 1)
+    }
+
+    /// Записывает пакет интерфейса `interface` с отметкой `timestamp_ns`
+    /// (нс от эпохи Unix) и номером очереди приема
+    pub fn write_packet(
+        &mut self,
+        interface: u32,
+        queue_id: u16,
+        timestamp_ns: u64,
+        orig_len: usize,
+        data: &[u8],
+    ) -> io::Result<()> {
+        let body = &mut self.block;
+        body.clear();
+        body.extend_from_slice(&interface.to_ne_bytes());
+        body.extend_from_slice(&((timestamp_ns >> 32) as u32).to_ne_bytes());
+        body.extend_from_slice(&(timestamp_ns as u32).to_ne_bytes());
+        body.extend_from_slice(&(data.len() as u32).to_ne_bytes());
+        body.extend_from_slice(&(orig_len as u32).to_ne_bytes());
+        body.extend_from_slice(data);
+        body.resize(body.len().next_multiple_of(4), 0);
+        push_option(body, EPB_QUEUE, &(queue_id as u32).to_ne_bytes());
+        push_option(body, OPT_END, &[]);
+
+        write_block(&mut self.out, BLOCK_ENHANCED_PACKET, &self.block)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Добавляет опцию блока (значение выравнивается до 4 байт)
+fn push_option(body: &mut Vec<u8>, code: u16, value: &[u8]) {
+    body.extend_from_slice(&code.to_ne_bytes());
+    body.extend_from_slice(&(value.len() as u16).to_ne_bytes());
+    body.extend_from_slice(value);
+    body.resize(body.len().next_multiple_of(4), 0);
+}
+
+/// Пишет блок: тип, длина, тело (кратно 4 байтам), повтор длины
+fn write_block(out: &mut impl Write, block_type: u32, body: &[u8]) -> io::Result<()> {
+    let total_len = (12 + body.len()) as u32;
+    out.write_all(&block_type.to_ne_bytes())?;
+    out.write_all(&total_len.to_ne_bytes())?;
+    out.write_all(body)?;
+    out.write_all(&total_len.to_ne_bytes())
+}
+
+/// Сдвиг шкалы `time::now_ns` (CLOCK_MONOTONIC) к эпохе Unix для отметок pcapng
+pub fn epoch_offset_ns() -> u64 {
+    let wall_ns = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+    wall_ns.saturating_sub(time::now_ns())
+}
@@ -0,0 +1,232 @@
+// src/capture/recorder.rs
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::capture::pcapng::{epoch_offset_ns, PcapngWriter};
+use crate::packet::data::PacketView;
+use crate::packet::handler::PacketHandler;
+use crate::time;
+use crate::{log_info, log_warn};
+
+/// Сохраняемая часть кадра: заголовки L2-L4 и начало сообщения
+pub const RECORD_HEADER_LEN: usize = 128;
+/// Записей в кольце одного рабочего потока по умолчанию
+pub const DEFAULT_RECORDER_CAPACITY: usize = 65536;
+
+thread_local! {
+    /// Кольцо, закрепленное за рабочим потоком (usize::MAX - еще не выбрано)
+    static RING_SLOT: Cell<usize> = const { Cell::new(usize::MAX) };
+}
+
+/// Заголовок принятого пакета с метаданными
+#[derive(Clone, Copy)]
+struct FlightRecord {
+    /// Время приема (`time::now_ns`)
+    timestamp_ns: u64,
+    port_id: u16,
+    queue_id: u16,
+    /// Длина кадра до усечения
+    orig_len: u32,
+    header_len: u16,
+    header: [u8; RECORD_HEADER_LEN],
+}
+
+/// Кольцо записей рабочего потока: новые записи вытесняют самые старые
+struct RecorderRing {
+    /// Растет до `capacity` по мере записи, поэтому память занимают только
+    /// кольца работающих потоков
+    records: Vec<FlightRecord>,
+    capacity: usize,
+    next: usize,
+}
+
+impl RecorderRing {
+    fn push(&mut self, record: FlightRecord) {
+        if self.records.len() < self.capacity {
+            self.records.push(record);
+        } else {
+            self.records[self.next] = record;
+        }
+        self.next = (self.next + 1) % self.capacity;
+    }
+}
+
+/// Бортовой самописец: последние секунды заголовков пакетов в памяти
+///
+/// Рабочие потоки постоянно пишут заголовки в собственные кольца, без
+/// обращения к диску; по запросу (команда `dump-recorder`, паника потока)
+/// записи за последние `window` сливаются по времени в файл pcapng. Глубина
+/// истории ограничена и окном, и емкостью кольца: при высокой интенсивности
+/// кольцо покрывает меньше окна. Пока кольцо читается при сбросе, рабочий
+/// поток не ждет, а пропускает запись (учитывается в `skipped`).
+pub struct FlightRecorder {
+    rings: Vec<Mutex<RecorderRing>>,
+    next_slot: AtomicUsize,
+    window: Duration,
+    dump_path: String,
+    skipped: AtomicU64,
+}
+
+impl FlightRecorder {
+    /// Создает кольца по числу логических ядер
+    pub fn new(window: Duration, records_per_worker: usize, dump_path: &str) -> Self {
+        Self {
+            rings: (0..num_cpus::get().max(1))
+                .map(|_| {
+                    Mutex::new(RecorderRing {
+                        records: Vec::new(),
+                        capacity: records_per_worker.max(1),
+                        next: 0,
+                    })
+                })
+                .collect(),
+            next_slot: AtomicUsize::new(0),
+            window,
+            dump_path: dump_path.to_string(),
+            skipped: AtomicU64::new(0),
+        }
+    }
+
+    /// Записывает заголовок пакета в кольцо потока
+    ///
+    /// Собранные из фрагментов датаграммы не записываются: их данные лежат не в кадре.
+    #[inline(always)]
+    pub fn record(&self, packet: &PacketView) {
+        let Some(frame) = packet.frame() else {
+            return;
+        };
+        let Ok(mut ring) = self.rings[self.ring_slot()].try_lock() else {
+            self.skipped.fetch_add(1, Ordering::Relaxed);
+            return;
+        };
+
+        let header_len = frame.len().min(RECORD_HEADER_LEN);
+        let mut record = FlightRecord {
+            timestamp_ns: packet.rx_timestamp_ns(),
+            port_id: packet.port_id(),
+            queue_id: packet.queue_id(),
+            orig_len: frame.len() as u32,
+            header_len: header_len as u16,
+            header: [0; RECORD_HEADER_LEN],
+        };
+        record.header[..header_len].copy_from_slice(&frame[..header_len]);
+        ring.push(record);
+    }
+
+    #[inline(always)]
+    fn ring_slot(&self) -> usize {
+        RING_SLOT.with(|slot| {
+            if slot.get() == usize::MAX {
+                slot.set(self.next_slot.fetch_add(1, Ordering::Relaxed));
+            }
+            slot.get() % self.rings.len()
+        })
+    }
+
+    /// Путь файла сброса по умолчанию
+    pub fn dump_path(&self) -> &str {
+        &self.dump_path
+    }
+
+    /// Записи, пропущенные во время сброса
+    pub fn skipped(&self) -> u64 {
+        self.skipped.load(Ordering::Relaxed)
+    }
+
+    /// Записывает заголовки за последние `window` в файл pcapng
+    ///
+    /// Возвращает число записанных пакетов. Кольца копируются по очереди,
+    /// поэтому рабочие потоки пропускают записи только на время копирования
+    /// своего кольца, а не всей записи файла.
+    pub fn dump(&self, path: &str) -> Result<usize, String> {
+        let since_ns = time::now_ns().saturating_sub(self.window.as_nanos() as u64);
+
+        let mut records = Vec::new();
+        for ring in &self.rings {
+            // Паника рабочего потока не портит кольцо: запись копируется целиком
+            let ring = ring.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            records.extend(
+                ring.records
+                    .iter()
+                    .filter(|record| record.timestamp_ns >= since_ns)
+                    .copied(),
+            );
+        }
+        records.sort_by_key(|record| record.timestamp_ns);
+
+        let write_error = |e: std::io::Error| format!("Failed to write {}: {}", path, e);
+        let file = File::create(path).map_err(write_error)?;
+        let mut writer = PcapngWriter::new(BufWriter::new(file)).map_err(write_error)?;
+
+        let epoch_offset_ns = epoch_offset_ns();
+        let mut interfaces = HashMap::new();
+        for record in &records {
+            let interface = match interfaces.get(&record.port_id) {
+                Some(&interface) => interface,
+                None => {
+                    let interface = writer
+                        .add_interface(
+                            &format!("port{}", record.port_id),
+                            Some("flight recorder, host clock"),
+                            RECORD_HEADER_LEN as u32,
+                        )
+                        .map_err(write_error)?;
+                    interfaces.insert(record.port_id, interface);
+                    interface
+                }
+            };
+
+            writer
+                .write_packet(
+                    interface,
+                    record.queue_id,
+                    record.timestamp_ns + epoch_offset_ns,
+                    record.orig_len as usize,
+                    &record.header[..record.header_len as usize],
+                )
+                .map_err(write_error)?;
+        }
+        writer.flush().map_err(write_error)?;
+
+        log_info!(
+            "recorder",
+            "Flight recorder dumped",
+            file = path,
+            packets = records.len(),
+            skipped = self.skipped()
+        );
+        Ok(records.len())
+    }
+}
+
+/// Оборачивает обработчик: заголовок пакета записывается перед обработкой
+pub fn recording_handler(recorder: Arc<FlightRecorder>, inner: PacketHandler) -> PacketHandler {
+    Arc::new(move |queue_id: u16, packet: &PacketView| {
+        recorder.record(packet);
+        inner(queue_id, packet);
+    })
+}
+
+/// Сбрасывает самописец в файл по умолчанию при панике любого потока
+///
+/// Срабатывает только на панику: после SIGSEGV и SIGABRT состояние процесса
+/// не позволяет безопасно выделять память и писать файл.
+pub fn install_panic_dump(recorder: Arc<FlightRecorder>) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        previous(info);
+        if let Err(e) = recorder.dump(recorder.dump_path()) {
+            log_warn!(
+                "recorder",
+                "Flight recorder dump on panic failed",
+                error = e
+            );
+        }
+        crate::logging::logger::flush();
+    }));
+}
@@ -0,0 +1,317 @@
+// src/capture/tee.rs
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use core_affinity::CoreId;
+use crossbeam::queue::ArrayQueue;
+
+use crate::capture::filter::CaptureFilter;
+use crate::capture::pcapng::{epoch_offset_ns, PcapngWriter};
+use crate::cpu::housekeeping;
+use crate::packet::data::PacketView;
+use crate::packet::handler::PacketHandler;
+use crate::{log_info, log_warn};
+
+/// Наибольшая длина записываемой части кадра по умолчанию
+pub const DEFAULT_CAPTURE_SNAPLEN: usize = 65535;
+/// Емкость кольца одного рабочего потока по умолчанию
+pub const DEFAULT_CAPTURE_RING_SIZE: usize = 16384;
+
+const WRITER_BATCH: usize = 256;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+thread_local! {
+    /// Кольцо, закрепленное за рабочим потоком (usize::MAX - еще не выбрано)
+    static RING_SLOT: Cell<usize> = const { Cell::new(usize::MAX) };
+}
+
+/// Кадр, скопированный рабочим потоком для записи
+pub struct CapturedFrame {
+    pub port_id: u16,
+    pub queue_id: u16,
+    /// Время приема (`time::now_ns`)
+    pub timestamp_ns: u64,
+    /// Аппаратная отметка NIC (если включена и поставлена)
+    pub hw_timestamp_ns: Option<u64>,
+    /// Длина кадра до усечения
+    pub orig_len: usize,
+    pub data: Vec<u8>,
+}
+
+/// Ответвление записи пакетов в pcapng
+///
+/// Рабочие потоки копируют отобранные кадры в собственные кольца (поток
+/// закрепляет кольцо при первом пакете), поток записи разбирает кольца на
+/// отдельном ядре. Кадр копируется, а не удерживается, чтобы медленная
+/// запись на диск не истощала пулы mbuf. Если запись не успевает, новые
+/// кадры отбрасываются и учитываются в `dropped`.
+pub struct CaptureTee {
+    rings: Vec<ArrayQueue<CapturedFrame>>,
+    next_slot: AtomicUsize,
+    filter: CaptureFilter,
+    snaplen: usize,
+    captured: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl CaptureTee {
+    /// Создает кольца по числу логических ядер
+    pub fn new(filter: CaptureFilter, ring_size: usize, snaplen: usize) -> Self {
+        Self {
+            rings: (0..num_cpus::get().max(1))
+                .map(|_| ArrayQueue::new(ring_size.max(1)))
+                .collect(),
+            next_slot: AtomicUsize::new(0),
+            filter,
+            snaplen: snaplen.max(1),
+            captured: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Копирует кадр пакета в кольцо потока, если пакет подходит под фильтр
+    ///
+    /// Собранные из фрагментов датаграммы не записываются: их данные лежат не в кадре.
+    #[inline(always)]
+    pub fn capture(&self, packet: &PacketView) {
+        if !self.filter.matches(packet) {
+            return;
+        }
+        let Some(frame) = packet.frame() else {
+            return;
+        };
+
+        let captured = CapturedFrame {
+            port_id: packet.port_id(),
+            queue_id: packet.queue_id(),
+            timestamp_ns: packet.rx_timestamp_ns(),
+            hw_timestamp_ns: packet.hw_timestamp_ns(),
+            orig_len: frame.len(),
+            data: frame[..frame.len().min(self.snaplen)].to_vec(),
+        };
+        if self.rings[self.ring_slot()].push(captured).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.captured.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[inline(always)]
+    fn ring_slot(&self) -> usize {
+        RING_SLOT.with(|slot| {
+            if slot.get() == usize::MAX {
+                slot.set(self.next_slot.fetch_add(1, Ordering::Relaxed));
+            }
+            // Потоков больше, чем колец: кольцо MPMC, совместное использование безопасно
+            slot.get() % self.rings.len()
+        })
+    }
+
+    pub fn snaplen(&self) -> usize {
+        self.snaplen
+    }
+
+    /// Кадры, переданные потоку записи
+    pub fn captured(&self) -> u64 {
+        self.captured.load(Ordering::Relaxed)
+    }
+
+    /// Кадры, не поместившиеся в кольца
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// Оборачивает обработчик: отобранные пакеты копируются для записи перед обработкой
+pub fn capture_handler(tee: Arc<CaptureTee>, inner: PacketHandler) -> PacketHandler {
+    Arc::new(move |queue_id: u16, packet: &PacketView| {
+        tee.capture(packet);
+        inner(queue_id, packet);
+    })
+}
+
+/// Поток записи колец `CaptureTee` в файл pcapng
+///
+/// Каждый порт описывается интерфейсом `port<N>` с отметками по часам хоста
+/// (время приема пачки, приведенное к эпохе Unix). Кадры с аппаратной
+/// отметкой пишутся в интерфейс `port<N>-hw` с отметкой NIC как есть: часы
+/// NIC должны идти в наносекундах от эпохи (синхронизация PTP).
+pub struct CaptureWriter {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl CaptureWriter {
+    pub fn start(
+        path: &str,
+        tee: Arc<CaptureTee>,
+        core_id: Option<CoreId>,
+    ) -> Result<Self, String> {
+        let file = File::create(path)
+            .map_err(|e| format!("Failed to create capture file {}: {}", path, e))?;
+        let writer = PcapngWriter::new(BufWriter::with_capacity(1 << 20, file))
+            .map_err(|e| format!("Failed to write capture file {}: {}", path, e))?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let path = path.to_string();
+
+        let thread = thread::Builder::new()
+            .name("hfeec-capture".to_string())
+            .spawn(move || {
+                match core_id {
+                    Some(core_id) => {
+                        core_affinity::set_for_current(core_id);
+                    }
+                    None => housekeeping::pin_current_thread(),
+                }
+                log_info!(
+                    "capture",
+                    "Capture writer started",
+                    file = path,
+                    core = core_id.map_or("none".to_string(), |core| core.id.to_string())
+                );
+
+                let mut session = CaptureSession::new(writer, &tee);
+                if let Err(e) = session.run(&thread_stop, core_id.is_some()) {
+                    log_warn!(
+                        "capture",
+                        "Capture stopped on write error",
+                        file = path,
+                        error = e
+                    );
+                }
+
+                log_info!(
+                    "capture",
+                    "Capture writer stopped",
+                    file = path,
+                    written = session.written,
+                    dropped = tee.dropped()
+                );
+            })
+            .map_err(|e| format!("Failed to spawn capture writer: {}", e))?;
+
+        Ok(Self {
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// Дописывает оставшиеся в кольцах кадры и закрывает файл
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for CaptureWriter {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Состояние потока записи
+struct CaptureSession<'a> {
+    writer: PcapngWriter<BufWriter<File>>,
+    tee: &'a CaptureTee,
+    /// Номера интерфейсов pcapng по (порт, аппаратная отметка)
+    interfaces: HashMap<(u16, bool), u32>,
+    /// Сдвиг шкалы `time::now_ns` (CLOCK_MONOTONIC) к эпохе Unix
+    epoch_offset_ns: u64,
+    written: u64,
+}
+
+impl<'a> CaptureSession<'a> {
+    fn new(writer: PcapngWriter<BufWriter<File>>, tee: &'a CaptureTee) -> Self {
+        Self {
+            writer,
+            tee,
+            interfaces: HashMap::new(),
+            epoch_offset_ns: epoch_offset_ns(),
+            written: 0,
+        }
+    }
+
+    fn run(&mut self, stop: &AtomicBool, spin: bool) -> std::io::Result<()> {
+        let mut last_flush = Instant::now();
+
+        loop {
+            let stopping = stop.load(Ordering::Relaxed);
+            let drained = self.drain()?;
+
+            // После остановки кольца дочитываются до конца
+            if stopping && drained == 0 {
+                break;
+            }
+            if last_flush.elapsed() >= FLUSH_INTERVAL {
+                self.writer.flush()?;
+                last_flush = Instant::now();
+            }
+            if drained == 0 {
+                if spin {
+                    std::hint::spin_loop();
+                } else {
+                    thread::sleep(Duration::from_micros(100));
+                }
+            }
+        }
+
+        self.writer.flush()
+    }
+
+    /// Записывает до `WRITER_BATCH` кадров из каждого кольца
+    fn drain(&mut self) -> std::io::Result<usize> {
+        let mut drained = 0;
+        for ring in 0..self.tee.rings.len() {
+            for _ in 0..WRITER_BATCH {
+                let Some(frame) = self.tee.rings[ring].pop() else {
+                    break;
+                };
+                self.write(&frame)?;
+                drained += 1;
+            }
+        }
+        Ok(drained)
+    }
+
+    fn write(&mut self, frame: &CapturedFrame) -> std::io::Result<()> {
+        let (hw, timestamp_ns) = match frame.hw_timestamp_ns {
+            Some(timestamp_ns) => (true, timestamp_ns),
+            None => (false, frame.timestamp_ns + self.epoch_offset_ns),
+        };
+
+        let interface = match self.interfaces.get(&(frame.port_id, hw)) {
+            Some(&interface) => interface,
+            None => {
+                let (name, comment) = match hw {
+                    true => (format!("port{}-hw", frame.port_id), "NIC clock"),
+                    false => (format!("port{}", frame.port_id), "host clock"),
+                };
+                let interface =
+                    self.writer
+                        .add_interface(&name, Some(comment), self.tee.snaplen() as u32)?;
+                self.interfaces.insert((frame.port_id, hw), interface);
+                interface
+            }
+        };
+
+        self.writer.write_packet(
+            interface,
+            frame.queue_id,
+            timestamp_ns,
+            frame.orig_len,
+            &frame.data,
+        )?;
+        self.written += 1;
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+// src/control/build_info.rs
+use std::fmt;
+
+/// Сведения о сборке, встроенные build.rs на этапе компиляции
+///
+/// Позволяют эксплуатации убедиться, какой именно бинарник и с какими
+/// возможностями запущен на конкретном сервере.
+#[derive(Debug, Clone, Copy)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub git_commit: &'static str,
+    pub profile: &'static str,
+    pub march: &'static str,
+    pub pgo: &'static str,
+    pub dpdk_version: &'static str,
+    pub features: &'static str,
+    pub cpu_features: &'static str,
+}
+
+/// Возвращает сведения о текущей сборке
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_commit: option_env!("HFEEC_GIT_COMMIT").unwrap_or("unknown"),
+        profile: option_env!("HFEEC_BUILD_PROFILE").unwrap_or("unknown"),
+        march: option_env!("HFEEC_BUILD_MARCH").unwrap_or("unknown"),
+        pgo: option_env!("HFEEC_BUILD_PGO").unwrap_or("unknown"),
+        dpdk_version: option_env!("HFEEC_DPDK_VERSION").unwrap_or("unknown"),
+        features: option_env!("HFEEC_FEATURES").unwrap_or(""),
+        cpu_features: option_env!("HFEEC_CPU_FEATURES").unwrap_or(""),
+    }
+}
+
+impl BuildInfo {
+    /// Однострочное описание для логов и ответа control plane
+    pub fn summary(&self) -> String {
+        format!(
+            "hfeec {} ({}, {}, march={}, pgo={}, dpdk {})",
+            self.version, self.git_commit, self.profile, self.march, self.pgo, self.dpdk_version
+        )
+    }
+}
+
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "HFEEC {}", self.version)?;
+        writeln!(f, "  Git commit:    {}", self.git_commit)?;
+        writeln!(f, "  Profile:       {}", self.profile)?;
+        writeln!(f, "  march:         {}", self.march)?;
+        writeln!(f, "  PGO:           {}", self.pgo)?;
+        writeln!(f, "  DPDK version:  {}", self.dpdk_version)?;
+        writeln!(f, "  Features:      {}", list_or_none(self.features))?;
+        write!(f, "  CPU features:  {}", list_or_none(self.cpu_features))
+    }
+}
+
+fn list_or_none(list: &str) -> &str {
+    if list.is_empty() {
+        "none"
+    } else {
+        list
+    }
+}
@@ -0,0 +1,241 @@
+// src/control/events.rs
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+/// Событие жизненного цикла движка
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LifecycleEvent {
+    /// Порт сконфигурирован и запущен
+    PortStarted {
+        port_id: u16,
+        node_id: usize,
+        rx_queues: u16,
+        tx_queues: u16,
+    },
+    /// Порт остановлен и его устройство отключено
+    PortRemoved { port_id: u16, node_id: usize },
+    /// Линк порта поднялся или упал
+    LinkChanged {
+        port_id: u16,
+        up: bool,
+        speed_mbps: u32,
+    },
+    /// Порты и рабочие потоки узла NUMA остановлены (остальные узлы работают)
+    NodeStopped { node_id: usize },
+    /// Узел NUMA снова запущен после остановки
+    NodeStarted { node_id: usize },
+    /// Рабочий поток запущен и привязан к ядру
+    WorkerStarted {
+        node_id: usize,
+        port_id: u16,
+        queue_id: u16,
+        core_id: usize,
+    },
+    /// Рабочий поток завершен
+    WorkerStopped {
+        node_id: usize,
+        port_id: u16,
+        queue_id: u16,
+        core_id: usize,
+    },
+    /// Рабочий поток перенесен на другое ядро
+    WorkerMoved {
+        port_id: u16,
+        queue_id: u16,
+        from_core: usize,
+        to_core: usize,
+    },
+    /// Пульс рабочего потока не продвигался дольше срока проверки зависания
+    WorkerStalled {
+        port_id: u16,
+        queue_id: u16,
+        core_id: usize,
+        stalled_ms: u64,
+    },
+    /// Обработчик пакетов паниковал в рабочем потоке (паника перехвачена)
+    WorkerPanicked {
+        port_id: u16,
+        queue_id: u16,
+        core_id: usize,
+        message: String,
+    },
+    /// Выключатель очереди сработал (`open`) или вернул ее к обычной обработке
+    CircuitBreaker {
+        port_id: u16,
+        queue_id: u16,
+        open: bool,
+        rate_pps: u64,
+    },
+    /// Начато восстановление (например, после потери пакетов фида)
+    RecoveryStarted { source: String, reason: String },
+    /// Восстановление завершено
+    RecoveryCompleted { source: String, duration_ns: u64 },
+    /// Переключение на резервный источник или узел
+    Failover { from: String, to: String },
+    /// Изменение состояния аварийного выключателя
+    KillSwitch { engaged: bool, reason: String },
+}
+
+impl fmt::Display for LifecycleEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LifecycleEvent::PortStarted {
+                port_id,
+                node_id,
+                rx_queues,
+                tx_queues,
+            } => write!(
+                f,
+                "port {} started on NUMA node {} (rx queues {}, tx queues {})",
+                port_id, node_id, rx_queues, tx_queues
+            ),
+            LifecycleEvent::PortRemoved { port_id, node_id } => {
+                write!(f, "port {} removed from NUMA node {}", port_id, node_id)
+            }
+            LifecycleEvent::LinkChanged {
+                port_id,
+                up: true,
+                speed_mbps,
+            } => write!(f, "port {} link up ({} Mbps)", port_id, speed_mbps),
+            LifecycleEvent::LinkChanged { port_id, .. } => {
+                write!(f, "port {} link down", port_id)
+            }
+            LifecycleEvent::NodeStopped { node_id } => write!(f, "NUMA node {} stopped", node_id),
+            LifecycleEvent::NodeStarted { node_id } => write!(f, "NUMA node {} started", node_id),
+            LifecycleEvent::WorkerStarted {
+                node_id,
+                port_id,
+                queue_id,
+                core_id,
+            } => write!(
+                f,
+                "worker for port {} queue {} started on NUMA node {} core {}",
+                port_id, queue_id, node_id, core_id
+            ),
+            LifecycleEvent::WorkerStopped {
+                node_id,
+                port_id,
+                queue_id,
+                core_id,
+            } => write!(
+                f,
+                "worker for port {} queue {} stopped on NUMA node {} core {}",
+                port_id, queue_id, node_id, core_id
+            ),
+            LifecycleEvent::WorkerMoved {
+                port_id,
+                queue_id,
+                from_core,
+                to_core,
+            } => write!(
+                f,
+                "worker for port {} queue {} moved from core {} to core {}",
+                port_id, queue_id, from_core, to_core
+            ),
+            LifecycleEvent::WorkerStalled {
+                port_id,
+                queue_id,
+                core_id,
+                stalled_ms,
+            } => write!(
+                f,
+                "worker for port {} queue {} on core {} stalled for {} ms",
+                port_id, queue_id, core_id, stalled_ms
+            ),
+            LifecycleEvent::WorkerPanicked {
+                port_id,
+                queue_id,
+                core_id,
+                message,
+            } => write!(
+                f,
+                "handler for port {} queue {} panicked on core {}: {}",
+                port_id, queue_id, core_id, message
+            ),
+            LifecycleEvent::CircuitBreaker {
+                port_id,
+                queue_id,
+                open,
+                rate_pps,
+            } => write!(
+                f,
+                "circuit breaker for port {} queue {} {} at {} pps",
+                port_id,
+                queue_id,
+                if *open { "tripped" } else { "reset" },
+                rate_pps
+            ),
+            LifecycleEvent::RecoveryStarted { source, reason } => {
+                write!(f, "recovery started for {}: {}", source, reason)
+            }
+            LifecycleEvent::RecoveryCompleted {
+                source,
+                duration_ns,
+            } => write!(
+                f,
+                "recovery completed for {} in {} us",
+                source,
+                duration_ns / 1_000
+            ),
+            LifecycleEvent::Failover { from, to } => write!(f, "failover from {} to {}", from, to),
+            LifecycleEvent::KillSwitch { engaged, reason } => write!(
+                f,
+                "kill switch {}: {}",
+                if *engaged { "engaged" } else { "released" },
+                reason
+            ),
+        }
+    }
+}
+
+/// Обработчик событий жизненного цикла
+///
+/// Вызывается синхронно в потоке, породившем событие (в том числе в рабочих
+/// потоках при их запуске), поэтому не должен блокироваться надолго.
+pub type EventListener = Arc<dyn Fn(&LifecycleEvent) + Send + Sync + 'static>;
+
+/// Шина событий жизненного цикла
+///
+/// Клонируется дешево: все копии разделяют один список подписчиков.
+#[derive(Clone, Default)]
+pub struct EventBus {
+    listeners: Arc<RwLock<Vec<EventListener>>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Добавляет подписчика
+    pub fn subscribe(&self, listener: EventListener) {
+        if let Ok(mut listeners) = self.listeners.write() {
+            listeners.push(listener);
+        }
+    }
+
+    /// Количество подписчиков
+    pub fn listener_count(&self) -> usize {
+        self.listeners.read().map(|l| l.len()).unwrap_or(0)
+    }
+
+    /// Рассылает событие всем подписчикам
+    pub fn emit(&self, event: LifecycleEvent) {
+        let listeners = match self.listeners.read() {
+            Ok(listeners) => listeners,
+            Err(_) => return,
+        };
+
+        for listener in listeners.iter() {
+            listener(&event);
+        }
+    }
+}
+
+impl fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventBus")
+            .field("listeners", &self.listener_count())
+            .finish()
+    }
+}
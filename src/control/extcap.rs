@@ -0,0 +1,255 @@
+// src/control/extcap.rs
+use std::fs::OpenOptions;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::control::build_info::build_info;
+use crate::cpu::housekeeping;
+use crate::packet::tap::{parse_tap_filter, TapFrame, TapRing};
+use crate::time;
+
+/// Период проверки флага остановки и пустого кольца
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+/// Наибольший интервал между сбросами буфера клиенту
+const FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+/// Имя интерфейса extcap
+const EXTCAP_INTERFACE: &str = "hfeec";
+/// Тип канального уровня pcap: Ethernet
+const LINKTYPE_ETHERNET: u32 = 1;
+/// Сигнатура pcap с наносекундными отметками времени
+const PCAP_MAGIC_NS: u32 = 0xa1b2_3c4d;
+
+/// Сервер зеркалирования на Unix-сокете
+///
+/// Клиент (`hfeec extcap`) отправляет строку фильтра (`parse_tap_filter`) и
+/// получает поток pcap. Одновременно обслуживается один клиент; пока
+/// клиента нет, зеркалирование выключено.
+pub struct TapServer {
+    path: String,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl TapServer {
+    pub fn start(path: &str, tap: Arc<TapRing>) -> Result<Self, String> {
+        // Удаляем сокет, оставшийся от предыдущего запуска
+        let _ = std::fs::remove_file(path);
+
+        let listener = UnixListener::bind(path)
+            .map_err(|e| format!("Failed to bind tap socket {}: {}", path, e))?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| format!("Failed to configure tap socket: {}", e))?;
+
+        let running = Arc::new(AtomicBool::new(true));
+        let thread_running = running.clone();
+
+        let thread = thread::Builder::new()
+            .name("hfeec-tap".to_string())
+            .spawn(move || {
+                housekeeping::pin_current_thread();
+
+                while thread_running.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        Ok((stream, _)) => {
+                            let result = serve_client(stream, &tap, &thread_running);
+                            tap.stop();
+                            match result {
+                                Ok(()) => crate::log_info!("tap", "Tap client detached"),
+                                Err(e) => {
+                                    crate::log_info!("tap", "Tap client detached", reason = e)
+                                }
+                            }
+                        }
+                        Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                            thread::sleep(POLL_INTERVAL * 50);
+                        }
+                        Err(e) => {
+                            crate::log_warn!("tap", "Tap socket accept failed", error = e);
+                            thread::sleep(POLL_INTERVAL * 50);
+                        }
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to spawn tap thread: {}", e))?;
+
+        crate::log_info!("tap", "Tap socket listening", path = path);
+
+        Ok(Self {
+            path: path.to_string(),
+            running,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl Drop for TapServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Передает клиенту поток pcap, пока клиент не отключится или сервер не остановится
+fn serve_client(stream: UnixStream, tap: &TapRing, running: &AtomicBool) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+
+    let mut spec = String::new();
+    BufReader::new(stream.try_clone()?).read_line(&mut spec)?;
+    let filter =
+        parse_tap_filter(&spec).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    crate::log_info!("tap", "Tap client attached", filter = spec.trim());
+
+    // Отметки времени пакетов - по шкале CLOCK_MONOTONIC, pcap - от эпохи Unix
+    let wall_ns = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+    let epoch_offset_ns = wall_ns.saturating_sub(time::now_ns());
+
+    let mut writer = BufWriter::new(stream);
+    write_pcap_header(&mut writer, tap.snaplen() as u32)?;
+    writer.flush()?;
+
+    tap.start(filter);
+    let mut last_flush = Instant::now();
+    while running.load(Ordering::Relaxed) {
+        match tap.pop() {
+            Some(frame) => write_pcap_record(&mut writer, &frame, epoch_offset_ns)?,
+            None => {
+                writer.flush()?;
+                last_flush = Instant::now();
+                thread::sleep(POLL_INTERVAL);
+            }
+        }
+        if last_flush.elapsed() >= FLUSH_INTERVAL {
+            writer.flush()?;
+            last_flush = Instant::now();
+        }
+    }
+
+    writer.flush()
+}
+
+fn write_pcap_header(writer: &mut impl Write, snaplen: u32) -> io::Result<()> {
+    writer.write_all(&PCAP_MAGIC_NS.to_le_bytes())?;
+    writer.write_all(&2u16.to_le_bytes())?; // версия 2.4
+    writer.write_all(&4u16.to_le_bytes())?;
+    writer.write_all(&0i32.to_le_bytes())?; // часовой пояс
+    writer.write_all(&0u32.to_le_bytes())?; // точность отметок времени
+    writer.write_all(&snaplen.to_le_bytes())?;
+    writer.write_all(&LINKTYPE_ETHERNET.to_le_bytes())
+}
+
+fn write_pcap_record(
+    writer: &mut impl Write,
+    frame: &TapFrame,
+    epoch_offset_ns: u64,
+) -> io::Result<()> {
+    let timestamp_ns = frame.timestamp_ns + epoch_offset_ns;
+    writer.write_all(&((timestamp_ns / 1_000_000_000) as u32).to_le_bytes())?;
+    writer.write_all(&((timestamp_ns % 1_000_000_000) as u32).to_le_bytes())?;
+    writer.write_all(&(frame.data.len() as u32).to_le_bytes())?;
+    writer.write_all(&(frame.orig_len as u32).to_le_bytes())?;
+    writer.write_all(&frame.data)
+}
+
+/// Интерфейс extcap для Wireshark
+///
+/// Wireshark запускает программу из каталога extcap с аргументами
+/// `--extcap-interfaces`, `--extcap-dlts`, `--extcap-config` и `--capture`;
+/// для этого достаточно ссылки `hfeec-extcap` на исполняемый файл (или
+/// сценария, вызывающего `hfeec extcap "$@"`). При захвате поток pcap от
+/// `--tap-socket` запущенного экземпляра копируется в `--fifo` Wireshark.
+pub fn run_extcap(args: &[String]) -> Result<(), String> {
+    let has = |flag: &str| args.iter().any(|arg| arg == flag);
+    let value = |flag: &str| {
+        args.iter()
+            .position(|arg| arg == flag)
+            .and_then(|idx| args.get(idx + 1))
+            .map(String::as_str)
+    };
+
+    if has("--extcap-interfaces") {
+        println!(
+            "extcap {{version={}}}{{help=Live mirror of HFEEC traffic from the tap socket}}",
+            build_info().version
+        );
+        println!(
+            "interface {{value={}}}{{display=HFEEC tap}}",
+            EXTCAP_INTERFACE
+        );
+        return Ok(());
+    }
+
+    if let Some(interface) = value("--extcap-interface") {
+        if interface != EXTCAP_INTERFACE {
+            return Err(format!("Unknown extcap interface '{}'", interface));
+        }
+    }
+
+    if has("--extcap-dlts") {
+        println!(
+            "dlt {{number={}}}{{name=EN10MB}}{{display=Ethernet}}",
+            LINKTYPE_ETHERNET
+        );
+        return Ok(());
+    }
+
+    if has("--extcap-config") {
+        println!(
+            "arg {{number=0}}{{call=--tap-socket}}{{display=Tap socket}}{{type=string}}\
+             {{required=true}}{{tooltip=--tap-socket of the running HFEEC instance}}"
+        );
+        println!(
+            "arg {{number=1}}{{call=--tap-filter}}{{display=Filter}}{{type=string}}\
+             {{tooltip=<udp_port>[-<udp_port>][,<dst_ip>], empty for all traffic}}"
+        );
+        return Ok(());
+    }
+
+    if has("--capture") {
+        let socket = value("--tap-socket").ok_or("extcap: --tap-socket is required")?;
+        let fifo = value("--fifo").ok_or("extcap: --fifo is required")?;
+        return capture(socket, value("--tap-filter").unwrap_or(""), fifo);
+    }
+
+    Err(
+        "extcap: expected --extcap-interfaces, --extcap-dlts, --extcap-config or --capture"
+            .to_string(),
+    )
+}
+
+/// Копирует поток pcap от сервера зеркалирования в FIFO Wireshark до остановки захвата
+fn capture(socket: &str, filter: &str, fifo: &str) -> Result<(), String> {
+    // Фильтр проверяется здесь, чтобы ошибка была видна в Wireshark
+    parse_tap_filter(filter)?;
+
+    let mut stream = UnixStream::connect(socket)
+        .map_err(|e| format!("Failed to connect to tap socket {}: {}", socket, e))?;
+    writeln!(stream, "{}", filter).map_err(|e| format!("Failed to send tap filter: {}", e))?;
+
+    let mut output = OpenOptions::new()
+        .write(true)
+        .open(fifo)
+        .map_err(|e| format!("Failed to open fifo {}: {}", fifo, e))?;
+
+    // Wireshark останавливает захват, закрывая FIFO: копирование завершается ошибкой записи
+    match io::copy(&mut stream, &mut output) {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        Err(e) => Err(format!("Tap stream interrupted: {}", e)),
+    }
+}
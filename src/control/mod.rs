@@ -0,0 +1,7 @@
+pub mod build_info;
+pub mod events;
+pub mod extcap;
+pub mod profile;
+pub mod snapshot;
+pub mod socket;
+pub mod state;
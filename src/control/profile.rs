@@ -0,0 +1,179 @@
+// src/control/profile.rs
+use std::collections::BTreeMap;
+use std::ffi::CStr;
+use std::fs;
+use std::path::Path;
+
+use crate::control::snapshot::{parse_lines, ConfigSnapshot};
+
+/// Слои профиля в порядке применения: каждый следующий переопределяет предыдущие
+pub const PROFILE_LAYERS: [&str; 4] = ["base", "venue", "environment", "host"];
+
+/// Источник итогового значения параметра
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    /// Слой, задавший итоговое значение
+    pub layer: String,
+    pub path: String,
+    pub line: usize,
+    /// Слои, значения которых были переопределены (в порядке применения)
+    pub overrides: Vec<String>,
+}
+
+/// Профиль конфигурации, собранный из слоев
+///
+/// Каталог профилей содержит `base.conf`, `venue/<площадка>.conf`,
+/// `environment/<среда>.conf` и `host/<имя хоста>.conf` в формате
+/// `key = value` (как снимок конфигурации). Слои накладываются в порядке
+/// `PROFILE_LAYERS`, поэтому один набор файлов обслуживает разработку,
+/// UAT и промышленную среду без копирования общих параметров. Для каждого
+/// значения запоминается, из какого файла и строки оно взято.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigProfile {
+    values: ConfigSnapshot,
+    provenance: BTreeMap<String, Provenance>,
+    /// Примененные слои: (слой, путь)
+    layers: Vec<(String, String)>,
+}
+
+impl ConfigProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Загружает профиль из каталога `dir`
+    ///
+    /// `base.conf` обязателен, как и файлы явно выбранных площадки и среды.
+    /// Файл хоста необязателен: переопределения нужны немногим хостам.
+    pub fn load(
+        dir: &str,
+        venue: Option<&str>,
+        environment: Option<&str>,
+        host: Option<&str>,
+    ) -> Result<Self, String> {
+        let dir = Path::new(dir);
+        let mut profile = Self::new();
+
+        let layers = [
+            ("base", Some(dir.join("base.conf")), true),
+            (
+                "venue",
+                venue.map(|v| dir.join("venue").join(format!("{}.conf", v))),
+                true,
+            ),
+            (
+                "environment",
+                environment.map(|e| dir.join("environment").join(format!("{}.conf", e))),
+                true,
+            ),
+            (
+                "host",
+                host.map(|h| dir.join("host").join(format!("{}.conf", h))),
+                false,
+            ),
+        ];
+
+        for (layer, path, required) in layers {
+            let Some(path) = path else {
+                continue;
+            };
+            if !required && !path.exists() {
+                continue;
+            }
+
+            let path = path.to_string_lossy().into_owned();
+            let text = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {} profile {}: {}", layer, path, e))?;
+            profile.apply_layer(layer, &path, &text)?;
+        }
+
+        Ok(profile)
+    }
+
+    /// Накладывает слой `layer` из текста `text` (файл `path`)
+    ///
+    /// Повтор ключа внутри одного слоя - ошибка: итоговое значение не должно
+    /// зависеть от порядка строк в файле.
+    pub fn apply_layer(&mut self, layer: &str, path: &str, text: &str) -> Result<(), String> {
+        let entries = parse_lines(text).map_err(|e| format!("{}: {}", path, e))?;
+
+        let mut seen: BTreeMap<&str, usize> = BTreeMap::new();
+        for &(line, key, _) in &entries {
+            if let Some(first) = seen.insert(key, line) {
+                return Err(format!(
+                    "{}: key '{}' set twice (lines {} and {})",
+                    path, key, first, line
+                ));
+            }
+        }
+
+        for (line, key, value) in entries {
+            let overrides = match self.provenance.remove(key) {
+                Some(mut previous) => {
+                    previous.overrides.push(previous.layer);
+                    previous.overrides
+                }
+                None => Vec::new(),
+            };
+
+            self.values.set(key, value);
+            self.provenance.insert(
+                key.to_string(),
+                Provenance {
+                    layer: layer.to_string(),
+                    path: path.to_string(),
+                    line,
+                    overrides,
+                },
+            );
+        }
+
+        self.layers.push((layer.to_string(), path.to_string()));
+        Ok(())
+    }
+
+    /// Итоговые значения
+    pub fn values(&self) -> &ConfigSnapshot {
+        &self.values
+    }
+
+    /// Источник значения параметра
+    pub fn provenance(&self, key: &str) -> Option<&Provenance> {
+        self.provenance.get(key)
+    }
+
+    /// Примененные слои в порядке применения
+    pub fn layers(&self) -> &[(String, String)] {
+        &self.layers
+    }
+
+    /// Итоговые значения с источниками: `key = value  # layer path:line (overrides ...)`
+    pub fn explain(&self) -> String {
+        let mut out = String::new();
+        for (key, value) in self.values.iter() {
+            out.push_str(&format!("{} = {}", key, value));
+            if let Some(source) = self.provenance.get(key) {
+                out.push_str(&format!(
+                    "  # {} {}:{}",
+                    source.layer, source.path, source.line
+                ));
+                if !source.overrides.is_empty() {
+                    out.push_str(&format!(" (overrides {})", source.overrides.join(", ")));
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Имя хоста для выбора слоя `host`
+pub fn local_hostname() -> Option<String> {
+    let mut buf = [0 as libc::c_char; 256];
+    if unsafe { libc::gethostname(buf.as_mut_ptr(), buf.len()) } != 0 {
+        return None;
+    }
+    buf[buf.len() - 1] = 0;
+    let name = unsafe { CStr::from_ptr(buf.as_ptr()) };
+    Some(name.to_string_lossy().into_owned())
+}
@@ -0,0 +1,178 @@
+// src/control/snapshot.rs
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+
+/// Снимок эффективной конфигурации в виде упорядоченных пар ключ-значение
+///
+/// Включает как явно заданные параметры, так и производные значения
+/// (распределение ядер, размеры пулов), чтобы расхождение между
+/// задуманной и фактически работающей конфигурацией было видно целиком.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigSnapshot {
+    entries: BTreeMap<String, String>,
+}
+
+/// Расхождение между задуманной и работающей конфигурацией
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigDiff {
+    /// Значение отличается
+    Changed {
+        key: String,
+        intended: String,
+        running: String,
+    },
+    /// Ключ есть в задуманной конфигурации, но отсутствует в работающей
+    Missing { key: String, intended: String },
+    /// Ключ присутствует только в работающей конфигурации
+    Extra { key: String, running: String },
+}
+
+impl fmt::Display for ConfigDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigDiff::Changed {
+                key,
+                intended,
+                running,
+            } => write!(f, "~ {}: intended {}, running {}", key, intended, running),
+            ConfigDiff::Missing { key, intended } => {
+                write!(f, "- {}: intended {}, not present", key, intended)
+            }
+            ConfigDiff::Extra { key, running } => write!(f, "+ {}: running {}", key, running),
+        }
+    }
+}
+
+impl ConfigSnapshot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Устанавливает значение параметра
+    pub fn set<V: fmt::Display>(&mut self, key: &str, value: V) {
+        self.entries.insert(key.to_string(), value.to_string());
+    }
+
+    /// Устанавливает список значений (через запятую) или `none`
+    pub fn set_list<V: fmt::Display>(&mut self, key: &str, values: Option<&[V]>) {
+        let value = match values {
+            Some(values) => values
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            None => "none".to_string(),
+        };
+        self.entries.insert(key.to_string(), value);
+    }
+
+    /// Возвращает значение параметра
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(|v| v.as_str())
+    }
+
+    /// Возвращает количество параметров
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Итератор по параметрам в детерминированном порядке
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Сериализует снимок в текстовый формат `key = value`
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for (key, value) in &self.entries {
+            text.push_str(key);
+            text.push_str(" = ");
+            text.push_str(value);
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Разбирает текстовый формат `key = value` (пустые строки и `#` комментарии пропускаются)
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut snapshot = Self::new();
+        for (_, key, value) in parse_lines(text)? {
+            snapshot.set(key, value);
+        }
+        Ok(snapshot)
+    }
+
+    /// Загружает снимок из файла
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read config file {}: {}", path, e))?;
+        Self::parse(&text)
+    }
+
+    /// Сравнивает работающую конфигурацию (`self`) с задуманной
+    ///
+    /// Ключи, отсутствующие в задуманной конфигурации, считаются
+    /// расхождением только при `strict`, так как файл обычно описывает
+    /// лишь часть параметров.
+    pub fn diff(&self, intended: &ConfigSnapshot, strict: bool) -> Vec<ConfigDiff> {
+        let mut diffs = Vec::new();
+
+        for (key, intended_value) in &intended.entries {
+            match self.entries.get(key) {
+                Some(running) if running != intended_value => diffs.push(ConfigDiff::Changed {
+                    key: key.clone(),
+                    intended: intended_value.clone(),
+                    running: running.clone(),
+                }),
+                Some(_) => {}
+                None => diffs.push(ConfigDiff::Missing {
+                    key: key.clone(),
+                    intended: intended_value.clone(),
+                }),
+            }
+        }
+
+        if strict {
+            for (key, running) in &self.entries {
+                if !intended.entries.contains_key(key) {
+                    diffs.push(ConfigDiff::Extra {
+                        key: key.clone(),
+                        running: running.clone(),
+                    });
+                }
+            }
+        }
+
+        diffs
+    }
+}
+
+/// Разбирает текстовый формат `key = value` в пары с номерами строк (с 1)
+pub fn parse_lines(text: &str) -> Result<Vec<(usize, &str, &str)>, String> {
+    let mut entries = Vec::new();
+
+    for (line_no, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        match line.split_once('=') {
+            Some((key, value)) => entries.push((line_no + 1, key.trim(), value.trim())),
+            None => {
+                return Err(format!(
+                    "Invalid config line {}: expected 'key = value', got '{}'",
+                    line_no + 1,
+                    line
+                ))
+            }
+        }
+    }
+
+    Ok(entries)
+}
@@ -0,0 +1,440 @@
+// src/control/socket.rs
+use std::fmt;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::cpu::housekeeping;
+use crate::packet::handler::FeedClass;
+
+/// Время ожидания ответа от основного потока
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+/// Период проверки флага остановки при ожидании подключений
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Команда интерфейса управления
+#[derive(Debug, Clone)]
+pub enum ControlCommand {
+    /// Счетчики рабочих потоков (`stats --json` - с задержками, в формате JSON)
+    Stats { json: bool },
+    /// Перцентили задержек рабочих потоков
+    Latency,
+    /// Приостановить опрос очереди (`--hw` - также остановить очередь в NIC)
+    PauseQueue {
+        port_id: u16,
+        queue_id: u16,
+        hw: bool,
+    },
+    /// Возобновить опрос очереди
+    ResumeQueue { port_id: u16, queue_id: u16 },
+    /// Остановить порты и рабочие потоки узла NUMA
+    StopNode(usize),
+    /// Запустить остановленный узел NUMA
+    StartNode(usize),
+    /// Приостановить опрос всех очередей узла NUMA
+    PauseNode(usize),
+    /// Возобновить опрос всех очередей узла NUMA
+    ResumeNode(usize),
+    /// Перенести рабочий поток очереди на другое ядро
+    MoveQueue {
+        port_id: u16,
+        queue_id: u16,
+        core_id: usize,
+    },
+    /// Размещение рабочих потоков по ядрам
+    Workers,
+    /// Заполненность пулов mbuf
+    Mempools,
+    /// Таблица перенаправления RSS порта
+    Reta(u16),
+    /// Перераспределить корзины RSS порта по весам очередей
+    SetReta { port_id: u16, weights: Vec<u32> },
+    /// Управление потоком порта: показать или задать режим (`none`, `rx`, `tx`, `full`)
+    FlowControl { port_id: u16, mode: Option<String> },
+    /// Фильтр приема порта: показать, задать выражение tcpdump или снять (`none`)
+    RxFilter {
+        port_id: u16,
+        expression: Option<String>,
+    },
+    /// Перечитать файл ключей расшифровки фида
+    FeedKeys(String),
+    /// Изменить размер пачки rx_burst
+    SetBurstSize(u16),
+    /// Подключить устройство DPDK и запустить его порты
+    AttachPort(String),
+    /// Остановить порт и отключить его устройство
+    DetachPort(u16),
+    /// Добавить класс фида с именованным обработчиком
+    AddFeed { class: FeedClass, handler: String },
+    /// Записать снимок состояния движка в файл (в точке покоя рабочих потоков)
+    SnapshotState(String),
+    /// Сбросить бортовой самописец в файл pcapng (None - путь по умолчанию)
+    DumpRecorder(Option<String>),
+    /// Эффективная конфигурация
+    Config,
+    /// Версия и параметры сборки
+    Version,
+    /// Остановить коннектор
+    Shutdown,
+    /// Список команд
+    Help,
+}
+
+impl ControlCommand {
+    /// Разбирает строку команды
+    ///
+    /// `add-feed <name> <first_port>[-<last_port>] <handler> [dst_ip] [port_id]`
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let (&name, args) = parts.split_first().ok_or("Empty command")?;
+
+        let arg = |idx: usize, what: &str| -> Result<&str, String> {
+            args.get(idx)
+                .copied()
+                .ok_or_else(|| format!("{}: missing {}", name, what))
+        };
+        let num = |idx: usize, what: &str| -> Result<u16, String> {
+            let value = arg(idx, what)?;
+            value
+                .parse()
+                .map_err(|_| format!("{}: invalid {} '{}'", name, what, value))
+        };
+
+        match name {
+            "stats" => match args.first() {
+                None => Ok(ControlCommand::Stats { json: false }),
+                Some(&"--json") => Ok(ControlCommand::Stats { json: true }),
+                Some(other) => Err(format!("stats: unknown option '{}'", other)),
+            },
+            "latency" => Ok(ControlCommand::Latency),
+            "pause-queue" => Ok(ControlCommand::PauseQueue {
+                port_id: num(0, "port")?,
+                queue_id: num(1, "queue")?,
+                hw: match args.get(2) {
+                    None => false,
+                    Some(&"--hw") => true,
+                    Some(other) => return Err(format!("pause-queue: unknown option '{}'", other)),
+                },
+            }),
+            "resume-queue" => Ok(ControlCommand::ResumeQueue {
+                port_id: num(0, "port")?,
+                queue_id: num(1, "queue")?,
+            }),
+            "stop-node" => Ok(ControlCommand::StopNode(num(0, "node")? as usize)),
+            "start-node" => Ok(ControlCommand::StartNode(num(0, "node")? as usize)),
+            "pause-node" => Ok(ControlCommand::PauseNode(num(0, "node")? as usize)),
+            "resume-node" => Ok(ControlCommand::ResumeNode(num(0, "node")? as usize)),
+            "move-queue" => Ok(ControlCommand::MoveQueue {
+                port_id: num(0, "port")?,
+                queue_id: num(1, "queue")?,
+                core_id: num(2, "core")? as usize,
+            }),
+            "workers" => Ok(ControlCommand::Workers),
+            "mempools" => Ok(ControlCommand::Mempools),
+            "reta" => Ok(ControlCommand::Reta(num(0, "port")?)),
+            "set-reta" => Ok(ControlCommand::SetReta {
+                port_id: num(0, "port")?,
+                weights: arg(1, "queue weights")?
+                    .split(',')
+                    .map(|w| {
+                        w.parse()
+                            .map_err(|_| format!("set-reta: invalid weight '{}'", w))
+                    })
+                    .collect::<Result<_, _>>()?,
+            }),
+            "flowctrl" => Ok(ControlCommand::FlowControl {
+                port_id: num(0, "port")?,
+                mode: args.get(1).map(|mode| mode.to_string()),
+            }),
+            "rx-filter" => Ok(ControlCommand::RxFilter {
+                port_id: num(0, "port")?,
+                expression: (args.len() > 1).then(|| args[1..].join(" ")),
+            }),
+            "feedkeys" => Ok(ControlCommand::FeedKeys(arg(0, "feed name")?.to_string())),
+            "set-burst-size" => Ok(ControlCommand::SetBurstSize(num(0, "burst size")?)),
+            "attach-port" => Ok(ControlCommand::AttachPort(arg(0, "device")?.to_string())),
+            "detach-port" => Ok(ControlCommand::DetachPort(num(0, "port")?)),
+            "add-feed" => {
+                let feed_name = arg(0, "feed name")?;
+                let range = arg(1, "UDP port range")?;
+                let mut class =
+                    FeedClass::parse(feed_name, range).map_err(|e| format!("add-feed: {}", e))?;
+                let handler = arg(2, "handler name")?.to_string();
+
+                if let Some(ip) = args.get(3) {
+                    let addr: std::net::Ipv4Addr = ip
+                        .parse()
+                        .map_err(|_| format!("add-feed: invalid IPv4 address '{}'", ip))?;
+                    class = class.with_dst_ip(addr.octets());
+                }
+                if args.get(4).is_some() {
+                    class = class.with_port_id(num(4, "port")?);
+                }
+
+                Ok(ControlCommand::AddFeed { class, handler })
+            }
+            "snapshot-state" => Ok(ControlCommand::SnapshotState(
+                arg(0, "output path")?.to_string(),
+            )),
+            "dump-recorder" => Ok(ControlCommand::DumpRecorder(
+                args.first().map(|path| path.to_string()),
+            )),
+            "config" => Ok(ControlCommand::Config),
+            "version" => Ok(ControlCommand::Version),
+            "shutdown" => Ok(ControlCommand::Shutdown),
+            "help" => Ok(ControlCommand::Help),
+            other => Err(format!("Unknown command: {} (try 'help')", other)),
+        }
+    }
+
+    /// Справка по командам
+    pub fn help() -> &'static str {
+        "stats [--json] | latency | pause-queue <port> <queue> [--hw] | resume-queue <port> <queue> | \
+         stop-node <node> | start-node <node> | pause-node <node> | resume-node <node> | \
+         move-queue <port> <queue> <core> | workers | mempools | \
+         reta <port> | set-reta <port> <w0,w1,...> | flowctrl <port> [none|rx|tx|full] | rx-filter <port> [<expression>|none] | feedkeys <feed> | \
+         set-burst-size <n> | attach-port <devargs> | detach-port <port> | add-feed <name> <port>[-<port>] <handler> [dst_ip] [port_id] | \
+         snapshot-state <path> | dump-recorder [path] | config | version | shutdown | help"
+    }
+}
+
+impl fmt::Display for ControlCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ControlCommand::Stats { json: false } => write!(f, "stats"),
+            ControlCommand::Stats { json: true } => write!(f, "stats --json"),
+            ControlCommand::Latency => write!(f, "latency"),
+            ControlCommand::PauseQueue {
+                port_id,
+                queue_id,
+                hw: false,
+            } => write!(f, "pause-queue {} {}", port_id, queue_id),
+            ControlCommand::PauseQueue {
+                port_id,
+                queue_id,
+                hw: true,
+            } => write!(f, "pause-queue {} {} --hw", port_id, queue_id),
+            ControlCommand::ResumeQueue { port_id, queue_id } => {
+                write!(f, "resume-queue {} {}", port_id, queue_id)
+            }
+            ControlCommand::StopNode(node_id) => write!(f, "stop-node {}", node_id),
+            ControlCommand::StartNode(node_id) => write!(f, "start-node {}", node_id),
+            ControlCommand::PauseNode(node_id) => write!(f, "pause-node {}", node_id),
+            ControlCommand::ResumeNode(node_id) => write!(f, "resume-node {}", node_id),
+            ControlCommand::MoveQueue {
+                port_id,
+                queue_id,
+                core_id,
+            } => write!(f, "move-queue {} {} {}", port_id, queue_id, core_id),
+            ControlCommand::Workers => write!(f, "workers"),
+            ControlCommand::Mempools => write!(f, "mempools"),
+            ControlCommand::Reta(port_id) => write!(f, "reta {}", port_id),
+            ControlCommand::SetReta { port_id, weights } => write!(
+                f,
+                "set-reta {} {}",
+                port_id,
+                weights
+                    .iter()
+                    .map(|w| w.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            ControlCommand::FlowControl {
+                port_id,
+                mode: None,
+            } => write!(f, "flowctrl {}", port_id),
+            ControlCommand::FlowControl {
+                port_id,
+                mode: Some(mode),
+            } => write!(f, "flowctrl {} {}", port_id, mode),
+            ControlCommand::RxFilter {
+                port_id,
+                expression: None,
+            } => write!(f, "rx-filter {}", port_id),
+            ControlCommand::RxFilter {
+                port_id,
+                expression: Some(expression),
+            } => write!(f, "rx-filter {} {}", port_id, expression),
+            ControlCommand::FeedKeys(feed) => write!(f, "feedkeys {}", feed),
+            ControlCommand::SetBurstSize(size) => write!(f, "set-burst-size {}", size),
+            ControlCommand::AttachPort(devargs) => write!(f, "attach-port {}", devargs),
+            ControlCommand::DetachPort(port_id) => write!(f, "detach-port {}", port_id),
+            ControlCommand::AddFeed { class, handler } => {
+                write!(f, "add-feed {} {}", class.name, handler)
+            }
+            ControlCommand::SnapshotState(path) => write!(f, "snapshot-state {}", path),
+            ControlCommand::DumpRecorder(None) => write!(f, "dump-recorder"),
+            ControlCommand::DumpRecorder(Some(path)) => write!(f, "dump-recorder {}", path),
+            ControlCommand::Config => write!(f, "config"),
+            ControlCommand::Version => write!(f, "version"),
+            ControlCommand::Shutdown => write!(f, "shutdown"),
+            ControlCommand::Help => write!(f, "help"),
+        }
+    }
+}
+
+/// Запрос от интерфейса управления к основному потоку
+pub struct ControlRequest {
+    pub command: ControlCommand,
+    reply: Sender<String>,
+}
+
+impl ControlRequest {
+    /// Отправляет ответ клиенту
+    pub fn respond(self, response: impl Into<String>) {
+        let _ = self.reply.send(response.into());
+    }
+
+    /// Отправляет результат выполнения команды
+    pub fn respond_result(self, result: Result<(), String>) {
+        match result {
+            Ok(()) => self.respond("OK"),
+            Err(e) => self.respond(format!("ERROR {}", e)),
+        }
+    }
+}
+
+/// Сервер управления на Unix-сокете
+///
+/// Принимает текстовые команды (по одной на строку) в отдельном потоке и
+/// передает их основному потоку через канал; рабочие потоки сокета не касаются.
+pub struct ControlServer {
+    path: String,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ControlServer {
+    /// Создает сокет по пути `path` и запускает поток приема подключений
+    pub fn start(path: &str) -> Result<(Self, Receiver<ControlRequest>), String> {
+        // Удаляем сокет, оставшийся от предыдущего запуска
+        let _ = std::fs::remove_file(path);
+
+        let listener = UnixListener::bind(path)
+            .map_err(|e| format!("Failed to bind control socket {}: {}", path, e))?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| format!("Failed to configure control socket: {}", e))?;
+
+        let (tx, rx) = mpsc::channel();
+        let running = Arc::new(AtomicBool::new(true));
+        let thread_running = running.clone();
+
+        let thread = thread::Builder::new()
+            .name("hfeec-control".to_string())
+            .spawn(move || {
+                housekeeping::pin_current_thread();
+
+                while thread_running.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        Ok((stream, _)) => {
+                            if let Err(e) = handle_client(stream, &tx) {
+                                crate::log_warn!("control", "Control client error", error = e);
+                            }
+                        }
+                        Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                            thread::sleep(ACCEPT_POLL_INTERVAL);
+                        }
+                        Err(e) => {
+                            crate::log_warn!("control", "Control socket accept failed", error = e);
+                            thread::sleep(ACCEPT_POLL_INTERVAL);
+                        }
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to spawn control thread: {}", e))?;
+
+        crate::log_info!("control", "Control socket listening", path = path);
+
+        Ok((
+            Self {
+                path: path.to_string(),
+                running,
+                thread: Some(thread),
+            },
+            rx,
+        ))
+    }
+
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Отправляет одну команду запущенному экземпляру и возвращает ответ
+pub fn query(path: &str, command: &str) -> Result<String, String> {
+    let mut stream = UnixStream::connect(path)
+        .map_err(|e| format!("Failed to connect to control socket {}: {}", path, e))?;
+    stream
+        .set_read_timeout(Some(REPLY_TIMEOUT + Duration::from_secs(1)))
+        .map_err(|e| format!("Failed to configure control socket: {}", e))?;
+
+    writeln!(stream, "{}", command)
+        .map_err(|e| format!("Failed to send control command: {}", e))?;
+    // Закрытие записи завершает сеанс на стороне сервера после ответа
+    stream
+        .shutdown(std::net::Shutdown::Write)
+        .map_err(|e| format!("Failed to send control command: {}", e))?;
+
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .map_err(|e| format!("Failed to read control reply: {}", e))?;
+    Ok(response)
+}
+
+/// Обслуживает одно подключение: команда - ответ, пока клиент не закроет сокет
+fn handle_client(stream: UnixStream, requests: &Sender<ControlRequest>) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    let mut writer = stream.try_clone()?;
+    let reader = BufReader::new(stream);
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match ControlCommand::parse(&line) {
+            Ok(ControlCommand::Help) => ControlCommand::help().to_string(),
+            Ok(command) => {
+                let (reply_tx, reply_rx) = mpsc::channel();
+                let request = ControlRequest {
+                    command,
+                    reply: reply_tx,
+                };
+
+                if requests.send(request).is_err() {
+                    "ERROR connector is shutting down".to_string()
+                } else {
+                    reply_rx
+                        .recv_timeout(REPLY_TIMEOUT)
+                        .unwrap_or_else(|_| "ERROR timed out waiting for reply".to_string())
+                }
+            }
+            Err(e) => format!("ERROR {}", e),
+        };
+
+        writer.write_all(response.as_bytes())?;
+        if !response.ends_with('\n') {
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+    }
+
+    Ok(())
+}
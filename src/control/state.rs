@@ -0,0 +1,85 @@
+// src/control/state.rs
+use std::fs;
+use std::sync::{Arc, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::control::snapshot::ConfigSnapshot;
+use crate::time;
+
+/// Компонент движка, состояние которого входит в снимок
+///
+/// `capture` вызывается, когда рабочие потоки остановлены между пачками,
+/// поэтому компоненты, изменяемые только рабочими потоками, видны в
+/// согласованном состоянии.
+pub trait StateSource: Send + Sync {
+    /// Записывает состояние в снимок; ключи получают префикс имени источника
+    fn capture(&self, prefix: &str, state: &mut ConfigSnapshot);
+}
+
+/// Зарегистрированный компонент и его имя
+type NamedSource = (String, Arc<dyn StateSource>);
+
+/// Реестр компонентов, входящих в снимок состояния движка
+///
+/// Снимок хранится в том же формате `key = value`, что и снимок
+/// конфигурации: его можно сравнить с другим снимком (`ConfigSnapshot::diff`),
+/// чтобы увидеть, что изменилось в представлении движка между моментами.
+#[derive(Clone, Default)]
+pub struct StateRegistry {
+    sources: Arc<RwLock<Vec<NamedSource>>>,
+}
+
+impl StateRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Добавляет компонент под именем `name` (префикс ключей `state.<name>.`)
+    pub fn register(&self, name: &str, source: Arc<dyn StateSource>) {
+        if let Ok(mut sources) = self.sources.write() {
+            sources.push((name.to_string(), source));
+        }
+    }
+
+    /// Количество зарегистрированных компонентов
+    pub fn len(&self) -> usize {
+        self.sources.read().map(|s| s.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Снимает состояние всех компонентов
+    ///
+    /// Вызывающий отвечает за точку покоя (см. `NumaManager::quiesce`).
+    /// Снимок содержит время по часам движка и по системным часам, чтобы его
+    /// можно было сопоставить с журналами и записью трафика.
+    pub fn capture(&self) -> ConfigSnapshot {
+        let mut state = ConfigSnapshot::new();
+        state.set("snapshot.engine_time_ns", time::now_ns());
+        state.set(
+            "snapshot.wall_time_ns",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(0),
+        );
+
+        if let Ok(sources) = self.sources.read() {
+            for (name, source) in sources.iter() {
+                source.capture(&format!("state.{}", name), &mut state);
+            }
+        }
+
+        state
+    }
+
+    /// Снимает состояние и записывает его в файл
+    pub fn write(&self, path: &str) -> Result<usize, String> {
+        let state = self.capture();
+        fs::write(path, state.to_text())
+            .map_err(|e| format!("Failed to write state snapshot {}: {}", path, e))?;
+        Ok(state.len())
+    }
+}
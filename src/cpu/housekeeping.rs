@@ -0,0 +1,192 @@
+// src/cpu/housekeeping.rs
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::{log_info, log_warn};
+
+/// Ядро служебных потоков (usize::MAX - не задано)
+static HOUSEKEEPING_CORE: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// Служебные потоки, закрепляемые за ядром (tid, имя)
+static THREADS: Mutex<Vec<(i32, String)>> = Mutex::new(Vec::new());
+
+/// Ядро служебных потоков
+pub fn core() -> Option<usize> {
+    match HOUSEKEEPING_CORE.load(Ordering::Acquire) {
+        usize::MAX => None,
+        core => Some(core),
+    }
+}
+
+/// Задает ядро служебных потоков и переносит на него уже запущенные
+///
+/// Потоки, запущенные до настройки (журнал, сокет управления), закрепляются
+/// здесь же, поэтому порядок запуска служб и применения конфигурации не важен.
+pub fn set_core(core: usize) {
+    HOUSEKEEPING_CORE.store(core, Ordering::Release);
+
+    let threads = THREADS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    for (tid, name) in threads.iter() {
+        if let Err(e) = pin_thread(*tid, core) {
+            log_warn!(
+                "cpu",
+                "Failed to pin housekeeping thread",
+                thread = name,
+                core = core,
+                error = e
+            );
+        }
+    }
+    log_info!(
+        "cpu",
+        "Housekeeping core set",
+        core = core,
+        threads = threads.len()
+    );
+}
+
+/// Отмечает текущий поток как служебный и закрепляет его за ядром служебных потоков
+///
+/// Вызывается в начале каждого потока вне пути обработки пакетов (мониторы,
+/// журнал, управление, публикация статистики). Пока ядро не задано, поток
+/// только запоминается.
+pub fn pin_current_thread() {
+    let tid = unsafe { libc::syscall(libc::SYS_gettid) } as i32;
+    let name = thread::current().name().unwrap_or("unnamed").to_string();
+
+    if let Some(core) = core() {
+        if let Err(e) = pin_thread(tid, core) {
+            log_warn!(
+                "cpu",
+                "Failed to pin housekeeping thread",
+                thread = name,
+                core = core,
+                error = e
+            );
+        }
+    }
+
+    let mut threads = THREADS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    // Завершившиеся потоки вытесняются новыми с тем же tid
+    threads.retain(|(known, _)| *known != tid);
+    threads.push((tid, name));
+}
+
+fn pin_thread(tid: i32, core: usize) -> Result<(), String> {
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(core, &mut set);
+        if libc::sched_setaffinity(tid, std::mem::size_of::<libc::cpu_set_t>(), &set) != 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Периодическая задача служебного ядра
+struct Task {
+    name: String,
+    interval: Duration,
+    next_run: Instant,
+    run: Box<dyn FnMut() + Send>,
+}
+
+/// Поток периодических задач на служебном ядре
+///
+/// Опрос состояния линков, таймеры протоколов и сбор статистики
+/// регистрируются здесь, а не в рабочих потоках: на ядрах обработки
+/// пакетов не выполняется ничего, кроме опроса очередей. Задачи
+/// выполняются по очереди в одном потоке, поэтому долгая задача
+/// задерживает остальные, но не рабочие потоки.
+pub struct Housekeeper {
+    tasks: Arc<Mutex<Vec<Task>>>,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Housekeeper {
+    /// Наибольший сон между проверками задач
+    const MAX_SLEEP: Duration = Duration::from_millis(100);
+
+    pub fn start() -> Result<Self, String> {
+        let tasks: Arc<Mutex<Vec<Task>>> = Arc::new(Mutex::new(Vec::new()));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread_tasks = tasks.clone();
+        let thread_running = running.clone();
+        let thread = thread::Builder::new()
+            .name("hfeec-housekeeping".to_string())
+            .spawn(move || {
+                pin_current_thread();
+
+                while thread_running.load(Ordering::Relaxed) {
+                    let mut sleep = Self::MAX_SLEEP;
+                    if let Ok(mut tasks) = thread_tasks.lock() {
+                        let now = Instant::now();
+                        for task in tasks.iter_mut() {
+                            if task.next_run <= now {
+                                (task.run)();
+                                task.next_run = now + task.interval;
+                            }
+                            sleep = sleep.min(task.next_run.saturating_duration_since(now));
+                        }
+                    }
+                    thread::sleep(sleep);
+                }
+            })
+            .map_err(|e| format!("Failed to spawn housekeeping thread: {}", e))?;
+
+        Ok(Self {
+            tasks,
+            running,
+            thread: Some(thread),
+        })
+    }
+
+    /// Добавляет задачу, выполняемую раз в `interval` (первый раз - сразу)
+    pub fn add_task(&self, name: &str, interval: Duration, run: impl FnMut() + Send + 'static) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            tasks.push(Task {
+                name: name.to_string(),
+                interval,
+                next_run: Instant::now(),
+                run: Box::new(run),
+            });
+        }
+    }
+
+    /// Удаляет задачу по имени
+    pub fn remove_task(&self, name: &str) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            tasks.retain(|task| task.name != name);
+        }
+    }
+
+    /// Имена зарегистрированных задач
+    pub fn task_names(&self) -> Vec<String> {
+        self.tasks
+            .lock()
+            .map(|tasks| tasks.iter().map(|task| task.name.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Останавливает поток задач
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for Housekeeper {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
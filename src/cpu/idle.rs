@@ -0,0 +1,184 @@
+// src/cpu/idle.rs
+use std::fmt;
+use std::time::Duration;
+
+/// Пустых опросов подряд до начала отступа по умолчанию
+pub const DEFAULT_IDLE_AFTER: u32 = 1024;
+
+/// Пауза между опросами в режиме сна по умолчанию (мкс)
+pub const DEFAULT_IDLE_SLEEP_US: u32 = 50;
+
+/// Наибольшее ожидание прерывания RX по умолчанию (мс)
+///
+/// Ограничивает задержку реакции потока на остановку и пакеты, пришедшие
+/// между последним опросом и взведением прерывания.
+pub const DEFAULT_IDLE_INTERRUPT_TIMEOUT_MS: u32 = 10;
+
+/// Максимум инструкций `pause` между опросами (~10 мкс на современных x86)
+const MAX_PAUSES: u32 = 128;
+
+/// Поведение рабочего потока при пустых опросах очереди
+///
+/// Чистый опрос дает минимальную задержку, но занимает ядро целиком даже
+/// без трафика. На тестовых стендах и портах с редкими пакетами поток
+/// после `after` пустых опросов подряд начинает уступать ядро; первый
+/// непустой опрос возвращает его к чистому опросу.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IdlePolicy {
+    /// Опрос без пауз (production)
+    #[default]
+    Spin,
+    /// Экспоненциально растущая серия `pause` между опросами
+    Pause { after: u32 },
+    /// Серия `pause`, затем `usleep(sleep_us)` между опросами после `after` пустых опросов
+    Sleep { after: u32, sleep_us: u32 },
+    /// Ожидание прерывания очереди RX (epoll) после `after` пустых опросов
+    ///
+    /// Требует поддержки прерываний RX в PMD; очереди без нее и источники,
+    /// не являющиеся очередью NIC, переходят к `Sleep`.
+    Interrupt { after: u32, timeout_ms: u32 },
+}
+
+impl IdlePolicy {
+    /// Разбирает `spin`, `pause[,<after>]`, `sleep[,<after>[,<us>]]` или
+    /// `interrupt[,<after>[,<timeout_ms>]]`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut parts = spec.split(',');
+        let mode = parts.next().unwrap_or_default();
+        let mut next = |default: u32| -> Result<u32, String> {
+            match parts.next() {
+                Some(value) => value
+                    .parse()
+                    .map_err(|_| format!("Invalid idle policy value '{}'", value)),
+                None => Ok(default),
+            }
+        };
+
+        let policy = match mode {
+            "spin" => IdlePolicy::Spin,
+            "pause" => IdlePolicy::Pause {
+                after: next(DEFAULT_IDLE_AFTER)?,
+            },
+            "sleep" => IdlePolicy::Sleep {
+                after: next(DEFAULT_IDLE_AFTER)?,
+                sleep_us: next(DEFAULT_IDLE_SLEEP_US)?,
+            },
+            "interrupt" => IdlePolicy::Interrupt {
+                after: next(DEFAULT_IDLE_AFTER)?,
+                timeout_ms: next(DEFAULT_IDLE_INTERRUPT_TIMEOUT_MS)?,
+            },
+            _ => {
+                return Err(format!(
+                    "Invalid idle policy '{}': expected spin, pause, sleep or interrupt",
+                    spec
+                ))
+            }
+        };
+
+        if let IdlePolicy::Sleep { sleep_us: 0, .. } | IdlePolicy::Interrupt { timeout_ms: 0, .. } =
+            policy
+        {
+            return Err(format!("Invalid idle policy '{}': wait must be > 0", spec));
+        }
+        Ok(policy)
+    }
+
+    /// Политика для источника без прерываний: ожидание прерывания
+    /// заменяется сном
+    pub fn polling(self) -> Self {
+        match self {
+            IdlePolicy::Interrupt { after, .. } => IdlePolicy::Sleep {
+                after,
+                sleep_us: DEFAULT_IDLE_SLEEP_US,
+            },
+            policy => policy,
+        }
+    }
+}
+
+impl fmt::Display for IdlePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdlePolicy::Spin => write!(f, "spin"),
+            IdlePolicy::Pause { after } => write!(f, "pause,{}", after),
+            IdlePolicy::Sleep { after, sleep_us } => write!(f, "sleep,{},{}", after, sleep_us),
+            IdlePolicy::Interrupt { after, timeout_ms } => {
+                write!(f, "interrupt,{},{}", after, timeout_ms)
+            }
+        }
+    }
+}
+
+/// Состояние отступа одного рабочего потока
+///
+/// Принадлежит потоку; вызывается после каждого опроса.
+#[derive(Debug)]
+pub struct IdleBackoff {
+    policy: IdlePolicy,
+    empty_polls: u32,
+    pauses: u32,
+}
+
+impl IdleBackoff {
+    pub fn new(policy: IdlePolicy) -> Self {
+        Self {
+            policy,
+            empty_polls: 0,
+            pauses: 1,
+        }
+    }
+
+    /// Учитывает непустой опрос: следующий простой начинается с чистого опроса
+    #[inline(always)]
+    pub fn reset(&mut self) {
+        self.empty_polls = 0;
+        self.pauses = 1;
+    }
+
+    /// Учитывает пустой опрос и уступает ядро согласно политике
+    ///
+    /// Возвращает срок ожидания прерывания RX (мс), если поток должен уснуть
+    /// до прихода пакетов: ожидание выполняет вызывающий, владеющий очередью.
+    #[inline(always)]
+    pub fn idle(&mut self) -> Option<u32> {
+        let after = match self.policy {
+            // Без pause: на части процессоров она занимает сотни тактов
+            IdlePolicy::Spin => return None,
+            IdlePolicy::Pause { after }
+            | IdlePolicy::Sleep { after, .. }
+            | IdlePolicy::Interrupt { after, .. } => after,
+        };
+
+        if self.empty_polls < after {
+            self.empty_polls += 1;
+            std::hint::spin_loop();
+            return None;
+        }
+        if let IdlePolicy::Interrupt { timeout_ms, .. } = self.policy {
+            return Some(timeout_ms);
+        }
+        self.backoff();
+        None
+    }
+
+    /// Переходит к политике без прерываний (прерывание очереди недоступно)
+    pub fn disable_interrupts(&mut self) {
+        self.policy = self.policy.polling();
+    }
+
+    #[cold]
+    fn backoff(&mut self) {
+        if let IdlePolicy::Sleep { sleep_us, .. } = self.policy {
+            // Сон начинается, когда серия pause достигла максимума
+            if self.pauses >= MAX_PAUSES {
+                std::thread::sleep(Duration::from_micros(sleep_us as u64));
+                return;
+            }
+        }
+
+        for _ in 0..self.pauses {
+            std::hint::spin_loop();
+        }
+        self.pauses = (self.pauses * 2).min(MAX_PAUSES);
+    }
+}
@@ -0,0 +1,296 @@
+// src/cpu/isolation.rs
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+
+use crate::cpu::topology::parse_cpu_list;
+
+const ISOLATED_CPUS: &str = "/sys/devices/system/cpu/isolated";
+const NOHZ_FULL_CPUS: &str = "/sys/devices/system/cpu/nohz_full";
+const KERNEL_CMDLINE: &str = "/proc/cmdline";
+
+/// Проверка готовности ядер рабочих потоков при запуске
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IsolationPolicy {
+    /// Не проверять
+    Off,
+    /// Вывести отчет о готовности
+    #[default]
+    Report,
+    /// Вывести отчет и прервать запуск, если ядра не готовы
+    Strict,
+}
+
+impl IsolationPolicy {
+    /// Разбирает `off`, `report` или `strict`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        match spec {
+            "off" => Ok(IsolationPolicy::Off),
+            "report" => Ok(IsolationPolicy::Report),
+            "strict" => Ok(IsolationPolicy::Strict),
+            _ => Err(format!(
+                "Invalid CPU isolation policy '{}': expected off, report or strict",
+                spec
+            )),
+        }
+    }
+}
+
+impl fmt::Display for IsolationPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IsolationPolicy::Off => write!(f, "off"),
+            IsolationPolicy::Report => write!(f, "report"),
+            IsolationPolicy::Strict => write!(f, "strict"),
+        }
+    }
+}
+
+/// Готовность одного ядра рабочего потока
+#[derive(Debug, Clone)]
+pub struct CoreReadiness {
+    pub core_id: usize,
+    /// Ядро исключено из планировщика (isolcpus)
+    pub isolated: bool,
+    /// Тик планировщика отключен (nohz_full)
+    pub nohz_full: bool,
+    /// Обратные вызовы RCU вынесены с ядра (rcu_nocbs)
+    pub rcu_nocbs: bool,
+    /// irqbalance может направлять прерывания на ядро
+    pub irqbalance: bool,
+    /// Прерываний, обслуживаемых ядром (effective_affinity)
+    pub irqs: usize,
+    /// Регулятор частоты (None - cpufreq недоступен)
+    pub governor: Option<String>,
+}
+
+impl CoreReadiness {
+    /// Найденные проблемы ядра
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !self.isolated {
+            problems.push(format!("core {} not in isolcpus", self.core_id));
+        }
+        if !self.nohz_full {
+            problems.push(format!("core {} not in nohz_full", self.core_id));
+        }
+        if !self.rcu_nocbs {
+            problems.push(format!("core {} not in rcu_nocbs", self.core_id));
+        }
+        if self.irqbalance {
+            problems.push(format!("irqbalance may target core {}", self.core_id));
+        }
+        if self.irqs > 0 {
+            problems.push(format!(
+                "core {} handles {} interrupts",
+                self.core_id, self.irqs
+            ));
+        }
+        match self.governor.as_deref() {
+            Some("performance") | None => {}
+            Some(governor) => problems.push(format!(
+                "core {} governor is '{}', not 'performance'",
+                self.core_id, governor
+            )),
+        }
+        problems
+    }
+}
+
+/// Отчет о готовности ядер рабочих потоков к обработке без джиттера
+///
+/// Ядро рабочего потока должно быть изолировано от планировщика (isolcpus),
+/// тика (nohz_full) и обратных вызовов RCU (rcu_nocbs), не получать
+/// прерываний (irqbalance) и работать на максимальной частоте (регулятор
+/// performance). Каждое нарушение - источник пауз в десятки микросекунд.
+#[derive(Debug, Clone)]
+pub struct IsolationReport {
+    pub cores: Vec<CoreReadiness>,
+    /// irqbalance запущен
+    pub irqbalance_running: bool,
+}
+
+impl IsolationReport {
+    /// Проверяет ядра `cores` по sysfs, /proc/cmdline и /proc/irq
+    pub fn check(cores: &[usize]) -> Self {
+        let isolated = read_cpu_list(ISOLATED_CPUS);
+        let nohz_full = read_cpu_list(NOHZ_FULL_CPUS);
+        let rcu_nocbs = kernel_rcu_nocbs();
+        let irqbalance_banned = irqbalance_banned_cpus();
+        let irqs = irqs_per_cpu();
+
+        let cores = cores
+            .iter()
+            .map(|&core_id| {
+                // irqbalance сам обходит ядра isolcpus и nohz_full
+                let irqbalance = irqbalance_banned.as_ref().is_some_and(|banned| {
+                    !banned.contains(&core_id)
+                        && !isolated.contains(&core_id)
+                        && !nohz_full.contains(&core_id)
+                });
+                CoreReadiness {
+                    core_id,
+                    isolated: isolated.contains(&core_id),
+                    nohz_full: nohz_full.contains(&core_id),
+                    rcu_nocbs: rcu_nocbs
+                        .as_ref()
+                        .is_some_and(|cpus| cpus.is_empty() || cpus.contains(&core_id)),
+                    irqbalance,
+                    irqs: irqs.get(&core_id).copied().unwrap_or(0),
+                    governor: fs::read_to_string(format!(
+                        "/sys/devices/system/cpu/cpu{}/cpufreq/scaling_governor",
+                        core_id
+                    ))
+                    .ok()
+                    .map(|governor| governor.trim().to_string()),
+                }
+            })
+            .collect();
+
+        Self {
+            cores,
+            irqbalance_running: irqbalance_banned.is_some(),
+        }
+    }
+
+    /// Все найденные проблемы
+    pub fn problems(&self) -> Vec<String> {
+        self.cores
+            .iter()
+            .flat_map(CoreReadiness::problems)
+            .collect()
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.cores.iter().all(|core| core.problems().is_empty())
+    }
+}
+
+impl fmt::Display for IsolationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flag = |ok: bool| if ok { "yes" } else { "NO" };
+
+        writeln!(f, "==== CPU Isolation Readiness ====")?;
+        writeln!(
+            f,
+            "irqbalance: {}",
+            if self.irqbalance_running {
+                "running"
+            } else {
+                "not running"
+            }
+        )?;
+        writeln!(
+            f,
+            "{:>5} {:>8} {:>9} {:>9} {:>10} {:>5} {:>12}",
+            "core", "isolcpus", "nohz_full", "rcu_nocbs", "irqbalance", "irqs", "governor"
+        )?;
+        for core in &self.cores {
+            writeln!(
+                f,
+                "{:>5} {:>8} {:>9} {:>9} {:>10} {:>5} {:>12}",
+                core.core_id,
+                flag(core.isolated),
+                flag(core.nohz_full),
+                flag(core.rcu_nocbs),
+                if core.irqbalance { "TARGETED" } else { "no" },
+                core.irqs,
+                core.governor.as_deref().unwrap_or("n/a")
+            )?;
+        }
+
+        let problems = self.problems();
+        if problems.is_empty() {
+            return writeln!(f, "All worker cores are ready");
+        }
+        writeln!(f, "{} problems:", problems.len())?;
+        for problem in problems {
+            writeln!(f, "  {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+fn read_cpu_list(path: &str) -> Vec<usize> {
+    // Пустой файл или "(null)" - список не задан
+    fs::read_to_string(path)
+        .map(|list| parse_cpu_list(&list))
+        .unwrap_or_default()
+}
+
+/// Ядра rcu_nocbs из командной строки ядра (пустой список - все ядра,
+/// None - параметр не задан)
+fn kernel_rcu_nocbs() -> Option<Vec<usize>> {
+    let cmdline = fs::read_to_string(KERNEL_CMDLINE).ok()?;
+    cmdline.split_whitespace().find_map(|arg| match arg {
+        "rcu_nocbs" => Some(Vec::new()),
+        _ => arg.strip_prefix("rcu_nocbs=").map(parse_cpu_list),
+    })
+}
+
+/// Ядра, исключенные из балансировки запущенным irqbalance
+/// (None - irqbalance не запущен)
+///
+/// Исключения задаются переменными окружения процесса
+/// IRQBALANCE_BANNED_CPULIST или IRQBALANCE_BANNED_CPUS (шестнадцатеричная маска).
+fn irqbalance_banned_cpus() -> Option<Vec<usize>> {
+    let pid = fs::read_dir("/proc").ok()?.flatten().find_map(|entry| {
+        let name = entry.file_name();
+        let pid = name.to_str()?;
+        if !pid.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let comm = fs::read_to_string(entry.path().join("comm")).ok()?;
+        (comm.trim() == "irqbalance").then(|| pid.to_string())
+    })?;
+
+    // Окружение чужого процесса может быть недоступно: тогда исключений нет
+    let environ = fs::read(format!("/proc/{}/environ", pid)).unwrap_or_default();
+    let mut banned = Vec::new();
+    for var in environ.split(|&b| b == 0) {
+        let var = String::from_utf8_lossy(var);
+        if let Some(list) = var.strip_prefix("IRQBALANCE_BANNED_CPULIST=") {
+            banned.extend(parse_cpu_list(list));
+        } else if let Some(mask) = var.strip_prefix("IRQBALANCE_BANNED_CPUS=") {
+            banned.extend(parse_cpu_mask(mask));
+        }
+    }
+    Some(banned)
+}
+
+/// Разбирает шестнадцатеричную маску ядер ("00000000,000000f0")
+fn parse_cpu_mask(mask: &str) -> Vec<usize> {
+    let digits: Vec<u32> = mask
+        .trim()
+        .chars()
+        .filter(|&c| c != ',')
+        .filter_map(|c| c.to_digit(16))
+        .collect();
+
+    let mut cpus = Vec::new();
+    for (index, digit) in digits.iter().rev().enumerate() {
+        for bit in 0..4 {
+            if digit & (1 << bit) != 0 {
+                cpus.push(index * 4 + bit);
+            }
+        }
+    }
+    cpus
+}
+
+/// Число прерываний, фактически обслуживаемых каждым ядром
+fn irqs_per_cpu() -> HashMap<usize, usize> {
+    let mut counts = HashMap::new();
+    let Ok(entries) = fs::read_dir("/proc/irq") else {
+        return counts;
+    };
+    for entry in entries.flatten() {
+        let Ok(list) = fs::read_to_string(entry.path().join("effective_affinity_list")) else {
+            continue;
+        };
+        for cpu in parse_cpu_list(&list) {
+            *counts.entry(cpu).or_insert(0) += 1;
+        }
+    }
+    counts
+}
@@ -0,0 +1,151 @@
+// src/cpu/migration.rs
+use std::fs;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::cpu::housekeeping;
+use crate::log_warn;
+use crate::numa::node::{WorkerControl, NO_CORE_CHANGE};
+
+/// Рабочий поток под контролем: порт, очередь и параметры управления
+pub type WorkerControlRef = (u16, u16, Arc<WorkerControl>);
+
+/// Нарушение привязки рабочего потока к ядру
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AffinityViolation {
+    /// Поток выполнялся на другом ядре
+    Migrated { expected: usize, actual: usize },
+    /// Назначенное ядро исключено из маски потока (например, изменен cpuset)
+    MaskChanged { expected: usize },
+}
+
+/// Проверка того, что рабочие потоки остаются на назначенных ядрах
+///
+/// Контейнеры и изменения cpuset могут незаметно перенести поток на другое
+/// ядро. Поток проверки периодически читает последнее ядро потока из
+/// `/proc/self/task/<tid>/stat` и маску `sched_getaffinity`, пишет в журнал
+/// предупреждение и при `repin` возвращает поток на назначенное ядро.
+pub struct MigrationGuard {
+    violations: Arc<AtomicU64>,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MigrationGuard {
+    /// Запускает проверку для указанных рабочих потоков
+    pub fn start(
+        workers: Vec<WorkerControlRef>,
+        interval: Duration,
+        repin: bool,
+    ) -> Result<Self, String> {
+        let violations = Arc::new(AtomicU64::new(0));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread_violations = violations.clone();
+        let thread_running = running.clone();
+        let thread = thread::Builder::new()
+            .name("hfeec-affinity".to_string())
+            .spawn(move || {
+                housekeeping::pin_current_thread();
+
+                while thread_running.load(Ordering::Relaxed) {
+                    for (port_id, queue_id, control) in &workers {
+                        let Some(violation) = check_worker(control) else {
+                            continue;
+                        };
+
+                        thread_violations.fetch_add(1, Ordering::Relaxed);
+                        match violation {
+                            AffinityViolation::Migrated { expected, actual } => log_warn!(
+                                "affinity",
+                                "Worker thread migrated off its core",
+                                port = port_id,
+                                queue = queue_id,
+                                expected = expected,
+                                actual = actual
+                            ),
+                            AffinityViolation::MaskChanged { expected } => log_warn!(
+                                "affinity",
+                                "Worker core removed from thread affinity mask",
+                                port = port_id,
+                                queue = queue_id,
+                                expected = expected
+                            ),
+                        }
+
+                        if repin {
+                            let core = control.current_core.load(Ordering::Relaxed);
+                            control.target_core.store(core, Ordering::Release);
+                        }
+                    }
+
+                    thread::sleep(interval);
+                }
+            })
+            .map_err(|e| format!("Failed to spawn affinity guard: {}", e))?;
+
+        Ok(Self {
+            violations,
+            running,
+            thread: Some(thread),
+        })
+    }
+
+    /// Количество обнаруженных нарушений
+    pub fn violations(&self) -> u64 {
+        self.violations.load(Ordering::Relaxed)
+    }
+
+    /// Останавливает проверку
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Проверяет привязку одного рабочего потока
+fn check_worker(control: &WorkerControl) -> Option<AffinityViolation> {
+    let tid = control.tid.load(Ordering::Acquire);
+    // Поток еще не запущен или выполняет перенос на другое ядро
+    if tid == 0 || control.target_core.load(Ordering::Acquire) != NO_CORE_CHANGE {
+        return None;
+    }
+
+    let expected = control.current_core.load(Ordering::Acquire);
+
+    if !affinity_contains(tid, expected)? {
+        return Some(AffinityViolation::MaskChanged { expected });
+    }
+
+    let actual = last_cpu(tid)?;
+    if actual != expected {
+        return Some(AffinityViolation::Migrated { expected, actual });
+    }
+
+    None
+}
+
+/// Ядро, на котором поток выполнялся последним (поле `processor` из stat)
+fn last_cpu(tid: i32) -> Option<usize> {
+    let stat = fs::read_to_string(format!("/proc/self/task/{}/stat", tid)).ok()?;
+    // Имя потока может содержать пробелы, поэтому поля считаются после ')'
+    let (_, fields) = stat.rsplit_once(')')?;
+    // Поле 39 по нумерации proc(5); после ')' поля начинаются с 3-го
+    fields.split_whitespace().nth(36)?.parse().ok()
+}
+
+/// Проверяет, входит ли ядро в маску привязки потока
+fn affinity_contains(tid: i32, core_id: usize) -> Option<bool> {
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        let ret = libc::sched_getaffinity(tid, std::mem::size_of::<libc::cpu_set_t>(), &mut set);
+        if ret != 0 {
+            return None;
+        }
+        Some(libc::CPU_ISSET(core_id, &set))
+    }
+}
@@ -1 +1,8 @@
+pub mod housekeeping;
+pub mod idle;
+pub mod isolation;
+#[cfg(feature = "dpdk")]
+pub mod migration;
+pub mod power;
+pub mod realtime;
 pub mod topology;
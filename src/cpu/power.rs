@@ -0,0 +1,143 @@
+// src/cpu/power.rs
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::{log_info, log_warn};
+
+const CPU_DMA_LATENCY: &str = "/dev/cpu_dma_latency";
+
+/// Исходные настройки cpufreq ядра для восстановления
+#[derive(Debug, Clone)]
+struct CoreFrequency {
+    core_id: usize,
+    governor: Option<String>,
+    min_freq_khz: Option<String>,
+    max_freq_khz: Option<String>,
+}
+
+/// Параметры управления питанием рабочих ядер
+#[derive(Debug, Clone, Default)]
+pub struct PowerSettings {
+    /// Фиксированная частота ядра; None - максимальная частота cpuinfo_max_freq
+    pub frequency_khz: Option<u64>,
+    /// Запретить глубокие C-состояния через /dev/cpu_dma_latency
+    pub disable_deep_cstates: bool,
+}
+
+/// Фиксирует частоту рабочих ядер и запрещает глубокие C-состояния
+///
+/// Переходы частоты и выход из глубоких C-состояний дают десятки микросекунд
+/// джиттера. Требует прав на запись в sysfs и /dev/cpu_dma_latency; исходные
+/// значения восстанавливаются при уничтожении объекта. Ограничение C-состояний
+/// действует, пока открыт /dev/cpu_dma_latency.
+pub struct PowerGuard {
+    saved: Vec<CoreFrequency>,
+    dma_latency: Option<File>,
+}
+
+impl PowerGuard {
+    pub fn apply(cores: &[usize], settings: &PowerSettings) -> Result<Self, String> {
+        let mut guard = Self {
+            saved: Vec::with_capacity(cores.len()),
+            dma_latency: None,
+        };
+
+        for &core_id in cores {
+            let saved = CoreFrequency {
+                core_id,
+                governor: read_cpufreq(core_id, "scaling_governor"),
+                min_freq_khz: read_cpufreq(core_id, "scaling_min_freq"),
+                max_freq_khz: read_cpufreq(core_id, "scaling_max_freq"),
+            };
+
+            if saved.governor.is_none() {
+                log_warn!("cpu", "cpufreq not available for core", core = core_id);
+                continue;
+            }
+
+            let frequency = match settings.frequency_khz {
+                Some(khz) => khz.to_string(),
+                None => read_cpufreq(core_id, "cpuinfo_max_freq").ok_or_else(|| {
+                    format!("Failed to read cpuinfo_max_freq for core {}", core_id)
+                })?,
+            };
+
+            guard.saved.push(saved);
+
+            write_cpufreq(core_id, "scaling_governor", "performance")?;
+            // Порядок важен: минимум не может превышать максимум
+            write_cpufreq(core_id, "scaling_max_freq", &frequency)?;
+            write_cpufreq(core_id, "scaling_min_freq", &frequency)?;
+
+            log_info!(
+                "cpu",
+                "Pinned core frequency",
+                core = core_id,
+                khz = frequency
+            );
+        }
+
+        if settings.disable_deep_cstates {
+            let mut file = OpenOptions::new()
+                .write(true)
+                .open(CPU_DMA_LATENCY)
+                .map_err(|e| format!("Failed to open {}: {}", CPU_DMA_LATENCY, e))?;
+            file.write_all(&0i32.to_ne_bytes())
+                .map_err(|e| format!("Failed to write {}: {}", CPU_DMA_LATENCY, e))?;
+            guard.dma_latency = Some(file);
+
+            log_info!("cpu", "Deep C-states disabled");
+        }
+
+        Ok(guard)
+    }
+
+    /// Восстанавливает исходные настройки (вызывается и при уничтожении)
+    pub fn restore(&mut self) {
+        for saved in self.saved.drain(..) {
+            // Сначала минимум, чтобы исходный максимум не оказался ниже него
+            let files = [
+                ("scaling_min_freq", &saved.min_freq_khz),
+                ("scaling_max_freq", &saved.max_freq_khz),
+                ("scaling_min_freq", &saved.min_freq_khz),
+                ("scaling_governor", &saved.governor),
+            ];
+
+            for (name, value) in files {
+                if let Some(value) = value {
+                    // Повторная запись минимума может временно не пройти
+                    let _ = write_cpufreq(saved.core_id, name, value);
+                }
+            }
+        }
+
+        if self.dma_latency.take().is_some() {
+            log_info!("cpu", "Deep C-states restored");
+        }
+    }
+}
+
+impl Drop for PowerGuard {
+    fn drop(&mut self) {
+        self.restore();
+    }
+}
+
+fn cpufreq_path(core_id: usize, name: &str) -> PathBuf {
+    PathBuf::from(format!(
+        "/sys/devices/system/cpu/cpu{}/cpufreq/{}",
+        core_id, name
+    ))
+}
+
+fn read_cpufreq(core_id: usize, name: &str) -> Option<String> {
+    fs::read_to_string(cpufreq_path(core_id, name))
+        .ok()
+        .map(|value| value.trim().to_string())
+}
+
+fn write_cpufreq(core_id: usize, name: &str, value: &str) -> Result<(), String> {
+    fs::write(cpufreq_path(core_id, name), value)
+        .map_err(|e| format!("Failed to set {} for core {}: {}", name, core_id, e))
+}
@@ -0,0 +1,138 @@
+// src/cpu/realtime.rs
+use std::fmt;
+use std::fs;
+
+use crate::cpu::housekeeping;
+use crate::log_warn;
+
+/// Приоритет рабочих потоков по умолчанию
+pub const DEFAULT_RT_PRIORITY: i32 = 50;
+
+/// Наибольший допустимый приоритет: 99 остается потокам ядра (migration, watchdog)
+const MAX_RT_PRIORITY: i32 = 98;
+
+/// Ограничение времени realtime-потоков (RT throttling)
+const SCHED_RT_RUNTIME: &str = "/proc/sys/kernel/sched_rt_runtime_us";
+
+/// Класс планирования реального времени
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RealtimeClass {
+    Fifo,
+    RoundRobin,
+}
+
+/// Планирование рабочих потоков с приоритетом реального времени
+///
+/// Рабочий поток с SCHED_FIFO не вытесняется обычными потоками и потоками
+/// ядра с меньшим приоритетом (kworker, ksoftirqd), которые иначе
+/// отнимают у него ядро на сотни микросекунд. Потоки, которым ядро CPU
+/// нужно для работы системы, на ядре рабочего потока голодают: ядра
+/// рабочих потоков должны быть изолированы (isolcpus, nohz_full, rcu_nocbs).
+/// Служебное ядро всегда остается с обычным приоритетом.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RealtimeConfig {
+    pub class: RealtimeClass,
+    /// Приоритет 1..=98
+    pub priority: i32,
+}
+
+impl RealtimeConfig {
+    /// Разбирает `fifo[,<priority>]` или `rr[,<priority>]`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut parts = spec.split(',');
+        let class = match parts.next().unwrap_or_default() {
+            "fifo" => RealtimeClass::Fifo,
+            "rr" => RealtimeClass::RoundRobin,
+            _ => {
+                return Err(format!(
+                    "Invalid realtime scheduling '{}': expected fifo or rr[,<priority>]",
+                    spec
+                ))
+            }
+        };
+        let priority = match parts.next() {
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("Invalid realtime priority '{}'", value))?,
+            None => DEFAULT_RT_PRIORITY,
+        };
+        if parts.next().is_some() {
+            return Err(format!("Invalid realtime scheduling '{}'", spec));
+        }
+        if !(1..=MAX_RT_PRIORITY).contains(&priority) {
+            return Err(format!(
+                "Realtime priority {} out of range 1..={}",
+                priority, MAX_RT_PRIORITY
+            ));
+        }
+        Ok(Self { class, priority })
+    }
+
+    fn policy(&self) -> libc::c_int {
+        match self.class {
+            RealtimeClass::Fifo => libc::SCHED_FIFO,
+            RealtimeClass::RoundRobin => libc::SCHED_RR,
+        }
+    }
+
+    /// Переводит текущий поток на ядре `core_id` в класс реального времени
+    ///
+    /// Поток на служебном ядре не переводится: он делил бы ядро со
+    /// служебными потоками и вытеснял их.
+    pub fn apply_current_thread(&self, core_id: usize) -> Result<(), String> {
+        if housekeeping::core() == Some(core_id) {
+            return Err(format!(
+                "core {} is the housekeeping core, keeping normal priority",
+                core_id
+            ));
+        }
+
+        let param = libc::sched_param {
+            sched_priority: self.priority,
+        };
+        // 0 - текущий поток
+        if unsafe { libc::sched_setscheduler(0, self.policy(), &param) } != 0 {
+            return Err(format!(
+                "sched_setscheduler({}) failed: {} (requires CAP_SYS_NICE or RLIMIT_RTPRIO)",
+                self,
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for RealtimeClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RealtimeClass::Fifo => write!(f, "fifo"),
+            RealtimeClass::RoundRobin => write!(f, "rr"),
+        }
+    }
+}
+
+impl fmt::Display for RealtimeConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.class, self.priority)
+    }
+}
+
+/// Предупреждает, если ядро ограничивает время realtime-потоков
+///
+/// При включенном RT throttling (sched_rt_runtime_us != -1) постоянно
+/// занятый опросом поток SCHED_FIFO каждую секунду принудительно
+/// вытесняется на десятки миллисекунд.
+pub fn warn_rt_throttling() {
+    let Ok(runtime) = fs::read_to_string(SCHED_RT_RUNTIME) else {
+        return;
+    };
+    let runtime = runtime.trim();
+    if runtime != "-1" {
+        log_warn!(
+            "cpu",
+            "RT throttling is enabled, busy-polling realtime workers will be preempted",
+            sched_rt_runtime_us = runtime,
+            fix = "sysctl kernel.sched_rt_runtime_us=-1"
+        );
+    }
+}
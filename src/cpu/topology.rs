@@ -3,6 +3,7 @@ use std::fmt;
 use std::fs::{self, File};
 use std::io::{self, Read};
 use std::path::Path;
+use std::sync::OnceLock;
 
 use core_affinity::CoreId;
 
@@ -23,6 +24,16 @@ pub struct CpuTopology {
     /// List of cores belonging to each socket
     /// Key: Socket ID, Value: List of logical core IDs
     pub socket_cores: HashMap<usize, Vec<usize>>,
+    /// Mapping of logical cores to L3 cache domains (CCX on chiplet CPUs)
+    /// Key: Logical core ID, Value: lowest logical core ID sharing the L3 cache
+    pub l3_mapping: HashMap<usize, usize>,
+    /// Logical cores the process may run on (cgroup cpuset and affinity mask)
+    /// None: unknown, every core is allowed
+    pub allowed_cores: Option<HashSet<usize>>,
+    /// Logical cores never given to workers (core 0 by default)
+    pub reserved_cores: Vec<usize>,
+    /// Give workers every logical core, not only the first of each physical core
+    pub use_hyperthreads: bool,
 }
 
 impl CpuTopology {
@@ -35,12 +46,54 @@ impl CpuTopology {
             socket_mapping: HashMap::new(),
             sibling_cores: HashMap::new(),
             socket_cores: HashMap::new(),
+            l3_mapping: HashMap::new(),
+            allowed_cores: allowed_cpus(),
+            reserved_cores: vec![0],
+            use_hyperthreads: false,
         };
 
         topology.load_topology()?;
+        topology.load_cache_topology();
         Ok(topology)
     }
 
+    /// Loads L3 cache sharing from cpu*/cache/index*/shared_cpu_list
+    ///
+    /// Missing cache information leaves the mapping empty, which disables L3 grouping.
+    fn load_cache_topology(&mut self) {
+        let Ok(entries) = fs::read_dir("/sys/devices/system/cpu") else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let Some(cpu_id) = name
+                .to_str()
+                .and_then(|name| name.strip_prefix("cpu"))
+                .and_then(|id| id.parse::<usize>().ok())
+            else {
+                continue;
+            };
+
+            let Ok(caches) = fs::read_dir(entry.path().join("cache")) else {
+                continue;
+            };
+            for cache in caches.flatten() {
+                let path = cache.path();
+                let is_l3 =
+                    read_first_line(path.join("level")).is_ok_and(|level| level.trim() == "3");
+                if !is_l3 {
+                    continue;
+                }
+                if let Ok(shared) = read_first_line(path.join("shared_cpu_list")) {
+                    if let Some(&domain) = parse_cpu_list(&shared).iter().min() {
+                        self.l3_mapping.insert(cpu_id, domain);
+                    }
+                }
+            }
+        }
+    }
+
     /// Loads processor topology information from system files
     fn load_topology(&mut self) -> io::Result<()> {
         let cpu_path = Path::new("/sys/devices/system/cpu");
@@ -121,25 +174,52 @@ impl CpuTopology {
         result
     }
 
-    /// Returns a list of CoreId for core_affinity, excluding core 0
+    /// Sets which cores are kept away from workers
+    ///
+    /// `reserved_cores` replaces the default reservation of core 0; with
+    /// `use_hyperthreads` both siblings of a physical core may run workers.
+    pub fn set_core_policy(&mut self, reserved_cores: Vec<usize>, use_hyperthreads: bool) {
+        self.reserved_cores = reserved_cores;
+        self.use_hyperthreads = use_hyperthreads;
+    }
+
+    /// Checks if the process may run on the core (containers restrict this via cgroup cpuset)
+    pub fn is_allowed_core(&self, core_id: usize) -> bool {
+        self.allowed_cores
+            .as_ref()
+            .is_none_or(|allowed| allowed.contains(&core_id))
+    }
+
+    /// Checks if the core may run a worker under the current core policy
+    pub fn is_worker_core(&self, core_id: usize) -> bool {
+        self.is_allowed_core(core_id)
+            && !self.reserved_cores.contains(&core_id)
+            && (self.use_hyperthreads || self.is_primary_logical_core(core_id))
+    }
+
+    /// Returns a list of CoreId for core_affinity, excluding reserved cores
+    /// (and HT siblings unless `use_hyperthreads` is set)
     pub fn get_filtered_core_ids(&self) -> Vec<CoreId> {
-        let physical_ids = self.get_physical_core_ids();
+        let mut ids: Vec<usize> = if self.use_hyperthreads {
+            self.core_mapping.keys().copied().collect()
+        } else {
+            self.get_physical_core_ids()
+        };
+        ids.sort();
 
-        physical_ids
-            .iter()
-            .filter(|&&id| id != 0) // Exclude core 0
-            .map(|&id| CoreId { id })
+        ids.into_iter()
+            .filter(|&id| self.is_worker_core(id))
+            .map(|id| CoreId { id })
             .collect()
     }
 
-    /// Returns a list of CoreId for a specific NUMA node, excluding core 0 and HT threads
+    /// Returns a list of CoreId for a specific NUMA node, excluding reserved cores
+    /// (and HT siblings unless `use_hyperthreads` is set)
     pub fn get_socket_core_ids(&self, socket_id: usize) -> Vec<CoreId> {
-        let physical_cores = self.get_physical_core_ids();
-
         match self.socket_cores.get(&socket_id) {
             Some(cores) => cores
                 .iter()
-                .filter(|&&id| physical_cores.contains(&id) && id != 0)
+                .filter(|&&id| self.is_worker_core(id))
                 .map(|&id| CoreId { id })
                 .collect(),
             None => Vec::new(),
@@ -180,6 +260,11 @@ impl CpuTopology {
         args
     }
 
+    /// Returns the L3 cache domain (lowest core sharing the L3) for the specified core
+    pub fn get_l3_domain(&self, core_id: usize) -> Option<usize> {
+        self.l3_mapping.get(&core_id).copied()
+    }
+
     /// Returns the socket ID (NUMA node) for the specified core
     pub fn get_core_socket_id(&self, core_id: usize) -> Option<usize> {
         self.socket_mapping.get(&core_id).copied()
@@ -212,6 +297,11 @@ impl CpuTopology {
         println!("  Total logical cores: {}", self.total_cores);
         println!("  Physical cores: {}", self.physical_cores);
         println!("  Sockets (NUMA nodes): {}", self.sockets);
+        if let Some(allowed) = &self.allowed_cores {
+            let mut allowed: Vec<usize> = allowed.iter().copied().collect();
+            allowed.sort();
+            println!("  Allowed cores (cpuset): {:?}", allowed);
+        }
 
         println!("\nSocket mapping:");
         for socket_id in self.get_available_sockets() {
@@ -236,7 +326,9 @@ impl CpuTopology {
         }
 
         println!(
-            "\nFiltered core IDs (excluding HT and core 0): {:?}",
+            "\nWorker core IDs (reserved {:?}, hyperthreads {}): {:?}",
+            self.reserved_cores,
+            self.use_hyperthreads,
             self.get_filtered_core_ids()
                 .iter()
                 .map(|c| c.id)
@@ -274,6 +366,86 @@ impl fmt::Display for CpuTopology {
     }
 }
 
+/// Returns the number of configured logical cores, including cores outside the process cpuset
+///
+/// `num_cpus::get()` counts only the cores the process may use, so inside a
+/// container it is not an upper bound for core IDs.
+pub fn configured_cpus() -> usize {
+    let count = unsafe { libc::sysconf(libc::_SC_NPROCESSORS_CONF) };
+    if count > 0 {
+        count as usize
+    } else {
+        num_cpus::get()
+    }
+}
+
+/// Affinity mask of the process, captured once before any thread is pinned
+static PROCESS_AFFINITY: OnceLock<Option<Vec<usize>>> = OnceLock::new();
+
+/// Returns the logical cores the process may run on
+///
+/// Inside a container the cgroup cpuset restricts the cores, and
+/// sched_getaffinity may restrict them further (taskset, numactl). The
+/// affinity mask is captured on the first call: later EAL and housekeeping
+/// pinning of the main thread must not shrink it.
+fn allowed_cpus() -> Option<HashSet<usize>> {
+    let affinity = PROCESS_AFFINITY.get_or_init(thread_affinity).clone();
+    let cpuset = cgroup_cpuset();
+
+    match (affinity, cpuset) {
+        (Some(affinity), Some(cpuset)) => Some(
+            affinity
+                .into_iter()
+                .filter(|cpu| cpuset.contains(cpu))
+                .collect(),
+        ),
+        (Some(cpus), None) | (None, Some(cpus)) => Some(cpus.into_iter().collect()),
+        (None, None) => None,
+    }
+}
+
+/// Affinity mask of the calling thread
+fn thread_affinity() -> Option<Vec<usize>> {
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    if unsafe { libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set) } != 0
+    {
+        return None;
+    }
+    Some(
+        (0..libc::CPU_SETSIZE as usize)
+            .filter(|&cpu| unsafe { libc::CPU_ISSET(cpu, &set) })
+            .collect(),
+    )
+}
+
+/// Effective cpuset of the process cgroup (v2 or v1 cpuset controller)
+fn cgroup_cpuset() -> Option<Vec<usize>> {
+    let cgroups = fs::read_to_string("/proc/self/cgroup").ok()?;
+    for line in cgroups.lines() {
+        let mut fields = line.splitn(3, ':');
+        let (Some(_), Some(controllers), Some(path)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let file = if controllers.is_empty() {
+            format!("/sys/fs/cgroup{}/cpuset.cpus.effective", path)
+        } else if controllers.split(',').any(|c| c == "cpuset") {
+            format!("/sys/fs/cgroup/cpuset{}/cpuset.effective_cpus", path)
+        } else {
+            continue;
+        };
+        let Ok(list) = read_first_line(file) else {
+            continue;
+        };
+        let cpus = parse_cpu_list(&list);
+        if !cpus.is_empty() {
+            return Some(cpus);
+        }
+    }
+    None
+}
+
 /// Reads the first line from a file
 fn read_first_line<P: AsRef<Path>>(path: P) -> io::Result<String> {
     let mut file = File::open(path)?;
@@ -283,8 +455,34 @@ fn read_first_line<P: AsRef<Path>>(path: P) -> io::Result<String> {
     Ok(contents.lines().next().unwrap_or("").to_string())
 }
 
+/// Parses a strict core list "0-3,5" or "none" (empty list) from configuration
+pub fn parse_core_list(spec: &str) -> Result<Vec<usize>, String> {
+    if spec == "none" {
+        return Ok(Vec::new());
+    }
+
+    let mut cores = Vec::new();
+    for part in spec.split(',') {
+        let invalid = || format!("Invalid core list '{}'", spec);
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let start: usize = start.parse().map_err(|_| invalid())?;
+                let end: usize = end.parse().map_err(|_| invalid())?;
+                if start > end {
+                    return Err(invalid());
+                }
+                cores.extend(start..=end);
+            }
+            None => cores.push(part.parse().map_err(|_| invalid())?),
+        }
+    }
+    cores.sort_unstable();
+    cores.dedup();
+    Ok(cores)
+}
+
 /// Parses a processor list from a string in the format "0-3,5,7-9"
-fn parse_cpu_list(list: &str) -> Vec<usize> {
+pub(crate) fn parse_cpu_list(list: &str) -> Vec<usize> {
     let mut result = Vec::new();
 
     for part in list.trim().split(',') {
@@ -1,9 +1,387 @@
+use std::fmt;
 use std::os::raw::{c_uint, c_ushort};
+use std::time::Duration;
+
+use crate::control::snapshot::ConfigSnapshot;
+use crate::cpu::idle::IdlePolicy;
+use crate::cpu::isolation::IsolationPolicy;
+use crate::cpu::realtime::RealtimeConfig;
+use crate::cpu::topology::parse_core_list;
+use crate::dpdk::eventdev::{SchedulingMode, DEFAULT_EVENT_DEVICE};
+use crate::dpdk::extmem::ExternalMemoryConfig;
+use crate::dpdk::flow::FlowRule;
+use crate::dpdk::flow_ctrl::FlowControlMode;
+use crate::dpdk::init::{is_pci_address, normalize_pci_address, port_matches, DpdkPortInfo};
+use crate::dpdk::multiproc::ProcessType;
+use crate::dpdk::validate::ValidationPolicy;
+use crate::numa::supervisor::PanicPolicy;
+use crate::numa::watchdog::WatchdogConfig;
+use crate::packet::breaker::CircuitBreakerConfig;
+use crate::packet::parser;
+use crate::packet::reassembly::ReassemblyConfig;
+
+/// Способ указания порта в конфигурации
+///
+/// Числовые идентификаторы портов зависят от порядка probe, поэтому
+/// предпочтительно указывать порт по PCI адресу или MAC-адресу.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PortSelector {
+    /// Идентификатор порта DPDK
+    Id(u16),
+    /// PCI адрес (`0000:3b:00.0` или `3b:00.0`)
+    Pci(String),
+    /// MAC-адрес порта
+    Mac([u8; 6]),
+}
+
+impl PortSelector {
+    /// Разбирает строку: число - идентификатор порта, `xx:xx:xx:xx:xx:xx` - MAC,
+    /// иначе PCI адрес
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+
+        if let Ok(port_id) = value.parse::<u16>() {
+            return Ok(PortSelector::Id(port_id));
+        }
+
+        if let Some(mac) = parse_mac(value) {
+            return Ok(PortSelector::Mac(mac));
+        }
+
+        if is_pci_address(value) {
+            return Ok(PortSelector::Pci(normalize_pci_address(value)));
+        }
+
+        Err(format!(
+            "Invalid port selector '{}': expected port id, PCI address or MAC",
+            value
+        ))
+    }
+}
+
+impl fmt::Display for PortSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PortSelector::Id(port_id) => write!(f, "{}", port_id),
+            PortSelector::Pci(pci) => write!(f, "{}", pci),
+            PortSelector::Mac(mac) => write!(f, "{}", format_mac(mac)),
+        }
+    }
+}
+
+/// Разбирает MAC-адрес вида `aa:bb:cc:dd:ee:ff` (допускается разделитель `-`)
+pub fn parse_mac(value: &str) -> Option<[u8; 6]> {
+    let parts: Vec<&str> = value.split([':', '-']).collect();
+    if parts.len() != 6 {
+        return None;
+    }
+
+    let mut mac = [0u8; 6];
+    for (i, part) in parts.iter().enumerate() {
+        if part.len() != 2 {
+            return None;
+        }
+        mac[i] = u8::from_str_radix(part, 16).ok()?;
+    }
+
+    Some(mac)
+}
+
+/// Форматирует MAC-адрес в виде `aa:bb:cc:dd:ee:ff`
+pub fn format_mac(mac: &[u8; 6]) -> String {
+    mac.iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Параметры отдельного порта
+///
+/// Незаданные поля берутся из общих значений `DpdkConfig`, поэтому в одном
+/// приложении можно по-разному настроить, например, NIC рыночных данных
+/// (много очередей RX, RSS) и NIC отправки заявок (одна очередь, TSO).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortConfig {
+    pub selector: PortSelector,
+    pub num_rx_queues: Option<u16>,
+    pub num_tx_queues: Option<u16>,
+    pub rx_ring_size: Option<u32>,
+    pub tx_ring_size: Option<u32>,
+    pub promiscuous: Option<bool>,
+    pub use_rss: Option<bool>,
+    pub rss_hf: Option<u64>,
+    pub rss_key: Option<Vec<u8>>,
+    /// Максимальная длина кадра (включает поддержку Jumbo Frames)
+    pub max_rx_pkt_len: Option<u32>,
+    pub use_hw_checksum: Option<bool>,
+    pub use_tso: Option<bool>,
+    pub use_udp_tso: Option<bool>,
+    pub use_lro: Option<bool>,
+    pub use_gro: Option<bool>,
+    /// Правила Flow Director порта (действуют при `DpdkConfig::use_flow_director`)
+    pub flow_rules: Vec<FlowRule>,
+    /// Веса очередей в таблице перенаправления RSS (None - распределение PMD)
+    pub reta_weights: Option<Vec<u32>>,
+    /// Внешняя закрепленная память для буферов mbuf (None - память EAL)
+    pub external_memory: Option<ExternalMemoryConfig>,
+    /// Управление потоком Ethernet (None - настройка PMD и коммутатора)
+    pub flow_control: Option<FlowControlMode>,
+    /// Ядер декодирования в режиме конвейера (None - общее значение)
+    pub decode_cores: Option<u16>,
+    /// Закрепление рабочих потоков за ядрами: `(очередь, ядро)`
+    pub queue_cores: Vec<(u16, usize)>,
+}
+
+impl PortConfig {
+    /// Создает конфигурацию порта без переопределений
+    pub fn new(selector: PortSelector) -> Self {
+        Self {
+            selector,
+            num_rx_queues: None,
+            num_tx_queues: None,
+            rx_ring_size: None,
+            tx_ring_size: None,
+            promiscuous: None,
+            use_rss: None,
+            rss_hf: None,
+            rss_key: None,
+            max_rx_pkt_len: None,
+            use_hw_checksum: None,
+            use_tso: None,
+            use_udp_tso: None,
+            use_lro: None,
+            use_gro: None,
+            flow_rules: Vec::new(),
+            reta_weights: None,
+            external_memory: None,
+            flow_control: None,
+            decode_cores: None,
+            queue_cores: Vec::new(),
+        }
+    }
+
+    /// Задает количество очередей порта
+    pub fn with_queues(mut self, num_rx_queues: u16, num_tx_queues: u16) -> Self {
+        self.num_rx_queues = Some(num_rx_queues);
+        self.num_tx_queues = Some(num_tx_queues);
+        self
+    }
+
+    /// Задает размеры колец дескрипторов
+    pub fn with_ring_sizes(mut self, rx_ring_size: u32, tx_ring_size: u32) -> Self {
+        self.rx_ring_size = Some(rx_ring_size);
+        self.tx_ring_size = Some(tx_ring_size);
+        self
+    }
+
+    /// Включает или отключает неразборчивый режим
+    pub fn with_promiscuous(mut self, enabled: bool) -> Self {
+        self.promiscuous = Some(enabled);
+        self
+    }
+
+    /// Включает RSS с указанными типами хэширования (None - общие значения)
+    pub fn with_rss(mut self, rss_hf: Option<u64>, rss_key: Option<Vec<u8>>) -> Self {
+        self.use_rss = Some(true);
+        self.rss_hf = rss_hf;
+        self.rss_key = rss_key;
+        self
+    }
+
+    /// Отключает RSS
+    pub fn without_rss(mut self) -> Self {
+        self.use_rss = Some(false);
+        self
+    }
+
+    /// Включает поддержку Jumbo Frames
+    pub fn with_jumbo_frames(mut self, mtu: u32) -> Self {
+        self.max_rx_pkt_len = Some(mtu + 18); // Ethernet header (14) + VLAN tag (4)
+        self
+    }
+
+    /// Включает или отключает аппаратный подсчет контрольных сумм
+    pub fn with_hw_checksum(mut self, enabled: bool) -> Self {
+        self.use_hw_checksum = Some(enabled);
+        self
+    }
+
+    /// Включает или отключает TCP и UDP TSO
+    pub fn with_tso(mut self, tcp: bool, udp: bool) -> Self {
+        self.use_tso = Some(tcp);
+        self.use_udp_tso = Some(udp);
+        self
+    }
+
+    /// Включает или отключает LRO и GRO
+    pub fn with_receive_offload(mut self, lro: bool, gro: bool) -> Self {
+        self.use_lro = Some(lro);
+        self.use_gro = Some(gro);
+        self
+    }
+
+    /// Добавляет правило Flow Director (например, `FlowRule::perfect_match`)
+    pub fn with_flow_rule(mut self, rule: FlowRule) -> Self {
+        self.flow_rules.push(rule);
+        self
+    }
+
+    /// Задает веса очередей в таблице перенаправления RSS (`weights[q]` - вес очереди q)
+    ///
+    /// Например, `[0, 1, 1, 1]` убирает очередь 0 из RSS, оставляя ее для
+    /// фида, направленного правилом Flow Director.
+    pub fn with_reta_weights(mut self, weights: Vec<u32>) -> Self {
+        self.reta_weights = Some(weights);
+        self
+    }
+
+    /// Размещает буферы mbuf порта во внешней закрепленной памяти
+    ///
+    /// Например, `ExternalMemoryConfig::shared("/dev/hugepages/md0", 2 << 20)`
+    /// делает принятые кадры доступными процессу, отобразившему тот же файл.
+    pub fn with_external_memory(mut self, memory: ExternalMemoryConfig) -> Self {
+        self.external_memory = Some(memory);
+        self
+    }
+
+    /// Задает режим управления потоком (кадры паузы)
+    ///
+    /// Для портов рыночных данных обычно `FlowControlMode::None`: пауза от
+    /// коммутатора задерживает отправку заявок на время, которое не видно
+    /// ни в одном программном счетчике.
+    pub fn with_flow_control(mut self, mode: FlowControlMode) -> Self {
+        self.flow_control = Some(mode);
+        self
+    }
+
+    /// Включает режим конвейера с `decode_cores` ядрами декодирования (0 - отключает)
+    ///
+    /// Ядра очередей RX только принимают и разбирают заголовки, обработчики
+    /// выполняются на ядрах декодирования. Нужен, когда обработчик порта
+    /// дольше бюджета приема на пакет.
+    pub fn with_pipeline(mut self, decode_cores: u16) -> Self {
+        self.decode_cores = Some(decode_cores);
+        self
+    }
+
+    /// Закрепляет рабочий поток очереди `queue_id` за ядром `core_id`
+    ///
+    /// Незакрепленные очереди распределяются по кругу по остальным ядрам
+    /// узла NUMA порта. При программном RSS и eventdev `queue_id` - номер
+    /// рабочего потока порта. Ядро другого узла NUMA допускается с
+    /// предупреждением: пакеты и пулы порта остаются в памяти своего узла.
+    pub fn with_queue_core(mut self, queue_id: u16, core_id: usize) -> Self {
+        self.queue_cores.retain(|&(queue, _)| queue != queue_id);
+        self.queue_cores.push((queue_id, core_id));
+        self
+    }
+
+    /// Записывает переопределенные параметры порта в снимок
+    fn write_snapshot(&self, snapshot: &mut ConfigSnapshot) {
+        let prefix = format!("dpdk.port.{}", self.selector);
+        let mut set = |name: &str, value: Option<String>| {
+            if let Some(value) = value {
+                snapshot.set(&format!("{}.{}", prefix, name), value);
+            }
+        };
+
+        set("num_rx_queues", self.num_rx_queues.map(|v| v.to_string()));
+        set("num_tx_queues", self.num_tx_queues.map(|v| v.to_string()));
+        set("rx_ring_size", self.rx_ring_size.map(|v| v.to_string()));
+        set("tx_ring_size", self.tx_ring_size.map(|v| v.to_string()));
+        set("promiscuous", self.promiscuous.map(|v| v.to_string()));
+        set("use_rss", self.use_rss.map(|v| v.to_string()));
+        set("rss_hf", self.rss_hf.map(|v| format!("0x{:x}", v)));
+        set(
+            "rss_key",
+            self.rss_key
+                .as_ref()
+                .map(|key| key.iter().map(|b| format!("{:02x}", b)).collect()),
+        );
+        set("max_rx_pkt_len", self.max_rx_pkt_len.map(|v| v.to_string()));
+        set(
+            "use_hw_checksum",
+            self.use_hw_checksum.map(|v| v.to_string()),
+        );
+        set("use_tso", self.use_tso.map(|v| v.to_string()));
+        set("use_udp_tso", self.use_udp_tso.map(|v| v.to_string()));
+        set("use_lro", self.use_lro.map(|v| v.to_string()));
+        set("use_gro", self.use_gro.map(|v| v.to_string()));
+        set(
+            "external_memory",
+            self.external_memory.as_ref().map(|m| m.to_string()),
+        );
+        set("flow_control", self.flow_control.map(|m| m.to_string()));
+        set("decode_cores", self.decode_cores.map(|v| v.to_string()));
+
+        if !self.flow_rules.is_empty() {
+            snapshot.set_list(&format!("{}.flow_rules", prefix), Some(&self.flow_rules));
+        }
+        if let Some(weights) = &self.reta_weights {
+            snapshot.set_list(&format!("{}.reta_weights", prefix), Some(weights));
+        }
+        if !self.queue_cores.is_empty() {
+            let pins: Vec<String> = self
+                .queue_cores
+                .iter()
+                .map(|(queue, core)| format!("{}={}", queue, core))
+                .collect();
+            snapshot.set_list(&format!("{}.queue_cores", prefix), Some(&pins));
+        }
+    }
+}
+
+/// Итоговые параметры порта (общие значения с учетом `PortConfig`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortSettings {
+    pub num_rx_queues: u16,
+    pub num_tx_queues: u16,
+    pub rx_ring_size: u32,
+    pub tx_ring_size: u32,
+    pub promiscuous: bool,
+    pub use_rss: bool,
+    pub rss_hf: u64,
+    pub rss_key: Option<Vec<u8>>,
+    pub use_jumbo_frames: bool,
+    pub max_rx_pkt_len: u32,
+    pub use_hw_checksum: bool,
+    pub use_tso: bool,
+    pub use_udp_tso: bool,
+    pub use_lro: bool,
+    pub use_gro: bool,
+    pub use_hw_timestamp: bool,
+    pub use_tx_launch_time: bool,
+    pub flow_rules: Vec<FlowRule>,
+    pub reta_weights: Option<Vec<u32>>,
+    pub external_memory: Option<ExternalMemoryConfig>,
+    pub flow_control: Option<FlowControlMode>,
+    pub decode_cores: u16,
+    pub queue_cores: Vec<(u16, usize)>,
+}
+
+impl PortSettings {
+    /// Может ли порт принимать пакеты цепочкой mbuf (jumbo кадры, LRO, GRO)
+    pub fn rx_chained_mbufs(&self) -> bool {
+        self.use_jumbo_frames || self.use_lro || self.use_gro
+    }
+
+    /// Ядро, за которым закреплен рабочий поток очереди
+    pub fn queue_core(&self, queue_id: u16) -> Option<usize> {
+        self.queue_cores
+            .iter()
+            .find(|&&(queue, _)| queue == queue_id)
+            .map(|&(_, core)| core)
+    }
+}
 
 /// Конфигурация DPDK с поддержкой NUMA
+///
+/// Поля верхнего уровня - общие параметры и значения по умолчанию для портов,
+/// `ports` - используемые порты с собственными переопределениями.
 #[repr(C)]
 pub struct DpdkConfig {
-    pub port_id: c_ushort,
+    /// Используемые порты (пустой список - все найденные порты с общими параметрами)
+    pub ports: Vec<PortConfig>,
     pub num_rx_queues: c_ushort,
     pub num_tx_queues: c_ushort,
     pub promiscuous: bool,
@@ -31,6 +409,64 @@ pub struct DpdkConfig {
     pub max_tso_segment_size: u16,
     pub use_gro: bool,
     pub max_gro_size: u16,
+    /// Аппаратная отметка времени приема в mbuf (RTE_ETH_RX_OFFLOAD_TIMESTAMP)
+    pub use_hw_timestamp: bool,
+    /// Отправка по времени из mbuf (RTE_ETH_TX_OFFLOAD_SEND_ON_TIMESTAMP)
+    pub use_tx_launch_time: bool,
+    pub use_ip_reassembly: bool,
+    pub ip_reassembly_timeout_ms: u32,
+    pub ip_reassembly_max_flows: u32,
+    pub use_vxlan_decap: bool,
+    pub vxlan_udp_port: u16,
+    pub use_gre_decap: bool,
+    /// Реакция на несоответствие конфигурации возможностям NIC и числу ядер
+    pub validation_policy: ValidationPolicy,
+    /// Максимальное ожидание перед повторным rx_burst после неполной пачки (0 - отключено)
+    pub adaptive_burst_wait_ns: u64,
+    /// Выключатель по интенсивности входящего потока для каждой очереди RX (None - отключен)
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// Распределять пакеты программно, если NIC поддерживает только одну очередь RX
+    pub use_software_rss: bool,
+    /// Отдельный пул mbuf для каждой очереди RX вместо общего пула порта
+    pub use_per_queue_mempools: bool,
+    /// Управление потоком Ethernet для всех портов (None - настройка PMD)
+    pub flow_control: Option<FlowControlMode>,
+    /// Распределение пакетов по рабочим потокам: очередь на ядро или eventdev
+    pub scheduling: SchedulingMode,
+    /// Устройство событий для `SchedulingMode::EventDev`
+    pub event_device: String,
+    /// Ядер декодирования на порт в режиме конвейера (0 - обработка на ядре очереди RX)
+    pub decode_cores: u16,
+    /// Перераспределять пачки перегруженной очереди простаивающим рабочим потокам порта
+    pub use_work_stealing: bool,
+    /// Поведение рабочих потоков при пустых опросах (по умолчанию чистый опрос)
+    pub idle_policy: IdlePolicy,
+    /// Проверка изоляции ядер рабочих потоков при запуске
+    pub cpu_isolation: IsolationPolicy,
+    /// Планирование реального времени рабочих потоков (None - обычный приоритет)
+    pub realtime: Option<RealtimeConfig>,
+    /// Проверка зависания рабочих потоков (None - отключена)
+    pub watchdog: Option<WatchdogConfig>,
+    /// Реакция рабочих потоков на панику обработчика (по умолчанию поток завершается)
+    pub panic_policy: PanicPolicy,
+    /// Время на дообработку принятых пакетов при остановке движка
+    pub drain_timeout_ms: u32,
+    /// Закрепить память процесса (mlockall) и заранее обратиться к пулам и hugepages
+    pub use_memory_lock: bool,
+    /// Добавлять узлам недостающие hugepages через sysfs перед инициализацией EAL
+    pub use_hugepage_provisioning: bool,
+    /// Ядро служебных потоков (None - служебные потоки не закрепляются)
+    pub housekeeping_core: Option<usize>,
+    /// Ядра, не отдаваемые рабочим потокам (по умолчанию ядро 0)
+    pub reserved_cores: Vec<usize>,
+    /// Отдавать рабочим потокам оба логических ядра физического ядра (Hyper-Threading)
+    pub use_hyperthreads: bool,
+    /// Размещать потоки порта на ядрах с общим кэшем L3 (CCX)
+    pub use_l3_grouping: bool,
+    /// Роль процесса в многопроцессном режиме DPDK
+    pub process_type: ProcessType,
+    /// Префикс файлов hugepages и runtime-каталога (общий для первичного и вторичных процессов)
+    pub file_prefix: Option<String>,
 }
 
 impl Default for DpdkConfig {
@@ -40,7 +476,7 @@ impl Default for DpdkConfig {
         };
 
         Self {
-            port_id: 0,
+            ports: Vec::new(),
             num_rx_queues: 4,
             num_tx_queues: 4,
             promiscuous: true,
@@ -68,6 +504,38 @@ impl Default for DpdkConfig {
             max_tso_segment_size: 1460, // Типичный размер MSS (MTU - заголовки TCP/IP)
             use_gro: false,
             max_gro_size: 65535,
+            use_hw_timestamp: false,
+            use_tx_launch_time: false,
+            use_ip_reassembly: false,
+            ip_reassembly_timeout_ms: 100,
+            ip_reassembly_max_flows: 64,
+            use_vxlan_decap: false,
+            vxlan_udp_port: 4789,
+            use_gre_decap: false,
+            validation_policy: ValidationPolicy::AutoAdjust,
+            adaptive_burst_wait_ns: 0,
+            circuit_breaker: None,
+            use_software_rss: false,
+            use_per_queue_mempools: false,
+            flow_control: None,
+            scheduling: SchedulingMode::QueuePerCore,
+            event_device: DEFAULT_EVENT_DEVICE.to_string(),
+            decode_cores: 0,
+            use_work_stealing: false,
+            idle_policy: IdlePolicy::Spin,
+            cpu_isolation: IsolationPolicy::Report,
+            realtime: None,
+            watchdog: None,
+            panic_policy: PanicPolicy::Propagate,
+            drain_timeout_ms: 1000,
+            use_memory_lock: false,
+            use_hugepage_provisioning: false,
+            housekeeping_core: None,
+            reserved_cores: vec![0],
+            use_hyperthreads: false,
+            use_l3_grouping: false,
+            process_type: ProcessType::Primary,
+            file_prefix: None,
         }
     }
 }
@@ -109,6 +577,18 @@ impl DpdkConfig {
         self
     }
 
+    /// Включает аппаратную отметку времени приема пакетов
+    pub fn with_hw_timestamps(mut self) -> Self {
+        self.use_hw_timestamp = true;
+        self
+    }
+
+    /// Включает отправку пакетов NIC в заданное время (см. `TxQueue::send_at`)
+    pub fn with_tx_launch_time(mut self) -> Self {
+        self.use_tx_launch_time = true;
+        self
+    }
+
     /// Включает поддержку UDP TSO (GSO) для фрагментированных UDP пакетов
     pub fn with_udp_tso(mut self, max_segment_size: Option<u16>) -> Self {
         self.use_udp_tso = true;
@@ -126,6 +606,584 @@ impl DpdkConfig {
         }
         self
     }
+
+    /// Включает Flow Director: правила `PortConfig::flow_rules` создаются после запуска порта
+    ///
+    /// Правила реализованы через rte_flow (устаревший `fdir_conf` удален из
+    /// DPDK 22.11). Совпавший с правилом пакет попадает в очередь правила
+    /// независимо от RSS; остальные пакеты распределяются RSS как обычно,
+    /// а при отключенном RSS попадают в очередь 0. Поэтому для закрепления
+    /// фида за ядром достаточно правила на его адрес и порт.
+    pub fn with_flow_director(mut self) -> Self {
+        self.use_flow_director = true;
+        self
+    }
+
+    /// Добавляет порт с общими параметрами в список используемых портов
+    pub fn with_port(mut self, selector: PortSelector) -> Self {
+        self.ports.push(PortConfig::new(selector));
+        self
+    }
+
+    /// Добавляет порт с собственными параметрами
+    pub fn with_port_config(mut self, port: PortConfig) -> Self {
+        self.ports.push(port);
+        self
+    }
+
+    /// Селекторы используемых портов
+    pub fn port_selectors(&self) -> Vec<PortSelector> {
+        self.ports
+            .iter()
+            .map(|port| port.selector.clone())
+            .collect()
+    }
+
+    /// Итоговые параметры порта: переопределения из `ports` поверх общих значений
+    pub fn port_settings(&self, port: &DpdkPortInfo) -> PortSettings {
+        let overrides = self
+            .ports
+            .iter()
+            .find(|cfg| port_matches(port, &cfg.selector));
+        let max_rx_pkt_len = overrides.and_then(|cfg| cfg.max_rx_pkt_len);
+
+        let mut settings = PortSettings {
+            num_rx_queues: self.num_rx_queues,
+            num_tx_queues: self.num_tx_queues,
+            rx_ring_size: self.rx_ring_size,
+            tx_ring_size: self.tx_ring_size,
+            promiscuous: self.promiscuous,
+            use_rss: self.use_rss,
+            rss_hf: self.rss_hf,
+            rss_key: self.rss_key.clone(),
+            use_jumbo_frames: self.use_jumbo_frames || max_rx_pkt_len.is_some(),
+            max_rx_pkt_len: max_rx_pkt_len.unwrap_or(self.max_rx_pkt_len),
+            use_hw_checksum: self.use_hw_checksum,
+            use_tso: self.use_tso,
+            use_udp_tso: self.use_udp_tso,
+            use_lro: self.use_lro,
+            use_gro: self.use_gro,
+            use_hw_timestamp: self.use_hw_timestamp,
+            use_tx_launch_time: self.use_tx_launch_time,
+            flow_rules: Vec::new(),
+            reta_weights: None,
+            external_memory: None,
+            flow_control: self.flow_control,
+            decode_cores: self.decode_cores,
+            queue_cores: Vec::new(),
+        };
+
+        let Some(cfg) = overrides else {
+            return settings;
+        };
+
+        settings.num_rx_queues = cfg.num_rx_queues.unwrap_or(settings.num_rx_queues);
+        settings.num_tx_queues = cfg.num_tx_queues.unwrap_or(settings.num_tx_queues);
+        settings.rx_ring_size = cfg.rx_ring_size.unwrap_or(settings.rx_ring_size);
+        settings.tx_ring_size = cfg.tx_ring_size.unwrap_or(settings.tx_ring_size);
+        settings.promiscuous = cfg.promiscuous.unwrap_or(settings.promiscuous);
+        settings.use_rss = cfg.use_rss.unwrap_or(settings.use_rss);
+        settings.rss_hf = cfg.rss_hf.unwrap_or(settings.rss_hf);
+        if cfg.rss_key.is_some() {
+            settings.rss_key = cfg.rss_key.clone();
+        }
+        settings.use_hw_checksum = cfg.use_hw_checksum.unwrap_or(settings.use_hw_checksum);
+        settings.use_tso = cfg.use_tso.unwrap_or(settings.use_tso);
+        settings.use_udp_tso = cfg.use_udp_tso.unwrap_or(settings.use_udp_tso);
+        settings.use_lro = cfg.use_lro.unwrap_or(settings.use_lro);
+        settings.use_gro = cfg.use_gro.unwrap_or(settings.use_gro);
+        settings.flow_rules = cfg.flow_rules.clone();
+        settings.reta_weights = cfg.reta_weights.clone();
+        settings.external_memory = cfg.external_memory.clone();
+        settings.flow_control = cfg.flow_control.or(settings.flow_control);
+        settings.decode_cores = cfg.decode_cores.unwrap_or(settings.decode_cores);
+        settings.queue_cores = cfg.queue_cores.clone();
+
+        settings
+    }
+
+    /// Задает реакцию на несоответствие конфигурации возможностям порта
+    pub fn with_validation_policy(mut self, policy: ValidationPolicy) -> Self {
+        self.validation_policy = policy;
+        self
+    }
+
+    /// Включает адаптивное ожидание после неполной пачки
+    ///
+    /// Увеличивает средний размер пачки на фидах средней интенсивности;
+    /// ожидание не превышает `max_wait_ns` и сокращается, если не помогает.
+    pub fn with_adaptive_burst_wait(mut self, max_wait_ns: u64) -> Self {
+        self.adaptive_burst_wait_ns = max_wait_ns;
+        self
+    }
+
+    /// Включает выключатель по интенсивности входящего потока
+    ///
+    /// Очередь, поток которой превысил `trip_pps` (например, шторм сообщений
+    /// при сбое площадки), переводится в режим отбрасывания или схлопывания,
+    /// чтобы не отнимать ресурсы у соседних сессий; каждое срабатывание
+    /// публикуется как событие `CircuitBreaker`.
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.circuit_breaker = Some(config);
+        self
+    }
+
+    /// Включает программный RSS для NIC с единственной очередью RX
+    ///
+    /// Для одноочередных NIC (virtio, часть VF) отдельное ядро опрашивает
+    /// аппаратную очередь и распределяет кадры по хэшу 5-кортежа между
+    /// `num_rx_queues` рабочими потоками. Стоит одного ядра и одного перехода
+    /// через кольцо на пакет, но сохраняет модель "поток - ядро".
+    pub fn with_software_rss(mut self) -> Self {
+        self.use_software_rss = true;
+        self
+    }
+
+    /// Создает отдельный пул mbuf для каждой очереди RX
+    ///
+    /// Размер пула выводится из размера кольца и пачки (`num_mbufs` не
+    /// используется). Очереди не конкурируют за общий пул, а исчерпание
+    /// пула видно по конкретной очереди (команда `mempools`).
+    pub fn with_per_queue_mempools(mut self) -> Self {
+        self.use_per_queue_mempools = true;
+        self
+    }
+
+    /// Задает режим управления потоком для портов без собственного значения
+    pub fn with_flow_control(mut self, mode: FlowControlMode) -> Self {
+        self.flow_control = Some(mode);
+        self
+    }
+
+    /// Задает распределение пакетов по рабочим потокам
+    ///
+    /// В режиме eventdev поток на ядре порта опрашивает все очереди RX и
+    /// передает пакеты устройству событий, которое балансирует потоки
+    /// (5-кортежи) между рабочими потоками порта по загрузке, сохраняя
+    /// порядок внутри потока (`atomic`, `ordered`). Пакеты проходят
+    /// дополнительный переход через планировщик, зато один тяжелый поток не
+    /// перегружает ядро, за которым закреплена его очередь RSS. Порты,
+    /// подключенные во время работы, обслуживаются по очереди на ядро.
+    pub fn with_scheduling(mut self, mode: SchedulingMode) -> Self {
+        self.scheduling = mode;
+        self
+    }
+
+    /// Задает устройство событий (по умолчанию программный `event_sw0`)
+    pub fn with_event_device(mut self, device: &str) -> Self {
+        self.event_device = device.to_string();
+        self
+    }
+
+    /// Включает режим конвейера для всех портов (см. `PortConfig::with_pipeline`)
+    pub fn with_pipeline(mut self, decode_cores: u16) -> Self {
+        self.decode_cores = decode_cores;
+        self
+    }
+
+    /// Включает перераспределение пачек между рабочими потоками порта на узле
+    ///
+    /// Микровсплеск в одной корзине RSS обрабатывают простаивающие соседние
+    /// ядра вместо переполнения очереди NIC. Порядок пакетов внутри очереди
+    /// при этом не сохраняется.
+    pub fn with_work_stealing(mut self) -> Self {
+        self.use_work_stealing = true;
+        self
+    }
+
+    /// Задает поведение рабочих потоков при пустых опросах
+    ///
+    /// Для тестовых стендов и портов с редким трафиком: поток перестает
+    /// занимать ядро целиком ценой задержки первого пакета после простоя.
+    pub fn with_idle_policy(mut self, policy: IdlePolicy) -> Self {
+        self.idle_policy = policy;
+        self
+    }
+
+    /// Задает проверку изоляции ядер рабочих потоков (см. `IsolationReport`)
+    ///
+    /// `Strict` прерывает запуск, если ядра не изолированы, получают
+    /// прерывания или работают не с регулятором performance.
+    pub fn with_cpu_isolation(mut self, policy: IsolationPolicy) -> Self {
+        self.cpu_isolation = policy;
+        self
+    }
+
+    /// Переводит рабочие потоки и ядра декодирования в класс реального времени
+    ///
+    /// Поток на служебном ядре остается с обычным приоритетом. Требует
+    /// CAP_SYS_NICE (или RLIMIT_RTPRIO) и изолированных ядер рабочих потоков:
+    /// иначе потоки ядра на этих ядрах голодают.
+    pub fn with_realtime(mut self, realtime: RealtimeConfig) -> Self {
+        self.realtime = Some(realtime);
+        self
+    }
+
+    /// Включает проверку зависания рабочих потоков (см. `StallWatchdog`)
+    pub fn with_watchdog(mut self, config: WatchdogConfig) -> Self {
+        self.watchdog = Some(config);
+        self
+    }
+
+    /// Задает реакцию рабочих потоков на панику обработчика (см. `PanicPolicy`)
+    ///
+    /// Пачка с пакетом, вызвавшим панику, освобождается; поток либо
+    /// продолжает опрос со сброшенным состоянием, либо включает аварийный
+    /// выключатель.
+    pub fn with_panic_policy(mut self, policy: PanicPolicy) -> Self {
+        self.panic_policy = policy;
+        self
+    }
+
+    /// Задает время на дообработку принятых пакетов при остановке
+    /// (см. `NumaManager::drain`)
+    pub fn with_drain_timeout(mut self, timeout_ms: u32) -> Self {
+        self.drain_timeout_ms = timeout_ms;
+        self
+    }
+
+    /// Закрепляет память процесса и заранее обращается ко всей памяти пакетов
+    ///
+    /// Перед запуском рабочих потоков выполняется mlockall и чтение каждой
+    /// страницы пулов mbuf и hugepages, чтобы рабочие потоки не получали
+    /// страничных прерываний. Требует CAP_IPC_LOCK; без него запуск
+    /// прерывается.
+    pub fn with_memory_lock(mut self) -> Self {
+        self.use_memory_lock = true;
+        self
+    }
+
+    /// Выделяет узлам недостающие для `--socket-mem` страницы 2 МБ при запуске
+    ///
+    /// Запись в nr_hugepages узлов требует root или CAP_DAC_OVERRIDE; без
+    /// прав запуск прерывается с указанием нужной возможности.
+    pub fn with_hugepage_provisioning(mut self) -> Self {
+        self.use_hugepage_provisioning = true;
+        self
+    }
+
+    /// Выделяет ядро под служебные потоки
+    ///
+    /// Ядро исключается из ядер рабочих потоков; на нем выполняются поток
+    /// управления движка, периодические задачи (`Housekeeper`: линки,
+    /// таймеры), публикация статистики, мониторы и журнал. Без этого
+    /// служебные потоки планируются ядром ОС где угодно, в том числе на
+    /// ядрах обработки пакетов.
+    pub fn with_housekeeping_core(mut self, core: usize) -> Self {
+        self.housekeeping_core = Some(core);
+        self
+    }
+
+    /// Задает ядра, не отдаваемые рабочим потокам (вместо ядра 0)
+    ///
+    /// Пустой список отдает рабочим потокам все ядра, включая ядро 0:
+    /// для систем, где ядро 0 изолировано, а ОС работает на других ядрах.
+    pub fn with_reserved_cores(mut self, cores: Vec<usize>) -> Self {
+        self.reserved_cores = cores;
+        self
+    }
+
+    /// Отдает рабочим потокам оба логических ядра физического ядра
+    ///
+    /// Соседние логические ядра делят кэши L1/L2 и конвейер: пригодно для
+    /// пары потоков, обменивающихся данными (например, прием и отправка),
+    /// но не для независимых очередей.
+    pub fn with_hyperthreads(mut self) -> Self {
+        self.use_hyperthreads = true;
+        self
+    }
+
+    /// Группирует потоки порта по доменам кэша L3 (см. `NumaManager::group_cores_by_l3`)
+    ///
+    /// На процессорах из чиплетов (AMD EPYC) обмен через кольцо между
+    /// ядрами разных CCX идет через межкристальную шину и стоит в несколько
+    /// раз дороже, чем внутри одного CCX.
+    pub fn with_l3_grouping(mut self) -> Self {
+        self.use_l3_grouping = true;
+        self
+    }
+
+    /// Задает роль процесса (первичный процесс настраивает порты, вторичный подключается к ним)
+    pub fn with_process_type(mut self, process_type: ProcessType) -> Self {
+        self.process_type = process_type;
+        self
+    }
+
+    /// Задает префикс файлов DPDK; процессы одной группы используют один префикс
+    pub fn with_file_prefix(mut self, prefix: &str) -> Self {
+        self.file_prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// Включает программную сборку фрагментированных IPv4 пакетов
+    pub fn with_ip_reassembly(mut self, timeout_ms: u32, max_flows: u32) -> Self {
+        self.use_ip_reassembly = true;
+        self.ip_reassembly_timeout_ms = timeout_ms;
+        self.ip_reassembly_max_flows = max_flows;
+        self
+    }
+
+    /// Включает декапсуляцию VXLAN (порт по умолчанию 4789)
+    pub fn with_vxlan_decap(mut self, udp_port: Option<u16>) -> Self {
+        self.use_vxlan_decap = true;
+        if let Some(port) = udp_port {
+            self.vxlan_udp_port = port;
+        }
+        self
+    }
+
+    /// Включает декапсуляцию GRE
+    pub fn with_gre_decap(mut self) -> Self {
+        self.use_gre_decap = true;
+        self
+    }
+
+    /// Возвращает флаги разбора пакетов (декапсуляция туннелей)
+    pub fn extract_flags(&self) -> u32 {
+        let mut flags = 0;
+        if self.use_vxlan_decap {
+            flags |= parser::EXTRACT_DECAP_VXLAN;
+        }
+        if self.use_gre_decap {
+            flags |= parser::EXTRACT_DECAP_GRE;
+        }
+        flags
+    }
+
+    /// Возвращает параметры сборки фрагментов, если она включена
+    pub fn reassembly_config(&self) -> Option<ReassemblyConfig> {
+        if !self.use_ip_reassembly {
+            return None;
+        }
+
+        Some(ReassemblyConfig {
+            timeout: Duration::from_millis(self.ip_reassembly_timeout_ms as u64),
+            max_flows: self.ip_reassembly_max_flows as usize,
+            ..ReassemblyConfig::default()
+        })
+    }
+}
+
+impl DpdkConfig {
+    /// Записывает все параметры конфигурации в снимок
+    pub fn write_snapshot(&self, snapshot: &mut ConfigSnapshot) {
+        if self.ports.is_empty() {
+            snapshot.set("dpdk.ports", "all");
+        } else {
+            snapshot.set_list("dpdk.ports", Some(&self.port_selectors()));
+            for port in &self.ports {
+                port.write_snapshot(snapshot);
+            }
+        }
+        snapshot.set("dpdk.num_rx_queues", self.num_rx_queues);
+        snapshot.set("dpdk.num_tx_queues", self.num_tx_queues);
+        snapshot.set("dpdk.promiscuous", self.promiscuous);
+        snapshot.set("dpdk.rx_ring_size", self.rx_ring_size);
+        snapshot.set("dpdk.tx_ring_size", self.tx_ring_size);
+        snapshot.set("dpdk.num_mbufs", self.num_mbufs);
+        snapshot.set("dpdk.mbuf_cache_size", self.mbuf_cache_size);
+        snapshot.set("dpdk.burst_size", self.burst_size);
+        snapshot.set("dpdk.use_rss", self.use_rss);
+        snapshot.set("dpdk.rss_hf", format!("0x{:x}", self.rss_hf));
+        snapshot.set("dpdk.use_cpu_affinity", self.use_cpu_affinity);
+        snapshot.set(
+            "dpdk.rss_key",
+            self.rss_key.as_ref().map_or_else(
+                || "none".to_string(),
+                |key| key.iter().map(|b| format!("{:02x}", b)).collect(),
+            ),
+        );
+        snapshot.set("dpdk.use_huge_pages", self.use_huge_pages);
+        snapshot.set_list("dpdk.socket_mem", self.socket_mem.as_deref());
+        snapshot.set("dpdk.huge_dir", self.huge_dir.as_deref().unwrap_or("none"));
+        snapshot.set("dpdk.data_room_size", self.data_room_size);
+        snapshot.set("dpdk.use_numa_on_socket", self.use_numa_on_socket);
+        snapshot.set("dpdk.use_jumbo_frames", self.use_jumbo_frames);
+        snapshot.set("dpdk.max_rx_pkt_len", self.max_rx_pkt_len);
+        snapshot.set("dpdk.use_hw_checksum", self.use_hw_checksum);
+        snapshot.set("dpdk.use_flow_director", self.use_flow_director);
+        snapshot.set("dpdk.use_tso", self.use_tso);
+        snapshot.set("dpdk.use_lro", self.use_lro);
+        snapshot.set("dpdk.use_udp_tso", self.use_udp_tso);
+        snapshot.set("dpdk.max_tso_segment_size", self.max_tso_segment_size);
+        snapshot.set("dpdk.use_gro", self.use_gro);
+        snapshot.set("dpdk.max_gro_size", self.max_gro_size);
+        snapshot.set("dpdk.use_hw_timestamp", self.use_hw_timestamp);
+        snapshot.set("dpdk.use_tx_launch_time", self.use_tx_launch_time);
+        snapshot.set("dpdk.use_ip_reassembly", self.use_ip_reassembly);
+        snapshot.set(
+            "dpdk.ip_reassembly_timeout_ms",
+            self.ip_reassembly_timeout_ms,
+        );
+        snapshot.set("dpdk.ip_reassembly_max_flows", self.ip_reassembly_max_flows);
+        snapshot.set("dpdk.use_vxlan_decap", self.use_vxlan_decap);
+        snapshot.set("dpdk.vxlan_udp_port", self.vxlan_udp_port);
+        snapshot.set("dpdk.use_gre_decap", self.use_gre_decap);
+        snapshot.set("dpdk.validation_policy", self.validation_policy);
+        snapshot.set("dpdk.adaptive_burst_wait_ns", self.adaptive_burst_wait_ns);
+        snapshot.set("dpdk.use_software_rss", self.use_software_rss);
+        snapshot.set("dpdk.use_per_queue_mempools", self.use_per_queue_mempools);
+        if let Some(mode) = self.flow_control {
+            snapshot.set("dpdk.flow_control", mode);
+        }
+        snapshot.set("dpdk.scheduling", self.scheduling);
+        snapshot.set("dpdk.event_device", &self.event_device);
+        snapshot.set("dpdk.decode_cores", self.decode_cores);
+        snapshot.set("dpdk.use_work_stealing", self.use_work_stealing);
+        snapshot.set("dpdk.idle_policy", self.idle_policy);
+        snapshot.set("dpdk.cpu_isolation", self.cpu_isolation);
+        snapshot.set("dpdk.realtime", self.realtime.is_some());
+        if let Some(realtime) = &self.realtime {
+            snapshot.set("dpdk.realtime.class", realtime.class);
+            snapshot.set("dpdk.realtime.priority", realtime.priority);
+        }
+        snapshot.set("dpdk.watchdog", self.watchdog.is_some());
+        if let Some(watchdog) = &self.watchdog {
+            snapshot.set(
+                "dpdk.watchdog.deadline_ms",
+                watchdog.deadline.as_millis() as u64,
+            );
+            snapshot.set("dpdk.watchdog.restart", watchdog.restart);
+        }
+        snapshot.set("dpdk.panic_policy", self.panic_policy);
+        snapshot.set("dpdk.drain_timeout_ms", self.drain_timeout_ms);
+        snapshot.set("dpdk.use_memory_lock", self.use_memory_lock);
+        snapshot.set(
+            "dpdk.use_hugepage_provisioning",
+            self.use_hugepage_provisioning,
+        );
+        match self.housekeeping_core {
+            Some(core) => snapshot.set("dpdk.housekeeping_core", core),
+            None => snapshot.set("dpdk.housekeeping_core", "none"),
+        }
+        snapshot.set_list(
+            "dpdk.reserved_cores",
+            (!self.reserved_cores.is_empty()).then_some(&self.reserved_cores[..]),
+        );
+        snapshot.set("dpdk.use_hyperthreads", self.use_hyperthreads);
+        snapshot.set("dpdk.use_l3_grouping", self.use_l3_grouping);
+        snapshot.set("dpdk.process_type", self.process_type);
+        snapshot.set(
+            "dpdk.file_prefix",
+            self.file_prefix.as_deref().unwrap_or("none"),
+        );
+        snapshot.set("dpdk.circuit_breaker", self.circuit_breaker.is_some());
+        if let Some(breaker) = &self.circuit_breaker {
+            snapshot.set("dpdk.circuit_breaker.trip_pps", breaker.trip_pps);
+            snapshot.set("dpdk.circuit_breaker.reset_pps", breaker.reset_pps);
+            snapshot.set(
+                "dpdk.circuit_breaker.window_us",
+                breaker.window.as_micros() as u64,
+            );
+            snapshot.set("dpdk.circuit_breaker.mode", breaker.mode);
+        }
+    }
+
+    /// Применяет параметры `dpdk.*` профиля конфигурации (см. `ConfigProfile`)
+    ///
+    /// Ключи совпадают с ключами снимка. Неизвестный ключ `dpdk.*` - ошибка,
+    /// чтобы опечатка в профиле не оставляла значение по умолчанию незаметно.
+    pub fn apply_profile(mut self, values: &ConfigSnapshot) -> Result<Self, String> {
+        for (key, value) in values.iter() {
+            let Some(name) = key.strip_prefix("dpdk.") else {
+                continue;
+            };
+
+            fn parse<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+                value
+                    .parse()
+                    .map_err(|_| format!("Invalid value '{}' for {}", value, key))
+            }
+
+            match name {
+                "ports" if value == "all" => self.ports.clear(),
+                "ports" => {
+                    self.ports = value
+                        .split(',')
+                        .map(|s| PortSelector::parse(s.trim()).map(PortConfig::new))
+                        .collect::<Result<_, _>>()?;
+                }
+                "num_rx_queues" => self.num_rx_queues = parse(key, value)?,
+                "num_tx_queues" => self.num_tx_queues = parse(key, value)?,
+                "promiscuous" => self.promiscuous = parse(key, value)?,
+                "rx_ring_size" => self.rx_ring_size = parse(key, value)?,
+                "tx_ring_size" => self.tx_ring_size = parse(key, value)?,
+                "num_mbufs" => self.num_mbufs = parse(key, value)?,
+                "mbuf_cache_size" => self.mbuf_cache_size = parse(key, value)?,
+                "burst_size" => self.burst_size = parse(key, value)?,
+                "use_rss" => self.use_rss = parse(key, value)?,
+                "use_cpu_affinity" => self.use_cpu_affinity = parse(key, value)?,
+                "use_huge_pages" => self.use_huge_pages = parse(key, value)?,
+                "huge_dir" if value == "none" => self.huge_dir = None,
+                "huge_dir" => self.huge_dir = Some(value.to_string()),
+                "data_room_size" => self.data_room_size = parse(key, value)?,
+                "use_numa_on_socket" => self.use_numa_on_socket = parse(key, value)?,
+                "use_jumbo_frames" => self.use_jumbo_frames = parse(key, value)?,
+                "max_rx_pkt_len" => self.max_rx_pkt_len = parse(key, value)?,
+                "use_hw_checksum" => self.use_hw_checksum = parse(key, value)?,
+                "use_flow_director" => self.use_flow_director = parse(key, value)?,
+                "use_tso" => self.use_tso = parse(key, value)?,
+                "use_lro" => self.use_lro = parse(key, value)?,
+                "use_udp_tso" => self.use_udp_tso = parse(key, value)?,
+                "max_tso_segment_size" => self.max_tso_segment_size = parse(key, value)?,
+                "use_gro" => self.use_gro = parse(key, value)?,
+                "max_gro_size" => self.max_gro_size = parse(key, value)?,
+                "use_hw_timestamp" => self.use_hw_timestamp = parse(key, value)?,
+                "use_tx_launch_time" => self.use_tx_launch_time = parse(key, value)?,
+                "use_ip_reassembly" => self.use_ip_reassembly = parse(key, value)?,
+                "ip_reassembly_timeout_ms" => self.ip_reassembly_timeout_ms = parse(key, value)?,
+                "ip_reassembly_max_flows" => self.ip_reassembly_max_flows = parse(key, value)?,
+                "use_vxlan_decap" => self.use_vxlan_decap = parse(key, value)?,
+                "vxlan_udp_port" => self.vxlan_udp_port = parse(key, value)?,
+                "use_gre_decap" => self.use_gre_decap = parse(key, value)?,
+                "validation_policy" => {
+                    self.validation_policy = match value {
+                        "auto-adjust" => ValidationPolicy::AutoAdjust,
+                        "fail-fast" => ValidationPolicy::FailFast,
+                        _ => return Err(format!("Invalid value '{}' for {}", value, key)),
+                    }
+                }
+                "adaptive_burst_wait_ns" => self.adaptive_burst_wait_ns = parse(key, value)?,
+                "use_software_rss" => self.use_software_rss = parse(key, value)?,
+                "use_per_queue_mempools" => self.use_per_queue_mempools = parse(key, value)?,
+                "flow_control" => self.flow_control = Some(FlowControlMode::parse(value)?),
+                "scheduling" => self.scheduling = SchedulingMode::parse(value)?,
+                "event_device" => self.event_device = value.to_string(),
+                "decode_cores" => self.decode_cores = parse(key, value)?,
+                "use_work_stealing" => self.use_work_stealing = parse(key, value)?,
+                "idle_policy" => self.idle_policy = IdlePolicy::parse(value)?,
+                "cpu_isolation" => self.cpu_isolation = IsolationPolicy::parse(value)?,
+                "realtime" if value == "none" || value == "false" => self.realtime = None,
+                // Та же запись, что у --realtime
+                "realtime" => self.realtime = Some(RealtimeConfig::parse(value)?),
+                "watchdog" if value == "none" || value == "false" => self.watchdog = None,
+                // Та же запись, что у --watchdog
+                "watchdog" => self.watchdog = Some(WatchdogConfig::parse(value)?),
+                "panic_policy" => self.panic_policy = PanicPolicy::parse(value)?,
+                "drain_timeout_ms" => self.drain_timeout_ms = parse(key, value)?,
+                "use_memory_lock" => self.use_memory_lock = parse(key, value)?,
+                "use_hugepage_provisioning" => self.use_hugepage_provisioning = parse(key, value)?,
+                "housekeeping_core" if value == "none" => self.housekeeping_core = None,
+                "housekeeping_core" => self.housekeeping_core = Some(parse(key, value)?),
+                "reserved_cores" => self.reserved_cores = parse_core_list(value)?,
+                "use_hyperthreads" => self.use_hyperthreads = parse(key, value)?,
+                "use_l3_grouping" => self.use_l3_grouping = parse(key, value)?,
+                "process_type" => self.process_type = ProcessType::parse(value)?,
+                "file_prefix" if value == "none" => self.file_prefix = None,
+                "file_prefix" => self.file_prefix = Some(value.to_string()),
+                "circuit_breaker" if value == "none" || value == "false" => {
+                    self.circuit_breaker = None
+                }
+                // Та же запись, что у --circuit-breaker
+                "circuit_breaker" => {
+                    self.circuit_breaker = Some(CircuitBreakerConfig::parse(value)?)
+                }
+                _ => return Err(format!("Unknown config key {}", key)),
+            }
+        }
+
+        Ok(self)
+    }
 }
 
 /// Создает конфигурацию DPDK с параметрами по умолчанию
@@ -0,0 +1,264 @@
+// src/dpdk/devbind.rs
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::dpdk::init::{is_pci_address, normalize_pci_address};
+use crate::{log_info, log_warn};
+
+const PCI_DEVICES: &str = "/sys/bus/pci/devices";
+const PCI_DRIVERS: &str = "/sys/bus/pci/drivers";
+const PCI_DRIVERS_PROBE: &str = "/sys/bus/pci/drivers_probe";
+const VFIO_NOIOMMU: &str = "/sys/module/vfio/parameters/enable_unsafe_noiommu_mode";
+
+/// Драйвер пользовательского пространства для DPDK по умолчанию
+pub const DEFAULT_DPDK_DRIVER: &str = "vfio-pci";
+
+/// Класс PCI сетевых контроллеров (старший байт `class`)
+const PCI_CLASS_NETWORK: u32 = 0x02;
+
+/// Сетевое PCI устройство
+#[derive(Debug, Clone)]
+pub struct NetDevice {
+    /// Полный PCI адрес (`0000:3b:00.0`)
+    pub pci_address: String,
+    pub vendor_id: u16,
+    pub device_id: u16,
+    /// Текущий драйвер (None - устройство ни к чему не привязано)
+    pub driver: Option<String>,
+    /// Узел NUMA устройства (None - неизвестен)
+    pub numa_node: Option<usize>,
+    /// Интерфейсы ядра (пусто, если устройство привязано к драйверу DPDK)
+    pub interfaces: Vec<String>,
+    /// Группа IOMMU (нужна vfio-pci без режима noiommu)
+    pub iommu_group: Option<u32>,
+}
+
+impl NetDevice {
+    /// Читает устройство из sysfs (None - нет такого устройства или оно не сетевое)
+    pub fn read(pci: &str) -> Option<Self> {
+        let pci_address = normalize_pci_address(pci);
+        let dir = device_dir(&pci_address);
+
+        let class = read_hex(&dir.join("class"))?;
+        if class >> 16 != PCI_CLASS_NETWORK {
+            return None;
+        }
+
+        let mut interfaces: Vec<String> = fs::read_dir(dir.join("net"))
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                    .collect()
+            })
+            .unwrap_or_default();
+        interfaces.sort();
+
+        Some(Self {
+            vendor_id: read_hex(&dir.join("vendor")).unwrap_or(0) as u16,
+            device_id: read_hex(&dir.join("device")).unwrap_or(0) as u16,
+            driver: link_name(&dir.join("driver")),
+            // -1 - платформа не сообщает узел
+            numa_node: fs::read_to_string(dir.join("numa_node"))
+                .ok()
+                .and_then(|node| node.trim().parse::<i64>().ok())
+                .and_then(|node| usize::try_from(node).ok()),
+            interfaces,
+            iommu_group: link_name(&dir.join("iommu_group")).and_then(|group| group.parse().ok()),
+            pci_address,
+        })
+    }
+
+    /// Активные (поднятые) интерфейсы ядра устройства
+    pub fn active_interfaces(&self) -> Vec<&str> {
+        self.interfaces
+            .iter()
+            .filter(|name| {
+                fs::read_to_string(Path::new("/sys/class/net").join(name).join("operstate"))
+                    .is_ok_and(|state| state.trim() == "up")
+            })
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+impl fmt::Display for NetDevice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<14} {:04x}:{:04x} {:<12} {:>4} {}",
+            self.pci_address,
+            self.vendor_id,
+            self.device_id,
+            self.driver.as_deref().unwrap_or("-"),
+            self.numa_node
+                .map_or("-".to_string(), |node| node.to_string()),
+            if self.interfaces.is_empty() {
+                "-".to_string()
+            } else {
+                self.interfaces.join(",")
+            }
+        )
+    }
+}
+
+/// Сетевые PCI устройства системы, упорядоченные по адресу
+pub fn list_network_devices() -> Vec<NetDevice> {
+    let mut devices: Vec<NetDevice> = fs::read_dir(PCI_DEVICES)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| NetDevice::read(&entry.file_name().to_string_lossy()))
+                .collect()
+        })
+        .unwrap_or_default();
+    devices.sort_by(|a, b| a.pci_address.cmp(&b.pci_address));
+    devices
+}
+
+/// Таблица сетевых устройств для вывода
+pub fn format_devices(devices: &[NetDevice]) -> String {
+    let mut out = format!(
+        "{:<14} {:<9} {:<12} {:>4} {}\n",
+        "pci", "id", "driver", "node", "interfaces"
+    );
+    for device in devices {
+        out.push_str(&format!("{}\n", device));
+    }
+    out
+}
+
+/// Привязывает устройство к драйверу `driver` (обычно vfio-pci)
+///
+/// Драйвер задается через `driver_override`, поэтому привязка не
+/// затрагивает другие устройства с тем же идентификатором (как `new_id`).
+/// Устройство с поднятым интерфейсом ядра не перепривязывается: через
+/// него может идти управляющий трафик хоста.
+pub fn bind(pci: &str, driver: &str) -> Result<NetDevice, String> {
+    let device = network_device(pci)?;
+    if device.driver.as_deref() == Some(driver) {
+        return Ok(device);
+    }
+
+    let active = device.active_interfaces();
+    if !active.is_empty() {
+        return Err(format!(
+            "{} has active interfaces ({}), bring them down first",
+            device.pci_address,
+            active.join(",")
+        ));
+    }
+
+    if !Path::new(PCI_DRIVERS).join(driver).exists() {
+        return Err(format!(
+            "Driver {} is not loaded (modprobe {})",
+            driver, driver
+        ));
+    }
+    if driver == DEFAULT_DPDK_DRIVER && device.iommu_group.is_none() && !vfio_noiommu() {
+        return Err(format!(
+            "{} has no IOMMU group: enable the IOMMU (intel_iommu=on / amd_iommu=on) \
+             or vfio noiommu mode",
+            device.pci_address
+        ));
+    }
+
+    let dir = device_dir(&device.pci_address);
+    write_sysfs(&dir.join("driver_override"), driver)?;
+    if device.driver.is_some() {
+        write_sysfs(&dir.join("driver/unbind"), &device.pci_address)?;
+    }
+    write_sysfs(Path::new(PCI_DRIVERS_PROBE), &device.pci_address)?;
+
+    let bound = network_device(&device.pci_address)?;
+    if bound.driver.as_deref() != Some(driver) {
+        return Err(format!(
+            "{} did not bind to {} (driver: {})",
+            bound.pci_address,
+            driver,
+            bound.driver.as_deref().unwrap_or("none")
+        ));
+    }
+
+    log_info!(
+        "dpdk",
+        "Device bound",
+        pci = bound.pci_address,
+        driver = driver,
+        previous = device.driver.as_deref().unwrap_or("none")
+    );
+    Ok(bound)
+}
+
+/// Отвязывает устройство от драйвера и снимает `driver_override`
+///
+/// С `reprobe` устройство сразу привязывается к драйверу ядра по
+/// умолчанию (возврат интерфейса ядру после DPDK).
+pub fn unbind(pci: &str, reprobe: bool) -> Result<NetDevice, String> {
+    let device = network_device(pci)?;
+    let dir = device_dir(&device.pci_address);
+
+    // Пустая строка (перевод строки) снимает переопределение
+    write_sysfs(&dir.join("driver_override"), "\n")?;
+    if device.driver.is_some() {
+        write_sysfs(&dir.join("driver/unbind"), &device.pci_address)?;
+    }
+    if reprobe {
+        write_sysfs(Path::new(PCI_DRIVERS_PROBE), &device.pci_address)?;
+    }
+
+    let unbound = network_device(&device.pci_address)?;
+    if reprobe && unbound.driver.is_none() {
+        log_warn!(
+            "dpdk",
+            "No kernel driver claimed the device",
+            pci = unbound.pci_address
+        );
+    }
+    log_info!(
+        "dpdk",
+        "Device unbound",
+        pci = unbound.pci_address,
+        previous = device.driver.as_deref().unwrap_or("none"),
+        driver = unbound.driver.as_deref().unwrap_or("none")
+    );
+    Ok(unbound)
+}
+
+fn network_device(pci: &str) -> Result<NetDevice, String> {
+    if !is_pci_address(pci) {
+        return Err(format!("Invalid PCI address '{}'", pci));
+    }
+    NetDevice::read(pci).ok_or_else(|| format!("{} is not a network PCI device", pci))
+}
+
+fn device_dir(pci_address: &str) -> PathBuf {
+    Path::new(PCI_DEVICES).join(pci_address)
+}
+
+fn write_sysfs(path: &Path, value: &str) -> Result<(), String> {
+    fs::write(path, value).map_err(|e| {
+        let hint = if e.kind() == std::io::ErrorKind::PermissionDenied {
+            " (requires root)"
+        } else {
+            ""
+        };
+        format!("Failed to write {}: {}{}", path.display(), e, hint)
+    })
+}
+
+fn vfio_noiommu() -> bool {
+    fs::read_to_string(VFIO_NOIOMMU).is_ok_and(|mode| mode.trim() == "Y")
+}
+
+/// Имя цели символической ссылки sysfs (драйвер, группа IOMMU)
+fn link_name(path: &Path) -> Option<String> {
+    let target = fs::read_link(path).ok()?;
+    Some(target.file_name()?.to_string_lossy().into_owned())
+}
+
+fn read_hex(path: &Path) -> Option<u32> {
+    let value = fs::read_to_string(path).ok()?;
+    u32::from_str_radix(value.trim().trim_start_matches("0x"), 16).ok()
+}
@@ -0,0 +1,362 @@
+// src/dpdk/eventdev.rs
+use core_affinity::CoreId;
+use std::ffi::CString;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use crate::dpdk::ffi::{self, RteMbuf};
+use crate::dpdk::soft_rss::mbuf_flow_hash;
+use crate::log_info;
+use crate::numa::node::{WorkerSettings, MAX_BURST_SIZE};
+
+/// Устройство событий по умолчанию (программный планировщик, создается через `--vdev`)
+pub const DEFAULT_EVENT_DEVICE: &str = "event_sw0";
+
+/// Тип планирования очереди событий
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventSchedType {
+    /// Пакеты потока обрабатываются одним ядром за раз (порядок сохраняется)
+    Atomic,
+    /// Пакеты потока обрабатываются параллельно, порядок восстанавливается при освобождении
+    Ordered,
+    /// Без гарантий порядка
+    Parallel,
+}
+
+impl EventSchedType {
+    fn to_raw(self) -> u8 {
+        match self {
+            EventSchedType::Atomic => ffi::RTE_SCHED_TYPE_ATOMIC,
+            EventSchedType::Ordered => ffi::RTE_SCHED_TYPE_ORDERED,
+            EventSchedType::Parallel => ffi::RTE_SCHED_TYPE_PARALLEL,
+        }
+    }
+}
+
+/// Модель распределения пакетов по рабочим потокам
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SchedulingMode {
+    /// Статическая модель: очередь RX закреплена за ядром
+    #[default]
+    QueuePerCore,
+    /// Динамическая балансировка потоков через устройство событий DPDK (eventdev)
+    EventDev(EventSchedType),
+}
+
+impl SchedulingMode {
+    /// Разбирает `queue`, `atomic`, `ordered` или `parallel`
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "queue" => Ok(SchedulingMode::QueuePerCore),
+            "atomic" => Ok(SchedulingMode::EventDev(EventSchedType::Atomic)),
+            "ordered" => Ok(SchedulingMode::EventDev(EventSchedType::Ordered)),
+            "parallel" => Ok(SchedulingMode::EventDev(EventSchedType::Parallel)),
+            other => Err(format!(
+                "Invalid scheduling mode '{}': expected queue, atomic, ordered or parallel",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SchedulingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchedulingMode::QueuePerCore => write!(f, "queue"),
+            SchedulingMode::EventDev(EventSchedType::Atomic) => write!(f, "atomic"),
+            SchedulingMode::EventDev(EventSchedType::Ordered) => write!(f, "ordered"),
+            SchedulingMode::EventDev(EventSchedType::Parallel) => write!(f, "parallel"),
+        }
+    }
+}
+
+/// Раскладка порта Ethernet на устройстве событий
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventPortLayout {
+    /// Очередь событий порта
+    pub queue_id: u8,
+    /// Порт событий потока-производителя
+    pub producer_port: u8,
+    /// Первый порт событий рабочих потоков (далее подряд)
+    pub first_worker_port: u8,
+    /// Рабочих потоков порта
+    pub workers: u16,
+}
+
+/// Устройство событий, распределяющее пакеты портов по рабочим потокам
+///
+/// У каждого порта Ethernet своя очередь событий. Производитель опрашивает
+/// все аппаратные очереди RX порта и ставит пакеты в очередь событий с
+/// хэшем 5-кортежа как идентификатором потока; планировщик раздает их
+/// рабочим потокам порта по текущей загрузке. Атомарная очередь
+/// гарантирует, что поток в каждый момент обрабатывает одно ядро,
+/// упорядоченная - что порядок потока восстанавливается после обработки.
+#[derive(Debug)]
+pub struct EventScheduler {
+    dev_id: u8,
+    sched_type: EventSchedType,
+    /// Служба программного планировщика (None - аппаратный планировщик)
+    service_id: Option<u32>,
+    ports: Vec<(u16, EventPortLayout)>,
+}
+
+impl EventScheduler {
+    /// Настраивает и запускает устройство `device` для портов `(port_id, workers)`
+    pub fn start(
+        device: &str,
+        sched_type: EventSchedType,
+        ports: &[(u16, u16)],
+    ) -> Result<Self, String> {
+        let name = CString::new(device).map_err(|e| e.to_string())?;
+        let dev_id = unsafe { ffi::rte_event_dev_get_dev_id(name.as_ptr()) };
+        if dev_id < 0 {
+            return Err(format!(
+                "Event device {} not found ({} event devices available)",
+                device,
+                unsafe { ffi::rte_event_dev_count() }
+            ));
+        }
+        let dev_id = dev_id as u8;
+
+        // Раскладка совпадает с dpdk_eventdev_setup: производители, затем рабочие потоки
+        let total_ports = ports.len() + ports.iter().map(|&(_, w)| w as usize).sum::<usize>();
+        if total_ports > u8::MAX as usize {
+            return Err(format!(
+                "Event device {}: {} event ports requested, at most 255 supported",
+                device, total_ports
+            ));
+        }
+
+        let mut layouts = Vec::with_capacity(ports.len());
+        let mut next_worker_port = ports.len();
+        for (index, &(port_id, workers)) in ports.iter().enumerate() {
+            layouts.push((
+                port_id,
+                EventPortLayout {
+                    queue_id: index as u8,
+                    producer_port: index as u8,
+                    first_worker_port: next_worker_port as u8,
+                    workers,
+                },
+            ));
+            next_worker_port += workers as usize;
+        }
+
+        let workers: Vec<u16> = ports.iter().map(|&(_, w)| w).collect();
+        let mut service_id = u32::MAX;
+        let ret = unsafe {
+            ffi::dpdk_eventdev_setup(
+                dev_id,
+                workers.as_ptr(),
+                workers.len() as u16,
+                sched_type.to_raw(),
+                &mut service_id,
+            )
+        };
+        if ret != 0 {
+            return Err(format!(
+                "Failed to set up event device {}: error {}",
+                device, ret
+            ));
+        }
+
+        log_info!(
+            "worker",
+            "Event scheduling started",
+            device = device,
+            sched = SchedulingMode::EventDev(sched_type),
+            ports = ports.len(),
+            event_ports = total_ports,
+            software = service_id != u32::MAX
+        );
+
+        Ok(Self {
+            dev_id,
+            sched_type,
+            service_id: (service_id != u32::MAX).then_some(service_id),
+            ports: layouts,
+        })
+    }
+
+    /// Раскладка порта Ethernet (None - порт не обслуживается устройством)
+    pub fn layout(&self, port_id: u16) -> Option<EventPortLayout> {
+        self.ports
+            .iter()
+            .find(|(id, _)| *id == port_id)
+            .map(|&(_, layout)| layout)
+    }
+
+    /// Извлекает пакеты для рабочего потока; аналог rte_eth_rx_burst
+    ///
+    /// События предыдущей пачки освобождаются этим же вызовом, поэтому
+    /// поток обрабатывает их до следующего опроса.
+    #[inline(always)]
+    pub fn dequeue_burst(&self, event_port: u8, rx_pkts: &mut [*mut RteMbuf]) -> u16 {
+        unsafe {
+            ffi::dpdk_event_dequeue_mbufs(
+                self.dev_id,
+                event_port,
+                rx_pkts.as_mut_ptr(),
+                rx_pkts.len() as u16,
+            )
+        }
+    }
+
+    /// Ставит пакеты в очередь порта; возвращает число принятых
+    #[inline(always)]
+    fn enqueue_burst(
+        &self,
+        layout: &EventPortLayout,
+        pkts: &mut [*mut RteMbuf],
+        flow_ids: &[u32],
+    ) -> usize {
+        unsafe {
+            ffi::dpdk_event_enqueue_mbufs(
+                self.dev_id,
+                layout.producer_port,
+                layout.queue_id,
+                self.sched_type.to_raw(),
+                pkts.as_mut_ptr(),
+                flow_ids.as_ptr(),
+                pkts.len() as u16,
+            ) as usize
+        }
+    }
+
+    /// Выполняет итерацию программного планировщика (для аппаратного - ничего)
+    #[inline(always)]
+    fn run_service(&self) {
+        if let Some(service_id) = self.service_id {
+            unsafe { ffi::dpdk_service_run_iter(service_id) };
+        }
+    }
+}
+
+impl Drop for EventScheduler {
+    fn drop(&mut self) {
+        unsafe { ffi::dpdk_eventdev_stop(self.dev_id) };
+        log_info!("worker", "Event scheduling stopped", dev = self.dev_id);
+    }
+}
+
+/// Поток-производитель: переносит пакеты из очередей RX порта в очередь событий
+///
+/// Для программного планировщика он же выполняет службу планировщика, поэтому
+/// отдельное ядро службы не требуется.
+pub struct EventRxAdapter {
+    pub port_id: u16,
+    pub core_id: CoreId,
+    stop: Arc<AtomicBool>,
+    dropped: Arc<AtomicU64>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl EventRxAdapter {
+    /// Запускает производителя для `num_rx_queues` очередей порта на ядре `core_id`
+    pub fn start(
+        port_id: u16,
+        num_rx_queues: u16,
+        core_id: CoreId,
+        scheduler: Arc<EventScheduler>,
+        running: Arc<AtomicBool>,
+        settings: &WorkerSettings,
+    ) -> Result<Self, String> {
+        let layout = scheduler
+            .layout(port_id)
+            .ok_or_else(|| format!("Port {} is not served by the event device", port_id))?;
+
+        let burst_size = settings.burst_size.clamp(1, MAX_BURST_SIZE as u32) as u16;
+        let extract_flags = settings.extract_flags;
+        let vxlan_port = settings.vxlan_port;
+        let chained_mbufs = settings.chained_mbufs;
+        let stop = Arc::new(AtomicBool::new(false));
+        let dropped = Arc::new(AtomicU64::new(0));
+        let thread_stop = stop.clone();
+        let thread_dropped = dropped.clone();
+
+        let thread = thread::Builder::new()
+            .name(format!("hfeec-evrx-p{}", port_id))
+            .spawn(move || {
+                core_affinity::set_for_current(core_id);
+                log_info!(
+                    "worker",
+                    "Event RX adapter started",
+                    port = port_id,
+                    queues = num_rx_queues,
+                    core = core_id.id,
+                    workers = layout.workers
+                );
+
+                let mut rx_pkts = vec![std::ptr::null_mut(); burst_size as usize];
+                let mut flow_ids = vec![0u32; burst_size as usize];
+                while running.load(Ordering::SeqCst) && !thread_stop.load(Ordering::Relaxed) {
+                    for queue_id in 0..num_rx_queues {
+                        let nb_rx = unsafe {
+                            ffi::rte_eth_rx_burst(
+                                port_id,
+                                queue_id,
+                                rx_pkts.as_mut_ptr(),
+                                burst_size,
+                            )
+                        } as usize;
+                        if nb_rx == 0 {
+                            continue;
+                        }
+
+                        for (flow_id, &pkt) in flow_ids.iter_mut().zip(&rx_pkts[..nb_rx]) {
+                            *flow_id =
+                                mbuf_flow_hash(pkt, chained_mbufs, extract_flags, vxlan_port);
+                        }
+
+                        let sent = scheduler.enqueue_burst(
+                            &layout,
+                            &mut rx_pkts[..nb_rx],
+                            &flow_ids[..nb_rx],
+                        );
+                        for &pkt in &rx_pkts[sent..nb_rx] {
+                            thread_dropped.fetch_add(1, Ordering::Relaxed);
+                            unsafe { ffi::rte_pktmbuf_free(pkt) };
+                        }
+                    }
+
+                    scheduler.run_service();
+                }
+            })
+            .map_err(|e| format!("Failed to spawn event RX adapter: {}", e))?;
+
+        Ok(Self {
+            port_id,
+            core_id,
+            stop,
+            dropped,
+            thread: Some(thread),
+        })
+    }
+
+    /// Пакетов, отброшенных из-за переполнения устройства событий
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Останавливает производителя
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+            log_info!(
+                "worker",
+                "Event RX adapter stopped",
+                port = self.port_id,
+                dropped = self.dropped()
+            );
+        }
+    }
+}
+
+impl Drop for EventRxAdapter {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -0,0 +1,208 @@
+// src/dpdk/extmem.rs
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::os::raw::c_int;
+use std::ptr;
+
+use crate::dpdk::ffi;
+use crate::log_info;
+
+/// Размер страницы hugepage по умолчанию
+pub const EXTMEM_DEFAULT_PAGE_SIZE: usize = 2 << 20;
+
+/// Внешняя закрепленная память для буферов mbuf порта
+///
+/// Буферы данных размещаются не в памяти EAL, а в отдельной области,
+/// которую может отобразить другой потребитель: процесс, читающий файл
+/// в hugetlbfs или /dev/shm, или драйвер устройства (GPU-direct).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalMemoryConfig {
+    /// Файл, отображаемый в память (None - анонимная память на hugepages)
+    pub path: Option<String>,
+    /// Размер страницы области (для регистрации в DPDK)
+    pub page_size: usize,
+}
+
+impl ExternalMemoryConfig {
+    /// Анонимная память на hugepages 2 МБ
+    pub fn anonymous() -> Self {
+        Self {
+            path: None,
+            page_size: EXTMEM_DEFAULT_PAGE_SIZE,
+        }
+    }
+
+    /// Память из файла `path`, доступная другим процессам
+    pub fn shared(path: &str, page_size: usize) -> Self {
+        Self {
+            path: Some(path.to_string()),
+            page_size,
+        }
+    }
+}
+
+impl fmt::Display for ExternalMemoryConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{} page {}", path, self.page_size),
+            None => write!(f, "anonymous page {}", self.page_size),
+        }
+    }
+}
+
+/// Закрепленная (mlock) область памяти вне EAL
+///
+/// Область не освобождается: пулы mbuf живут до завершения процесса, как и
+/// пулы в памяти EAL.
+struct ExternalRegion {
+    addr: *mut u8,
+    len: usize,
+}
+
+impl ExternalRegion {
+    /// Отображает и закрепляет не меньше `len` байт (с округлением до страницы)
+    fn map(config: &ExternalMemoryConfig, len: usize) -> Result<Self, String> {
+        if !config.page_size.is_power_of_two() {
+            return Err(format!(
+                "External memory page size {} is not a power of two",
+                config.page_size
+            ));
+        }
+        let len = len.div_ceil(config.page_size) * config.page_size;
+
+        let addr = match &config.path {
+            Some(path) => map_file(path, len)?,
+            None => map_anonymous(len, config.page_size)?,
+        };
+
+        if unsafe { libc::mlock(addr, len) } != 0 {
+            let err = std::io::Error::last_os_error();
+            unsafe { libc::munmap(addr, len) };
+            return Err(format!("Failed to pin external memory: {}", err));
+        }
+
+        Ok(Self {
+            addr: addr as *mut u8,
+            len,
+        })
+    }
+}
+
+fn map_file(path: &str, len: usize) -> Result<*mut libc::c_void, String> {
+    let c_path =
+        CString::new(path).map_err(|_| format!("Invalid external memory path: {}", path))?;
+
+    unsafe {
+        let fd = libc::open(c_path.as_ptr(), libc::O_RDWR | libc::O_CREAT, 0o600);
+        if fd < 0 {
+            return Err(format!(
+                "Failed to open external memory {}: {}",
+                path,
+                std::io::Error::last_os_error()
+            ));
+        }
+
+        if libc::ftruncate(fd, len as libc::off_t) != 0 {
+            let err = std::io::Error::last_os_error();
+            libc::close(fd);
+            return Err(format!("Failed to size external memory {}: {}", path, err));
+        }
+
+        let addr = libc::mmap(
+            ptr::null_mut(),
+            len,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED | libc::MAP_POPULATE,
+            fd,
+            0,
+        );
+        libc::close(fd);
+
+        if addr == libc::MAP_FAILED {
+            return Err(format!(
+                "Failed to map external memory {}: {}",
+                path,
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok(addr)
+    }
+}
+
+fn map_anonymous(len: usize, page_size: usize) -> Result<*mut libc::c_void, String> {
+    let huge_flags =
+        libc::MAP_HUGETLB | ((page_size.trailing_zeros() as c_int) << libc::MAP_HUGE_SHIFT);
+
+    let addr = unsafe {
+        libc::mmap(
+            ptr::null_mut(),
+            len,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_POPULATE | huge_flags,
+            -1,
+            0,
+        )
+    };
+
+    if addr == libc::MAP_FAILED {
+        return Err(format!(
+            "Failed to map {} bytes of {}-byte hugepages: {}",
+            len,
+            page_size,
+            std::io::Error::last_os_error()
+        ));
+    }
+    Ok(addr)
+}
+
+/// Создает пул mbuf, буферы данных которого лежат во внешней памяти
+///
+/// Область регистрируется в DPDK и отображается для DMA устройства порта;
+/// требуется режим IOVA as VA (`--iova-mode=va`).
+pub fn create_extbuf_pool(
+    port_id: u16,
+    name: &CStr,
+    num_mbufs: u32,
+    cache_size: u32,
+    data_room_size: u16,
+    socket_id: c_int,
+    config: &ExternalMemoryConfig,
+) -> Result<*mut ffi::RteMempool, String> {
+    let region = ExternalRegion::map(config, num_mbufs as usize * data_room_size as usize)?;
+
+    log_info!(
+        "dpdk",
+        "Mapped external mbuf memory",
+        port = port_id,
+        pool = name.to_string_lossy(),
+        bytes = region.len,
+        memory = config
+    );
+
+    let mut pool = ptr::null_mut();
+    let ret = unsafe {
+        ffi::dpdk_extbuf_pool_create(
+            port_id,
+            name.as_ptr(),
+            num_mbufs,
+            cache_size,
+            data_room_size,
+            socket_id,
+            region.addr as *mut libc::c_void,
+            region.len,
+            config.page_size,
+            &mut pool,
+        )
+    };
+    if ret != 0 || pool.is_null() {
+        unsafe { libc::munmap(region.addr as *mut libc::c_void, region.len) };
+        return Err(format!(
+            "Failed to create external memory mbuf pool {} ({}): error code {}",
+            name.to_string_lossy(),
+            config,
+            ret
+        ));
+    }
+
+    Ok(pool)
+}
@@ -1,5 +1,6 @@
 use std::ffi::c_void;
 use std::os::raw::{c_char, c_int, c_uint, c_ushort};
+use std::ptr;
 
 #[repr(C)]
 pub struct RteMbuf {
@@ -12,83 +13,134 @@ pub struct RteMempool {
 }
 
 #[repr(C)]
-pub struct RteEthRssConf {
-    pub rss_key: *mut u8,
-    pub rss_key_len: u8,
-    pub rss_hf: u64,
+pub struct RteFlow {
+    _private: [u8; 0],
+}
+
+/// Привязки, сгенерированные bindgen из установленных заголовков DPDK (build.rs)
+///
+/// Раскладка rte_eth_conf и вложенных структур меняется между версиями DPDK,
+/// поэтому эти структуры и функции, которые их принимают, не описываются
+/// вручную. Остальной код использует псевдонимы и обертки ниже.
+#[allow(
+    non_camel_case_types,
+    non_snake_case,
+    non_upper_case_globals,
+    clippy::all
+)]
+pub mod sys {
+    include!(concat!(env!("OUT_DIR"), "/dpdk_bindings.rs"));
 }
 
+pub type RteEthConf = sys::rte_eth_conf;
+pub type RteEthRssConf = sys::rte_eth_rss_conf;
+pub type RteEtherAddr = sys::rte_ether_addr;
+pub type RteEthXstat = sys::rte_eth_xstat;
+pub type RteEthXstatName = sys::rte_eth_xstat_name;
+pub type RteEthFcConf = sys::rte_eth_fc_conf;
+
 // RSS константы
-pub const ETH_RSS_IP: u64 = 0x1;
-pub const ETH_RSS_TCP: u64 = 0x2;
-pub const ETH_RSS_UDP: u64 = 0x4;
-pub const ETH_RSS_SCTP: u64 = 0x8;
-pub const ETH_MQ_RX_RSS: u32 = 1;
-pub const ETH_RSS_NONFRAG_IPV4_TCP: u64 = 0x40;
-pub const ETH_RSS_NONFRAG_IPV4_UDP: u64 = 0x80;
-pub const ETH_RSS_L4_DST_ONLY: u64 = 0x200;
-pub const ETH_RSS_L4_SRC_ONLY: u64 = 0x100;
+pub const ETH_RSS_IP: u64 = sys::RTE_ETH_RSS_IP;
+pub const ETH_RSS_TCP: u64 = sys::RTE_ETH_RSS_TCP;
+pub const ETH_RSS_UDP: u64 = sys::RTE_ETH_RSS_UDP;
+pub const ETH_RSS_SCTP: u64 = sys::RTE_ETH_RSS_SCTP;
+pub const ETH_MQ_RX_RSS: sys::rte_eth_rx_mq_mode = sys::RTE_ETH_MQ_RX_RSS;
+pub const ETH_RSS_NONFRAG_IPV4_TCP: u64 = sys::RTE_ETH_RSS_NONFRAG_IPV4_TCP;
+pub const ETH_RSS_NONFRAG_IPV4_UDP: u64 = sys::RTE_ETH_RSS_NONFRAG_IPV4_UDP;
+pub const ETH_RSS_L4_DST_ONLY: u64 = sys::RTE_ETH_RSS_L4_DST_ONLY;
+pub const ETH_RSS_L4_SRC_ONLY: u64 = sys::RTE_ETH_RSS_L4_SRC_ONLY;
 
 // Константы для RX offload флагов
-pub const DEV_RX_OFFLOAD_CHECKSUM: u64 = 0x00000001;
-pub const DEV_RX_OFFLOAD_TCP_LRO: u64 = 0x00000010;
-pub const DEV_RX_OFFLOAD_SCATTER: u64 = 0x00000100;
-pub const DEV_RX_OFFLOAD_TCP_GRO: u64 = 0x00000040;
+pub const DEV_RX_OFFLOAD_CHECKSUM: u64 = sys::RTE_ETH_RX_OFFLOAD_CHECKSUM;
+pub const DEV_RX_OFFLOAD_TCP_LRO: u64 = sys::RTE_ETH_RX_OFFLOAD_TCP_LRO;
+pub const DEV_RX_OFFLOAD_SCATTER: u64 = sys::RTE_ETH_RX_OFFLOAD_SCATTER;
+pub const DEV_RX_OFFLOAD_TIMESTAMP: u64 = sys::RTE_ETH_RX_OFFLOAD_TIMESTAMP;
+// Отдельного флага GRO в ethdev нет: аппаратная сборка TCP включается флагом LRO
+pub const DEV_RX_OFFLOAD_TCP_GRO: u64 = sys::RTE_ETH_RX_OFFLOAD_TCP_LRO;
 
 // Константы для TX offload флагов
-pub const DEV_TX_OFFLOAD_MBUF_FAST_FREE: u64 = 0x00000001;
-pub const DEV_TX_OFFLOAD_IPV4_CKSUM: u64 = 0x00000002;
-pub const DEV_TX_OFFLOAD_UDP_CKSUM: u64 = 0x00000004;
-pub const DEV_TX_OFFLOAD_TCP_CKSUM: u64 = 0x00000008;
-pub const DEV_TX_OFFLOAD_SCTP_CKSUM: u64 = 0x00000010;
-pub const DEV_TX_OFFLOAD_TCP_TSO: u64 = 0x00000020;
-pub const DEV_TX_OFFLOAD_UDP_TSO: u64 = 0x00000040;
-pub const DEV_TX_OFFLOAD_MULTI_SEGS: u64 = 0x00000080;
+pub const DEV_TX_OFFLOAD_MBUF_FAST_FREE: u64 = sys::RTE_ETH_TX_OFFLOAD_MBUF_FAST_FREE;
+pub const DEV_TX_OFFLOAD_IPV4_CKSUM: u64 = sys::RTE_ETH_TX_OFFLOAD_IPV4_CKSUM;
+pub const DEV_TX_OFFLOAD_UDP_CKSUM: u64 = sys::RTE_ETH_TX_OFFLOAD_UDP_CKSUM;
+pub const DEV_TX_OFFLOAD_TCP_CKSUM: u64 = sys::RTE_ETH_TX_OFFLOAD_TCP_CKSUM;
+pub const DEV_TX_OFFLOAD_SCTP_CKSUM: u64 = sys::RTE_ETH_TX_OFFLOAD_SCTP_CKSUM;
+pub const DEV_TX_OFFLOAD_TCP_TSO: u64 = sys::RTE_ETH_TX_OFFLOAD_TCP_TSO;
+pub const DEV_TX_OFFLOAD_UDP_TSO: u64 = sys::RTE_ETH_TX_OFFLOAD_UDP_TSO;
+pub const DEV_TX_OFFLOAD_MULTI_SEGS: u64 = sys::RTE_ETH_TX_OFFLOAD_MULTI_SEGS;
+pub const DEV_TX_OFFLOAD_SEND_ON_TIMESTAMP: u64 = sys::RTE_ETH_TX_OFFLOAD_SEND_ON_TIMESTAMP;
 
 // Флаги пакетов (метки для mbuf)
-pub const RTE_MBUF_F_TX_TCP_SEG: u64 = 1 << 9;
-pub const RTE_MBUF_F_TX_UDP_SEG: u64 = 1 << 10;
+pub const RTE_MBUF_F_TX_TCP_SEG: u64 = sys::RTE_MBUF_F_TX_TCP_SEG;
+pub const RTE_MBUF_F_TX_UDP_SEG: u64 = sys::RTE_MBUF_F_TX_UDP_SEG;
 
-#[repr(C)]
-pub struct RteEthConf {
-    pub rxmode: RteEthRxMode,
-    pub txmode: RteEthTxMode,
-    pub lpbk_mode: u32,
-    pub rx_adv_conf: RteEthRxAdvConf,
-    pub tx_adv_conf: RteEthTxAdvConf,
-    pub dcb_capability_en: u32,
-    pub fdir_conf: RteEthFdirConf,
-    pub intr_conf: RteEthIntrConf,
-}
+// Заголовок Ethernet и CRC: разница между длиной кадра и MTU
+pub const RTE_ETHER_OVERHEAD: u32 = sys::RTE_ETHER_HDR_LEN + sys::RTE_ETHER_CRC_LEN;
 
-#[repr(C)]
-pub struct RteEthRxMode {
-    pub mq_mode: u32,
-    pub max_rx_pkt_len: u32,
-    pub split_hdr_size: u16,
-    pub offloads: u64,
+/// Задает наибольшую длину принимаемого кадра
+///
+/// До DPDK 21.11 длина задается полем max_rx_pkt_len вместе с флагом
+/// JUMBO_FRAME, начиная с 21.11 - через MTU (поле и флаг удалены).
+pub fn set_max_rx_frame_len(conf: &mut RteEthConf, frame_len: u32) {
+    #[cfg(dpdk_pre_21_11)]
+    {
+        conf.rxmode.max_rx_pkt_len = frame_len;
+        conf.rxmode.offloads |= sys::RTE_ETH_RX_OFFLOAD_JUMBO_FRAME;
+    }
+    #[cfg(not(dpdk_pre_21_11))]
+    {
+        conf.rxmode.mtu = frame_len.saturating_sub(RTE_ETHER_OVERHEAD);
+    }
 }
 
+/// Возможности порта (зеркало struct dpdk_port_caps из native/dpdk.c)
 #[repr(C)]
-pub struct RteEthTxMode {
-    pub mq_mode: u32,
-    pub pvid: u16,
-    pub offloads: u64,
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DpdkPortCaps {
+    pub max_rx_queues: u16,
+    pub max_tx_queues: u16,
+    pub rx_desc_max: u16,
+    pub rx_desc_min: u16,
+    pub tx_desc_max: u16,
+    pub tx_desc_min: u16,
+    pub max_rx_pktlen: u32,
+    pub rx_offload_capa: u64,
+    pub tx_offload_capa: u64,
+    pub flow_type_rss_offloads: u64,
+    pub reta_size: u16,
+    pub hash_key_size: u8,
 }
 
+/// Плоское описание правила rte_flow (зеркало struct dpdk_flow_spec из native/dpdk.c)
+///
+/// Адреса и порты - в сетевом порядке байт, нулевая маска - поле не сравнивается.
 #[repr(C)]
-pub struct RteEthRxAdvConf {
-    pub rss_conf: RteEthRssConf,
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DpdkFlowSpec {
+    pub priority: u32,
+    pub dst_ip: u32,
+    pub dst_ip_mask: u32,
+    pub src_ip: u32,
+    pub src_ip_mask: u32,
+    pub dst_port: u16,
+    pub dst_port_mask: u16,
+    pub l4_proto: u8,
+    pub drop: u8,
+    pub queue_index: u16,
+    pub has_mark: u8,
+    pub mark_id: u32,
 }
 
-#[repr(C)]
-pub struct RteEthTxAdvConf {}
+// Максимальная длина имени устройства ethdev
+pub const RTE_ETH_NAME_MAX_LEN: usize = sys::RTE_ETH_NAME_MAX_LEN as usize;
 
-#[repr(C)]
-pub struct RteEthFdirConf {}
+// Роль процесса (enum rte_proc_type_t)
+pub const RTE_PROC_PRIMARY: c_int = 0;
+pub const RTE_PROC_SECONDARY: c_int = 1;
 
-#[repr(C)]
-pub struct RteEthIntrConf {}
+// Типы планирования очереди событий (RTE_SCHED_TYPE_*, стабильны во всех LTS)
+pub const RTE_SCHED_TYPE_ORDERED: u8 = 0;
+pub const RTE_SCHED_TYPE_ATOMIC: u8 = 1;
+pub const RTE_SCHED_TYPE_PARALLEL: u8 = 2;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DpdkError {
@@ -107,6 +159,7 @@ pub enum DpdkError {
 extern "C" {
     pub fn rte_eal_init(argc: c_int, argv: *mut *mut c_char) -> c_int;
     pub fn rte_eal_cleanup() -> c_int;
+    pub fn rte_eal_process_type() -> c_int;
 
     pub fn rte_pktmbuf_pool_create(
         name: *const c_char,
@@ -117,13 +170,11 @@ extern "C" {
         socket_id: c_int,
     ) -> *mut RteMempool;
 
+    pub fn rte_mempool_lookup(name: *const c_char) -> *mut RteMempool;
+    pub fn rte_mempool_avail_count(mp: *const RteMempool) -> c_uint;
+    pub fn rte_mempool_in_use_count(mp: *const RteMempool) -> c_uint;
+
     pub fn rte_eth_dev_is_valid_port(port_id: c_ushort) -> c_int;
-    pub fn rte_eth_dev_configure(
-        port_id: c_ushort,
-        nb_rx_queue: c_ushort,
-        nb_tx_queue: c_ushort,
-        eth_conf: *const c_void,
-    ) -> c_int;
     pub fn rte_eth_rx_queue_setup(
         port_id: c_ushort,
         rx_queue_id: c_ushort,
@@ -143,6 +194,8 @@ extern "C" {
     pub fn rte_eth_promiscuous_enable(port_id: c_ushort) -> c_int;
     pub fn rte_eth_dev_stop(port_id: c_ushort) -> c_int;
     pub fn rte_eth_dev_close(port_id: c_ushort) -> c_int;
+    pub fn rte_eth_dev_rx_queue_start(port_id: c_ushort, rx_queue_id: c_ushort) -> c_int;
+    pub fn rte_eth_dev_rx_queue_stop(port_id: c_ushort, rx_queue_id: c_ushort) -> c_int;
 
     pub fn rte_eth_rx_burst(
         port_id: c_ushort,
@@ -158,19 +211,200 @@ extern "C" {
     ) -> c_ushort;
 
     pub fn rte_pktmbuf_free(m: *mut RteMbuf);
+    pub fn rte_pktmbuf_free_bulk(mbufs: *mut *mut RteMbuf, count: c_uint);
     pub fn rte_pktmbuf_mtod(m: *const RteMbuf, t: *const c_void) -> *mut c_void;
     pub fn rte_pktmbuf_data_len(m: *const RteMbuf) -> c_ushort;
     pub fn rte_eth_dev_socket_id(port_id: c_ushort) -> c_int;
+    pub fn rte_eth_dev_get_name_by_port(port_id: c_ushort, name: *mut c_char) -> c_int;
+    pub fn rte_eth_dev_get_port_by_name(name: *const c_char, port_id: *mut c_ushort) -> c_int;
+
+    pub fn rte_eth_dev_adjust_nb_rx_tx_desc(
+        port_id: c_ushort,
+        nb_rx_desc: *mut c_ushort,
+        nb_tx_desc: *mut c_ushort,
+    ) -> c_int;
 
-    pub fn dpdk_extract_packet_data(
-        pkt: *const RteMbuf,
-        src_ip_out: *mut *mut u8,
-        src_ip_len_out: *mut u32,
-        dst_ip_out: *mut *mut u8,
-        dst_ip_len_out: *mut u32,
-        src_port_out: *mut u16,
-        dst_port_out: *mut u16,
-        data_out: *mut *mut u8,
-        data_len_out: *mut u32,
+    pub fn rte_eth_timesync_enable(port_id: c_ushort) -> c_int;
+    pub fn rte_eth_timesync_disable(port_id: c_ushort) -> c_int;
+    pub fn rte_eth_timesync_read_rx_timestamp(
+        port_id: c_ushort,
+        timestamp: *mut libc::timespec,
+        flags: u32,
+    ) -> c_int;
+    pub fn rte_eth_timesync_read_tx_timestamp(
+        port_id: c_ushort,
+        timestamp: *mut libc::timespec,
     ) -> c_int;
+    pub fn rte_eth_timesync_adjust_time(port_id: c_ushort, delta: i64) -> c_int;
+    pub fn rte_eth_timesync_read_time(port_id: c_ushort, time: *mut libc::timespec) -> c_int;
+    pub fn rte_eth_timesync_write_time(port_id: c_ushort, time: *const libc::timespec) -> c_int;
+
+    pub fn rte_eth_read_clock(port_id: c_ushort, clock: *mut u64) -> c_int;
+    pub fn rte_eth_tx_done_cleanup(port_id: c_ushort, queue_id: c_ushort, free_cnt: u32) -> c_int;
+
+    pub fn rte_eth_xstats_reset(port_id: c_ushort) -> c_int;
+
+    pub fn rte_dev_probe(devargs: *const c_char) -> c_int;
+
+    pub fn dpdk_get_port_caps(port_id: c_ushort, caps: *mut DpdkPortCaps) -> c_int;
+    pub fn dpdk_port_detach(port_id: c_ushort) -> c_int;
+    pub fn dpdk_flow_create(
+        port_id: c_ushort,
+        spec: *const DpdkFlowSpec,
+        validate_only: c_int,
+        flow: *mut *mut RteFlow,
+        error_msg: *mut c_char,
+        error_len: usize,
+    ) -> c_int;
+    pub fn dpdk_flow_destroy(port_id: c_ushort, flow: *mut RteFlow) -> c_int;
+    pub fn dpdk_mbuf_flow_mark(mbuf: *const RteMbuf, mark: *mut u32) -> c_int;
+    pub fn dpdk_rx_timestamp_register() -> c_int;
+    pub fn dpdk_mbuf_rx_timestamp(mbuf: *const RteMbuf, timestamp: *mut u64) -> c_int;
+    pub fn dpdk_tx_timestamp_register() -> c_int;
+    pub fn dpdk_mbuf_set_tx_timestamp(mbuf: *mut RteMbuf, timestamp: u64) -> c_int;
+    pub fn dpdk_mbuf_request_tx_timestamp(mbuf: *mut RteMbuf);
+    pub fn dpdk_mbuf_refcnt(mbuf: *const RteMbuf) -> u16;
+    pub fn dpdk_mbuf_refcnt_update(mbuf: *mut RteMbuf, value: i16);
+    pub fn dpdk_mbuf_pkt_len(mbuf: *const RteMbuf) -> u32;
+    pub fn dpdk_mbuf_next(mbuf: *const RteMbuf) -> *mut RteMbuf;
+    pub fn dpdk_mbuf_mtod(mbuf: *const RteMbuf) -> *mut c_void;
+    pub fn dpdk_mbuf_data_len(mbuf: *const RteMbuf) -> u16;
+    pub fn dpdk_mbuf_free(mbuf: *mut RteMbuf);
+    pub fn dpdk_mbuf_alloc(pool: *mut RteMempool) -> *mut RteMbuf;
+    pub fn dpdk_mbuf_append(mbuf: *mut RteMbuf, len: c_ushort) -> *mut c_char;
+    pub fn dpdk_mbuf_from_buffer(pool: *mut RteMempool, data: *const u8, len: u32) -> *mut RteMbuf;
+    pub fn dpdk_mbuf_set_tso(
+        mbuf: *mut RteMbuf,
+        l2_len: u16,
+        l3_len: u16,
+        l4_len: u16,
+        segment_size: u16,
+        udp: c_int,
+    ) -> c_int;
+    pub fn dpdk_rss_reta_update(port_id: c_ushort, queues: *const u16, reta_size: u16) -> c_int;
+    pub fn dpdk_rss_reta_query(port_id: c_ushort, queues: *mut u16, reta_size: u16) -> c_int;
+    pub fn dpdk_extbuf_pool_create(
+        port_id: c_ushort,
+        name: *const c_char,
+        n: c_uint,
+        cache_size: c_uint,
+        data_room_size: c_ushort,
+        socket_id: c_int,
+        addr: *mut libc::c_void,
+        len: usize,
+        page_size: usize,
+        pool: *mut *mut RteMempool,
+    ) -> c_int;
+
+    pub fn rte_event_dev_count() -> u8;
+    pub fn rte_event_dev_get_dev_id(name: *const c_char) -> c_int;
+    pub fn dpdk_eventdev_setup(
+        dev_id: u8,
+        workers: *const u16,
+        nb_queues: u16,
+        sched_type: u8,
+        service_id: *mut u32,
+    ) -> c_int;
+    pub fn dpdk_event_enqueue_mbufs(
+        dev_id: u8,
+        port_id: u8,
+        queue_id: u8,
+        sched_type: u8,
+        mbufs: *mut *mut RteMbuf,
+        flow_ids: *const u32,
+        count: u16,
+    ) -> u16;
+    pub fn dpdk_event_dequeue_mbufs(
+        dev_id: u8,
+        port_id: u8,
+        mbufs: *mut *mut RteMbuf,
+        count: u16,
+    ) -> u16;
+    pub fn dpdk_service_run_iter(service_id: u32) -> c_int;
+    pub fn dpdk_eventdev_stop(dev_id: u8);
+    pub fn dpdk_link_status(port_id: c_ushort, up: *mut c_int, speed_mbps: *mut u32) -> c_int;
+    pub fn dpdk_rx_intr_setup(port_id: c_ushort, queue_id: c_ushort) -> c_int;
+    pub fn dpdk_rx_intr_teardown(port_id: c_ushort, queue_id: c_ushort);
+    pub fn dpdk_rx_intr_wait(port_id: c_ushort, queue_id: c_ushort, timeout_ms: c_int) -> c_int;
+    pub fn dpdk_mempool_prefault(mp: *mut RteMempool) -> u64;
+    pub fn dpdk_prefault_hugepages() -> u64;
+}
+
+/// Настраивает порт (rte_eth_dev_configure); Err - код ошибки DPDK
+pub fn eth_dev_configure(
+    port_id: u16,
+    nb_rx_queues: u16,
+    nb_tx_queues: u16,
+    conf: &RteEthConf,
+) -> Result<(), c_int> {
+    let ret = unsafe { sys::rte_eth_dev_configure(port_id, nb_rx_queues, nb_tx_queues, conf) };
+    if ret < 0 {
+        Err(ret)
+    } else {
+        Ok(())
+    }
+}
+
+/// MAC адрес порта
+pub fn eth_macaddr(port_id: u16) -> Result<[u8; 6], c_int> {
+    let mut mac = RteEtherAddr::default();
+    let ret = unsafe { sys::rte_eth_macaddr_get(port_id, &mut mac) };
+    if ret < 0 {
+        Err(ret)
+    } else {
+        Ok(mac.addr_bytes)
+    }
+}
+
+/// Текущая настройка управления потоком порта (rte_eth_dev_flow_ctrl_get)
+pub fn eth_flow_ctrl_get(port_id: u16) -> Result<RteEthFcConf, c_int> {
+    let mut conf = RteEthFcConf::default();
+    let ret = unsafe { sys::rte_eth_dev_flow_ctrl_get(port_id, &mut conf) };
+    if ret < 0 {
+        Err(ret)
+    } else {
+        Ok(conf)
+    }
+}
+
+/// Записывает настройку управления потоком порта (rte_eth_dev_flow_ctrl_set)
+pub fn eth_flow_ctrl_set(port_id: u16, conf: &RteEthFcConf) -> Result<(), c_int> {
+    let mut conf = *conf;
+    let ret = unsafe { sys::rte_eth_dev_flow_ctrl_set(port_id, &mut conf) };
+    if ret < 0 {
+        Err(ret)
+    } else {
+        Ok(())
+    }
+}
+
+/// Заполняет `names` именами xstats порта
+///
+/// Возвращает число счетчиков порта: если оно больше `names.len()`, буфер
+/// мал и не заполнен. С пустым `names` только запрашивает число счетчиков.
+pub fn eth_xstats_names(port_id: u16, names: &mut [RteEthXstatName]) -> Result<usize, c_int> {
+    let buf = if names.is_empty() {
+        ptr::null_mut()
+    } else {
+        names.as_mut_ptr()
+    };
+    let ret = unsafe { sys::rte_eth_xstats_get_names(port_id, buf, names.len() as c_uint) };
+    if ret < 0 {
+        Err(ret)
+    } else {
+        Ok(ret as usize)
+    }
+}
+
+/// Заполняет `values` значениями xstats порта
+///
+/// Возвращает число счетчиков порта (как `eth_xstats_names`).
+pub fn eth_xstats_get(port_id: u16, values: &mut [RteEthXstat]) -> Result<usize, c_int> {
+    let ret =
+        unsafe { sys::rte_eth_xstats_get(port_id, values.as_mut_ptr(), values.len() as c_uint) };
+    if ret < 0 {
+        Err(ret)
+    } else {
+        Ok(ret as usize)
+    }
 }
@@ -0,0 +1,305 @@
+// src/dpdk/flow.rs
+use std::ffi::CStr;
+use std::fmt;
+use std::os::raw::c_char;
+
+use crate::dpdk::ffi;
+use crate::packet::data::PacketView;
+
+const ERROR_MSG_LEN: usize = 256;
+
+/// Действие правила над совпавшими пакетами
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowAction {
+    /// Направить в очередь RX (и, следовательно, на ядро ее рабочего потока)
+    Queue(u16),
+    /// Отбросить в NIC
+    Drop,
+}
+
+/// Построитель правила аппаратного распределения потоков (rte_flow)
+///
+/// Сопоставление по IPv4 адресам и порту назначения UDP/TCP; незаданные поля
+/// не сравниваются. Например, фид с 239.1.1.1:30001 можно закрепить за
+/// очередью 2, а лишний широковещательный трафик отбросить в NIC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlowRule {
+    priority: u32,
+    l4_proto: u8,
+    dst_ip: Option<([u8; 4], [u8; 4])>,
+    src_ip: Option<([u8; 4], [u8; 4])>,
+    dst_port: Option<u16>,
+    action: FlowAction,
+    mark: Option<u32>,
+}
+
+impl FlowRule {
+    fn new(l4_proto: u8) -> Self {
+        Self {
+            priority: 0,
+            l4_proto,
+            dst_ip: None,
+            src_ip: None,
+            dst_port: None,
+            action: FlowAction::Drop,
+            mark: None,
+        }
+    }
+
+    /// Правило точного совпадения (perfect match) фида: адрес и порт UDP -> очередь
+    pub fn perfect_match(dst_ip: [u8; 4], dst_port: u16, queue_id: u16) -> Self {
+        Self::udp()
+            .dst_ip(dst_ip)
+            .dst_port(dst_port)
+            .to_queue(queue_id)
+    }
+
+    /// Действие правила
+    pub fn action(&self) -> FlowAction {
+        self.action
+    }
+
+    /// Правило для пакетов IPv4/UDP
+    pub fn udp() -> Self {
+        Self::new(libc::IPPROTO_UDP as u8)
+    }
+
+    /// Правило для пакетов IPv4/TCP
+    pub fn tcp() -> Self {
+        Self::new(libc::IPPROTO_TCP as u8)
+    }
+
+    /// Правило для любых пакетов IPv4 (порт назначения не сравнивается)
+    pub fn ipv4() -> Self {
+        Self::new(0)
+    }
+
+    /// Точный адрес назначения
+    pub fn dst_ip(self, addr: [u8; 4]) -> Self {
+        self.dst_prefix(addr, 32)
+    }
+
+    /// Подсеть назначения
+    pub fn dst_prefix(mut self, addr: [u8; 4], prefix_len: u8) -> Self {
+        self.dst_ip = Some((addr, prefix_mask(prefix_len)));
+        self
+    }
+
+    /// Подсеть источника
+    pub fn src_prefix(mut self, addr: [u8; 4], prefix_len: u8) -> Self {
+        self.src_ip = Some((addr, prefix_mask(prefix_len)));
+        self
+    }
+
+    /// Порт назначения UDP/TCP
+    pub fn dst_port(mut self, port: u16) -> Self {
+        self.dst_port = Some(port);
+        self
+    }
+
+    /// Приоритет правила (0 - наивысший)
+    pub fn priority(mut self, priority: u32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Направить совпавшие пакеты в очередь RX
+    pub fn to_queue(mut self, queue_id: u16) -> Self {
+        self.action = FlowAction::Queue(queue_id);
+        self
+    }
+
+    /// Отбросить совпавшие пакеты в NIC
+    pub fn drop_packets(mut self) -> Self {
+        self.action = FlowAction::Drop;
+        self
+    }
+
+    /// Пометить совпавшие пакеты идентификатором (см. `flow_mark`)
+    ///
+    /// Часть PMD передает метку только после rte_eth_rx_metadata_negotiate.
+    pub fn mark(mut self, id: u32) -> Self {
+        self.mark = Some(id);
+        self
+    }
+
+    fn to_spec(self) -> Result<ffi::DpdkFlowSpec, String> {
+        if self.dst_port.is_some() && self.l4_proto == 0 {
+            return Err("Destination port match requires a UDP or TCP rule".to_string());
+        }
+
+        let (dst_ip, dst_ip_mask) = self.dst_ip.unwrap_or_default();
+        let (src_ip, src_ip_mask) = self.src_ip.unwrap_or_default();
+        let (queue_index, drop) = match self.action {
+            FlowAction::Queue(queue_id) => (queue_id, 0),
+            FlowAction::Drop => (0, 1),
+        };
+
+        Ok(ffi::DpdkFlowSpec {
+            priority: self.priority,
+            // Байты адреса уже в сетевом порядке
+            dst_ip: u32::from_ne_bytes(dst_ip),
+            dst_ip_mask: u32::from_ne_bytes(dst_ip_mask),
+            src_ip: u32::from_ne_bytes(src_ip),
+            src_ip_mask: u32::from_ne_bytes(src_ip_mask),
+            dst_port: self.dst_port.unwrap_or(0).to_be(),
+            dst_port_mask: if self.dst_port.is_some() { 0xffff } else { 0 },
+            l4_proto: self.l4_proto,
+            drop,
+            queue_index,
+            has_mark: self.mark.is_some() as u8,
+            mark_id: self.mark.unwrap_or(0),
+        })
+    }
+
+    /// Проверяет, поддерживает ли NIC правило (rte_flow_validate), не создавая его
+    pub fn validate(&self, port_id: u16) -> Result<(), String> {
+        self.submit(port_id, true).map(|_| ())
+    }
+
+    /// Создает правило на порту
+    pub fn create(&self, port_id: u16) -> Result<Flow, String> {
+        let handle = self.submit(port_id, false)?;
+        Ok(Flow {
+            port_id,
+            handle,
+            rule: *self,
+        })
+    }
+
+    fn submit(&self, port_id: u16, validate_only: bool) -> Result<*mut ffi::RteFlow, String> {
+        let spec = self.to_spec()?;
+        let mut handle: *mut ffi::RteFlow = std::ptr::null_mut();
+        let mut error_msg = [0 as c_char; ERROR_MSG_LEN];
+
+        let ret = unsafe {
+            ffi::dpdk_flow_create(
+                port_id,
+                &spec,
+                validate_only as i32,
+                &mut handle,
+                error_msg.as_mut_ptr(),
+                ERROR_MSG_LEN,
+            )
+        };
+
+        if ret != 0 {
+            let message = unsafe { CStr::from_ptr(error_msg.as_ptr()) }.to_string_lossy();
+            return Err(format!(
+                "Flow rule rejected on port {}: {} (error code {})",
+                port_id, message, ret
+            ));
+        }
+
+        Ok(handle)
+    }
+}
+
+impl fmt::Display for FlowRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let proto = match self.l4_proto as i32 {
+            libc::IPPROTO_UDP => "udp",
+            libc::IPPROTO_TCP => "tcp",
+            _ => "ipv4",
+        };
+        write!(f, "{}", proto)?;
+
+        let prefix = |mask: &[u8; 4]| u32::from_be_bytes(*mask).count_ones();
+        if let Some((addr, mask)) = &self.src_ip {
+            write!(
+                f,
+                " src {}/{}",
+                std::net::Ipv4Addr::from(*addr),
+                prefix(mask)
+            )?;
+        }
+        if let Some((addr, mask)) = &self.dst_ip {
+            write!(
+                f,
+                " dst {}/{}",
+                std::net::Ipv4Addr::from(*addr),
+                prefix(mask)
+            )?;
+        }
+        if let Some(port) = self.dst_port {
+            write!(f, " port {}", port)?;
+        }
+
+        match self.action {
+            FlowAction::Queue(queue_id) => write!(f, " -> queue {}", queue_id)?,
+            FlowAction::Drop => write!(f, " -> drop")?,
+        }
+        if let Some(mark) = self.mark {
+            write!(f, " mark {}", mark)?;
+        }
+        if self.priority != 0 {
+            write!(f, " priority {}", self.priority)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Созданное правило rte_flow; удаляется из NIC при уничтожении
+#[derive(Debug)]
+pub struct Flow {
+    port_id: u16,
+    handle: *mut ffi::RteFlow,
+    rule: FlowRule,
+}
+
+// Дескриптор правила используется только для rte_flow_destroy
+unsafe impl Send for Flow {}
+
+impl Flow {
+    pub fn port_id(&self) -> u16 {
+        self.port_id
+    }
+
+    pub fn rule(&self) -> &FlowRule {
+        &self.rule
+    }
+
+    /// Удаляет правило из NIC
+    pub fn destroy(mut self) -> Result<(), String> {
+        self.release()
+    }
+
+    fn release(&mut self) -> Result<(), String> {
+        if self.handle.is_null() {
+            return Ok(());
+        }
+
+        let ret = unsafe { ffi::dpdk_flow_destroy(self.port_id, self.handle) };
+        self.handle = std::ptr::null_mut();
+        if ret != 0 {
+            return Err(format!(
+                "Failed to destroy flow rule on port {}: error code {}",
+                self.port_id, ret
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Flow {
+    fn drop(&mut self) {
+        let _ = self.release();
+    }
+}
+
+/// Метка правила rte_flow (действие MARK) принятого пакета
+#[inline(always)]
+pub fn flow_mark(packet: &PacketView) -> Option<u32> {
+    let mut mark = 0u32;
+    let marked = unsafe { ffi::dpdk_mbuf_flow_mark(packet.mbuf_ptr(), &mut mark) };
+    (marked != 0).then_some(mark)
+}
+
+/// Маска подсети в сетевом порядке байт
+fn prefix_mask(prefix_len: u8) -> [u8; 4] {
+    let bits = prefix_len.min(32) as u32;
+    let mask = u32::MAX.checked_shl(32 - bits).unwrap_or(0);
+    mask.to_be_bytes()
+}
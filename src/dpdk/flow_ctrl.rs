@@ -0,0 +1,146 @@
+// src/dpdk/flow_ctrl.rs
+use std::fmt;
+
+use crate::dpdk::ffi::{self, sys};
+
+/// Режим управления потоком Ethernet (кадры паузы IEEE 802.3x)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowControlMode {
+    /// Кадры паузы не отправляются и не соблюдаются
+    None,
+    /// Порт соблюдает кадры паузы коммутатора (приостанавливает отправку)
+    RxPause,
+    /// Порт отправляет кадры паузы при заполнении буфера RX
+    TxPause,
+    /// Оба направления
+    Full,
+}
+
+impl FlowControlMode {
+    /// Разбирает `none`, `rx`, `tx` или `full`
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "none" | "off" => Ok(FlowControlMode::None),
+            "rx" => Ok(FlowControlMode::RxPause),
+            "tx" => Ok(FlowControlMode::TxPause),
+            "full" => Ok(FlowControlMode::Full),
+            other => Err(format!(
+                "Invalid flow control mode '{}': expected none, rx, tx or full",
+                other
+            )),
+        }
+    }
+
+    fn to_raw(self) -> sys::rte_eth_fc_mode {
+        match self {
+            FlowControlMode::None => sys::RTE_ETH_FC_NONE,
+            FlowControlMode::RxPause => sys::RTE_ETH_FC_RX_PAUSE,
+            FlowControlMode::TxPause => sys::RTE_ETH_FC_TX_PAUSE,
+            FlowControlMode::Full => sys::RTE_ETH_FC_FULL,
+        }
+    }
+
+    fn from_raw(mode: sys::rte_eth_fc_mode) -> Option<Self> {
+        match mode {
+            sys::RTE_ETH_FC_NONE => Some(FlowControlMode::None),
+            sys::RTE_ETH_FC_RX_PAUSE => Some(FlowControlMode::RxPause),
+            sys::RTE_ETH_FC_TX_PAUSE => Some(FlowControlMode::TxPause),
+            sys::RTE_ETH_FC_FULL => Some(FlowControlMode::Full),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for FlowControlMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlowControlMode::None => write!(f, "none"),
+            FlowControlMode::RxPause => write!(f, "rx"),
+            FlowControlMode::TxPause => write!(f, "tx"),
+            FlowControlMode::Full => write!(f, "full"),
+        }
+    }
+}
+
+/// Настройка управления потоком порта, прочитанная из PMD
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlowControlStatus {
+    pub port_id: u16,
+    pub mode: FlowControlMode,
+    /// Режим согласуется с коммутатором при установке связи
+    pub autoneg: bool,
+    /// Пороги буфера RX для отправки XOFF и XON (байты)
+    pub high_water: u32,
+    pub low_water: u32,
+    /// Длительность паузы в отправляемых кадрах (кванты по 512 бит)
+    pub pause_time: u16,
+}
+
+impl FlowControlStatus {
+    /// Читает текущую настройку порта
+    pub fn query(port_id: u16) -> Result<Self, String> {
+        let conf = ffi::eth_flow_ctrl_get(port_id).map_err(|ret| {
+            format!(
+                "Failed to get flow control for port {}: error code {}",
+                port_id, ret
+            )
+        })?;
+
+        let mode = FlowControlMode::from_raw(conf.mode).ok_or_else(|| {
+            format!(
+                "Port {} reports unknown flow control mode {}",
+                port_id, conf.mode
+            )
+        })?;
+
+        Ok(Self {
+            port_id,
+            mode,
+            autoneg: conf.autoneg != 0,
+            high_water: conf.high_water,
+            low_water: conf.low_water,
+            pause_time: conf.pause_time,
+        })
+    }
+}
+
+impl fmt::Display for FlowControlStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "port {} flow control {} (autoneg {}) high_water={} low_water={} pause_time={}",
+            self.port_id,
+            self.mode,
+            if self.autoneg { "on" } else { "off" },
+            self.high_water,
+            self.low_water,
+            self.pause_time
+        )
+    }
+}
+
+/// Устанавливает режим управления потоком порта
+///
+/// Пороги и длительность паузы остаются значениями PMD. Согласование
+/// отключается: иначе коммутатор при переустановке связи может снова
+/// включить паузу, которую отключили на стороне коннектора.
+pub fn set_flow_control(port_id: u16, mode: FlowControlMode) -> Result<FlowControlStatus, String> {
+    let mut conf = ffi::eth_flow_ctrl_get(port_id).map_err(|ret| {
+        format!(
+            "Failed to get flow control for port {}: error code {}",
+            port_id, ret
+        )
+    })?;
+
+    conf.mode = mode.to_raw();
+    conf.autoneg = 0;
+
+    ffi::eth_flow_ctrl_set(port_id, &conf).map_err(|ret| {
+        format!(
+            "Failed to set flow control {} on port {}: error code {}",
+            mode, port_id, ret
+        )
+    })?;
+
+    FlowControlStatus::query(port_id)
+}
@@ -1,7 +1,8 @@
 use std::fs::{self, File};
 use std::io::{self, Read};
 use std::path::Path;
-use std::process::Command;
+
+use crate::log_info;
 
 #[derive(Debug, Clone)]
 pub struct HugePagesInfo {
@@ -10,6 +11,8 @@ pub struct HugePagesInfo {
     pub size_2mb_total: u32,
     pub size_1gb_total: u32,
     pub numa_mapping: Vec<(u32, u32, u32)>,
+    /// Свободные страницы узлов: (узел, 2 МБ, 1 ГБ)
+    pub numa_free: Vec<(u32, u32, u32)>,
 }
 
 pub fn check_hugepages_available() -> bool {
@@ -23,6 +26,7 @@ pub fn get_hugepages_info() -> io::Result<HugePagesInfo> {
         size_2mb_total: 0,
         size_1gb_total: 0,
         numa_mapping: Vec::new(),
+        numa_free: Vec::new(),
     };
 
     if let Ok(mut file) = File::open("/sys/kernel/mm/hugepages/hugepages-2048kB/nr_hugepages") {
@@ -86,6 +90,13 @@ pub fn get_hugepages_info() -> io::Result<HugePagesInfo> {
                             }
 
                             info.numa_mapping.push((node_id, node_2mb, node_1gb));
+                            info.numa_free.push((
+                                node_id,
+                                read_count(&path.join("hugepages/hugepages-2048kB/free_hugepages")),
+                                read_count(
+                                    &path.join("hugepages/hugepages-1048576kB/free_hugepages"),
+                                ),
+                            ));
                         }
                     }
                 }
@@ -96,77 +107,205 @@ pub fn get_hugepages_info() -> io::Result<HugePagesInfo> {
     Ok(info)
 }
 
-pub fn configure_hugepages(mb_2m_count: u32, mb_1g_count: u32) -> io::Result<()> {
-    if mb_2m_count > 0 {
-        let output = Command::new("sudo")
-            .args(["sysctl", "-w", &format!("vm.nr_hugepages={}", mb_2m_count)])
-            .output()?;
-
-        if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("Failed to configure 2MB hugepages: {}", error),
-            ));
+/// Проверяет, что свободных hugepages каждого узла хватает на `--socket-mem`
+///
+/// `socket_mem[node]` - запрошенная память узла в МБ. Err содержит по строке
+/// на каждый узел с нехваткой: запрошено, свободно по размерам страниц и
+/// сколько страниц 2 МБ нужно выделить узлу.
+pub fn check_socket_mem(socket_mem: &[u32]) -> Result<(), String> {
+    let info = get_hugepages_info().map_err(|e| format!("Failed to read hugepages: {}", e))?;
+
+    let mut problems = Vec::new();
+    for (node, &requested_mb) in socket_mem.iter().enumerate() {
+        if requested_mb == 0 {
+            continue;
         }
-    }
 
-    if mb_1g_count > 0 && Path::new("/sys/kernel/mm/hugepages/hugepages-1048576kB").exists() {
-        let output = Command::new("sudo")
-            .args([
-                "sysctl",
-                "-w",
-                &format!("vm.nr_hugepages_1g={}", mb_1g_count),
-            ])
-            .output()?;
-
-        if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("Failed to configure 1GB hugepages: {}", error),
-            ));
+        let pages = node_pages(&info, node);
+        let free_mb = pages.free_mb();
+        if free_mb >= requested_mb as u64 {
+            continue;
         }
+
+        let missing_pages = (requested_mb as u64 - free_mb).div_ceil(2);
+        problems.push(format!(
+            "node {}: requested {} MB, free {} MB (2MB pages {}/{} free, 1GB pages {}/{} free); \
+             fix: echo {} > /sys/devices/system/node/node{}/hugepages/hugepages-2048kB/nr_hugepages",
+            node,
+            requested_mb,
+            free_mb,
+            pages.free_2mb,
+            pages.total_2mb,
+            pages.free_1gb,
+            pages.total_1gb,
+            pages.total_2mb as u64 + missing_pages,
+            node
+        ));
     }
 
-    Ok(())
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Not enough hugepages for --socket-mem: {}",
+            problems.join("; ")
+        ))
+    }
+}
+
+/// Страницы hugepages одного узла NUMA
+struct NodePages {
+    free_2mb: u32,
+    free_1gb: u32,
+    total_2mb: u32,
+    total_1gb: u32,
+}
+
+impl NodePages {
+    fn free_mb(&self) -> u64 {
+        self.free_2mb as u64 * 2 + self.free_1gb as u64 * 1024
+    }
 }
 
-pub fn mount_hugetlbfs(mount_path: &str, page_size: &str) -> io::Result<()> {
-    if !Path::new(mount_path).exists() {
-        fs::create_dir_all(mount_path)?;
+/// Страницы узла `node` (без узлов в sysfs вся память - узел 0)
+fn node_pages(info: &HugePagesInfo, node: usize) -> NodePages {
+    let total = info
+        .numa_mapping
+        .iter()
+        .find(|(id, _, _)| *id as usize == node);
+    match info
+        .numa_free
+        .iter()
+        .find(|(id, _, _)| *id as usize == node)
+    {
+        Some(&(_, free_2mb, free_1gb)) => NodePages {
+            free_2mb,
+            free_1gb,
+            total_2mb: total.map_or(0, |&(_, pages, _)| pages),
+            total_1gb: total.map_or(0, |&(_, _, pages)| pages),
+        },
+        None if node == 0 && info.numa_free.is_empty() => NodePages {
+            free_2mb: info.size_2mb_available,
+            free_1gb: info.size_1gb_available,
+            total_2mb: info.size_2mb_total,
+            total_1gb: info.size_1gb_total,
+        },
+        None => NodePages {
+            free_2mb: 0,
+            free_1gb: 0,
+            total_2mb: 0,
+            total_1gb: 0,
+        },
     }
+}
+
+fn read_count(path: &Path) -> u32 {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| content.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+const HUGEPAGES_2MB: &str = "hugepages-2048kB";
+const HUGEPAGES_1GB: &str = "hugepages-1048576kB";
 
-    let output = Command::new("mount").output()?;
+/// Бит CAP_DAC_OVERRIDE в CapEff: запись в nr_hugepages проверяется только правами файла
+const CAP_DAC_OVERRIDE: u32 = 1;
 
-    let mount_output = String::from_utf8_lossy(&output.stdout);
-    if mount_output.contains(mount_path) && mount_output.contains("hugetlbfs") {
-        return Ok(());
+/// Задает число страниц 2 МБ и 1 ГБ узла NUMA `node` (0 - не менять)
+pub fn configure_node_hugepages(node: usize, pages_2mb: u32, pages_1gb: u32) -> io::Result<()> {
+    let root = Path::new("/sys/devices/system/node")
+        .join(format!("node{}", node))
+        .join("hugepages");
+    if pages_2mb > 0 {
+        set_nr_hugepages(&root.join(HUGEPAGES_2MB), pages_2mb)?;
     }
+    if pages_1gb > 0 {
+        set_nr_hugepages(&root.join(HUGEPAGES_1GB), pages_1gb)?;
+    }
+    Ok(())
+}
+
+/// Добавляет узлам страницы 2 МБ, которых не хватает на `--socket-mem`
+///
+/// `socket_mem[node]` - запрошенная память узла в МБ. Недостающее
+/// добирается страницами 2 МБ: страницы 1 ГБ после загрузки ядра обычно
+/// уже не выделить из-за фрагментации памяти.
+pub fn provision_socket_mem(socket_mem: &[u32]) -> Result<(), String> {
+    let info = get_hugepages_info().map_err(|e| format!("Failed to read hugepages: {}", e))?;
+
+    for (node, &requested_mb) in socket_mem.iter().enumerate() {
+        let pages = node_pages(&info, node);
+        let free_mb = pages.free_mb();
+        if requested_mb as u64 <= free_mb {
+            continue;
+        }
 
-    let output = Command::new("sudo")
-        .args([
-            "mount",
-            "-t",
-            "hugetlbfs",
-            "-o",
-            &format!("pagesize={}", page_size),
-            "none",
-            mount_path,
-        ])
-        .output()?;
+        let missing_pages = (requested_mb as u64 - free_mb).div_ceil(2) as u32;
+        let target = pages.total_2mb + missing_pages;
+        configure_node_hugepages(node, target, 0)
+            .map_err(|e| format!("Failed to provision hugepages on node {}: {}", node, e))?;
+        log_info!(
+            "dpdk",
+            "Hugepages provisioned",
+            node = node,
+            pages_2mb = target,
+            added = missing_pages
+        );
+    }
+    Ok(())
+}
 
-    if !output.status.success() {
-        let error = String::from_utf8_lossy(&output.stderr);
+/// Записывает nr_hugepages и проверяет, сколько страниц ядро выделило на самом деле
+fn set_nr_hugepages(dir: &Path, pages: u32) -> io::Result<()> {
+    if !dir.exists() {
         return Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!("Failed to mount hugetlbfs: {}", error),
+            io::ErrorKind::NotFound,
+            format!("{} not supported by the kernel", dir.display()),
+        ));
+    }
+    if !can_write_sysfs() {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "writing {} requires root or CAP_DAC_OVERRIDE",
+                dir.join("nr_hugepages").display()
+            ),
         ));
     }
 
+    let path = dir.join("nr_hugepages");
+    fs::write(&path, pages.to_string())
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+
+    // Ядро выделяет столько страниц, сколько найдет непрерывной памяти
+    let allocated = read_count(&path);
+    if allocated < pages {
+        return Err(io::Error::other(format!(
+            "kernel allocated {} of {} pages in {} (memory fragmented, reserve at boot with hugepages=)",
+            allocated,
+            pages,
+            dir.display()
+        )));
+    }
     Ok(())
 }
 
+/// Может ли процесс писать в файлы sysfs, принадлежащие root
+fn can_write_sysfs() -> bool {
+    if unsafe { libc::geteuid() } == 0 {
+        return true;
+    }
+    let Ok(status) = fs::read_to_string("/proc/self/status") else {
+        return false;
+    };
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("CapEff:"))
+        .and_then(|caps| u64::from_str_radix(caps.trim(), 16).ok())
+        .is_some_and(|caps| caps & (1 << CAP_DAC_OVERRIDE) != 0)
+}
+
 pub fn recommend_hugepage_config() -> io::Result<(u32, u32, Vec<String>)> {
     let num_numa_nodes = get_numa_node_count()?;
     let total_memory_mb = get_total_memory_mb()?;
@@ -1,52 +1,106 @@
 // src/dpdk/init.rs
-use std::ffi::{c_void, CString};
+use std::ffi::{CStr, CString};
+use std::fs;
 use std::os::raw::{c_char, c_int};
+use std::path::Path;
 use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
 
-use crate::dpdk::config::DpdkConfig;
+use crate::cpu::idle::IdlePolicy;
+use crate::dpdk::config::{DpdkConfig, PortSelector, PortSettings};
+use crate::dpdk::extmem::create_extbuf_pool;
 use crate::dpdk::ffi;
 use crate::dpdk::hugepages;
-use crate::numa::node::NumaNode;
+use crate::numa::node::{DpdkPort, NumaNode};
+use crate::{log_info, log_warn};
 
 /// Структура для представления порта DPDK
+#[derive(Debug, Clone)]
 pub struct DpdkPortInfo {
     pub port_id: u16,
+    /// Имя интерфейса ядра (при бифуркационном драйвере) или имя устройства DPDK
     pub if_name: String,
+    /// Имя устройства в DPDK (обычно PCI адрес или имя vdev)
+    pub dev_name: String,
+    /// PCI адрес устройства, если порт является PCI устройством
+    pub pci_address: Option<String>,
+    pub mac_address: [u8; 6],
     pub numa_node: Option<usize>,
 }
 
-/// Инициализирует DPDK EAL для конкретного узла NUMA
-pub fn init_dpdk_for_node(
-    node: &NumaNode,
-    dpdk_config: &DpdkConfig,
-    additional_args: &[String],
-) -> Result<(), String> {
-    if !hugepages::check_hugepages_available() && dpdk_config.use_huge_pages {
-        return Err("Huge pages not available but required by config".to_string());
-    }
+/// Итог настройки порта
+#[derive(Debug, Clone)]
+pub struct ConfiguredPort {
+    /// Размеры колец дескрипторов после корректировки PMD
+    pub rx_ring_size: u16,
+    pub tx_ring_size: u16,
+    /// Имена пулов mbuf: один общий или по одному на очередь RX
+    pub rx_pools: Vec<String>,
+}
 
-    let mut eal_args = vec![
-        "hfeec".to_string(), // Имя программы
-    ];
+/// Заполненность пула mbuf
+#[derive(Debug, Clone, Copy)]
+pub struct MempoolUsage {
+    pub in_use: u32,
+    pub available: u32,
+}
 
-    let core_mask = node.generate_core_mask();
-    eal_args.push(format!("--lcores={}", core_mask));
+/// Пул mbuf по имени (None - пул не найден)
+pub fn lookup_mempool(name: &str) -> Option<*mut ffi::RteMempool> {
+    let name = CString::new(name).ok()?;
+    let pool = unsafe { ffi::rte_mempool_lookup(name.as_ptr()) };
+    (!pool.is_null()).then_some(pool)
+}
 
-    eal_args.push("--master-lcore=0".to_string());
+/// Заполненность пула mbuf по имени (None - пул не найден)
+pub fn mempool_usage(name: &str) -> Option<MempoolUsage> {
+    let pool = lookup_mempool(name)?;
+    unsafe {
+        Some(MempoolUsage {
+            in_use: ffi::rte_mempool_in_use_count(pool),
+            available: ffi::rte_mempool_avail_count(pool),
+        })
+    }
+}
 
-    eal_args.extend(node.generate_eal_args(dpdk_config));
+/// Обращается ко всем страницам пула mbuf (None - пул не найден)
+///
+/// Возвращает пройденный объем в байтах.
+pub fn prefault_mempool(name: &str) -> Option<u64> {
+    let pool = lookup_mempool(name)?;
+    Some(unsafe { ffi::dpdk_mempool_prefault(pool) })
+}
 
-    eal_args.extend_from_slice(additional_args);
+/// Обращается ко всем сегментам памяти EAL, возвращает пройденный объем в байтах
+pub fn prefault_hugepages() -> u64 {
+    unsafe { ffi::dpdk_prefault_hugepages() }
+}
 
-    println!(
-        "Initializing DPDK for NUMA node {} with arguments:",
-        node.node_id
-    );
-    for arg in &eal_args {
-        println!("  {}", arg);
+/// Признак выполненной инициализации EAL: rte_eal_init допускает один вызов на процесс
+static EAL_INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+/// Инициализирует DPDK EAL для всего процесса
+///
+/// `eal_args` содержат ядра и память всех узлов NUMA сразу: пулы, очереди и
+/// рабочие потоки узлов создаются уже после инициализации.
+pub fn init_eal(dpdk_config: &DpdkConfig, eal_args: &[String]) -> Result<(), String> {
+    if !hugepages::check_hugepages_available() && dpdk_config.use_huge_pages {
+        return Err("Huge pages not available but required by config".to_string());
     }
 
-    let c_args: Vec<CString> = eal_args
+    if EAL_INITIALIZED.swap(true, Ordering::SeqCst) {
+        return Err("DPDK EAL is already initialized".to_string());
+    }
+
+    let mut args = vec![
+        "hfeec".to_string(), // Имя программы
+    ];
+    args.extend_from_slice(eal_args);
+
+    log_info!("dpdk", "Initializing EAL", args = args.join(" "));
+
+    let c_args: Vec<CString> = args
         .iter()
         .map(|arg| CString::new(arg.as_str()).unwrap())
         .collect();
@@ -58,18 +112,39 @@ pub fn init_dpdk_for_node(
 
     let ret = unsafe { ffi::rte_eal_init(c_args.len() as c_int, c_argv.as_mut_ptr()) };
     if ret < 0 {
+        EAL_INITIALIZED.store(false, Ordering::SeqCst);
         return Err(format!("Failed to initialize DPDK EAL: error code {}", ret));
     }
 
     Ok(())
 }
 
+/// Получает возможности и ограничения порта
+pub fn get_port_caps(port_id: u16) -> Result<ffi::DpdkPortCaps, String> {
+    let mut caps = ffi::DpdkPortCaps::default();
+
+    let ret = unsafe { ffi::dpdk_get_port_caps(port_id, &mut caps) };
+    if ret != 0 {
+        return Err(format!(
+            "Failed to get device info for port {}: error code {}",
+            port_id, ret
+        ));
+    }
+
+    Ok(caps)
+}
+
 /// Конфигурирует порт DPDK для конкретного узла NUMA
+///
+/// Возвращает итоговые размеры RX и TX колец дескрипторов, скорректированные
+/// под ограничения PMD.
 pub fn configure_port_for_node(
     node: &NumaNode,
-    port_id: u16,
+    port: &DpdkPort,
     dpdk_config: &DpdkConfig,
-) -> Result<(), String> {
+) -> Result<ConfiguredPort, String> {
+    let port_id = port.port_id;
+    let settings = &port.settings;
     let is_valid = unsafe { ffi::rte_eth_dev_is_valid_port(port_id) };
     if is_valid == 0 {
         return Err(format!("Invalid port id: {}", port_id));
@@ -94,36 +169,49 @@ pub fn configure_port_for_node(
         ));
     }
 
-    println!("Configuring port {} on socket {}", port_id, port_socket_id);
+    log_info!(
+        "dpdk",
+        "Configuring port",
+        port = port_id,
+        socket = port_socket_id
+    );
 
-    let mbuf_pool = create_mbuf_pool_for_port(port_id, dpdk_config)?;
-    if mbuf_pool.is_null() {
-        return Err("Failed to create mbuf pool".to_string());
-    }
+    // Общий пул порта; при пулах по очередям они создаются после выбора размера колец
+    let shared_pool = if dpdk_config.use_per_queue_mempools {
+        None
+    } else {
+        Some(create_mbuf_pool_for_port(
+            port_id,
+            None,
+            dpdk_config.num_mbufs,
+            settings,
+            dpdk_config,
+        )?)
+    };
 
     let mut eth_conf = default_eth_config();
 
     // Настраиваем Receive Side Scaling (RSS)
-    let enable_rss = dpdk_config.use_rss && dpdk_config.num_rx_queues > 1;
+    let enable_rss = settings.use_rss && port.num_rx_queues > 1;
     if enable_rss {
         eth_conf.rxmode.mq_mode = ffi::ETH_MQ_RX_RSS;
-        eth_conf.rx_adv_conf.rss_conf.rss_hf = dpdk_config.rss_hf;
+        eth_conf.rx_adv_conf.rss_conf.rss_hf = settings.rss_hf;
 
-        if let Some(ref key) = dpdk_config.rss_key {
+        if let Some(ref key) = settings.rss_key {
             eth_conf.rx_adv_conf.rss_conf.rss_key = key.as_ptr() as *mut u8;
             eth_conf.rx_adv_conf.rss_conf.rss_key_len = key.len() as u8;
         }
     }
 
     // Настраиваем размер Jumbo фреймов
-    if dpdk_config.use_jumbo_frames {
-        eth_conf.rxmode.max_rx_pkt_len = dpdk_config.max_rx_pkt_len;
+    if settings.use_jumbo_frames {
+        ffi::set_max_rx_frame_len(&mut eth_conf, settings.max_rx_pkt_len);
         // Для Jumbo фреймов требуется scatter
         eth_conf.rxmode.offloads |= ffi::DEV_RX_OFFLOAD_SCATTER;
     }
 
     // Включаем аппаратный подсчет контрольных сумм
-    if dpdk_config.use_hw_checksum {
+    if settings.use_hw_checksum {
         eth_conf.rxmode.offloads |= ffi::DEV_RX_OFFLOAD_CHECKSUM;
         eth_conf.txmode.offloads |= ffi::DEV_TX_OFFLOAD_IPV4_CKSUM
             | ffi::DEV_TX_OFFLOAD_UDP_CKSUM
@@ -131,67 +219,137 @@ pub fn configure_port_for_node(
     }
 
     // Настройка TSO
-    if dpdk_config.use_tso {
-        println!(
-            "Enabling TCP Segmentation Offload (TSO) with MSS: {}",
-            dpdk_config.max_tso_segment_size
+    if settings.use_tso {
+        log_info!(
+            "dpdk",
+            "Enabling TCP Segmentation Offload (TSO)",
+            port = port_id,
+            mss = dpdk_config.max_tso_segment_size
         );
         eth_conf.txmode.offloads |= ffi::DEV_TX_OFFLOAD_TCP_TSO | ffi::DEV_TX_OFFLOAD_MULTI_SEGS;
     }
 
     // Настройка UDP TSO (GSO)
-    if dpdk_config.use_udp_tso {
-        println!(
-            "Enabling UDP TSO (GSO) with segment size: {}",
-            dpdk_config.max_tso_segment_size
+    if settings.use_udp_tso {
+        log_info!(
+            "dpdk",
+            "Enabling UDP TSO (GSO)",
+            port = port_id,
+            segment_size = dpdk_config.max_tso_segment_size
         );
         eth_conf.txmode.offloads |= ffi::DEV_TX_OFFLOAD_UDP_TSO | ffi::DEV_TX_OFFLOAD_MULTI_SEGS;
     }
 
     // Настройка LRO
-    if dpdk_config.use_lro {
-        println!("Enabling Large Receive Offload (LRO)");
+    if settings.use_lro {
+        log_info!(
+            "dpdk",
+            "Enabling Large Receive Offload (LRO)",
+            port = port_id
+        );
         eth_conf.rxmode.offloads |= ffi::DEV_RX_OFFLOAD_TCP_LRO;
     }
 
+    // Аппаратная отметка времени приема: поле mbuf регистрируется до запуска порта
+    if settings.use_hw_timestamp {
+        let ret = unsafe { ffi::dpdk_rx_timestamp_register() };
+        if ret < 0 {
+            return Err(format!(
+                "Failed to register RX timestamp field for port {}: error code {}",
+                port_id, ret
+            ));
+        }
+        log_info!("dpdk", "Enabling hardware RX timestamps", port = port_id);
+        eth_conf.rxmode.offloads |= ffi::DEV_RX_OFFLOAD_TIMESTAMP;
+    }
+
+    // Отправка по времени: поле и флаг mbuf регистрируются до запуска порта
+    if settings.use_tx_launch_time {
+        let ret = unsafe { ffi::dpdk_tx_timestamp_register() };
+        if ret < 0 {
+            return Err(format!(
+                "Failed to register TX timestamp field for port {}: error code {}",
+                port_id, ret
+            ));
+        }
+        log_info!("dpdk", "Enabling TX launch time", port = port_id);
+        eth_conf.txmode.offloads |= ffi::DEV_TX_OFFLOAD_SEND_ON_TIMESTAMP;
+    }
+
     // Настройка GRO
-    if dpdk_config.use_gro {
-        println!(
-            "Enabling Generic Receive Offload (GRO) with max size: {}",
-            dpdk_config.max_gro_size
+    if settings.use_gro {
+        log_info!(
+            "dpdk",
+            "Enabling Generic Receive Offload (GRO)",
+            port = port_id,
+            max_size = dpdk_config.max_gro_size
         );
         eth_conf.rxmode.offloads |= ffi::DEV_RX_OFFLOAD_TCP_GRO;
         eth_conf.rxmode.offloads |= ffi::DEV_RX_OFFLOAD_SCATTER;
     }
 
-    let ret = unsafe {
-        ffi::rte_eth_dev_configure(
-            port_id,
-            dpdk_config.num_rx_queues,
-            dpdk_config.num_tx_queues,
-            &eth_conf as *const ffi::RteEthConf as *const c_void,
-        )
-    };
+    // Прерывания очередей RX для рабочих потоков, засыпающих в простое
+    if let IdlePolicy::Interrupt { .. } = dpdk_config.idle_policy {
+        log_info!("dpdk", "Enabling RX queue interrupts", port = port_id);
+        eth_conf.intr_conf.set_rxq(1);
+    }
 
+    ffi::eth_dev_configure(port_id, port.num_rx_queues, port.num_tx_queues, &eth_conf)
+        .map_err(|ret| format!("Failed to configure port {}: error code {}", port_id, ret))?;
+
+    // Приводим размеры колец к ограничениям PMD (min/max/кратность)
+    let mut nb_rx_desc = settings.rx_ring_size.min(u16::MAX as u32) as u16;
+    let mut nb_tx_desc = settings.tx_ring_size.min(u16::MAX as u32) as u16;
+
+    let ret =
+        unsafe { ffi::rte_eth_dev_adjust_nb_rx_tx_desc(port_id, &mut nb_rx_desc, &mut nb_tx_desc) };
     if ret < 0 {
         return Err(format!(
-            "Failed to configure port {}: error code {}",
+            "Failed to adjust descriptor ring sizes for port {}: error code {}",
             port_id, ret
         ));
     }
 
+    if nb_rx_desc as u32 != settings.rx_ring_size || nb_tx_desc as u32 != settings.tx_ring_size {
+        log_warn!(
+            "dpdk",
+            "Descriptor rings adjusted by PMD",
+            port = port_id,
+            rx_requested = settings.rx_ring_size,
+            rx = nb_rx_desc,
+            tx_requested = settings.tx_ring_size,
+            tx = nb_tx_desc
+        );
+    }
+
     // Настройка RX и TX очередей
-    for q in 0..dpdk_config.num_rx_queues {
+    let mut rx_pools = Vec::new();
+    if let Some((_, name)) = &shared_pool {
+        rx_pools.push(name.clone());
+    }
+
+    for q in 0..port.num_rx_queues {
         let queue_socket_id = match dpdk_config.use_numa_on_socket {
             true => port_socket_id,
             false => -1,
         };
 
+        let mbuf_pool = match &shared_pool {
+            Some((pool, _)) => *pool,
+            None => {
+                let num_mbufs = per_queue_pool_size(nb_rx_desc, dpdk_config);
+                let (pool, name) =
+                    create_mbuf_pool_for_port(port_id, Some(q), num_mbufs, settings, dpdk_config)?;
+                rx_pools.push(name);
+                pool
+            }
+        };
+
         let ret = unsafe {
             ffi::rte_eth_rx_queue_setup(
                 port_id,
                 q,
-                dpdk_config.rx_ring_size as u16,
+                nb_rx_desc,
                 queue_socket_id,
                 ptr::null(),
                 mbuf_pool,
@@ -206,20 +364,14 @@ pub fn configure_port_for_node(
         }
     }
 
-    for q in 0..dpdk_config.num_tx_queues {
+    for q in 0..port.num_tx_queues {
         let queue_socket_id = match dpdk_config.use_numa_on_socket {
             true => port_socket_id,
             false => -1,
         };
 
         let ret = unsafe {
-            ffi::rte_eth_tx_queue_setup(
-                port_id,
-                q,
-                dpdk_config.tx_ring_size as u16,
-                queue_socket_id,
-                ptr::null(),
-            )
+            ffi::rte_eth_tx_queue_setup(port_id, q, nb_tx_desc, queue_socket_id, ptr::null())
         };
 
         if ret < 0 {
@@ -238,7 +390,7 @@ pub fn configure_port_for_node(
         ));
     }
 
-    if dpdk_config.promiscuous {
+    if settings.promiscuous {
         let ret = unsafe { ffi::rte_eth_promiscuous_enable(port_id) };
         if ret < 0 {
             return Err(format!(
@@ -248,14 +400,45 @@ pub fn configure_port_for_node(
         }
     }
 
-    Ok(())
+    Ok(ConfiguredPort {
+        rx_ring_size: nb_rx_desc,
+        tx_ring_size: nb_tx_desc,
+        rx_pools,
+    })
+}
+
+/// Размер пула одной очереди RX: кольцо дескрипторов, пачка в обработке и кэш
+///
+/// Округляется до 2^n - 1 (оптимальный размер rte_mempool).
+fn per_queue_pool_size(nb_rx_desc: u16, dpdk_config: &DpdkConfig) -> u32 {
+    let needed = nb_rx_desc as u32 + 2 * dpdk_config.burst_size + dpdk_config.mbuf_cache_size;
+    (needed + 1).next_power_of_two() - 1
+}
+
+/// Создает memory pool для порта (или одной его очереди RX) в памяти NUMA-узла порта
+///
+/// Возвращает пул и его имя (для поиска через rte_mempool_lookup).
+/// Имя пула mbuf порта (или очереди RX) на узле NUMA
+///
+/// Имя уникально для порта (и очереди): несколько портов могут быть на одном
+/// узле; длина ограничена RTE_MEMPOOL_NAMESIZE. По этому имени пулы находит
+/// вторичный процесс.
+pub fn mbuf_pool_name(port_id: u16, queue_id: Option<u16>, node: Option<usize>) -> String {
+    match (queue_id, node) {
+        (None, Some(node)) => format!("mbuf_pool_p{}_node{}", port_id, node),
+        (None, None) => format!("mbuf_pool_p{}", port_id),
+        (Some(queue), Some(node)) => format!("mbuf_p{}_q{}_n{}", port_id, queue, node),
+        (Some(queue), None) => format!("mbuf_p{}_q{}", port_id, queue),
+    }
 }
 
-/// Создает memory pool для порта в соответствующей NUMA-узлу памяти
 fn create_mbuf_pool_for_port(
     port_id: u16,
+    queue_id: Option<u16>,
+    num_mbufs: u32,
+    settings: &PortSettings,
     dpdk_config: &DpdkConfig,
-) -> Result<*mut ffi::RteMempool, String> {
+) -> Result<(*mut ffi::RteMempool, String), String> {
     let port_numa_node = unsafe {
         let node = ffi::rte_eth_dev_socket_id(port_id);
         if node >= 0 {
@@ -265,63 +448,61 @@ fn create_mbuf_pool_for_port(
         }
     };
 
-    println!(
-        "Creating memory pool for port {} on NUMA node {:?}",
-        port_id, port_numa_node
-    );
+    let name = mbuf_pool_name(port_id, queue_id, port_numa_node);
+    let pool_name = CString::new(name.clone()).unwrap();
 
-    let pool_name = match port_numa_node {
-        Some(node) => CString::new(format!("mbuf_pool_node{}", node)).unwrap(),
-        None => CString::new("mbuf_pool_default").unwrap(),
+    // Jumbo Frames порта требуют буферов большего размера, чем общие
+    let data_room_size = if settings.use_jumbo_frames {
+        dpdk_config
+            .data_room_size
+            .max((settings.max_rx_pkt_len + 128).min(u16::MAX as u32) as u16)
+    } else {
+        dpdk_config.data_room_size
     };
 
     let socket_id = port_numa_node.map_or(-1, |id| id as c_int);
 
-    let mbuf_pool = unsafe {
-        ffi::rte_pktmbuf_pool_create(
-            pool_name.as_ptr(),
-            dpdk_config.num_mbufs,
+    log_info!(
+        "dpdk",
+        "Creating mbuf pool",
+        port = port_id,
+        pool = name,
+        mbufs = num_mbufs,
+        socket = socket_id
+    );
+
+    let mbuf_pool = match &settings.external_memory {
+        Some(memory) => create_extbuf_pool(
+            port_id,
+            &pool_name,
+            num_mbufs,
             dpdk_config.mbuf_cache_size,
-            0,
-            dpdk_config.data_room_size,
+            data_room_size,
             socket_id,
-        )
+            memory,
+        )?,
+        None => unsafe {
+            ffi::rte_pktmbuf_pool_create(
+                pool_name.as_ptr(),
+                num_mbufs,
+                dpdk_config.mbuf_cache_size,
+                0,
+                data_room_size,
+                socket_id,
+            )
+        },
     };
 
     if mbuf_pool.is_null() {
-        Err("Failed to create mbuf pool".to_string())
+        Err(format!("Failed to create mbuf pool {}", name))
     } else {
-        Ok(mbuf_pool)
+        Ok((mbuf_pool, name))
     }
 }
 
-/// Создает Ethernet конфигурацию по умолчанию
+/// Создает Ethernet конфигурацию по умолчанию (все поля нулевые, как memset в C)
 fn default_eth_config() -> ffi::RteEthConf {
-    ffi::RteEthConf {
-        rxmode: ffi::RteEthRxMode {
-            mq_mode: 0,
-            max_rx_pkt_len: 0,
-            split_hdr_size: 0,
-            offloads: 0,
-        },
-        txmode: ffi::RteEthTxMode {
-            mq_mode: 0,
-            pvid: 0,
-            offloads: 0,
-        },
-        lpbk_mode: 0,
-        rx_adv_conf: ffi::RteEthRxAdvConf {
-            rss_conf: ffi::RteEthRssConf {
-                rss_key: ptr::null_mut(),
-                rss_key_len: 0,
-                rss_hf: 0,
-            },
-        },
-        tx_adv_conf: ffi::RteEthTxAdvConf {},
-        dcb_capability_en: 0,
-        fdir_conf: ffi::RteEthFdirConf {},
-        intr_conf: ffi::RteEthIntrConf {},
-    }
+    ffi::RteEthConf::default()
 }
 
 /// Перечисляет доступные порты DPDK и возвращает информацию о них
@@ -341,12 +522,28 @@ pub fn enumerate_dpdk_ports() -> Vec<DpdkPortInfo> {
                 }
             };
 
-            // В реальном коде нужно получить имя интерфейса
-            let if_name = format!("eth{}", port_id);
+            let dev_name =
+                get_port_name(port_id as u16).unwrap_or_else(|| format!("port{}", port_id));
+            let pci_address = if is_pci_address(&dev_name) {
+                Some(dev_name.clone())
+            } else {
+                None
+            };
+
+            // Имя интерфейса ядра существует, только если драйвер бифуркационный (mlx5 и т.п.)
+            let if_name = pci_address
+                .as_deref()
+                .and_then(resolve_kernel_ifname)
+                .unwrap_or_else(|| dev_name.clone());
+
+            let mac = ffi::eth_macaddr(port_id as u16).unwrap_or_default();
 
             ports.push(DpdkPortInfo {
                 port_id: port_id as u16,
                 if_name,
+                dev_name,
+                pci_address,
+                mac_address: mac,
                 numa_node,
             });
         }
@@ -355,6 +552,280 @@ pub fn enumerate_dpdk_ports() -> Vec<DpdkPortInfo> {
     ports
 }
 
+/// Отбирает порты, указанные в конфигурации (пустой список селекторов - все порты)
+///
+/// Каждый селектор должен соответствовать ровно одному порту, иначе
+/// возвращается ошибка: неверно выбранная сетевая карта опаснее отказа запуска.
+pub fn select_ports(
+    ports: Vec<DpdkPortInfo>,
+    selectors: &[PortSelector],
+) -> Result<Vec<DpdkPortInfo>, String> {
+    if selectors.is_empty() {
+        return Ok(ports);
+    }
+
+    let mut selected: Vec<DpdkPortInfo> = Vec::new();
+
+    for selector in selectors {
+        let port = ports
+            .iter()
+            .find(|port| port_matches(port, selector))
+            .ok_or_else(|| format!("No DPDK port matches selector {}", selector))?;
+
+        if selected.iter().any(|p| p.port_id == port.port_id) {
+            return Err(format!(
+                "Port {} selected more than once (selector {})",
+                port.port_id, selector
+            ));
+        }
+
+        log_info!(
+            "dpdk",
+            "Port selector resolved",
+            selector = selector,
+            port = port.port_id
+        );
+        selected.push(port.clone());
+    }
+
+    Ok(selected)
+}
+
+/// Проверяет, соответствует ли порт селектору
+pub fn port_matches(port: &DpdkPortInfo, selector: &PortSelector) -> bool {
+    match selector {
+        PortSelector::Id(port_id) => port.port_id == *port_id,
+        PortSelector::Pci(pci) => port
+            .pci_address
+            .as_deref()
+            .is_some_and(|addr| normalize_pci_address(addr) == *pci),
+        PortSelector::Mac(mac) => port.mac_address == *mac,
+    }
+}
+
+/// Возвращает идентификатор порта по имени устройства DPDK (например, PCI адресу)
+pub fn get_port_by_name(name: &str) -> Option<u16> {
+    let c_name = CString::new(name).ok()?;
+    let mut port_id: u16 = 0;
+
+    let ret = unsafe { ffi::rte_eth_dev_get_port_by_name(c_name.as_ptr(), &mut port_id) };
+    if ret == 0 {
+        Some(port_id)
+    } else {
+        None
+    }
+}
+
+/// Возвращает имя устройства DPDK для порта
+pub fn get_port_name(port_id: u16) -> Option<String> {
+    let mut buf = [0 as c_char; ffi::RTE_ETH_NAME_MAX_LEN];
+
+    let ret = unsafe { ffi::rte_eth_dev_get_name_by_port(port_id, buf.as_mut_ptr()) };
+    if ret != 0 {
+        return None;
+    }
+
+    let name = unsafe { CStr::from_ptr(buf.as_ptr()) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+/// Проверяет, является ли строка PCI адресом вида `0000:3b:00.0` или `3b:00.0`
+pub fn is_pci_address(name: &str) -> bool {
+    let parts: Vec<&str> = name.split(':').collect();
+    let (bus, devfn) = match parts.len() {
+        2 => (parts[0], parts[1]),
+        3 if parts[0].len() == 4 && parts[0].chars().all(|c| c.is_ascii_hexdigit()) => {
+            (parts[1], parts[2])
+        }
+        _ => return false,
+    };
+
+    let (dev, func) = match devfn.split_once('.') {
+        Some(pair) => pair,
+        None => return false,
+    };
+
+    bus.len() == 2
+        && dev.len() == 2
+        && func.len() == 1
+        && bus.chars().all(|c| c.is_ascii_hexdigit())
+        && dev.chars().all(|c| c.is_ascii_hexdigit())
+        && func.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Приводит PCI адрес к полной форме с доменом (`3b:00.0` -> `0000:3b:00.0`)
+pub fn normalize_pci_address(pci: &str) -> String {
+    if pci.matches(':').count() == 1 {
+        format!("0000:{}", pci.to_lowercase())
+    } else {
+        pci.to_lowercase()
+    }
+}
+
+/// Находит имя интерфейса ядра для PCI устройства через sysfs
+pub fn resolve_kernel_ifname(pci: &str) -> Option<String> {
+    let net_dir = Path::new("/sys/bus/pci/devices")
+        .join(normalize_pci_address(pci))
+        .join("net");
+
+    let mut names: Vec<String> = fs::read_dir(net_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+
+    names.sort();
+    names.into_iter().next()
+}
+
+/// Подключает устройство после инициализации EAL (rte_dev_probe)
+///
+/// `devargs` - строка устройства DPDK, например `0000:3b:02.0` или
+/// `net_pcap0,iface=eth1`. Возвращает порты, появившиеся после подключения.
+pub fn attach_device(devargs: &str) -> Result<Vec<DpdkPortInfo>, String> {
+    let c_devargs =
+        CString::new(devargs).map_err(|_| format!("Invalid device arguments '{}'", devargs))?;
+
+    let before: Vec<u16> = enumerate_dpdk_ports().iter().map(|p| p.port_id).collect();
+
+    let ret = unsafe { ffi::rte_dev_probe(c_devargs.as_ptr()) };
+    if ret < 0 {
+        return Err(format!("Failed to probe device {}: error {}", devargs, ret));
+    }
+
+    let attached: Vec<DpdkPortInfo> = enumerate_dpdk_ports()
+        .into_iter()
+        .filter(|port| !before.contains(&port.port_id))
+        .collect();
+
+    if attached.is_empty() {
+        return Err(format!("Device {} did not create any ports", devargs));
+    }
+
+    for port in &attached {
+        log_info!(
+            "dpdk",
+            "Device attached",
+            devargs = devargs,
+            port = port.port_id,
+            device = port.dev_name
+        );
+    }
+
+    Ok(attached)
+}
+
+/// Останавливает порт и отключает его устройство (rte_dev_remove)
+///
+/// Рабочие потоки порта должны быть остановлены заранее. Пул mbuf порта
+/// не освобождается.
+pub fn detach_port(port_id: u16) -> Result<(), String> {
+    let dev_name = get_port_name(port_id);
+
+    let ret = unsafe { ffi::dpdk_port_detach(port_id) };
+    if ret != 0 {
+        return Err(format!("Failed to detach port {}: error {}", port_id, ret));
+    }
+
+    log_info!(
+        "dpdk",
+        "Device detached",
+        port = port_id,
+        device = dev_name.unwrap_or_default()
+    );
+
+    Ok(())
+}
+
+/// Останавливает порт, сохраняя его конфигурацию (например, на время обслуживания NIC)
+pub fn stop_port(port_id: u16) -> Result<(), String> {
+    let ret = unsafe { ffi::rte_eth_dev_stop(port_id) };
+    if ret != 0 {
+        return Err(format!(
+            "Failed to stop port {}: error code {}",
+            port_id, ret
+        ));
+    }
+
+    log_info!("dpdk", "Port stopped", port = port_id);
+    Ok(())
+}
+
+/// Останавливает очередь RX в NIC, не затрагивая остальные очереди порта
+///
+/// Очередь не должен опрашивать ни один поток. Поддерживается не всеми PMD.
+pub fn stop_rx_queue(port_id: u16, queue_id: u16) -> Result<(), String> {
+    let ret = unsafe { ffi::rte_eth_dev_rx_queue_stop(port_id, queue_id) };
+    if ret != 0 {
+        return Err(format!(
+            "Failed to stop RX queue {} of port {}: error code {}",
+            queue_id, port_id, ret
+        ));
+    }
+
+    log_info!("dpdk", "RX queue stopped", port = port_id, queue = queue_id);
+    Ok(())
+}
+
+/// Повторно запускает очередь RX, остановленную `stop_rx_queue`
+pub fn start_rx_queue(port_id: u16, queue_id: u16) -> Result<(), String> {
+    let ret = unsafe { ffi::rte_eth_dev_rx_queue_start(port_id, queue_id) };
+    if ret != 0 {
+        return Err(format!(
+            "Failed to start RX queue {} of port {}: error code {}",
+            queue_id, port_id, ret
+        ));
+    }
+
+    log_info!("dpdk", "RX queue started", port = port_id, queue = queue_id);
+    Ok(())
+}
+
+/// Освобождает mbuf кадров, отправленных из очередей TX порта (перед `stop_port`)
+///
+/// Возвращает false, если к `deadline` PMD еще освобождал дескрипторы.
+/// PMD без rte_eth_tx_done_cleanup пропускаются: их mbuf освобождает
+/// остановка порта.
+pub fn flush_tx_queues(port_id: u16, num_tx_queues: u16, deadline: Instant) -> bool {
+    for queue_id in 0..num_tx_queues {
+        // Повтор, пока PMD находит отправленные кадры
+        while unsafe { ffi::rte_eth_tx_done_cleanup(port_id, queue_id, 0) } > 0 {
+            if Instant::now() >= deadline {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// Повторно запускает порт, остановленный `stop_port`
+pub fn restart_port(port_id: u16) -> Result<(), String> {
+    let ret = unsafe { ffi::rte_eth_dev_start(port_id) };
+    if ret < 0 {
+        return Err(format!(
+            "Failed to start port {}: error code {}",
+            port_id, ret
+        ));
+    }
+
+    log_info!("dpdk", "Port restarted", port = port_id);
+    Ok(())
+}
+
+/// Состояние линка порта (поднят, скорость в Мбит/с; 0 - неизвестна) без ожидания
+pub fn link_status(port_id: u16) -> Result<(bool, u32), String> {
+    let mut up = 0;
+    let mut speed_mbps = 0;
+    let ret = unsafe { ffi::dpdk_link_status(port_id, &mut up, &mut speed_mbps) };
+    if ret < 0 {
+        return Err(format!(
+            "Failed to read link status of port {}: error code {}",
+            port_id, ret
+        ));
+    }
+    Ok((up == 1, speed_mbps))
+}
+
 /// Завершает работу DPDK и освобождает ресурсы
 pub fn cleanup_dpdk() {
     unsafe {
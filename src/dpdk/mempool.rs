@@ -0,0 +1,226 @@
+// src/dpdk/mempool.rs
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::cpu::housekeeping;
+use crate::dpdk::init::mempool_usage;
+use crate::{log_info, log_warn};
+
+/// Пороги заполненности пула mbuf (в процентах занятых mbuf)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MempoolThresholds {
+    pub warn_pct: u8,
+    pub critical_pct: u8,
+}
+
+impl Default for MempoolThresholds {
+    fn default() -> Self {
+        Self {
+            warn_pct: 80,
+            critical_pct: 95,
+        }
+    }
+}
+
+impl MempoolThresholds {
+    /// Разбирает `<warn_pct>[,<critical_pct>]`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let parse_pct = |value: &str| -> Result<u8, String> {
+            value
+                .parse()
+                .map_err(|_| format!("Invalid mempool threshold '{}'", value))
+        };
+
+        let mut parts = spec.split(',');
+        let warn_pct = parse_pct(parts.next().unwrap_or_default())?;
+        let critical_pct = match parts.next() {
+            Some(value) => parse_pct(value)?,
+            None => Self::default().critical_pct.max(warn_pct),
+        };
+
+        if parts.next().is_some() || warn_pct == 0 || warn_pct > critical_pct || critical_pct > 100
+        {
+            return Err(format!(
+                "Invalid mempool thresholds '{}': expected 0 < warn <= critical <= 100",
+                spec
+            ));
+        }
+
+        Ok(Self {
+            warn_pct,
+            critical_pct,
+        })
+    }
+
+    fn level(&self, utilization_pct: u32) -> MempoolLevel {
+        if utilization_pct >= self.critical_pct as u32 {
+            MempoolLevel::Critical
+        } else if utilization_pct >= self.warn_pct as u32 {
+            MempoolLevel::Warning
+        } else {
+            MempoolLevel::Normal
+        }
+    }
+}
+
+/// Уровень заполненности пула относительно порогов
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MempoolLevel {
+    Normal,
+    Warning,
+    Critical,
+}
+
+impl fmt::Display for MempoolLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MempoolLevel::Normal => write!(f, "normal"),
+            MempoolLevel::Warning => write!(f, "warning"),
+            MempoolLevel::Critical => write!(f, "critical"),
+        }
+    }
+}
+
+/// Последний замер пула mbuf
+#[derive(Debug, Clone)]
+pub struct MempoolSample {
+    pub port_id: u16,
+    pub pool: String,
+    pub in_use: u32,
+    pub available: u32,
+    /// Наибольшее число занятых mbuf с момента запуска монитора
+    pub peak_in_use: u32,
+    pub level: MempoolLevel,
+}
+
+impl MempoolSample {
+    /// Доля занятых mbuf, %
+    pub fn utilization_pct(&self) -> u32 {
+        let total = self.in_use as u64 + self.available as u64;
+        if total == 0 {
+            return 0;
+        }
+        (self.in_use as u64 * 100 / total) as u32
+    }
+}
+
+/// Периодический замер заполненности пулов mbuf
+///
+/// Исчерпание пула развивается постепенно (обработчик удерживает mbuf,
+/// кольцо TX не освобождается), а rx_nombuf начинает расти, только когда
+/// mbuf уже не осталось. Монитор предупреждает при переходе порогов заранее.
+/// Набор пулов фиксируется при запуске: пулы портов, подключенных позже,
+/// не отслеживаются.
+pub struct MempoolMonitor {
+    latest: Arc<Mutex<Vec<MempoolSample>>>,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MempoolMonitor {
+    /// Запускает монитор для пулов `(порт, имя пула)`
+    pub fn start(
+        pools: Vec<(u16, String)>,
+        interval: Duration,
+        thresholds: MempoolThresholds,
+    ) -> Result<Self, String> {
+        if pools.is_empty() {
+            return Err("No mbuf pools to monitor".to_string());
+        }
+
+        let latest = Arc::new(Mutex::new(Vec::new()));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread_latest = latest.clone();
+        let thread_running = running.clone();
+        let thread = thread::Builder::new()
+            .name("hfeec-mempool".to_string())
+            .spawn(move || {
+                housekeeping::pin_current_thread();
+
+                let mut samples: Vec<MempoolSample> = pools
+                    .into_iter()
+                    .map(|(port_id, pool)| MempoolSample {
+                        port_id,
+                        pool,
+                        in_use: 0,
+                        available: 0,
+                        peak_in_use: 0,
+                        level: MempoolLevel::Normal,
+                    })
+                    .collect();
+
+                while thread_running.load(Ordering::Relaxed) {
+                    for sample in samples.iter_mut() {
+                        let Some(usage) = mempool_usage(&sample.pool) else {
+                            continue;
+                        };
+                        sample.in_use = usage.in_use;
+                        sample.available = usage.available;
+                        sample.peak_in_use = sample.peak_in_use.max(usage.in_use);
+
+                        let level = thresholds.level(sample.utilization_pct());
+                        if level > sample.level {
+                            log_warn!(
+                                "mempool",
+                                "Mbuf pool utilization above threshold",
+                                port = sample.port_id,
+                                pool = sample.pool,
+                                level = level,
+                                utilization_pct = sample.utilization_pct(),
+                                in_use = usage.in_use,
+                                available = usage.available
+                            );
+                        } else if level == MempoolLevel::Normal && sample.level != level {
+                            log_info!(
+                                "mempool",
+                                "Mbuf pool utilization back to normal",
+                                port = sample.port_id,
+                                pool = sample.pool,
+                                utilization_pct = sample.utilization_pct()
+                            );
+                        }
+                        sample.level = level;
+                    }
+
+                    if let Ok(mut latest) = thread_latest.lock() {
+                        latest.clone_from(&samples);
+                    }
+
+                    thread::sleep(interval);
+                }
+            })
+            .map_err(|e| format!("Failed to spawn mempool monitor: {}", e))?;
+
+        Ok(Self {
+            latest,
+            running,
+            thread: Some(thread),
+        })
+    }
+
+    /// Последние замеры всех пулов
+    pub fn samples(&self) -> Vec<MempoolSample> {
+        self.latest
+            .lock()
+            .map(|latest| latest.clone())
+            .unwrap_or_default()
+    }
+
+    /// Останавливает монитор
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for MempoolMonitor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
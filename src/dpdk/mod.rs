@@ -1,4 +1,20 @@
 pub mod config;
+pub mod devbind;
+pub mod eventdev;
+pub mod extmem;
 pub mod ffi;
+pub mod flow;
+pub mod flow_ctrl;
 pub mod hugepages;
 pub mod init;
+pub mod mempool;
+pub mod multiproc;
+pub mod pipeline;
+pub mod probe;
+pub mod ptp;
+pub mod reta;
+pub mod rx_intr;
+pub mod soft_rss;
+pub mod tx;
+pub mod validate;
+pub mod xstats;
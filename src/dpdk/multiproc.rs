@@ -0,0 +1,68 @@
+// src/dpdk/multiproc.rs
+use std::fmt;
+
+use crate::dpdk::ffi;
+use crate::dpdk::init::{lookup_mempool, mbuf_pool_name};
+
+/// Роль процесса в многопроцессном режиме DPDK (--proc-type)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessType {
+    /// Процесс настраивает порты и создает пулы
+    Primary,
+    /// Процесс подключается к портам и пулам первичного процесса
+    Secondary,
+    /// Вторичный, если первичный процесс с тем же --file-prefix уже запущен
+    Auto,
+}
+
+impl ProcessType {
+    /// Разбирает `primary`, `secondary` или `auto`
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "primary" => Ok(ProcessType::Primary),
+            "secondary" => Ok(ProcessType::Secondary),
+            "auto" => Ok(ProcessType::Auto),
+            other => Err(format!(
+                "Invalid process type '{}': expected primary, secondary or auto",
+                other
+            )),
+        }
+    }
+
+    /// Роль, определенная EAL после инициализации (для `Auto` - фактическая)
+    pub fn current() -> Self {
+        match unsafe { ffi::rte_eal_process_type() } {
+            ffi::RTE_PROC_SECONDARY => ProcessType::Secondary,
+            _ => ProcessType::Primary,
+        }
+    }
+}
+
+impl fmt::Display for ProcessType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProcessType::Primary => write!(f, "primary"),
+            ProcessType::Secondary => write!(f, "secondary"),
+            ProcessType::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+/// Находит пулы mbuf порта, созданные первичным процессом
+///
+/// Имена пулов строятся так же, как при создании (`mbuf_pool_name`): сначала
+/// ищется общий пул порта, затем пулы очередей.
+pub fn find_port_pools(port_id: u16, num_rx_queues: u16) -> Vec<String> {
+    let node = unsafe { ffi::rte_eth_dev_socket_id(port_id) };
+    let node = (node >= 0).then_some(node as usize);
+
+    let shared = mbuf_pool_name(port_id, None, node);
+    if lookup_mempool(&shared).is_some() {
+        return vec![shared];
+    }
+
+    (0..num_rx_queues)
+        .map(|queue| mbuf_pool_name(port_id, Some(queue), node))
+        .filter(|name| lookup_mempool(name).is_some())
+        .collect()
+}
@@ -0,0 +1,420 @@
+// src/dpdk/pipeline.rs
+use core_affinity::CoreId;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::control::events::{EventBus, LifecycleEvent};
+use crate::cpu::idle::IdleBackoff;
+use crate::dpdk::ffi::{self, RteMbuf};
+use crate::mem::spsc::{Consumer, Producer};
+use crate::metrics::counters::WorkerMetrics;
+use crate::numa::ffi::NumaAllocator;
+use crate::numa::node::{fill_packet, handle_fragment, WorkerSettings, MAX_BURST_SIZE};
+use crate::numa::supervisor::{call_isolated, PanicAction};
+use crate::packet::handler::SharedHandlers;
+use crate::packet::parser::ParsedPacket;
+use crate::packet::pool::PacketDataPool;
+use crate::packet::reassembly::FragmentTable;
+use crate::time;
+use crate::{log_error, log_info, log_warn};
+
+/// Емкость кольца от очереди RX к ядру декодирования (в пакетах)
+pub const PIPELINE_RING_SIZE: usize = 4096;
+
+/// Результат разбора заголовков на ядре приема
+#[derive(Debug, Clone, Copy)]
+pub enum StagedKind {
+    Packet(ParsedPacket),
+    /// IPv4 фрагмент: собирается на ядре декодирования
+    Fragment {
+        l3_offset: usize,
+    },
+}
+
+/// Принятый и разобранный пакет, передаваемый ядру декодирования
+///
+/// mbuf принадлежит ядру декодирования с момента постановки в кольцо и
+/// освобождается им после обработчика.
+pub struct StagedPacket {
+    mbuf: *mut RteMbuf,
+    kind: StagedKind,
+    rx_timestamp_ns: u64,
+}
+
+// mbuf принадлежит тому потоку, который извлек запись из кольца
+unsafe impl Send for StagedPacket {}
+
+/// Сторона приема конвейера: копит разобранные пакеты пачки и публикует
+/// их в кольцо одной записью
+pub struct PipelineStage {
+    ring: Producer<StagedPacket>,
+    pending: Vec<StagedPacket>,
+}
+
+impl PipelineStage {
+    pub fn new(ring: Producer<StagedPacket>) -> Self {
+        Self {
+            ring,
+            pending: Vec::with_capacity(MAX_BURST_SIZE as usize),
+        }
+    }
+
+    /// Добавляет пакет к пачке
+    #[inline(always)]
+    pub fn stage(&mut self, mbuf: *mut RteMbuf, kind: StagedKind, rx_timestamp_ns: u64) {
+        self.pending.push(StagedPacket {
+            mbuf,
+            kind,
+            rx_timestamp_ns,
+        });
+    }
+
+    /// Публикует пачку; пакеты, не поместившиеся в кольцо, освобождаются
+    ///
+    /// Возвращает число отброшенных пакетов.
+    #[inline(always)]
+    pub fn flush(&mut self) -> usize {
+        if self.pending.is_empty() {
+            return 0;
+        }
+        self.ring.push_burst(&mut self.pending);
+        let dropped = self.pending.len();
+        for staged in self.pending.drain(..) {
+            unsafe { ffi::rte_pktmbuf_free(staged.mbuf) };
+        }
+        dropped
+    }
+}
+
+/// Входное кольцо ядра декодирования: одна очередь RX порта
+struct DecoderInput {
+    queue_id: u16,
+    ring: Consumer<StagedPacket>,
+    /// Счетчики обработчиков очереди (счетчики приема ведет ядро приема)
+    metrics: Arc<WorkerMetrics>,
+}
+
+impl Drop for DecoderInput {
+    fn drop(&mut self) {
+        let mut staged = Vec::new();
+        while self.ring.pop_burst(&mut staged, MAX_BURST_SIZE as usize) > 0 {
+            for packet in staged.drain(..) {
+                unsafe { ffi::rte_pktmbuf_free(packet.mbuf) };
+            }
+        }
+    }
+}
+
+/// Ядро декодирования конвейера: выполняет обработчики для пакетов,
+/// принятых и разобранных на ядрах очередей RX
+///
+/// Поток обслуживает кольца нескольких очередей порта по очереди; каждое
+/// кольцо пишет ровно одно ядро приема, поэтому кольца - SPSC. Пакеты
+/// одной очереди обрабатываются по порядку.
+pub struct PipelineDecoder {
+    pub port_id: u16,
+    pub core_id: CoreId,
+    /// Очереди RX и счетчики их обработчиков
+    pub queues: Vec<(u16, Arc<WorkerMetrics>)>,
+    stop: Arc<AtomicBool>,
+    /// Завершиться, когда кольца опустеют (см. `drain`)
+    drain: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl PipelineDecoder {
+    /// Запускает ядро декодирования для колец `inputs` (очередь RX, кольцо)
+    #[allow(clippy::too_many_arguments)]
+    pub fn start(
+        node_id: usize,
+        port_id: u16,
+        core_id: CoreId,
+        inputs: Vec<(u16, Consumer<StagedPacket>)>,
+        handlers: Arc<SharedHandlers>,
+        running: Arc<AtomicBool>,
+        events: EventBus,
+        settings: &WorkerSettings,
+    ) -> Result<Self, String> {
+        let mut inputs: Vec<DecoderInput> = inputs
+            .into_iter()
+            .map(|(queue_id, ring)| DecoderInput {
+                queue_id,
+                ring,
+                metrics: Arc::new(WorkerMetrics::default()),
+            })
+            .collect();
+        let queues = inputs
+            .iter()
+            .map(|input| (input.queue_id, input.metrics.clone()))
+            .collect();
+
+        let burst_size = settings.burst_size.clamp(1, MAX_BURST_SIZE as u32) as usize;
+        let reassembly = settings.reassembly;
+        let timers = settings.timers.clone();
+        let idle_policy = settings.idle_policy.polling();
+        let panic_policy = settings.panic_policy;
+        let realtime = settings.realtime;
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let drain = Arc::new(AtomicBool::new(false));
+        let thread_drain = drain.clone();
+
+        let thread = thread::Builder::new()
+            .name(format!("hfeec-decode-p{}c{}", port_id, core_id.id))
+            .spawn(move || {
+                core_affinity::set_for_current(core_id);
+                if NumaAllocator::is_available() {
+                    NumaAllocator::bind_thread_to_node(node_id);
+                }
+                log_info!(
+                    "worker",
+                    "Pipeline decoder started",
+                    port = port_id,
+                    core = core_id.id,
+                    queues = inputs.len()
+                );
+                if let Some(realtime) = &realtime {
+                    if let Err(e) = realtime.apply_current_thread(core_id.id) {
+                        log_warn!(
+                            "worker",
+                            "Realtime scheduling not applied",
+                            port = port_id,
+                            core = core_id.id,
+                            error = e
+                        );
+                    }
+                }
+
+                let packet_pool = PacketDataPool::new(burst_size, Some(node_id));
+                let mut fragment_tables: Vec<Option<FragmentTable>> = inputs
+                    .iter()
+                    .map(|_| reassembly.map(FragmentTable::new))
+                    .collect();
+                let mut dispatch: Vec<_> = inputs
+                    .iter()
+                    .map(|input| handlers.resolve(port_id, input.queue_id))
+                    .collect();
+                let mut dispatch_generation = handlers.generation();
+                let mut staged = Vec::with_capacity(burst_size);
+                let mut done = Vec::with_capacity(burst_size);
+                let mut backoff = IdleBackoff::new(idle_policy);
+                let isolate = panic_policy.isolates();
+                let mut panics: u64 = 0;
+
+                while running.load(Ordering::SeqCst) && !thread_stop.load(Ordering::Relaxed) {
+                    let generation = handlers.generation();
+                    if generation != dispatch_generation {
+                        for (slot, input) in dispatch.iter_mut().zip(&inputs) {
+                            *slot = handlers.resolve(port_id, input.queue_id);
+                        }
+                        dispatch_generation = generation;
+                    }
+
+                    let mut idle = true;
+                    for (index, input) in inputs.iter_mut().enumerate() {
+                        staged.clear();
+                        if input.ring.pop_burst(&mut staged, burst_size) == 0 {
+                            continue;
+                        }
+                        idle = false;
+
+                        // Перехваченная паника обработчика очереди
+                        let mut handler_panic = None;
+
+                        for packet in staged.drain(..) {
+                            // После паники остаток пачки отбрасывается, mbuf освобождаются
+                            if handler_panic.is_some() {
+                                input.metrics.counters.dropped.inc();
+                                done.push(packet.mbuf);
+                                continue;
+                            }
+
+                            let frame = unsafe {
+                                let data = ffi::dpdk_mbuf_mtod(packet.mbuf) as *const u8;
+                                let len = ffi::dpdk_mbuf_data_len(packet.mbuf) as usize;
+                                std::slice::from_raw_parts(data, len)
+                            };
+
+                            match packet.kind {
+                                StagedKind::Packet(parsed) => {
+                                    let mut data = packet_pool.acquire();
+                                    fill_packet(
+                                        &mut data,
+                                        frame,
+                                        &parsed,
+                                        packet.mbuf,
+                                        port_id,
+                                        input.queue_id,
+                                        packet.rx_timestamp_ns,
+                                    );
+                                    let result = call_isolated(isolate, || {
+                                        dispatch[index].dispatch_timed(
+                                            &input.metrics,
+                                            input.queue_id,
+                                            &data,
+                                        )
+                                    });
+                                    packet_pool.release(data);
+                                    if let Err(message) = result {
+                                        input.metrics.counters.dropped.inc();
+                                        handler_panic = Some(message);
+                                    }
+                                }
+                                StagedKind::Fragment { l3_offset } => {
+                                    match fragment_tables[index].as_mut() {
+                                        Some(table) => {
+                                            let result = call_isolated(isolate, || {
+                                                handle_fragment(
+                                                    table,
+                                                    frame,
+                                                    l3_offset,
+                                                    port_id,
+                                                    input.queue_id,
+                                                    packet.rx_timestamp_ns,
+                                                    &packet_pool,
+                                                    &dispatch[index],
+                                                    &input.metrics,
+                                                )
+                                            });
+                                            if let Err(message) = result {
+                                                input.metrics.counters.dropped.inc();
+                                                handler_panic = Some(message);
+                                            }
+                                        }
+                                        None => input.metrics.counters.dropped.inc(),
+                                    }
+                                }
+                            }
+                            done.push(packet.mbuf);
+                        }
+
+                        unsafe { ffi::rte_pktmbuf_free_bulk(done.as_mut_ptr(), done.len() as u32) };
+                        done.clear();
+
+                        if let Some(message) = handler_panic {
+                            panics += 1;
+                            log_error!(
+                                "worker",
+                                "Packet handler panicked on decode core",
+                                port = port_id,
+                                queue = input.queue_id,
+                                core = core_id.id,
+                                panics = panics,
+                                message = message
+                            );
+                            events.emit(LifecycleEvent::WorkerPanicked {
+                                port_id,
+                                queue_id: input.queue_id,
+                                core_id: core_id.id,
+                                message: message.clone(),
+                            });
+
+                            match panic_policy.action(panics) {
+                                PanicAction::Restart => {
+                                    fragment_tables[index] = reassembly.map(FragmentTable::new);
+                                    dispatch[index] = handlers.resolve(port_id, input.queue_id);
+                                    continue;
+                                }
+                                PanicAction::KillSwitch => {
+                                    thread_stop.store(true, Ordering::Relaxed);
+                                    events.emit(LifecycleEvent::KillSwitch {
+                                        engaged: true,
+                                        reason: format!(
+                                            "handler panic on port {} queue {}: {}",
+                                            port_id, input.queue_id, message
+                                        ),
+                                    });
+                                    break;
+                                }
+                            }
+                        }
+
+                        if let Some(table) = fragment_tables[index].as_mut() {
+                            if table.pending() > 0 {
+                                table.evict_expired(time::now_ns());
+                            }
+                        }
+                    }
+
+                    if idle {
+                        // Ядра приема остановлены и кольца пусты: дообработка завершена
+                        if thread_drain.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        if let Some(timers) = &timers {
+                            timers.poll(time::now_ns());
+                        }
+                        backoff.idle();
+                    } else {
+                        backoff.reset();
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to spawn pipeline decoder: {}", e))?;
+
+        Ok(Self {
+            port_id,
+            core_id,
+            queues,
+            stop,
+            drain,
+            thread: Some(thread),
+        })
+    }
+
+    /// Счетчики обработчиков очереди RX (None - очередь обслуживает другое ядро)
+    pub fn queue_metrics(&self, queue_id: u16) -> Option<&Arc<WorkerMetrics>> {
+        self.queues
+            .iter()
+            .find(|(id, _)| *id == queue_id)
+            .map(|(_, metrics)| metrics)
+    }
+
+    /// Дообрабатывает пакеты в кольцах и останавливает ядро декодирования
+    ///
+    /// Ядра приема очередей должны быть уже остановлены. Пакеты, не
+    /// обработанные к `deadline`, освобождаются; тогда возвращается false.
+    pub fn drain(&mut self, deadline: Instant) -> bool {
+        self.drain.store(true, Ordering::Relaxed);
+        let drained = match &self.thread {
+            Some(thread) => {
+                while !thread.is_finished() && Instant::now() < deadline {
+                    thread::sleep(Duration::from_micros(100));
+                }
+                thread.is_finished()
+            }
+            None => true,
+        };
+        self.stop();
+        drained
+    }
+
+    /// Останавливает ядро декодирования; необработанные пакеты освобождаются
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                log_warn!(
+                    "worker",
+                    "Pipeline decoder panicked",
+                    port = self.port_id,
+                    core = self.core_id.id
+                );
+            }
+            log_info!(
+                "worker",
+                "Pipeline decoder stopped",
+                port = self.port_id,
+                core = self.core_id.id
+            );
+        }
+    }
+}
+
+impl Drop for PipelineDecoder {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
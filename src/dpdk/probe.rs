@@ -0,0 +1,288 @@
+// src/dpdk/probe.rs
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::dpdk::ffi::{self, RteMbuf, RteMempool};
+use crate::metrics::histogram::{HistogramSnapshot, LatencyHistogram};
+use crate::packet::parser::{parse_frame, ETHER_TYPE_IPV4, ETH_HDR_LEN, VXLAN_DEFAULT_PORT};
+use crate::time;
+
+/// Сигнатура полезной нагрузки пробы ("HFEECPRB")
+pub const PROBE_MAGIC: u64 = u64::from_le_bytes(*b"HFEECPRB");
+/// UDP порт проб (источник и назначение)
+pub const PROBE_UDP_PORT: u16 = 7357;
+/// Заголовок пробы в полезной нагрузке: сигнатура, номер, время отправки
+pub const PROBE_HEADER_LEN: usize = 24;
+
+const IPV4_HDR_LEN: usize = 20;
+const UDP_HDR_LEN: usize = 8;
+/// Смещение времени отправки в кадре (дописывается непосредственно перед tx_burst)
+const TX_TIMESTAMP_OFFSET: usize = ETH_HDR_LEN + IPV4_HDR_LEN + UDP_HDR_LEN + 16;
+
+/// Параметры измерения
+#[derive(Debug, Clone)]
+pub struct ProbeConfig {
+    pub tx_port: u16,
+    pub rx_port: u16,
+    pub src_mac: [u8; 6],
+    pub dst_mac: [u8; 6],
+    /// Количество проб
+    pub count: u64,
+    /// Интервал между пробами
+    pub interval: Duration,
+    /// Длина полезной нагрузки UDP (не меньше `PROBE_HEADER_LEN`)
+    pub payload_len: usize,
+    /// Ожидание последних проб после отправки
+    pub drain_timeout: Duration,
+}
+
+impl ProbeConfig {
+    pub fn new(tx_port: u16, rx_port: u16) -> Self {
+        Self {
+            tx_port,
+            rx_port,
+            src_mac: [0x02, 0, 0, 0, 0, 0x01],
+            dst_mac: [0xff; 6],
+            count: 10_000,
+            interval: Duration::from_micros(100),
+            payload_len: 64,
+            drain_timeout: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Итог измерения пути между портами
+#[derive(Debug, Clone)]
+pub struct ProbeReport {
+    pub sent: u64,
+    /// Пробы, которые NIC отправки не принял в очередь TX
+    pub tx_refused: u64,
+    pub received: u64,
+    /// Пробы, пришедшие с номером меньше уже полученного
+    pub reordered: u64,
+    /// Наименьшая задержка (нс)
+    pub min_ns: u64,
+    /// Средний модуль разности задержек соседних проб (нс)
+    pub jitter_ns: u64,
+    pub latency: HistogramSnapshot,
+}
+
+impl ProbeReport {
+    pub fn lost(&self) -> u64 {
+        self.sent.saturating_sub(self.tx_refused + self.received)
+    }
+}
+
+impl fmt::Display for ProbeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Probes: sent {} (tx refused {}) received {} lost {} reordered {}",
+            self.sent,
+            self.tx_refused,
+            self.received,
+            self.lost(),
+            self.reordered
+        )?;
+        writeln!(
+            f,
+            "Latency: min={}ns mean={}ns {}",
+            self.min_ns,
+            self.latency.mean(),
+            self.latency
+        )?;
+        write!(f, "Jitter: {}ns", self.jitter_ns)
+    }
+}
+
+/// Измеряет задержку физического пути: пробы уходят из `tx_port` и
+/// принимаются на `rx_port` (через коммутатор, используемый для площадки)
+///
+/// Порты должны быть настроены и запущены, рабочие потоки на них не
+/// запускаются: очередь 0 обоих портов опрашивает вызывающий поток. Обе
+/// отметки времени берутся по одним часам (`time::now_ns`), поэтому в
+/// задержку входят также PCIe и драйвер обоих NIC.
+pub fn run_probe(config: &ProbeConfig, pool: *mut RteMempool) -> Result<ProbeReport, String> {
+    if pool.is_null() {
+        return Err("Probe requires an mbuf pool".to_string());
+    }
+    let payload_len = config.payload_len.max(PROBE_HEADER_LEN);
+    let template = build_frame(config, payload_len);
+
+    let histogram = LatencyHistogram::new();
+    let mut receiver = ProbeReceiver::default();
+    let mut rx_pkts: [*mut RteMbuf; 32] = [std::ptr::null_mut(); 32];
+
+    let mut sent = 0;
+    let mut tx_refused = 0;
+    let mut next_send = Instant::now();
+    let mut deadline = None;
+
+    loop {
+        let now = Instant::now();
+        if sent < config.count && now >= next_send {
+            if !send_probe(config.tx_port, pool, &template, sent)? {
+                tx_refused += 1;
+            }
+            sent += 1;
+            next_send += config.interval;
+            if sent == config.count {
+                deadline = Some(now + config.drain_timeout);
+            }
+        }
+
+        let nb_rx =
+            unsafe { ffi::rte_eth_rx_burst(config.rx_port, 0, rx_pkts.as_mut_ptr(), 32) } as usize;
+        let rx_ns = time::now_ns();
+        for &pkt in &rx_pkts[..nb_rx] {
+            let frame = unsafe {
+                let data = ffi::dpdk_mbuf_mtod(pkt) as *const u8;
+                let len = ffi::dpdk_mbuf_data_len(pkt) as usize;
+                std::slice::from_raw_parts(data, len)
+            };
+            if let Some(latency_ns) = receiver.on_frame(frame, rx_ns) {
+                histogram.record(latency_ns);
+            }
+        }
+        if nb_rx > 0 {
+            unsafe { ffi::rte_pktmbuf_free_bulk(rx_pkts.as_mut_ptr(), nb_rx as u32) };
+        }
+
+        let done = receiver.received + tx_refused == config.count;
+        if done || deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            break;
+        }
+    }
+
+    Ok(ProbeReport {
+        sent,
+        tx_refused,
+        received: receiver.received,
+        reordered: receiver.reordered,
+        min_ns: receiver.min_ns.unwrap_or(0),
+        jitter_ns: receiver
+            .jitter_sum_ns
+            .checked_div(receiver.received.saturating_sub(1))
+            .unwrap_or(0),
+        latency: histogram.snapshot(),
+    })
+}
+
+/// Разбор принятых проб
+#[derive(Default)]
+struct ProbeReceiver {
+    received: u64,
+    reordered: u64,
+    highest_sequence: Option<u64>,
+    last_latency_ns: Option<u64>,
+    jitter_sum_ns: u64,
+    min_ns: Option<u64>,
+}
+
+impl ProbeReceiver {
+    /// Учитывает кадр; возвращает задержку, если это проба
+    fn on_frame(&mut self, frame: &[u8], rx_ns: u64) -> Option<u64> {
+        let parsed = parse_frame(frame, 0, VXLAN_DEFAULT_PORT).ok()?;
+        if parsed.dst_port != PROBE_UDP_PORT || parsed.payload_len < PROBE_HEADER_LEN {
+            return None;
+        }
+
+        let payload = &frame[parsed.payload_offset..parsed.payload_offset + PROBE_HEADER_LEN];
+        let field = |offset: usize| {
+            u64::from_le_bytes(payload[offset..offset + 8].try_into().unwrap_or_default())
+        };
+        if field(0) != PROBE_MAGIC {
+            return None;
+        }
+        let sequence = field(8);
+        let latency_ns = rx_ns.saturating_sub(field(16));
+
+        self.received += 1;
+        match self.highest_sequence {
+            Some(highest) if sequence < highest => self.reordered += 1,
+            _ => self.highest_sequence = Some(sequence),
+        }
+        if let Some(last) = self.last_latency_ns {
+            self.jitter_sum_ns += last.abs_diff(latency_ns);
+        }
+        self.last_latency_ns = Some(latency_ns);
+        self.min_ns = Some(self.min_ns.map_or(latency_ns, |min| min.min(latency_ns)));
+
+        Some(latency_ns)
+    }
+}
+
+/// Отправляет пробу с номером `sequence`; false, если NIC не принял кадр
+fn send_probe(
+    port_id: u16,
+    pool: *mut RteMempool,
+    template: &[u8],
+    sequence: u64,
+) -> Result<bool, String> {
+    let mut mbuf = unsafe { ffi::dpdk_mbuf_alloc(pool) };
+    if mbuf.is_null() {
+        return Err("Failed to allocate probe mbuf".to_string());
+    }
+
+    unsafe {
+        let data = ffi::dpdk_mbuf_append(mbuf, template.len() as u16) as *mut u8;
+        if data.is_null() {
+            ffi::rte_pktmbuf_free(mbuf);
+            return Err("Probe frame does not fit into mbuf".to_string());
+        }
+        let frame = std::slice::from_raw_parts_mut(data, template.len());
+        frame.copy_from_slice(template);
+        frame[TX_TIMESTAMP_OFFSET - 8..TX_TIMESTAMP_OFFSET]
+            .copy_from_slice(&sequence.to_le_bytes());
+        // Время отправки записывается последним, непосредственно перед tx_burst
+        frame[TX_TIMESTAMP_OFFSET..TX_TIMESTAMP_OFFSET + 8]
+            .copy_from_slice(&time::now_ns().to_le_bytes());
+
+        if ffi::rte_eth_tx_burst(port_id, 0, &mut mbuf, 1) == 0 {
+            ffi::rte_pktmbuf_free(mbuf);
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Собирает кадр Ethernet/IPv4/UDP пробы (номер и время заполняются при отправке)
+fn build_frame(config: &ProbeConfig, payload_len: usize) -> Vec<u8> {
+    let ip_len = IPV4_HDR_LEN + UDP_HDR_LEN + payload_len;
+    let mut frame = Vec::with_capacity(ETH_HDR_LEN + ip_len);
+
+    frame.extend_from_slice(&config.dst_mac);
+    frame.extend_from_slice(&config.src_mac);
+    frame.extend_from_slice(&ETHER_TYPE_IPV4.to_be_bytes());
+
+    let ip_start = frame.len();
+    frame.extend_from_slice(&[0x45, 0]);
+    frame.extend_from_slice(&(ip_len as u16).to_be_bytes());
+    frame.extend_from_slice(&[0, 0, 0x40, 0]); // id, DF
+    frame.extend_from_slice(&[64, 17, 0, 0]); // TTL, UDP, контрольная сумма
+    frame.extend_from_slice(&[198, 18, 0, 1]); // 198.18.0.0/15 - сеть для тестов (RFC 2544)
+    frame.extend_from_slice(&[198, 18, 0, 2]);
+    let checksum = ipv4_checksum(&frame[ip_start..]);
+    frame[ip_start + 10..ip_start + 12].copy_from_slice(&checksum.to_be_bytes());
+
+    frame.extend_from_slice(&PROBE_UDP_PORT.to_be_bytes());
+    frame.extend_from_slice(&PROBE_UDP_PORT.to_be_bytes());
+    frame.extend_from_slice(&((UDP_HDR_LEN + payload_len) as u16).to_be_bytes());
+    frame.extend_from_slice(&[0, 0]); // без контрольной суммы UDP
+
+    frame.extend_from_slice(&PROBE_MAGIC.to_le_bytes());
+    frame.resize(ETH_HDR_LEN + ip_len, 0);
+    frame
+}
+
+fn ipv4_checksum(header: &[u8]) -> u16 {
+    let mut sum: u32 = header
+        .chunks(2)
+        .map(|word| u16::from_be_bytes([word[0], *word.get(1).unwrap_or(&0)]) as u32)
+        .sum();
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
@@ -0,0 +1,406 @@
+// src/dpdk/ptp.rs
+use crate::dpdk::ffi;
+
+/// UDP порт событийных сообщений PTP (Sync, Delay_Req)
+pub const PTP_EVENT_PORT: u16 = 319;
+/// UDP порт общих сообщений PTP (Follow_Up, Delay_Resp, Announce)
+pub const PTP_GENERAL_PORT: u16 = 320;
+
+const PTP_HEADER_LEN: usize = 34;
+const PTP_TIMESTAMP_LEN: usize = 10;
+const PTP_DELAY_REQ_LEN: usize = PTP_HEADER_LEN + PTP_TIMESTAMP_LEN;
+const PTP_FLAG_TWO_STEP: u16 = 0x0200;
+
+const NS_PER_SEC: i64 = 1_000_000_000;
+
+/// Аппаратные часы порта (rte_eth_timesync_*)
+pub struct PortClock {
+    port_id: u16,
+}
+
+impl PortClock {
+    /// Включает аппаратную отметку времени PTP пакетов на порту
+    pub fn enable(port_id: u16) -> Result<Self, String> {
+        let ret = unsafe { ffi::rte_eth_timesync_enable(port_id) };
+        if ret < 0 {
+            return Err(format!(
+                "Failed to enable timesync on port {}: error code {}",
+                port_id, ret
+            ));
+        }
+
+        Ok(Self { port_id })
+    }
+
+    /// Текущее время аппаратных часов порта в наносекундах
+    pub fn read_time(&self) -> Option<i64> {
+        let mut ts = zero_timespec();
+        let ret = unsafe { ffi::rte_eth_timesync_read_time(self.port_id, &mut ts) };
+        (ret == 0).then(|| timespec_to_ns(&ts))
+    }
+
+    /// Отметка времени последнего принятого PTP пакета
+    ///
+    /// `flags` - индекс регистра отметки (timesync поле mbuf) для NIC с несколькими регистрами
+    pub fn read_rx_timestamp(&self, flags: u32) -> Option<i64> {
+        let mut ts = zero_timespec();
+        let ret = unsafe { ffi::rte_eth_timesync_read_rx_timestamp(self.port_id, &mut ts, flags) };
+        (ret == 0).then(|| timespec_to_ns(&ts))
+    }
+
+    /// Отметка времени последнего отправленного PTP пакета
+    pub fn read_tx_timestamp(&self) -> Option<i64> {
+        let mut ts = zero_timespec();
+        let ret = unsafe { ffi::rte_eth_timesync_read_tx_timestamp(self.port_id, &mut ts) };
+        (ret == 0).then(|| timespec_to_ns(&ts))
+    }
+
+    /// Сдвигает аппаратные часы порта на `delta_ns`
+    pub fn adjust_time(&self, delta_ns: i64) -> Result<(), String> {
+        let ret = unsafe { ffi::rte_eth_timesync_adjust_time(self.port_id, delta_ns) };
+        if ret < 0 {
+            return Err(format!(
+                "Failed to adjust clock on port {}: error code {}",
+                self.port_id, ret
+            ));
+        }
+        Ok(())
+    }
+
+    /// Устанавливает аппаратные часы порта
+    pub fn write_time(&self, time_ns: i64) -> Result<(), String> {
+        let ts = ns_to_timespec(time_ns);
+        let ret = unsafe { ffi::rte_eth_timesync_write_time(self.port_id, &ts) };
+        if ret < 0 {
+            return Err(format!(
+                "Failed to set clock on port {}: error code {}",
+                self.port_id, ret
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn port_id(&self) -> u16 {
+        self.port_id
+    }
+}
+
+impl Drop for PortClock {
+    fn drop(&mut self) {
+        unsafe { ffi::rte_eth_timesync_disable(self.port_id) };
+    }
+}
+
+/// Тип сообщения PTPv2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PtpMessageType {
+    Sync,
+    DelayReq,
+    FollowUp,
+    DelayResp,
+    Announce,
+    Other(u8),
+}
+
+impl From<u8> for PtpMessageType {
+    fn from(value: u8) -> Self {
+        match value & 0x0f {
+            0x0 => PtpMessageType::Sync,
+            0x1 => PtpMessageType::DelayReq,
+            0x8 => PtpMessageType::FollowUp,
+            0x9 => PtpMessageType::DelayResp,
+            0xb => PtpMessageType::Announce,
+            other => PtpMessageType::Other(other),
+        }
+    }
+}
+
+/// Разобранный заголовок PTPv2 с временной меткой тела сообщения
+#[derive(Debug, Clone, Copy)]
+pub struct PtpMessage {
+    pub message_type: PtpMessageType,
+    pub domain: u8,
+    pub flags: u16,
+    /// correctionField в наносекундах
+    pub correction_ns: i64,
+    pub source_port_identity: [u8; 10],
+    pub sequence_id: u16,
+    /// originTimestamp / preciseOriginTimestamp / receiveTimestamp
+    pub timestamp_ns: i64,
+    /// requestingPortIdentity (только для Delay_Resp)
+    pub requesting_port_identity: Option<[u8; 10]>,
+}
+
+impl PtpMessage {
+    /// Разбирает полезную нагрузку UDP пакета PTP
+    pub fn parse(payload: &[u8]) -> Option<Self> {
+        if payload.len() < PTP_HEADER_LEN + PTP_TIMESTAMP_LEN || payload[1] & 0x0f != 2 {
+            return None;
+        }
+
+        let message_type = PtpMessageType::from(payload[0]);
+        let correction = i64::from_be_bytes(payload[8..16].try_into().ok()?);
+
+        let mut source_port_identity = [0u8; 10];
+        source_port_identity.copy_from_slice(&payload[20..30]);
+
+        let requesting_port_identity = if message_type == PtpMessageType::DelayResp
+            && payload.len() >= PTP_HEADER_LEN + PTP_TIMESTAMP_LEN + 10
+        {
+            let mut identity = [0u8; 10];
+            identity.copy_from_slice(&payload[44..54]);
+            Some(identity)
+        } else {
+            None
+        };
+
+        Some(Self {
+            message_type,
+            domain: payload[4],
+            flags: u16::from_be_bytes([payload[6], payload[7]]),
+            // correctionField хранится в 2^-16 нс
+            correction_ns: correction >> 16,
+            source_port_identity,
+            sequence_id: u16::from_be_bytes([payload[30], payload[31]]),
+            timestamp_ns: read_ptp_timestamp(&payload[PTP_HEADER_LEN..]),
+            requesting_port_identity,
+        })
+    }
+}
+
+/// Состояние PTP клиента
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PtpState {
+    /// Ожидание первого Sync от мастера
+    Listening,
+    /// Получен Sync в двухшаговом режиме, ожидается Follow_Up
+    AwaitingFollowUp,
+    /// Ожидается отправка Delay_Req
+    DelayReqPending,
+    /// Delay_Req отправлен, ожидается Delay_Resp
+    AwaitingDelayResp,
+    /// Часы синхронизированы, ожидается следующий Sync
+    Synchronized,
+}
+
+/// Результат одного цикла обмена Sync / Delay_Req
+#[derive(Debug, Clone, Copy)]
+pub struct PtpMeasurement {
+    /// Смещение локальных часов относительно мастера (local - master)
+    pub offset_ns: i64,
+    /// Односторонняя задержка пути
+    pub path_delay_ns: i64,
+}
+
+/// PTP клиент (ordinary clock, slave-only, end-to-end delay mechanism)
+///
+/// Не отправляет пакеты сам: вызывающий код (служебное ядро) передает ему
+/// принятые сообщения с аппаратными отметками, отправляет сформированный
+/// Delay_Req и сообщает отметку времени его отправки.
+pub struct PtpClient {
+    domain: u8,
+    clock_identity: [u8; 10],
+    state: PtpState,
+    master: Option<[u8; 10]>,
+    /// t1 - время отправки Sync мастером, t2 - время приема Sync
+    t1: i64,
+    t2: i64,
+    sync_sequence: u16,
+    /// t3 - время отправки Delay_Req
+    t3: i64,
+    delay_req_sequence: u16,
+    /// Сглаженная оценка смещения и задержки
+    offset_ns: i64,
+    path_delay_ns: i64,
+    samples: u64,
+}
+
+impl PtpClient {
+    /// Создает клиента для домена PTP с указанной идентичностью порта
+    /// (EUI-64 + номер порта)
+    pub fn new(domain: u8, clock_identity: [u8; 10]) -> Self {
+        Self {
+            domain,
+            clock_identity,
+            state: PtpState::Listening,
+            master: None,
+            t1: 0,
+            t2: 0,
+            sync_sequence: 0,
+            t3: 0,
+            delay_req_sequence: 0,
+            offset_ns: 0,
+            path_delay_ns: 0,
+            samples: 0,
+        }
+    }
+
+    pub fn state(&self) -> PtpState {
+        self.state
+    }
+
+    /// Текущая оценка смещения локальных часов относительно мастера
+    pub fn offset_ns(&self) -> i64 {
+        self.offset_ns
+    }
+
+    pub fn path_delay_ns(&self) -> i64 {
+        self.path_delay_ns
+    }
+
+    /// Количество завершенных измерений
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// Переводит аппаратную отметку NIC во время мастера (wall-clock)
+    #[inline(always)]
+    pub fn to_master_time(&self, hw_timestamp_ns: i64) -> i64 {
+        hw_timestamp_ns - self.offset_ns
+    }
+
+    /// Обрабатывает принятое сообщение PTP с аппаратной отметкой приема
+    ///
+    /// Возвращает измерение, когда цикл обмена завершен.
+    pub fn on_message(&mut self, msg: &PtpMessage, rx_timestamp_ns: i64) -> Option<PtpMeasurement> {
+        if msg.domain != self.domain {
+            return None;
+        }
+
+        // Следуем за первым увиденным мастером (без BMCA)
+        let master = *self.master.get_or_insert(msg.source_port_identity);
+        if msg.message_type != PtpMessageType::DelayReq && msg.source_port_identity != master {
+            return None;
+        }
+
+        match msg.message_type {
+            PtpMessageType::Sync => {
+                self.t2 = rx_timestamp_ns;
+                self.sync_sequence = msg.sequence_id;
+
+                if msg.flags & PTP_FLAG_TWO_STEP != 0 {
+                    self.state = PtpState::AwaitingFollowUp;
+                } else {
+                    self.t1 = msg.timestamp_ns + msg.correction_ns;
+                    self.state = PtpState::DelayReqPending;
+                }
+                None
+            }
+            PtpMessageType::FollowUp => {
+                if self.state == PtpState::AwaitingFollowUp && msg.sequence_id == self.sync_sequence
+                {
+                    self.t1 = msg.timestamp_ns + msg.correction_ns;
+                    self.state = PtpState::DelayReqPending;
+                }
+                None
+            }
+            PtpMessageType::DelayResp => {
+                if self.state != PtpState::AwaitingDelayResp
+                    || msg.sequence_id != self.delay_req_sequence
+                    || msg.requesting_port_identity != Some(self.clock_identity)
+                {
+                    return None;
+                }
+
+                let t4 = msg.timestamp_ns - msg.correction_ns;
+                Some(self.complete_exchange(t4))
+            }
+            _ => None,
+        }
+    }
+
+    /// Формирует Delay_Req, если он ожидает отправки
+    pub fn poll_delay_req(&mut self) -> Option<[u8; PTP_DELAY_REQ_LEN]> {
+        if self.state != PtpState::DelayReqPending {
+            return None;
+        }
+
+        self.delay_req_sequence = self.delay_req_sequence.wrapping_add(1);
+
+        let mut msg = [0u8; PTP_DELAY_REQ_LEN];
+        msg[0] = 0x01; // Delay_Req
+        msg[1] = 0x02; // PTPv2
+        msg[2..4].copy_from_slice(&(PTP_DELAY_REQ_LEN as u16).to_be_bytes());
+        msg[4] = self.domain;
+        msg[20..30].copy_from_slice(&self.clock_identity);
+        msg[30..32].copy_from_slice(&self.delay_req_sequence.to_be_bytes());
+        msg[32] = 0x01; // controlField: Delay_Req
+        msg[33] = 0x7f; // logMessageInterval
+
+        Some(msg)
+    }
+
+    /// Сообщает аппаратную отметку отправки Delay_Req
+    pub fn on_delay_req_sent(&mut self, tx_timestamp_ns: i64) {
+        if self.state == PtpState::DelayReqPending {
+            self.t3 = tx_timestamp_ns;
+            self.state = PtpState::AwaitingDelayResp;
+        }
+    }
+
+    /// Вычисляет смещение и задержку по четырем отметкам
+    fn complete_exchange(&mut self, t4: i64) -> PtpMeasurement {
+        let master_to_slave = self.t2 - self.t1;
+        let slave_to_master = t4 - self.t3;
+
+        let measurement = PtpMeasurement {
+            offset_ns: (master_to_slave - slave_to_master) / 2,
+            path_delay_ns: (master_to_slave + slave_to_master) / 2,
+        };
+
+        // Экспоненциальное сглаживание (1/8) после первого измерения
+        if self.samples == 0 {
+            self.offset_ns = measurement.offset_ns;
+            self.path_delay_ns = measurement.path_delay_ns;
+        } else {
+            self.offset_ns += (measurement.offset_ns - self.offset_ns) / 8;
+            self.path_delay_ns += (measurement.path_delay_ns - self.path_delay_ns) / 8;
+        }
+
+        self.samples += 1;
+        self.state = PtpState::Synchronized;
+        measurement
+    }
+
+    /// Применяет текущую оценку смещения к аппаратным часам порта и
+    /// сбрасывает ее (после коррекции часы NIC показывают время мастера)
+    pub fn discipline(&mut self, clock: &PortClock) -> Result<(), String> {
+        if self.samples == 0 || self.offset_ns == 0 {
+            return Ok(());
+        }
+
+        clock.adjust_time(-self.offset_ns)?;
+        self.offset_ns = 0;
+        Ok(())
+    }
+}
+
+/// Читает 10-байтовую метку PTP (48 бит секунд + 32 бита наносекунд)
+fn read_ptp_timestamp(data: &[u8]) -> i64 {
+    let mut seconds: i64 = 0;
+    for &b in &data[0..6] {
+        seconds = (seconds << 8) | b as i64;
+    }
+    let nanos = u32::from_be_bytes([data[6], data[7], data[8], data[9]]) as i64;
+    seconds * NS_PER_SEC + nanos
+}
+
+fn zero_timespec() -> libc::timespec {
+    libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    }
+}
+
+// time_t и c_long совпадают с i64 только на 64-битных платформах
+#[allow(clippy::unnecessary_cast)]
+fn timespec_to_ns(ts: &libc::timespec) -> i64 {
+    ts.tv_sec as i64 * NS_PER_SEC + ts.tv_nsec as i64
+}
+
+fn ns_to_timespec(ns: i64) -> libc::timespec {
+    libc::timespec {
+        tv_sec: ns.div_euclid(NS_PER_SEC) as libc::time_t,
+        tv_nsec: ns.rem_euclid(NS_PER_SEC) as libc::c_long,
+    }
+}
@@ -0,0 +1,118 @@
+// src/dpdk/reta.rs
+use std::fmt;
+
+use crate::dpdk::ffi;
+
+/// Максимальный размер таблицы, поддерживаемый нативными функциями
+pub const MAX_RETA_SIZE: u16 = 512;
+
+/// Таблица перенаправления RSS (RETA): корзина хэша -> очередь RX
+///
+/// По умолчанию PMD распределяет корзины между очередями поровну. Весами
+/// можно сместить распределение, например, убрать очередь 0 из RSS, чтобы
+/// она обслуживала только горячий фид, направленный правилом Flow Director.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetaTable {
+    entries: Vec<u16>,
+}
+
+impl RetaTable {
+    /// Распределяет `size` корзин пропорционально весам очередей (`weights[q]`)
+    ///
+    /// Нулевой вес исключает очередь из RSS. Корзины чередуются (плавный
+    /// взвешенный round-robin), чтобы соседние значения хэша попадали в разные
+    /// очереди.
+    pub fn weighted(size: u16, weights: &[u32]) -> Result<Self, String> {
+        if size == 0 || size > MAX_RETA_SIZE {
+            return Err(format!("Unsupported RETA size {}", size));
+        }
+        let total: u64 = weights.iter().map(|&w| w as u64).sum();
+        if total == 0 {
+            return Err("RETA weights must include at least one non-zero queue".to_string());
+        }
+
+        let mut current = vec![0i64; weights.len()];
+        let entries = (0..size)
+            .map(|_| {
+                for (credit, &weight) in current.iter_mut().zip(weights) {
+                    *credit += weight as i64;
+                }
+                let (queue, _) = current
+                    .iter()
+                    .enumerate()
+                    .filter(|&(queue, _)| weights[queue] > 0)
+                    .max_by_key(|&(queue, &credit)| (credit, std::cmp::Reverse(queue)))
+                    .unwrap_or((0, &0));
+                current[queue] -= total as i64;
+                queue as u16
+            })
+            .collect();
+
+        Ok(Self { entries })
+    }
+
+    /// Читает текущую таблицу порта
+    pub fn query(port_id: u16, size: u16) -> Result<Self, String> {
+        if size == 0 || size > MAX_RETA_SIZE {
+            return Err(format!(
+                "Port {} reports unsupported RETA size {}",
+                port_id, size
+            ));
+        }
+
+        let mut entries = vec![0u16; size as usize];
+        let ret = unsafe { ffi::dpdk_rss_reta_query(port_id, entries.as_mut_ptr(), size) };
+        if ret != 0 {
+            return Err(format!(
+                "Failed to query RETA on port {}: error code {}",
+                port_id, ret
+            ));
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Записывает таблицу в NIC; применяется к следующим пакетам без остановки порта
+    pub fn apply(&self, port_id: u16) -> Result<(), String> {
+        let ret = unsafe {
+            ffi::dpdk_rss_reta_update(port_id, self.entries.as_ptr(), self.entries.len() as u16)
+        };
+        if ret != 0 {
+            return Err(format!(
+                "Failed to update RETA on port {}: error code {}",
+                port_id, ret
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Очередь каждой корзины
+    pub fn entries(&self) -> &[u16] {
+        &self.entries
+    }
+
+    /// Наибольший номер очереди в таблице
+    pub fn max_queue(&self) -> u16 {
+        self.entries.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Количество корзин каждой очереди
+    pub fn queue_shares(&self) -> Vec<usize> {
+        let mut shares = vec![0; self.max_queue() as usize + 1];
+        for &queue in &self.entries {
+            shares[queue as usize] += 1;
+        }
+        shares
+    }
+}
+
+impl fmt::Display for RetaTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} buckets:", self.entries.len())?;
+        for (queue, share) in self.queue_shares().iter().enumerate() {
+            write!(f, " q{}={}", queue, share)?;
+        }
+        Ok(())
+    }
+}
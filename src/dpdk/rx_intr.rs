@@ -0,0 +1,65 @@
+// src/dpdk/rx_intr.rs
+use std::marker::PhantomData;
+
+use crate::dpdk::ffi;
+
+/// Прерывание очереди RX, зарегистрированное в epoll рабочего потока
+///
+/// DPDK хранит epoll для каждого потока (RTE_EPOLL_PER_THREAD), поэтому
+/// объект создается, используется и уничтожается в потоке очереди и не
+/// передается между потоками. Порт должен быть настроен с
+/// `intr_conf.rxq = 1`, а PMD - поддерживать прерывания RX (vfio-pci с
+/// MSI-X и т.п.).
+pub struct RxInterrupt {
+    port_id: u16,
+    queue_id: u16,
+    /// Регистрация принадлежит epoll потока: объект не Send
+    _thread: PhantomData<*const ()>,
+}
+
+impl RxInterrupt {
+    /// Регистрирует прерывание очереди в epoll текущего потока
+    pub fn register(port_id: u16, queue_id: u16) -> Result<Self, String> {
+        let ret = unsafe { ffi::dpdk_rx_intr_setup(port_id, queue_id) };
+        if ret < 0 {
+            return Err(format!(
+                "Failed to register RX interrupt for port {} queue {}: error code {}",
+                port_id, queue_id, ret
+            ));
+        }
+        Ok(Self {
+            port_id,
+            queue_id,
+            _thread: PhantomData,
+        })
+    }
+
+    /// Спит до прихода пакета в очередь или истечения `timeout_ms`
+    ///
+    /// Пакет, пришедший между последним опросом и взведением прерывания,
+    /// прерывания не вызывает и ждет тайм-аута, поэтому `timeout_ms`
+    /// ограничивает задержку в худшем случае. Err - прерывание взвести не
+    /// удалось.
+    pub fn wait(&self, timeout_ms: u32) -> Result<bool, String> {
+        let ret = unsafe {
+            ffi::dpdk_rx_intr_wait(
+                self.port_id,
+                self.queue_id,
+                timeout_ms.min(i32::MAX as u32) as i32,
+            )
+        };
+        if ret < 0 {
+            return Err(format!(
+                "RX interrupt wait failed for port {} queue {}: error code {}",
+                self.port_id, self.queue_id, ret
+            ));
+        }
+        Ok(ret > 0)
+    }
+}
+
+impl Drop for RxInterrupt {
+    fn drop(&mut self) {
+        unsafe { ffi::dpdk_rx_intr_teardown(self.port_id, self.queue_id) };
+    }
+}
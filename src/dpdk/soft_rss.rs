@@ -0,0 +1,230 @@
+// src/dpdk/soft_rss.rs
+use core_affinity::CoreId;
+use crossbeam::queue::ArrayQueue;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use crate::dpdk::ffi::{self, RteMbuf};
+use crate::log_info;
+use crate::numa::node::{WorkerSettings, MAX_BURST_SIZE};
+use crate::packet::parser::parse_chained;
+
+/// Емкость кольца одного рабочего потока (в mbuf)
+pub const SOFT_RSS_RING_SIZE: usize = 4096;
+
+/// Указатель на mbuf, передаваемый между потоками через кольцо
+#[derive(Clone, Copy)]
+struct MbufPtr(*mut RteMbuf);
+
+// mbuf принадлежит тому потоку, который извлек его из кольца
+unsafe impl Send for MbufPtr {}
+
+/// Кольцо mbuf от распределителя к одному рабочему потоку
+pub struct MbufRing {
+    queue: ArrayQueue<MbufPtr>,
+}
+
+impl MbufRing {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            queue: ArrayQueue::new(capacity.max(1)),
+        }
+    }
+
+    /// Извлекает до `rx_pkts.len()` mbuf; аналог rte_eth_rx_burst для рабочего потока
+    #[inline(always)]
+    pub fn pop_burst(&self, rx_pkts: &mut [*mut RteMbuf]) -> u16 {
+        let mut count = 0;
+        for slot in rx_pkts.iter_mut() {
+            match self.queue.pop() {
+                Some(MbufPtr(mbuf)) => {
+                    *slot = mbuf;
+                    count += 1;
+                }
+                None => break,
+            }
+        }
+        count
+    }
+
+    /// Число mbuf в кольце
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    #[inline(always)]
+    pub(crate) fn push(&self, mbuf: *mut RteMbuf) -> bool {
+        self.queue.push(MbufPtr(mbuf)).is_ok()
+    }
+}
+
+impl Drop for MbufRing {
+    fn drop(&mut self) {
+        while let Some(MbufPtr(mbuf)) = self.queue.pop() {
+            unsafe { ffi::dpdk_mbuf_free(mbuf) };
+        }
+    }
+}
+
+/// Хэш 5-кортежа кадра в mbuf (`chained_mbufs` - порт принимает цепочки mbuf)
+#[inline(always)]
+pub fn mbuf_flow_hash(
+    pkt: *mut RteMbuf,
+    chained_mbufs: bool,
+    extract_flags: u32,
+    vxlan_port: u16,
+) -> u32 {
+    let frame = unsafe {
+        let data = ffi::dpdk_mbuf_mtod(pkt) as *const u8;
+        let len = ffi::dpdk_mbuf_data_len(pkt) as usize;
+        std::slice::from_raw_parts(data, len)
+    };
+    let pkt_len = if chained_mbufs {
+        unsafe { ffi::dpdk_mbuf_pkt_len(pkt) as usize }
+    } else {
+        frame.len()
+    };
+
+    flow_hash(frame, pkt_len, extract_flags, vxlan_port)
+}
+
+/// Хэш 5-кортежа кадра; кадры, которые не удалось разобрать, получают 0
+///
+/// `frame` - первый сегмент mbuf, `pkt_len` - длина всего кадра.
+#[inline(always)]
+pub fn flow_hash(frame: &[u8], pkt_len: usize, extract_flags: u32, vxlan_port: u16) -> u32 {
+    let Ok(parsed) = parse_chained(frame, pkt_len, extract_flags, vxlan_port) else {
+        return 0;
+    };
+
+    let ip = |offset: usize| {
+        u32::from_be_bytes([
+            frame[offset],
+            frame[offset + 1],
+            frame[offset + 2],
+            frame[offset + 3],
+        ])
+    };
+
+    let mut hash = (ip(parsed.src_ip_offset()) as u64) << 32 | ip(parsed.dst_ip_offset()) as u64;
+    hash ^=
+        ((parsed.src_port as u64) << 24 | (parsed.dst_port as u64) << 8 | parsed.protocol as u64)
+            .rotate_left(17);
+    hash = hash.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    (hash >> 32) as u32
+}
+
+/// Программное распределение потоков для NIC без многоочередного RSS
+///
+/// Один поток опрашивает единственную аппаратную очередь, хэширует 5-кортеж
+/// каждого кадра и передает mbuf в кольцо рабочего потока. Пакеты одного
+/// потока всегда попадают к одному рабочему, поэтому модель "очередь - ядро"
+/// сохраняется и на слабых NIC (одноочередной virtio, часть VF).
+pub struct SoftRssDistributor {
+    pub port_id: u16,
+    pub core_id: CoreId,
+    stop: Arc<AtomicBool>,
+    dropped: Arc<AtomicU64>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SoftRssDistributor {
+    /// Запускает распределитель аппаратной очереди `queue_id` порта на ядре `core_id`
+    ///
+    /// Поток работает, пока установлен `running` узла и не вызван `stop`.
+    pub fn start(
+        port_id: u16,
+        queue_id: u16,
+        core_id: CoreId,
+        rings: Vec<Arc<MbufRing>>,
+        running: Arc<AtomicBool>,
+        settings: &WorkerSettings,
+    ) -> Result<Self, String> {
+        if rings.is_empty() {
+            return Err(format!(
+                "Port {}: no worker rings to distribute to",
+                port_id
+            ));
+        }
+
+        let burst_size = settings.burst_size.clamp(1, MAX_BURST_SIZE as u32) as u16;
+        let extract_flags = settings.extract_flags;
+        let vxlan_port = settings.vxlan_port;
+        let chained_mbufs = settings.chained_mbufs;
+        let stop = Arc::new(AtomicBool::new(false));
+        let dropped = Arc::new(AtomicU64::new(0));
+        let thread_stop = stop.clone();
+        let thread_dropped = dropped.clone();
+
+        let thread = thread::Builder::new()
+            .name(format!("hfeec-rss-p{}", port_id))
+            .spawn(move || {
+                core_affinity::set_for_current(core_id);
+                log_info!(
+                    "worker",
+                    "Software RSS distributor started",
+                    port = port_id,
+                    queue = queue_id,
+                    core = core_id.id,
+                    workers = rings.len()
+                );
+
+                let mut rx_pkts = vec![std::ptr::null_mut(); burst_size as usize];
+                while running.load(Ordering::SeqCst) && !thread_stop.load(Ordering::Relaxed) {
+                    let nb_rx = unsafe {
+                        ffi::rte_eth_rx_burst(port_id, queue_id, rx_pkts.as_mut_ptr(), burst_size)
+                    };
+
+                    for &pkt in &rx_pkts[..nb_rx as usize] {
+                        let hash = mbuf_flow_hash(pkt, chained_mbufs, extract_flags, vxlan_port);
+                        let ring = &rings[hash as usize % rings.len()];
+                        if !ring.push(pkt) {
+                            thread_dropped.fetch_add(1, Ordering::Relaxed);
+                            unsafe { ffi::dpdk_mbuf_free(pkt) };
+                        }
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to spawn software RSS distributor: {}", e))?;
+
+        Ok(Self {
+            port_id,
+            core_id,
+            stop,
+            dropped,
+            thread: Some(thread),
+        })
+    }
+
+    /// Кадров, отброшенных из-за переполнения колец рабочих потоков
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Останавливает распределитель
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+            log_info!(
+                "worker",
+                "Software RSS distributor stopped",
+                port = self.port_id,
+                dropped = self.dropped()
+            );
+        }
+    }
+}
+
+impl Drop for SoftRssDistributor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
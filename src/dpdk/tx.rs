@@ -0,0 +1,504 @@
+// src/dpdk/tx.rs
+use std::collections::VecDeque;
+use std::os::raw::c_int;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::dpdk::config::PortSettings;
+use crate::dpdk::ffi::{self, RteMbuf, RteMempool};
+use crate::dpdk::ptp::PortClock;
+use crate::packet::gso;
+use crate::time;
+use crate::{log_info, log_warn};
+
+const IPPROTO_UDP: u8 = 17;
+/// Данных в сегменте по умолчанию (MSS при MTU 1500)
+const DEFAULT_SEGMENT_SIZE: u16 = 1460;
+
+/// Подтверждение отправки сообщения
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TxCompletion {
+    /// Идентификатор сообщения приложения (см. `TxQueue::send_tracked`)
+    pub message_id: u64,
+    /// Время передачи в очередь TX (`time::now_ns`)
+    pub submitted_ns: u64,
+    /// Время обнаружения завершения отправки (`time::now_ns`)
+    pub completed_ns: u64,
+    /// Аппаратная отметка отправки (часы NIC), если NIC ее поставил
+    pub hw_egress_ns: Option<i64>,
+}
+
+/// Сообщение, ожидающее завершения отправки
+struct PendingTx {
+    mbuf: *mut RteMbuf,
+    message_id: u64,
+    submitted_ns: u64,
+    /// Для сообщения запрошена аппаратная отметка отправки
+    hw_stamped: bool,
+}
+
+/// Отслеживание завершения отправки (см. `TxQueue::with_completions`)
+struct CompletionTracker {
+    pending: VecDeque<PendingTx>,
+    capacity: usize,
+    stuck_after_ns: u64,
+    /// Часы порта для аппаратных отметок отправки
+    egress_clock: Option<Arc<PortClock>>,
+    /// Ожидает завершения сообщение с запрошенной аппаратной отметкой
+    stamp_in_flight: bool,
+    stuck: bool,
+    stuck_events: u64,
+}
+
+/// Очередь отправки порта
+///
+/// Кадр копируется в mbuf из пула порта и передается rte_eth_tx_burst.
+/// Очередь TX не потокобезопасна: каждой очередью владеет один поток.
+pub struct TxQueue {
+    port_id: u16,
+    queue_id: u16,
+    pool: *mut RteMempool,
+    /// Поле времени отправки зарегистрировано (см. `with_launch_time`)
+    launch_time: bool,
+    /// Сдвиг часов NIC относительно `time::now_ns` (см. `calibrate_clock`)
+    clock_offset_ns: i64,
+    /// Данных в сегменте при сегментации больших кадров (`send_segmented`)
+    segment_size: u16,
+    /// Сегментацию TCP и UDP выполняет NIC (TSO и UDP GSO)
+    hw_tcp_segmentation: bool,
+    hw_udp_segmentation: bool,
+    /// Отслеживание завершения отправки (None - не включено)
+    completions: Option<CompletionTracker>,
+    sent: u64,
+    refused: u64,
+}
+
+// Пул и очередь используются только потоком-владельцем
+unsafe impl Send for TxQueue {}
+
+impl TxQueue {
+    pub fn new(port_id: u16, queue_id: u16, pool: *mut RteMempool) -> Result<Self, String> {
+        if pool.is_null() {
+            return Err(format!(
+                "TX queue {} of port {} requires an mbuf pool",
+                queue_id, port_id
+            ));
+        }
+
+        Ok(Self {
+            port_id,
+            queue_id,
+            pool,
+            launch_time: false,
+            clock_offset_ns: 0,
+            segment_size: DEFAULT_SEGMENT_SIZE,
+            hw_tcp_segmentation: false,
+            hw_udp_segmentation: false,
+            completions: None,
+            sent: 0,
+            refused: 0,
+        })
+    }
+
+    /// Включает отправку по времени (`send_at`) и калибрует часы NIC
+    ///
+    /// Порт должен быть настроен с `DpdkConfig::with_tx_launch_time` и не
+    /// отключить его при проверке возможностей (`PortSettings::use_tx_launch_time`):
+    /// иначе NIC игнорирует время и отправляет пакет сразу.
+    pub fn with_launch_time(mut self) -> Result<Self, String> {
+        let ret = unsafe { ffi::dpdk_tx_timestamp_register() };
+        if ret < 0 {
+            return Err(format!(
+                "Failed to register TX timestamp field for port {}: error code {}",
+                self.port_id, ret
+            ));
+        }
+        self.launch_time = true;
+        self.calibrate_clock()?;
+        Ok(self)
+    }
+
+    /// Настраивает сегментацию больших кадров (`send_segmented`)
+    ///
+    /// Кадр делит NIC, если TSO (UDP TSO) остался включенным после проверки
+    /// возможностей порта, иначе кадр делится программно (`gso::segment`).
+    /// `segment_size` - данных в сегменте (обычно `DpdkConfig::max_tso_segment_size`).
+    pub fn with_segmentation(mut self, settings: &PortSettings, segment_size: u16) -> Self {
+        self.segment_size = segment_size.max(1);
+        self.hw_tcp_segmentation = settings.use_tso;
+        self.hw_udp_segmentation = settings.use_udp_tso;
+        self
+    }
+
+    /// Включает подтверждения отправки сообщений (`send_tracked`, `poll_completions`)
+    ///
+    /// Отправленный mbuf удерживается лишней ссылкой: NIC освобождает свою
+    /// ссылку после отправки, и сообщение считается отправленным. Кольцо TX
+    /// освобождается по порядку, поэтому проверяется только самое старое
+    /// сообщение. Если оно ждет дольше `stuck_after`, очередь считается
+    /// зависшей. `capacity` - наибольшее число неподтвержденных сообщений.
+    pub fn with_completions(mut self, capacity: usize, stuck_after: Duration) -> Self {
+        self.completions = Some(CompletionTracker {
+            pending: VecDeque::with_capacity(capacity.max(1)),
+            capacity: capacity.max(1),
+            stuck_after_ns: stuck_after.as_nanos() as u64,
+            egress_clock: None,
+            stamp_in_flight: false,
+            stuck: false,
+            stuck_events: 0,
+        });
+        self
+    }
+
+    /// Запрашивает аппаратные отметки отправки отслеживаемых сообщений
+    ///
+    /// NIC хранит одну отметку (регистр IEEE 1588), поэтому отметка
+    /// запрашивается, только если не ждет завершения другое сообщение с
+    /// отметкой; остальные подтверждения приходят без `hw_egress_ns`.
+    pub fn with_egress_timestamps(mut self, clock: Arc<PortClock>) -> Result<Self, String> {
+        let Some(tracker) = self.completions.as_mut() else {
+            return Err(format!(
+                "TX completions are not enabled on port {} queue {}",
+                self.port_id, self.queue_id
+            ));
+        };
+        if clock.port_id() != self.port_id {
+            return Err(format!(
+                "Clock of port {} cannot stamp TX on port {}",
+                clock.port_id(),
+                self.port_id
+            ));
+        }
+        tracker.egress_clock = Some(clock);
+        Ok(self)
+    }
+
+    pub fn port_id(&self) -> u16 {
+        self.port_id
+    }
+
+    pub fn queue_id(&self) -> u16 {
+        self.queue_id
+    }
+
+    /// Текущее время часов NIC (rte_eth_read_clock)
+    pub fn device_clock(&self) -> Option<u64> {
+        let mut clock = 0;
+        let ret = unsafe { ffi::rte_eth_read_clock(self.port_id, &mut clock) };
+        (ret == 0).then_some(clock)
+    }
+
+    /// Измеряет сдвиг часов NIC относительно `time::now_ns`
+    ///
+    /// Часы NIC считаются идущими в наносекундах (i210/i225, mlx5 в режиме
+    /// real-time clock). Вызывается периодически, если часы NIC
+    /// подстраиваются PTP.
+    pub fn calibrate_clock(&mut self) -> Result<(), String> {
+        let before = time::now_ns();
+        let clock = self.device_clock().ok_or_else(|| {
+            format!(
+                "Port {} does not support reading the device clock",
+                self.port_id
+            )
+        })?;
+        let after = time::now_ns();
+
+        // Чтение часов NIC отнесено к середине интервала чтения
+        let host = before + (after - before) / 2;
+        self.clock_offset_ns = clock as i64 - host as i64;
+        Ok(())
+    }
+
+    /// Отправляет кадр немедленно
+    ///
+    /// Ok(false) - очередь TX заполнена, кадр не отправлен.
+    #[inline]
+    pub fn send(&mut self, frame: &[u8]) -> Result<bool, String> {
+        self.transmit(frame, None, None)
+    }
+
+    /// Отправляет кадр в момент `launch_time` по часам NIC
+    ///
+    /// NIC держит пакет в очереди до наступления времени, поэтому пакеты
+    /// одной очереди должны идти в порядке возрастания времени отправки.
+    #[inline]
+    pub fn send_at(&mut self, frame: &[u8], launch_time: u64) -> Result<bool, String> {
+        if !self.launch_time {
+            return Err(format!(
+                "TX launch time is not enabled on port {} queue {}",
+                self.port_id, self.queue_id
+            ));
+        }
+        self.transmit(frame, Some(launch_time), None)
+    }
+
+    /// Отправляет кадр в момент `deadline_ns` по часам хоста (`time::now_ns`)
+    #[inline]
+    pub fn send_at_host_time(&mut self, frame: &[u8], deadline_ns: u64) -> Result<bool, String> {
+        let launch_time = deadline_ns.saturating_add_signed(self.clock_offset_ns);
+        self.send_at(frame, launch_time)
+    }
+
+    /// Отправляет кадр TCP или UDP с данными больше сегмента (запросы
+    /// восстановления, снимки)
+    ///
+    /// Возвращает число сегментов, принятых в очередь TX: при аппаратной
+    /// сегментации кадр принимается целиком или не принимается, при
+    /// программной отправка останавливается на первом непринятом сегменте.
+    /// Кадр с данными не больше сегмента отправляется как есть.
+    pub fn send_segmented(&mut self, frame: &[u8]) -> Result<usize, String> {
+        let parsed = gso::parse_segmentable(frame)?;
+        let segment_size = self.segment_size as usize;
+        if parsed.payload_len <= segment_size {
+            return self.send(frame).map(usize::from);
+        }
+
+        let udp = parsed.protocol == IPPROTO_UDP;
+        let hardware = match udp {
+            true => self.hw_udp_segmentation,
+            false => self.hw_tcp_segmentation,
+        };
+        if !hardware {
+            return gso::segment(frame, segment_size, |segment| self.send(segment));
+        }
+
+        let l3_len = ((frame[parsed.l3_offset] & 0x0f) as usize) * 4;
+        let l4_len = parsed.payload_offset - parsed.l3_offset - l3_len;
+        let frame_len = parsed.payload_offset + parsed.payload_len;
+
+        let mut mbuf =
+            unsafe { ffi::dpdk_mbuf_from_buffer(self.pool, frame.as_ptr(), frame_len as u32) };
+        if mbuf.is_null() {
+            return Err(format!(
+                "Failed to allocate TX mbufs for port {}",
+                self.port_id
+            ));
+        }
+
+        unsafe {
+            let ret = ffi::dpdk_mbuf_set_tso(
+                mbuf,
+                parsed.l3_offset as u16,
+                l3_len as u16,
+                l4_len as u16,
+                self.segment_size,
+                c_int::from(udp),
+            );
+            if ret < 0 {
+                ffi::rte_pktmbuf_free(mbuf);
+                return Err(format!(
+                    "Failed to set segmentation offload: error code {}",
+                    ret
+                ));
+            }
+
+            if ffi::rte_eth_tx_burst(self.port_id, self.queue_id, &mut mbuf, 1) == 0 {
+                ffi::rte_pktmbuf_free(mbuf);
+                self.refused += 1;
+                return Ok(0);
+            }
+        }
+
+        self.sent += 1;
+        Ok(parsed.payload_len.div_ceil(segment_size))
+    }
+
+    /// Отправляет кадр немедленно с подтверждением (см. `poll_completions`)
+    ///
+    /// Ok(false) - очередь TX заполнена, кадр не отправлен и подтверждения не будет.
+    #[inline]
+    pub fn send_tracked(&mut self, frame: &[u8], message_id: u64) -> Result<bool, String> {
+        match &self.completions {
+            Some(tracker) if tracker.pending.len() >= tracker.capacity => Err(format!(
+                "Too many unconfirmed messages on port {} queue {}",
+                self.port_id, self.queue_id
+            )),
+            Some(_) => self.transmit(frame, None, Some(message_id)),
+            None => Err(format!(
+                "TX completions are not enabled on port {} queue {}",
+                self.port_id, self.queue_id
+            )),
+        }
+    }
+
+    /// Передает `on_done` подтверждения отправленных сообщений
+    ///
+    /// Просит PMD освободить отправленные дескрипторы (rte_eth_tx_done_cleanup;
+    /// без поддержки в PMD подтверждения приходят по мере освобождения кольца
+    /// при следующих отправках) и проверяет, не зависла ли очередь.
+    /// Возвращает число подтверждений.
+    pub fn poll_completions(&mut self, mut on_done: impl FnMut(TxCompletion)) -> usize {
+        let Some(tracker) = self.completions.as_mut() else {
+            return 0;
+        };
+        if tracker.pending.is_empty() {
+            return 0;
+        }
+
+        unsafe { ffi::rte_eth_tx_done_cleanup(self.port_id, self.queue_id, 0) };
+        let now = time::now_ns();
+
+        let mut completed = 0;
+        while let Some(front) = tracker.pending.front() {
+            // Осталась только ссылка очереди: NIC отправил кадр
+            if unsafe { ffi::dpdk_mbuf_refcnt(front.mbuf) } > 1 {
+                break;
+            }
+            let Some(done) = tracker.pending.pop_front() else {
+                break;
+            };
+
+            let mut hw_egress_ns = None;
+            if done.hw_stamped {
+                tracker.stamp_in_flight = false;
+                hw_egress_ns = tracker
+                    .egress_clock
+                    .as_ref()
+                    .and_then(|clock| clock.read_tx_timestamp());
+            }
+            unsafe { ffi::rte_pktmbuf_free(done.mbuf) };
+
+            on_done(TxCompletion {
+                message_id: done.message_id,
+                submitted_ns: done.submitted_ns,
+                completed_ns: now,
+                hw_egress_ns,
+            });
+            completed += 1;
+        }
+
+        let oldest_age_ns = tracker
+            .pending
+            .front()
+            .map_or(0, |oldest| now.saturating_sub(oldest.submitted_ns));
+        if oldest_age_ns > tracker.stuck_after_ns {
+            if !tracker.stuck {
+                tracker.stuck = true;
+                tracker.stuck_events += 1;
+                log_warn!(
+                    "tx",
+                    "TX queue stuck",
+                    port = self.port_id,
+                    queue = self.queue_id,
+                    pending = tracker.pending.len(),
+                    oldest_age_us = oldest_age_ns / 1000
+                );
+            }
+        } else if tracker.stuck {
+            tracker.stuck = false;
+            log_info!(
+                "tx",
+                "TX queue recovered",
+                port = self.port_id,
+                queue = self.queue_id
+            );
+        }
+
+        completed
+    }
+
+    /// Самое старое неподтвержденное сообщение ждет дольше `stuck_after`
+    pub fn is_stuck(&self) -> bool {
+        self.completions
+            .as_ref()
+            .is_some_and(|tracker| tracker.stuck)
+    }
+
+    /// Сколько раз очередь признавалась зависшей
+    pub fn stuck_events(&self) -> u64 {
+        self.completions
+            .as_ref()
+            .map_or(0, |tracker| tracker.stuck_events)
+    }
+
+    /// Сообщения, ожидающие подтверждения
+    pub fn pending_completions(&self) -> usize {
+        self.completions
+            .as_ref()
+            .map_or(0, |tracker| tracker.pending.len())
+    }
+
+    fn transmit(
+        &mut self,
+        frame: &[u8],
+        launch_time: Option<u64>,
+        message_id: Option<u64>,
+    ) -> Result<bool, String> {
+        let too_long = || format!("Frame of {} bytes does not fit into TX mbuf", frame.len());
+        let len = u16::try_from(frame.len()).map_err(|_| too_long())?;
+
+        let mut mbuf: *mut RteMbuf = unsafe { ffi::dpdk_mbuf_alloc(self.pool) };
+        if mbuf.is_null() {
+            return Err(format!(
+                "Failed to allocate TX mbuf for port {}",
+                self.port_id
+            ));
+        }
+
+        unsafe {
+            let data = ffi::dpdk_mbuf_append(mbuf, len) as *mut u8;
+            if data.is_null() {
+                ffi::rte_pktmbuf_free(mbuf);
+                return Err(too_long());
+            }
+            std::ptr::copy_nonoverlapping(frame.as_ptr(), data, frame.len());
+
+            if let Some(launch_time) = launch_time {
+                ffi::dpdk_mbuf_set_tx_timestamp(mbuf, launch_time);
+            }
+
+            // Отслеживаемый mbuf удерживается до подтверждения (см. `poll_completions`)
+            let mut hw_stamped = false;
+            if let (Some(_), Some(tracker)) = (message_id, self.completions.as_mut()) {
+                ffi::dpdk_mbuf_refcnt_update(mbuf, 1);
+                if tracker.egress_clock.is_some() && !tracker.stamp_in_flight {
+                    ffi::dpdk_mbuf_request_tx_timestamp(mbuf);
+                    hw_stamped = true;
+                }
+            }
+
+            if ffi::rte_eth_tx_burst(self.port_id, self.queue_id, &mut mbuf, 1) == 0 {
+                if message_id.is_some() {
+                    ffi::dpdk_mbuf_refcnt_update(mbuf, -1);
+                }
+                ffi::rte_pktmbuf_free(mbuf);
+                self.refused += 1;
+                return Ok(false);
+            }
+
+            if let (Some(message_id), Some(tracker)) = (message_id, self.completions.as_mut()) {
+                tracker.stamp_in_flight |= hw_stamped;
+                tracker.pending.push_back(PendingTx {
+                    mbuf,
+                    message_id,
+                    submitted_ns: time::now_ns(),
+                    hw_stamped,
+                });
+            }
+        }
+
+        self.sent += 1;
+        Ok(true)
+    }
+
+    /// Кадры, принятые NIC в очередь
+    pub fn sent(&self) -> u64 {
+        self.sent
+    }
+
+    /// Кадры, не принятые NIC (очередь TX заполнена)
+    pub fn refused(&self) -> u64 {
+        self.refused
+    }
+}
+
+impl Drop for TxQueue {
+    /// Отпускает удерживаемые mbuf неподтвержденных сообщений
+    fn drop(&mut self) {
+        if let Some(tracker) = self.completions.as_mut() {
+            for pending in tracker.pending.drain(..) {
+                unsafe { ffi::rte_pktmbuf_free(pending.mbuf) };
+            }
+        }
+    }
+}
@@ -0,0 +1,241 @@
+// src/dpdk/validate.rs
+use std::fmt;
+
+use crate::dpdk::config::PortSettings;
+use crate::dpdk::ffi::{self, DpdkPortCaps};
+use crate::{log_info, log_warn};
+
+/// Поведение при несоответствии конфигурации возможностям порта
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationPolicy {
+    /// Скорректировать параметры и вывести отчет
+    AutoAdjust,
+    /// Прервать запуск с описанием всех проблем
+    FailFast,
+}
+
+impl fmt::Display for ValidationPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationPolicy::AutoAdjust => write!(f, "auto-adjust"),
+            ValidationPolicy::FailFast => write!(f, "fail-fast"),
+        }
+    }
+}
+
+/// Итоговое распределение очередей порта после проверки
+#[derive(Debug, Clone)]
+pub struct QueueLayoutReport {
+    pub port_id: u16,
+    pub rx_queues: u16,
+    pub tx_queues: u16,
+    /// Выполненные корректировки (пусто, если конфигурация принята как есть)
+    pub adjustments: Vec<String>,
+}
+
+impl QueueLayoutReport {
+    /// Записывает отчет о корректировках в журнал
+    pub fn log(&self) {
+        if self.adjustments.is_empty() {
+            log_info!(
+                "dpdk",
+                "Queue layout OK",
+                port = self.port_id,
+                rx_queues = self.rx_queues,
+                tx_queues = self.tx_queues
+            );
+            return;
+        }
+
+        for adjustment in &self.adjustments {
+            log_warn!(
+                "dpdk",
+                "Queue layout adjusted",
+                port = self.port_id,
+                reason = adjustment
+            );
+        }
+        log_info!(
+            "dpdk",
+            "Queue layout",
+            port = self.port_id,
+            rx_queues = self.rx_queues,
+            tx_queues = self.tx_queues
+        );
+    }
+}
+
+/// Сверяет запрошенное количество очередей с ограничениями NIC и числом
+/// NUMA-локальных ядер
+///
+/// Нарушения обнаруживаются до `rte_eth_dev_configure`, который сообщает
+/// лишь код ошибки без объяснения причины.
+pub fn validate_queue_layout(
+    port_id: u16,
+    requested_rx: u16,
+    requested_tx: u16,
+    caps: &DpdkPortCaps,
+    available_cores: usize,
+    policy: ValidationPolicy,
+) -> Result<QueueLayoutReport, String> {
+    if available_cores == 0 {
+        return Err(format!(
+            "Port {}: no NUMA-local worker cores available for RX queues",
+            port_id
+        ));
+    }
+
+    let mut problems = Vec::new();
+    let mut rx_queues = requested_rx.max(1);
+    let mut tx_queues = requested_tx.max(1);
+
+    // Нулевой максимум означает, что PMD не сообщил ограничение
+    if caps.max_rx_queues > 0 && rx_queues > caps.max_rx_queues {
+        problems.push(format!(
+            "requested {} RX queues, NIC supports at most {}",
+            rx_queues, caps.max_rx_queues
+        ));
+        rx_queues = caps.max_rx_queues;
+    }
+
+    if caps.max_tx_queues > 0 && tx_queues > caps.max_tx_queues {
+        problems.push(format!(
+            "requested {} TX queues, NIC supports at most {}",
+            tx_queues, caps.max_tx_queues
+        ));
+        tx_queues = caps.max_tx_queues;
+    }
+
+    // Каждая RX очередь обслуживается собственным потоком на отдельном ядре
+    if rx_queues as usize > available_cores {
+        problems.push(format!(
+            "{} RX queues but only {} NUMA-local worker cores",
+            rx_queues, available_cores
+        ));
+        rx_queues = available_cores as u16;
+    }
+
+    if !problems.is_empty() && policy == ValidationPolicy::FailFast {
+        return Err(format!(
+            "Port {} queue configuration rejected: {}",
+            port_id,
+            problems.join("; ")
+        ));
+    }
+
+    Ok(QueueLayoutReport {
+        port_id,
+        rx_queues,
+        tx_queues,
+        adjustments: problems,
+    })
+}
+
+/// Сверяет запрошенные offload-функции и RSS с возможностями NIC
+///
+/// Неподдерживаемые функции отключаются в `settings` (обработка переходит на
+/// программный путь), описание каждого отключения возвращается для журнала.
+/// Без проверки `rte_eth_dev_configure` отклонил бы конфигурацию с
+/// непрозрачным кодом ошибки.
+pub fn validate_offloads(
+    port_id: u16,
+    settings: &mut PortSettings,
+    caps: &DpdkPortCaps,
+    policy: ValidationPolicy,
+) -> Result<Vec<String>, String> {
+    let mut downgrades = Vec::new();
+
+    let rx_supported = |bits: u64| caps.rx_offload_capa & bits == bits;
+    let tx_supported = |bits: u64| caps.tx_offload_capa & bits == bits;
+
+    if settings.use_hw_checksum {
+        let tx_bits = ffi::DEV_TX_OFFLOAD_IPV4_CKSUM
+            | ffi::DEV_TX_OFFLOAD_UDP_CKSUM
+            | ffi::DEV_TX_OFFLOAD_TCP_CKSUM;
+        if !rx_supported(ffi::DEV_RX_OFFLOAD_CHECKSUM) || !tx_supported(tx_bits) {
+            downgrades.push("hardware checksum not supported, using software checksum".to_string());
+            settings.use_hw_checksum = false;
+        }
+    }
+
+    if settings.use_tso
+        && !tx_supported(ffi::DEV_TX_OFFLOAD_TCP_TSO | ffi::DEV_TX_OFFLOAD_MULTI_SEGS)
+    {
+        downgrades.push("TCP TSO not supported, segmenting in software".to_string());
+        settings.use_tso = false;
+    }
+
+    if settings.use_udp_tso
+        && !tx_supported(ffi::DEV_TX_OFFLOAD_UDP_TSO | ffi::DEV_TX_OFFLOAD_MULTI_SEGS)
+    {
+        downgrades.push("UDP TSO not supported, segmenting in software".to_string());
+        settings.use_udp_tso = false;
+    }
+
+    if settings.use_lro && !rx_supported(ffi::DEV_RX_OFFLOAD_TCP_LRO) {
+        downgrades.push("LRO not supported, disabled".to_string());
+        settings.use_lro = false;
+    }
+
+    if settings.use_gro && !rx_supported(ffi::DEV_RX_OFFLOAD_TCP_GRO | ffi::DEV_RX_OFFLOAD_SCATTER)
+    {
+        downgrades.push("GRO not supported, disabled".to_string());
+        settings.use_gro = false;
+    }
+
+    if settings.use_hw_timestamp && !rx_supported(ffi::DEV_RX_OFFLOAD_TIMESTAMP) {
+        downgrades.push("RX timestamp not supported, using software timestamps".to_string());
+        settings.use_hw_timestamp = false;
+    }
+
+    if settings.use_tx_launch_time && !tx_supported(ffi::DEV_TX_OFFLOAD_SEND_ON_TIMESTAMP) {
+        downgrades.push("TX launch time not supported, disabled".to_string());
+        settings.use_tx_launch_time = false;
+    }
+
+    if settings.use_jumbo_frames {
+        if !rx_supported(ffi::DEV_RX_OFFLOAD_SCATTER) {
+            downgrades.push("scattered RX not supported, jumbo frames disabled".to_string());
+            settings.use_jumbo_frames = false;
+        } else if caps.max_rx_pktlen > 0 && settings.max_rx_pkt_len > caps.max_rx_pktlen {
+            downgrades.push(format!(
+                "max RX packet length {} exceeds NIC limit {}, clamped",
+                settings.max_rx_pkt_len, caps.max_rx_pktlen
+            ));
+            settings.max_rx_pkt_len = caps.max_rx_pktlen;
+        }
+    }
+
+    // Нулевая маска означает, что PMD не сообщил поддерживаемые типы хэширования
+    if settings.use_rss && caps.flow_type_rss_offloads != 0 {
+        let unsupported = settings.rss_hf & !caps.flow_type_rss_offloads;
+        if unsupported != 0 {
+            downgrades.push(format!(
+                "RSS hash types 0x{:x} not supported, masked",
+                unsupported
+            ));
+            settings.rss_hf &= caps.flow_type_rss_offloads;
+        }
+    }
+
+    if let Some(key) = &settings.rss_key {
+        if caps.hash_key_size > 0 && key.len() != caps.hash_key_size as usize {
+            downgrades.push(format!(
+                "RSS key length {} does not match NIC key size {}, using default key",
+                key.len(),
+                caps.hash_key_size
+            ));
+            settings.rss_key = None;
+        }
+    }
+
+    if !downgrades.is_empty() && policy == ValidationPolicy::FailFast {
+        return Err(format!(
+            "Port {} offload configuration rejected: {}",
+            port_id,
+            downgrades.join("; ")
+        ));
+    }
+
+    Ok(downgrades)
+}
@@ -0,0 +1,352 @@
+// src/dpdk/xstats.rs
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::cpu::housekeeping;
+use crate::dpdk::ffi;
+use crate::log_warn;
+
+/// Чтение расширенной статистики (xstats) одного порта
+pub struct XstatsReader {
+    port_id: u16,
+    names: Vec<String>,
+    values: Vec<ffi::RteEthXstat>,
+}
+
+impl XstatsReader {
+    /// Запрашивает у PMD список имен расширенной статистики
+    pub fn new(port_id: u16) -> Result<Self, String> {
+        let count = ffi::eth_xstats_names(port_id, &mut []).map_err(|ret| {
+            format!(
+                "Failed to get xstats count for port {}: error code {}",
+                port_id, ret
+            )
+        })?;
+
+        let mut raw_names = vec![ffi::RteEthXstatName::default(); count];
+        let ret = match ffi::eth_xstats_names(port_id, &mut raw_names) {
+            Ok(ret) if ret <= count => ret,
+            Ok(ret) => {
+                return Err(format!(
+                    "Xstats of port {} changed while reading names: {} > {}",
+                    port_id, ret, count
+                ))
+            }
+            Err(ret) => {
+                return Err(format!(
+                    "Failed to get xstats names for port {}: error code {}",
+                    port_id, ret
+                ))
+            }
+        };
+
+        let names = raw_names[..ret as usize]
+            .iter()
+            .map(|n| {
+                unsafe { CStr::from_ptr(n.name.as_ptr()) }
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+
+        Ok(Self {
+            port_id,
+            names,
+            values: vec![ffi::RteEthXstat::default(); ret],
+        })
+    }
+
+    pub fn port_id(&self) -> u16 {
+        self.port_id
+    }
+
+    /// Читает текущие значения всех счетчиков
+    pub fn read(&mut self) -> Result<PortXstats, String> {
+        let ret = match ffi::eth_xstats_get(self.port_id, &mut self.values) {
+            Ok(ret) if ret <= self.values.len() => ret,
+            Ok(ret) => {
+                return Err(format!(
+                    "Xstats of port {} changed since names were read: {} > {}",
+                    self.port_id,
+                    ret,
+                    self.values.len()
+                ))
+            }
+            Err(ret) => {
+                return Err(format!(
+                    "Failed to read xstats for port {}: error code {}",
+                    self.port_id, ret
+                ))
+            }
+        };
+
+        let values = self.values[..ret]
+            .iter()
+            .filter_map(|x| {
+                self.names
+                    .get(x.id as usize)
+                    .map(|name| (name.clone(), x.value))
+            })
+            .collect();
+
+        Ok(PortXstats {
+            port_id: self.port_id,
+            values,
+        })
+    }
+}
+
+/// Снимок расширенной статистики порта
+#[derive(Debug, Clone, Default)]
+pub struct PortXstats {
+    pub port_id: u16,
+    pub values: HashMap<String, u64>,
+}
+
+impl PortXstats {
+    pub fn get(&self, name: &str) -> Option<u64> {
+        self.values.get(name).copied()
+    }
+
+    /// Сводка аппаратных потерь
+    pub fn drops(&self) -> NicDrops {
+        let sum_matching = |pred: &dyn Fn(&str) -> bool| -> u64 {
+            self.values
+                .iter()
+                .filter(|(name, _)| pred(name))
+                .map(|(_, value)| *value)
+                .sum()
+        };
+
+        NicDrops {
+            imissed: self.get("rx_missed_errors").unwrap_or(0),
+            rx_nombuf: self.get("rx_mbuf_allocation_errors").unwrap_or(0),
+            rx_errors: self.get("rx_errors").unwrap_or(0),
+            // Имена ошибок MAC уровня различаются у PMD (crc, длина, symbol и т.п.)
+            mac_errors: sum_matching(&|name| {
+                name.starts_with("mac_")
+                    || name.contains("crc_errors")
+                    || name.contains("length_errors")
+                    || name.contains("symbol_errors")
+            }),
+        }
+    }
+
+    /// Кадры паузы порта
+    ///
+    /// Имена счетчиков различаются у PMD (`rx_xoff_packets` у Intel,
+    /// `rx_pause_ctrl_phy` у mlx5). Счетчики по приоритетам (PFC) и
+    /// длительности пауз не суммируются, чтобы не учесть кадр дважды.
+    pub fn pause_frames(&self) -> PauseFrames {
+        let sum_direction = |direction: &str| -> u64 {
+            self.values
+                .iter()
+                .filter(|(name, _)| {
+                    name.starts_with(direction)
+                        && (name.contains("xoff") || name.contains("pause"))
+                        && !name.contains("prio")
+                        && !name.contains("duration")
+                        && !name.contains("storm")
+                })
+                .map(|(_, value)| *value)
+                .sum()
+        };
+
+        PauseFrames {
+            rx: sum_direction("rx_"),
+            tx: sum_direction("tx_"),
+        }
+    }
+}
+
+/// Кадры паузы Ethernet порта
+///
+/// Кадр паузы от коммутатора останавливает отправку порта; рост `rx` на
+/// порту без включенного управления потоком означает, что пауза включена на
+/// стороне коммутатора.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PauseFrames {
+    /// Принятые кадры паузы (XOFF)
+    pub rx: u64,
+    /// Отправленные кадры паузы (XOFF)
+    pub tx: u64,
+}
+
+impl PauseFrames {
+    /// Прирост относительно предыдущего снимка
+    pub fn delta(&self, previous: &PauseFrames) -> PauseFrames {
+        PauseFrames {
+            rx: self.rx.saturating_sub(previous.rx),
+            tx: self.tx.saturating_sub(previous.tx),
+        }
+    }
+}
+
+/// Аппаратные потери порта
+///
+/// Отличает потери в NIC (нет дескрипторов, нет mbuf, ошибки MAC) от
+/// программных потерь, учитываемых счетчиками рабочих потоков.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NicDrops {
+    /// Пакеты, отброшенные NIC из-за переполнения кольца RX (imissed)
+    pub imissed: u64,
+    /// Ошибки выделения mbuf (rx_nombuf)
+    pub rx_nombuf: u64,
+    /// Ошибочные принятые пакеты (ierrors)
+    pub rx_errors: u64,
+    /// Ошибки MAC уровня
+    pub mac_errors: u64,
+}
+
+impl NicDrops {
+    pub fn total(&self) -> u64 {
+        self.imissed + self.rx_nombuf + self.rx_errors + self.mac_errors
+    }
+
+    /// Прирост относительно предыдущего снимка
+    pub fn delta(&self, previous: &NicDrops) -> NicDrops {
+        NicDrops {
+            imissed: self.imissed.saturating_sub(previous.imissed),
+            rx_nombuf: self.rx_nombuf.saturating_sub(previous.rx_nombuf),
+            rx_errors: self.rx_errors.saturating_sub(previous.rx_errors),
+            mac_errors: self.mac_errors.saturating_sub(previous.mac_errors),
+        }
+    }
+}
+
+/// Периодический сборщик расширенной статистики
+///
+/// Опрашивает порты в отдельном потоке, хранит последние снимки и пишет в
+/// журнал предупреждение при росте аппаратных потерь и принятых кадров паузы.
+pub struct XstatsCollector {
+    latest: Arc<Mutex<HashMap<u16, PortXstats>>>,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl XstatsCollector {
+    /// Запускает сборщик для указанных портов
+    pub fn start(port_ids: &[u16], interval: Duration) -> Result<Self, String> {
+        let mut readers = port_ids
+            .iter()
+            .map(|&port_id| XstatsReader::new(port_id))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let latest = Arc::new(Mutex::new(HashMap::new()));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread_latest = latest.clone();
+        let thread_running = running.clone();
+        let thread = thread::Builder::new()
+            .name("hfeec-xstats".to_string())
+            .spawn(move || {
+                housekeeping::pin_current_thread();
+
+                let mut previous: HashMap<u16, NicDrops> = HashMap::new();
+                let mut previous_pause: HashMap<u16, PauseFrames> = HashMap::new();
+
+                while thread_running.load(Ordering::Relaxed) {
+                    for reader in readers.iter_mut() {
+                        let stats = match reader.read() {
+                            Ok(stats) => stats,
+                            Err(e) => {
+                                log_warn!("xstats", "Failed to read xstats", error = e);
+                                continue;
+                            }
+                        };
+
+                        let drops = stats.drops();
+                        if let Some(prev) = previous.get(&stats.port_id) {
+                            let delta = drops.delta(prev);
+                            if delta.total() > 0 {
+                                log_warn!(
+                                    "xstats",
+                                    "NIC drops",
+                                    port = stats.port_id,
+                                    imissed = delta.imissed,
+                                    rx_nombuf = delta.rx_nombuf,
+                                    rx_errors = delta.rx_errors,
+                                    mac_errors = delta.mac_errors
+                                );
+                            }
+                        }
+                        previous.insert(stats.port_id, drops);
+
+                        let pause = stats.pause_frames();
+                        if let Some(prev) = previous_pause.get(&stats.port_id) {
+                            let delta = pause.delta(prev);
+                            if delta.rx > 0 {
+                                log_warn!(
+                                    "xstats",
+                                    "Pause frames received",
+                                    port = stats.port_id,
+                                    rx_pause = delta.rx,
+                                    tx_pause = delta.tx
+                                );
+                            }
+                        }
+                        previous_pause.insert(stats.port_id, pause);
+
+                        if let Ok(mut latest) = thread_latest.lock() {
+                            latest.insert(stats.port_id, stats);
+                        }
+                    }
+
+                    thread::sleep(interval);
+                }
+            })
+            .map_err(|e| format!("Failed to spawn xstats collector: {}", e))?;
+
+        Ok(Self {
+            latest,
+            running,
+            thread: Some(thread),
+        })
+    }
+
+    /// Последний снимок статистики порта
+    pub fn latest(&self, port_id: u16) -> Option<PortXstats> {
+        self.latest.lock().ok()?.get(&port_id).cloned()
+    }
+
+    /// Последние сводки аппаратных потерь по всем портам
+    pub fn drops(&self) -> Vec<(u16, NicDrops)> {
+        let mut drops: Vec<(u16, NicDrops)> = match self.latest.lock() {
+            Ok(latest) => latest.values().map(|s| (s.port_id, s.drops())).collect(),
+            Err(_) => Vec::new(),
+        };
+        drops.sort_by_key(|(port_id, _)| *port_id);
+        drops
+    }
+
+    /// Последние счетчики кадров паузы по всем портам
+    pub fn pause_frames(&self) -> Vec<(u16, PauseFrames)> {
+        let mut pause: Vec<(u16, PauseFrames)> = match self.latest.lock() {
+            Ok(latest) => latest
+                .values()
+                .map(|s| (s.port_id, s.pause_frames()))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        pause.sort_by_key(|(port_id, _)| *port_id);
+        pause
+    }
+
+    /// Останавливает сборщик
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for XstatsCollector {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -0,0 +1,716 @@
+// src/engine/builder.rs
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use core_affinity::CoreId;
+
+use crate::capture::filter::CaptureFilter;
+use crate::capture::recorder::{
+    install_panic_dump, recording_handler, FlightRecorder, DEFAULT_RECORDER_CAPACITY,
+};
+use crate::capture::tee::{capture_handler, CaptureTee, DEFAULT_CAPTURE_RING_SIZE};
+use crate::control::events::EventListener;
+use crate::control::profile::ConfigProfile;
+use crate::control::state::{StateRegistry, StateSource};
+use crate::cpu::housekeeping;
+use crate::cpu::isolation::{IsolationPolicy, IsolationReport};
+use crate::dpdk::config::{default_dpdk_config, DpdkConfig, PortSelector};
+use crate::dpdk::mempool::MempoolThresholds;
+use crate::dpdk::multiproc::ProcessType;
+use crate::engine::runtime::Engine;
+use crate::engine::services::{Components, ServiceSettings, Services};
+use crate::mem::lock::lock_memory;
+use crate::metrics::line_correlation::{
+    correlating_handler, Line, LineCorrelator, SequenceExtractor,
+};
+use crate::numa::manager::NumaManager;
+use crate::packet::arbitration::{arbitrated_handler, LineArbiter};
+use crate::packet::budget::{budgeted_handler, FeedBudget, FeedBudgetConfig};
+use crate::packet::decrypt::{decrypting_handler, FeedDecryptor};
+use crate::packet::filter::RxFilter;
+use crate::packet::gro::{gro_handler, GroConfig, TcpGro};
+use crate::packet::handler::{FeedClass, HandlerRegistry, PacketHandler};
+use crate::packet::reorder::{reordering_handler, FeedReorder, ReorderConfig};
+use crate::packet::tap::{tap_handler, TapRing, DEFAULT_TAP_SNAPLEN};
+use crate::time::wheel::Timers;
+use crate::{log_info, log_warn};
+
+/// Изменение конфигурации DPDK поверх профиля
+type ConfigOverride = Box<dyn FnOnce(DpdkConfig) -> DpdkConfig>;
+
+/// Пара портов с копиями одного фида и извлечение порядкового номера
+type LinePair = (u16, u16, SequenceExtractor);
+
+/// Отбор и длина кадров захвата в pcapng
+struct CaptureStage {
+    filter: CaptureFilter,
+    snaplen: usize,
+}
+
+/// Построитель движка: собирает конфигурацию, порты, фиды и компоненты
+/// состояния и инициализирует узлы NUMA, EAL и порты в правильном порядке
+///
+/// Порядок применения конфигурации: базовая конфигурация, размещение памяти
+/// по узлам и jumbo frames, профиль площадки (`with_profile`), затем явные
+/// изменения (`configure`, `with_port`) в порядке вызова.
+///
+/// Этапы обработки (GRO, зеркалирование, захват, самописец, линии A/B,
+/// расшифровка, бюджет и переупорядочивание фидов) оборачивают обработчики
+/// в `build`; службы вокруг рабочих потоков запускает `Engine::serve`.
+pub struct EngineBuilder {
+    config: DpdkConfig,
+    /// Память на узел NUMA, МБ
+    mb_per_node: u32,
+    jumbo_mtu: Option<u32>,
+    profile: Option<ConfigProfile>,
+    overrides: Vec<ConfigOverride>,
+    default_handler: Option<PacketHandler>,
+    named_handlers: Vec<(String, PacketHandler)>,
+    port_handlers: Vec<(u16, PacketHandler)>,
+    feeds: Vec<(FeedClass, PacketHandler)>,
+    port_filters: Vec<(u16, RxFilter)>,
+    state: Vec<(String, Arc<dyn StateSource>)>,
+    listeners: Vec<EventListener>,
+    timers: Arc<Timers>,
+    tcp_gro: Option<GroConfig>,
+    capture: Option<CaptureStage>,
+    /// Окно бортового самописца и файл сброса
+    flight_recorder: Option<(Duration, String)>,
+    line_correlation: Option<LinePair>,
+    line_arbitration: Option<LinePair>,
+    feed_decryptors: Vec<(FeedClass, FeedDecryptor)>,
+    feed_budgets: Vec<(FeedClass, FeedBudgetConfig)>,
+    feed_reorders: Vec<(FeedClass, SequenceExtractor, ReorderConfig)>,
+    services: ServiceSettings,
+}
+
+impl EngineBuilder {
+    pub fn new() -> Self {
+        Self {
+            config: default_dpdk_config(),
+            mb_per_node: 1024,
+            jumbo_mtu: Some(9000),
+            profile: None,
+            overrides: Vec::new(),
+            default_handler: None,
+            named_handlers: Vec::new(),
+            port_handlers: Vec::new(),
+            feeds: Vec::new(),
+            port_filters: Vec::new(),
+            state: Vec::new(),
+            listeners: Vec::new(),
+            timers: Arc::new(Timers::new()),
+            tcp_gro: None,
+            capture: None,
+            flight_recorder: None,
+            line_correlation: None,
+            line_arbitration: None,
+            feed_decryptors: Vec::new(),
+            feed_budgets: Vec::new(),
+            feed_reorders: Vec::new(),
+            services: ServiceSettings::default(),
+        }
+    }
+
+    /// Заменяет базовую конфигурацию DPDK
+    pub fn with_config(mut self, config: DpdkConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Задает объем памяти на узел NUMA (МБ)
+    pub fn with_socket_memory(mut self, mb_per_node: u32) -> Self {
+        self.mb_per_node = mb_per_node;
+        self
+    }
+
+    /// Задает MTU jumbo frames (None - без jumbo frames)
+    pub fn with_jumbo_frames(mut self, mtu: Option<u32>) -> Self {
+        self.jumbo_mtu = mtu;
+        self
+    }
+
+    /// Накладывает профиль площадки и среды
+    pub fn with_profile(mut self, profile: ConfigProfile) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
+    /// Изменяет конфигурацию DPDK после профиля
+    pub fn configure(mut self, f: impl FnOnce(DpdkConfig) -> DpdkConfig + 'static) -> Self {
+        self.overrides.push(Box::new(f));
+        self
+    }
+
+    /// Добавляет выбранный порт
+    pub fn with_port(self, selector: PortSelector) -> Self {
+        self.configure(move |config| config.with_port(selector))
+    }
+
+    /// Задает обработчик по умолчанию (доступен также по имени "default")
+    pub fn with_handler(mut self, handler: PacketHandler) -> Self {
+        self.default_handler = Some(handler);
+        self
+    }
+
+    /// Регистрирует именованный обработчик для команды add-feed
+    pub fn with_named_handler(mut self, name: &str, handler: PacketHandler) -> Self {
+        self.named_handlers.push((name.to_string(), handler));
+        self
+    }
+
+    /// Назначает обработчик всем очередям порта
+    pub fn with_port_handler(mut self, port_id: u16, handler: PacketHandler) -> Self {
+        self.port_handlers.push((port_id, handler));
+        self
+    }
+
+    /// Добавляет фид (классы проверяются в порядке добавления)
+    pub fn with_feed(mut self, class: FeedClass, handler: PacketHandler) -> Self {
+        self.feeds.push((class, handler));
+        self
+    }
+
+    /// Задает фильтр приема порта (пакеты вне фильтра не доходят до обработчиков)
+    pub fn with_port_filter(mut self, port_id: u16, filter: RxFilter) -> Self {
+        self.port_filters.push((port_id, filter));
+        self
+    }
+
+    /// Имена добавленных фидов
+    pub fn feed_names(&self) -> Vec<&str> {
+        self.feeds
+            .iter()
+            .map(|(class, _)| class.name.as_str())
+            .chain(
+                self.feed_reorders
+                    .iter()
+                    .map(|(class, _, _)| class.name.as_str()),
+            )
+            .collect()
+    }
+
+    /// Объединяет сегменты TCP перед обработчиком по умолчанию
+    pub fn with_tcp_gro(mut self, config: GroConfig) -> Self {
+        self.tcp_gro = Some(config);
+        self
+    }
+
+    /// Зеркалирует пакеты в сокет для Wireshark (см. `hfeec extcap`)
+    pub fn with_tap_socket(mut self, path: &str) -> Self {
+        self.services.tap_socket = Some(path.to_string());
+        self
+    }
+
+    /// Записывает отобранные кадры в pcapng; файл пишет поток на `core`
+    pub fn with_capture(
+        mut self,
+        path: &str,
+        filter: CaptureFilter,
+        snaplen: usize,
+        core: Option<CoreId>,
+    ) -> Self {
+        self.capture = Some(CaptureStage { filter, snaplen });
+        self.services.capture_file = Some((path.to_string(), core));
+        self
+    }
+
+    /// Хранит заголовки пакетов за последние `window` в памяти; сбрасываются
+    /// в `dump_path` командой dump-recorder и при панике
+    pub fn with_flight_recorder(mut self, window: Duration, dump_path: &str) -> Self {
+        self.flight_recorder = Some((window, dump_path.to_string()));
+        self
+    }
+
+    /// Сопоставляет копии фида с портов `port_a` и `port_b`
+    pub fn with_line_correlation(
+        mut self,
+        port_a: u16,
+        port_b: u16,
+        extractor: SequenceExtractor,
+    ) -> Self {
+        self.line_correlation = Some((port_a, port_b, extractor));
+        self
+    }
+
+    /// Доставляет первую пришедшую копию фида с портов `port_a` и `port_b`
+    pub fn with_line_arbitration(
+        mut self,
+        port_a: u16,
+        port_b: u16,
+        extractor: SequenceExtractor,
+    ) -> Self {
+        self.line_arbitration = Some((port_a, port_b, extractor));
+        self
+    }
+
+    /// Расшифровывает фид на месте перед обработчиком
+    pub fn with_feed_decryption(mut self, class: FeedClass, decryptor: FeedDecryptor) -> Self {
+        self.feed_decryptors.push((class, decryptor));
+        self
+    }
+
+    /// Ограничивает долю времени рабочего потока на декодирование фида
+    pub fn with_feed_budget(mut self, class: FeedClass, config: FeedBudgetConfig) -> Self {
+        self.feed_budgets.push((class, config));
+        self
+    }
+
+    /// Задает резервное ядро для фидов, превысивших бюджет
+    pub fn with_overflow_core(mut self, core: CoreId) -> Self {
+        self.services.overflow_core = Some(core);
+        self
+    }
+
+    /// Восстанавливает порядок пакетов фида перед обработчиком
+    pub fn with_feed_reorder(
+        mut self,
+        class: FeedClass,
+        extractor: SequenceExtractor,
+        config: ReorderConfig,
+    ) -> Self {
+        self.feed_reorders.push((class, extractor, config));
+        self
+    }
+
+    /// Открывает сокет управления работающим движком
+    pub fn with_control_socket(mut self, path: &str) -> Self {
+        self.services.control_socket = Some(path.to_string());
+        self
+    }
+
+    /// Фиксирует частоту рабочих ядер (None - максимальная частота)
+    pub fn with_cpu_frequency(mut self, frequency_khz: Option<u64>) -> Self {
+        self.services.power.frequency_khz = frequency_khz;
+        self.services.pin_frequency = true;
+        self
+    }
+
+    /// Запрещает глубокие C-состояния на время работы
+    pub fn with_deep_cstates_disabled(mut self) -> Self {
+        self.services.power.disable_deep_cstates = true;
+        self
+    }
+
+    /// Публикует статистику рабочих потоков в разделяемую память
+    pub fn with_stats_shm(mut self, path: &str) -> Self {
+        self.services.stats_shm = Some(path.to_string());
+        self
+    }
+
+    /// Задает пороги предупреждений о заполненности пулов mbuf
+    pub fn with_mempool_thresholds(mut self, thresholds: MempoolThresholds) -> Self {
+        self.services.mempool_thresholds = thresholds;
+        self
+    }
+
+    /// Проверяет привязку рабочих потоков к ядрам; с `repin` поток
+    /// возвращается на назначенное ядро
+    pub fn with_affinity_guard(mut self, repin: bool) -> Self {
+        self.services.affinity_guard = Some(repin);
+        self
+    }
+
+    /// Регистрирует компонент снимка состояния (snapshot-state)
+    pub fn with_state(mut self, name: &str, source: Arc<dyn StateSource>) -> Self {
+        self.state.push((name.to_string(), source));
+        self
+    }
+
+    /// Подписывает слушателя на события жизненного цикла
+    pub fn on_event(mut self, listener: EventListener) -> Self {
+        self.listeners.push(listener);
+        self
+    }
+
+    /// Проверяет согласованность описания движка без обращения к оборудованию
+    pub fn validate(&self) -> Result<(), String> {
+        if self.mb_per_node == 0 {
+            return Err("Socket memory per NUMA node must be positive".to_string());
+        }
+
+        if self.default_handler.is_none() && self.port_handlers.is_empty() && self.feeds.is_empty()
+        {
+            return Err("No packet handlers configured".to_string());
+        }
+
+        // Этапы оборачивают обработчик по умолчанию
+        let has_stages = self.tcp_gro.is_some()
+            || self.services.tap_socket.is_some()
+            || self.capture.is_some()
+            || self.flight_recorder.is_some()
+            || self.line_correlation.is_some()
+            || self.line_arbitration.is_some()
+            || !self.feed_decryptors.is_empty()
+            || !self.feed_budgets.is_empty()
+            || !self.feed_reorders.is_empty();
+        if has_stages && self.default_handler.is_none() {
+            return Err("Packet processing stages require a default handler".to_string());
+        }
+
+        let mut feeds = HashSet::new();
+        let feed_names = self
+            .feeds
+            .iter()
+            .map(|(class, _)| &class.name)
+            .chain(self.feed_reorders.iter().map(|(class, _, _)| &class.name));
+        for name in feed_names {
+            if !feeds.insert(name.as_str()) {
+                return Err(format!("Duplicate feed '{}'", name));
+            }
+        }
+
+        let mut decrypted = HashSet::new();
+        for (class, _) in &self.feed_decryptors {
+            if !decrypted.insert(class.name.as_str()) {
+                return Err(format!("Feed '{}' is decrypted more than once", class.name));
+            }
+        }
+
+        let mut budgeted = HashSet::new();
+        for (class, _) in &self.feed_budgets {
+            if !budgeted.insert(class.name.as_str()) {
+                return Err(format!("Feed '{}' has more than one budget", class.name));
+            }
+        }
+
+        let mut ports = HashSet::new();
+        let line_ports = [self.line_correlation, self.line_arbitration]
+            .into_iter()
+            .flatten()
+            .flat_map(|(port_a, port_b, _)| [port_a, port_b]);
+        let handler_ports = self.port_handlers.iter().map(|(port_id, _)| *port_id);
+        for port_id in handler_ports.chain(line_ports) {
+            if !ports.insert(port_id) {
+                return Err(format!("Port {} has more than one handler", port_id));
+            }
+        }
+
+        let mut filtered = HashSet::new();
+        for (port_id, _) in &self.port_filters {
+            if !filtered.insert(*port_id) {
+                return Err(format!("Port {} has more than one RX filter", port_id));
+            }
+        }
+
+        let mut names = HashSet::new();
+        for (name, _) in &self.named_handlers {
+            if !names.insert(name.as_str()) {
+                return Err(format!("Duplicate handler name '{}'", name));
+            }
+        }
+
+        let mut state = HashSet::new();
+        for (name, _) in &self.state {
+            if !state.insert(name.as_str()) {
+                return Err(format!("Duplicate state component '{}'", name));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Таймеры протоколов движка
+    ///
+    /// Обработчики получают службу до построения движка и ставят таймеры
+    /// heartbeat, повторов и таймаутов сессий; сроки проверяются рабочими
+    /// потоками в простое и служебным ядром.
+    pub fn timers(&self) -> Arc<Timers> {
+        self.timers.clone()
+    }
+
+    /// Оборачивает обработчики заданными этапами обработки
+    ///
+    /// Обработчик по умолчанию: GRO, затем зеркалирование, захват и
+    /// самописец (внешний). Линии A/B и фиды получают уже обернутый
+    /// обработчик по умолчанию; фид: переупорядочивание, бюджет, расшифровка
+    /// (ближе всего к обработчику).
+    fn compose_handlers(&mut self) -> Components {
+        let mut components = Components::default();
+        let Some(mut handler) = self.default_handler.take() else {
+            return components;
+        };
+
+        if let Some(config) = self.tcp_gro.take() {
+            let gro = Arc::new(TcpGro::new(config));
+            handler = gro_handler(gro.clone(), handler);
+            self.state.push(("gro".to_string(), gro.clone()));
+            components.tcp_gro = Some(gro);
+        }
+
+        // Пока клиент не подключен, обработчик только проверяет флаг
+        if self.services.tap_socket.is_some() {
+            let tap = Arc::new(TapRing::new(65536, DEFAULT_TAP_SNAPLEN));
+            handler = tap_handler(tap.clone(), handler);
+            components.tap = Some(tap);
+        }
+
+        // Рабочие потоки копируют кадры в свои кольца, файл пишет отдельный поток
+        if let Some(capture) = self.capture.take() {
+            let tee = Arc::new(CaptureTee::new(
+                capture.filter,
+                DEFAULT_CAPTURE_RING_SIZE,
+                capture.snaplen,
+            ));
+            handler = capture_handler(tee.clone(), handler);
+            components.capture = Some(tee);
+        }
+
+        if let Some((window, dump_path)) = self.flight_recorder.take() {
+            let recorder = Arc::new(FlightRecorder::new(
+                window,
+                DEFAULT_RECORDER_CAPACITY,
+                &dump_path,
+            ));
+            install_panic_dump(recorder.clone());
+            handler = recording_handler(recorder.clone(), handler);
+            components.flight_recorder = Some(recorder);
+        }
+
+        if let Some((port_a, port_b, extractor)) = self.line_correlation.take() {
+            let correlator = Arc::new(Mutex::new(LineCorrelator::new(65536)));
+            for (port_id, line) in [(port_a, Line::A), (port_b, Line::B)] {
+                let wrapped =
+                    correlating_handler(correlator.clone(), line, extractor, handler.clone());
+                self.port_handlers.push((port_id, wrapped));
+            }
+            components.line_correlator = Some(correlator);
+        }
+
+        if let Some((port_a, port_b, extractor)) = self.line_arbitration.take() {
+            let arbiter = Arc::new(LineArbiter::new(65536));
+            for (port_id, line) in [(port_a, Line::A), (port_b, Line::B)] {
+                let wrapped = arbitrated_handler(arbiter.clone(), line, extractor, handler.clone());
+                self.port_handlers.push((port_id, wrapped));
+            }
+            self.state.push(("arbiter".to_string(), arbiter.clone()));
+            components.line_arbiter = Some(arbiter);
+        }
+
+        for (class, decryptor) in self.feed_decryptors.drain(..) {
+            let decryptor = Arc::new(decryptor);
+            self.state
+                .push((format!("decrypt.{}", class.name), decryptor.clone()));
+            components.feed_decryptors.push((class, decryptor));
+        }
+        for (class, config) in self.feed_budgets.drain(..) {
+            let budget = Arc::new(FeedBudget::new(&class.name, config));
+            self.state
+                .push((format!("budget.{}", class.name), budget.clone()));
+            components.feed_budgets.push((class, budget));
+        }
+
+        // Декодирование фида: расшифровка (если задана для фида), затем обработчик
+        let decode_handler = |name: &str| -> PacketHandler {
+            match components
+                .feed_decryptors
+                .iter()
+                .find(|(class, _)| class.name == name)
+            {
+                Some((_, decryptor)) => decrypting_handler(decryptor.clone(), handler.clone()),
+                None => handler.clone(),
+            }
+        };
+        // Обработчик фида: декодирование с учетом бюджета (если задан для фида)
+        let feed_handler = |name: &str| -> PacketHandler {
+            match components
+                .feed_budgets
+                .iter()
+                .find(|(class, _)| class.name == name)
+            {
+                Some((_, budget)) => budgeted_handler(budget.clone(), decode_handler(name)),
+                None => decode_handler(name),
+            }
+        };
+
+        let mut feed_reorders = Vec::new();
+        for (class, extractor, config) in self.feed_reorders.drain(..) {
+            let reorder = Arc::new(FeedReorder::new(config));
+            let wrapped =
+                reordering_handler(reorder.clone(), extractor, feed_handler(&class.name), None);
+            self.state
+                .push((format!("reorder.{}", class.name), reorder.clone()));
+            feed_reorders.push((class.name.clone(), reorder));
+            self.feeds.push((class, wrapped));
+        }
+
+        // Расшифровываемые фиды и фиды с бюджетом без переупорядочивания
+        let plain_feeds: Vec<FeedClass> = components
+            .feed_decryptors
+            .iter()
+            .map(|(class, _)| class)
+            .chain(components.feed_budgets.iter().map(|(class, _)| class))
+            .cloned()
+            .collect();
+        for class in plain_feeds {
+            if !self.feed_names().contains(&class.name.as_str()) {
+                let wrapped = feed_handler(&class.name);
+                self.feeds.push((class, wrapped));
+            }
+        }
+
+        // Резервное ядро декодирует фиды сверх бюджета без обертки бюджета
+        let overflow_feeds = components
+            .feed_budgets
+            .iter()
+            .map(|(class, budget)| (budget.clone(), decode_handler(&class.name)))
+            .collect();
+
+        components.feed_reorders = feed_reorders;
+        components.overflow_feeds = overflow_feeds;
+        self.default_handler = Some(handler);
+        components
+    }
+
+    /// Проверяет описание и инициализирует узлы NUMA, EAL и порты
+    pub fn build(mut self) -> Result<Engine, String> {
+        self.validate()?;
+        let components = self.compose_handlers();
+
+        let mut numa_manager =
+            NumaManager::new().map_err(|e| format!("Failed to initialize NUMA manager: {}", e))?;
+        for listener in self.listeners {
+            numa_manager.events().subscribe(listener);
+        }
+        numa_manager.set_timers(self.timers.clone());
+
+        numa_manager
+            .init_nodes()
+            .map_err(|e| format!("Failed to initialize NUMA nodes: {}", e))?;
+
+        // Память распределяется по числу найденных узлов NUMA
+        let node_count = numa_manager.get_node_count();
+        let mut config = self
+            .config
+            .with_numa_allocation(node_count, self.mb_per_node);
+        if let Some(mtu) = self.jumbo_mtu {
+            config = config.with_jumbo_frames(mtu);
+        }
+
+        let mut profile_layers = Vec::new();
+        if let Some(profile) = &self.profile {
+            profile_layers = profile
+                .layers()
+                .iter()
+                .map(|(layer, _)| layer.clone())
+                .collect();
+            config = config
+                .apply_profile(profile.values())
+                .map_err(|e| format!("Invalid config profile: {}", e))?;
+        }
+
+        for apply in self.overrides {
+            config = apply(config);
+        }
+
+        numa_manager
+            .set_core_policy(&config.reserved_cores, config.use_hyperthreads)
+            .map_err(|e| format!("Invalid reserved cores: {}", e))?;
+
+        // Служебное ядро исключается из ядер узлов до построения маски EAL
+        if let Some(core) = config.housekeeping_core {
+            numa_manager
+                .reserve_core(core)
+                .map_err(|e| format!("Invalid housekeeping core: {}", e))?;
+            housekeeping::set_core(core);
+        }
+        if config.use_l3_grouping {
+            numa_manager.group_cores_by_l3();
+        }
+
+        // EAL инициализируется один раз для всех узлов
+        numa_manager
+            .init_dpdk(&config)
+            .map_err(|e| format!("Failed to initialize DPDK: {}", e))?;
+        numa_manager
+            .distribute_interfaces(&config)
+            .map_err(|e| format!("Failed to distribute interfaces: {}", e))?;
+        numa_manager
+            .setup_ports(&config)
+            .map_err(|e| format!("Failed to set up ports: {}", e))?;
+
+        numa_manager
+            .validate_queue_cores()
+            .map_err(|e| format!("Invalid queue pinning: {}", e))?;
+
+        // Готовность ядер, которые займут рабочие потоки (вторичный процесс их не запускает)
+        if config.cpu_isolation != IsolationPolicy::Off
+            && numa_manager.process_type() != ProcessType::Secondary
+        {
+            let report = IsolationReport::check(&numa_manager.planned_worker_cores());
+            for core in &report.cores {
+                log_info!(
+                    "cpu",
+                    "Worker core isolation",
+                    core = core.core_id,
+                    isolcpus = core.isolated,
+                    nohz_full = core.nohz_full,
+                    rcu_nocbs = core.rcu_nocbs,
+                    irqbalance = core.irqbalance,
+                    irqs = core.irqs,
+                    governor = core.governor.as_deref().unwrap_or("n/a")
+                );
+            }
+            for problem in report.problems() {
+                log_warn!("cpu", "Worker core not ready", problem = problem);
+            }
+            if config.cpu_isolation == IsolationPolicy::Strict && !report.is_ready() {
+                return Err(format!(
+                    "Worker cores are not isolated: {}",
+                    report.problems().join("; ")
+                ));
+            }
+        }
+
+        // Страницы рабочих потоков подгружаются до приема первого пакета
+        if config.use_memory_lock {
+            lock_memory()?;
+            let touched = numa_manager.prefault_memory();
+            log_info!(
+                "dpdk",
+                "Memory locked and prefaulted",
+                prefaulted_mb = touched >> 20
+            );
+        }
+
+        let mut handlers = match &self.default_handler {
+            Some(handler) => {
+                HandlerRegistry::new(handler.clone()).with_named_handler("default", handler.clone())
+            }
+            None => HandlerRegistry::empty(),
+        };
+        for (name, handler) in self.named_handlers {
+            handlers = handlers.with_named_handler(&name, handler);
+        }
+        for (port_id, handler) in self.port_handlers {
+            handlers = handlers.with_port(port_id, handler);
+        }
+        for (class, handler) in self.feeds {
+            handlers = handlers.with_feed_class(class, handler);
+        }
+        for (port_id, filter) in self.port_filters {
+            handlers = handlers.with_port_filter(port_id, filter);
+        }
+
+        let state = StateRegistry::new();
+        for (name, source) in &self.state {
+            state.register(name, source.clone());
+        }
+
+        Ok(Engine::new(
+            numa_manager,
+            config,
+            handlers,
+            state,
+            profile_layers,
+            Services::new(self.services, components),
+        ))
+    }
+}
+
+impl Default for EngineBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,235 @@
+// src/engine/runtime.rs
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::control::snapshot::ConfigSnapshot;
+use crate::control::socket::ControlRequest;
+use crate::control::state::StateRegistry;
+use crate::cpu::housekeeping::{self, Housekeeper};
+use crate::dpdk::config::DpdkConfig;
+use crate::dpdk::multiproc::ProcessType;
+use crate::engine::services::Services;
+use crate::log_warn;
+use crate::logging::logger;
+use crate::numa::manager::NumaManager;
+use crate::packet::handler::HandlerRegistry;
+use crate::time::{self, wheel::Timers};
+
+/// Службы приложения вокруг работающего движка
+///
+/// Все методы, кроме `control`, необязательны. Службы, которым нужны рабочие
+/// потоки (мониторы, защита привязки), создаются в `started` и
+/// останавливаются в `stopping`, до остановки рабочих потоков.
+pub trait EngineHooks {
+    /// Вызывается после запуска рабочих потоков
+    fn started(&mut self, _engine: &Engine) {}
+
+    /// Обрабатывает команду управления; false - остановить движок
+    fn control(&mut self, engine: &mut Engine, request: ControlRequest) -> bool;
+
+    /// Периодический отчет (см. `Engine::with_report_interval`)
+    fn report(&mut self, _engine: &Engine) {}
+
+    /// Вызывается перед остановкой рабочих потоков
+    fn stopping(&mut self, _engine: &Engine) {}
+
+    /// Вызывается после дообработки принятых пакетов, до остановки портов
+    ///
+    /// Здесь службы дописывают свои буферы (захват, журналы).
+    fn drained(&mut self, _engine: &Engine) {}
+}
+
+/// Инициализированный движок: узлы NUMA и порты настроены, рабочие потоки
+/// запускаются в `run`
+///
+/// Создается через `EngineBuilder::build`.
+pub struct Engine {
+    numa_manager: NumaManager,
+    config: DpdkConfig,
+    handlers: Option<HandlerRegistry>,
+    state: StateRegistry,
+    profile_layers: Vec<String>,
+    report_interval: Duration,
+    /// Периодические задачи служебного ядра (запущены в `run`)
+    housekeeper: Option<Housekeeper>,
+    /// Службы, заданные в `EngineBuilder` (запускаются в `serve`)
+    services: Option<Services>,
+}
+
+impl Engine {
+    pub(crate) fn new(
+        numa_manager: NumaManager,
+        config: DpdkConfig,
+        handlers: HandlerRegistry,
+        state: StateRegistry,
+        profile_layers: Vec<String>,
+        services: Services,
+    ) -> Self {
+        Self {
+            numa_manager,
+            config,
+            handlers: Some(handlers),
+            state,
+            profile_layers,
+            report_interval: Duration::from_secs(10),
+            housekeeper: None,
+            services: Some(services),
+        }
+    }
+
+    /// Задает период вызова `EngineHooks::report`
+    pub fn with_report_interval(mut self, interval: Duration) -> Self {
+        self.report_interval = interval;
+        self
+    }
+
+    pub fn manager(&self) -> &NumaManager {
+        &self.numa_manager
+    }
+
+    pub fn manager_mut(&mut self) -> &mut NumaManager {
+        &mut self.numa_manager
+    }
+
+    /// Менеджер и конфигурация одновременно (команды перезапуска узлов)
+    pub fn manager_and_config(&mut self) -> (&mut NumaManager, &DpdkConfig) {
+        (&mut self.numa_manager, &self.config)
+    }
+
+    /// Итоговая конфигурация DPDK (после профиля и изменений)
+    pub fn config(&self) -> &DpdkConfig {
+        &self.config
+    }
+
+    /// Компоненты снимка состояния
+    pub fn state(&self) -> &StateRegistry {
+        &self.state
+    }
+
+    /// Периодические задачи служебного ядра (None до запуска `run`)
+    ///
+    /// Службы регистрируют здесь свои задачи в `EngineHooks::started`.
+    pub fn housekeeper(&self) -> Option<&Housekeeper> {
+        self.housekeeper.as_ref()
+    }
+
+    /// Таймеры протоколов (см. `EngineBuilder::timers`)
+    pub fn timers(&self) -> &Arc<Timers> {
+        self.numa_manager.timers()
+    }
+
+    /// Эффективная конфигурация, включая примененные слои профиля
+    pub fn snapshot(&self) -> ConfigSnapshot {
+        let mut snapshot = self.numa_manager.config_snapshot(&self.config);
+        if !self.profile_layers.is_empty() {
+            snapshot.set_list("config.layers", Some(&self.profile_layers));
+        }
+        snapshot
+    }
+
+    /// Вторичный процесс: очереди опрашивает первичный
+    pub fn is_secondary(&self) -> bool {
+        self.numa_manager.process_type() == ProcessType::Secondary
+    }
+
+    /// Запускает движок вместе со службами, заданными в `EngineBuilder`
+    ///
+    /// Резервное ядро фидов, запись захвата, сокеты управления и
+    /// зеркалирования запускаются до рабочих потоков, мониторы - после
+    /// (см. `run`).
+    pub fn serve(mut self) -> Result<(), String> {
+        let mut services = self.services.take().unwrap_or_default();
+        let control = services.prepare(&self);
+        self.run(
+            control.as_ref().map(|(_, requests)| requests),
+            &mut services,
+        )
+    }
+
+    /// Запускает рабочие потоки, обрабатывает команды управления до команды
+    /// остановки и корректно завершает обработку
+    ///
+    /// Завершение: прием прекращается, принятые пакеты дообрабатываются
+    /// (не дольше `DpdkConfig::drain_timeout_ms`), очереди TX и буферы служб
+    /// (`EngineHooks::drained`) сбрасываются, затем останавливаются порты.
+    ///
+    /// Без `control` движок работает до завершения процесса. Вторичный
+    /// процесс не запускает рабочие потоки: доступны только счетчики NIC,
+    /// пулы и команды управления.
+    pub fn run(
+        mut self,
+        control: Option<&Receiver<ControlRequest>>,
+        hooks: &mut impl EngineHooks,
+    ) -> Result<(), String> {
+        if let Some(handlers) = self.handlers.take() {
+            if !self.is_secondary() {
+                self.numa_manager
+                    .start_packet_processing(handlers, &self.config)
+                    .map_err(|e| format!("Failed to start packet processing: {}", e))?;
+            }
+        }
+
+        // Поток управления и запускаемые из него службы работают на служебном ядре
+        housekeeping::pin_current_thread();
+        let housekeeper = Housekeeper::start()?;
+        if !self.is_secondary() {
+            housekeeper.add_task(
+                "link-monitor",
+                Duration::from_secs(1),
+                self.numa_manager.link_monitor(),
+            );
+        }
+        // Служебное ядро проверяет таймеры, даже когда рабочие потоки не простаивают
+        let timers = self.timers().clone();
+        housekeeper.add_task("timers", Duration::from_millis(1), move || {
+            timers.poll(time::now_ns());
+        });
+        if let Some(watchdog) = self.numa_manager.watchdog().cloned() {
+            let interval = watchdog.config().interval();
+            housekeeper.add_task("stall-watchdog", interval, move || watchdog.check());
+        }
+        self.housekeeper = Some(housekeeper);
+
+        hooks.started(&self);
+
+        let mut last_report = Instant::now();
+        loop {
+            let request = match control {
+                Some(requests) => requests.recv_timeout(Duration::from_secs(1)).ok(),
+                None => {
+                    thread::sleep(Duration::from_secs(1));
+                    None
+                }
+            };
+
+            if let Some(request) = request {
+                if !hooks.control(&mut self, request) {
+                    break;
+                }
+            }
+
+            // Зависшие потоки заменяются здесь: служебному ядру менеджер недоступен
+            self.numa_manager.restart_stalled_workers();
+
+            if last_report.elapsed() >= self.report_interval {
+                last_report = Instant::now();
+                hooks.report(&self);
+            }
+        }
+
+        hooks.stopping(&self);
+        self.housekeeper = None;
+
+        let timeout = Duration::from_millis(self.config.drain_timeout_ms as u64);
+        let drained = self.numa_manager.drain(Instant::now() + timeout);
+        if let Err(e) = &drained {
+            log_warn!("worker", "Shutdown drain incomplete", error = e);
+        }
+        hooks.drained(&self);
+        logger::flush();
+        self.numa_manager.stop_ports();
+        drained
+    }
+}
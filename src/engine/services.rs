@@ -0,0 +1,481 @@
+// src/engine/services.rs
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use core_affinity::CoreId;
+
+use crate::capture::recorder::FlightRecorder;
+use crate::capture::tee::{CaptureTee, CaptureWriter};
+use crate::control::build_info::build_info;
+use crate::control::extcap::TapServer;
+use crate::control::snapshot::ConfigSnapshot;
+use crate::control::socket::{ControlCommand, ControlRequest, ControlServer};
+use crate::cpu::migration::MigrationGuard;
+use crate::cpu::power::{PowerGuard, PowerSettings};
+use crate::dpdk::flow_ctrl::FlowControlMode;
+use crate::dpdk::mempool::{MempoolMonitor, MempoolThresholds};
+use crate::dpdk::xstats::XstatsCollector;
+use crate::engine::runtime::{Engine, EngineHooks};
+use crate::metrics::line_correlation::LineCorrelator;
+use crate::metrics::shm::StatsPublisher;
+use crate::packet::arbitration::LineArbiter;
+use crate::packet::budget::{FeedBudget, OverflowWorker};
+use crate::packet::decrypt::FeedDecryptor;
+use crate::packet::filter::RxFilter;
+use crate::packet::gro::TcpGro;
+use crate::packet::handler::{FeedClass, PacketHandler};
+use crate::packet::reorder::FeedReorder;
+use crate::packet::tap::TapRing;
+use crate::{log_error, log_info, log_warn};
+
+/// Службы вокруг рабочих потоков, заданные в `EngineBuilder`
+#[derive(Default)]
+pub(crate) struct ServiceSettings {
+    pub control_socket: Option<String>,
+    /// Файл захвата pcapng и ядро потока записи
+    pub capture_file: Option<(String, Option<CoreId>)>,
+    pub tap_socket: Option<String>,
+    /// Резервное ядро для фидов сверх бюджета
+    pub overflow_core: Option<CoreId>,
+    pub power: PowerSettings,
+    /// Фиксировать частоту рабочих ядер (`power.frequency_khz`, None - максимальная)
+    pub pin_frequency: bool,
+    pub stats_shm: Option<String>,
+    pub mempool_thresholds: MempoolThresholds,
+    /// Защита привязки рабочих потоков; true - возвращать поток на ядро
+    pub affinity_guard: Option<bool>,
+}
+
+/// Компоненты обработки пакетов, которыми `EngineBuilder` обернул обработчики
+///
+/// Нужны службам для отчета, команд управления и остановки.
+#[derive(Default)]
+pub(crate) struct Components {
+    pub tcp_gro: Option<Arc<TcpGro>>,
+    pub tap: Option<Arc<TapRing>>,
+    pub capture: Option<Arc<CaptureTee>>,
+    pub flight_recorder: Option<Arc<FlightRecorder>>,
+    pub line_correlator: Option<Arc<Mutex<LineCorrelator>>>,
+    pub line_arbiter: Option<Arc<LineArbiter>>,
+    pub feed_reorders: Vec<(String, Arc<FeedReorder>)>,
+    pub feed_decryptors: Vec<(FeedClass, Arc<FeedDecryptor>)>,
+    pub feed_budgets: Vec<(FeedClass, Arc<FeedBudget>)>,
+    /// Обработчики фидов с бюджетом без обертки бюджета (для резервного ядра)
+    pub overflow_feeds: Vec<(Arc<FeedBudget>, PacketHandler)>,
+}
+
+/// Службы коннектора вокруг движка: мониторы, защита привязки, захват,
+/// команды управления и периодический отчет (см. `Engine::serve`)
+#[derive(Default)]
+pub(crate) struct Services {
+    settings: ServiceSettings,
+    components: Components,
+    snapshot: ConfigSnapshot,
+    power_guard: Option<PowerGuard>,
+    stats_publisher: Option<StatsPublisher>,
+    xstats: Option<XstatsCollector>,
+    mempool_monitor: Option<MempoolMonitor>,
+    migration_guard: Option<MigrationGuard>,
+    overflow_worker: Option<OverflowWorker>,
+    capture_writer: Option<CaptureWriter>,
+    tap_server: Option<TapServer>,
+}
+
+impl Services {
+    pub(crate) fn new(settings: ServiceSettings, components: Components) -> Self {
+        Self {
+            settings,
+            components,
+            ..Default::default()
+        }
+    }
+
+    /// Запускает службы, которые должны работать до рабочих потоков, и
+    /// открывает сокет управления
+    pub(crate) fn prepare(
+        &mut self,
+        engine: &Engine,
+    ) -> Option<(ControlServer, Receiver<ControlRequest>)> {
+        self.snapshot = engine.snapshot();
+
+        // Резервное ядро для изолированных фидов
+        let feeds = std::mem::take(&mut self.components.overflow_feeds);
+        if !feeds.is_empty() {
+            match OverflowWorker::start(feeds, self.settings.overflow_core) {
+                Ok(worker) => self.overflow_worker = Some(worker),
+                Err(e) => log_warn!(
+                    "budget",
+                    "Over-budget feeds stay on their workers",
+                    error = e
+                ),
+            }
+        }
+
+        // Файл создается после инициализации портов: до запуска потоков пакетов нет
+        if let (Some((path, core)), Some(tee)) =
+            (&self.settings.capture_file, &self.components.capture)
+        {
+            match CaptureWriter::start(path, tee.clone(), *core) {
+                Ok(writer) => self.capture_writer = Some(writer),
+                Err(e) => log_warn!("capture", "Capture disabled", error = e),
+            }
+        }
+
+        if let (Some(path), Some(tap)) = (&self.settings.tap_socket, &self.components.tap) {
+            match TapServer::start(path, tap.clone()) {
+                Ok(server) => self.tap_server = Some(server),
+                Err(e) => log_error!("tap", "Failed to start tap socket", error = e),
+            }
+        }
+
+        // Интерфейс управления работающим коннектором
+        self.settings
+            .control_socket
+            .as_deref()
+            .and_then(|path| match ControlServer::start(path) {
+                Ok(control) => Some(control),
+                Err(e) => {
+                    log_error!("control", "Failed to start control socket", error = e);
+                    None
+                }
+            })
+    }
+}
+
+impl EngineHooks for Services {
+    fn started(&mut self, engine: &Engine) {
+        let settings = &self.settings;
+        let numa_manager = engine.manager();
+
+        // Вторичный процесс не опрашивает очереди: они принадлежат первичному,
+        // здесь доступны только счетчики NIC, пулы и сокет управления
+        if engine.is_secondary() {
+            log_info!(
+                "engine",
+                "Running as secondary process (monitoring only), press Ctrl+C to stop"
+            );
+        } else {
+            log_info!("engine", "Packet processing started, press Ctrl+C to stop");
+        }
+
+        // Фиксируем частоту рабочих ядер и запрещаем глубокие C-состояния
+        if settings.pin_frequency || settings.power.disable_deep_cstates {
+            let cores = if settings.pin_frequency {
+                numa_manager.worker_cores()
+            } else {
+                Vec::new()
+            };
+
+            match PowerGuard::apply(&cores, &settings.power) {
+                Ok(guard) => self.power_guard = Some(guard),
+                Err(e) => log_error!("cpu", "Failed to apply CPU power settings", error = e),
+            }
+        }
+
+        // Публикуем статистику в разделяемую память для внешних мониторов
+        self.stats_publisher = settings.stats_shm.as_deref().and_then(|path| {
+            match StatsPublisher::start(
+                path,
+                numa_manager.worker_metrics(),
+                Duration::from_millis(100),
+            ) {
+                Ok(publisher) => Some(publisher),
+                Err(e) => {
+                    log_error!("metrics", "Failed to start stats publisher", error = e);
+                    None
+                }
+            }
+        });
+
+        // Опрашиваем аппаратные счетчики NIC, чтобы отличать потери NIC от программных
+        self.xstats = match XstatsCollector::start(&numa_manager.port_ids(), Duration::from_secs(1))
+        {
+            Ok(collector) => Some(collector),
+            Err(e) => {
+                log_error!("xstats", "Failed to start xstats collector", error = e);
+                None
+            }
+        };
+
+        // Следим за заполненностью пулов mbuf, чтобы исчерпание было видно до роста rx_nombuf
+        self.mempool_monitor = match MempoolMonitor::start(
+            numa_manager.mempools(),
+            Duration::from_secs(1),
+            settings.mempool_thresholds,
+        ) {
+            Ok(monitor) => Some(monitor),
+            Err(e) => {
+                log_error!("pool", "Failed to start mempool monitor", error = e);
+                None
+            }
+        };
+
+        // Проверяем, что рабочие потоки не перенесены с назначенных ядер
+        if let Some(repin) = settings.affinity_guard {
+            match MigrationGuard::start(
+                numa_manager.worker_controls(),
+                Duration::from_secs(1),
+                repin,
+            ) {
+                Ok(guard) => self.migration_guard = Some(guard),
+                Err(e) => log_error!("cpu", "Failed to start affinity guard", error = e),
+            }
+        }
+    }
+
+    /// Выполняет команду интерфейса управления; возвращает false для остановки
+    fn control(&mut self, engine: &mut Engine, request: ControlRequest) -> bool {
+        let state = engine.state().clone();
+        let (numa_manager, dpdk_config) = engine.manager_and_config();
+
+        match request.command.clone() {
+            ControlCommand::Stats { json: false } => request.respond(numa_manager.format_stats()),
+            ControlCommand::Stats { json: true } => {
+                request.respond(numa_manager.format_stats_json())
+            }
+            ControlCommand::Latency => request.respond(numa_manager.format_latency_stats()),
+            ControlCommand::PauseQueue {
+                port_id,
+                queue_id,
+                hw: false,
+            } => request.respond_result(numa_manager.set_queue_paused(port_id, queue_id, true)),
+            ControlCommand::PauseQueue {
+                port_id,
+                queue_id,
+                hw: true,
+            } => request.respond_result(numa_manager.stop_queue(
+                port_id,
+                queue_id,
+                Duration::from_secs(1),
+            )),
+            ControlCommand::ResumeQueue { port_id, queue_id } => {
+                request.respond_result(numa_manager.set_queue_paused(port_id, queue_id, false))
+            }
+            ControlCommand::StopNode(node_id) => {
+                request.respond_result(numa_manager.stop_node(node_id))
+            }
+            ControlCommand::StartNode(node_id) => {
+                request.respond_result(numa_manager.start_node(node_id, dpdk_config))
+            }
+            ControlCommand::PauseNode(node_id) => {
+                request.respond_result(numa_manager.set_node_paused(node_id, true))
+            }
+            ControlCommand::ResumeNode(node_id) => {
+                request.respond_result(numa_manager.set_node_paused(node_id, false))
+            }
+            ControlCommand::MoveQueue {
+                port_id,
+                queue_id,
+                core_id,
+            } => request.respond_result(numa_manager.move_queue(port_id, queue_id, core_id)),
+            ControlCommand::Workers => request.respond(numa_manager.format_worker_info()),
+            ControlCommand::Mempools => request.respond(numa_manager.format_mempool_info()),
+            ControlCommand::Reta(port_id) => match numa_manager.reta_info(port_id) {
+                Ok(info) => request.respond(info),
+                Err(e) => request.respond(format!("ERROR {}", e)),
+            },
+            ControlCommand::SetReta { port_id, weights } => {
+                match numa_manager.set_reta(port_id, &weights) {
+                    Ok(info) => request.respond(info),
+                    Err(e) => request.respond(format!("ERROR {}", e)),
+                }
+            }
+            ControlCommand::FlowControl { port_id, mode } => {
+                let result = match mode {
+                    None => numa_manager.flow_control_info(port_id),
+                    Some(mode) => FlowControlMode::parse(&mode)
+                        .and_then(|mode| numa_manager.set_flow_control(port_id, mode)),
+                };
+                match result {
+                    Ok(info) => request.respond(info),
+                    Err(e) => request.respond(format!("ERROR {}", e)),
+                }
+            }
+            ControlCommand::RxFilter {
+                port_id,
+                expression: None,
+            } => request.respond(
+                numa_manager
+                    .port_filter(port_id)
+                    .unwrap_or_else(|| "none".to_string()),
+            ),
+            ControlCommand::RxFilter {
+                port_id,
+                expression: Some(expression),
+            } => {
+                let filter = match expression.as_str() {
+                    "none" => Ok(None),
+                    _ => RxFilter::parse(&expression).map(Some),
+                };
+                request.respond_result(
+                    filter.and_then(|filter| numa_manager.set_port_filter(port_id, filter)),
+                )
+            }
+            ControlCommand::FeedKeys(feed) => {
+                let result = self
+                    .components
+                    .feed_decryptors
+                    .iter()
+                    .find(|(class, _)| class.name == feed)
+                    .ok_or_else(|| format!("Feed {} is not decrypted", feed))
+                    .and_then(|(_, decryptor)| decryptor.reload_keys());
+                match result {
+                    Ok(info) => request.respond(info),
+                    Err(e) => request.respond(format!("ERROR {}", e)),
+                }
+            }
+            ControlCommand::SetBurstSize(size) => {
+                request.respond_result(numa_manager.set_burst_size(size))
+            }
+            ControlCommand::AttachPort(devargs) => {
+                let result = numa_manager
+                    .attach_port(&devargs, dpdk_config)
+                    .map(|ports| format!("Attached ports {:?}", ports));
+                match result {
+                    Ok(message) => request.respond(message),
+                    Err(e) => request.respond(format!("ERROR {}", e)),
+                }
+            }
+            ControlCommand::DetachPort(port_id) => {
+                request.respond_result(numa_manager.detach_port(port_id))
+            }
+            ControlCommand::AddFeed { class, handler } => {
+                request.respond_result(numa_manager.add_feed(class, &handler))
+            }
+            ControlCommand::SnapshotState(path) => {
+                // Рабочие потоки должны дойти до конца пачки за время, сопоставимое с обработкой пачки
+                let result = numa_manager
+                    .quiesce(Duration::from_millis(100), || state.write(&path))
+                    .and_then(|written| written);
+                match result {
+                    Ok(entries) => {
+                        request.respond(format!("Wrote {} state entries to {}", entries, path))
+                    }
+                    Err(e) => request.respond(format!("ERROR {}", e)),
+                }
+            }
+            ControlCommand::DumpRecorder(path) => {
+                let result = self
+                    .components
+                    .flight_recorder
+                    .as_deref()
+                    .ok_or_else(|| {
+                        "Flight recorder is not enabled (use --flight-recorder)".to_string()
+                    })
+                    .and_then(|recorder| {
+                        let path = path.as_deref().unwrap_or(recorder.dump_path());
+                        recorder
+                            .dump(path)
+                            .map(|packets| format!("Wrote {} packets to {}", packets, path))
+                    });
+                match result {
+                    Ok(info) => request.respond(info),
+                    Err(e) => request.respond(format!("ERROR {}", e)),
+                }
+            }
+            ControlCommand::Config => request.respond(self.snapshot.to_text()),
+            ControlCommand::Version => request.respond(build_info().to_string()),
+            ControlCommand::Help => request.respond(ControlCommand::help()),
+            ControlCommand::Shutdown => {
+                log_info!("control", "Shutdown requested via control socket");
+                request.respond("OK");
+                return false;
+            }
+        }
+
+        true
+    }
+
+    // Периодически выводим счетчики и перцентили задержек рабочих потоков
+    fn report(&mut self, engine: &Engine) {
+        let numa_manager = engine.manager();
+        let components = &self.components;
+
+        numa_manager.print_stats();
+        if let Some(collector) = &self.xstats {
+            for (port_id, drops) in collector.drops() {
+                log_info!(
+                    "xstats",
+                    "NIC drops",
+                    port = port_id,
+                    imissed = drops.imissed,
+                    rx_nombuf = drops.rx_nombuf,
+                    rx_errors = drops.rx_errors,
+                    mac_errors = drops.mac_errors
+                );
+            }
+            for (port_id, pause) in collector.pause_frames() {
+                log_info!(
+                    "xstats",
+                    "Pause frames",
+                    port = port_id,
+                    rx = pause.rx,
+                    tx = pause.tx
+                );
+            }
+        }
+        if let Some(monitor) = &self.mempool_monitor {
+            for sample in monitor.samples() {
+                log_info!(
+                    "pool",
+                    "Mempool usage",
+                    port = sample.port_id,
+                    pool = sample.pool,
+                    in_use = sample.in_use,
+                    utilization_pct = sample.utilization_pct(),
+                    level = sample.level,
+                    available = sample.available,
+                    peak = sample.peak_in_use
+                );
+            }
+        }
+        numa_manager.print_latency_stats();
+        if let Some(correlator) = &components.line_correlator {
+            if let Ok(correlator) = correlator.lock() {
+                log_info!("line", "Line correlation", report = correlator.report());
+            }
+        }
+        if let Some(arbiter) = &components.line_arbiter {
+            log_info!("line", "Line arbitration", report = arbiter.report());
+        }
+        for (name, reorder) in &components.feed_reorders {
+            log_info!("feed", "Feed reorder", feed = name, stats = reorder.stats());
+        }
+        for (class, decryptor) in &components.feed_decryptors {
+            log_info!(
+                "feed",
+                "Feed decrypt",
+                feed = class.name,
+                stats = decryptor.stats()
+            );
+        }
+        for (class, budget) in &components.feed_budgets {
+            log_info!(
+                "feed",
+                "Feed budget",
+                feed = class.name,
+                stats = budget.stats()
+            );
+        }
+        if let Some(gro) = &components.tcp_gro {
+            log_info!("gro", "TCP GRO", stats = gro.stats());
+        }
+    }
+
+    fn stopping(&mut self, _engine: &Engine) {
+        if let Some(guard) = self.migration_guard.as_mut() {
+            guard.stop();
+        }
+    }
+
+    fn drained(&mut self, _engine: &Engine) {
+        // Пакеты фидов сверх бюджета и кадры захвата, принятые до остановки
+        if let Some(worker) = self.overflow_worker.as_mut() {
+            worker.stop();
+        }
+        if let Some(writer) = self.capture_writer.as_mut() {
+            writer.stop();
+        }
+    }
+}
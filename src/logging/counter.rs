@@ -0,0 +1,70 @@
+// src/logging/counter.rs
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::logging::logger::{self, Level};
+use crate::time;
+
+/// Счетчик аномалий с ограничением частоты сообщений
+///
+/// Каждое событие увеличивает счетчик; в журнал попадает не более одной записи
+/// за `interval_ns` с количеством событий с прошлой записи. Подходит для
+/// горячего пути: в обычном случае это один fetch_add и чтение часов.
+pub struct AnomalyCounter {
+    target: &'static str,
+    message: &'static str,
+    interval_ns: u64,
+    count: AtomicU64,
+    reported: AtomicU64,
+    next_report_ns: AtomicU64,
+}
+
+impl AnomalyCounter {
+    pub const fn new(target: &'static str, message: &'static str, interval_ns: u64) -> Self {
+        Self {
+            target,
+            message,
+            interval_ns,
+            count: AtomicU64::new(0),
+            reported: AtomicU64::new(0),
+            next_report_ns: AtomicU64::new(0),
+        }
+    }
+
+    /// Учитывает событие
+    #[inline]
+    pub fn hit(&self) {
+        let total = self.count.fetch_add(1, Ordering::Relaxed) + 1;
+
+        let now = time::now_ns();
+        let next = self.next_report_ns.load(Ordering::Relaxed);
+        if now < next {
+            return;
+        }
+
+        // Сообщение пишет только поток, выигравший CAS
+        if self
+            .next_report_ns
+            .compare_exchange(
+                next,
+                now + self.interval_ns,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            )
+            .is_ok()
+        {
+            let since = total - self.reported.swap(total, Ordering::Relaxed);
+            if logger::enabled(Level::Warn) {
+                logger::log(
+                    Level::Warn,
+                    self.target,
+                    logger::format_fields(self.message, &[("count", &since), ("total", &total)]),
+                );
+            }
+        }
+    }
+
+    /// Общее количество событий
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+}
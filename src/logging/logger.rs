@@ -0,0 +1,179 @@
+// src/logging/logger.rs
+use crossbeam::queue::ArrayQueue;
+use std::fmt::{self, Write as _};
+use std::io::Write as _;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::sync::OnceLock;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::cpu::housekeeping;
+use crate::time;
+
+/// Емкость очереди записей
+const QUEUE_CAPACITY: usize = 16 * 1024;
+/// Период опроса очереди фоновым потоком
+const FLUSH_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Уровень записи
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Level {
+    Debug = 0,
+    Info = 1,
+    Warn = 2,
+    Error = 3,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+        };
+        f.pad(name)
+    }
+}
+
+/// Запись журнала
+#[derive(Debug)]
+pub struct Record {
+    pub timestamp_ns: u64,
+    pub level: Level,
+    pub target: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{:06} {:<5} {}: {}",
+            self.timestamp_ns / 1_000_000_000,
+            (self.timestamp_ns / 1_000) % 1_000_000,
+            self.level,
+            self.target,
+            self.message
+        )
+    }
+}
+
+/// Журнал без блокировок
+///
+/// Писатели только кладут запись в ограниченную очередь и никогда не ждут
+/// stdout; при переполнении запись отбрасывается и учитывается в `dropped`.
+/// Вывод выполняет фоновый поток (`start_flusher`); до его запуска записи
+/// выводятся синхронно, что допустимо на этапе инициализации.
+struct Logger {
+    queue: ArrayQueue<Record>,
+    dropped: AtomicU64,
+    min_level: AtomicU8,
+    flusher_running: AtomicBool,
+}
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+fn logger() -> &'static Logger {
+    LOGGER.get_or_init(|| Logger {
+        queue: ArrayQueue::new(QUEUE_CAPACITY),
+        dropped: AtomicU64::new(0),
+        min_level: AtomicU8::new(Level::Info as u8),
+        flusher_running: AtomicBool::new(false),
+    })
+}
+
+/// Устанавливает минимальный выводимый уровень
+pub fn set_level(level: Level) {
+    logger().min_level.store(level as u8, Ordering::Relaxed);
+}
+
+/// Включен ли уровень (проверяется до форматирования записи)
+#[inline(always)]
+pub fn enabled(level: Level) -> bool {
+    level as u8 >= logger().min_level.load(Ordering::Relaxed)
+}
+
+/// Добавляет запись в журнал
+pub fn log(level: Level, target: &'static str, message: String) {
+    let logger = logger();
+    let record = Record {
+        timestamp_ns: time::now_ns(),
+        level,
+        target,
+        message,
+    };
+
+    if !logger.flusher_running.load(Ordering::Acquire) {
+        write_record(&record);
+        return;
+    }
+
+    if logger.queue.push(record).is_err() {
+        logger.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Количество записей, отброшенных из-за переполнения очереди
+pub fn dropped() -> u64 {
+    logger().dropped.load(Ordering::Relaxed)
+}
+
+/// Запускает фоновый поток вывода журнала
+pub fn start_flusher() -> JoinHandle<()> {
+    let logger = logger();
+    logger.flusher_running.store(true, Ordering::Release);
+
+    thread::Builder::new()
+        .name("hfeec-log".to_string())
+        .spawn(move || {
+            housekeeping::pin_current_thread();
+
+            let mut reported_dropped = 0;
+            loop {
+                flush();
+
+                let dropped = logger.dropped.load(Ordering::Relaxed);
+                if dropped != reported_dropped {
+                    write_record(&Record {
+                        timestamp_ns: time::now_ns(),
+                        level: Level::Warn,
+                        target: "log",
+                        message: format!("{} records dropped", dropped - reported_dropped),
+                    });
+                    reported_dropped = dropped;
+                }
+
+                thread::sleep(FLUSH_INTERVAL);
+            }
+        })
+        .expect("failed to spawn log flusher thread")
+}
+
+/// Выводит все накопленные записи
+pub fn flush() {
+    let logger = logger();
+    while let Some(record) = logger.queue.pop() {
+        write_record(&record);
+    }
+    let _ = std::io::stdout().flush();
+}
+
+fn write_record(record: &Record) {
+    if record.level >= Level::Warn {
+        eprintln!("{}", record);
+    } else {
+        println!("{}", record);
+    }
+}
+
+/// Формирует сообщение со структурированными полями: `message key=value ...`
+pub fn format_fields(message: &str, fields: &[(&str, &dyn fmt::Display)]) -> String {
+    let mut out = String::with_capacity(message.len() + fields.len() * 16);
+    out.push_str(message);
+    for (key, value) in fields {
+        let _ = write!(out, " {}={}", key, value);
+    }
+    out
+}
@@ -0,0 +1,52 @@
+// src/logging/mod.rs
+pub mod counter;
+pub mod logger;
+
+/// Пишет запись уровня Info: `log_info!("target", "message", key = value, ...)`
+#[macro_export]
+macro_rules! log_info {
+    ($target:expr, $msg:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::log_at!($crate::logging::logger::Level::Info, $target, $msg $(, $key = $value)*)
+    };
+}
+
+/// Пишет запись уровня Warn
+#[macro_export]
+macro_rules! log_warn {
+    ($target:expr, $msg:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::log_at!($crate::logging::logger::Level::Warn, $target, $msg $(, $key = $value)*)
+    };
+}
+
+/// Пишет запись уровня Error
+#[macro_export]
+macro_rules! log_error {
+    ($target:expr, $msg:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::log_at!($crate::logging::logger::Level::Error, $target, $msg $(, $key = $value)*)
+    };
+}
+
+/// Пишет запись уровня Debug
+#[macro_export]
+macro_rules! log_debug {
+    ($target:expr, $msg:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::log_at!($crate::logging::logger::Level::Debug, $target, $msg $(, $key = $value)*)
+    };
+}
+
+/// Пишет запись заданного уровня; поля форматируются только если уровень включен
+#[macro_export]
+macro_rules! log_at {
+    ($level:expr, $target:expr, $msg:expr $(, $key:ident = $value:expr)*) => {
+        if $crate::logging::logger::enabled($level) {
+            $crate::logging::logger::log(
+                $level,
+                $target,
+                $crate::logging::logger::format_fields(
+                    $msg,
+                    &[$((stringify!($key), &$value as &dyn std::fmt::Display)),*],
+                ),
+            );
+        }
+    };
+}
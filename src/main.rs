@@ -1,63 +1,752 @@
 #![allow(dead_code)]
+// Без DPDK собираются только независимые модули (разбор, метрики, стратегии)
+#![cfg_attr(not(feature = "dpdk"), allow(unused_imports))]
+mod backend;
+mod capture;
+mod control;
 mod cpu;
+#[cfg(feature = "dpdk")]
 mod dpdk;
+#[cfg(feature = "dpdk")]
+mod engine;
+mod logging;
+mod mem;
+mod metrics;
 mod numa;
 mod packet;
 mod protocols;
+mod replay;
+mod strategy;
+mod time;
+mod xdp;
 
+use core_affinity::CoreId;
+use std::path::Path;
 use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use crate::dpdk::config::default_dpdk_config;
+use crate::backend::af_packet::AfPacketBackend;
+use crate::backend::rx::{Backend, InterfaceSpec};
+use crate::backend::workers::{BackendSettings, BackendWorkers};
+use crate::capture::filter::CaptureFilter;
+use crate::capture::tee::DEFAULT_CAPTURE_SNAPLEN;
+use crate::control::build_info::build_info;
+use crate::control::extcap;
+#[cfg(feature = "dpdk")]
+use crate::control::profile::{local_hostname, ConfigProfile};
+use crate::control::snapshot::ConfigSnapshot;
+use crate::control::socket::{self, ControlCommand, ControlServer};
+#[cfg(feature = "dpdk")]
+use crate::cpu::idle::IdlePolicy;
+#[cfg(feature = "dpdk")]
+use crate::cpu::isolation::IsolationPolicy;
+#[cfg(feature = "dpdk")]
+use crate::cpu::realtime::RealtimeConfig;
+#[cfg(feature = "dpdk")]
+use crate::cpu::topology::parse_core_list;
+#[cfg(feature = "dpdk")]
+use crate::dpdk::config::{default_dpdk_config, DpdkConfig, PortSelector};
+#[cfg(feature = "dpdk")]
+use crate::dpdk::devbind::{self, DEFAULT_DPDK_DRIVER};
+#[cfg(feature = "dpdk")]
+use crate::dpdk::eventdev::SchedulingMode;
+#[cfg(feature = "dpdk")]
+use crate::dpdk::flow_ctrl::FlowControlMode;
+#[cfg(feature = "dpdk")]
+use crate::dpdk::init::{enumerate_dpdk_ports, lookup_mempool, select_ports};
+#[cfg(feature = "dpdk")]
+use crate::dpdk::mempool::MempoolThresholds;
+#[cfg(feature = "dpdk")]
+use crate::dpdk::multiproc::ProcessType;
+#[cfg(feature = "dpdk")]
+use crate::dpdk::probe::{run_probe, ProbeConfig};
+#[cfg(feature = "dpdk")]
+use crate::engine::builder::EngineBuilder;
+use crate::metrics::line_correlation::SequenceExtractor;
+#[cfg(feature = "dpdk")]
 use crate::numa::manager::NumaManager;
-use crate::packet::data::PacketData;
+#[cfg(feature = "dpdk")]
+use crate::numa::supervisor::PanicPolicy;
+#[cfg(feature = "dpdk")]
+use crate::numa::watchdog::WatchdogConfig;
+use crate::packet::breaker::CircuitBreakerConfig;
+use crate::packet::budget::FeedBudgetConfig;
+use crate::packet::data::PacketView;
+use crate::packet::decrypt::{CipherLayout, FeedDecryptor};
+use crate::packet::filter::RxFilter;
+use crate::packet::gro::GroConfig;
+use crate::packet::handler::{FeedClass, HandlerRegistry, PacketHandler, SharedHandlers};
+use crate::packet::reorder::ReorderConfig;
+use crate::replay::backend::{ReplayBackend, ReplaySpeed};
+use crate::xdp::backend::XdpBackend;
+use crate::xdp::program::XdpMode;
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.iter().any(|arg| arg == "--version") {
+        println!("{}", build_info());
+        return;
+    }
+
+    // `hfeec extcap ...` (или ссылка hfeec-extcap в каталоге extcap Wireshark)
+    let program = args.first().map(Path::new).and_then(Path::file_name);
+    if args.get(1).map(String::as_str) == Some("extcap")
+        || program.is_some_and(|name| name == "hfeec-extcap")
+    {
+        if let Err(e) = extcap::run_extcap(&args) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // `hfeec stats [--json] --control-socket <path>` опрашивает запущенный экземпляр
+    if args.get(1).map(String::as_str) == Some("stats") {
+        if let Err(e) = query_stats(&args) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // `hfeec probe ...` измеряет задержку пути между двумя портами
+    #[cfg(feature = "dpdk")]
+    if args.get(1).map(String::as_str) == Some("probe") {
+        if let Err(e) = probe(&args) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // `hfeec devbind ...` показывает сетевые устройства и перепривязывает их к vfio-pci
+    #[cfg(feature = "dpdk")]
+    if args.get(1).map(String::as_str) == Some("devbind") {
+        if let Err(e) = devbind(&args) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Бэкенд ввода-вывода: --backend dpdk (по умолчанию), af_xdp, af_packet или pcap
+    match arg_value(&args, "--backend") {
+        Some("af_xdp") => {
+            run_xdp(&args);
+            return;
+        }
+        Some("af_packet") => {
+            run_af_packet(&args);
+            return;
+        }
+        Some("pcap") => {
+            run_replay(&args);
+            return;
+        }
+        None | Some("dpdk") => {}
+        Some(other) => {
+            eprintln!(
+                "Unknown backend '{}': expected dpdk, af_xdp, af_packet or pcap",
+                other
+            );
+            std::process::exit(1);
+        }
+    }
+
+    #[cfg(feature = "dpdk")]
+    run(&args);
+
+    #[cfg(not(feature = "dpdk"))]
+    eprintln!(
+        "HFEEC was built without the dpdk feature, use --backend af_xdp or af_packet for packet processing"
+    );
+}
+
+/// Запускает коннектор: узлы NUMA, порты DPDK, рабочие потоки и цикл управления
+#[cfg(feature = "dpdk")]
+fn run(args: &[String]) {
     println!("Starting HFEEC - High Frequency Electronic Exchange Connector");
+    println!("{}", build_info());
 
-    // Создаем менеджер NUMA
-    let mut numa_manager = match NumaManager::new() {
-        Ok(manager) => manager,
+    // Калибруем TSC до запуска рабочих потоков
+    let clock = time::tsc::init();
+    if clock.is_invariant() {
+        println!("TSC clock calibrated: {} Hz", clock.tsc_hz());
+    } else {
+        println!("Invariant TSC not available, using CLOCK_MONOTONIC");
+    }
+
+    // Инициализируем узлы NUMA, EAL и порты
+    let engine = match engine_builder(args).and_then(EngineBuilder::build) {
+        Ok(engine) => engine,
         Err(e) => {
-            eprintln!("Failed to initialize NUMA manager: {}", e);
+            eprintln!("{}", e);
             return;
         }
     };
 
-    // Инициализируем NUMA-узлы
-    if let Err(e) = numa_manager.init_nodes() {
-        eprintln!("Failed to initialize NUMA nodes: {}", e);
-        return;
+    // Выводим итоговую раскладку портов (очереди и кольца после корректировок)
+    engine.manager().print_numa_topology();
+
+    // Выводим эффективную конфигурацию и сверяем ее с ожидаемой
+    let snapshot = engine.snapshot();
+    if args.iter().any(|arg| arg == "--dump-config") {
+        print!("{}", snapshot.to_text());
+    }
+
+    if let Some(path) = arg_value(args, "--diff-config") {
+        match ConfigSnapshot::load(path) {
+            Ok(intended) => {
+                let diffs = snapshot.diff(&intended, false);
+                if diffs.is_empty() {
+                    println!("Running configuration matches {}", path);
+                } else {
+                    println!("Configuration drift against {}:", path);
+                    for diff in &diffs {
+                        println!("  {}", diff);
+                    }
+                }
+            }
+            Err(e) => eprintln!("Failed to load config for diff: {}", e),
+        }
     }
 
-    // Выводим информацию о топологии
-    numa_manager.print_numa_topology();
+    // Дальше журнал выводится фоновым потоком: рабочие потоки не ждут stdout
+    logging::logger::start_flusher();
 
-    // Создаем конфигурацию DPDK
-    let mut dpdk_config = default_dpdk_config();
+    if let Err(e) = engine.serve() {
+        eprintln!("{}", e);
+    }
 
-    // Настраиваем DPDK с учетом количества узлов NUMA
-    let node_count = numa_manager.get_node_count();
-    dpdk_config = dpdk_config.with_numa_allocation(node_count, 1024);
+    logging::logger::flush();
+}
 
-    // Включаем поддержку Jumbo Frames
-    dpdk_config = dpdk_config.with_jumbo_frames(9000);
+/// Описывает движок по аргументам командной строки
+///
+/// Выводит события жизненного цикла; память распределяется по узлам NUMA
+/// (1024 МБ на узел), jumbo frames включены по умолчанию.
+#[cfg(feature = "dpdk")]
+fn engine_builder(args: &[String]) -> Result<EngineBuilder, String> {
+    let mut builder =
+        EngineBuilder::new().on_event(Arc::new(|event| println!("Lifecycle event: {}", event)));
 
-    // Распределяем интерфейсы по узлам NUMA
-    if let Err(e) = numa_manager.distribute_interfaces(&dpdk_config) {
-        eprintln!("Failed to distribute interfaces: {}", e);
-        return;
+    // Накладываем профиль конфигурации: base -> venue -> environment -> host
+    // (--config-dir <каталог> [--venue <площадка>] [--env <среда>])
+    if let Some(dir) = arg_value(args, "--config-dir") {
+        let host = local_hostname();
+        let profile = ConfigProfile::load(
+            dir,
+            arg_value(args, "--venue"),
+            arg_value(args, "--env"),
+            host.as_deref(),
+        )
+        .map_err(|e| format!("Failed to load config profile: {}", e))?;
+
+        for (layer, path) in profile.layers() {
+            println!("Config layer {}: {}", layer, path);
+        }
+        if args.iter().any(|arg| arg == "--explain-config") {
+            print!("{}", profile.explain());
+        }
+
+        builder = builder.with_profile(profile);
     }
 
-    // Инициализируем DPDK для всех узлов
-    if let Err(e) = numa_manager.init_dpdk(&dpdk_config) {
-        eprintln!("Failed to initialize DPDK: {}", e);
-        return;
+    // Выбираем порты по идентификатору, PCI адресу или MAC (--port можно указать несколько раз)
+    for value in arg_values(args, "--port")? {
+        builder = builder.with_port(PortSelector::parse(value)?);
+    }
+
+    // Защита от шторма сообщений: --circuit-breaker <trip_pps>[,<reset_pps>][,shed|conflate]
+    if let Some(spec) = arg_value(args, "--circuit-breaker") {
+        let breaker = CircuitBreakerConfig::parse(spec)?;
+        builder = builder.configure(move |c| c.with_circuit_breaker(breaker));
+    }
+
+    // Управление потоком (кадры паузы) всех портов: --flow-control none|rx|tx|full
+    if let Some(spec) = arg_value(args, "--flow-control") {
+        let mode = FlowControlMode::parse(spec)?;
+        builder = builder.configure(move |c| c.with_flow_control(mode));
+    }
+
+    // Многопроцессный режим DPDK: --proc-type primary|secondary|auto и общий --file-prefix
+    if let Some(spec) = arg_value(args, "--proc-type") {
+        let process_type = ProcessType::parse(spec)?;
+        builder = builder.configure(move |c| c.with_process_type(process_type));
+    }
+    if let Some(prefix) = arg_value(args, "--file-prefix") {
+        let prefix = prefix.to_string();
+        builder = builder.configure(move |c| c.with_file_prefix(&prefix));
+    }
+
+    // Отдельный пул mbuf на каждую очередь RX
+    if args.iter().any(|arg| arg == "--per-queue-mempools") {
+        builder = builder.configure(DpdkConfig::with_per_queue_mempools);
+    }
+
+    // Программное распределение для одноочередных NIC (virtio, часть VF)
+    if args.iter().any(|arg| arg == "--software-rss") {
+        builder = builder.configure(DpdkConfig::with_software_rss);
+    }
+
+    // Распределение по ядрам: --scheduling queue|atomic|ordered|parallel [--event-device <name>]
+    if let Some(spec) = arg_value(args, "--scheduling") {
+        let mode = SchedulingMode::parse(spec)?;
+        builder = builder.configure(move |c| c.with_scheduling(mode));
+    }
+    if let Some(device) = arg_value(args, "--event-device") {
+        let device = device.to_string();
+        builder = builder.configure(move |c| c.with_event_device(&device));
+    }
+
+    // Режим конвейера: --decode-cores <n> ядер декодирования на порт
+    if let Some(spec) = arg_value(args, "--decode-cores") {
+        let cores = spec
+            .parse::<u16>()
+            .map_err(|_| format!("Invalid decode core count '{}'", spec))?;
+        builder = builder.configure(move |c| c.with_pipeline(cores));
+    }
+
+    // Выделение недостающих hugepages узлам перед инициализацией EAL
+    if args.iter().any(|arg| arg == "--provision-hugepages") {
+        builder = builder.configure(DpdkConfig::with_hugepage_provisioning);
+    }
+
+    // Закрепление памяти и предварительное обращение к пулам и hugepages
+    if args.iter().any(|arg| arg == "--lock-memory") {
+        builder = builder.configure(DpdkConfig::with_memory_lock);
+    }
+
+    // Перераспределение пачек перегруженной очереди соседним рабочим потокам
+    if args.iter().any(|arg| arg == "--work-stealing") {
+        builder = builder.configure(DpdkConfig::with_work_stealing);
+    }
+
+    // Поведение при простое: --idle-policy spin|pause|sleep|interrupt[,<after>[,<wait>]]
+    if let Some(spec) = arg_value(args, "--idle-policy") {
+        let policy = IdlePolicy::parse(spec)?;
+        builder = builder.configure(move |c| c.with_idle_policy(policy));
+    }
+
+    // Проверка изоляции ядер рабочих потоков: --cpu-isolation off|report|strict
+    if let Some(spec) = arg_value(args, "--cpu-isolation") {
+        let policy = IsolationPolicy::parse(spec)?;
+        builder = builder.configure(move |c| c.with_cpu_isolation(policy));
+    }
+
+    // Приоритет реального времени рабочих потоков: --realtime fifo|rr[,<priority>]
+    if let Some(spec) = arg_value(args, "--realtime") {
+        let realtime = RealtimeConfig::parse(spec)?;
+        builder = builder.configure(move |c| c.with_realtime(realtime));
+    }
+
+    // Проверка зависания рабочих потоков: --watchdog <deadline_ms>[,restart]
+    if let Some(spec) = arg_value(args, "--watchdog") {
+        let watchdog = WatchdogConfig::parse(spec)?;
+        builder = builder.configure(move |c| c.with_watchdog(watchdog));
+    }
+
+    // Дообработка принятых пакетов при остановке: --drain-timeout <ms>
+    if let Some(spec) = arg_value(args, "--drain-timeout") {
+        let timeout_ms = spec
+            .parse::<u32>()
+            .map_err(|_| format!("Invalid drain timeout '{}'", spec))?;
+        builder = builder.configure(move |c| c.with_drain_timeout(timeout_ms));
+    }
+
+    // Паника обработчика: --panic-policy propagate|restart[,<max>]|kill-switch
+    if let Some(spec) = arg_value(args, "--panic-policy") {
+        let policy = PanicPolicy::parse(spec)?;
+        builder = builder.configure(move |c| c.with_panic_policy(policy));
+    }
+
+    // Ядра вне рабочих потоков: --reserved-cores <list>|none (по умолчанию 0)
+    if let Some(spec) = arg_value(args, "--reserved-cores") {
+        let cores = parse_core_list(spec)?;
+        builder = builder.configure(move |c| c.with_reserved_cores(cores));
+    }
+
+    // Рабочие потоки и на соседних логических ядрах (Hyper-Threading)
+    if args.iter().any(|arg| arg == "--hyperthreads") {
+        builder = builder.configure(DpdkConfig::with_hyperthreads);
+    }
+
+    // Потоки порта на ядрах с общим кэшем L3
+    if args.iter().any(|arg| arg == "--l3-grouping") {
+        builder = builder.configure(DpdkConfig::with_l3_grouping);
+    }
+
+    // Служебное ядро: --housekeeping-core <n> (исключается из рабочих ядер)
+    if let Some(spec) = arg_value(args, "--housekeeping-core") {
+        let core = spec
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid housekeeping core '{}'", spec))?;
+        builder = builder.configure(move |c| c.with_housekeeping_core(core));
+    }
+
+    // Аппаратная отметка времени приема (если NIC не поддерживает - отметка хоста)
+    if args.iter().any(|arg| arg == "--hw-timestamps") {
+        builder = builder.configure(DpdkConfig::with_hw_timestamps);
+    }
+
+    // Отправка по времени из mbuf (если NIC не поддерживает - отключается)
+    if args.iter().any(|arg| arg == "--tx-launch-time") {
+        builder = builder.configure(DpdkConfig::with_tx_launch_time);
+    }
+
+    // Обработчик по умолчанию доступен по имени для команды add-feed
+    builder = builder.with_handler(default_packet_handler());
+
+    // Объединение сегментов TCP (загрузка снимков, восстановление) перед
+    // обработчиком: --tcp-gro <max_bytes>[,<max_hold_us>]
+    if let Some(spec) = arg_value(args, "--tcp-gro") {
+        builder = builder.with_tcp_gro(GroConfig::parse(spec)?);
+    }
+
+    // Зеркалирование пакетов для просмотра в Wireshark (--tap-socket <path>, см. `hfeec extcap`)
+    if let Some(path) = arg_value(args, "--tap-socket") {
+        builder = builder.with_tap_socket(path);
+    }
+
+    // Запись пакетов в pcapng (--capture-file <path>), поток записи на
+    // --capture-core <core_id>; отбор --capture-filter port=<id>,queue=<id>,
+    // proto=<udp|tcp>,src=<ip>,dst=<ip>,sport=<port>[-<port>],dport=<port>[-<port>],
+    // длина кадра --capture-snaplen <bytes>
+    if let Some(path) = arg_value(args, "--capture-file") {
+        let filter = match arg_value(args, "--capture-filter") {
+            Some(spec) => CaptureFilter::parse(spec)?,
+            None => CaptureFilter::default(),
+        };
+        let snaplen = match arg_value(args, "--capture-snaplen").map(str::parse::<usize>) {
+            Some(Ok(snaplen)) if snaplen > 0 => snaplen,
+            Some(_) => return Err("Invalid --capture-snaplen value".to_string()),
+            None => DEFAULT_CAPTURE_SNAPLEN,
+        };
+        let core = match arg_value(args, "--capture-core").map(str::parse::<usize>) {
+            Some(Ok(id)) => Some(CoreId { id }),
+            Some(Err(_)) => return Err("Invalid --capture-core value".to_string()),
+            None => None,
+        };
+        builder = builder.with_capture(path, filter, snaplen, core);
+    }
+
+    // Бортовой самописец: заголовки пакетов за последние --flight-recorder <seconds>
+    // в памяти; сбрасывается командой dump-recorder и при панике в
+    // --flight-recorder-file <path>
+    if let Some(spec) = arg_value(args, "--flight-recorder") {
+        let seconds = match spec.parse::<u64>() {
+            Ok(seconds) if seconds > 0 => seconds,
+            _ => return Err("Invalid --flight-recorder value".to_string()),
+        };
+        let path =
+            arg_value(args, "--flight-recorder-file").unwrap_or("hfeec-flight-recorder.pcapng");
+        builder = builder.with_flight_recorder(Duration::from_secs(seconds), path);
+    }
+
+    // Фильтры приема портов в синтаксисе tcpdump (можно указать несколько раз):
+    // --rx-filter '<port_id>:<expression>'
+    for (port_id, filter) in parse_rx_filters(args)? {
+        builder = builder.with_port_filter(port_id, filter);
+    }
+
+    // Сопоставление копий фида с двух линий: --correlate-lines <port_a>,<port_b>,<seq_spec>
+    if let Some(value) = arg_value(args, "--correlate-lines") {
+        let (port_a, port_b, extractor) = parse_line_pair("--correlate-lines", value)?;
+        builder = builder.with_line_correlation(port_a, port_b, extractor);
+    }
+
+    // Доставка первой пришедшей копии: --arbitrate-lines <port_a>,<port_b>,<seq_spec>
+    if let Some(value) = arg_value(args, "--arbitrate-lines") {
+        let (port_a, port_b, extractor) = parse_line_pair("--arbitrate-lines", value)?;
+        builder = builder.with_line_arbitration(port_a, port_b, extractor);
+    }
+
+    // Расшифровка фида на месте перед обработчиком (можно указать несколько раз):
+    // --decrypt-feed <name>,<udp_port>[-<udp_port>],<key_file>,<cipher_layout>
+    for value in arg_values(args, "--decrypt-feed")? {
+        let (class, decryptor) = parse_decrypt_feed(value)?;
+        builder = builder.with_feed_decryption(class, decryptor);
     }
 
-    // Создаем обработчик пакетов
-    let packet_handler = Arc::new(|_queue_id: u16, packet: &PacketData| {
+    // Бюджет декодирования фида (можно указать несколько раз); фид, превысивший
+    // бюджет, изолируется на резервном ядре --overflow-core <core_id>:
+    // --feed-budget <name>,<udp_port>[-<udp_port>],<max_pct>[,offload|conflate]
+    for value in arg_values(args, "--feed-budget")? {
+        let (class, config) = parse_feed_budget(value)?;
+        builder = builder.with_feed_budget(class, config);
+    }
+    if let Some(spec) = arg_value(args, "--overflow-core") {
+        let id = spec
+            .parse::<usize>()
+            .map_err(|_| "Invalid --overflow-core value".to_string())?;
+        builder = builder.with_overflow_core(CoreId { id });
+    }
+
+    // Восстановление порядка фида перед обработчиком (можно указать несколько раз):
+    // --reorder-feed <name>,<udp_port>[-<udp_port>],<seq_spec>,<max_distance>[,<max_hold_us>]
+    for value in arg_values(args, "--reorder-feed")? {
+        let (class, extractor, config) = parse_reorder_feed(value)?;
+        builder = builder.with_feed_reorder(class, extractor, config);
+    }
+
+    // Интерфейс управления работающим коннектором
+    if let Some(path) = arg_value(args, "--control-socket") {
+        builder = builder.with_control_socket(path);
+    }
+
+    // Фиксируем частоту рабочих ядер (--cpu-freq <khz|max>) и запрещаем
+    // глубокие C-состояния (--no-cstates)
+    match arg_value(args, "--cpu-freq") {
+        None => {}
+        Some("max") => builder = builder.with_cpu_frequency(None),
+        Some(value) => {
+            let khz = value
+                .parse()
+                .map_err(|_| format!("Invalid --cpu-freq value '{}'", value))?;
+            builder = builder.with_cpu_frequency(Some(khz));
+        }
+    }
+    if args.iter().any(|arg| arg == "--no-cstates") {
+        builder = builder.with_deep_cstates_disabled();
+    }
+
+    // Публикуем статистику в разделяемую память для внешних мониторов
+    if let Some(path) = arg_value(args, "--stats-shm") {
+        builder = builder.with_stats_shm(path);
+    }
+
+    // Пороги заполненности пулов mbuf: --mempool-thresholds <warn_pct>[,<critical_pct>]
+    if let Some(spec) = arg_value(args, "--mempool-thresholds") {
+        builder = builder.with_mempool_thresholds(MempoolThresholds::parse(spec)?);
+    }
+
+    // Проверяем, что рабочие потоки не перенесены с назначенных ядер
+    // (--affinity-guard, с --affinity-repin поток возвращается на ядро)
+    if args.iter().any(|arg| arg == "--affinity-guard") {
+        let repin = args.iter().any(|arg| arg == "--affinity-repin");
+        builder = builder.with_affinity_guard(repin);
+    }
+
+    Ok(builder)
+}
+
+/// Запускает коннектор на бэкенде AF_XDP: без EAL и привязки NIC к vfio
+///
+/// Интерфейсы задаются `--xdp-interface <ifname>[,<queues>]` (можно указать
+/// несколько раз), режим подключения - `--xdp-mode auto|generic|native`.
+fn run_xdp(args: &[String]) {
+    let interfaces = match parse_interfaces(args, "--xdp-interface") {
+        Ok(interfaces) => interfaces,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    let mode = match arg_value(args, "--xdp-mode").map(XdpMode::parse) {
+        Some(Ok(mode)) => mode,
+        Some(Err(e)) => {
+            eprintln!("{}", e);
+            return;
+        }
+        None => XdpMode::Auto,
+    };
+
+    run_backend(args, "AF_XDP", || {
+        XdpBackend::new(&interfaces, mode).map(|backend| Arc::new(backend) as Arc<dyn Backend>)
+    });
+}
+
+/// Запускает коннектор на бэкенде AF_PACKET: для разработки и тестов без
+/// DPDK и поддержки XDP в драйвере
+///
+/// Интерфейсы задаются `--packet-interface <ifname>[,<queues>]` (можно
+/// указать несколько раз).
+fn run_af_packet(args: &[String]) {
+    let interfaces = match parse_interfaces(args, "--packet-interface") {
+        Ok(interfaces) => interfaces,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+
+    run_backend(args, "AF_PACKET", || {
+        AfPacketBackend::new(&interfaces).map(|backend| Arc::new(backend) as Arc<dyn Backend>)
+    });
+}
+
+/// Воспроизводит записи pcap/pcapng через рабочие потоки и обработчики
+///
+/// Файлы задаются `--replay-file <path>` (можно указать несколько раз), темп -
+/// `--replay-speed <factor>|max` (по умолчанию 1 - как в записи). Коннектор
+/// останавливается, когда все файлы прочитаны.
+fn run_replay(args: &[String]) {
+    let paths: Vec<String> = match arg_values(args, "--replay-file") {
+        Ok(paths) => paths.into_iter().map(String::from).collect(),
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    let speed = match arg_value(args, "--replay-speed").map(ReplaySpeed::parse) {
+        Some(Ok(speed)) => speed,
+        Some(Err(e)) => {
+            eprintln!("{}", e);
+            return;
+        }
+        None => ReplaySpeed::Scaled(1.0),
+    };
+
+    run_backend(args, "pcap replay", || {
+        ReplayBackend::new(&paths, speed).map(|backend| Arc::new(backend) as Arc<dyn Backend>)
+    });
+}
+
+/// Разбирает повторяемый параметр `<flag> <ifname>[,<queues>]`
+fn parse_interfaces(args: &[String], flag: &str) -> Result<Vec<InterfaceSpec>, String> {
+    arg_values(args, flag)?
+        .into_iter()
+        .map(InterfaceSpec::parse)
+        .collect()
+}
+
+/// Разбирает повторяемый `--rx-filter <port_id>:<expression>`
+fn parse_rx_filters(args: &[String]) -> Result<Vec<(u16, RxFilter)>, String> {
+    let mut filters = Vec::new();
+    for value in arg_values(args, "--rx-filter")? {
+        let (port, expression) = value.split_once(':').ok_or_else(|| {
+            format!(
+                "Invalid --rx-filter '{}': expected <port_id>:<expression>",
+                value
+            )
+        })?;
+        let port_id = port
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid port '{}' in --rx-filter", port))?;
+        filters.push((port_id, RxFilter::parse(expression)?));
+    }
+    Ok(filters)
+}
+
+/// Запускает рабочие потоки бэкенда без DPDK и цикл управления
+///
+/// Интерфейс управления поддерживает только статистику и остановку.
+fn run_backend(
+    args: &[String],
+    title: &str,
+    create: impl FnOnce() -> Result<Arc<dyn Backend>, String>,
+) {
+    println!(
+        "Starting HFEEC - High Frequency Electronic Exchange Connector ({} backend)",
+        title
+    );
+    println!("{}", build_info());
+
+    // Калибруем TSC до запуска рабочих потоков
+    let clock = time::tsc::init();
+    if clock.is_invariant() {
+        println!("TSC clock calibrated: {} Hz", clock.tsc_hz());
+    } else {
+        println!("Invariant TSC not available, using CLOCK_MONOTONIC");
+    }
+
+    let backend = match create() {
+        Ok(backend) => backend,
+        Err(e) => {
+            eprintln!("Failed to initialize {} backend: {}", title, e);
+            return;
+        }
+    };
+    let name = backend.name();
+
+    // Дальше журнал выводится фоновым потоком: рабочие потоки не ждут stdout
+    logging::logger::start_flusher();
+
+    let mut registry = HandlerRegistry::new(default_packet_handler());
+    match parse_rx_filters(args) {
+        Ok(filters) => {
+            for (port_id, filter) in filters {
+                registry = registry.with_port_filter(port_id, filter);
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    }
+    let handlers = Arc::new(SharedHandlers::new(registry));
+    let source = backend.clone();
+    let mut workers = match BackendWorkers::start(backend, handlers, BackendSettings::default()) {
+        Ok(workers) => workers,
+        Err(e) => {
+            eprintln!("Failed to start packet processing: {}", e);
+            return;
+        }
+    };
+
+    println!("Packet processing started. Press Ctrl+C to stop.");
+
+    // Интерфейс управления: статистика и остановка
+    let control =
+        arg_value(args, "--control-socket").and_then(|path| match ControlServer::start(path) {
+            Ok(control) => Some(control),
+            Err(e) => {
+                eprintln!("Failed to start control socket: {}", e);
+                None
+            }
+        });
+
+    let mut last_report = Instant::now();
+    loop {
+        let request = match &control {
+            Some((_, requests)) => requests.recv_timeout(Duration::from_secs(1)).ok(),
+            None => {
+                thread::sleep(Duration::from_secs(1));
+                None
+            }
+        };
+
+        if let Some(request) = request {
+            match request.command.clone() {
+                ControlCommand::Stats { json: false } => request.respond(workers.format_stats()),
+                ControlCommand::Stats { json: true } => {
+                    request.respond(workers.format_stats_json())
+                }
+                ControlCommand::Shutdown => {
+                    println!("Shutdown requested via control socket");
+                    request.respond("OK");
+                    break;
+                }
+                command => request.respond(format!(
+                    "ERROR {} is not supported by the {} backend",
+                    command, name
+                )),
+            }
+        }
+
+        if last_report.elapsed() >= Duration::from_secs(10) {
+            last_report = Instant::now();
+            print!("{}", workers.format_stats());
+        }
+
+        // Источник исчерпан (запись воспроизведена): итоговые счетчики и остановка
+        if source.is_finished() {
+            println!("Backend {} has no more packets, stopping", name);
+            print!("{}", workers.format_stats());
+            break;
+        }
+    }
+
+    workers.stop();
+    logging::logger::flush();
+}
+
+/// Обработчик пакетов по умолчанию
+fn default_packet_handler() -> PacketHandler {
+    Arc::new(|_queue_id: u16, packet: &PacketView| {
         // В реальном коде здесь была бы обработка пакетов
         // Для примера просто считаем количество пакетов
         static mut PACKET_COUNT: u64 = 0;
@@ -69,7 +758,7 @@ fn main() {
             // Выводим статистику каждые 1 000 000 пакетов
             if PACKET_COUNT - LAST_REPORT >= 1_000_000 {
                 // Выводим первые несколько байт данных (для отладки)
-                let data = packet.get_data();
+                let data = packet.data();
                 if data.len() > 16 {
                     println!("Data sample: {:02X?}", &data[0..16]);
                 }
@@ -77,18 +766,214 @@ fn main() {
                 LAST_REPORT = PACKET_COUNT;
             }
         }
-    });
+    })
+}
 
-    if let Err(e) = numa_manager.start_packet_processing(packet_handler, &dpdk_config) {
-        eprintln!("Failed to start packet processing: {}", e);
-        return;
+/// Запрашивает статистику у запущенного экземпляра через сокет управления
+fn query_stats(args: &[String]) -> Result<(), String> {
+    let path =
+        arg_value(args, "--control-socket").ok_or("stats: --control-socket <path> is required")?;
+    let command = if args.iter().any(|arg| arg == "--json") {
+        ControlCommand::Stats { json: true }
+    } else {
+        ControlCommand::Stats { json: false }
+    };
+
+    let response = socket::query(path, &command.to_string())?;
+    if let Some(error) = response.strip_prefix("ERROR ") {
+        return Err(format!("stats: {}", error.trim_end()));
     }
+    print!("{}", response);
+    Ok(())
+}
 
-    println!("Packet processing started. Press Ctrl+C to stop.");
+/// Измеряет задержку пути между двумя портами через коммутатор
+///
+/// `hfeec probe --tx-port <порт> --rx-port <порт> [--count <n>] [--interval-us <мкс>] [--size <байт>]`
+#[cfg(feature = "dpdk")]
+fn probe(args: &[String]) -> Result<(), String> {
+    let selector = |flag: &str| -> Result<PortSelector, String> {
+        PortSelector::parse(arg_value(args, flag).ok_or(format!("probe: {} is required", flag))?)
+    };
+    let number = |flag: &str, default: u64| -> Result<u64, String> {
+        match arg_value(args, flag) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("probe: invalid {} value '{}'", flag, value)),
+            None => Ok(default),
+        }
+    };
+    let tx_selector = selector("--tx-port")?;
+    let rx_selector = selector("--rx-port")?;
 
-    loop {
-        thread::sleep(Duration::from_secs(1));
+    time::tsc::init();
+    let mut numa_manager = NumaManager::new()?;
+    numa_manager.init_nodes()?;
+
+    // Одна очередь на порт: ее опрашивает этот поток, рабочие потоки не запускаются
+    let mut dpdk_config = default_dpdk_config()
+        .with_numa_allocation(numa_manager.get_node_count(), 1024)
+        .with_port(tx_selector.clone())
+        .with_port(rx_selector.clone());
+    dpdk_config.num_rx_queues = 1;
+    dpdk_config.num_tx_queues = 1;
+
+    numa_manager.init_dpdk(&dpdk_config)?;
+    numa_manager.distribute_interfaces(&dpdk_config)?;
+    numa_manager.setup_ports(&dpdk_config)?;
+
+    let ports = select_ports(enumerate_dpdk_ports(), &[tx_selector, rx_selector])?;
+    let [tx_port, rx_port] = ports.as_slice() else {
+        return Err("probe: expected two ports".to_string());
+    };
+
+    let mut config = ProbeConfig::new(tx_port.port_id, rx_port.port_id);
+    config.src_mac = tx_port.mac_address;
+    config.dst_mac = rx_port.mac_address;
+    config.count = number("--count", config.count)?;
+    config.interval =
+        Duration::from_micros(number("--interval-us", config.interval.as_micros() as u64)?);
+    config.payload_len = number("--size", config.payload_len as u64)? as usize;
+
+    let pool = numa_manager
+        .mempools()
+        .into_iter()
+        .find(|(port_id, _)| *port_id == tx_port.port_id)
+        .and_then(|(_, name)| lookup_mempool(&name))
+        .ok_or_else(|| format!("probe: no mbuf pool for port {}", tx_port.port_id))?;
+
+    println!(
+        "Probing port {} -> port {}: {} probes every {:?}",
+        config.tx_port, config.rx_port, config.count, config.interval
+    );
+    let report = run_probe(&config, pool)?;
+    println!("{}", report);
+    Ok(())
+}
+
+/// Показывает сетевые PCI устройства или меняет их драйвер
+///
+/// `hfeec devbind` - список; `hfeec devbind --bind <pci> [--driver <имя>]`
+/// привязывает к драйверу (по умолчанию vfio-pci); `hfeec devbind --unbind <pci>
+/// [--reprobe]` отвязывает (с `--reprobe` - возвращает драйверу ядра).
+#[cfg(feature = "dpdk")]
+fn devbind(args: &[String]) -> Result<(), String> {
+    let device = if let Some(pci) = arg_value(args, "--bind") {
+        let driver = arg_value(args, "--driver").unwrap_or(DEFAULT_DPDK_DRIVER);
+        Some(devbind::bind(pci, driver)?)
+    } else if let Some(pci) = arg_value(args, "--unbind") {
+        let reprobe = args.iter().any(|arg| arg == "--reprobe");
+        Some(devbind::unbind(pci, reprobe)?)
+    } else {
+        None
+    };
+
+    let devices = match device {
+        Some(device) => vec![device],
+        None => devbind::list_network_devices(),
+    };
+    print!("{}", devbind::format_devices(&devices));
+    Ok(())
+}
+
+fn parse_line_pair(flag: &str, value: &str) -> Result<(u16, u16, SequenceExtractor), String> {
+    let parts: Vec<&str> = value.splitn(3, ',').collect();
+    if parts.len() != 3 {
+        return Err(format!(
+            "{} expects <port_a>,<port_b>,<seq_spec>, got '{}'",
+            flag, value
+        ));
+    }
+
+    let port = |s: &str| {
+        s.parse::<u16>()
+            .map_err(|_| format!("Invalid port id '{}' in {}", s, flag))
+    };
+
+    Ok((
+        port(parts[0])?,
+        port(parts[1])?,
+        SequenceExtractor::parse(parts[2])?,
+    ))
+}
+
+/// Разбирает `<name>,<udp_port>[-<udp_port>],<seq_spec>,<max_distance>[,<max_hold_us>]`
+fn parse_reorder_feed(
+    value: &str,
+) -> Result<(FeedClass, SequenceExtractor, ReorderConfig), String> {
+    let parts: Vec<&str> = value.splitn(4, ',').collect();
+    if parts.len() != 4 {
+        return Err(format!(
+            "--reorder-feed expects <name>,<udp_port>[-<udp_port>],<seq_spec>,<max_distance>[,<max_hold_us>], got '{}'",
+            value
+        ));
     }
 
-    // numa_manager.stop_packet_processing();
+    let class =
+        FeedClass::parse(parts[0], parts[1]).map_err(|e| format!("{} in --reorder-feed", e))?;
+
+    Ok((
+        class,
+        SequenceExtractor::parse(parts[2])?,
+        ReorderConfig::parse(parts[3])?,
+    ))
+}
+
+/// Разбирает `<name>,<udp_port>[-<udp_port>],<key_file>,<cipher_layout>`
+fn parse_decrypt_feed(value: &str) -> Result<(FeedClass, FeedDecryptor), String> {
+    let parts: Vec<&str> = value.splitn(4, ',').collect();
+    if parts.len() != 4 {
+        return Err(format!(
+            "--decrypt-feed expects <name>,<udp_port>[-<udp_port>],<key_file>,<cipher_layout>, got '{}'",
+            value
+        ));
+    }
+
+    let class =
+        FeedClass::parse(parts[0], parts[1]).map_err(|e| format!("{} in --decrypt-feed", e))?;
+
+    Ok((
+        class,
+        FeedDecryptor::new(CipherLayout::parse(parts[3])?, parts[2])?,
+    ))
+}
+
+/// Разбирает `<name>,<udp_port>[-<udp_port>],<max_pct>[,offload|conflate]`
+fn parse_feed_budget(value: &str) -> Result<(FeedClass, FeedBudgetConfig), String> {
+    let parts: Vec<&str> = value.splitn(3, ',').collect();
+    if parts.len() != 3 {
+        return Err(format!(
+            "--feed-budget expects <name>,<udp_port>[-<udp_port>],<max_pct>[,offload|conflate], got '{}'",
+            value
+        ));
+    }
+
+    let class =
+        FeedClass::parse(parts[0], parts[1]).map_err(|e| format!("{} in --feed-budget", e))?;
+
+    Ok((class, FeedBudgetConfig::parse(parts[2])?))
+}
+
+/// Возвращает значение аргумента командной строки вида `--name value`
+fn arg_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|idx| args.get(idx + 1))
+        .map(|value| value.as_str())
+}
+
+/// Возвращает значения повторяемого аргумента `--name value` в порядке указания
+fn arg_values<'a>(args: &'a [String], name: &str) -> Result<Vec<&'a str>, String> {
+    let mut values = Vec::new();
+    for (idx, arg) in args.iter().enumerate() {
+        if arg != name {
+            continue;
+        }
+
+        match args.get(idx + 1) {
+            Some(value) => values.push(value.as_str()),
+            None => return Err(format!("{} requires a value", name)),
+        }
+    }
+    Ok(values)
 }
@@ -0,0 +1,136 @@
+// src/mem/copy.rs
+// Копирование коротких сообщений (заявки, заголовки, записи журнала)
+//
+// Для копий в несколько десятков байт вызов memcpy с разбором длины и
+// выравнивания дороже самого копирования. Здесь длина покрывается двумя
+// перекрывающимися блоками одного размера (начало и конец), поэтому на
+// каждый диапазон длины приходится одно ветвление и нет хвостовых циклов.
+
+use std::ptr;
+
+/// Наибольшая длина, на которую рассчитаны встроенные функции
+pub const SMALL_COPY_MAX: usize = 256;
+
+/// Блок 32 байта: с AVX компилируется в одну пару vmovdqu
+type Block32 = [u64; 4];
+
+/// Копирует начало и конец диапазона блоками `T` (для `len` от size_of::<T>() до 2 * size_of::<T>())
+///
+/// Оба блока читаются до записи, поэтому копия корректна и для перекрывающихся
+/// областей.
+#[inline(always)]
+unsafe fn copy_head_tail<T: Copy>(dst: *mut u8, src: *const u8, len: usize) {
+    let size = std::mem::size_of::<T>();
+    let head = (src as *const T).read_unaligned();
+    let tail = (src.add(len - size) as *const T).read_unaligned();
+    (dst as *mut T).write_unaligned(head);
+    (dst.add(len - size) as *mut T).write_unaligned(tail);
+}
+
+/// Копирует `len > 32` байт блоками по 32 байта регистрами AVX
+///
+/// `feature = "avx"` выставляет build.rs по целевому профилю CPU, но сам код
+/// без `-C target-cpu` собирается под базовый x86-64, поэтому AVX включается
+/// для этой функции отдельно. Встроить ее в вызывающий код без AVX нельзя,
+/// так что цикл целиком находится внутри, а вызов окупается на длинах > 64.
+#[cfg(all(target_arch = "x86_64", any(target_feature = "avx", feature = "avx")))]
+#[target_feature(enable = "avx")]
+#[inline]
+unsafe fn copy_blocks(dst: *mut u8, src: *const u8, len: usize) {
+    use std::arch::x86_64::{__m256i, _mm256_loadu_si256, _mm256_storeu_si256};
+
+    let copy_32 = |offset: usize| {
+        _mm256_storeu_si256(
+            dst.add(offset) as *mut __m256i,
+            _mm256_loadu_si256(src.add(offset) as *const __m256i),
+        )
+    };
+
+    let mut offset = 0;
+    while offset + 32 < len {
+        copy_32(offset);
+        offset += 32;
+    }
+    // Последний блок перекрывает уже скопированные байты
+    copy_32(len - 32);
+}
+
+/// Копирует `len > 32` байт блоками по 32 байта
+#[cfg(not(all(target_arch = "x86_64", any(target_feature = "avx", feature = "avx"))))]
+#[inline(always)]
+unsafe fn copy_blocks(dst: *mut u8, src: *const u8, len: usize) {
+    let copy_32 = |offset: usize| {
+        (dst.add(offset) as *mut Block32)
+            .write_unaligned((src.add(offset) as *const Block32).read_unaligned())
+    };
+
+    let mut offset = 0;
+    while offset + 32 < len {
+        copy_32(offset);
+        offset += 32;
+    }
+    // Последний блок перекрывает уже скопированные байты
+    copy_32(len - 32);
+}
+
+/// Копирует до 64 байт; корректно и для перекрывающихся областей
+#[inline(always)]
+unsafe fn copy_upto_64(dst: *mut u8, src: *const u8, len: usize) {
+    match len {
+        0 => {}
+        1 => *dst = *src,
+        2..=3 => copy_head_tail::<u16>(dst, src, len),
+        4..=7 => copy_head_tail::<u32>(dst, src, len),
+        8..=16 => copy_head_tail::<u64>(dst, src, len),
+        17..=32 => copy_head_tail::<u128>(dst, src, len),
+        _ => copy_head_tail::<Block32>(dst, src, len),
+    }
+}
+
+/// Копирует `len` байт между непересекающимися областями (аналог memcpy)
+///
+/// Рассчитана на `len <= SMALL_COPY_MAX`; большие длины копируются
+/// корректно, но блоками по 32 байта без оптимизаций memcpy.
+///
+/// # Safety
+/// Те же требования, что у `ptr::copy_nonoverlapping`.
+#[inline(always)]
+pub unsafe fn copy_small(dst: *mut u8, src: *const u8, len: usize) {
+    if len <= 64 {
+        copy_upto_64(dst, src, len);
+    } else {
+        copy_blocks(dst, src, len);
+    }
+}
+
+/// Копирует `len` байт, области могут пересекаться (аналог memmove)
+///
+/// До 64 байт все данные читаются до записи; большие длины передаются
+/// `ptr::copy`.
+///
+/// # Safety
+/// Те же требования, что у `ptr::copy`.
+#[inline(always)]
+pub unsafe fn move_small(dst: *mut u8, src: *const u8, len: usize) {
+    if len <= 64 {
+        copy_upto_64(dst, src, len);
+    } else {
+        ptr::copy(src, dst, len);
+    }
+}
+
+/// Копирует `src` в `dst` (длины должны совпадать, как у `copy_from_slice`)
+#[inline(always)]
+pub fn copy_bytes(dst: &mut [u8], src: &[u8]) {
+    assert_eq!(
+        dst.len(),
+        src.len(),
+        "copy_bytes: destination and source lengths differ"
+    );
+
+    if src.len() <= SMALL_COPY_MAX {
+        unsafe { copy_small(dst.as_mut_ptr(), src.as_ptr(), src.len()) };
+    } else {
+        dst.copy_from_slice(src);
+    }
+}
@@ -0,0 +1,41 @@
+// src/mem/lock.rs
+// Закрепление адресного пространства процесса в памяти
+//
+// Страница, впервые затронутая рабочим потоком посреди торгового дня, стоит
+// от микросекунды (minor fault) до миллисекунд (major fault, своп или чтение
+// файла). mlockall(MCL_CURRENT) заранее подгружает и закрепляет все текущие
+// отображения, а MCL_FUTURE - все последующие: пулы пакетов и стеки рабочих
+// потоков, созданные позже, заполняются страницами сразу при выделении.
+
+use std::io;
+
+/// Закрепляет текущие и будущие страницы процесса (mlockall)
+///
+/// Требует CAP_IPC_LOCK или достаточного RLIMIT_MEMLOCK: в лимит входит все
+/// адресное пространство процесса, включая резерв адресов EAL.
+pub fn lock_memory() -> Result<(), String> {
+    if unsafe { libc::mlockall(libc::MCL_CURRENT | libc::MCL_FUTURE) } != 0 {
+        return Err(format!(
+            "mlockall failed: {} (RLIMIT_MEMLOCK {}, requires CAP_IPC_LOCK or ulimit -l unlimited)",
+            io::Error::last_os_error(),
+            memlock_limit()
+        ));
+    }
+    Ok(())
+}
+
+/// Текущий RLIMIT_MEMLOCK для сообщений об ошибках
+fn memlock_limit() -> String {
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    if unsafe { libc::getrlimit(libc::RLIMIT_MEMLOCK, &mut limit) } != 0 {
+        return "unknown".to_string();
+    }
+    if limit.rlim_cur == libc::RLIM_INFINITY {
+        "unlimited".to_string()
+    } else {
+        format!("{} bytes", limit.rlim_cur)
+    }
+}
@@ -0,0 +1,3 @@
+pub mod copy;
+pub mod lock;
+pub mod spsc;
@@ -0,0 +1,314 @@
+// src/mem/spsc.rs
+// Кольца одного производителя и одного потребителя между ядрами
+//
+// Рабочие потоки RX передают разобранные события ядрам стратегии через
+// кольцо фиксированного размера без блокировок: каждая сторона пишет
+// только свой индекс, а индекс другой стороны кэширует и перечитывает,
+// лишь когда кэшированного значения не хватает. Пачка публикуется одной
+// записью индекса, поэтому линия кэша индекса переходит между ядрами раз
+// на пачку, а не на событие. Память кольца берется из huge pages (при
+// наличии) на узле NUMA потребителя.
+
+use crossbeam::utils::CachePadded;
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::os::raw::c_void;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::log_debug;
+use crate::numa::ffi::NumaAllocator;
+
+/// Размер huge page, на который округляется память кольца
+const HUGE_PAGE_SIZE: usize = 2 * 1024 * 1024;
+
+/// Откуда взята память кольца
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RingMemory {
+    /// Анонимные huge pages (MAP_HUGETLB)
+    HugePages,
+    /// Память узла NUMA (обычные страницы)
+    NumaNode,
+    /// Куча процесса
+    Heap,
+}
+
+/// Память слотов кольца
+struct RingBuffer<T> {
+    ptr: *mut UnsafeCell<MaybeUninit<T>>,
+    capacity: usize,
+    /// Размер отображения (для huge pages - с округлением до страницы)
+    bytes: usize,
+    memory: RingMemory,
+}
+
+impl<T> RingBuffer<T> {
+    fn allocate(capacity: usize, numa_node: Option<usize>) -> Self {
+        let bytes = capacity * std::mem::size_of::<T>().max(1);
+
+        // Huge pages берутся только для колец не меньше страницы: иначе страница 2 МБ тратится впустую
+        if bytes >= HUGE_PAGE_SIZE / 2 {
+            let rounded = bytes.div_ceil(HUGE_PAGE_SIZE) * HUGE_PAGE_SIZE;
+            let ptr = unsafe {
+                libc::mmap(
+                    std::ptr::null_mut(),
+                    rounded,
+                    libc::PROT_READ | libc::PROT_WRITE,
+                    libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_HUGETLB,
+                    -1,
+                    0,
+                )
+            };
+            if ptr != libc::MAP_FAILED {
+                // Страницы выделяются при первой записи - уже на узле потребителя
+                if let Some(node) = numa_node.filter(|_| NumaAllocator::is_available()) {
+                    NumaAllocator::bind_memory(ptr, rounded, node);
+                }
+                return Self {
+                    ptr: ptr as *mut _,
+                    capacity,
+                    bytes: rounded,
+                    memory: RingMemory::HugePages,
+                };
+            }
+            log_debug!(
+                "ring",
+                "Huge pages unavailable for ring, using regular pages",
+                bytes = bytes
+            );
+        }
+
+        if let Some(node) = numa_node.filter(|_| NumaAllocator::is_available()) {
+            let ptr = NumaAllocator::alloc_on_node(bytes, node);
+            if !ptr.is_null() {
+                return Self {
+                    ptr: ptr as *mut _,
+                    capacity,
+                    bytes,
+                    memory: RingMemory::NumaNode,
+                };
+            }
+        }
+
+        let mut slots: Vec<UnsafeCell<MaybeUninit<T>>> = Vec::with_capacity(capacity);
+        slots.resize_with(capacity, || UnsafeCell::new(MaybeUninit::uninit()));
+        let ptr = Box::into_raw(slots.into_boxed_slice()) as *mut UnsafeCell<MaybeUninit<T>>;
+        Self {
+            ptr,
+            capacity,
+            bytes,
+            memory: RingMemory::Heap,
+        }
+    }
+
+    #[inline(always)]
+    fn slot(&self, index: usize) -> *mut MaybeUninit<T> {
+        unsafe { (*self.ptr.add(index)).get() }
+    }
+}
+
+impl<T> Drop for RingBuffer<T> {
+    fn drop(&mut self) {
+        match self.memory {
+            RingMemory::HugePages => unsafe {
+                libc::munmap(self.ptr as *mut c_void, self.bytes);
+            },
+            RingMemory::NumaNode => NumaAllocator::free(self.ptr as *mut c_void, self.bytes),
+            RingMemory::Heap => unsafe {
+                drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+                    self.ptr,
+                    self.capacity,
+                )));
+            },
+        }
+    }
+}
+
+/// Общее состояние кольца
+struct Shared<T> {
+    buffer: RingBuffer<T>,
+    mask: usize,
+    /// Следующий слот для чтения (пишет потребитель)
+    head: CachePadded<AtomicUsize>,
+    /// Следующий слот для записи (пишет производитель)
+    tail: CachePadded<AtomicUsize>,
+}
+
+unsafe impl<T: Send> Send for Shared<T> {}
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        let head = *self.head.get_mut();
+        let count = self.tail.get_mut().wrapping_sub(head);
+        for offset in 0..count {
+            let index = head.wrapping_add(offset) & self.mask;
+            unsafe { (*self.buffer.slot(index)).assume_init_drop() };
+        }
+    }
+}
+
+/// Создает кольцо на `capacity` событий (округляется до степени двойки)
+///
+/// `numa_node` - узел потребителя: память кольца читается им, поэтому
+/// размещается на его узле.
+pub fn channel<T: Send>(capacity: usize, numa_node: Option<usize>) -> (Producer<T>, Consumer<T>) {
+    let capacity = capacity.max(2).next_power_of_two();
+    let shared = Arc::new(Shared {
+        buffer: RingBuffer::allocate(capacity, numa_node),
+        mask: capacity - 1,
+        head: CachePadded::new(AtomicUsize::new(0)),
+        tail: CachePadded::new(AtomicUsize::new(0)),
+    });
+
+    (
+        Producer {
+            shared: shared.clone(),
+            tail: 0,
+            cached_head: 0,
+        },
+        Consumer {
+            shared,
+            head: 0,
+            cached_tail: 0,
+        },
+    )
+}
+
+/// Сторона записи кольца (одна на кольцо, передается в поток производителя)
+pub struct Producer<T> {
+    shared: Arc<Shared<T>>,
+    tail: usize,
+    cached_head: usize,
+}
+
+impl<T: Send> Producer<T> {
+    /// Свободных слотов (не меньше фактического числа)
+    #[inline(always)]
+    fn free_slots(&mut self, wanted: usize) -> usize {
+        let capacity = self.shared.mask + 1;
+        let mut free = capacity - self.tail.wrapping_sub(self.cached_head);
+        if free < wanted {
+            self.cached_head = self.shared.head.load(Ordering::Acquire);
+            free = capacity - self.tail.wrapping_sub(self.cached_head);
+        }
+        free
+    }
+
+    /// Ставит событие в кольцо; при заполненном кольце возвращает его обратно
+    #[inline(always)]
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        if self.free_slots(1) == 0 {
+            return Err(value);
+        }
+        unsafe { (*self.shared.buffer.slot(self.tail & self.shared.mask)).write(value) };
+        self.tail = self.tail.wrapping_add(1);
+        self.shared.tail.store(self.tail, Ordering::Release);
+        Ok(())
+    }
+
+    /// Ставит в кольцо столько событий из начала `values`, сколько помещается,
+    /// и публикует их одной записью; возвращает число поставленных
+    #[inline(always)]
+    pub fn push_burst(&mut self, values: &mut Vec<T>) -> usize {
+        let count = self.free_slots(values.len()).min(values.len());
+        if count == 0 {
+            return 0;
+        }
+        for (offset, value) in values.drain(..count).enumerate() {
+            let index = self.tail.wrapping_add(offset) & self.shared.mask;
+            unsafe { (*self.shared.buffer.slot(index)).write(value) };
+        }
+        self.tail = self.tail.wrapping_add(count);
+        self.shared.tail.store(self.tail, Ordering::Release);
+        count
+    }
+
+    /// Емкость кольца
+    pub fn capacity(&self) -> usize {
+        self.shared.mask + 1
+    }
+
+    /// Событий в кольце
+    pub fn len(&self) -> usize {
+        self.tail
+            .wrapping_sub(self.shared.head.load(Ordering::Acquire))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Откуда взята память кольца
+    pub fn memory(&self) -> RingMemory {
+        self.shared.buffer.memory
+    }
+}
+
+/// Сторона чтения кольца (одна на кольцо, передается в поток потребителя)
+pub struct Consumer<T> {
+    shared: Arc<Shared<T>>,
+    head: usize,
+    cached_tail: usize,
+}
+
+impl<T: Send> Consumer<T> {
+    /// Событий, доступных для чтения (не больше фактического числа)
+    #[inline(always)]
+    fn available(&mut self, wanted: usize) -> usize {
+        let mut available = self.cached_tail.wrapping_sub(self.head);
+        if available < wanted {
+            self.cached_tail = self.shared.tail.load(Ordering::Acquire);
+            available = self.cached_tail.wrapping_sub(self.head);
+        }
+        available
+    }
+
+    /// Извлекает событие (None - кольцо пусто)
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<T> {
+        if self.available(1) == 0 {
+            return None;
+        }
+        let value =
+            unsafe { (*self.shared.buffer.slot(self.head & self.shared.mask)).assume_init_read() };
+        self.head = self.head.wrapping_add(1);
+        self.shared.head.store(self.head, Ordering::Release);
+        Some(value)
+    }
+
+    /// Извлекает до `max` событий в конец `out` и освобождает их слоты одной
+    /// записью; возвращает число извлеченных
+    #[inline(always)]
+    pub fn pop_burst(&mut self, out: &mut Vec<T>, max: usize) -> usize {
+        let count = self.available(max).min(max);
+        if count == 0 {
+            return 0;
+        }
+        out.reserve(count);
+        for offset in 0..count {
+            let index = self.head.wrapping_add(offset) & self.shared.mask;
+            out.push(unsafe { (*self.shared.buffer.slot(index)).assume_init_read() });
+        }
+        self.head = self.head.wrapping_add(count);
+        self.shared.head.store(self.head, Ordering::Release);
+        count
+    }
+
+    /// Емкость кольца
+    pub fn capacity(&self) -> usize {
+        self.shared.mask + 1
+    }
+
+    /// Событий в кольце
+    pub fn len(&self) -> usize {
+        self.shared
+            .tail
+            .load(Ordering::Acquire)
+            .wrapping_sub(self.head)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,174 @@
+// src/metrics/counters.rs
+use std::fmt;
+use std::ops::AddAssign;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::metrics::histogram::WorkerLatency;
+
+/// Счетчик с одним писателем: увеличивается через load/store без RMW
+#[derive(Debug, Default)]
+pub struct LocalCounter(AtomicU64);
+
+impl LocalCounter {
+    #[inline(always)]
+    pub fn add(&self, value: u64) {
+        self.0.store(
+            self.0.load(Ordering::Relaxed).wrapping_add(value),
+            Ordering::Relaxed,
+        );
+    }
+
+    #[inline(always)]
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    #[inline(always)]
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Счетчики рабочего потока (одна очередь RX)
+///
+/// Пишутся только рабочим потоком, читаются из любого потока.
+/// Выравнивание по кэш-линии исключает ложное разделение между потоками.
+#[derive(Debug, Default)]
+#[repr(C, align(64))]
+pub struct WorkerCounters {
+    /// Принятые пакеты
+    pub rx_packets: LocalCounter,
+    /// Принятые байты (длина кадра)
+    pub rx_bytes: LocalCounter,
+    /// Пакеты, переданные обработчику
+    pub processed: LocalCounter,
+    /// Пакеты, не прошедшие разбор (не IPv4, неподдерживаемый протокол и т.п.)
+    pub parse_errors: LocalCounter,
+    /// Отброшенные пакеты (нет обработчика, фрагмент без сборки, сборка не удалась)
+    pub dropped: LocalCounter,
+    /// Пакеты, не прошедшие фильтр приема порта
+    pub filtered: LocalCounter,
+    /// Суммарное время выполнения обработчиков
+    pub handler_time_ns: LocalCounter,
+    /// Всего вызовов rx_burst
+    pub polls: LocalCounter,
+    /// Вызовы rx_burst без пакетов
+    pub empty_polls: LocalCounter,
+}
+
+impl WorkerCounters {
+    /// Снимок значений счетчиков
+    pub fn snapshot(&self) -> WorkerStats {
+        WorkerStats {
+            rx_packets: self.rx_packets.get(),
+            rx_bytes: self.rx_bytes.get(),
+            processed: self.processed.get(),
+            parse_errors: self.parse_errors.get(),
+            dropped: self.dropped.get(),
+            filtered: self.filtered.get(),
+            handler_time_ns: self.handler_time_ns.get(),
+            polls: self.polls.get(),
+            empty_polls: self.empty_polls.get(),
+        }
+    }
+}
+
+/// Снимок счетчиков рабочего потока (или сумма по нескольким потокам)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WorkerStats {
+    pub rx_packets: u64,
+    pub rx_bytes: u64,
+    pub processed: u64,
+    pub parse_errors: u64,
+    pub dropped: u64,
+    pub filtered: u64,
+    pub handler_time_ns: u64,
+    pub polls: u64,
+    pub empty_polls: u64,
+}
+
+impl WorkerStats {
+    /// Среднее время обработчика на пакет
+    pub fn avg_handler_ns(&self) -> u64 {
+        self.handler_time_ns
+            .checked_div(self.processed)
+            .unwrap_or(0)
+    }
+
+    /// Средний размер непустой пачки
+    pub fn avg_burst(&self) -> f64 {
+        let busy = self.polls.saturating_sub(self.empty_polls);
+        if busy == 0 {
+            0.0
+        } else {
+            self.rx_packets as f64 / busy as f64
+        }
+    }
+
+    /// Счетчики в виде объекта JSON
+    pub fn to_json(self) -> String {
+        format!(
+            "{{\"rx_packets\":{},\"rx_bytes\":{},\"processed\":{},\"parse_errors\":{},\"dropped\":{},\"filtered\":{},\"handler_time_ns\":{},\"avg_handler_ns\":{},\"polls\":{},\"empty_polls\":{},\"avg_burst\":{:.2}}}",
+            self.rx_packets,
+            self.rx_bytes,
+            self.processed,
+            self.parse_errors,
+            self.dropped,
+            self.filtered,
+            self.handler_time_ns,
+            self.avg_handler_ns(),
+            self.polls,
+            self.empty_polls,
+            self.avg_burst()
+        )
+    }
+}
+
+impl AddAssign for WorkerStats {
+    fn add_assign(&mut self, other: Self) {
+        self.rx_packets += other.rx_packets;
+        self.rx_bytes += other.rx_bytes;
+        self.processed += other.processed;
+        self.parse_errors += other.parse_errors;
+        self.dropped += other.dropped;
+        self.filtered += other.filtered;
+        self.handler_time_ns += other.handler_time_ns;
+        self.polls += other.polls;
+        self.empty_polls += other.empty_polls;
+    }
+}
+
+impl fmt::Display for WorkerStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "rx={} bytes={} processed={} parse_errors={} dropped={} filtered={} avg_handler={}ns empty_polls={}/{} avg_burst={:.1}",
+            self.rx_packets,
+            self.rx_bytes,
+            self.processed,
+            self.parse_errors,
+            self.dropped,
+            self.filtered,
+            self.avg_handler_ns(),
+            self.empty_polls,
+            self.polls,
+            self.avg_burst()
+        )
+    }
+}
+
+/// Статистика одной очереди RX
+#[derive(Debug, Clone, Copy)]
+pub struct QueueStats {
+    pub node_id: usize,
+    pub port_id: u16,
+    pub queue_id: u16,
+    pub stats: WorkerStats,
+}
+
+/// Метрики рабочего потока: счетчики и гистограммы задержек
+#[derive(Debug, Default)]
+pub struct WorkerMetrics {
+    pub counters: WorkerCounters,
+    pub latency: WorkerLatency,
+}
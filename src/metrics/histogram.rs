@@ -0,0 +1,213 @@
+// src/metrics/histogram.rs
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Количество линейных корзин для малых значений (0..64 нс с точностью 1 нс)
+const LINEAR_BUCKETS: usize = 64;
+/// Корзин на каждую следующую октаву (относительная точность ~3%)
+const OCTAVE_BUCKETS: usize = 32;
+const OCTAVE_BITS: u32 = 5;
+/// Октав выше линейного диапазона (до 2^64)
+const OCTAVES: usize = 58;
+const BUCKET_COUNT: usize = LINEAR_BUCKETS + OCTAVES * OCTAVE_BUCKETS;
+const COUNTERS_PER_LINE: usize = 8;
+
+/// Кэш-линия счетчиков
+#[repr(C, align(64))]
+struct CounterLine([AtomicU64; COUNTERS_PER_LINE]);
+
+/// Гистограмма задержек в стиле HDR (лог-линейные корзины)
+///
+/// Рассчитана на одного писателя (рабочий поток): запись выполняется
+/// обычными load/store без атомарных RMW операций, поэтому не блокирует шину.
+/// Снимки можно брать из любого потока, не останавливая писателя.
+#[repr(C, align(64))]
+pub struct LatencyHistogram {
+    count: AtomicU64,
+    sum: AtomicU64,
+    max: AtomicU64,
+    lines: Box<[CounterLine]>,
+}
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        let lines = (0..BUCKET_COUNT.div_ceil(COUNTERS_PER_LINE))
+            .map(|_| CounterLine(std::array::from_fn(|_| AtomicU64::new(0))))
+            .collect();
+
+        Self {
+            count: AtomicU64::new(0),
+            sum: AtomicU64::new(0),
+            max: AtomicU64::new(0),
+            lines,
+        }
+    }
+
+    /// Записывает значение (в наносекундах). Вызывается только потоком-владельцем.
+    #[inline(always)]
+    pub fn record(&self, value_ns: u64) {
+        let idx = bucket_index(value_ns);
+        let counter = &self.lines[idx / COUNTERS_PER_LINE].0[idx % COUNTERS_PER_LINE];
+        counter.store(counter.load(Ordering::Relaxed) + 1, Ordering::Relaxed);
+
+        self.sum.store(
+            self.sum.load(Ordering::Relaxed).wrapping_add(value_ns),
+            Ordering::Relaxed,
+        );
+        if value_ns > self.max.load(Ordering::Relaxed) {
+            self.max.store(value_ns, Ordering::Relaxed);
+        }
+        // Счетчик публикуется последним: читатель видит не меньше корзин, чем count
+        self.count
+            .store(self.count.load(Ordering::Relaxed) + 1, Ordering::Release);
+    }
+
+    /// Снимает копию гистограммы без остановки писателя
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        let count = self.count.load(Ordering::Acquire);
+        let counts: Vec<u64> = self
+            .lines
+            .iter()
+            .flat_map(|line| line.0.iter().map(|c| c.load(Ordering::Relaxed)))
+            .take(BUCKET_COUNT)
+            .collect();
+
+        HistogramSnapshot {
+            count: count.min(counts.iter().sum()),
+            sum: self.sum.load(Ordering::Relaxed),
+            max: self.max.load(Ordering::Relaxed),
+            counts,
+        }
+    }
+}
+
+impl fmt::Debug for LatencyHistogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LatencyHistogram")
+            .field("count", &self.count.load(Ordering::Relaxed))
+            .field("max", &self.max.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Снимок гистограммы
+#[derive(Debug, Clone)]
+pub struct HistogramSnapshot {
+    pub count: u64,
+    pub sum: u64,
+    pub max: u64,
+    counts: Vec<u64>,
+}
+
+impl HistogramSnapshot {
+    /// Значение перцентиля `p` (0.0..=100.0); верхняя граница корзины, не больше max
+    pub fn percentile(&self, p: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+
+        let rank = ((p.clamp(0.0, 100.0) / 100.0) * self.count as f64)
+            .ceil()
+            .max(1.0) as u64;
+        let mut seen = 0;
+        for (idx, &c) in self.counts.iter().enumerate() {
+            seen += c;
+            if seen >= rank {
+                return bucket_upper_bound(idx).min(self.max);
+            }
+        }
+
+        self.max
+    }
+
+    pub fn p50(&self) -> u64 {
+        self.percentile(50.0)
+    }
+
+    pub fn p99(&self) -> u64 {
+        self.percentile(99.0)
+    }
+
+    pub fn p999(&self) -> u64 {
+        self.percentile(99.9)
+    }
+
+    pub fn mean(&self) -> u64 {
+        self.sum.checked_div(self.count).unwrap_or(0)
+    }
+
+    /// Перцентили в виде объекта JSON (значения в наносекундах)
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"count\":{},\"mean\":{},\"p50\":{},\"p99\":{},\"p999\":{},\"max\":{}}}",
+            self.count,
+            self.mean(),
+            self.p50(),
+            self.p99(),
+            self.p999(),
+            self.max
+        )
+    }
+
+    /// Объединяет снимки (например, всех рабочих потоков узла)
+    pub fn merge(&mut self, other: &HistogramSnapshot) {
+        self.count += other.count;
+        self.sum = self.sum.wrapping_add(other.sum);
+        self.max = self.max.max(other.max);
+        for (a, b) in self.counts.iter_mut().zip(&other.counts) {
+            *a += b;
+        }
+    }
+}
+
+impl fmt::Display for HistogramSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "n={} p50={}ns p99={}ns p99.9={}ns max={}ns",
+            self.count,
+            self.p50(),
+            self.p99(),
+            self.p999(),
+            self.max
+        )
+    }
+}
+
+#[inline(always)]
+fn bucket_index(value: u64) -> usize {
+    if value < LINEAR_BUCKETS as u64 {
+        return value as usize;
+    }
+
+    let msb = 63 - value.leading_zeros();
+    let shift = msb - OCTAVE_BITS;
+    let mantissa = (value >> shift) as usize - OCTAVE_BUCKETS;
+    LINEAR_BUCKETS + (shift as usize - 1) * OCTAVE_BUCKETS + mantissa
+}
+
+fn bucket_upper_bound(idx: usize) -> u64 {
+    if idx < LINEAR_BUCKETS {
+        return idx as u64;
+    }
+
+    let k = idx - LINEAR_BUCKETS;
+    let shift = (k / OCTAVE_BUCKETS + 1) as u32;
+    let mantissa = (k % OCTAVE_BUCKETS + OCTAVE_BUCKETS) as u64;
+    (mantissa << shift) + ((1u64 << shift) - 1)
+}
+
+/// Гистограммы задержек одного рабочего потока
+#[derive(Debug, Default)]
+pub struct WorkerLatency {
+    /// Время выполнения обработчика
+    pub handler: LatencyHistogram,
+    /// Время от приема пачки до вызова обработчика
+    pub wire_to_handler: LatencyHistogram,
+}
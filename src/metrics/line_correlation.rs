@@ -0,0 +1,226 @@
+// src/metrics/line_correlation.rs
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::metrics::histogram::{HistogramSnapshot, LatencyHistogram};
+use crate::packet::data::PacketView;
+use crate::packet::handler::PacketHandler;
+
+/// Линия фида (основная и резервная)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Line {
+    A,
+    B,
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Line::A => write!(f, "A"),
+            Line::B => write!(f, "B"),
+        }
+    }
+}
+
+/// Извлечение порядкового номера из полезной нагрузки UDP
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SequenceExtractor {
+    pub offset: usize,
+    /// Ширина поля в байтах (4 или 8)
+    pub width: usize,
+    pub big_endian: bool,
+}
+
+impl SequenceExtractor {
+    /// Разбирает описание вида `offset[:u32|u64][:be|le]`, по умолчанию u32 little-endian
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut parts = spec.split(':');
+        let offset = parts
+            .next()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| format!("Invalid sequence offset in '{}'", spec))?;
+
+        let mut extractor = Self {
+            offset,
+            width: 4,
+            big_endian: false,
+        };
+
+        for part in parts {
+            match part {
+                "u32" => extractor.width = 4,
+                "u64" => extractor.width = 8,
+                "be" => extractor.big_endian = true,
+                "le" => extractor.big_endian = false,
+                other => return Err(format!("Invalid sequence field option '{}'", other)),
+            }
+        }
+
+        Ok(extractor)
+    }
+
+    #[inline(always)]
+    pub fn extract(&self, payload: &[u8]) -> Option<u64> {
+        let field = payload.get(self.offset..self.offset + self.width)?;
+        let mut bytes = [0u8; 8];
+
+        if self.big_endian {
+            bytes[8 - self.width..].copy_from_slice(field);
+            Some(u64::from_be_bytes(bytes))
+        } else {
+            bytes[..self.width].copy_from_slice(field);
+            Some(u64::from_le_bytes(bytes))
+        }
+    }
+}
+
+/// Сопоставление копий одного фида, принятых по двум линиям
+///
+/// Пакеты сопоставляются по порядковому номеру; для каждой пары фиксируется,
+/// какая линия доставила копию раньше, и разница отметок времени. Копии без
+/// пары хранятся в окне ограниченного размера.
+pub struct LineCorrelator {
+    pending: HashMap<u64, (Line, u64)>,
+    order: VecDeque<u64>,
+    max_pending: usize,
+    matched: u64,
+    unmatched: u64,
+    a_first: u64,
+    b_first: u64,
+    /// Сумма (t_B - t_A) для средней разницы со знаком
+    delta_sum_ns: i128,
+    /// Абсолютная разница отметок
+    delta_histogram: LatencyHistogram,
+}
+
+impl LineCorrelator {
+    pub fn new(max_pending: usize) -> Self {
+        Self {
+            pending: HashMap::with_capacity(max_pending),
+            order: VecDeque::with_capacity(max_pending),
+            max_pending: max_pending.max(1),
+            matched: 0,
+            unmatched: 0,
+            a_first: 0,
+            b_first: 0,
+            delta_sum_ns: 0,
+            delta_histogram: LatencyHistogram::new(),
+        }
+    }
+
+    /// Учитывает копию пакета; возвращает t_B - t_A, если найдена пара
+    pub fn observe(&mut self, line: Line, sequence: u64, timestamp_ns: u64) -> Option<i64> {
+        match self.pending.get(&sequence) {
+            Some(&(other_line, other_ts)) if other_line != line => {
+                self.pending.remove(&sequence);
+
+                let (a_ts, b_ts) = match line {
+                    Line::A => (timestamp_ns, other_ts),
+                    Line::B => (other_ts, timestamp_ns),
+                };
+                let delta = b_ts as i64 - a_ts as i64;
+
+                self.matched += 1;
+                if delta >= 0 {
+                    self.a_first += 1;
+                } else {
+                    self.b_first += 1;
+                }
+                self.delta_sum_ns += delta as i128;
+                self.delta_histogram.record(delta.unsigned_abs());
+
+                Some(delta)
+            }
+            // Повтор на той же линии: сохраняем первую отметку
+            Some(_) => None,
+            None => {
+                while self.pending.len() >= self.max_pending {
+                    match self.order.pop_front() {
+                        Some(old) => {
+                            if self.pending.remove(&old).is_some() {
+                                self.unmatched += 1;
+                            }
+                        }
+                        None => break,
+                    }
+                }
+
+                self.pending.insert(sequence, (line, timestamp_ns));
+                self.order.push_back(sequence);
+                None
+            }
+        }
+    }
+
+    /// Сводка сопоставления
+    pub fn report(&self) -> LineCorrelationReport {
+        LineCorrelationReport {
+            matched: self.matched,
+            unmatched: self.unmatched,
+            a_first: self.a_first,
+            b_first: self.b_first,
+            mean_delta_ns: if self.matched == 0 {
+                0
+            } else {
+                (self.delta_sum_ns / self.matched as i128) as i64
+            },
+            abs_delta: self.delta_histogram.snapshot(),
+        }
+    }
+}
+
+/// Сводка сопоставления двух линий
+#[derive(Debug, Clone)]
+pub struct LineCorrelationReport {
+    pub matched: u64,
+    /// Копии, для которых пара не пришла в пределах окна
+    pub unmatched: u64,
+    pub a_first: u64,
+    pub b_first: u64,
+    /// Средняя разница t_B - t_A (положительная - линия A быстрее)
+    pub mean_delta_ns: i64,
+    /// Распределение абсолютной разницы
+    pub abs_delta: HistogramSnapshot,
+}
+
+impl fmt::Display for LineCorrelationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let faster = if self.mean_delta_ns >= 0 {
+            Line::A
+        } else {
+            Line::B
+        };
+        write!(
+            f,
+            "matched={} unmatched={} A first={} B first={} line {} faster by {}ns on average, |delta| [{}]",
+            self.matched,
+            self.unmatched,
+            self.a_first,
+            self.b_first,
+            faster,
+            self.mean_delta_ns.unsigned_abs(),
+            self.abs_delta
+        )
+    }
+}
+
+/// Оборачивает обработчик линии: пакет учитывается в сопоставлении и передается дальше
+///
+/// Используется отметка приема пачки (`PacketView::rx_timestamp_ns`).
+pub fn correlating_handler(
+    correlator: Arc<Mutex<LineCorrelator>>,
+    line: Line,
+    extractor: SequenceExtractor,
+    inner: PacketHandler,
+) -> PacketHandler {
+    Arc::new(move |queue_id: u16, packet: &PacketView| {
+        if let Some(sequence) = extractor.extract(packet.data()) {
+            if let Ok(mut correlator) = correlator.lock() {
+                correlator.observe(line, sequence, packet.rx_timestamp_ns());
+            }
+        }
+
+        inner(queue_id, packet);
+    })
+}
@@ -0,0 +1,6 @@
+// src/metrics/mod.rs
+pub mod counters;
+pub mod histogram;
+pub mod line_correlation;
+pub mod shm;
+pub mod tick_to_trade;
@@ -0,0 +1,270 @@
+// src/metrics/shm.rs
+use std::ffi::CString;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::cpu::housekeeping;
+use crate::metrics::counters::WorkerMetrics;
+use crate::metrics::histogram::HistogramSnapshot;
+use crate::time;
+
+/// Сигнатура сегмента ("HFEECSTS")
+pub const STATS_MAGIC: u64 = u64::from_le_bytes(*b"HFEECSTS");
+/// Версия раскладки сегмента
+pub const STATS_VERSION: u32 = 1;
+
+/// Заголовок сегмента статистики
+#[repr(C, align(64))]
+pub struct StatsHeader {
+    pub magic: AtomicU64,
+    pub version: AtomicU32,
+    /// Количество слотов рабочих потоков
+    pub slot_count: AtomicU32,
+    /// Размер слота в байтах (для проверки раскладки читателем)
+    pub slot_size: AtomicU32,
+    /// Время последней публикации (`time::now_ns`)
+    pub publish_ns: AtomicU64,
+}
+
+/// Сводка гистограммы задержек в сегменте
+#[repr(C)]
+pub struct LatencySummary {
+    pub count: AtomicU64,
+    pub p50: AtomicU64,
+    pub p99: AtomicU64,
+    pub p999: AtomicU64,
+    pub max: AtomicU64,
+}
+
+/// Слот рабочего потока
+///
+/// Защищен seqlock: `seq` нечетный во время записи. Читатель копирует слот и
+/// повторяет чтение, если `seq` изменился или был нечетным.
+#[repr(C, align(64))]
+pub struct StatsSlot {
+    pub seq: AtomicU64,
+    pub node_id: AtomicU32,
+    pub port_id: AtomicU32,
+    pub queue_id: AtomicU32,
+    pub rx_packets: AtomicU64,
+    pub rx_bytes: AtomicU64,
+    pub processed: AtomicU64,
+    pub parse_errors: AtomicU64,
+    pub dropped: AtomicU64,
+    pub handler_time_ns: AtomicU64,
+    pub polls: AtomicU64,
+    pub empty_polls: AtomicU64,
+    pub handler_latency: LatencySummary,
+    pub wire_to_handler_latency: LatencySummary,
+}
+
+/// Сегмент статистики в разделяемой памяти (файл, отображенный в память)
+///
+/// Единственный писатель - поток публикации; рабочие потоки сегмента не
+/// касаются, поэтому внешний монитор не влияет на их ядра.
+pub struct StatsSegment {
+    ptr: *mut u8,
+    len: usize,
+    slot_count: usize,
+}
+
+unsafe impl Send for StatsSegment {}
+
+impl StatsSegment {
+    /// Создает (или пересоздает) сегмент по пути `path`, например `/dev/shm/hfeec-stats`
+    pub fn create(path: &str, slot_count: usize) -> Result<Self, String> {
+        let len =
+            std::mem::size_of::<StatsHeader>() + slot_count * std::mem::size_of::<StatsSlot>();
+        let c_path =
+            CString::new(path).map_err(|_| format!("Invalid stats segment path: {}", path))?;
+
+        let ptr = unsafe {
+            let fd = libc::open(c_path.as_ptr(), libc::O_RDWR | libc::O_CREAT, 0o644);
+            if fd < 0 {
+                return Err(format!(
+                    "Failed to open stats segment {}: {}",
+                    path,
+                    std::io::Error::last_os_error()
+                ));
+            }
+
+            if libc::ftruncate(fd, len as libc::off_t) != 0 {
+                let err = std::io::Error::last_os_error();
+                libc::close(fd);
+                return Err(format!("Failed to size stats segment {}: {}", path, err));
+            }
+
+            let ptr = libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                fd,
+                0,
+            );
+            libc::close(fd);
+
+            if ptr == libc::MAP_FAILED {
+                return Err(format!(
+                    "Failed to map stats segment {}: {}",
+                    path,
+                    std::io::Error::last_os_error()
+                ));
+            }
+
+            std::ptr::write_bytes(ptr as *mut u8, 0, len);
+            ptr as *mut u8
+        };
+
+        let segment = Self {
+            ptr,
+            len,
+            slot_count,
+        };
+
+        let header = segment.header();
+        header.version.store(STATS_VERSION, Ordering::Relaxed);
+        header
+            .slot_count
+            .store(slot_count as u32, Ordering::Relaxed);
+        header
+            .slot_size
+            .store(std::mem::size_of::<StatsSlot>() as u32, Ordering::Relaxed);
+        // Сигнатура пишется последней: читатель видит инициализированный заголовок
+        header.magic.store(STATS_MAGIC, Ordering::Release);
+
+        Ok(segment)
+    }
+
+    fn header(&self) -> &StatsHeader {
+        unsafe { &*(self.ptr as *const StatsHeader) }
+    }
+
+    fn slot(&self, idx: usize) -> &StatsSlot {
+        assert!(idx < self.slot_count);
+        unsafe {
+            let base = self.ptr.add(std::mem::size_of::<StatsHeader>()) as *const StatsSlot;
+            &*base.add(idx)
+        }
+    }
+
+    /// Публикует метрики рабочего потока в слот `idx`
+    pub fn publish(
+        &self,
+        idx: usize,
+        node_id: usize,
+        port_id: u16,
+        queue_id: u16,
+        metrics: &WorkerMetrics,
+    ) {
+        let slot = self.slot(idx);
+        let stats = metrics.counters.snapshot();
+        let handler = metrics.latency.handler.snapshot();
+        let wire = metrics.latency.wire_to_handler.snapshot();
+
+        let seq = slot.seq.load(Ordering::Relaxed);
+        slot.seq.store(seq + 1, Ordering::Relaxed);
+        std::sync::atomic::fence(Ordering::Release);
+
+        slot.node_id.store(node_id as u32, Ordering::Relaxed);
+        slot.port_id.store(port_id as u32, Ordering::Relaxed);
+        slot.queue_id.store(queue_id as u32, Ordering::Relaxed);
+        slot.rx_packets.store(stats.rx_packets, Ordering::Relaxed);
+        slot.rx_bytes.store(stats.rx_bytes, Ordering::Relaxed);
+        slot.processed.store(stats.processed, Ordering::Relaxed);
+        slot.parse_errors
+            .store(stats.parse_errors, Ordering::Relaxed);
+        slot.dropped.store(stats.dropped, Ordering::Relaxed);
+        slot.handler_time_ns
+            .store(stats.handler_time_ns, Ordering::Relaxed);
+        slot.polls.store(stats.polls, Ordering::Relaxed);
+        slot.empty_polls.store(stats.empty_polls, Ordering::Relaxed);
+        write_summary(&slot.handler_latency, &handler);
+        write_summary(&slot.wire_to_handler_latency, &wire);
+
+        slot.seq.store(seq + 2, Ordering::Release);
+    }
+
+    /// Отмечает время публикации
+    pub fn mark_published(&self) {
+        self.header()
+            .publish_ns
+            .store(time::now_ns(), Ordering::Release);
+    }
+
+    pub fn slot_count(&self) -> usize {
+        self.slot_count
+    }
+}
+
+impl Drop for StatsSegment {
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.ptr as *mut libc::c_void, self.len) };
+    }
+}
+
+fn write_summary(summary: &LatencySummary, snapshot: &HistogramSnapshot) {
+    summary.count.store(snapshot.count, Ordering::Relaxed);
+    summary.p50.store(snapshot.p50(), Ordering::Relaxed);
+    summary.p99.store(snapshot.p99(), Ordering::Relaxed);
+    summary.p999.store(snapshot.p999(), Ordering::Relaxed);
+    summary.max.store(snapshot.max, Ordering::Relaxed);
+}
+
+/// Метрики рабочего потока с его координатами
+pub type WorkerMetricsRef = (usize, u16, u16, Arc<WorkerMetrics>);
+
+/// Поток публикации статистики в разделяемую память
+pub struct StatsPublisher {
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl StatsPublisher {
+    /// Создает сегмент и запускает периодическую публикацию
+    pub fn start(
+        path: &str,
+        workers: Vec<WorkerMetricsRef>,
+        interval: Duration,
+    ) -> Result<Self, String> {
+        let segment = StatsSegment::create(path, workers.len())?;
+        let running = Arc::new(AtomicBool::new(true));
+        let thread_running = running.clone();
+
+        let thread = thread::Builder::new()
+            .name("hfeec-stats-shm".to_string())
+            .spawn(move || {
+                housekeeping::pin_current_thread();
+
+                while thread_running.load(Ordering::Relaxed) {
+                    for (idx, (node_id, port_id, queue_id, metrics)) in workers.iter().enumerate() {
+                        segment.publish(idx, *node_id, *port_id, *queue_id, metrics);
+                    }
+                    segment.mark_published();
+
+                    thread::sleep(interval);
+                }
+            })
+            .map_err(|e| format!("Failed to spawn stats publisher: {}", e))?;
+
+        Ok(Self {
+            running,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn stop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for StatsPublisher {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -0,0 +1,129 @@
+// src/metrics/tick_to_trade.rs
+use crossbeam::queue::ArrayQueue;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::metrics::histogram::LatencyHistogram;
+use crate::packet::data::PacketView;
+use crate::time;
+
+/// Отметка входящего тика, передаваемая стратегией в отправку заявки
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickToken {
+    /// Порядковый номер тика
+    pub tick_id: u64,
+    pub port_id: u16,
+    pub queue_id: u16,
+    /// Программное время приема пачки (`time::now_ns`)
+    pub rx_ns: u64,
+    /// Аппаратная отметка приема (часы NIC), если доступна
+    pub hw_rx_ns: Option<i64>,
+}
+
+/// Запись задержки тик-заявка
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickToTradeRecord {
+    pub tick: TickToken,
+    /// Идентификатор заявки приложения
+    pub order_id: u64,
+    /// Программное время отправки (`time::now_ns`)
+    pub tx_ns: u64,
+    /// Аппаратная отметка отправки (часы NIC), если доступна
+    pub hw_tx_ns: Option<i64>,
+}
+
+impl TickToTradeRecord {
+    /// Задержка тик-заявка; по аппаратным отметкам, если доступны обе
+    pub fn latency_ns(&self) -> u64 {
+        match (self.tick.hw_rx_ns, self.hw_tx_ns) {
+            (Some(rx), Some(tx)) if tx >= rx => (tx - rx) as u64,
+            _ => self.tx_ns.saturating_sub(self.tick.rx_ns),
+        }
+    }
+
+    /// Получена ли задержка по аппаратным отметкам
+    pub fn is_hardware(&self) -> bool {
+        matches!((self.tick.hw_rx_ns, self.hw_tx_ns), (Some(rx), Some(tx)) if tx >= rx)
+    }
+}
+
+/// Трекер задержки тик-заявка
+///
+/// Связывает отметку входящего пакета рыночных данных с последующей отправкой
+/// заявки. Записи попадают в ограниченную очередь без блокировок, откуда их
+/// забирает потребитель (журнал, экспорт); при переполнении записи
+/// отбрасываются и учитываются в `dropped`, гистограмма обновляется всегда.
+pub struct TickToTradeTracker {
+    next_tick: AtomicU64,
+    records: ArrayQueue<TickToTradeRecord>,
+    dropped: AtomicU64,
+    histogram: LatencyHistogram,
+}
+
+impl TickToTradeTracker {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            next_tick: AtomicU64::new(0),
+            records: ArrayQueue::new(capacity.max(1)),
+            dropped: AtomicU64::new(0),
+            histogram: LatencyHistogram::new(),
+        }
+    }
+
+    /// Отмечает входящий пакет, ставший поводом для заявки
+    #[inline(always)]
+    pub fn mark_tick(&self, port_id: u16, packet: &PacketView, hw_rx_ns: Option<i64>) -> TickToken {
+        TickToken {
+            tick_id: self.next_tick.fetch_add(1, Ordering::Relaxed),
+            port_id,
+            queue_id: packet.queue_id(),
+            rx_ns: packet.rx_timestamp_ns(),
+            hw_rx_ns,
+        }
+    }
+
+    /// Записывает отправку заявки, вызванной тиком `tick`
+    ///
+    /// `hw_tx_ns` - аппаратная отметка отправки (например, `PortClock::read_tx_timestamp`).
+    /// Гистограмма рассчитана на одного писателя: при отправке заявок из
+    /// нескольких потоков каждому потоку нужен свой трекер.
+    #[inline(always)]
+    pub fn record_order(
+        &self,
+        tick: TickToken,
+        order_id: u64,
+        hw_tx_ns: Option<i64>,
+    ) -> TickToTradeRecord {
+        let record = TickToTradeRecord {
+            tick,
+            order_id,
+            tx_ns: time::now_ns(),
+            hw_tx_ns,
+        };
+
+        self.histogram.record(record.latency_ns());
+        if self.records.push(record).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+
+        record
+    }
+
+    /// Забирает накопленные записи
+    pub fn drain(&self, out: &mut Vec<TickToTradeRecord>) -> usize {
+        let before = out.len();
+        while let Some(record) = self.records.pop() {
+            out.push(record);
+        }
+        out.len() - before
+    }
+
+    /// Количество записей, отброшенных из-за переполнения очереди
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Гистограмма задержек тик-заявка
+    pub fn histogram(&self) -> &LatencyHistogram {
+        &self.histogram
+    }
+}
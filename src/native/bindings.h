@@ -0,0 +1,13 @@
+/**
+ * Заголовки DPDK, из которых build.rs генерирует привязки (модуль dpdk::ffi::sys)
+ *
+ * В привязки попадают только структуры с версионно-зависимой раскладкой
+ * (rte_eth_conf и вложенные), константы флагов и функции, принимающие эти
+ * структуры. Остальное объявляется в ffi.rs вручную или через dpdk.c.
+ */
+#include <rte_ethdev.h>
+#include <rte_ether.h>
+#include <rte_mbuf.h>
+
+/* Новые имена констант для DPDK 20.11 */
+#include "compat.h"
@@ -1,110 +1,112 @@
 #include <rte_eal.h>
+#include <rte_dev.h>
 #include <rte_ethdev.h>
+#include <rte_eventdev.h>
 #include <rte_mbuf.h>
+#include <rte_mbuf_dyn.h>
+#include <rte_memory.h>
+#include <rte_service.h>
 #include <rte_ip.h>
 #include <rte_tcp.h>
 #include <rte_udp.h>
 #include <rte_ether.h>
+#include <rte_flow.h>
+#include <rte_interrupts.h>
+#include <rte_errno.h>
 #include <string.h>
 #include <stdio.h>
 #include <stdlib.h>
 #include <arpa/inet.h>
+#include <errno.h>
+
+#include "compat.h"
 
 /**
- * Извлекает информацию и данные из пакета DPDK для передачи в Rust
- * 
- * @param pkt Указатель на структуру пакета DPDK
- * @param src_ip_out Указатель на буфер для записи IP-адреса источника
- * @param dst_ip_out Указатель на буфер для записи IP-адреса назначения
- * @param src_port_out Указатель на переменную для записи порта источника
- * @param dst_port_out Указатель на переменную для записи порта назначения
- * @param data_out Указатель на переменную для указателя на данные пакета
- * @param data_len_out Указатель на переменную для длины данных
- * @return 0 в случае успеха, ненулевое значение в случае ошибки
- */
-int dpdk_extract_packet_data(
-    const struct rte_mbuf *pkt,
-    uint8_t **src_ip_out,
-    uint32_t *src_ip_len_out,
-    uint8_t **dst_ip_out,
-    uint32_t *dst_ip_len_out,
-    uint16_t *src_port_out,
-    uint16_t *dst_port_out,
-    uint8_t **data_out,
-    uint32_t *data_len_out
-) {
-    if (!pkt || !src_ip_out || !src_ip_len_out || !dst_ip_out || !dst_ip_len_out || 
-        !src_port_out || !dst_port_out || !data_out || !data_len_out) {
-        return -1;
-    }
-    
-    *src_port_out = 0;
-    *dst_port_out = 0;
-    *data_out = NULL;
-    *data_len_out = 0;
-    *src_ip_out = NULL;
-    *src_ip_len_out = 0;
-    *dst_ip_out = NULL;
-    *dst_ip_len_out = 0;
-    
-    struct rte_ether_hdr *eth_hdr = rte_pktmbuf_mtod(pkt, struct rte_ether_hdr *);
-    
-    if (rte_be_to_cpu_16(eth_hdr->ether_type) != RTE_ETHER_TYPE_IPV4) {
-        return -2;
+ * Возможности и ограничения порта, копируемые из rte_eth_dev_info
+ * (структура rte_eth_dev_info меняется между версиями DPDK, поэтому
+ * в Rust передается только плоский набор нужных полей)
+ */
+struct dpdk_port_caps {
+    uint16_t max_rx_queues;
+    uint16_t max_tx_queues;
+    uint16_t rx_desc_max;
+    uint16_t rx_desc_min;
+    uint16_t tx_desc_max;
+    uint16_t tx_desc_min;
+    uint32_t max_rx_pktlen;
+    uint64_t rx_offload_capa;
+    uint64_t tx_offload_capa;
+    uint64_t flow_type_rss_offloads;
+    uint16_t reta_size;
+    uint8_t hash_key_size;
+};
+
+/**
+ * Получает возможности порта через rte_eth_dev_info_get
+ *
+ * @param port_id Идентификатор порта
+ * @param caps Указатель на структуру для записи результата
+ * @return 0 в случае успеха, отрицательный код ошибки DPDK иначе
+ */
+int dpdk_get_port_caps(uint16_t port_id, struct dpdk_port_caps *caps) {
+    if (caps == NULL) {
+        return -EINVAL;
     }
-    
-    struct rte_ipv4_hdr *ip_hdr = (struct rte_ipv4_hdr *)(eth_hdr + 1);
-    
-    *src_ip_out = (uint8_t *)&ip_hdr->src_addr;
-    *src_ip_len_out = sizeof(ip_hdr->src_addr);
-    *dst_ip_out = (uint8_t *)&ip_hdr->dst_addr;
-    *dst_ip_len_out = sizeof(ip_hdr->dst_addr);
-    
-    uint16_t payload_offset = 0;
-    
-    if (ip_hdr->next_proto_id == IPPROTO_TCP) {
-        struct rte_tcp_hdr *tcp_hdr = (struct rte_tcp_hdr *)((uint8_t *)ip_hdr + 
-                                    (ip_hdr->version_ihl & 0x0f) * 4);
-        
-        *src_port_out = rte_be_to_cpu_16(tcp_hdr->src_port);
-        *dst_port_out = rte_be_to_cpu_16(tcp_hdr->dst_port);
-        
-        uint8_t tcp_header_size = ((tcp_hdr->data_off & 0xf0) >> 4) * 4;
-        payload_offset = ((ip_hdr->version_ihl & 0x0f) * 4) + tcp_header_size;
-    } 
-    else if (ip_hdr->next_proto_id == IPPROTO_UDP) {
-        struct rte_udp_hdr *udp_hdr = (struct rte_udp_hdr *)((uint8_t *)ip_hdr + 
-                                    (ip_hdr->version_ihl & 0x0f) * 4);
-        
-        *src_port_out = rte_be_to_cpu_16(udp_hdr->src_port);
-        *dst_port_out = rte_be_to_cpu_16(udp_hdr->dst_port);
-        
-        payload_offset = ((ip_hdr->version_ihl & 0x0f) * 4) + 8;
+
+    struct rte_eth_dev_info dev_info;
+    int ret = rte_eth_dev_info_get(port_id, &dev_info);
+    if (ret != 0) {
+        return ret;
     }
-    else {
-        return -3;
+
+    caps->max_rx_queues = dev_info.max_rx_queues;
+    caps->max_tx_queues = dev_info.max_tx_queues;
+    caps->rx_desc_max = dev_info.rx_desc_lim.nb_max;
+    caps->rx_desc_min = dev_info.rx_desc_lim.nb_min;
+    caps->tx_desc_max = dev_info.tx_desc_lim.nb_max;
+    caps->tx_desc_min = dev_info.tx_desc_lim.nb_min;
+    caps->max_rx_pktlen = dev_info.max_rx_pktlen;
+    caps->rx_offload_capa = dev_info.rx_offload_capa;
+    caps->tx_offload_capa = dev_info.tx_offload_capa;
+    caps->flow_type_rss_offloads = dev_info.flow_type_rss_offloads;
+    caps->reta_size = dev_info.reta_size;
+    caps->hash_key_size = dev_info.hash_key_size;
+
+    return 0;
+}
+
+/**
+ * Останавливает и закрывает порт, затем отключает его устройство
+ *
+ * Указатель rte_device доступен только через rte_eth_dev_info и должен быть
+ * получен до rte_eth_dev_close (после закрытия порт освобождается).
+ *
+ * @param port_id Идентификатор порта
+ * @return 0 в случае успеха, отрицательный код ошибки DPDK иначе
+ */
+int dpdk_port_detach(uint16_t port_id) {
+    struct rte_eth_dev_info dev_info;
+    int ret = rte_eth_dev_info_get(port_id, &dev_info);
+    if (ret != 0) {
+        return ret;
     }
-    
-    uint16_t ip_total_length = rte_be_to_cpu_16(ip_hdr->total_length);
-    uint32_t payload_length = 0;
-    
-    if (ip_total_length > payload_offset) {
-        payload_length = ip_total_length - payload_offset;
+
+    struct rte_device *dev = (struct rte_device *)dev_info.device;
+    if (dev == NULL) {
+        return -ENODEV;
     }
-    else {
-        return -4;
+
+    ret = rte_eth_dev_stop(port_id);
+    if (ret != 0) {
+        return ret;
     }
-    
-    if (payload_length > 0) {
-        uint8_t *payload = (uint8_t *)ip_hdr + payload_offset;
-        
-        *data_out = payload;
-        *data_len_out = payload_length;
-        
-        return 0;
+
+    ret = rte_eth_dev_close(port_id);
+    if (ret != 0) {
+        return ret;
     }
-    
-    return -5;
+
+    return rte_dev_remove(dev);
 }
 
 /**
@@ -254,4 +256,837 @@ struct rte_mbuf* dpdk_create_packet(
     }
     
     return mbuf;
-}
\ No newline at end of file
+}
+/**
+ * Плоское описание правила rte_flow (зеркало FlowSpec из src/dpdk/flow.rs)
+ *
+ * Шаблон: ETH / IPV4 / [UDP|TCP]; поля с нулевой маской не сравниваются.
+ * Адреса и порты передаются в сетевом порядке байт.
+ */
+struct dpdk_flow_spec {
+    uint32_t priority;
+    uint32_t dst_ip;
+    uint32_t dst_ip_mask;
+    uint32_t src_ip;
+    uint32_t src_ip_mask;
+    uint16_t dst_port;
+    uint16_t dst_port_mask;
+    /* IPPROTO_UDP, IPPROTO_TCP или 0 - только IPv4 */
+    uint8_t l4_proto;
+    /* 1 - отбросить пакет, 0 - направить в очередь queue_index */
+    uint8_t drop;
+    uint16_t queue_index;
+    /* 1 - пометить пакет значением mark_id (читается из mbuf) */
+    uint8_t has_mark;
+    uint32_t mark_id;
+};
+
+/**
+ * Создает правило rte_flow по плоскому описанию
+ *
+ * @param port_id Идентификатор порта
+ * @param spec Описание правила
+ * @param validate_only 1 - только проверить правило (rte_flow_validate)
+ * @param flow Указатель для записи созданного правила
+ * @param error_msg Буфер для сообщения об ошибке PMD
+ * @param error_len Размер буфера
+ * @return 0 в случае успеха, отрицательный код ошибки иначе
+ */
+int dpdk_flow_create(
+    uint16_t port_id,
+    const struct dpdk_flow_spec *spec,
+    int validate_only,
+    struct rte_flow **flow,
+    char *error_msg,
+    size_t error_len
+) {
+    if (spec == NULL || flow == NULL) {
+        return -EINVAL;
+    }
+
+    struct rte_flow_attr attr;
+    memset(&attr, 0, sizeof(attr));
+    attr.ingress = 1;
+    attr.priority = spec->priority;
+
+    struct rte_flow_item_ipv4 ip_spec, ip_mask;
+    memset(&ip_spec, 0, sizeof(ip_spec));
+    memset(&ip_mask, 0, sizeof(ip_mask));
+    ip_spec.hdr.dst_addr = spec->dst_ip;
+    ip_mask.hdr.dst_addr = spec->dst_ip_mask;
+    ip_spec.hdr.src_addr = spec->src_ip;
+    ip_mask.hdr.src_addr = spec->src_ip_mask;
+
+    struct rte_flow_item_udp udp_spec, udp_mask;
+    memset(&udp_spec, 0, sizeof(udp_spec));
+    memset(&udp_mask, 0, sizeof(udp_mask));
+    udp_spec.hdr.dst_port = spec->dst_port;
+    udp_mask.hdr.dst_port = spec->dst_port_mask;
+
+    struct rte_flow_item_tcp tcp_spec, tcp_mask;
+    memset(&tcp_spec, 0, sizeof(tcp_spec));
+    memset(&tcp_mask, 0, sizeof(tcp_mask));
+    tcp_spec.hdr.dst_port = spec->dst_port;
+    tcp_mask.hdr.dst_port = spec->dst_port_mask;
+
+    struct rte_flow_item pattern[4];
+    memset(pattern, 0, sizeof(pattern));
+    int n = 0;
+    pattern[n++].type = RTE_FLOW_ITEM_TYPE_ETH;
+    pattern[n].type = RTE_FLOW_ITEM_TYPE_IPV4;
+    pattern[n].spec = &ip_spec;
+    pattern[n].mask = &ip_mask;
+    n++;
+    if (spec->l4_proto == IPPROTO_UDP) {
+        pattern[n].type = RTE_FLOW_ITEM_TYPE_UDP;
+        pattern[n].spec = &udp_spec;
+        pattern[n].mask = &udp_mask;
+        n++;
+    } else if (spec->l4_proto == IPPROTO_TCP) {
+        pattern[n].type = RTE_FLOW_ITEM_TYPE_TCP;
+        pattern[n].spec = &tcp_spec;
+        pattern[n].mask = &tcp_mask;
+        n++;
+    }
+    pattern[n].type = RTE_FLOW_ITEM_TYPE_END;
+
+    struct rte_flow_action_mark mark;
+    memset(&mark, 0, sizeof(mark));
+    mark.id = spec->mark_id;
+
+    struct rte_flow_action_queue queue;
+    memset(&queue, 0, sizeof(queue));
+    queue.index = spec->queue_index;
+
+    struct rte_flow_action actions[3];
+    memset(actions, 0, sizeof(actions));
+    int a = 0;
+    if (spec->has_mark) {
+        actions[a].type = RTE_FLOW_ACTION_TYPE_MARK;
+        actions[a].conf = &mark;
+        a++;
+    }
+    if (spec->drop) {
+        actions[a++].type = RTE_FLOW_ACTION_TYPE_DROP;
+    } else {
+        actions[a].type = RTE_FLOW_ACTION_TYPE_QUEUE;
+        actions[a].conf = &queue;
+        a++;
+    }
+    actions[a].type = RTE_FLOW_ACTION_TYPE_END;
+
+    struct rte_flow_error error;
+    memset(&error, 0, sizeof(error));
+
+    int ret = rte_flow_validate(port_id, &attr, pattern, actions, &error);
+    if (ret == 0 && !validate_only) {
+        *flow = rte_flow_create(port_id, &attr, pattern, actions, &error);
+        if (*flow == NULL) {
+            ret = -rte_errno;
+        }
+    }
+
+    if (ret != 0 && error_msg != NULL && error_len > 0) {
+        snprintf(error_msg, error_len, "%s", error.message ? error.message : "unspecified error");
+    }
+
+    return ret;
+}
+
+/**
+ * Удаляет правило rte_flow
+ *
+ * @param port_id Идентификатор порта
+ * @param flow Правило, созданное dpdk_flow_create
+ * @return 0 в случае успеха, отрицательный код ошибки DPDK иначе
+ */
+int dpdk_flow_destroy(uint16_t port_id, struct rte_flow *flow) {
+    struct rte_flow_error error;
+    return rte_flow_destroy(port_id, flow, &error);
+}
+
+/**
+ * Читает метку rte_flow (действие MARK) из принятого пакета
+ *
+ * @param mbuf Принятый пакет
+ * @param mark Указатель для записи метки
+ * @return 1, если пакет помечен, 0 иначе
+ */
+int dpdk_mbuf_flow_mark(const struct rte_mbuf *mbuf, uint32_t *mark) {
+    if (mbuf == NULL || !(mbuf->ol_flags & RTE_MBUF_F_RX_FDIR_ID)) {
+        return 0;
+    }
+
+    *mark = mbuf->hash.fdir.hi;
+    return 1;
+}
+
+/* Смещение динамического поля отметки времени приема и флаг ее наличия */
+static int rx_timestamp_offset = -1;
+static uint64_t rx_timestamp_flag = 0;
+
+/**
+ * Регистрирует динамическое поле mbuf для аппаратной отметки времени приема
+ *
+ * Вызывается до запуска портов с RTE_ETH_RX_OFFLOAD_TIMESTAMP. Повторный
+ * вызов возвращает уже зарегистрированное поле.
+ *
+ * @return 0 при успехе, отрицательный код ошибки иначе
+ */
+int dpdk_rx_timestamp_register(void) {
+    if (rx_timestamp_offset >= 0) {
+        return 0;
+    }
+
+    int ret = rte_mbuf_dyn_rx_timestamp_register(&rx_timestamp_offset, &rx_timestamp_flag);
+    if (ret < 0) {
+        rx_timestamp_offset = -1;
+        return -rte_errno;
+    }
+    return 0;
+}
+
+/**
+ * Читает аппаратную отметку времени приема пакета (часы NIC, обычно нс)
+ *
+ * @param mbuf Принятый пакет
+ * @param timestamp Указатель для записи отметки
+ * @return 1, если NIC поставил отметку, 0 иначе
+ */
+int dpdk_mbuf_rx_timestamp(const struct rte_mbuf *mbuf, uint64_t *timestamp) {
+    if (mbuf == NULL || rx_timestamp_offset < 0 || !(mbuf->ol_flags & rx_timestamp_flag)) {
+        return 0;
+    }
+
+    *timestamp = *RTE_MBUF_DYNFIELD(mbuf, rx_timestamp_offset, const rte_mbuf_timestamp_t *);
+    return 1;
+}
+
+/* Смещение динамического поля времени отправки и флаг отправки по времени */
+static int tx_timestamp_offset = -1;
+static uint64_t tx_timestamp_flag = 0;
+
+/**
+ * Регистрирует динамическое поле и флаг mbuf для отправки по времени
+ *
+ * Вызывается до запуска портов с RTE_ETH_TX_OFFLOAD_SEND_ON_TIMESTAMP.
+ * Поле общее с отметкой приема (rte_dynfield_timestamp), флаг - свой.
+ * Повторный вызов возвращает уже зарегистрированное поле.
+ *
+ * @return 0 при успехе, отрицательный код ошибки иначе
+ */
+int dpdk_tx_timestamp_register(void) {
+    if (tx_timestamp_offset >= 0) {
+        return 0;
+    }
+
+    int ret = rte_mbuf_dyn_tx_timestamp_register(&tx_timestamp_offset, &tx_timestamp_flag);
+    if (ret < 0) {
+        tx_timestamp_offset = -1;
+        return -rte_errno;
+    }
+    return 0;
+}
+
+/**
+ * Задает время отправки пакета по часам NIC
+ *
+ * @param mbuf Пакет перед rte_eth_tx_burst
+ * @param timestamp Время отправки (часы NIC, см. rte_eth_read_clock)
+ * @return 0 при успехе, -EINVAL если поле не зарегистрировано
+ */
+int dpdk_mbuf_set_tx_timestamp(struct rte_mbuf *mbuf, uint64_t timestamp) {
+    if (mbuf == NULL || tx_timestamp_offset < 0) {
+        return -EINVAL;
+    }
+
+    *RTE_MBUF_DYNFIELD(mbuf, tx_timestamp_offset, rte_mbuf_timestamp_t *) = timestamp;
+    mbuf->ol_flags |= tx_timestamp_flag;
+    return 0;
+}
+
+/**
+ * Запрашивает аппаратную отметку времени отправки пакета (IEEE 1588)
+ *
+ * Отметка читается rte_eth_timesync_read_tx_timestamp после отправки;
+ * NIC хранит только последнюю отметку.
+ */
+void dpdk_mbuf_request_tx_timestamp(struct rte_mbuf *mbuf) {
+    if (mbuf != NULL) {
+        mbuf->ol_flags |= RTE_MBUF_F_TX_IEEE1588_TMST;
+    }
+}
+
+/**
+ * Счетчик ссылок mbuf (rte_mbuf_refcnt_read - inline функция)
+ */
+uint16_t dpdk_mbuf_refcnt(const struct rte_mbuf *mbuf) {
+    return rte_mbuf_refcnt_read(mbuf);
+}
+
+/**
+ * Изменяет счетчик ссылок каждого сегмента цепочки mbuf
+ * (rte_pktmbuf_refcnt_update - inline функция)
+ */
+void dpdk_mbuf_refcnt_update(struct rte_mbuf *mbuf, int16_t value) {
+    rte_pktmbuf_refcnt_update(mbuf, value);
+}
+
+/**
+ * Длина всего пакета по цепочке сегментов (поле pkt_len первого сегмента)
+ */
+uint32_t dpdk_mbuf_pkt_len(const struct rte_mbuf *mbuf) {
+    return rte_pktmbuf_pkt_len(mbuf);
+}
+
+/**
+ * Следующий сегмент цепочки или NULL для последнего
+ */
+struct rte_mbuf *dpdk_mbuf_next(const struct rte_mbuf *mbuf) {
+    return mbuf->next;
+}
+
+/**
+ * Начало данных сегмента mbuf (rte_pktmbuf_mtod - макрос)
+ */
+void *dpdk_mbuf_mtod(const struct rte_mbuf *mbuf) {
+    return rte_pktmbuf_mtod(mbuf, void *);
+}
+
+/**
+ * Длина данных одного сегмента mbuf (rte_pktmbuf_data_len - макрос)
+ */
+uint16_t dpdk_mbuf_data_len(const struct rte_mbuf *mbuf) {
+    return rte_pktmbuf_data_len(mbuf);
+}
+
+/**
+ * Возвращает в пул все сегменты цепочки mbuf (rte_pktmbuf_free - inline функция)
+ */
+void dpdk_mbuf_free(struct rte_mbuf *mbuf) {
+    rte_pktmbuf_free(mbuf);
+}
+
+/**
+ * Выделяет mbuf из пула (rte_pktmbuf_alloc - inline функция)
+ */
+struct rte_mbuf *dpdk_mbuf_alloc(struct rte_mempool *pool) {
+    return rte_pktmbuf_alloc(pool);
+}
+
+/**
+ * Добавляет len байт в конец данных mbuf (rte_pktmbuf_append - inline функция)
+ *
+ * @return Указатель на добавленную область или NULL, если не хватает места
+ */
+char *dpdk_mbuf_append(struct rte_mbuf *mbuf, uint16_t len) {
+    return rte_pktmbuf_append(mbuf, len);
+}
+
+/**
+ * Копирует буфер в цепочку mbuf из пула
+ *
+ * Кадр больше сегмента пула (TSO/UFO до 64 КБ) занимает несколько
+ * сегментов; заголовки всегда помещаются в первый сегмент.
+ *
+ * @return Цепочка mbuf или NULL, если пул исчерпан
+ */
+struct rte_mbuf *dpdk_mbuf_from_buffer(struct rte_mempool *pool, const uint8_t *data, uint32_t len) {
+    struct rte_mbuf *head = NULL;
+    uint32_t copied = 0;
+
+    do {
+        struct rte_mbuf *seg = rte_pktmbuf_alloc(pool);
+        if (seg == NULL) {
+            rte_pktmbuf_free(head);
+            return NULL;
+        }
+
+        uint32_t room = rte_pktmbuf_tailroom(seg);
+        uint32_t chunk = (len - copied < room) ? len - copied : room;
+        if (chunk == 0 && copied < len) {
+            rte_pktmbuf_free(seg);
+            rte_pktmbuf_free(head);
+            return NULL;
+        }
+        memcpy(rte_pktmbuf_append(seg, (uint16_t)chunk), data + copied, chunk);
+        copied += chunk;
+
+        if (head == NULL) {
+            head = seg;
+        } else if (rte_pktmbuf_chain(head, seg) < 0) {
+            rte_pktmbuf_free(seg);
+            rte_pktmbuf_free(head);
+            return NULL;
+        }
+    } while (copied < len);
+
+    return head;
+}
+
+/**
+ * Задает метаданные аппаратной сегментации (TSO или UDP GSO) кадра IPv4
+ *
+ * Обнуляет контрольную сумму IPv4 и записывает в поле контрольной суммы
+ * L4 сумму псевдозаголовка без длины, как требует TSO.
+ *
+ * @param mbuf Кадр: заголовки L2-L4 в первом сегменте
+ * @param l2_len Длина заголовка L2 (с VLAN тегами)
+ * @param l3_len Длина заголовка IPv4
+ * @param l4_len Длина заголовка TCP или UDP
+ * @param segment_size Данных в одном сегменте (MSS)
+ * @param udp 1 - UDP GSO, 0 - TCP TSO
+ * @return 0 при успехе, -EINVAL если заголовки не в первом сегменте
+ */
+int dpdk_mbuf_set_tso(struct rte_mbuf *mbuf, uint16_t l2_len, uint16_t l3_len,
+                      uint16_t l4_len, uint16_t segment_size, int udp) {
+    if (mbuf == NULL || rte_pktmbuf_data_len(mbuf) < l2_len + l3_len + l4_len) {
+        return -EINVAL;
+    }
+
+    mbuf->l2_len = l2_len;
+    mbuf->l3_len = l3_len;
+    mbuf->l4_len = l4_len;
+    mbuf->tso_segsz = segment_size;
+    mbuf->ol_flags |= RTE_MBUF_F_TX_IPV4 | RTE_MBUF_F_TX_IP_CKSUM |
+                      (udp ? RTE_MBUF_F_TX_UDP_SEG : RTE_MBUF_F_TX_TCP_SEG);
+
+    struct rte_ipv4_hdr *ip = rte_pktmbuf_mtod_offset(mbuf, struct rte_ipv4_hdr *, l2_len);
+    ip->hdr_checksum = 0;
+    uint16_t phdr_cksum = rte_ipv4_phdr_cksum(ip, mbuf->ol_flags);
+    if (udp) {
+        rte_pktmbuf_mtod_offset(mbuf, struct rte_udp_hdr *, l2_len + l3_len)->dgram_cksum = phdr_cksum;
+    } else {
+        rte_pktmbuf_mtod_offset(mbuf, struct rte_tcp_hdr *, l2_len + l3_len)->cksum = phdr_cksum;
+    }
+    return 0;
+}
+
+/**
+ * Записывает таблицу перенаправления RSS (RETA)
+ *
+ * Структура rte_eth_rss_reta_entry64 группирует записи по 64, поэтому
+ * в Rust передается плоский массив очередей длиной reta_size.
+ *
+ * @param port_id Идентификатор порта
+ * @param queues Очередь RX для каждой корзины хэша
+ * @param reta_size Размер таблицы (dev_info.reta_size)
+ * @return 0 в случае успеха, отрицательный код ошибки DPDK иначе
+ */
+int dpdk_rss_reta_update(uint16_t port_id, const uint16_t *queues, uint16_t reta_size) {
+    struct rte_eth_rss_reta_entry64 reta_conf[RTE_ETH_RSS_RETA_SIZE_512 / RTE_ETH_RETA_GROUP_SIZE];
+
+    if (queues == NULL || reta_size == 0 || reta_size > RTE_ETH_RSS_RETA_SIZE_512) {
+        return -EINVAL;
+    }
+
+    memset(reta_conf, 0, sizeof(reta_conf));
+    for (uint16_t i = 0; i < reta_size; i++) {
+        uint16_t group = i / RTE_ETH_RETA_GROUP_SIZE;
+        uint16_t shift = i % RTE_ETH_RETA_GROUP_SIZE;
+        reta_conf[group].mask |= 1ULL << shift;
+        reta_conf[group].reta[shift] = queues[i];
+    }
+
+    return rte_eth_dev_rss_reta_update(port_id, reta_conf, reta_size);
+}
+
+/**
+ * Читает таблицу перенаправления RSS (RETA)
+ *
+ * @param port_id Идентификатор порта
+ * @param queues Массив длиной reta_size для записи очередей
+ * @param reta_size Размер таблицы (dev_info.reta_size)
+ * @return 0 в случае успеха, отрицательный код ошибки DPDK иначе
+ */
+int dpdk_rss_reta_query(uint16_t port_id, uint16_t *queues, uint16_t reta_size) {
+    struct rte_eth_rss_reta_entry64 reta_conf[RTE_ETH_RSS_RETA_SIZE_512 / RTE_ETH_RETA_GROUP_SIZE];
+
+    if (queues == NULL || reta_size == 0 || reta_size > RTE_ETH_RSS_RETA_SIZE_512) {
+        return -EINVAL;
+    }
+
+    memset(reta_conf, 0, sizeof(reta_conf));
+    for (uint16_t i = 0; i < reta_size; i++) {
+        reta_conf[i / RTE_ETH_RETA_GROUP_SIZE].mask |= 1ULL << (i % RTE_ETH_RETA_GROUP_SIZE);
+    }
+
+    int ret = rte_eth_dev_rss_reta_query(port_id, reta_conf, reta_size);
+    if (ret != 0) {
+        return ret;
+    }
+
+    for (uint16_t i = 0; i < reta_size; i++) {
+        queues[i] = reta_conf[i / RTE_ETH_RETA_GROUP_SIZE].reta[i % RTE_ETH_RETA_GROUP_SIZE];
+    }
+
+    return 0;
+}
+
+/**
+ * Создает пул mbuf с буферами данных во внешней (не EAL) памяти
+ *
+ * Область регистрируется в DPDK (rte_extmem_register) и отображается для DMA
+ * устройства порта. Поддерживается только режим IOVA as VA: виртуальный
+ * адрес области используется как IOVA.
+ *
+ * @param port_id Порт, устройство которого получает доступ к памяти
+ * @param name Имя пула
+ * @param n Количество mbuf
+ * @param cache_size Размер кэша пула на ядро
+ * @param data_room_size Размер буфера данных (он же размер элемента области)
+ * @param socket_id Узел NUMA для структур mbuf
+ * @param addr Адрес области, выровненный по странице
+ * @param len Длина области, кратная page_size
+ * @param page_size Размер страницы области
+ * @param pool Указатель для записи созданного пула
+ * @return 0 в случае успеха, отрицательный код ошибки иначе
+ */
+int dpdk_extbuf_pool_create(
+    uint16_t port_id,
+    const char *name,
+    unsigned int n,
+    unsigned int cache_size,
+    uint16_t data_room_size,
+    int socket_id,
+    void *addr,
+    size_t len,
+    size_t page_size,
+    struct rte_mempool **pool
+) {
+    if (name == NULL || addr == NULL || pool == NULL || data_room_size == 0) {
+        return -EINVAL;
+    }
+    if (rte_eal_iova_mode() != RTE_IOVA_VA) {
+        return -ENOTSUP;
+    }
+
+    struct rte_eth_dev_info dev_info;
+    int ret = rte_eth_dev_info_get(port_id, &dev_info);
+    if (ret != 0) {
+        return ret;
+    }
+
+    if (rte_extmem_register(addr, len, NULL, 0, page_size) != 0) {
+        return -rte_errno;
+    }
+
+    rte_iova_t iova = (rte_iova_t)(uintptr_t)addr;
+    if (rte_dev_dma_map(dev_info.device, addr, iova, len) != 0) {
+        ret = -rte_errno;
+        rte_extmem_unregister(addr, len);
+        return ret;
+    }
+
+    struct rte_pktmbuf_extmem ext_mem = {
+        .buf_ptr = addr,
+        .buf_iova = iova,
+        .buf_len = len,
+        .elt_size = data_room_size,
+    };
+
+    *pool = rte_pktmbuf_pool_create_extbuf(
+        name, n, cache_size, 0, data_room_size, socket_id, &ext_mem, 1);
+    if (*pool == NULL) {
+        ret = -rte_errno;
+        rte_dev_dma_unmap(dev_info.device, addr, iova, len);
+        rte_extmem_unregister(addr, len);
+        return ret;
+    }
+
+    return 0;
+}
+
+/* Наибольшая пачка событий за один вызов enqueue/dequeue */
+#define DPDK_EVENT_BURST_MAX 512
+
+/* Событий в устройстве одновременно (меньше, если устройство не вмещает) */
+#define DPDK_EVENTS_LIMIT 4096
+
+/**
+ * Освобождает mbuf событий, оставшихся в устройстве при остановке
+ */
+static void dpdk_event_flush_mbuf(uint8_t dev_id, struct rte_event ev, void *arg) {
+    (void)dev_id;
+    (void)arg;
+    if (ev.event_type == RTE_EVENT_TYPE_ETHDEV && ev.mbuf != NULL) {
+        rte_pktmbuf_free(ev.mbuf);
+    }
+}
+
+/**
+ * Настраивает и запускает устройство событий для планирования потоков
+ *
+ * Очередь событий q обслуживает один порт Ethernet. Порты событий
+ * 0..nb_queues-1 - производители (порт q заполняет очередь q), за ними
+ * подряд идут порты рабочих потоков: workers[0] портов очереди 0,
+ * workers[1] портов очереди 1 и т.д. Порт рабочего потока связан только
+ * со своей очередью.
+ *
+ * Программному планировщику (event_sw) нужна служба DPDK: она
+ * разрешается к запуску без ядра службы, и ее вызывают потоки-производители.
+ *
+ * @param sched_type RTE_SCHED_TYPE_ATOMIC, RTE_SCHED_TYPE_ORDERED или RTE_SCHED_TYPE_PARALLEL
+ * @param service_id Служба планировщика или UINT32_MAX для аппаратного планировщика
+ * @return 0 или отрицательный код ошибки
+ */
+int dpdk_eventdev_setup(uint8_t dev_id, const uint16_t *workers, uint16_t nb_queues,
+                        uint8_t sched_type, uint32_t *service_id) {
+    struct rte_event_dev_info info;
+    struct rte_event_dev_config config;
+    uint32_t nb_ports = nb_queues;
+    uint8_t port;
+    int ret;
+
+    for (uint16_t q = 0; q < nb_queues; q++) {
+        nb_ports += workers[q];
+    }
+
+    ret = rte_event_dev_info_get(dev_id, &info);
+    if (ret != 0) {
+        return ret;
+    }
+    if (nb_queues > info.max_event_queues || nb_ports > info.max_event_ports) {
+        return -ENOSPC;
+    }
+
+    memset(&config, 0, sizeof(config));
+    config.nb_event_queues = nb_queues;
+    config.nb_event_ports = nb_ports;
+    config.nb_events_limit = DPDK_EVENTS_LIMIT;
+    if (info.max_num_events > 0 && info.max_num_events < DPDK_EVENTS_LIMIT) {
+        config.nb_events_limit = info.max_num_events;
+    }
+    config.nb_event_queue_flows = info.max_event_queue_flows;
+    config.nb_event_port_dequeue_depth = info.max_event_port_dequeue_depth;
+    config.nb_event_port_enqueue_depth = info.max_event_port_enqueue_depth;
+    config.dequeue_timeout_ns = info.min_dequeue_timeout_ns;
+
+    ret = rte_event_dev_configure(dev_id, &config);
+    if (ret != 0) {
+        return ret;
+    }
+
+    for (uint16_t q = 0; q < nb_queues; q++) {
+        struct rte_event_queue_conf queue_conf;
+        ret = rte_event_queue_default_conf_get(dev_id, q, &queue_conf);
+        if (ret != 0) {
+            return ret;
+        }
+        queue_conf.event_queue_cfg = 0;
+        queue_conf.schedule_type = sched_type;
+        queue_conf.nb_atomic_flows = info.max_event_queue_flows;
+        ret = rte_event_queue_setup(dev_id, q, &queue_conf);
+        if (ret != 0) {
+            return ret;
+        }
+    }
+
+    for (uint32_t p = 0; p < nb_ports; p++) {
+        ret = rte_event_port_setup(dev_id, p, NULL);
+        if (ret != 0) {
+            return ret;
+        }
+    }
+
+    port = nb_queues;
+    for (uint16_t q = 0; q < nb_queues; q++) {
+        uint8_t queue = q;
+        for (uint16_t w = 0; w < workers[q]; w++, port++) {
+            if (rte_event_port_link(dev_id, port, &queue, NULL, 1) != 1) {
+                return -rte_errno;
+            }
+        }
+    }
+
+    *service_id = UINT32_MAX;
+    uint32_t id;
+    if (rte_event_dev_service_id_get(dev_id, &id) == 0) {
+        rte_service_runstate_set(id, 1);
+        rte_service_set_runstate_mapped_check(id, 0);
+        *service_id = id;
+    }
+
+    rte_event_dev_stop_flush_callback_register(dev_id, dpdk_event_flush_mbuf, NULL);
+    return rte_event_dev_start(dev_id);
+}
+
+/**
+ * Ставит mbuf в очередь событий как новые события
+ *
+ * @param flow_ids Идентификаторы потоков (младшие 20 бит), по ним сохраняется порядок
+ * @return Число принятых событий; остальные mbuf остаются у вызывающего
+ */
+uint16_t dpdk_event_enqueue_mbufs(uint8_t dev_id, uint8_t port_id, uint8_t queue_id,
+                                  uint8_t sched_type, struct rte_mbuf **mbufs,
+                                  const uint32_t *flow_ids, uint16_t count) {
+    struct rte_event events[DPDK_EVENT_BURST_MAX];
+
+    if (count > DPDK_EVENT_BURST_MAX) {
+        count = DPDK_EVENT_BURST_MAX;
+    }
+    for (uint16_t i = 0; i < count; i++) {
+        events[i].event = 0;
+        events[i].flow_id = flow_ids[i] & 0xfffff;
+        events[i].op = RTE_EVENT_OP_NEW;
+        events[i].sched_type = sched_type;
+        events[i].queue_id = queue_id;
+        events[i].event_type = RTE_EVENT_TYPE_ETHDEV;
+        events[i].priority = RTE_EVENT_DEV_PRIORITY_NORMAL;
+        events[i].mbuf = mbufs[i];
+    }
+
+    return rte_event_enqueue_new_burst(dev_id, port_id, events, count);
+}
+
+/**
+ * Извлекает до count событий порта без ожидания
+ *
+ * События предыдущего вызова освобождаются неявно (атомарный поток
+ * становится доступен другим портам, упорядоченный - восстанавливает порядок).
+ */
+uint16_t dpdk_event_dequeue_mbufs(uint8_t dev_id, uint8_t port_id, struct rte_mbuf **mbufs,
+                                  uint16_t count) {
+    struct rte_event events[DPDK_EVENT_BURST_MAX];
+
+    if (count > DPDK_EVENT_BURST_MAX) {
+        count = DPDK_EVENT_BURST_MAX;
+    }
+    uint16_t nb = rte_event_dequeue_burst(dev_id, port_id, events, count, 0);
+    for (uint16_t i = 0; i < nb; i++) {
+        mbufs[i] = events[i].mbuf;
+    }
+    return nb;
+}
+
+/**
+ * Выполняет одну итерацию службы из потока приложения
+ */
+int dpdk_service_run_iter(uint32_t service_id) {
+    return rte_service_run_iter_on_app_lcore(service_id, 1);
+}
+
+/**
+ * Останавливает и закрывает устройство событий (оставшиеся mbuf освобождаются)
+ */
+void dpdk_eventdev_stop(uint8_t dev_id) {
+    rte_event_dev_stop(dev_id);
+    rte_event_dev_close(dev_id);
+}
+
+/**
+ * Состояние линка порта без ожидания автосогласования
+ *
+ * @param up 1 - линк поднят
+ * @param speed_mbps Скорость линка (0 - неизвестна)
+ * @return 0 или отрицательный код ошибки
+ */
+int dpdk_link_status(uint16_t port_id, int *up, uint32_t *speed_mbps) {
+    struct rte_eth_link link;
+    memset(&link, 0, sizeof(link));
+
+    int ret = rte_eth_link_get_nowait(port_id, &link);
+    if (ret < 0) {
+        return ret;
+    }
+
+    *up = link.link_status == RTE_ETH_LINK_UP;
+    *speed_mbps = link.link_speed == RTE_ETH_SPEED_NUM_UNKNOWN ? 0 : link.link_speed;
+    return 0;
+}
+
+/**
+ * Регистрирует прерывание очереди RX в epoll вызывающего потока
+ *
+ * Вызывается из рабочего потока очереди: RTE_EPOLL_PER_THREAD - epoll
+ * текущего потока.
+ *
+ * @return 0 или отрицательный код ошибки (PMD без прерываний RX)
+ */
+int dpdk_rx_intr_setup(uint16_t port_id, uint16_t queue_id) {
+    return rte_eth_dev_rx_intr_ctl_q(port_id, queue_id, RTE_EPOLL_PER_THREAD,
+                                     RTE_INTR_EVENT_ADD, NULL);
+}
+
+/**
+ * Снимает регистрацию прерывания очереди RX (из того же потока)
+ */
+void dpdk_rx_intr_teardown(uint16_t port_id, uint16_t queue_id) {
+    rte_eth_dev_rx_intr_ctl_q(port_id, queue_id, RTE_EPOLL_PER_THREAD,
+                              RTE_INTR_EVENT_DEL, NULL);
+}
+
+/**
+ * Взводит прерывание очереди RX и ждет его не дольше timeout_ms
+ *
+ * После возврата прерывание снова выключено: опрос идет без прерываний.
+ *
+ * @return >0 - прерывание пришло, 0 - тайм-аут, <0 - код ошибки
+ */
+int dpdk_rx_intr_wait(uint16_t port_id, uint16_t queue_id, int timeout_ms) {
+    struct rte_epoll_event event;
+
+    int ret = rte_eth_dev_rx_intr_enable(port_id, queue_id);
+    if (ret < 0) {
+        return ret;
+    }
+
+    ret = rte_epoll_wait(RTE_EPOLL_PER_THREAD, &event, 1, timeout_ms);
+    rte_eth_dev_rx_intr_disable(port_id, queue_id);
+    return ret;
+}
+
+/* Шаг обхода памяти при предварительном обращении: наименьшая страница */
+#define DPDK_PREFAULT_STRIDE 4096
+
+/**
+ * Читает по байту на каждую страницу диапазона
+ *
+ * Только чтение: в буферы пула NIC может уже писать принятые пакеты.
+ */
+static uint64_t dpdk_prefault_range(const void *addr, size_t len) {
+    const volatile uint8_t *bytes = (const volatile uint8_t *)addr;
+    for (size_t offset = 0; offset < len; offset += DPDK_PREFAULT_STRIDE) {
+        (void)bytes[offset];
+    }
+    return len;
+}
+
+static void dpdk_prefault_memhdr_cb(struct rte_mempool *mp, void *opaque,
+                                    struct rte_mempool_memhdr *memhdr,
+                                    unsigned mem_idx) {
+    (void)mp;
+    (void)mem_idx;
+    *(uint64_t *)opaque += dpdk_prefault_range(memhdr->addr, memhdr->len);
+}
+
+/**
+ * Обращается ко всем страницам памяти пула mbuf
+ *
+ * @return байт пройдено
+ */
+uint64_t dpdk_mempool_prefault(struct rte_mempool *mp) {
+    uint64_t touched = 0;
+    rte_mempool_mem_iter(mp, dpdk_prefault_memhdr_cb, &touched);
+    return touched;
+}
+
+static int dpdk_prefault_memseg_cb(const struct rte_memseg_list *msl,
+                                   const struct rte_memseg *ms, void *arg) {
+    (void)msl;
+    if (ms->addr != NULL) {
+        *(uint64_t *)arg += dpdk_prefault_range(ms->addr, ms->len);
+    }
+    return 0;
+}
+
+/**
+ * Обращается ко всем выделенным EAL сегментам памяти (hugepages)
+ *
+ * @return байт пройдено
+ */
+uint64_t dpdk_prefault_hugepages(void) {
+    uint64_t touched = 0;
+    rte_memseg_walk(dpdk_prefault_memseg_cb, &touched);
+    return touched;
+}
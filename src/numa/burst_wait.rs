@@ -0,0 +1,66 @@
+// src/numa/burst_wait.rs
+// Адаптивное ожидание после неполной пачки RX
+//
+// На фидах средней интенсивности опрос сразу после неполной пачки чаще
+// всего возвращает 1-2 пакета, и фиксированная стоимость rx_burst делится
+// на малое их число. Короткое ожидание перед следующим опросом собирает
+// пачку крупнее; срок растет, пока пачки растут, и сокращается вдвое, когда
+// ожидание не дало новых пакетов. Замеры: benches/burst_wait.rs.
+
+use crate::time;
+
+/// Ожидание перед повторным опросом очереди после неполной пачки
+pub struct AdaptiveBurstWait {
+    max_ns: u64,
+    step_ns: u64,
+    current_ns: u64,
+    deadline_ns: u64,
+    last_nb_rx: u16,
+}
+
+impl AdaptiveBurstWait {
+    pub fn new(max_ns: u64) -> Self {
+        Self {
+            max_ns,
+            step_ns: (max_ns / 8).max(1),
+            current_ns: max_ns / 2,
+            deadline_ns: 0,
+            last_nb_rx: 0,
+        }
+    }
+
+    /// Ожидает до назначенного срока, если он установлен
+    #[inline(always)]
+    pub fn wait(&self) {
+        if self.deadline_ns == 0 {
+            return;
+        }
+
+        while time::now_ns() < self.deadline_ns {
+            std::hint::spin_loop();
+        }
+    }
+
+    /// Учитывает результат опроса и назначает следующее ожидание
+    #[inline(always)]
+    pub fn update(&mut self, nb_rx: u16, burst_size: u16, now_ns: u64) {
+        if self.max_ns == 0 {
+            return;
+        }
+
+        if self.deadline_ns != 0 {
+            self.current_ns = if nb_rx > self.last_nb_rx {
+                (self.current_ns + self.step_ns).min(self.max_ns)
+            } else {
+                (self.current_ns / 2).max(self.step_ns)
+            };
+        }
+
+        self.deadline_ns = if nb_rx > 0 && nb_rx < burst_size {
+            now_ns + self.current_ns
+        } else {
+            0
+        };
+        self.last_nb_rx = nb_rx;
+    }
+}
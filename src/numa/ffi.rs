@@ -1,6 +1,8 @@
 use std::mem::size_of;
 use std::os::raw::{c_int, c_ulong, c_void};
 
+use crate::cpu::topology::configured_cpus;
+
 #[link(name = "numa")]
 extern "C" {
     pub fn numa_available() -> c_int;
@@ -8,6 +10,7 @@ extern "C" {
     pub fn numa_node_to_cpus(node: c_int, mask: *mut c_ulong, size: c_int) -> c_int;
     pub fn numa_alloc_onnode(size: usize, node: c_int) -> *mut c_void;
     pub fn numa_free(start: *mut c_void, size: usize);
+    pub fn numa_tonode_memory(start: *mut c_void, size: usize, node: c_int);
     pub fn numa_run_on_node(node: c_int) -> c_int;
     pub fn numa_bind(nodemask: *const c_ulong);
     pub fn numa_set_localalloc();
@@ -38,7 +41,7 @@ impl NumaAllocator {
             return Vec::new();
         }
 
-        let num_possible_cpus = num_cpus::get();
+        let num_possible_cpus = configured_cpus();
         let mask_size =
             (num_possible_cpus + 8 * size_of::<c_ulong>() - 1) / (8 * size_of::<c_ulong>());
         let mut cpu_mask = vec![0 as c_ulong; mask_size];
@@ -84,6 +87,13 @@ impl NumaAllocator {
         }
     }
 
+    /// Привязывает еще не затронутую память (например, mmap) к узлу NUMA
+    pub fn bind_memory(ptr: *mut c_void, size: usize, node: usize) {
+        if Self::is_available() && !ptr.is_null() {
+            unsafe { numa_tonode_memory(ptr, size, node as c_int) };
+        }
+    }
+
     /// Привязывает текущий поток к узлу NUMA
     pub fn bind_thread_to_node(node: usize) -> bool {
         if !Self::is_available() {
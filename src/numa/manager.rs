@@ -1,12 +1,42 @@
 // src/numa/manager.rs
 use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use crate::cpu::topology::CpuTopology;
+use crate::control::build_info::build_info;
+use crate::control::events::{EventBus, LifecycleEvent};
+use crate::control::snapshot::ConfigSnapshot;
+use crate::cpu::housekeeping;
+use crate::cpu::migration::WorkerControlRef;
+use crate::cpu::realtime;
+use crate::cpu::topology::{configured_cpus, CpuTopology};
 use crate::dpdk::config::DpdkConfig;
-use crate::dpdk::init::{configure_port_for_node, enumerate_dpdk_ports, init_dpdk_for_node};
+use crate::dpdk::eventdev::{EventScheduler, SchedulingMode};
+use crate::dpdk::flow::FlowAction;
+use crate::dpdk::flow_ctrl::{set_flow_control, FlowControlMode, FlowControlStatus};
+use crate::dpdk::hugepages::{check_hugepages_available, check_socket_mem, provision_socket_mem};
+use crate::dpdk::init::{
+    attach_device, configure_port_for_node, detach_port, enumerate_dpdk_ports, flush_tx_queues,
+    get_port_caps, init_eal, link_status, mempool_usage, prefault_hugepages, prefault_mempool,
+    restart_port, select_ports, start_rx_queue, stop_port, stop_rx_queue, DpdkPortInfo,
+};
+use crate::dpdk::multiproc::{find_port_pools, ProcessType};
+use crate::dpdk::reta::RetaTable;
+use crate::dpdk::validate::{validate_offloads, validate_queue_layout, ValidationPolicy};
+use crate::metrics::counters::{QueueStats, WorkerStats};
+use crate::metrics::histogram::HistogramSnapshot;
+use crate::metrics::shm::WorkerMetricsRef;
 use crate::numa::ffi::NumaAllocator;
-use crate::numa::node::NumaNode;
+use crate::numa::node::{
+    DpdkPort, NumaNode, WorkerControl, WorkerSettings, MAX_BURST_SIZE, NO_CORE_CHANGE,
+};
 use crate::numa::topology::NumaTopology;
+use crate::numa::watchdog::StallWatchdog;
+use crate::packet::filter::RxFilter;
+use crate::packet::handler::{FeedClass, HandlerRegistry, SharedHandlers};
+use crate::time::wheel::Timers;
+use crate::{log_info, log_warn};
 
 /// Управляет созданием и инициализацией изолированных узлов NUMA
 pub struct NumaManager {
@@ -18,6 +48,18 @@ pub struct NumaManager {
     nodes: HashMap<usize, NumaNode>,
     /// Признак, что NUMA доступна
     numa_available: bool,
+    /// Шина событий жизненного цикла
+    events: EventBus,
+    /// Реестр обработчиков запущенных рабочих потоков
+    handlers: Option<Arc<SharedHandlers>>,
+    /// Роль процесса, определенная EAL при инициализации
+    process_type: ProcessType,
+    /// Устройство событий запущенной обработки (режим eventdev)
+    event_scheduler: Option<Arc<EventScheduler>>,
+    /// Таймеры протоколов (проверяются рабочими потоками и служебным ядром)
+    timers: Arc<Timers>,
+    /// Проверка зависания рабочих потоков (None - отключена)
+    watchdog: Option<Arc<StallWatchdog>>,
 }
 
 impl NumaManager {
@@ -31,16 +73,99 @@ impl NumaManager {
 
         let numa_available = NumaAllocator::is_available();
 
-        println!("NUMA support available: {}", numa_available);
+        log_info!("numa", "NUMA support", available = numa_available);
+        if let Some(allowed) = &cpu_topology.allowed_cores {
+            log_info!(
+                "cpu",
+                "Process cpuset",
+                allowed = allowed.len(),
+                configured = configured_cpus()
+            );
+        }
 
         Ok(Self {
             cpu_topology,
             numa_topology,
             nodes: HashMap::new(),
             numa_available,
+            events: EventBus::new(),
+            handlers: None,
+            process_type: ProcessType::Primary,
+            event_scheduler: None,
+            timers: Arc::new(Timers::new()),
+            watchdog: None,
         })
     }
 
+    /// Шина событий жизненного цикла для подписки приложения
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    /// Таймеры протоколов
+    pub fn timers(&self) -> &Arc<Timers> {
+        &self.timers
+    }
+
+    /// Заменяет службу таймеров (до запуска рабочих потоков)
+    pub fn set_timers(&mut self, timers: Arc<Timers>) {
+        self.timers = timers;
+    }
+
+    /// Проверка зависания рабочих потоков (задается `DpdkConfig::with_watchdog`)
+    ///
+    /// Создается при запуске обработки; проверку выполняет служебное ядро.
+    pub fn watchdog(&self) -> Option<&Arc<StallWatchdog>> {
+        self.watchdog.as_ref()
+    }
+
+    /// Передает проверке зависания текущий набор рабочих потоков
+    fn refresh_watchdog(&self) {
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.watch(self.worker_controls());
+        }
+    }
+
+    /// Заменяет рабочие потоки, зависание которых обнаружила проверка
+    ///
+    /// Вызывается потоком управления движка. Возвращает число замененных потоков.
+    pub fn restart_stalled_workers(&mut self) -> usize {
+        let Some(watchdog) = self.watchdog.clone() else {
+            return 0;
+        };
+
+        let mut restarted = 0;
+        for request in watchdog.take_restarts() {
+            let result = self
+                .nodes
+                .values_mut()
+                .find(|node| {
+                    node.find_worker(request.port_id, request.queue_id)
+                        .is_some()
+                })
+                .map(|node| {
+                    node.restart_worker(request.port_id, request.queue_id, request.heartbeat)
+                });
+            match result {
+                Some(Ok(())) => restarted += 1,
+                Some(Err(e)) => log_warn!(
+                    "watchdog",
+                    "Failed to restart stalled worker",
+                    port = request.port_id,
+                    queue = request.queue_id,
+                    error = e
+                ),
+                // Поток остановлен вместе с портом или узлом
+                None => {}
+            }
+        }
+
+        if restarted > 0 {
+            self.refresh_watchdog();
+        }
+        restarted
+    }
+
     /// Инициализирует необходимое количество NUMA-узлов
     pub fn init_nodes(&mut self) -> Result<(), String> {
         let node_count = if self.numa_available {
@@ -49,10 +174,15 @@ impl NumaManager {
             1
         };
 
-        println!("Initializing {} NUMA nodes", node_count);
+        log_info!("numa", "Initializing NUMA nodes", count = node_count);
 
         for node_id in 0..node_count {
-            let node = NumaNode::new(node_id, &self.cpu_topology, &self.numa_topology);
+            let node = NumaNode::new(
+                node_id,
+                &self.cpu_topology,
+                &self.numa_topology,
+                self.events.clone(),
+            );
             self.nodes.insert(node_id, node);
         }
 
@@ -68,19 +198,24 @@ impl NumaManager {
             return Err("No DPDK ports found".to_string());
         }
 
-        println!("Found {} DPDK ports", ports.len());
+        let ports = select_ports(ports, &dpdk_config.port_selectors())?;
+
+        log_info!("dpdk", "Found DPDK ports", count = ports.len());
 
         for port in ports {
-            let node_id = port.numa_node.unwrap_or_default();
+            let node_id = self.port_node(&port);
+
+            log_info!(
+                "dpdk",
+                "Port discovered",
+                port = port.port_id,
+                device = port.dev_name,
+                if_name = port.if_name,
+                node = node_id
+            );
 
             if let Some(node) = self.nodes.get_mut(&node_id) {
-                node.register_port(
-                    port.port_id,
-                    &port.if_name,
-                    dpdk_config.num_rx_queues,
-                    dpdk_config.num_tx_queues,
-                    &self.numa_topology,
-                );
+                node.register_port(&port, dpdk_config.port_settings(&port), &self.numa_topology);
             } else {
                 return Err(format!("NUMA node {} not available", node_id));
             }
@@ -89,52 +224,1562 @@ impl NumaManager {
         Ok(())
     }
 
-    /// Инициализирует DPDK для всех NUMA-узлов
+    /// Узел NUMA порта: сокет по данным DPDK, затем по PCI адресу
+    fn port_node(&self, port: &DpdkPortInfo) -> usize {
+        port.numa_node
+            .or_else(|| {
+                port.pci_address
+                    .as_deref()
+                    .and_then(|pci| self.numa_topology.get_device_node(pci))
+            })
+            .unwrap_or_default()
+    }
+
+    /// Задает резервирование ядер и пересчитывает ядра рабочих потоков узлов
+    ///
+    /// `reserved_cores` заменяет резервирование ядра 0 по умолчанию; с
+    /// `use_hyperthreads` рабочим потокам отдаются оба логических ядра
+    /// физического ядра. Выполняется до `reserve_core` и `init_dpdk`.
+    pub fn set_core_policy(
+        &mut self,
+        reserved_cores: &[usize],
+        use_hyperthreads: bool,
+    ) -> Result<(), String> {
+        let total = configured_cpus();
+        if let Some(&core_id) = reserved_cores.iter().find(|&&core| core >= total) {
+            return Err(format!(
+                "Core {} does not exist ({} logical cores)",
+                core_id, total
+            ));
+        }
+
+        self.cpu_topology
+            .set_core_policy(reserved_cores.to_vec(), use_hyperthreads);
+        for (node_id, node) in &mut self.nodes {
+            node.local_cpus = NumaNode::worker_cpus(*node_id, &self.cpu_topology);
+            if node.local_cpus.is_empty() {
+                log_warn!("numa", "NUMA node has no worker cores", node = node_id);
+            }
+        }
+
+        log_info!(
+            "numa",
+            "Core policy applied",
+            reserved = reserved_cores
+                .iter()
+                .map(|core| core.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            hyperthreads = use_hyperthreads
+        );
+        Ok(())
+    }
+
+    /// Упорядочивает ядра узлов по доменам кэша L3 (CCX на процессорах из чиплетов)
+    ///
+    /// Потоки порта получают ядра узла по порядку: рабочие потоки, за ними
+    /// распределитель и ядра декодирования. После группировки соседние ядра
+    /// делят L3, поэтому очереди порта и потребитель их колец попадают в один
+    /// CCX, пока в нем хватает ядер; первым идет домен с наибольшим числом
+    /// ядер. Выполняется после `set_core_policy` и `reserve_core`.
+    pub fn group_cores_by_l3(&mut self) {
+        for (node_id, node) in &mut self.nodes {
+            let mut sizes: HashMap<usize, usize> = HashMap::new();
+            for core in &node.local_cpus {
+                if let Some(domain) = self.cpu_topology.get_l3_domain(core.id) {
+                    *sizes.entry(domain).or_insert(0) += 1;
+                }
+            }
+            if sizes.len() < 2 {
+                continue;
+            }
+
+            // Домены по убыванию числа ядер; ядра без сведений о кэше - в конце
+            let rank = |core_id: usize| {
+                let domain = self.cpu_topology.get_l3_domain(core_id);
+                let size = domain.and_then(|d| sizes.get(&d)).copied().unwrap_or(0);
+                (usize::MAX - size, domain.unwrap_or(usize::MAX), core_id)
+            };
+            node.local_cpus.sort_by_key(|core| rank(core.id));
+
+            log_info!(
+                "numa",
+                "Worker cores grouped by L3 domain",
+                node = node_id,
+                domains = sizes.len()
+            );
+        }
+    }
+
+    /// Исключает ядро из ядер рабочих потоков всех узлов (служебное ядро)
+    ///
+    /// Выполняется до `init_dpdk`: ядро не попадает и в маску ядер EAL.
+    pub fn reserve_core(&mut self, core_id: usize) -> Result<(), String> {
+        if core_id >= configured_cpus() {
+            return Err(format!(
+                "Core {} does not exist ({} logical cores)",
+                core_id,
+                configured_cpus()
+            ));
+        }
+        if !self.cpu_topology.is_allowed_core(core_id) {
+            return Err(format!("Core {} is outside the process cpuset", core_id));
+        }
+
+        for (node_id, node) in &mut self.nodes {
+            let before = node.local_cpus.len();
+            node.local_cpus.retain(|core| core.id != core_id);
+            if node.local_cpus.len() == before {
+                continue;
+            }
+
+            log_info!("numa", "Core reserved", core = core_id, node = node_id);
+            if node.local_cpus.is_empty() {
+                log_warn!(
+                    "numa",
+                    "NUMA node has no worker cores left",
+                    node = node_id,
+                    reserved = core_id
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Аргументы DPDK EAL для всех узлов: объединенная маска ядер и память каждого узла
+    pub fn generate_eal_args(&self, dpdk_config: &DpdkConfig) -> Vec<String> {
+        let core_mask = self
+            .nodes
+            .values()
+            .fold(0u64, |mask, node| mask | node.core_mask());
+
+        let mut args = vec![
+            format!("--lcores=0x{:x}", core_mask),
+            "--master-lcore=0".to_string(),
+            format!("--proc-type={}", dpdk_config.process_type),
+        ];
+        if let Some(prefix) = &dpdk_config.file_prefix {
+            args.push(format!("--file-prefix={}", prefix));
+        }
+
+        // Программный планировщик событий создается как виртуальное устройство
+        if matches!(dpdk_config.scheduling, SchedulingMode::EventDev(_))
+            && dpdk_config.event_device.starts_with("event_sw")
+        {
+            args.push(format!("--vdev={}", dpdk_config.event_device));
+        }
+
+        // Вторичный процесс использует память первичного
+        if dpdk_config.use_huge_pages && dpdk_config.process_type != ProcessType::Secondary {
+            let socket_mem: Vec<String> = self
+                .socket_mem(dpdk_config)
+                .iter()
+                .map(|mb| mb.to_string())
+                .collect();
+            args.push(format!("--socket-mem={}", socket_mem.join(",")));
+        }
+
+        args
+    }
+
+    /// Память hugepages каждого сокета для --socket-mem (МБ)
+    ///
+    /// Память задается для каждого сокета, на узлах без портов и ядер - 0.
+    fn socket_mem(&self, dpdk_config: &DpdkConfig) -> Vec<u32> {
+        let socket_count = if self.numa_available {
+            NumaAllocator::get_node_count()
+        } else {
+            1
+        };
+        (0..socket_count)
+            .map(|node_id| {
+                self.nodes
+                    .get(&node_id)
+                    .map_or(0, |node| node.socket_mem(dpdk_config))
+            })
+            .collect()
+    }
+
+    /// Инициализирует DPDK EAL один раз для всех NUMA-узлов
+    ///
+    /// Выполняется до `distribute_interfaces`: порты перечисляются уже через EAL.
     pub fn init_dpdk(&mut self, dpdk_config: &DpdkConfig) -> Result<(), String> {
+        log_info!("dpdk", "Initializing DPDK", nodes = self.nodes.len());
+
+        // Нехватка страниц на узле иначе видна только как отказ EAL без подробностей.
+        // В режиме auto страницы может уже занимать первичный процесс.
+        if dpdk_config.use_huge_pages
+            && dpdk_config.process_type == ProcessType::Primary
+            && check_hugepages_available()
+        {
+            let socket_mem = self.socket_mem(dpdk_config);
+            if dpdk_config.use_hugepage_provisioning {
+                provision_socket_mem(&socket_mem)?;
+            }
+            check_socket_mem(&socket_mem)?;
+        }
+
+        init_eal(dpdk_config, &self.generate_eal_args(dpdk_config))?;
+
+        self.process_type = ProcessType::current();
+        if self.process_type == ProcessType::Secondary {
+            log_info!("dpdk", "Attached to primary process as secondary");
+        }
+
+        Ok(())
+    }
+
+    /// Роль процесса после инициализации EAL
+    pub fn process_type(&self) -> ProcessType {
+        self.process_type
+    }
+
+    /// Изменение портов доступно только первичному процессу
+    fn require_primary(&self, action: &str) -> Result<(), String> {
+        if self.process_type == ProcessType::Secondary {
+            return Err(format!(
+                "Cannot {} in a secondary process: ports belong to the primary process",
+                action
+            ));
+        }
+        Ok(())
+    }
+
+    /// Настраивает и запускает порты каждого узла (пулы и очереди на сокете узла)
+    pub fn setup_ports(&mut self, dpdk_config: &DpdkConfig) -> Result<(), String> {
         for (node_id, node) in &mut self.nodes {
-            let mut node_args = vec![];
+            log_info!(
+                "dpdk",
+                "Setting up ports for NUMA node",
+                node = node_id,
+                ports = node.local_ports.len()
+            );
 
-            if self.numa_available {
-                node_args.push(format!("--socket-id={}", node_id));
+            for index in 0..node.local_ports.len() {
+                if self.process_type == ProcessType::Secondary {
+                    Self::attach_primary_port(node, index);
+                } else {
+                    Self::setup_port(node, index, dpdk_config, &self.events)?;
+                }
             }
+        }
 
-            println!("Initializing DPDK for NUMA node {}", node_id);
+        Ok(())
+    }
 
-            init_dpdk_for_node(node, dpdk_config, &node_args)?;
+    /// Подключает порт, настроенный первичным процессом: порт не настраивается
+    /// и не запускается, находятся только его пулы mbuf
+    fn attach_primary_port(node: &mut NumaNode, index: usize) {
+        let port = &mut node.local_ports[index];
+        port.rx_pools = find_port_pools(port.port_id, port.num_rx_queues);
 
-            for port in &node.local_ports {
-                configure_port_for_node(node, port.port_id, dpdk_config)?;
+        if port.rx_pools.is_empty() {
+            log_warn!(
+                "dpdk",
+                "No mbuf pools of primary process found for port",
+                port = port.port_id
+            );
+        } else {
+            log_info!(
+                "dpdk",
+                "Attached to port of primary process",
+                port = port.port_id,
+                pools = port.rx_pools.join(",")
+            );
+        }
+    }
+
+    /// Сверяет очереди порта с возможностями NIC, настраивает и запускает порт
+    fn setup_port(
+        node: &mut NumaNode,
+        index: usize,
+        dpdk_config: &DpdkConfig,
+        events: &EventBus,
+    ) -> Result<(), String> {
+        // Сверяем очереди с возможностями NIC и ядрами узла до rte_eth_dev_configure
+        let available_cores = node.local_cpus.len();
+        let port = &mut node.local_ports[index];
+        let caps = get_port_caps(port.port_id)?;
+
+        // Одноочередной NIC: аппаратная очередь одна, рабочих потоков - сколько запрошено
+        // (одно ядро занимает распределитель)
+        if dpdk_config.use_software_rss
+            && port.settings.use_rss
+            && caps.max_rx_queues == 1
+            && port.num_rx_queues > 1
+        {
+            let workers = port
+                .num_rx_queues
+                .min(available_cores.saturating_sub(1) as u16);
+            if workers > 1 {
+                log_warn!(
+                    "dpdk",
+                    "NIC has a single RX queue, distributing packets in software",
+                    port = port.port_id,
+                    workers = workers
+                );
+                port.software_rss_queues = workers;
+                port.num_rx_queues = 1;
+            }
+        }
+
+        let report = validate_queue_layout(
+            port.port_id,
+            port.num_rx_queues,
+            port.num_tx_queues,
+            &caps,
+            available_cores,
+            dpdk_config.validation_policy,
+        )?;
+
+        report.log();
+        port.num_rx_queues = report.rx_queues;
+        port.num_tx_queues = report.tx_queues;
+
+        // Неподдерживаемые offload-функции отключаем до rte_eth_dev_configure
+        let downgrades = validate_offloads(
+            port.port_id,
+            &mut port.settings,
+            &caps,
+            dpdk_config.validation_policy,
+        )?;
+        for downgrade in downgrades {
+            log_warn!(
+                "dpdk",
+                "Offload downgraded",
+                port = port.port_id,
+                reason = downgrade
+            );
+        }
+
+        let configured = configure_port_for_node(node, &node.local_ports[index], dpdk_config)?;
+
+        let port = &mut node.local_ports[index];
+        port.rx_ring_size = configured.rx_ring_size;
+        port.tx_ring_size = configured.tx_ring_size;
+        port.rx_pools = configured.rx_pools;
+
+        if let Some(weights) = &port.settings.reta_weights {
+            if let Err(e) = Self::apply_reta(port, weights, caps.reta_size) {
+                if dpdk_config.validation_policy == ValidationPolicy::FailFast {
+                    return Err(e);
+                }
+                log_warn!(
+                    "dpdk",
+                    "RETA weights not applied",
+                    port = port.port_id,
+                    error = e
+                );
+            }
+        }
+
+        if let Some(mode) = port.settings.flow_control {
+            match set_flow_control(port.port_id, mode) {
+                Ok(status) => log_info!("dpdk", "Flow control set", status = status),
+                Err(e) if dpdk_config.validation_policy == ValidationPolicy::FailFast => {
+                    return Err(e);
+                }
+                Err(e) => log_warn!(
+                    "dpdk",
+                    "Flow control not applied",
+                    port = port.port_id,
+                    error = e
+                ),
+            }
+        }
+
+        Self::create_flow_rules(port, dpdk_config)?;
+
+        events.emit(LifecycleEvent::PortStarted {
+            port_id: port.port_id,
+            node_id: node.node_id,
+            rx_queues: port.num_rx_queues,
+            tx_queues: port.num_tx_queues,
+        });
+
+        Ok(())
+    }
+
+    /// Перераспределяет корзины RSS порта между очередями по весам
+    fn apply_reta(port: &DpdkPort, weights: &[u32], reta_size: u16) -> Result<RetaTable, String> {
+        if !port.settings.use_rss || port.num_rx_queues < 2 {
+            return Err(format!(
+                "Port {} has no hardware RSS across multiple queues",
+                port.port_id
+            ));
+        }
+        if weights.len() > port.num_rx_queues as usize {
+            return Err(format!(
+                "Port {}: {} RETA weights for {} RX queues",
+                port.port_id,
+                weights.len(),
+                port.num_rx_queues
+            ));
+        }
+
+        let table = RetaTable::weighted(reta_size, weights)?;
+        table.apply(port.port_id)?;
+
+        log_info!("dpdk", "RETA updated", port = port.port_id, table = table);
+        Ok(table)
+    }
+
+    /// Создает правила Flow Director порта после его запуска
+    fn create_flow_rules(port: &mut DpdkPort, dpdk_config: &DpdkConfig) -> Result<(), String> {
+        if port.settings.flow_rules.is_empty() {
+            return Ok(());
+        }
+
+        if !dpdk_config.use_flow_director {
+            log_warn!(
+                "dpdk",
+                "Flow rules configured but Flow Director is disabled",
+                port = port.port_id,
+                rules = port.settings.flow_rules.len()
+            );
+            return Ok(());
+        }
+
+        for rule in &port.settings.flow_rules {
+            let result = match rule.action() {
+                FlowAction::Queue(queue_id) if queue_id >= port.num_rx_queues => Err(format!(
+                    "Flow rule '{}' targets queue {} but port {} has {} RX queues",
+                    rule, queue_id, port.port_id, port.num_rx_queues
+                )),
+                _ => rule.create(port.port_id),
+            };
+
+            match result {
+                Ok(flow) => {
+                    log_info!(
+                        "dpdk",
+                        "Flow rule created",
+                        port = port.port_id,
+                        rule = rule
+                    );
+                    port.flows.push(flow);
+                }
+                Err(e) if dpdk_config.validation_policy == ValidationPolicy::FailFast => {
+                    return Err(e);
+                }
+                Err(e) => log_warn!("dpdk", "Flow rule skipped", error = e),
             }
         }
 
         Ok(())
     }
 
+    /// Подключает устройство во время работы и запускает обработку его портов
+    ///
+    /// Порт распределяется на узел NUMA так же, как при запуске. Счетчики
+    /// новых рабочих потоков не попадают в уже запущенный StatsPublisher.
+    pub fn attach_port(
+        &mut self,
+        devargs: &str,
+        dpdk_config: &DpdkConfig,
+    ) -> Result<Vec<u16>, String> {
+        self.require_primary("attach ports")?;
+        let ports = attach_device(devargs)?;
+        // Устройство событий настроено на порты, бывшие при запуске
+        let settings = WorkerSettings::from_config(dpdk_config);
+        if self.event_scheduler.is_some() {
+            log_warn!(
+                "worker",
+                "Attached ports use queue-per-core scheduling",
+                device = devargs
+            );
+        }
+        let mut attached = Vec::with_capacity(ports.len());
+
+        for port in ports {
+            let node_id = self.port_node(&port);
+            let node = self
+                .nodes
+                .get_mut(&node_id)
+                .ok_or_else(|| format!("NUMA node {} not available", node_id))?;
+
+            if !node.register_port(&port, dpdk_config.port_settings(&port), &self.numa_topology) {
+                return Err(format!(
+                    "Port {} could not be registered on NUMA node {}",
+                    port.port_id, node_id
+                ));
+            }
+
+            let index = node.local_ports.len() - 1;
+            Self::setup_port(node, index, dpdk_config, &self.events)?;
+            Self::check_queue_cores(
+                &self.cpu_topology,
+                &self.numa_topology,
+                node_id,
+                &node.local_ports[index],
+            )?;
+
+            if let Some(handlers) = &self.handlers {
+                node.start_port_workers(port.port_id, handlers, &settings)?;
+            }
+
+            attached.push(port.port_id);
+        }
+
+        self.refresh_watchdog();
+        Ok(attached)
+    }
+
+    /// Задача опроса линков портов для служебного ядра (`Housekeeper`)
+    ///
+    /// При изменении состояния линка пишет в журнал и отправляет
+    /// `LifecycleEvent::LinkChanged`. Опрашиваются порты, обслуживаемые на
+    /// момент вызова.
+    pub fn link_monitor(&self) -> impl FnMut() + Send + 'static {
+        let events = self.events.clone();
+        let mut links: Vec<(u16, Option<(bool, u32)>)> = self
+            .port_ids()
+            .into_iter()
+            .map(|port_id| (port_id, None))
+            .collect();
+
+        move || {
+            for (port_id, last) in links.iter_mut() {
+                let Ok((up, speed_mbps)) = link_status(*port_id) else {
+                    continue;
+                };
+                // Первое чтение только запоминается
+                let changed = last.is_some_and(|(was_up, _)| was_up != up);
+                *last = Some((up, speed_mbps));
+                if !changed {
+                    continue;
+                }
+
+                if up {
+                    log_info!("dpdk", "Link up", port = *port_id, speed_mbps = speed_mbps);
+                } else {
+                    log_warn!("dpdk", "Link down", port = *port_id);
+                }
+                events.emit(LifecycleEvent::LinkChanged {
+                    port_id: *port_id,
+                    up,
+                    speed_mbps,
+                });
+            }
+        }
+    }
+
+    /// Перераспределяет корзины RSS порта во время работы
+    pub fn set_reta(&self, port_id: u16, weights: &[u32]) -> Result<String, String> {
+        self.require_primary("change RETA")?;
+        let port = self
+            .find_port(port_id)
+            .ok_or_else(|| format!("Port {} is not in use", port_id))?;
+        let caps = get_port_caps(port_id)?;
+
+        Self::apply_reta(port, weights, caps.reta_size).map(|table| table.to_string())
+    }
+
+    /// Текущая таблица перенаправления RSS порта
+    pub fn reta_info(&self, port_id: u16) -> Result<String, String> {
+        if self.find_port(port_id).is_none() {
+            return Err(format!("Port {} is not in use", port_id));
+        }
+        let caps = get_port_caps(port_id)?;
+
+        RetaTable::query(port_id, caps.reta_size).map(|table| table.to_string())
+    }
+
+    /// Текущая настройка управления потоком порта
+    pub fn flow_control_info(&self, port_id: u16) -> Result<String, String> {
+        if self.find_port(port_id).is_none() {
+            return Err(format!("Port {} is not in use", port_id));
+        }
+        FlowControlStatus::query(port_id).map(|status| status.to_string())
+    }
+
+    /// Меняет режим управления потоком порта во время работы
+    pub fn set_flow_control(&self, port_id: u16, mode: FlowControlMode) -> Result<String, String> {
+        self.require_primary("change flow control")?;
+        if self.find_port(port_id).is_none() {
+            return Err(format!("Port {} is not in use", port_id));
+        }
+        let status = set_flow_control(port_id, mode)?;
+        log_info!("dpdk", "Flow control set", status = status);
+        Ok(status.to_string())
+    }
+
+    fn find_port(&self, port_id: u16) -> Option<&DpdkPort> {
+        self.nodes
+            .values()
+            .flat_map(|node| node.local_ports.iter())
+            .find(|port| port.port_id == port_id)
+    }
+
+    /// Формирует отчет о заполненности пулов mbuf портов
+    ///
+    /// При пулах по очередям исчерпание (и потери rx_nombuf) видно по очереди.
+    pub fn format_mempool_info(&self) -> String {
+        let mut out = String::from("==== Mbuf Pools ====\n");
+
+        let mut ports: Vec<&DpdkPort> = self
+            .nodes
+            .values()
+            .flat_map(|node| node.local_ports.iter())
+            .collect();
+        ports.sort_by_key(|port| port.port_id);
+
+        for port in ports {
+            for pool in &port.rx_pools {
+                match mempool_usage(pool) {
+                    Some(usage) => out.push_str(&format!(
+                        "Port {} {}: in use {} available {}\n",
+                        port.port_id, pool, usage.in_use, usage.available
+                    )),
+                    None => out.push_str(&format!("Port {} {}: not found\n", port.port_id, pool)),
+                }
+            }
+        }
+        out
+    }
+
+    /// Останавливает рабочие потоки порта и отключает его устройство
+    pub fn detach_port(&mut self, port_id: u16) -> Result<(), String> {
+        self.require_primary("detach ports")?;
+        let node = self
+            .nodes
+            .values_mut()
+            .find(|node| node.local_ports.iter().any(|p| p.port_id == port_id))
+            .ok_or_else(|| format!("Port {} is not in use", port_id))?;
+
+        node.stop_port_workers(port_id);
+        node.remove_port(port_id);
+        detach_port(port_id)?;
+
+        self.events.emit(LifecycleEvent::PortRemoved {
+            port_id,
+            node_id: node.node_id,
+        });
+
+        self.refresh_watchdog();
+        Ok(())
+    }
+
     /// Запускает обработку пакетов на всех узлах NUMA
+    ///
+    /// Принимает реестр обработчиков или один общий обработчик (`PacketHandler`).
     pub fn start_packet_processing(
         &mut self,
-        packet_handler: crate::numa::node::PacketHandler,
+        handlers: impl Into<HandlerRegistry>,
         dpdk_config: &DpdkConfig,
     ) -> Result<(), String> {
-        println!("Starting packet processing on all NUMA nodes");
+        // Очереди портов опрашивает первичный процесс
+        self.require_primary("poll queues")?;
+
+        log_info!("worker", "Starting packet processing on all NUMA nodes");
+
+        let handlers = Arc::new(SharedHandlers::new(handlers.into()));
+
+        // Предупреждаем о маршрутах на порты, которые не обслуживаются
+        for port_id in handlers.read(|r| r.routed_ports()).unwrap_or_default() {
+            let served = self
+                .nodes
+                .values()
+                .any(|node| node.local_ports.iter().any(|p| p.port_id == port_id));
+            if !served {
+                log_warn!(
+                    "worker",
+                    "Handler registered for port which is not in use",
+                    port = port_id
+                );
+            }
+        }
+
+        if let SchedulingMode::EventDev(sched_type) = dpdk_config.scheduling {
+            // Рабочих потоков порта столько же, сколько было бы очередей на ядро
+            let ports: Vec<(u16, u16)> = self
+                .nodes
+                .values()
+                .flat_map(|node| &node.local_ports)
+                .map(|port| {
+                    (
+                        port.port_id,
+                        port.num_rx_queues.max(port.software_rss_queues),
+                    )
+                })
+                .collect();
+            let scheduler = EventScheduler::start(&dpdk_config.event_device, sched_type, &ports)?;
+            self.event_scheduler = Some(Arc::new(scheduler));
+        }
+        let settings = self.worker_settings(dpdk_config);
+        if settings.realtime.is_some() {
+            realtime::warn_rt_throttling();
+        }
 
         for (node_id, node) in &mut self.nodes {
-            println!("Starting workers on NUMA node {}", node_id);
+            log_info!("worker", "Starting workers on NUMA node", node = node_id);
 
-            node.start_workers(packet_handler.clone(), dpdk_config.burst_size)?;
+            node.start_workers(&handlers, &settings)?;
         }
 
+        self.handlers = Some(handlers);
+        self.watchdog = dpdk_config
+            .watchdog
+            .map(|config| Arc::new(StallWatchdog::new(config, self.events.clone())));
+        self.refresh_watchdog();
+
         Ok(())
     }
 
+    /// Параметры рабочих потоков с устройством событий запущенной обработки
+    fn worker_settings(&self, dpdk_config: &DpdkConfig) -> WorkerSettings {
+        WorkerSettings {
+            event_scheduler: self.event_scheduler.clone(),
+            timers: Some(self.timers.clone()),
+            ..WorkerSettings::from_config(dpdk_config)
+        }
+    }
+
     /// Останавливает обработку пакетов на всех узлах NUMA
     pub fn stop_packet_processing(&mut self) {
-        println!("Stopping packet processing on all NUMA nodes");
+        log_info!("worker", "Stopping packet processing on all NUMA nodes");
 
         for (node_id, node) in &mut self.nodes {
-            println!("Stopping workers on NUMA node {}", node_id);
+            log_info!("worker", "Stopping workers on NUMA node", node = node_id);
             node.stop_workers();
         }
+
+        // Устройство событий останавливается после всех его потоков
+        self.event_scheduler = None;
+        self.refresh_watchdog();
+    }
+
+    /// Останавливает обработку пакетов, дообработав принятые пакеты
+    ///
+    /// Прием прекращается, рабочие потоки завершают пачки в обработке, ядра
+    /// декодирования - свои кольца, очереди TX отдают отправленные mbuf. Порты
+    /// не останавливаются (см. `stop_ports`). Err - к `deadline` успело не все;
+    /// остаток освобожден, обработка все равно остановлена.
+    pub fn drain(&mut self, deadline: Instant) -> Result<(), String> {
+        log_info!("worker", "Draining packet processing on all NUMA nodes");
+
+        let mut pending = Vec::new();
+        for (node_id, node) in &mut self.nodes {
+            if !node.drain_workers(deadline) {
+                pending.push(format!("node {} decode rings", node_id));
+            }
+        }
+        self.event_scheduler = None;
+        self.refresh_watchdog();
+
+        // Очередями TX владеют обработчики: после остановки рабочих потоков их никто не использует
+        if self.process_type != ProcessType::Secondary {
+            for node in self.nodes.values() {
+                for port in &node.local_ports {
+                    if !flush_tx_queues(port.port_id, port.num_tx_queues, deadline) {
+                        pending.push(format!("port {} TX queues", port.port_id));
+                    }
+                }
+            }
+        }
+
+        if !pending.is_empty() {
+            return Err(format!("Drain timed out: {}", pending.join(", ")));
+        }
+        log_info!("worker", "Packet processing drained");
+        Ok(())
+    }
+
+    /// Останавливает все порты (после `drain` или `stop_packet_processing`)
+    ///
+    /// Вторичный процесс порты не останавливает: ими владеет первичный.
+    pub fn stop_ports(&mut self) {
+        if self.process_type == ProcessType::Secondary {
+            return;
+        }
+        for node in self.nodes.values() {
+            for port in &node.local_ports {
+                if let Err(e) = stop_port(port.port_id) {
+                    log_warn!(
+                        "dpdk",
+                        "Failed to stop port",
+                        port = port.port_id,
+                        error = e
+                    );
+                }
+            }
+        }
+    }
+
+    /// Корректное завершение: `drain` с ограничением `timeout`, вывод журнала
+    /// и остановка портов
+    ///
+    /// Порты останавливаются и при Err (истек `timeout`).
+    pub fn shutdown_with_timeout(&mut self, timeout: Duration) -> Result<(), String> {
+        let result = self.drain(Instant::now() + timeout);
+        crate::logging::logger::flush();
+        self.stop_ports();
+        result
+    }
+
+    /// Останавливает рабочие потоки и порты одного узла NUMA
+    ///
+    /// Остальные узлы продолжают работу, поэтому порты узла можно обслуживать
+    /// (например, обновлять прошивку NIC) без остановки торговли.
+    pub fn stop_node(&mut self, node_id: usize) -> Result<(), String> {
+        let node = self
+            .nodes
+            .get_mut(&node_id)
+            .ok_or_else(|| format!("NUMA node {} not available", node_id))?;
+
+        if !node.running.load(Ordering::SeqCst) {
+            return Err(format!("NUMA node {} is not running", node_id));
+        }
+
+        node.stop_workers();
+        for port in &node.local_ports {
+            stop_port(port.port_id)?;
+        }
+
+        self.events.emit(LifecycleEvent::NodeStopped { node_id });
+        self.refresh_watchdog();
+        Ok(())
+    }
+
+    /// Запускает порты и рабочие потоки узла NUMA, остановленного `stop_node`
+    ///
+    /// Рабочие потоки создаются заново: их счетчики, как и при `attach_port`,
+    /// не попадают в уже запущенный StatsPublisher.
+    pub fn start_node(&mut self, node_id: usize, dpdk_config: &DpdkConfig) -> Result<(), String> {
+        let handlers = self
+            .handlers
+            .clone()
+            .ok_or("Packet processing is not running")?;
+        let settings = self.worker_settings(dpdk_config);
+        let node = self
+            .nodes
+            .get_mut(&node_id)
+            .ok_or_else(|| format!("NUMA node {} not available", node_id))?;
+
+        if node.running.load(Ordering::SeqCst) {
+            return Err(format!("NUMA node {} is already running", node_id));
+        }
+
+        for port in &node.local_ports {
+            restart_port(port.port_id)?;
+        }
+        node.start_workers(&handlers, &settings)?;
+
+        self.events.emit(LifecycleEvent::NodeStarted { node_id });
+        self.refresh_watchdog();
+        Ok(())
+    }
+
+    /// Приостанавливает или возобновляет опрос всех очередей узла NUMA
+    pub fn set_node_paused(&self, node_id: usize, paused: bool) -> Result<(), String> {
+        let node = self
+            .nodes
+            .get(&node_id)
+            .ok_or_else(|| format!("NUMA node {} not available", node_id))?;
+
+        for worker in &node.workers {
+            worker.control.paused.store(paused, Ordering::Relaxed);
+        }
+
+        log_info!(
+            "control",
+            if paused {
+                "Node paused"
+            } else {
+                "Node resumed"
+            },
+            node = node_id,
+            workers = node.workers.len()
+        );
+        Ok(())
+    }
+
+    /// Идентификаторы всех используемых портов
+    pub fn port_ids(&self) -> Vec<u16> {
+        let mut ports: Vec<u16> = self
+            .nodes
+            .values()
+            .flat_map(|node| node.local_ports.iter().map(|p| p.port_id))
+            .collect();
+        ports.sort_unstable();
+        ports
+    }
+
+    /// Пулы mbuf всех портов: `(порт, имя пула)`
+    pub fn mempools(&self) -> Vec<(u16, String)> {
+        let mut pools: Vec<(u16, String)> = self
+            .nodes
+            .values()
+            .flat_map(|node| node.local_ports.iter())
+            .flat_map(|port| {
+                port.rx_pools
+                    .iter()
+                    .map(|pool| (port.port_id, pool.clone()))
+            })
+            .collect();
+        pools.sort();
+        pools
+    }
+
+    /// Обращается ко всем страницам пулов mbuf и сегментов памяти EAL
+    ///
+    /// Вызывается до запуска рабочих потоков: первое обращение к странице
+    /// hugepage выделяет ее в ядре, и без этого шага его оплачивает первый
+    /// пакет, попавший в еще не затронутый буфер. Возвращает пройденный
+    /// объем в байтах.
+    pub fn prefault_memory(&self) -> u64 {
+        let mut touched = 0;
+        for (port_id, pool) in self.mempools() {
+            match prefault_mempool(&pool) {
+                Some(bytes) => touched += bytes,
+                None => log_warn!(
+                    "dpdk",
+                    "Mempool not found for prefault",
+                    port = port_id,
+                    pool = pool
+                ),
+            }
+        }
+        touched + prefault_hugepages()
+    }
+
+    /// Метрики всех рабочих потоков (для публикации вне горячего пути)
+    pub fn worker_metrics(&self) -> Vec<WorkerMetricsRef> {
+        self.nodes
+            .values()
+            .flat_map(|node| {
+                node.workers.iter().map(move |worker| {
+                    (
+                        node.node_id,
+                        worker.port_id,
+                        worker.queue_id,
+                        worker.metrics.clone(),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Параметры управления рабочими потоками для внешнего контроля
+    pub fn worker_controls(&self) -> Vec<WorkerControlRef> {
+        self.nodes
+            .values()
+            .flat_map(|node| node.workers.iter())
+            .map(|worker| (worker.port_id, worker.queue_id, worker.control.clone()))
+            .collect()
+    }
+
+    /// Собирает счетчики всех очередей RX
+    ///
+    /// Возвращает статистику по очередям и сумму по всем очередям.
+    pub fn get_stats(&self) -> (Vec<QueueStats>, WorkerStats) {
+        let mut queues: Vec<QueueStats> = self
+            .nodes
+            .values()
+            .flat_map(|node| node.queue_stats())
+            .collect();
+        queues.sort_by_key(|q| (q.port_id, q.queue_id));
+
+        let mut total = WorkerStats::default();
+        for queue in &queues {
+            total += queue.stats;
+        }
+
+        (queues, total)
+    }
+
+    /// Формирует текстовый отчет по счетчикам всех очередей RX
+    pub fn format_stats(&self) -> String {
+        let (queues, total) = self.get_stats();
+
+        let mut out = String::from("==== Worker Statistics ====\n");
+        for queue in &queues {
+            out.push_str(&format!(
+                "Node {} port {} queue {}: {}\n",
+                queue.node_id, queue.port_id, queue.queue_id, queue.stats
+            ));
+        }
+        out.push_str(&format!("Total: {}\n", total));
+        out
+    }
+
+    /// Формирует снимок счетчиков и задержек всех очередей RX в формате JSON
+    ///
+    /// Одна строка, пригодная для скриптов и систем мониторинга без Prometheus.
+    pub fn format_stats_json(&self) -> String {
+        let (queues, total) = self.get_stats();
+        let latency: HashMap<(u16, u16), (HistogramSnapshot, HistogramSnapshot)> = self
+            .nodes
+            .values()
+            .flat_map(|node| node.latency_snapshots())
+            .map(|(port_id, queue_id, handler, wire)| ((port_id, queue_id), (handler, wire)))
+            .collect();
+
+        let timestamp_ns = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+
+        let queues_json: Vec<String> = queues
+            .iter()
+            .map(|queue| {
+                let latency_json = match latency.get(&(queue.port_id, queue.queue_id)) {
+                    Some((handler, wire)) => format!(
+                        "{{\"handler\":{},\"wire_to_handler\":{}}}",
+                        handler.to_json(),
+                        wire.to_json()
+                    ),
+                    None => "null".to_string(),
+                };
+                format!(
+                    "{{\"node\":{},\"port\":{},\"queue\":{},\"counters\":{},\"latency\":{}}}",
+                    queue.node_id,
+                    queue.port_id,
+                    queue.queue_id,
+                    queue.stats.to_json(),
+                    latency_json
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\"timestamp_ns\":{},\"queues\":[{}],\"total\":{}}}",
+            timestamp_ns,
+            queues_json.join(","),
+            total.to_json()
+        )
+    }
+
+    /// Выводит счетчики по всем очередям RX
+    pub fn print_stats(&self) {
+        print!("{}", self.format_stats());
+    }
+
+    /// Приостанавливает или возобновляет опрос очереди RX
+    ///
+    /// Возобновление запускает и очередь, остановленную в NIC (`stop_queue`).
+    pub fn set_queue_paused(
+        &self,
+        port_id: u16,
+        queue_id: u16,
+        paused: bool,
+    ) -> Result<(), String> {
+        let worker = self
+            .nodes
+            .values()
+            .find_map(|node| node.find_worker(port_id, queue_id))
+            .ok_or_else(|| format!("No worker for port {} queue {}", port_id, queue_id))?;
+
+        if !paused && worker.control.rx_stopped.load(Ordering::SeqCst) {
+            start_rx_queue(port_id, queue_id)?;
+            worker.control.rx_stopped.store(false, Ordering::SeqCst);
+        }
+
+        worker.control.paused.store(paused, Ordering::Relaxed);
+        log_info!(
+            "control",
+            if paused {
+                "Queue paused"
+            } else {
+                "Queue resumed"
+            },
+            port = port_id,
+            queue = queue_id
+        );
+        Ok(())
+    }
+
+    /// Приостанавливает опрос очереди RX и останавливает очередь в NIC
+    /// (rte_eth_dev_rx_queue_stop), например на время обслуживания фида
+    ///
+    /// Очередь останавливается после того, как рабочий поток подтвердил паузу;
+    /// NIC освобождает ее дескрипторы, и пакеты, направленные в очередь,
+    /// отбрасываются NIC, не занимая mbuf. Доступно только для очередей,
+    /// которые опрашивает сам рабочий поток (не программный RSS и не
+    /// устройство событий). Возобновляется `set_queue_paused(.., false)`.
+    pub fn stop_queue(&self, port_id: u16, queue_id: u16, timeout: Duration) -> Result<(), String> {
+        let node = self
+            .nodes
+            .values()
+            .find(|node| node.find_worker(port_id, queue_id).is_some())
+            .ok_or_else(|| format!("No worker for port {} queue {}", port_id, queue_id))?;
+
+        let polls_queue = node
+            .local_ports
+            .iter()
+            .any(|port| port.port_id == port_id && port.software_rss_queues == 0)
+            && !node
+                .event_adapters
+                .iter()
+                .any(|adapter| adapter.port_id == port_id);
+        if !polls_queue {
+            return Err(format!(
+                "Queue {} of port {} is not polled directly by its worker",
+                queue_id, port_id
+            ));
+        }
+
+        let control = node
+            .find_worker(port_id, queue_id)
+            .map(|worker| worker.control.clone())
+            .ok_or_else(|| format!("No worker for port {} queue {}", port_id, queue_id))?;
+        if control.rx_stopped.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        self.set_queue_paused(port_id, queue_id, true)?;
+        let deadline = Instant::now() + timeout;
+        while !control.parked.load(Ordering::Acquire) {
+            if Instant::now() >= deadline {
+                return Err(format!(
+                    "Timed out waiting for worker of port {} queue {} to pause",
+                    port_id, queue_id
+                ));
+            }
+            std::thread::sleep(Duration::from_micros(50));
+        }
+
+        stop_rx_queue(port_id, queue_id)?;
+        control.rx_stopped.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Выполняет `f` в точке покоя: все рабочие потоки остановлены между пачками
+    ///
+    /// Рабочие потоки приостанавливаются и подтверждают паузу; после `f`
+    /// восстанавливается прежнее состояние пауз. Пакеты, пришедшие за это
+    /// время, остаются в кольцах NIC.
+    pub fn quiesce<R>(&self, timeout: Duration, f: impl FnOnce() -> R) -> Result<R, String> {
+        let controls: Vec<(Arc<WorkerControl>, bool)> = self
+            .nodes
+            .values()
+            .flat_map(|node| node.workers.iter())
+            .map(|worker| {
+                let was_paused = worker.control.paused.swap(true, Ordering::SeqCst);
+                (worker.control.clone(), was_paused)
+            })
+            .collect();
+
+        let restore = || {
+            for (control, was_paused) in &controls {
+                control.paused.store(*was_paused, Ordering::SeqCst);
+            }
+        };
+
+        let deadline = Instant::now() + timeout;
+        while !controls
+            .iter()
+            .all(|(control, _)| control.parked.load(Ordering::Acquire))
+        {
+            if Instant::now() >= deadline {
+                restore();
+                return Err("Timed out waiting for workers to reach a quiesce point".to_string());
+            }
+            std::thread::sleep(Duration::from_micros(50));
+        }
+
+        let result = f();
+        restore();
+        Ok(result)
+    }
+
+    /// Изменяет размер пачки rx_burst всех рабочих потоков
+    pub fn set_burst_size(&self, burst_size: u16) -> Result<(), String> {
+        if burst_size == 0 || burst_size > MAX_BURST_SIZE {
+            return Err(format!(
+                "Burst size must be between 1 and {}",
+                MAX_BURST_SIZE
+            ));
+        }
+
+        for node in self.nodes.values() {
+            for worker in &node.workers {
+                worker
+                    .control
+                    .burst_size
+                    .store(burst_size, Ordering::Relaxed);
+            }
+        }
+
+        log_info!("control", "Burst size changed", burst_size = burst_size);
+        Ok(())
+    }
+
+    /// Переносит рабочий поток очереди RX на другое ядро во время работы
+    ///
+    /// Поток завершает текущую пачку, перепривязывается к ядру и продолжает
+    /// опрос. Ядро на другом узле NUMA допускается, но память пула пакетов
+    /// остается на исходном узле, поэтому выводится предупреждение.
+    pub fn move_queue(&self, port_id: u16, queue_id: u16, core_id: usize) -> Result<(), String> {
+        if core_id >= configured_cpus() || !self.cpu_topology.is_allowed_core(core_id) {
+            return Err(format!("Core {} is not available", core_id));
+        }
+
+        let (node_id, worker) = self
+            .nodes
+            .values()
+            .find_map(|node| {
+                node.find_worker(port_id, queue_id)
+                    .map(|worker| (node.node_id, worker))
+            })
+            .ok_or_else(|| format!("No worker for port {} queue {}", port_id, queue_id))?;
+
+        if let Some(core_node) = self.core_node(core_id) {
+            if core_node != node_id {
+                log_warn!(
+                    "control",
+                    "Moving worker to a core on a remote NUMA node",
+                    port = port_id,
+                    queue = queue_id,
+                    core = core_id,
+                    core_node = core_node,
+                    port_node = node_id
+                );
+            }
+        }
+
+        let busy = self.nodes.values().flat_map(|n| n.workers.iter()).any(|w| {
+            !(w.port_id == port_id && w.queue_id == queue_id)
+                && w.control.current_core.load(Ordering::Relaxed) == core_id
+        });
+        if busy {
+            log_warn!(
+                "control",
+                "Target core is shared with another worker",
+                core = core_id
+            );
+        }
+
+        worker.control.target_core.store(core_id, Ordering::Release);
+
+        // Приостановленный поток выполнит перенос после возобновления
+        if worker.control.paused.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(1);
+        while worker.control.target_core.load(Ordering::Acquire) != NO_CORE_CHANGE {
+            if std::time::Instant::now() >= deadline {
+                return Err(format!(
+                    "Worker for port {} queue {} did not acknowledge the move",
+                    port_id, queue_id
+                ));
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+
+        if worker.control.current_core.load(Ordering::Acquire) != core_id {
+            return Err(format!("Failed to pin worker to core {}", core_id));
+        }
+
+        Ok(())
+    }
+
+    /// Ядра, на которых сейчас работают рабочие потоки
+    pub fn worker_cores(&self) -> Vec<usize> {
+        let mut cores: Vec<usize> = self
+            .nodes
+            .values()
+            .flat_map(|node| node.workers.iter())
+            .map(|worker| worker.control.current_core.load(Ordering::Relaxed))
+            .collect();
+        cores.sort_unstable();
+        cores.dedup();
+        cores
+    }
+
+    /// Ядра, которые займут потоки обработки пакетов настроенных портов
+    ///
+    /// Вычисляется до запуска (после `setup_ports`) так же, как ядра
+    /// назначает `NumaNode::start_port_workers`: закрепленные очереди на свои
+    /// ядра, остальные рабочие потоки по кругу с первого незакрепленного
+    /// ядра узла, за ними распределитель и ядра декодирования.
+    pub fn planned_worker_cores(&self) -> Vec<usize> {
+        let mut cores = Vec::new();
+        for node in self.nodes.values() {
+            if node.local_cpus.is_empty() {
+                continue;
+            }
+            let cpus = node.shared_cpus();
+            for port in &node.local_ports {
+                let workers = port.num_rx_queues.max(port.software_rss_queues);
+                for queue_id in 0..workers {
+                    let core = port.settings.queue_core(queue_id);
+                    cores.push(core.unwrap_or(cpus[queue_id as usize % cpus.len()].id));
+                }
+                let workers = workers as usize;
+                let feeder = usize::from(port.software_rss_queues > 0);
+                let decoders = (port.settings.decode_cores as usize).min(workers);
+                cores.extend(
+                    (workers..workers + feeder + decoders).map(|index| cpus[index % cpus.len()].id),
+                );
+            }
+        }
+        cores.sort_unstable();
+        cores.dedup();
+        cores
+    }
+
+    /// Проверяет закрепление очередей за ядрами (`PortConfig::with_queue_core`) всех портов
+    pub fn validate_queue_cores(&self) -> Result<(), String> {
+        for (node_id, node) in &self.nodes {
+            for port in &node.local_ports {
+                Self::check_queue_cores(&self.cpu_topology, &self.numa_topology, *node_id, port)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Проверяет закрепление очередей порта на узле `node_id`
+    ///
+    /// Несуществующая очередь или ядро и ядро вне рабочих (резерв, служебное
+    /// ядро) - ошибка. Ядро другого узла NUMA и одно ядро на несколько
+    /// очередей допускаются с предупреждением.
+    fn check_queue_cores(
+        cpu_topology: &CpuTopology,
+        numa_topology: &NumaTopology,
+        node_id: usize,
+        port: &DpdkPort,
+    ) -> Result<(), String> {
+        let workers = port.num_rx_queues.max(port.software_rss_queues);
+        let mut used: Vec<usize> = Vec::new();
+
+        for &(queue_id, core_id) in &port.settings.queue_cores {
+            if queue_id >= workers {
+                return Err(format!(
+                    "Port {} queue {} pinned to core {} does not exist ({} queues)",
+                    port.port_id, queue_id, core_id, workers
+                ));
+            }
+            if core_id >= configured_cpus() {
+                return Err(format!(
+                    "Port {} queue {} pinned to core {} that does not exist ({} logical cores)",
+                    port.port_id,
+                    queue_id,
+                    core_id,
+                    configured_cpus()
+                ));
+            }
+            if !cpu_topology.is_allowed_core(core_id) {
+                return Err(format!(
+                    "Port {} queue {} pinned to core {} outside the process cpuset",
+                    port.port_id, queue_id, core_id
+                ));
+            }
+            if !cpu_topology.is_worker_core(core_id) || housekeeping::core() == Some(core_id) {
+                return Err(format!(
+                    "Port {} queue {} pinned to reserved core {}",
+                    port.port_id, queue_id, core_id
+                ));
+            }
+
+            let core_node = numa_topology
+                .node_cores
+                .iter()
+                .find(|(_, cores)| cores.contains(&core_id))
+                .map(|(&node, _)| node);
+            if let Some(core_node) = core_node.filter(|&core_node| core_node != node_id) {
+                log_warn!(
+                    "numa",
+                    "Queue pinned to a core on a remote NUMA node",
+                    port = port.port_id,
+                    queue = queue_id,
+                    core = core_id,
+                    port_node = node_id,
+                    core_node = core_node
+                );
+            }
+            if used.contains(&core_id) {
+                log_warn!(
+                    "numa",
+                    "Several queues pinned to one core",
+                    port = port.port_id,
+                    queue = queue_id,
+                    core = core_id
+                );
+            }
+            used.push(core_id);
+        }
+        Ok(())
+    }
+
+    /// Формирует отчет о размещении рабочих потоков по ядрам и узлам NUMA
+    pub fn format_worker_info(&self) -> String {
+        let mut out = String::from("==== Worker Placement ====\n");
+
+        let mut node_ids: Vec<&usize> = self.nodes.keys().collect();
+        node_ids.sort();
+
+        for node_id in node_ids {
+            let node = &self.nodes[node_id];
+            for worker in &node.workers {
+                let core = worker.control.current_core.load(Ordering::Relaxed);
+                let core_node = self.core_node(core);
+                let mismatch = core_node.is_some_and(|n| n != node.node_id);
+
+                out.push_str(&format!(
+                    "Port {} queue {}: core {} (core node {}, port node {}, L3 {}){}{}{}\n",
+                    worker.port_id,
+                    worker.queue_id,
+                    core,
+                    core_node.map_or("?".to_string(), |n| n.to_string()),
+                    node.node_id,
+                    self.l3_label(core),
+                    if mismatch { " NUMA MISMATCH" } else { "" },
+                    if worker.control.paused.load(Ordering::Relaxed) {
+                        " paused"
+                    } else {
+                        ""
+                    },
+                    if worker.control.breaker_open.load(Ordering::Relaxed) {
+                        " breaker open"
+                    } else {
+                        ""
+                    }
+                ));
+            }
+        }
+
+        out.push_str(&self.format_l3_groups());
+        out
+    }
+
+    /// Домены L3 потоков каждого порта: рабочих, распределителя и ядер декодирования
+    fn format_l3_groups(&self) -> String {
+        let mut ports: HashMap<u16, Vec<(&str, usize)>> = HashMap::new();
+        for node in self.nodes.values() {
+            for worker in &node.workers {
+                let core = worker.control.current_core.load(Ordering::Relaxed);
+                ports.entry(worker.port_id).or_default().push(("rx", core));
+            }
+            for distributor in &node.distributors {
+                let entry = ports.entry(distributor.port_id).or_default();
+                entry.push(("feeder", distributor.core_id.id));
+            }
+            for decoder in &node.decoders {
+                let entry = ports.entry(decoder.port_id).or_default();
+                entry.push(("decode", decoder.core_id.id));
+            }
+        }
+
+        let mut port_ids: Vec<u16> = ports.keys().copied().collect();
+        port_ids.sort_unstable();
+
+        let mut out = String::new();
+        for port_id in port_ids {
+            let threads = &ports[&port_id];
+            let mut domains: Vec<String> = threads
+                .iter()
+                .map(|&(_, core)| self.l3_label(core))
+                .collect();
+            domains.sort();
+            domains.dedup();
+
+            let cores: Vec<String> = threads
+                .iter()
+                .map(|(role, core)| format!("{}:{}", role, core))
+                .collect();
+            out.push_str(&format!(
+                "Port {} L3 domains [{}]{}: {}\n",
+                port_id,
+                domains.join(","),
+                if domains.len() > 1 { " SPLIT" } else { "" },
+                cores.join(" ")
+            ));
+        }
+        out
+    }
+
+    /// Домен L3 ядра для отчетов (`?` - сведений о кэше нет)
+    fn l3_label(&self, core_id: usize) -> String {
+        self.cpu_topology
+            .get_l3_domain(core_id)
+            .map_or("?".to_string(), |domain| domain.to_string())
+    }
+
+    /// Узел NUMA, которому принадлежит ядро
+    fn core_node(&self, core_id: usize) -> Option<usize> {
+        if !self.numa_available {
+            return Some(0);
+        }
+
+        (0..NumaAllocator::get_node_count())
+            .find(|&node| NumaAllocator::get_node_cpus(node).contains(&core_id))
+    }
+
+    /// Добавляет класс фида с именованным обработчиком во время работы
+    pub fn add_feed(&self, class: FeedClass, handler_name: &str) -> Result<(), String> {
+        let handlers = self
+            .handlers
+            .as_ref()
+            .ok_or_else(|| "Packet processing is not running".to_string())?;
+
+        let name = class.name.clone();
+        handlers.update(|registry| registry.add_feed_class(class, handler_name))?;
+
+        log_info!("control", "Feed added", feed = name, handler = handler_name);
+        Ok(())
+    }
+
+    /// Заменяет или снимает (None) фильтр приема порта
+    pub fn set_port_filter(&self, port_id: u16, filter: Option<RxFilter>) -> Result<(), String> {
+        let handlers = self
+            .handlers
+            .as_ref()
+            .ok_or_else(|| "Packet processing is not running".to_string())?;
+
+        let expression = filter
+            .as_ref()
+            .map_or_else(|| "none".to_string(), |filter| filter.to_string());
+        handlers.update(|registry| {
+            registry.set_port_filter(port_id, filter);
+            Ok(())
+        })?;
+
+        log_info!(
+            "control",
+            "RX filter changed",
+            port = port_id,
+            filter = expression
+        );
+        Ok(())
+    }
+
+    /// Фильтр приема порта (None - пакеты не фильтруются)
+    pub fn port_filter(&self, port_id: u16) -> Option<String> {
+        self.handlers.as_ref().and_then(|handlers| {
+            handlers
+                .read(|registry| {
+                    registry
+                        .port_filter(port_id)
+                        .map(|filter| filter.to_string())
+                })
+                .flatten()
+        })
+    }
+
+    /// Выводит перцентили задержек по всем рабочим потокам
+    pub fn print_latency_stats(&self) {
+        print!("{}", self.format_latency_stats());
+    }
+
+    /// Формирует текстовый отчет по перцентилям задержек
+    pub fn format_latency_stats(&self) -> String {
+        let mut out = String::from("==== Worker Latency ====\n");
+
+        for (node_id, node) in &self.nodes {
+            for (port_id, queue_id, handler, wire) in node.latency_snapshots() {
+                if handler.count == 0 {
+                    continue;
+                }
+
+                out.push_str(&format!(
+                    "Node {} port {} queue {}: handler [{}] wire-to-handler [{}]\n",
+                    node_id, port_id, queue_id, handler, wire
+                ));
+            }
+        }
+        out
     }
 
     /// Выводит информацию о топологии NUMA
@@ -157,8 +1802,13 @@ impl NumaManager {
 
             for port in &node.local_ports {
                 println!(
-                    "    Port {} ({}): RX queues: {}, TX queues: {}",
-                    port.port_id, port.if_name, port.num_rx_queues, port.num_tx_queues
+                    "    Port {} ({}): RX queues: {}, TX queues: {}, RX ring: {}, TX ring: {}",
+                    port.port_id,
+                    port.if_name,
+                    port.num_rx_queues,
+                    port.num_tx_queues,
+                    port.rx_ring_size,
+                    port.tx_ring_size
                 );
             }
         }
@@ -166,6 +1816,74 @@ impl NumaManager {
         println!("====================================");
     }
 
+    /// Формирует снимок эффективной конфигурации, включая производные значения
+    /// (распределение ядер и очередей, аргументы EAL, размеры пулов)
+    pub fn config_snapshot(&self, dpdk_config: &DpdkConfig) -> ConfigSnapshot {
+        let mut snapshot = ConfigSnapshot::new();
+
+        dpdk_config.write_snapshot(&mut snapshot);
+
+        let build = build_info();
+        snapshot.set("build.git_commit", build.git_commit);
+        snapshot.set("build.dpdk_version", build.dpdk_version);
+
+        snapshot.set(
+            "dpdk.eal_args",
+            self.generate_eal_args(dpdk_config).join(" "),
+        );
+        snapshot.set("numa.available", self.numa_available);
+        snapshot.set("numa.node_count", self.nodes.len());
+        snapshot.set("cpu.total_cores", self.cpu_topology.total_cores);
+        snapshot.set("cpu.physical_cores", self.cpu_topology.physical_cores);
+
+        for (node_id, node) in &self.nodes {
+            let prefix = format!("node{}", node_id);
+            let cores: Vec<usize> = node.local_cpus.iter().map(|c| c.id).collect();
+
+            snapshot.set_list(&format!("{}.cores", prefix), Some(&cores));
+            snapshot.set(&format!("{}.core_mask", prefix), node.generate_core_mask());
+            snapshot.set(
+                &format!("{}.socket_mem", prefix),
+                node.socket_mem(dpdk_config),
+            );
+            snapshot.set(
+                &format!("{}.packet_pool_size", prefix),
+                dpdk_config.burst_size,
+            );
+
+            for port in &node.local_ports {
+                let port_prefix = format!("{}.port{}", prefix, port.port_id);
+                snapshot.set(&format!("{}.if_name", port_prefix), &port.if_name);
+                snapshot.set(
+                    &format!("{}.pci_address", port_prefix),
+                    port.pci_address.as_deref().unwrap_or("none"),
+                );
+                snapshot.set(&format!("{}.rx_queues", port_prefix), port.num_rx_queues);
+                snapshot.set(&format!("{}.tx_queues", port_prefix), port.num_tx_queues);
+                snapshot.set(&format!("{}.rx_ring_size", port_prefix), port.rx_ring_size);
+                snapshot.set(&format!("{}.tx_ring_size", port_prefix), port.tx_ring_size);
+                snapshot.set(&format!("{}.flow_rules", port_prefix), port.flows.len());
+                snapshot.set(
+                    &format!("{}.software_rss_queues", port_prefix),
+                    port.software_rss_queues,
+                );
+                snapshot.set(&format!("{}.rx_pools", port_prefix), port.rx_pools.len());
+
+                if !node.local_cpus.is_empty() {
+                    for queue_id in 0..port.num_rx_queues {
+                        let core_idx = (queue_id as usize) % node.local_cpus.len();
+                        snapshot.set(
+                            &format!("{}.queue{}.core", port_prefix, queue_id),
+                            node.local_cpus[core_idx].id,
+                        );
+                    }
+                }
+            }
+        }
+
+        snapshot
+    }
+
     /// Проверяет, доступна ли NUMA
     pub fn is_numa_available(&self) -> bool {
         self.numa_available
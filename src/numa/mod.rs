@@ -1,4 +1,13 @@
+#[cfg(feature = "dpdk")]
+pub mod burst_wait;
 pub mod ffi;
+#[cfg(feature = "dpdk")]
 pub mod manager;
+#[cfg(feature = "dpdk")]
 pub mod node;
+#[cfg(feature = "dpdk")]
+pub mod steal;
+pub mod supervisor;
 pub mod topology;
+#[cfg(feature = "dpdk")]
+pub mod watchdog;
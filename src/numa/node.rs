@@ -1,25 +1,61 @@
 // src/numa/node.rs
 use core_affinity::CoreId;
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
+    atomic::{AtomicBool, AtomicI32, AtomicU16, AtomicU64, AtomicUsize, Ordering},
     Arc,
 };
 use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
+use crate::control::events::{EventBus, LifecycleEvent};
+use crate::cpu::idle::{IdleBackoff, IdlePolicy};
+use crate::cpu::realtime::RealtimeConfig;
 use crate::cpu::topology::CpuTopology;
-use crate::dpdk::config::DpdkConfig;
+use crate::dpdk::config::{DpdkConfig, PortSettings};
+use crate::dpdk::eventdev::{EventRxAdapter, EventScheduler};
+use crate::dpdk::ffi::RteMbuf;
+use crate::dpdk::flow::Flow;
+use crate::dpdk::init::DpdkPortInfo;
+use crate::dpdk::pipeline::{PipelineDecoder, PipelineStage, StagedKind, PIPELINE_RING_SIZE};
+use crate::dpdk::rx_intr::RxInterrupt;
+use crate::dpdk::soft_rss::{MbufRing, SoftRssDistributor, SOFT_RSS_RING_SIZE};
+use crate::mem::spsc;
+use crate::metrics::counters::{QueueStats, WorkerMetrics};
+use crate::metrics::histogram::HistogramSnapshot;
+use crate::numa::burst_wait::AdaptiveBurstWait;
 use crate::numa::ffi::NumaAllocator;
+use crate::numa::steal::{StealGroup, StealMember};
+use crate::numa::supervisor::{call_isolated, PanicAction, PanicPolicy};
 use crate::numa::topology::NumaTopology;
+use crate::packet::breaker::{BreakerTransition, CircuitBreaker, CircuitBreakerConfig};
 use crate::packet::data::PacketData;
+use crate::packet::handler::{QueueDispatch, SharedHandlers};
+use crate::packet::parser::{parse_chained, parse_l2, ParseError, ParsedPacket};
 use crate::packet::pool::PacketDataPool;
+use crate::packet::reassembly::{FragmentResult, FragmentTable, ReassemblyConfig};
+use crate::time;
+use crate::time::wheel::Timers;
+use crate::{log_error, log_info, log_warn};
 
 /// Информация о DPDK порте
 #[derive(Debug)]
 pub struct DpdkPort {
     pub port_id: u16,
     pub if_name: String,
+    pub pci_address: Option<String>,
     pub num_rx_queues: u16,
     pub num_tx_queues: u16,
+    /// Итоговые размеры колец дескрипторов (после корректировки PMD, 0 - порт не настроен)
+    pub rx_ring_size: u16,
+    pub tx_ring_size: u16,
+    /// Параметры порта из конфигурации (очереди выше - после проверки возможностей NIC)
+    pub settings: PortSettings,
+    /// Созданные правила Flow Director (удаляются вместе с портом)
+    pub flows: Vec<Flow>,
+    /// Рабочих потоков программного RSS (0 - пакеты распределяет NIC)
+    pub software_rss_queues: u16,
+    /// Имена пулов mbuf очередей RX (один - общий пул порта)
+    pub rx_pools: Vec<String>,
 }
 
 /// Рабочий поток
@@ -29,10 +65,148 @@ pub struct Worker {
     pub core_id: CoreId,
     pub port_id: u16,
     pub queue_id: u16,
+    /// Счетчики и гистограммы задержек, заполняемые рабочим потоком
+    pub metrics: Arc<WorkerMetrics>,
+    /// Параметры, изменяемые во время работы
+    pub control: Arc<WorkerControl>,
+    /// Параметры запуска замены потока (None - поток не заменяется)
+    respawn: Option<WorkerSpawn>,
 }
 
-/// Тип обработчика пакетов
-pub type PacketHandler = Arc<dyn Fn(u16, &PacketData) + Send + Sync + 'static>;
+/// Параметры, с которыми запускается замена рабочего потока
+struct WorkerSpawn {
+    handlers: Arc<SharedHandlers>,
+    settings: WorkerSettings,
+    source: RxSource,
+}
+
+impl std::fmt::Debug for WorkerSpawn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkerSpawn")
+            .field("settings", &self.settings)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Источник пакетов рабочего потока
+#[derive(Clone)]
+enum RxSource {
+    /// Аппаратная очередь RX порта
+    Queue,
+    /// Кольцо программного распределителя (NIC без многоочередного RSS)
+    Ring(Arc<MbufRing>),
+    /// Порт устройства событий (режим eventdev)
+    Event(Arc<EventScheduler>, u8),
+}
+
+/// Максимальный размер пачки rx_burst, задаваемый во время работы
+pub const MAX_BURST_SIZE: u16 = 512;
+
+/// Управление рабочим потоком во время работы
+///
+/// Рабочий поток читает поля раз в пачку, поэтому изменения вступают в силу
+/// со следующего опроса очереди.
+#[derive(Debug)]
+pub struct WorkerControl {
+    /// Опрос очереди приостановлен
+    pub paused: AtomicBool,
+    /// Поток заметил `paused` и стоит между пачками (точка покоя)
+    pub parked: AtomicBool,
+    /// Остановить только этот поток (например, при отключении порта)
+    pub stop: AtomicBool,
+    /// Текущий размер пачки rx_burst
+    pub burst_size: AtomicU16,
+    /// Ядро, на которое нужно перенести поток (`NO_CORE_CHANGE` - без переноса)
+    pub target_core: AtomicUsize,
+    /// Ядро, на котором поток работает сейчас
+    pub current_core: AtomicUsize,
+    /// Идентификатор потока ОС (0 - поток еще не запущен)
+    pub tid: AtomicI32,
+    /// Выключатель по интенсивности сработал, очередь в защитном режиме
+    pub breaker_open: AtomicBool,
+    /// Пульс: продвигается дважды за опрос, нечетный - поток внутри rx_burst
+    pub heartbeat: AtomicU64,
+    /// Перехваченных паник обработчиков
+    pub panics: AtomicU64,
+    /// Очередь остановлена в NIC (см. `NumaManager::stop_queue`)
+    pub rx_stopped: AtomicBool,
+}
+
+/// Значение `target_core`, означающее отсутствие запроса на перенос
+pub const NO_CORE_CHANGE: usize = usize::MAX;
+
+impl WorkerControl {
+    fn new(burst_size: u16, core_id: usize) -> Self {
+        Self {
+            paused: AtomicBool::new(false),
+            parked: AtomicBool::new(false),
+            stop: AtomicBool::new(false),
+            burst_size: AtomicU16::new(burst_size.clamp(1, MAX_BURST_SIZE)),
+            target_core: AtomicUsize::new(NO_CORE_CHANGE),
+            current_core: AtomicUsize::new(core_id),
+            tid: AtomicI32::new(0),
+            breaker_open: AtomicBool::new(false),
+            heartbeat: AtomicU64::new(0),
+            panics: AtomicU64::new(0),
+            rx_stopped: AtomicBool::new(false),
+        }
+    }
+}
+
+/// Параметры рабочего потока, выводимые из конфигурации DPDK
+#[derive(Debug, Clone)]
+pub struct WorkerSettings {
+    /// Размер пачки rx_burst
+    pub burst_size: u32,
+    /// Параметры сборки IPv4 фрагментов (None - сборка отключена)
+    pub reassembly: Option<ReassemblyConfig>,
+    /// Флаги разбора пакетов (декапсуляция туннелей)
+    pub extract_flags: u32,
+    /// UDP порт VXLAN
+    pub vxlan_port: u16,
+    /// Максимальное адаптивное ожидание после неполной пачки (0 - отключено)
+    pub burst_wait_ns: u64,
+    /// Выключатель по интенсивности входящего потока (None - отключен)
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// Порт принимает пакеты цепочкой mbuf (задается для порта при запуске потоков)
+    pub chained_mbufs: bool,
+    /// Устройство событий режима eventdev (None - очередь на ядро)
+    pub event_scheduler: Option<Arc<EventScheduler>>,
+    /// Таймеры протоколов, проверяемые при пустом опросе (None - не проверяются)
+    pub timers: Option<Arc<Timers>>,
+    /// Разрешено перераспределение пачек между рабочими потоками порта
+    pub work_stealing: bool,
+    /// Группа перераспределения порта (задается для порта при запуске потоков)
+    pub steal_group: Option<Arc<StealGroup>>,
+    /// Поведение при пустых опросах
+    pub idle_policy: IdlePolicy,
+    /// Планирование реального времени (None - обычный приоритет)
+    pub realtime: Option<RealtimeConfig>,
+    /// Реакция на панику обработчика
+    pub panic_policy: PanicPolicy,
+}
+
+impl WorkerSettings {
+    /// Формирует параметры рабочего потока из конфигурации DPDK
+    pub fn from_config(dpdk_config: &DpdkConfig) -> Self {
+        Self {
+            burst_size: dpdk_config.burst_size,
+            reassembly: dpdk_config.reassembly_config(),
+            extract_flags: dpdk_config.extract_flags(),
+            vxlan_port: dpdk_config.vxlan_udp_port,
+            burst_wait_ns: dpdk_config.adaptive_burst_wait_ns,
+            circuit_breaker: dpdk_config.circuit_breaker,
+            chained_mbufs: false,
+            event_scheduler: None,
+            timers: None,
+            work_stealing: dpdk_config.use_work_stealing,
+            steal_group: None,
+            idle_policy: dpdk_config.idle_policy,
+            realtime: dpdk_config.realtime,
+            panic_policy: dpdk_config.panic_policy,
+        }
+    }
+}
 
 /// Автономный узел NUMA
 pub struct NumaNode {
@@ -44,30 +218,33 @@ pub struct NumaNode {
     pub local_ports: Vec<DpdkPort>,
     /// Рабочие потоки
     pub workers: Vec<Worker>,
+    /// Распределители программного RSS
+    pub distributors: Vec<SoftRssDistributor>,
+    /// Производители устройства событий (режим eventdev)
+    pub event_adapters: Vec<EventRxAdapter>,
+    /// Ядра декодирования (режим конвейера)
+    pub decoders: Vec<PipelineDecoder>,
     /// Флаг работы
     pub running: Arc<AtomicBool>,
+    /// Шина событий жизненного цикла (общая с менеджером)
+    pub events: EventBus,
 }
 
 impl NumaNode {
     /// Создает новый узел NUMA
-    pub fn new(node_id: usize, cpu_topology: &CpuTopology, _numa_topology: &NumaTopology) -> Self {
-        let local_cpus = if NumaAllocator::is_available() {
-            let numa_cpus = NumaAllocator::get_node_cpus(node_id);
+    pub fn new(
+        node_id: usize,
+        cpu_topology: &CpuTopology,
+        _numa_topology: &NumaTopology,
+        events: EventBus,
+    ) -> Self {
+        let local_cpus = Self::worker_cpus(node_id, cpu_topology);
 
-            numa_cpus
-                .into_iter()
-                .filter(|&id| id != 0) // Исключаем ядро 0
-                .filter(|&id| cpu_topology.is_primary_logical_core(id)) // Берем только первые логические ядра (без HT)
-                .map(|id| CoreId { id })
-                .collect()
-        } else {
-            cpu_topology.get_filtered_core_ids()
-        };
-
-        println!(
-            "Created NUMA node {} with {} CPU cores",
-            node_id,
-            local_cpus.len()
+        log_info!(
+            "numa",
+            "Created NUMA node",
+            node = node_id,
+            cores = local_cpus.len()
         );
 
         NumaNode {
@@ -75,42 +252,81 @@ impl NumaNode {
             local_cpus,
             local_ports: Vec::new(),
             workers: Vec::new(),
+            distributors: Vec::new(),
+            event_adapters: Vec::new(),
+            decoders: Vec::new(),
             running: Arc::new(AtomicBool::new(false)),
+            events,
         }
     }
 
-    /// Проверяет, принадлежит ли сетевая карта этому узлу NUMA
-    pub fn is_local_nic(&self, if_name: &str, numa_topology: &NumaTopology) -> bool {
-        if let Some(nic_node) = numa_topology.get_nic_node(if_name) {
-            nic_node == self.node_id
+    /// Ядра узла для рабочих потоков по правилам резервирования `CpuTopology`
+    pub fn worker_cpus(node_id: usize, cpu_topology: &CpuTopology) -> Vec<CoreId> {
+        if NumaAllocator::is_available() {
+            NumaAllocator::get_node_cpus(node_id)
+                .into_iter()
+                .filter(|&id| cpu_topology.is_worker_core(id))
+                .map(|id| CoreId { id })
+                .collect()
         } else {
-            true
+            cpu_topology.get_filtered_core_ids()
+        }
+    }
+
+    /// Проверяет, принадлежит ли сетевая карта этому узлу NUMA
+    ///
+    /// Сначала используется PCI адрес (работает и для портов, отвязанных от
+    /// драйвера ядра), затем имя интерфейса ядра.
+    pub fn is_local_nic(
+        &self,
+        if_name: &str,
+        pci_address: Option<&str>,
+        numa_topology: &NumaTopology,
+    ) -> bool {
+        let nic_node = pci_address
+            .and_then(|pci| numa_topology.get_device_node(pci))
+            .or_else(|| numa_topology.get_nic_node(if_name));
+
+        match nic_node {
+            Some(node) => node == self.node_id,
+            None => true,
         }
     }
 
     /// Регистрирует локальную сетевую карту
     pub fn register_port(
         &mut self,
-        port_id: u16,
-        if_name: &str,
-        num_rx_queues: u16,
-        num_tx_queues: u16,
+        port: &DpdkPortInfo,
+        settings: PortSettings,
         numa_topology: &NumaTopology,
     ) -> bool {
-        if !self.is_local_nic(if_name, numa_topology) {
+        let port_id = port.port_id;
+        let if_name = port.if_name.as_str();
+
+        if !self.is_local_nic(if_name, port.pci_address.as_deref(), numa_topology) {
             return false;
         }
 
-        println!(
-            "Registering port {} ({}) on NUMA node {}",
-            port_id, if_name, self.node_id
+        log_info!(
+            "numa",
+            "Registering port",
+            port = port_id,
+            if_name = if_name,
+            node = self.node_id
         );
 
         self.local_ports.push(DpdkPort {
             port_id,
             if_name: if_name.to_string(),
-            num_rx_queues,
-            num_tx_queues,
+            pci_address: port.pci_address.clone(),
+            num_rx_queues: settings.num_rx_queues,
+            num_tx_queues: settings.num_tx_queues,
+            rx_ring_size: 0,
+            tx_ring_size: 0,
+            settings,
+            flows: Vec::new(),
+            software_rss_queues: 0,
+            rx_pools: Vec::new(),
         });
 
         true
@@ -119,8 +335,8 @@ impl NumaNode {
     /// Запускает рабочие потоки для обработки пакетов
     pub fn start_workers(
         &mut self,
-        packet_handler: PacketHandler,
-        burst_size: u32,
+        handlers: &Arc<SharedHandlers>,
+        settings: &WorkerSettings,
     ) -> Result<(), String> {
         if self.running.load(Ordering::SeqCst) {
             return Err("Workers already running".to_string());
@@ -128,84 +344,527 @@ impl NumaNode {
 
         self.running.store(true, Ordering::SeqCst);
 
-        for port in &self.local_ports {
-            let port_id = port.port_id;
-            let num_rx_queues = port.num_rx_queues;
+        let ports: Vec<u16> = self.local_ports.iter().map(|p| p.port_id).collect();
+        for port_id in ports {
+            self.start_port_workers(port_id, handlers, settings)?;
+        }
 
-            println!(
-                "Starting {} worker threads for port {} on NUMA node {}",
-                num_rx_queues, port_id, self.node_id
-            );
+        log_info!(
+            "worker",
+            "Started worker threads",
+            workers = self.workers.len(),
+            node = self.node_id
+        );
+        Ok(())
+    }
+
+    /// Запускает рабочие потоки для всех очередей RX порта
+    ///
+    /// Используется и при запуске узла, и для порта, подключенного во время работы.
+    pub fn start_port_workers(
+        &mut self,
+        port_id: u16,
+        handlers: &Arc<SharedHandlers>,
+        settings: &WorkerSettings,
+    ) -> Result<(), String> {
+        let (num_rx_queues, software_rss_queues, chained_mbufs, decode_cores, queue_cores) = self
+            .local_ports
+            .iter()
+            .find(|p| p.port_id == port_id)
+            .map(|p| {
+                (
+                    p.num_rx_queues,
+                    p.software_rss_queues,
+                    p.settings.rx_chained_mbufs(),
+                    p.settings.decode_cores,
+                    p.settings.queue_cores.clone(),
+                )
+            })
+            .ok_or_else(|| {
+                format!(
+                    "Port {} is not registered on NUMA node {}",
+                    port_id, self.node_id
+                )
+            })?;
+
+        let settings = WorkerSettings {
+            chained_mbufs,
+            ..settings.clone()
+        };
+
+        // В режиме eventdev рабочие потоки читают порты устройства событий
+        let event = settings.event_scheduler.as_ref().and_then(|scheduler| {
+            scheduler
+                .layout(port_id)
+                .map(|layout| (scheduler.clone(), layout))
+        });
+
+        // При программном RSS рабочие потоки читают кольца распределителя
+        let mut rings = Vec::new();
+        let num_workers = if let Some((_, layout)) = &event {
+            layout.workers
+        } else if software_rss_queues > 0 {
+            rings = (0..software_rss_queues)
+                .map(|_| Arc::new(MbufRing::new(SOFT_RSS_RING_SIZE)))
+                .collect();
+            software_rss_queues
+        } else {
+            num_rx_queues
+        };
 
-            if self.local_cpus.is_empty() {
-                return Err(format!("No cores available for NUMA node {}", self.node_id));
+        log_info!(
+            "worker",
+            "Starting worker threads",
+            port = port_id,
+            workers = num_workers,
+            node = self.node_id
+        );
+
+        if self.local_cpus.is_empty() {
+            return Err(format!("No cores available for NUMA node {}", self.node_id));
+        }
+
+        // Узел мог не иметь портов при запуске
+        self.running.store(true, Ordering::SeqCst);
+
+        // Незакрепленные потоки не делят ядра с закрепленными
+        let cpus = self.shared_cpus();
+
+        // В режиме конвейера у каждого рабочего потока свое кольцо к ядру декодирования
+        let decode_cores = decode_cores.min(num_workers);
+        let mut stages = Vec::new();
+        let mut decoder_inputs: Vec<Vec<_>> = (0..decode_cores).map(|_| Vec::new()).collect();
+        for queue_id in 0..num_workers {
+            if decode_cores == 0 {
+                break;
             }
+            let (producer, consumer) = spsc::channel(PIPELINE_RING_SIZE, Some(self.node_id));
+            stages.push(PipelineStage::new(producer));
+            decoder_inputs[(queue_id % decode_cores) as usize].push((queue_id, consumer));
+        }
+        let mut stages = stages.into_iter();
 
-            for queue_id in 0..num_rx_queues {
-                let core_idx = (queue_id as usize) % self.local_cpus.len();
-                let core_id = self.local_cpus[core_idx];
+        // Перераспределение пачек: устройство событий и конвейер балансируют сами
+        let settings = WorkerSettings {
+            steal_group: (settings.work_stealing
+                && event.is_none()
+                && decode_cores == 0
+                && num_workers > 1)
+                .then(|| Arc::new(StealGroup::new(port_id, num_workers))),
+            ..settings
+        };
 
-                println!("  Queue {} -> Core {}", queue_id, core_id.id);
+        for queue_id in 0..num_workers {
+            let pinned = queue_cores
+                .iter()
+                .find(|&&(queue, _)| queue == queue_id)
+                .map(|&(_, id)| CoreId { id });
+            let core_id = pinned.unwrap_or(cpus[queue_id as usize % cpus.len()]);
 
-                let worker = self.start_worker_thread(
-                    port_id,
-                    queue_id,
-                    core_id,
-                    packet_handler.clone(),
-                    burst_size,
-                );
+            log_info!(
+                "worker",
+                "Queue assigned",
+                port = port_id,
+                queue = queue_id,
+                core = core_id.id,
+                pinned = pinned.is_some()
+            );
 
-                self.workers.push(worker);
+            if !handlers.resolve(port_id, queue_id).is_routed() {
+                log_warn!(
+                    "worker",
+                    "No handler registered, packets will be dropped",
+                    port = port_id,
+                    queue = queue_id
+                );
             }
+
+            let source = match (&event, rings.get(queue_id as usize)) {
+                (Some((scheduler, layout)), _) => {
+                    RxSource::Event(scheduler.clone(), layout.first_worker_port + queue_id as u8)
+                }
+                (None, Some(ring)) => RxSource::Ring(ring.clone()),
+                (None, None) => RxSource::Queue,
+            };
+
+            let worker = self.start_worker_thread(
+                port_id,
+                queue_id,
+                core_id,
+                handlers.clone(),
+                settings.clone(),
+                source,
+                stages.next(),
+            );
+
+            self.workers.push(worker);
+        }
+
+        let has_feeder = event.is_some() || !rings.is_empty();
+        if let Some((scheduler, _)) = event {
+            // Производитель занимает следующее за рабочими потоками ядро
+            let core_id = cpus[num_workers as usize % cpus.len()];
+            let adapter = EventRxAdapter::start(
+                port_id,
+                num_rx_queues,
+                core_id,
+                scheduler,
+                self.running.clone(),
+                &settings,
+            )?;
+            self.event_adapters.push(adapter);
+        } else if !rings.is_empty() {
+            // Распределитель занимает следующее за рабочими потоками ядро
+            let core_id = cpus[num_workers as usize % cpus.len()];
+            let distributor = SoftRssDistributor::start(
+                port_id,
+                0,
+                core_id,
+                rings,
+                self.running.clone(),
+                &settings,
+            )?;
+            self.distributors.push(distributor);
+        }
+
+        // Ядра декодирования занимают ядра за рабочими потоками и распределителем
+        let first_decode_core = num_workers as usize + usize::from(has_feeder);
+        for (index, inputs) in decoder_inputs.into_iter().enumerate() {
+            let core_id = cpus[(first_decode_core + index) % cpus.len()];
+            let decoder = PipelineDecoder::start(
+                self.node_id,
+                port_id,
+                core_id,
+                inputs,
+                handlers.clone(),
+                self.running.clone(),
+                self.events.clone(),
+                &settings,
+            )?;
+            self.decoders.push(decoder);
         }
 
-        println!(
-            "Started {} worker threads on NUMA node {}",
-            self.workers.len(),
-            self.node_id
-        );
         Ok(())
     }
 
+    /// Ядра узла для незакрепленных потоков (без ядер, закрепленных за очередями портов узла)
+    ///
+    /// Если закрепления заняли все ядра узла, возвращаются все ядра.
+    pub fn shared_cpus(&self) -> Vec<CoreId> {
+        let pinned: Vec<usize> = self
+            .local_ports
+            .iter()
+            .flat_map(|port| port.settings.queue_cores.iter().map(|&(_, core)| core))
+            .collect();
+        let shared: Vec<CoreId> = self
+            .local_cpus
+            .iter()
+            .copied()
+            .filter(|core| !pinned.contains(&core.id))
+            .collect();
+        if shared.is_empty() {
+            self.local_cpus.clone()
+        } else {
+            shared
+        }
+    }
+
+    /// Останавливает рабочие потоки порта, не затрагивая остальные
+    pub fn stop_port_workers(&mut self, port_id: u16) {
+        let (stopping, remaining): (Vec<Worker>, Vec<Worker>) = self
+            .workers
+            .drain(..)
+            .partition(|worker| worker.port_id == port_id);
+        self.workers = remaining;
+
+        // Распределитель останавливается первым, чтобы кольца больше не пополнялись
+        self.distributors.retain(|d| d.port_id != port_id);
+        self.event_adapters.retain(|a| a.port_id != port_id);
+
+        for worker in &stopping {
+            worker.control.stop.store(true, Ordering::SeqCst);
+        }
+
+        for worker in stopping {
+            self.join_worker(worker);
+        }
+
+        // Ядра декодирования останавливаются после ядер приема и освобождают остаток колец
+        self.decoders.retain(|d| d.port_id != port_id);
+    }
+
+    /// Удаляет порт из узла (после остановки его рабочих потоков)
+    pub fn remove_port(&mut self, port_id: u16) -> Option<DpdkPort> {
+        let index = self.local_ports.iter().position(|p| p.port_id == port_id)?;
+        Some(self.local_ports.remove(index))
+    }
+
     /// Запускает рабочий поток
+    #[allow(clippy::too_many_arguments)]
     fn start_worker_thread(
         &self,
         port_id: u16,
         queue_id: u16,
         core_id: CoreId,
-        packet_handler: PacketHandler,
-        burst_size: u32,
+        handlers: Arc<SharedHandlers>,
+        settings: WorkerSettings,
+        source: RxSource,
+        mut stage: Option<PipelineStage>,
     ) -> Worker {
         let running = self.running.clone();
         let node_id = self.node_id;
+        let metrics = Arc::new(WorkerMetrics::default());
+        let worker_metrics = metrics.clone();
+        let events = self.events.clone();
+        let control = Arc::new(WorkerControl::new(
+            settings.burst_size.min(MAX_BURST_SIZE as u32) as u16,
+            core_id.id,
+        ));
+        let worker_control = control.clone();
+        // Замена потока конвейера невозможна: кольцо к ядру декодирования уходит в поток
+        let respawn = stage.is_none().then(|| WorkerSpawn {
+            handlers: handlers.clone(),
+            settings: settings.clone(),
+            source: source.clone(),
+        });
 
         let thread = thread::spawn(move || {
             core_affinity::set_for_current(core_id);
+            worker_control.tid.store(
+                unsafe { libc::syscall(libc::SYS_gettid) } as i32,
+                Ordering::Release,
+            );
 
             if NumaAllocator::is_available() {
                 NumaAllocator::bind_thread_to_node(node_id);
-                println!(
-                    "Thread for port {}, queue {} bound to NUMA node {} core {}",
-                    port_id, queue_id, node_id, core_id.id
+                log_info!(
+                    "worker",
+                    "Thread bound to NUMA node",
+                    port = port_id,
+                    queue = queue_id,
+                    node = node_id,
+                    core = core_id.id
                 );
             }
 
-            let packet_pool = PacketDataPool::new(burst_size as usize, Some(node_id));
+            if let Some(realtime) = &settings.realtime {
+                match realtime.apply_current_thread(core_id.id) {
+                    Ok(()) => log_info!(
+                        "worker",
+                        "Realtime scheduling enabled",
+                        port = port_id,
+                        queue = queue_id,
+                        core = core_id.id,
+                        scheduling = realtime
+                    ),
+                    Err(e) => log_warn!(
+                        "worker",
+                        "Realtime scheduling not applied",
+                        port = port_id,
+                        queue = queue_id,
+                        error = e
+                    ),
+                }
+            }
+
+            events.emit(LifecycleEvent::WorkerStarted {
+                node_id,
+                port_id,
+                queue_id,
+                core_id: core_id.id,
+            });
+
+            let packet_pool = PacketDataPool::new(settings.burst_size as usize, Some(node_id));
+            let mut fragment_table = settings.reassembly.map(FragmentTable::new);
 
             const PREFETCH_AHEAD: usize = 4;
 
-            let mut rx_pkts = vec![std::ptr::null_mut(); burst_size as usize];
+            // Массив рассчитан на максимальный размер пачки, чтобы его можно было менять на ходу
+            let mut rx_pkts = vec![std::ptr::null_mut(); MAX_BURST_SIZE as usize];
+            let mut burst_wait = AdaptiveBurstWait::new(settings.burst_wait_ns);
+            let mut breaker = settings.circuit_breaker.map(CircuitBreaker::new);
+            let mut steal = settings
+                .steal_group
+                .clone()
+                .map(|group| StealMember::new(group, queue_id));
+            // Прерывание есть только у очереди NIC: кольца и eventdev переходят ко сну
+            let mut idle = IdleBackoff::new(match source {
+                RxSource::Queue => settings.idle_policy,
+                _ => settings.idle_policy.polling(),
+            });
+            let mut rx_interrupt = None;
+            if let (IdlePolicy::Interrupt { .. }, RxSource::Queue) = (settings.idle_policy, &source)
+            {
+                match RxInterrupt::register(port_id, queue_id) {
+                    Ok(interrupt) => rx_interrupt = Some(interrupt),
+                    Err(e) => {
+                        log_warn!(
+                            "worker",
+                            "RX interrupts unavailable, falling back to sleep",
+                            port = port_id,
+                            queue = queue_id,
+                            error = e
+                        );
+                        idle.disable_interrupts();
+                    }
+                }
+            }
+
+            // Таблицы очередей группы для излишков соседей (индекс - очередь),
+            // обновляются вместе с таблицей своей очереди
+            let steal_queues = settings
+                .steal_group
+                .as_ref()
+                .map_or(0, |group| group.workers());
+            let resolve_siblings = || -> Vec<QueueDispatch> {
+                (0..steal_queues as u16)
+                    .map(|queue| handlers.resolve(port_id, queue))
+                    .collect()
+            };
+            let mut dispatch = handlers.resolve(port_id, queue_id);
+            let mut sibling_dispatch = resolve_siblings();
+            let mut dispatch_generation = handlers.generation();
+            let mut parked = false;
+            let mut heartbeat: u64 = 0;
+            let isolate = settings.panic_policy.isolates();
 
-            while running.load(Ordering::SeqCst) {
-                let nb_rx = unsafe {
-                    crate::dpdk::ffi::rte_eth_rx_burst(
-                        port_id,
-                        queue_id,
-                        rx_pkts.as_mut_ptr(),
-                        burst_size as u16,
-                    )
+            while running.load(Ordering::SeqCst) && !worker_control.stop.load(Ordering::Relaxed) {
+                if worker_control.paused.load(Ordering::Relaxed) {
+                    if !parked {
+                        worker_control.parked.store(true, Ordering::Release);
+                        parked = true;
+                    }
+                    std::hint::spin_loop();
+                    continue;
+                }
+                if parked {
+                    worker_control.parked.store(false, Ordering::Release);
+                    parked = false;
+                }
+
+                // Перенос на другое ядро между пачками: текущая пачка уже обработана
+                let target_core = worker_control.target_core.load(Ordering::Acquire);
+                if target_core != NO_CORE_CHANGE {
+                    let from_core = worker_control.current_core.load(Ordering::Relaxed);
+                    if core_affinity::set_for_current(CoreId { id: target_core }) {
+                        worker_control
+                            .current_core
+                            .store(target_core, Ordering::Release);
+                        events.emit(LifecycleEvent::WorkerMoved {
+                            port_id,
+                            queue_id,
+                            from_core,
+                            to_core: target_core,
+                        });
+                    } else {
+                        log_warn!(
+                            "worker",
+                            "Failed to move worker thread",
+                            port = port_id,
+                            queue = queue_id,
+                            core = target_core
+                        );
+                    }
+                    worker_control
+                        .target_core
+                        .store(NO_CORE_CHANGE, Ordering::Release);
+                }
+
+                // Реестр обработчиков изменился через интерфейс управления
+                let generation = handlers.generation();
+                if generation != dispatch_generation {
+                    dispatch = handlers.resolve(port_id, queue_id);
+                    sibling_dispatch = resolve_siblings();
+                    dispatch_generation = generation;
+                }
+
+                let burst_size = worker_control.burst_size.load(Ordering::Relaxed);
+
+                burst_wait.wait();
+
+                // Пульс для проверки зависания. Остановка проверяется после
+                // записи нечетного пульса: поток, замененный зависшим, не
+                // начнет опрос очереди одновременно с заменой
+                heartbeat = heartbeat.wrapping_add(1);
+                worker_control.heartbeat.store(heartbeat, Ordering::SeqCst);
+                if worker_control.stop.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                let mut nb_rx = match &source {
+                    RxSource::Queue => unsafe {
+                        crate::dpdk::ffi::rte_eth_rx_burst(
+                            port_id,
+                            queue_id,
+                            rx_pkts.as_mut_ptr(),
+                            burst_size,
+                        )
+                    },
+                    RxSource::Ring(ring) => ring.pop_burst(&mut rx_pkts[..burst_size as usize]),
+                    RxSource::Event(scheduler, event_port) => {
+                        scheduler.dequeue_burst(*event_port, &mut rx_pkts[..burst_size as usize])
+                    }
+                };
+                heartbeat = heartbeat.wrapping_add(1);
+                worker_control.heartbeat.store(heartbeat, Ordering::Relaxed);
+
+                let counters = &worker_metrics.counters;
+                counters.polls.inc();
+                if nb_rx == 0 {
+                    counters.empty_polls.inc();
+                }
+                counters.rx_packets.add(nb_rx as u64);
+
+                // Перераспределение: пустой опрос забирает излишки соседа,
+                // перегруженная очередь отдает часть пачки
+                let mut rx_queue = queue_id;
+                if let Some(member) = steal.as_mut() {
+                    if let Some((victim, count)) =
+                        member.balance(&mut rx_pkts, &mut nb_rx, burst_size)
+                    {
+                        rx_queue = victim;
+                        nb_rx = count;
+                    }
+                }
+                // Излишки соседа обрабатываются обработчиком его очереди
+                let batch_dispatch = if rx_queue == queue_id {
+                    &dispatch
+                } else {
+                    &sibling_dispatch[rx_queue as usize]
                 };
 
+                // Одна отметка времени на пачку: пакеты пачки приняты одновременно
+                let rx_timestamp_ns = time::now_ns();
+                burst_wait.update(nb_rx, burst_size, rx_timestamp_ns);
+
+                // Таймеры проверяются только в простое, чтобы не задерживать пачку
+                if nb_rx == 0 {
+                    if let Some(timers) = &settings.timers {
+                        timers.poll(rx_timestamp_ns);
+                    }
+                    if let (Some(timeout_ms), Some(interrupt)) = (idle.idle(), &rx_interrupt) {
+                        if let Err(e) = interrupt.wait(timeout_ms) {
+                            log_warn!(
+                                "worker",
+                                "RX interrupt failed, falling back to sleep",
+                                port = port_id,
+                                queue = queue_id,
+                                error = e
+                            );
+                            rx_interrupt = None;
+                            idle.disable_interrupts();
+                        }
+                    }
+                } else {
+                    idle.reset();
+                }
+
+                if let Some(breaker) = breaker.as_mut() {
+                    if let Some(transition) = breaker.on_burst(nb_rx, rx_timestamp_ns) {
+                        report_breaker(&worker_control, &events, port_id, queue_id, transition);
+                    }
+                }
+
                 for i in 0..std::cmp::min(PREFETCH_AHEAD, nb_rx as usize) {
                     unsafe {
                         let pkt = rx_pkts[i];
@@ -216,6 +875,9 @@ impl NumaNode {
                     }
                 }
 
+                // Перехваченная паника обработчика: (сообщение, длина пакета)
+                let mut handler_panic = None;
+
                 for i in 0..nb_rx as usize {
                     if i + PREFETCH_AHEAD < nb_rx as usize {
                         unsafe {
@@ -227,52 +889,170 @@ impl NumaNode {
                         }
                     }
 
+                    // Отброшенные выключателем пакеты не разбираются
+                    if let Some(breaker) = breaker.as_mut() {
+                        if !breaker.admit(i, nb_rx as usize) {
+                            counters.dropped.inc();
+                            continue;
+                        }
+                    }
+
                     let pkt = rx_pkts[i];
 
-                    let mut src_ip_ptr = std::ptr::null_mut();
-                    let mut src_ip_len: u32 = 0;
-                    let mut dst_ip_ptr = std::ptr::null_mut();
-                    let mut dst_ip_len: u32 = 0;
-                    let mut src_port: u16 = 0;
-                    let mut dst_port: u16 = 0;
-                    let mut data_ptr = std::ptr::null_mut();
-                    let mut data_len: u32 = 0;
-
-                    let ret = unsafe {
-                        crate::dpdk::ffi::dpdk_extract_packet_data(
-                            pkt,
-                            &mut src_ip_ptr,
-                            &mut src_ip_len,
-                            &mut dst_ip_ptr,
-                            &mut dst_ip_len,
-                            &mut src_port,
-                            &mut dst_port,
-                            &mut data_ptr,
-                            &mut data_len,
-                        )
+                    let frame = unsafe {
+                        let data =
+                            crate::dpdk::ffi::rte_pktmbuf_mtod(pkt, std::ptr::null()) as *const u8;
+                        let len = crate::dpdk::ffi::rte_pktmbuf_data_len(pkt) as usize;
+                        std::slice::from_raw_parts(data, len)
                     };
+                    // Длина цепочки читается только на портах, где она бывает
+                    let pkt_len = if settings.chained_mbufs {
+                        unsafe { crate::dpdk::ffi::dpdk_mbuf_pkt_len(pkt) as usize }
+                    } else {
+                        frame.len()
+                    };
+                    counters.rx_bytes.add(pkt_len as u64);
 
-                    if ret == 0 && !data_ptr.is_null() && data_len > 0 {
-                        let mut packet = packet_pool.acquire();
+                    let parsed =
+                        parse_chained(frame, pkt_len, settings.extract_flags, settings.vxlan_port);
 
-                        packet.source_port = src_port;
-                        packet.dest_port = dst_port;
-                        packet.queue_id = queue_id;
-                        packet.source_ip_ptr = src_ip_ptr;
-                        packet.source_ip_len = src_ip_len as usize;
-                        packet.dest_ip_ptr = dst_ip_ptr;
-                        packet.dest_ip_len = dst_ip_len as usize;
-                        packet.data_ptr = data_ptr;
-                        packet.data_len = data_len as usize;
-                        packet.mbuf_ptr = pkt;
+                    // В режиме конвейера обработчик выполняет ядро декодирования,
+                    // mbuf переходит к нему вместе с результатом разбора
+                    if let Some(stage) = stage.as_mut() {
+                        let kind = match parsed {
+                            Ok(parsed) => StagedKind::Packet(parsed),
+                            Err(ParseError::Fragment { l3_offset }) => {
+                                StagedKind::Fragment { l3_offset }
+                            }
+                            Err(_) => {
+                                counters.parse_errors.inc();
+                                continue;
+                            }
+                        };
+                        stage.stage(pkt, kind, rx_timestamp_ns);
+                        rx_pkts[i] = std::ptr::null_mut();
+                        continue;
+                    }
 
-                        packet_handler(queue_id, &packet);
+                    match parsed {
+                        Ok(parsed) => {
+                            let mut packet = packet_pool.acquire();
+                            fill_packet(
+                                &mut packet,
+                                frame,
+                                &parsed,
+                                pkt,
+                                port_id,
+                                rx_queue,
+                                rx_timestamp_ns,
+                            );
 
-                        unsafe { crate::dpdk::ffi::rte_pktmbuf_free(packet.mbuf_ptr) };
+                            let result = call_isolated(isolate, || {
+                                batch_dispatch.dispatch_timed(&worker_metrics, rx_queue, &packet)
+                            });
 
-                        packet_pool.release(packet);
-                    } else {
-                        unsafe { crate::dpdk::ffi::rte_pktmbuf_free(pkt) };
+                            packet_pool.release(packet);
+
+                            // Остаток пачки отбрасывается вместе с пакетом, вызвавшим панику
+                            if let Err(message) = result {
+                                counters.dropped.add((nb_rx as usize - i) as u64);
+                                handler_panic = Some((message, pkt_len));
+                                break;
+                            }
+                        }
+                        Err(ParseError::Fragment { l3_offset }) => match fragment_table.as_mut() {
+                            Some(table) => {
+                                let result = call_isolated(isolate, || {
+                                    handle_fragment(
+                                        table,
+                                        frame,
+                                        l3_offset,
+                                        port_id,
+                                        rx_queue,
+                                        rx_timestamp_ns,
+                                        &packet_pool,
+                                        batch_dispatch,
+                                        &worker_metrics,
+                                    )
+                                });
+                                if let Err(message) = result {
+                                    counters.dropped.add((nb_rx as usize - i) as u64);
+                                    handler_panic = Some((message, pkt_len));
+                                    break;
+                                }
+                            }
+                            None => counters.dropped.inc(),
+                        },
+                        Err(_) => counters.parse_errors.inc(),
+                    }
+                }
+
+                if let Some(stage) = stage.as_mut() {
+                    counters.dropped.add(stage.flush() as u64);
+                }
+
+                // Освобождаем всю пачку одним вызовом: mbuf группируются по
+                // пулу и возвращаются в mempool пакетно, а не по одному
+                // (переданные ядру декодирования обнулены, NULL пропускается)
+                if nb_rx > 0 {
+                    unsafe {
+                        crate::dpdk::ffi::rte_pktmbuf_free_bulk(rx_pkts.as_mut_ptr(), nb_rx as u32)
+                    };
+                }
+
+                if let Some((message, pkt_len)) = handler_panic {
+                    let panics = worker_control.panics.fetch_add(1, Ordering::Relaxed) + 1;
+                    let core = worker_control.current_core.load(Ordering::Relaxed);
+                    log_error!(
+                        "worker",
+                        "Packet handler panicked",
+                        port = port_id,
+                        queue = rx_queue,
+                        core = core,
+                        pkt_len = pkt_len,
+                        panics = panics,
+                        message = message
+                    );
+                    events.emit(LifecycleEvent::WorkerPanicked {
+                        port_id,
+                        queue_id: rx_queue,
+                        core_id: core,
+                        message: message.clone(),
+                    });
+
+                    match settings.panic_policy.action(panics) {
+                        PanicAction::Restart => {
+                            // Состояние, которое паника могла оставить несогласованным,
+                            // создается заново; mbuf пачки уже освобождены
+                            fragment_table = settings.reassembly.map(FragmentTable::new);
+                            dispatch = handlers.resolve(port_id, queue_id);
+                            sibling_dispatch = resolve_siblings();
+                            dispatch_generation = handlers.generation();
+                            log_info!(
+                                "worker",
+                                "Worker restarted after handler panic",
+                                port = port_id,
+                                queue = queue_id
+                            );
+                            continue;
+                        }
+                        PanicAction::KillSwitch => {
+                            worker_control.stop.store(true, Ordering::SeqCst);
+                            events.emit(LifecycleEvent::KillSwitch {
+                                engaged: true,
+                                reason: format!(
+                                    "handler panic on port {} queue {}: {}",
+                                    port_id, rx_queue, message
+                                ),
+                            });
+                            break;
+                        }
+                    }
+                }
+
+                if let Some(table) = fragment_table.as_mut() {
+                    if table.pending() > 0 {
+                        table.evict_expired(rx_timestamp_ns);
                     }
                 }
             }
@@ -283,7 +1063,86 @@ impl NumaNode {
             core_id,
             port_id,
             queue_id,
+            metrics,
+            control,
+            respawn,
+        }
+    }
+
+    /// Заменяет зависший рабочий поток очереди новым
+    ///
+    /// `heartbeat` - пульс, на котором поток завис. Поток получает флаг
+    /// остановки; если пульс после этого не изменился, поток оставляется:
+    /// выйдя из обработчика, он завершится, не опросив очередь. Если поток
+    /// ожил, ожидается его завершение. Замена запускается на ядре, где
+    /// работал поток, с новыми счетчиками.
+    pub fn restart_worker(
+        &mut self,
+        port_id: u16,
+        queue_id: u16,
+        heartbeat: u64,
+    ) -> Result<(), String> {
+        let index = self
+            .workers
+            .iter()
+            .position(|w| w.port_id == port_id && w.queue_id == queue_id)
+            .ok_or_else(|| format!("No worker for port {} queue {}", port_id, queue_id))?;
+        if self.workers[index].respawn.is_none() {
+            return Err(format!(
+                "Worker for port {} queue {} cannot be restarted in pipeline mode",
+                port_id, queue_id
+            ));
+        }
+
+        let mut stalled = self.workers.remove(index);
+        let respawn = stalled.respawn.take().expect("checked above");
+        let core_id = CoreId {
+            id: stalled.control.current_core.load(Ordering::Acquire),
+        };
+
+        stalled.control.stop.store(true, Ordering::SeqCst);
+        let resumed = stalled.control.heartbeat.load(Ordering::SeqCst) != heartbeat;
+        let deadline = Instant::now() + Duration::from_millis(100);
+        while resumed
+            && !stalled.thread.as_ref().is_some_and(|t| t.is_finished())
+            && Instant::now() < deadline
+        {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        if stalled.thread.as_ref().is_some_and(|t| t.is_finished()) {
+            self.join_worker(stalled);
+        } else {
+            log_warn!(
+                "worker",
+                "Abandoning stalled worker thread",
+                port = port_id,
+                queue = queue_id,
+                core = core_id.id
+            );
+            // JoinHandle отсоединяется: зависший поток нельзя дождаться
+            stalled.thread = None;
         }
+
+        let worker = self.start_worker_thread(
+            port_id,
+            queue_id,
+            core_id,
+            respawn.handlers,
+            respawn.settings,
+            respawn.source,
+            None,
+        );
+        self.workers.insert(index, worker);
+
+        log_info!(
+            "worker",
+            "Worker restarted",
+            port = port_id,
+            queue = queue_id,
+            core = core_id.id
+        );
+        Ok(())
     }
 
     /// Останавливает рабочие потоки
@@ -292,52 +1151,146 @@ impl NumaNode {
             return;
         }
 
-        println!(
-            "Stopping {} worker threads on NUMA node {}",
-            self.workers.len(),
-            self.node_id
+        log_info!(
+            "worker",
+            "Stopping worker threads",
+            workers = self.workers.len(),
+            node = self.node_id
         );
 
         self.running.store(false, Ordering::SeqCst);
 
-        while let Some(mut worker) = self.workers.pop() {
-            if let Some(thread) = worker.thread.take() {
-                let _ = thread.join();
-                println!(
-                    "  Worker thread for port {}, queue {} on core {} stopped",
-                    worker.port_id, worker.queue_id, worker.core_id.id
-                );
-            }
+        while let Some(worker) = self.workers.pop() {
+            self.join_worker(worker);
         }
+        self.distributors.clear();
+        self.event_adapters.clear();
+        self.decoders.clear();
     }
 
-    /// Генерирует аргументы для DPDK EAL, относящиеся к этому узлу NUMA
-    pub fn generate_eal_args(&self, dpdk_config: &DpdkConfig) -> Vec<String> {
-        let mut args = Vec::new();
-
-        if dpdk_config.use_huge_pages {
-            let socket_mem = if NumaAllocator::is_available() {
-                let mut mem_per_node = vec!["0".to_string(); NumaAllocator::get_node_count()];
-                mem_per_node[self.node_id] = dpdk_config
-                    .socket_mem
-                    .as_ref()
-                    .map_or_else(|| "1024".to_string(), |v| v[self.node_id].to_string());
-                mem_per_node.join(",")
-            } else {
-                dpdk_config
-                    .socket_mem
-                    .as_ref()
-                    .map_or_else(|| "1024".to_string(), |v| v[0].to_string())
-            };
+    /// Останавливает рабочие потоки, дообработав принятые пакеты
+    ///
+    /// Прием прекращается (распределители, адаптеры событий, опрос очередей),
+    /// рабочие потоки завершают пачки в обработке, ядра декодирования
+    /// дообрабатывают свои кольца до `deadline`. Возвращает false, если к
+    /// `deadline` остались необработанные пакеты (они освобождаются).
+    pub fn drain_workers(&mut self, deadline: Instant) -> bool {
+        if !self.running.load(Ordering::SeqCst) {
+            return true;
+        }
+
+        log_info!(
+            "worker",
+            "Draining worker threads",
+            workers = self.workers.len(),
+            node = self.node_id
+        );
+
+        self.distributors.clear();
+        self.event_adapters.clear();
 
-            args.push(format!("--socket-mem={}", socket_mem));
+        for worker in &self.workers {
+            worker.control.stop.store(true, Ordering::SeqCst);
         }
+        while let Some(worker) = self.workers.pop() {
+            self.join_worker(worker);
+        }
+
+        let mut drained = true;
+        for decoder in &mut self.decoders {
+            drained &= decoder.drain(deadline);
+        }
+        self.decoders.clear();
 
-        args
+        self.running.store(false, Ordering::SeqCst);
+        drained
     }
 
-    /// Генерирует маску ядер для DPDK EAL, содержащую только ядра этого узла
-    pub fn generate_core_mask(&self) -> String {
+    /// Дожидается завершения рабочего потока
+    fn join_worker(&self, mut worker: Worker) {
+        if let Some(thread) = worker.thread.take() {
+            let _ = thread.join();
+            log_info!(
+                "worker",
+                "Worker thread stopped",
+                port = worker.port_id,
+                queue = worker.queue_id,
+                core = worker.core_id.id
+            );
+
+            self.events.emit(LifecycleEvent::WorkerStopped {
+                node_id: self.node_id,
+                port_id: worker.port_id,
+                queue_id: worker.queue_id,
+                core_id: worker.core_id.id,
+            });
+        }
+    }
+
+    /// Находит рабочий поток, обслуживающий очередь порта
+    pub fn find_worker(&self, port_id: u16, queue_id: u16) -> Option<&Worker> {
+        self.workers
+            .iter()
+            .find(|w| w.port_id == port_id && w.queue_id == queue_id)
+    }
+
+    /// Счетчики обработчиков очереди на ядре декодирования (режим конвейера)
+    fn decoder_metrics(&self, port_id: u16, queue_id: u16) -> Option<&Arc<WorkerMetrics>> {
+        self.decoders
+            .iter()
+            .filter(|decoder| decoder.port_id == port_id)
+            .find_map(|decoder| decoder.queue_metrics(queue_id))
+    }
+
+    /// Снимки счетчиков рабочих потоков (в режиме конвейера - вместе с ядром декодирования)
+    pub fn queue_stats(&self) -> Vec<QueueStats> {
+        self.workers
+            .iter()
+            .map(|worker| {
+                let mut stats = worker.metrics.counters.snapshot();
+                if let Some(metrics) = self.decoder_metrics(worker.port_id, worker.queue_id) {
+                    stats += metrics.counters.snapshot();
+                }
+                QueueStats {
+                    node_id: self.node_id,
+                    port_id: worker.port_id,
+                    queue_id: worker.queue_id,
+                    stats,
+                }
+            })
+            .collect()
+    }
+
+    /// Снимки гистограмм задержек рабочих потоков: (порт, очередь, обработчик, прием-обработчик)
+    pub fn latency_snapshots(&self) -> Vec<(u16, u16, HistogramSnapshot, HistogramSnapshot)> {
+        self.workers
+            .iter()
+            .map(|worker| {
+                // В режиме конвейера обработчики выполняет ядро декодирования
+                let metrics = self
+                    .decoder_metrics(worker.port_id, worker.queue_id)
+                    .unwrap_or(&worker.metrics);
+                (
+                    worker.port_id,
+                    worker.queue_id,
+                    metrics.latency.handler.snapshot(),
+                    metrics.latency.wire_to_handler.snapshot(),
+                )
+            })
+            .collect()
+    }
+
+    /// Память hugepages узла для --socket-mem (МБ)
+    pub fn socket_mem(&self, dpdk_config: &DpdkConfig) -> u32 {
+        dpdk_config
+            .socket_mem
+            .as_ref()
+            .and_then(|mem| mem.get(self.node_id).copied())
+            .unwrap_or(1024)
+    }
+
+    /// Маска ядер этого узла (ядра с номерами до 64)
+    pub fn core_mask(&self) -> u64 {
         let mut mask: u64 = 0;
 
         for core in &self.local_cpus {
@@ -346,7 +1299,12 @@ impl NumaNode {
             }
         }
 
-        format!("0x{:x}", mask)
+        mask
+    }
+
+    /// Маска ядер узла в формате аргументов DPDK EAL
+    pub fn generate_core_mask(&self) -> String {
+        format!("0x{:x}", self.core_mask())
     }
 }
 
@@ -356,6 +1314,140 @@ impl Drop for NumaNode {
     }
 }
 
+/// Адаптивное ожидание перед повторным опросом после неполной пачки
+///
+/// После неполной пачки следующий rx_burst откладывается на `current_ns`,
+/// чтобы накопить больше пакетов. Если ожидание увеличило пачку, оно растет
+/// на 1/8 максимума, иначе уменьшается вдвое (но не ниже 1/8 максимума).
+/// Публикует изменение состояния выключателя очереди
+#[cold]
+fn report_breaker(
+    control: &WorkerControl,
+    events: &EventBus,
+    port_id: u16,
+    queue_id: u16,
+    transition: BreakerTransition,
+) {
+    let (open, rate_pps) = match transition {
+        BreakerTransition::Tripped { rate_pps } => {
+            log_warn!(
+                "breaker",
+                "Inbound rate exceeded limit, queue switched to protective mode",
+                port = port_id,
+                queue = queue_id,
+                rate_pps = rate_pps
+            );
+            (true, rate_pps)
+        }
+        BreakerTransition::Reset { rate_pps, shed } => {
+            log_info!(
+                "breaker",
+                "Inbound rate back to normal, queue resumed",
+                port = port_id,
+                queue = queue_id,
+                rate_pps = rate_pps,
+                shed = shed
+            );
+            (false, rate_pps)
+        }
+    };
+
+    control.breaker_open.store(open, Ordering::Relaxed);
+    events.emit(LifecycleEvent::CircuitBreaker {
+        port_id,
+        queue_id,
+        open,
+        rate_pps,
+    });
+}
+
+/// Заполняет пакет для обработчика по результату разбора кадра из mbuf `pkt`
+#[inline(always)]
+pub(crate) fn fill_packet(
+    packet: &mut PacketData,
+    frame: &[u8],
+    parsed: &ParsedPacket,
+    pkt: *mut RteMbuf,
+    port_id: u16,
+    queue_id: u16,
+    rx_timestamp_ns: u64,
+) {
+    packet.source_port = parsed.src_port;
+    packet.dest_port = parsed.dst_port;
+    packet.queue_id = queue_id;
+    packet.port_id = port_id;
+    packet.protocol = parsed.protocol;
+    packet.source_ip_ptr = frame[parsed.src_ip_offset()..].as_ptr();
+    packet.source_ip_len = 4;
+    packet.dest_ip_ptr = frame[parsed.dst_ip_offset()..].as_ptr();
+    packet.dest_ip_len = 4;
+    packet.data_ptr = frame[parsed.payload_offset..].as_ptr();
+    // Остаток нагрузки за первым сегментом - в следующих
+    packet.data_len = parsed.payload_len.min(frame.len() - parsed.payload_offset);
+    packet.chained_len = parsed.payload_len - packet.data_len;
+    packet.mbuf_ptr = pkt;
+    packet.l2_ptr = frame.as_ptr();
+    packet.ether_type = parsed.ether_type;
+    packet.vlan_tci = parsed.vlan_tci.unwrap_or(0);
+    packet.has_vlan = parsed.vlan_tci.is_some();
+    packet.rx_timestamp_ns = rx_timestamp_ns;
+}
+
+/// Передает IPv4 фрагмент в таблицу сборки и вызывает обработчик для собранной датаграммы
+///
+/// Данные фрагмента копируются в таблицу, поэтому mbuf можно освобождать сразу
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn handle_fragment(
+    table: &mut FragmentTable,
+    frame: &[u8],
+    l3_offset: usize,
+    port_id: u16,
+    queue_id: u16,
+    rx_timestamp_ns: u64,
+    packet_pool: &PacketDataPool,
+    dispatch: &QueueDispatch,
+    metrics: &WorkerMetrics,
+) {
+    match table.process(&frame[l3_offset..], rx_timestamp_ns) {
+        FragmentResult::Complete(datagram) => {
+            if let Some((src_port, dst_port, payload)) = datagram.ports_and_payload() {
+                if !payload.is_empty() {
+                    let mut packet = packet_pool.acquire();
+
+                    packet.source_port = src_port;
+                    packet.dest_port = dst_port;
+                    packet.queue_id = queue_id;
+                    packet.port_id = port_id;
+                    packet.protocol = datagram.protocol;
+                    packet.source_ip_ptr = datagram.src_ip.as_ptr();
+                    packet.source_ip_len = datagram.src_ip.len();
+                    packet.dest_ip_ptr = datagram.dst_ip.as_ptr();
+                    packet.dest_ip_len = datagram.dst_ip.len();
+                    packet.data_ptr = payload.as_ptr();
+                    packet.data_len = payload.len();
+                    packet.rx_timestamp_ns = rx_timestamp_ns;
+                    // Собранная датаграмма не принадлежит ни одному mbuf
+                    packet.mbuf_ptr = std::ptr::null_mut();
+                    // L2 сведения берутся из кадра последнего фрагмента
+                    if let Ok((ether_type, vlan_tci, _)) = parse_l2(frame) {
+                        packet.l2_ptr = frame.as_ptr();
+                        packet.ether_type = ether_type;
+                        packet.vlan_tci = vlan_tci.unwrap_or(0);
+                        packet.has_vlan = vlan_tci.is_some();
+                    }
+
+                    dispatch.dispatch_timed(metrics, queue_id, &packet);
+
+                    packet_pool.release(packet);
+                }
+            }
+        }
+        FragmentResult::Dropped => metrics.counters.dropped.inc(),
+        _ => {}
+    }
+}
+
 // Функция для предзагрузки данных в кеш
 #[inline(always)]
 unsafe fn rte_prefetch0(p: *const libc::c_void) {
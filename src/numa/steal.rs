@@ -0,0 +1,216 @@
+// src/numa/steal.rs
+use std::fmt;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::dpdk::ffi::RteMbuf;
+use crate::dpdk::soft_rss::MbufRing;
+use crate::log_info;
+use crate::numa::node::MAX_BURST_SIZE;
+
+/// Пачек (текущего размера), которые могут ждать в кольце излишков
+///
+/// Кольцо сглаживает микровсплеск, а не постоянную перегрузку: пока сосед
+/// не забрал отданное, больше не отдается, остальное ждет в очереди NIC.
+const SPILL_BURSTS: usize = 2;
+
+/// Емкость кольца излишков одного рабочего потока (в mbuf): `SPILL_BURSTS`
+/// пачек максимального размера
+pub const STEAL_RING_SIZE: usize = SPILL_BURSTS * MAX_BURST_SIZE as usize;
+
+/// Полных пачек подряд, после которых очередь считается перегруженной
+const SATURATED_BURSTS: u32 = 2;
+
+/// Группа рабочих потоков порта на одном узле NUMA, делящихся пачками
+///
+/// Рабочий поток, получающий полные пачки подряд, при наличии простаивающего
+/// соседа кладет вторую половину пачки в свое кольцо излишков; сосед с
+/// пустой очередью забирает ее и обрабатывает обработчиком исходной
+/// очереди. Так всплеск в одной корзине RSS не переполняет очередь NIC,
+/// пока соседние ядра опрашивают пустые очереди. Пакеты исходной очереди
+/// обрабатываются двумя ядрами одновременно, поэтому порядок внутри
+/// очереди не сохраняется; фрагменты IPv4 собираются там, где оказались.
+pub struct StealGroup {
+    port_id: u16,
+    /// Кольцо излишков каждого рабочего потока (индекс - очередь)
+    spill: Vec<MbufRing>,
+    /// Рабочих потоков, последний опрос которых был пустым
+    idle: AtomicUsize,
+    offloaded: AtomicU64,
+    stolen: AtomicU64,
+}
+
+impl StealGroup {
+    pub fn new(port_id: u16, workers: u16) -> Self {
+        Self {
+            port_id,
+            spill: (0..workers)
+                .map(|_| MbufRing::new(STEAL_RING_SIZE))
+                .collect(),
+            idle: AtomicUsize::new(0),
+            offloaded: AtomicU64::new(0),
+            stolen: AtomicU64::new(0),
+        }
+    }
+
+    /// Отмечает смену состояния простоя рабочего потока
+    #[inline(always)]
+    fn set_idle(&self, idle: bool) {
+        if idle {
+            self.idle.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.idle.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Число рабочих потоков (очередей) группы
+    pub fn workers(&self) -> usize {
+        self.spill.len()
+    }
+
+    /// Пакетов, отданных соседям
+    pub fn offloaded(&self) -> u64 {
+        self.offloaded.load(Ordering::Relaxed)
+    }
+
+    /// Пакетов, обработанных соседями
+    pub fn stolen(&self) -> u64 {
+        self.stolen.load(Ordering::Relaxed)
+    }
+}
+
+impl fmt::Debug for StealGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StealGroup")
+            .field("port_id", &self.port_id)
+            .field("workers", &self.spill.len())
+            .field("offloaded", &self.offloaded())
+            .field("stolen", &self.stolen())
+            .finish()
+    }
+}
+
+impl Drop for StealGroup {
+    fn drop(&mut self) {
+        if self.offloaded() > 0 {
+            log_info!(
+                "worker",
+                "Work stealing stats",
+                port = self.port_id,
+                offloaded = self.offloaded(),
+                stolen = self.stolen()
+            );
+        }
+    }
+}
+
+/// Участие рабочего потока в группе (состояние одного потока)
+pub struct StealMember {
+    group: Arc<StealGroup>,
+    queue_id: u16,
+    idle: bool,
+    full_bursts: u32,
+    /// Очередь, с которой начинается поиск излишков (по кругу)
+    next_victim: usize,
+}
+
+impl StealMember {
+    pub fn new(group: Arc<StealGroup>, queue_id: u16) -> Self {
+        Self {
+            next_victim: queue_id as usize + 1,
+            group,
+            queue_id,
+            idle: false,
+            full_bursts: 0,
+        }
+    }
+
+    /// Обрабатывает результат опроса своей очереди
+    ///
+    /// Пустой опрос: забирает пачку излишков соседа в `rx_pkts` и возвращает
+    /// (очередь соседа, число пакетов). Полная пачка при перегрузке: часть
+    /// пачки уходит в кольцо излишков, `nb_rx` уменьшается.
+    #[inline(always)]
+    pub fn balance(
+        &mut self,
+        rx_pkts: &mut [*mut RteMbuf],
+        nb_rx: &mut u16,
+        burst_size: u16,
+    ) -> Option<(u16, u16)> {
+        let idle = *nb_rx == 0;
+        if idle != self.idle {
+            self.group.set_idle(idle);
+            self.idle = idle;
+        }
+
+        if idle {
+            self.full_bursts = 0;
+            return self.steal(&mut rx_pkts[..burst_size as usize]);
+        }
+
+        if *nb_rx < burst_size {
+            self.full_bursts = 0;
+            return None;
+        }
+        self.full_bursts += 1;
+        if self.full_bursts >= SATURATED_BURSTS && self.group.idle.load(Ordering::Relaxed) > 0 {
+            *nb_rx -= self.offload(rx_pkts, *nb_rx, burst_size);
+        }
+        None
+    }
+
+    /// Кладет вторую половину пачки в свое кольцо, не превышая `SPILL_BURSTS`
+    /// пачек в кольце; возвращает число отданных
+    #[inline(always)]
+    fn offload(&mut self, rx_pkts: &mut [*mut RteMbuf], nb_rx: u16, burst_size: u16) -> u16 {
+        let ring = &self.group.spill[self.queue_id as usize];
+        let room = (SPILL_BURSTS * burst_size as usize).saturating_sub(ring.len());
+        let keep = (nb_rx as usize / 2).max(nb_rx as usize - room.min(nb_rx as usize));
+        if keep == nb_rx as usize {
+            return 0;
+        }
+
+        let mut pushed = 0;
+        for &pkt in &rx_pkts[keep..nb_rx as usize] {
+            if !ring.push(pkt) {
+                break;
+            }
+            pushed += 1;
+        }
+        // Не поместившиеся в кольцо пакеты сдвигаются к оставленным
+        rx_pkts.copy_within(keep + pushed..nb_rx as usize, keep);
+
+        self.group
+            .offloaded
+            .fetch_add(pushed as u64, Ordering::Relaxed);
+        pushed as u16
+    }
+
+    /// Забирает излишки первого потока группы, у которого они есть
+    #[inline(always)]
+    fn steal(&mut self, rx_pkts: &mut [*mut RteMbuf]) -> Option<(u16, u16)> {
+        let workers = self.group.spill.len();
+        for step in 0..workers {
+            // Свое кольцо тоже проверяется: излишки, которые никто не забрал,
+            // обрабатывает сам поток, когда его очередь опустела
+            let victim = (self.next_victim + step) % workers;
+            let count = self.group.spill[victim].pop_burst(rx_pkts);
+            if count > 0 {
+                self.next_victim = victim + 1;
+                if victim != self.queue_id as usize {
+                    self.group.stolen.fetch_add(count as u64, Ordering::Relaxed);
+                }
+                return Some((victim as u16, count));
+            }
+        }
+        None
+    }
+}
+
+impl Drop for StealMember {
+    fn drop(&mut self) {
+        if self.idle {
+            self.group.set_idle(false);
+        }
+    }
+}
@@ -0,0 +1,113 @@
+// src/numa/supervisor.rs
+use std::any::Any;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Паник обработчиков одного потока до аварийного выключателя по умолчанию
+pub const DEFAULT_MAX_RESTARTS: u32 = 10;
+
+/// Реакция рабочего потока на панику обработчика пакетов
+///
+/// Без перехвата паника завершает рабочий поток: очередь перестает
+/// обслуживаться до перезапуска процесса, а ее пакеты копятся в NIC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PanicPolicy {
+    /// Паника не перехватывается (поток завершается)
+    #[default]
+    Propagate,
+    /// Пачка отбрасывается, поток сбрасывает свое состояние и продолжает
+    /// опрос; после `max_restarts` паник - как `KillSwitch`
+    Restart { max_restarts: u32 },
+    /// Поток останавливается и включает аварийный выключатель
+    /// (`LifecycleEvent::KillSwitch`)
+    KillSwitch,
+}
+
+/// Решение после перехваченной паники
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanicAction {
+    /// Продолжить опрос со сброшенным состоянием потока
+    Restart,
+    /// Остановить поток и включить аварийный выключатель
+    KillSwitch,
+}
+
+impl PanicPolicy {
+    /// Разбирает `propagate`, `restart[,<max_restarts>]` или `kill-switch`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut parts = spec.split(',');
+        let policy = match (parts.next().unwrap_or_default(), parts.next()) {
+            ("propagate" | "none", None) => PanicPolicy::Propagate,
+            ("restart", None) => PanicPolicy::Restart {
+                max_restarts: DEFAULT_MAX_RESTARTS,
+            },
+            ("restart", Some(max)) => PanicPolicy::Restart {
+                max_restarts: max
+                    .parse()
+                    .map_err(|_| format!("Invalid restart limit '{}'", max))?,
+            },
+            ("kill-switch", None) => PanicPolicy::KillSwitch,
+            _ => {
+                return Err(format!(
+                    "Invalid panic policy '{}': expected propagate, restart[,<max>] or kill-switch",
+                    spec
+                ))
+            }
+        };
+        if parts.next().is_some() {
+            return Err(format!("Invalid panic policy '{}'", spec));
+        }
+        Ok(policy)
+    }
+
+    /// Перехватываются ли паники обработчиков
+    #[inline(always)]
+    pub fn isolates(&self) -> bool {
+        *self != PanicPolicy::Propagate
+    }
+
+    /// Решение для `panics`-й паники потока (считая с 1)
+    pub fn action(&self, panics: u64) -> PanicAction {
+        match *self {
+            PanicPolicy::Restart { max_restarts } if panics <= max_restarts as u64 => {
+                PanicAction::Restart
+            }
+            _ => PanicAction::KillSwitch,
+        }
+    }
+}
+
+impl fmt::Display for PanicPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PanicPolicy::Propagate => write!(f, "propagate"),
+            PanicPolicy::Restart { max_restarts } => write!(f, "restart,{}", max_restarts),
+            PanicPolicy::KillSwitch => write!(f, "kill-switch"),
+        }
+    }
+}
+
+/// Вызывает обработчик; при `isolate` паника возвращается как Err с ее сообщением
+///
+/// Без паники перехват ничего не стоит: catch_unwind не добавляет работы
+/// на обычном пути. Место паники пишет в stderr стандартный обработчик
+/// паник (и дамп бортового самописца, если он установлен).
+#[inline(always)]
+pub fn call_isolated(isolate: bool, f: impl FnOnce()) -> Result<(), String> {
+    if !isolate {
+        f();
+        return Ok(());
+    }
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(panic_message)
+}
+
+/// Текст сообщения паники
+pub fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "non-string panic payload".to_string()
+    }
+}
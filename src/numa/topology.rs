@@ -150,17 +150,22 @@ impl NumaTopology {
         self.nic_node.get(ifname).copied()
     }
 
-    /// Returns all physical core IDs on a specific NUMA node, excluding hyperthread cores and core 0
+    /// Returns the NUMA node ID for a given PCI device address
+    pub fn get_device_node(&self, pci_address: &str) -> Option<usize> {
+        self.device_node.get(pci_address).copied()
+    }
+
+    /// Returns all worker core IDs on a specific NUMA node, excluding reserved cores
+    /// (and hyperthread cores unless the CPU topology allows them)
     pub fn get_node_physical_cores(
         &self,
         node_id: usize,
         cpu_topology: &CpuTopology,
     ) -> Vec<usize> {
         if let Some(core_ids) = self.node_cores.get(&node_id) {
-            let physical_cores = cpu_topology.get_physical_core_ids();
             core_ids
                 .iter()
-                .filter(|&&id| physical_cores.contains(&id) && id != 0)
+                .filter(|&&id| cpu_topology.is_worker_core(id))
                 .copied()
                 .collect()
         } else {
@@ -169,7 +174,7 @@ impl NumaTopology {
     }
 
     /// Returns all core_affinity::CoreId objects for a specific NUMA node,
-    /// excluding hyperthread cores and core 0
+    /// excluding reserved cores (and hyperthread cores unless allowed)
     pub fn get_node_core_ids(&self, node_id: usize, cpu_topology: &CpuTopology) -> Vec<CoreId> {
         self.get_node_physical_cores(node_id, cpu_topology)
             .into_iter()
@@ -207,7 +212,7 @@ impl NumaTopology {
                 println!("  All logical cores: {:?}", cores);
 
                 let physical_cores = self.get_node_physical_cores(node_id, cpu_topology);
-                println!("  Physical cores (excluding core 0): {:?}", physical_cores);
+                println!("  Worker cores: {:?}", physical_cores);
             } else {
                 println!("  No cores found");
             }
@@ -0,0 +1,232 @@
+// src/numa/watchdog.rs
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::control::events::{EventBus, LifecycleEvent};
+use crate::cpu::migration::WorkerControlRef;
+use crate::numa::node::WorkerControl;
+use crate::{log_info, log_warn};
+
+/// Параметры проверки зависания рабочих потоков
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchdogConfig {
+    /// Время без продвижения пульса, после которого поток считается зависшим
+    ///
+    /// Должно превышать наибольшее ожидание прерывания RX (`IdlePolicy::Interrupt`).
+    pub deadline: Duration,
+    /// Заменять зависший поток новым
+    pub restart: bool,
+}
+
+impl WatchdogConfig {
+    pub fn new(deadline: Duration) -> Self {
+        Self {
+            deadline,
+            restart: false,
+        }
+    }
+
+    /// Разбирает `<deadline_ms>[,restart]`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut parts = spec.split(',');
+        let deadline_ms: u64 = parts
+            .next()
+            .unwrap_or_default()
+            .parse()
+            .ok()
+            .filter(|&ms| ms > 0)
+            .ok_or_else(|| format!("Invalid watchdog deadline in '{}'", spec))?;
+
+        let mut config = Self::new(Duration::from_millis(deadline_ms));
+        for part in parts {
+            match part {
+                "restart" => config.restart = true,
+                "alert" => config.restart = false,
+                other => return Err(format!("Invalid watchdog action '{}'", other)),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Период проверки: четверть срока, не чаще раза в миллисекунду
+    pub fn interval(&self) -> Duration {
+        (self.deadline / 4).max(Duration::from_millis(1))
+    }
+}
+
+/// Запрос на замену зависшего рабочего потока
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StallRestart {
+    pub port_id: u16,
+    pub queue_id: u16,
+    /// Значение пульса, на котором поток завис
+    pub heartbeat: u64,
+}
+
+/// Состояние наблюдения за одним рабочим потоком
+struct Watched {
+    port_id: u16,
+    queue_id: u16,
+    control: Arc<WorkerControl>,
+    heartbeat: u64,
+    since: Instant,
+    stalled: bool,
+}
+
+/// Проверка зависания рабочих потоков
+///
+/// Рабочий поток продвигает пульс (`WorkerControl::heartbeat`) на каждом
+/// опросе очереди. Проверка выполняется задачей служебного ядра
+/// (`Housekeeper`): поток, пульс которого не менялся дольше `deadline`
+/// (взаимоблокировка в обработчике, зависание PMD), попадает в журнал и
+/// `LifecycleEvent::WorkerStalled`. При `restart` поток, зависший вне опроса
+/// очереди, ставится в очередь на замену; замену выполняет поток управления
+/// движка (`NumaManager::restart_stalled_workers`). Приостановленные потоки
+/// не проверяются.
+pub struct StallWatchdog {
+    config: WatchdogConfig,
+    events: EventBus,
+    workers: Mutex<Vec<Watched>>,
+    restarts: Mutex<Vec<StallRestart>>,
+    stalls: AtomicU64,
+}
+
+impl StallWatchdog {
+    pub fn new(config: WatchdogConfig, events: EventBus) -> Self {
+        Self {
+            config,
+            events,
+            workers: Mutex::new(Vec::new()),
+            restarts: Mutex::new(Vec::new()),
+            stalls: AtomicU64::new(0),
+        }
+    }
+
+    pub fn config(&self) -> &WatchdogConfig {
+        &self.config
+    }
+
+    /// Задает наблюдаемые рабочие потоки (после запуска, остановки или замены)
+    ///
+    /// Состояние уже наблюдаемых потоков сохраняется.
+    pub fn watch(&self, workers: Vec<WorkerControlRef>) {
+        let Ok(mut watched) = self.workers.lock() else {
+            return;
+        };
+        let now = Instant::now();
+        let mut previous = std::mem::take(&mut *watched);
+
+        for (port_id, queue_id, control) in workers {
+            match previous
+                .iter()
+                .position(|w| Arc::ptr_eq(&w.control, &control))
+            {
+                Some(index) => watched.push(previous.swap_remove(index)),
+                None => watched.push(Watched {
+                    port_id,
+                    queue_id,
+                    heartbeat: control.heartbeat.load(Ordering::Relaxed),
+                    control,
+                    since: now,
+                    stalled: false,
+                }),
+            }
+        }
+    }
+
+    /// Проверяет пульс всех наблюдаемых потоков (задача служебного ядра)
+    pub fn check(&self) {
+        let Ok(mut watched) = self.workers.lock() else {
+            return;
+        };
+        let now = Instant::now();
+
+        for worker in watched.iter_mut() {
+            let control = &worker.control;
+            let heartbeat = control.heartbeat.load(Ordering::Relaxed);
+
+            // Не запущенный, остановленный и приостановленный поток пульс не продвигает
+            let idle = control.tid.load(Ordering::Acquire) == 0
+                || control.stop.load(Ordering::Relaxed)
+                || control.paused.load(Ordering::Relaxed)
+                || control.parked.load(Ordering::Relaxed);
+
+            if idle || heartbeat != worker.heartbeat {
+                if worker.stalled {
+                    log_info!(
+                        "watchdog",
+                        "Worker resumed",
+                        port = worker.port_id,
+                        queue = worker.queue_id
+                    );
+                }
+                worker.heartbeat = heartbeat;
+                worker.since = now;
+                worker.stalled = false;
+                continue;
+            }
+
+            let stalled_for = now.duration_since(worker.since);
+            if worker.stalled || stalled_for < self.config.deadline {
+                continue;
+            }
+
+            worker.stalled = true;
+            self.stalls.fetch_add(1, Ordering::Relaxed);
+
+            // Нечетный пульс: поток внутри опроса очереди (PMD)
+            let in_poll = heartbeat % 2 == 1;
+            let core_id = control.current_core.load(Ordering::Relaxed);
+            log_warn!(
+                "watchdog",
+                "Worker stalled",
+                port = worker.port_id,
+                queue = worker.queue_id,
+                core = core_id,
+                stalled_ms = stalled_for.as_millis(),
+                in_poll = in_poll
+            );
+            self.events.emit(LifecycleEvent::WorkerStalled {
+                port_id: worker.port_id,
+                queue_id: worker.queue_id,
+                core_id,
+                stalled_ms: stalled_for.as_millis() as u64,
+            });
+
+            if !self.config.restart {
+                continue;
+            }
+            // Замена опрашивала бы очередь одновременно с зависшим в PMD потоком
+            if in_poll {
+                log_warn!(
+                    "watchdog",
+                    "Worker stalled inside queue poll, not restarting",
+                    port = worker.port_id,
+                    queue = worker.queue_id
+                );
+                continue;
+            }
+            if let Ok(mut restarts) = self.restarts.lock() {
+                restarts.push(StallRestart {
+                    port_id: worker.port_id,
+                    queue_id: worker.queue_id,
+                    heartbeat,
+                });
+            }
+        }
+    }
+
+    /// Забирает накопленные запросы на замену потоков
+    pub fn take_restarts(&self) -> Vec<StallRestart> {
+        self.restarts
+            .lock()
+            .map(|mut restarts| std::mem::take(&mut *restarts))
+            .unwrap_or_default()
+    }
+
+    /// Обнаруженных зависаний
+    pub fn stalls(&self) -> u64 {
+        self.stalls.load(Ordering::Relaxed)
+    }
+}
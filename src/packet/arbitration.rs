@@ -0,0 +1,219 @@
+// src/packet/arbitration.rs
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::control::snapshot::ConfigSnapshot;
+use crate::control::state::StateSource;
+use crate::metrics::line_correlation::{Line, SequenceExtractor};
+use crate::packet::data::PacketView;
+use crate::packet::handler::PacketHandler;
+
+/// Счетчики одной линии (пишутся рабочими потоками очередей линии)
+#[derive(Debug, Default)]
+#[repr(C, align(64))]
+struct LineCounters {
+    /// Копии, доставленные первыми
+    wins: AtomicU64,
+    /// Копии, пришедшие после копии с другой линии
+    duplicates: AtomicU64,
+    /// Копии старше окна арбитража
+    stale: AtomicU64,
+    /// Пакеты без порядкового номера (переданы без арбитража)
+    unsequenced: AtomicU64,
+}
+
+/// Арбитраж двух линий фида: доставляется первая пришедшая копия
+///
+/// Окно из `window` последних порядковых номеров хранится в кольце атомарных
+/// ячеек (номер + 1, ноль - пустая ячейка). Копия выигрывает, если успела
+/// записать свой номер в ячейку раньше копии с другой линии; блокировок
+/// на пути пакета нет, поэтому линии могут обслуживаться разными потоками.
+pub struct LineArbiter {
+    slots: Box<[AtomicU64]>,
+    mask: u64,
+    lines: [LineCounters; 2],
+}
+
+impl LineArbiter {
+    /// Размер окна округляется вверх до степени двойки
+    pub fn new(window: usize) -> Self {
+        let size = window.max(2).next_power_of_two();
+        Self {
+            slots: (0..size).map(|_| AtomicU64::new(0)).collect(),
+            mask: size as u64 - 1,
+            lines: [LineCounters::default(), LineCounters::default()],
+        }
+    }
+
+    #[inline(always)]
+    fn counters(&self, line: Line) -> &LineCounters {
+        match line {
+            Line::A => &self.lines[0],
+            Line::B => &self.lines[1],
+        }
+    }
+
+    /// Возвращает true, если копия с порядковым номером пришла первой
+    #[inline(always)]
+    pub fn arbitrate(&self, line: Line, sequence: u64) -> bool {
+        let tag = sequence.wrapping_add(1);
+        let slot = &self.slots[(sequence & self.mask) as usize];
+        let counters = self.counters(line);
+
+        let mut current = slot.load(Ordering::Acquire);
+        loop {
+            if current == tag {
+                counters.duplicates.fetch_add(1, Ordering::Relaxed);
+                return false;
+            }
+            if current > tag {
+                // Ячейку уже заняли номера следующего окна
+                counters.stale.fetch_add(1, Ordering::Relaxed);
+                return false;
+            }
+
+            match slot.compare_exchange_weak(current, tag, Ordering::AcqRel, Ordering::Acquire) {
+                Ok(_) => {
+                    counters.wins.fetch_add(1, Ordering::Relaxed);
+                    return true;
+                }
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Очищает окно (например, после сброса порядковых номеров фида)
+    pub fn reset(&self) {
+        for slot in self.slots.iter() {
+            slot.store(0, Ordering::Release);
+        }
+    }
+
+    /// Наибольший порядковый номер в окне (None - окно пусто)
+    pub fn last_sequence(&self) -> Option<u64> {
+        self.slots
+            .iter()
+            .map(|slot| slot.load(Ordering::Acquire))
+            .max()
+            .filter(|&tag| tag != 0)
+            .map(|tag| tag - 1)
+    }
+
+    /// Сводка арбитража
+    pub fn report(&self) -> ArbitrationReport {
+        let line = |line: Line| {
+            let counters = self.counters(line);
+            LineArbitrationStats {
+                wins: counters.wins.load(Ordering::Relaxed),
+                duplicates: counters.duplicates.load(Ordering::Relaxed),
+                stale: counters.stale.load(Ordering::Relaxed),
+                unsequenced: counters.unsequenced.load(Ordering::Relaxed),
+            }
+        };
+
+        ArbitrationReport {
+            a: line(Line::A),
+            b: line(Line::B),
+        }
+    }
+}
+
+impl StateSource for LineArbiter {
+    fn capture(&self, prefix: &str, state: &mut ConfigSnapshot) {
+        match self.last_sequence() {
+            Some(sequence) => state.set(&format!("{}.last_sequence", prefix), sequence),
+            None => state.set(&format!("{}.last_sequence", prefix), "none"),
+        }
+
+        let report = self.report();
+        for (name, line) in [("a", report.a), ("b", report.b)] {
+            state.set(&format!("{}.{}.wins", prefix, name), line.wins);
+            state.set(&format!("{}.{}.duplicates", prefix, name), line.duplicates);
+            state.set(&format!("{}.{}.stale", prefix, name), line.stale);
+            state.set(
+                &format!("{}.{}.unsequenced", prefix, name),
+                line.unsequenced,
+            );
+        }
+    }
+}
+
+/// Счетчики арбитража одной линии
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LineArbitrationStats {
+    pub wins: u64,
+    pub duplicates: u64,
+    pub stale: u64,
+    pub unsequenced: u64,
+}
+
+/// Сводка арбитража двух линий
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArbitrationReport {
+    pub a: LineArbitrationStats,
+    pub b: LineArbitrationStats,
+}
+
+impl ArbitrationReport {
+    /// Доля копий, доставленных линией первыми
+    pub fn win_rate(&self, line: Line) -> f64 {
+        let total = self.a.wins + self.b.wins;
+        if total == 0 {
+            return 0.0;
+        }
+
+        let wins = match line {
+            Line::A => self.a.wins,
+            Line::B => self.b.wins,
+        };
+        wins as f64 / total as f64
+    }
+}
+
+impl fmt::Display for ArbitrationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "A wins={} ({:.1}%) dup={} stale={} unseq={}, B wins={} ({:.1}%) dup={} stale={} unseq={}",
+            self.a.wins,
+            self.win_rate(Line::A) * 100.0,
+            self.a.duplicates,
+            self.a.stale,
+            self.a.unsequenced,
+            self.b.wins,
+            self.win_rate(Line::B) * 100.0,
+            self.b.duplicates,
+            self.b.stale,
+            self.b.unsequenced
+        )
+    }
+}
+
+/// Оборачивает обработчик линии: дальше передается только первая копия пакета
+///
+/// Пакеты без порядкового номера передаются без арбитража.
+pub fn arbitrated_handler(
+    arbiter: Arc<LineArbiter>,
+    line: Line,
+    extractor: SequenceExtractor,
+    inner: PacketHandler,
+) -> PacketHandler {
+    Arc::new(move |queue_id: u16, packet: &PacketView| {
+        match extractor.extract(packet.data()) {
+            Some(sequence) => {
+                if !arbiter.arbitrate(line, sequence) {
+                    return;
+                }
+            }
+            None => {
+                arbiter
+                    .counters(line)
+                    .unsequenced
+                    .fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        inner(queue_id, packet);
+    })
+}
@@ -0,0 +1,186 @@
+// src/packet/breaker.rs
+use std::fmt;
+use std::time::Duration;
+
+/// Поведение очереди при сработавшем выключателе
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerMode {
+    /// Передавать обработчику не больше `reset_pps` пакетов в секунду, остальные отбрасывать
+    Shed,
+    /// Передавать обработчику только последний пакет каждой пачки (актуальное состояние)
+    Conflate,
+}
+
+impl fmt::Display for BreakerMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BreakerMode::Shed => write!(f, "shed"),
+            BreakerMode::Conflate => write!(f, "conflate"),
+        }
+    }
+}
+
+/// Параметры выключателя по интенсивности входящего потока
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+    /// Интенсивность (пакетов в секунду), при которой выключатель срабатывает
+    pub trip_pps: u64,
+    /// Интенсивность, ниже которой очередь возвращается к обычной обработке
+    pub reset_pps: u64,
+    /// Окно измерения интенсивности
+    pub window: Duration,
+    pub mode: BreakerMode,
+}
+
+impl CircuitBreakerConfig {
+    /// Выключатель с возвратом при половинной интенсивности и окном 10 мс
+    pub fn new(trip_pps: u64, mode: BreakerMode) -> Self {
+        Self {
+            trip_pps,
+            reset_pps: trip_pps / 2,
+            window: Duration::from_millis(10),
+            mode,
+        }
+    }
+
+    /// Разбирает `<trip_pps>[,<reset_pps>][,shed|conflate]`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut parts = spec.split(',');
+        let parse_rate = |value: &str| -> Result<u64, String> {
+            value
+                .parse()
+                .map_err(|_| format!("Invalid circuit breaker rate '{}'", value))
+        };
+
+        let trip_pps = parse_rate(parts.next().unwrap_or_default())?;
+        let mut config = Self::new(trip_pps, BreakerMode::Shed);
+
+        for part in parts {
+            match part {
+                "shed" => config.mode = BreakerMode::Shed,
+                "conflate" => config.mode = BreakerMode::Conflate,
+                rate => config.reset_pps = parse_rate(rate)?,
+            }
+        }
+
+        if config.trip_pps == 0 || config.reset_pps > config.trip_pps {
+            return Err(format!(
+                "Invalid circuit breaker '{}': expected 0 < reset rate <= trip rate",
+                spec
+            ));
+        }
+
+        Ok(config)
+    }
+}
+
+/// Изменение состояния выключателя
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerTransition {
+    /// Интенсивность превысила порог, очередь переведена в защитный режим
+    Tripped { rate_pps: u64 },
+    /// Интенсивность снизилась, очередь возвращена к обычной обработке
+    Reset { rate_pps: u64, shed: u64 },
+}
+
+/// Выключатель одной очереди RX
+///
+/// Защищает соседние сессии от патологического потока (например, шторма
+/// сообщений при сбое площадки): при превышении `trip_pps` очередь
+/// переводится в режим отбрасывания или схлопывания, а не занимает ядро
+/// целиком. Принадлежит рабочему потоку, атомарные операции не используются.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    window_ns: u64,
+    window_start_ns: u64,
+    window_packets: u64,
+    /// Пакетов, которые еще можно передать обработчику в текущем окне (режим Shed)
+    budget: u64,
+    open: bool,
+    /// Отброшено с момента срабатывания
+    shed: u64,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        let window_ns = (config.window.as_nanos() as u64).max(1);
+        Self {
+            config,
+            window_ns,
+            window_start_ns: 0,
+            window_packets: 0,
+            budget: 0,
+            open: false,
+            shed: 0,
+        }
+    }
+
+    /// Выключатель сработал
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Учитывает принятую пачку; возвращает изменение состояния по итогам окна
+    #[inline(always)]
+    pub fn on_burst(&mut self, nb_rx: u16, now_ns: u64) -> Option<BreakerTransition> {
+        self.window_packets += nb_rx as u64;
+
+        let elapsed = now_ns.saturating_sub(self.window_start_ns);
+        if elapsed < self.window_ns {
+            return None;
+        }
+
+        let first_window = self.window_start_ns == 0;
+        let rate_pps = (self.window_packets as u128 * 1_000_000_000 / elapsed as u128) as u64;
+        self.window_start_ns = now_ns;
+        self.window_packets = 0;
+        self.budget =
+            (self.config.reset_pps as u128 * self.window_ns as u128 / 1_000_000_000).max(1) as u64;
+
+        if first_window {
+            return None;
+        }
+
+        if !self.open && rate_pps >= self.config.trip_pps {
+            self.open = true;
+            self.shed = 0;
+            return Some(BreakerTransition::Tripped { rate_pps });
+        }
+
+        if self.open && rate_pps < self.config.reset_pps {
+            self.open = false;
+            return Some(BreakerTransition::Reset {
+                rate_pps,
+                shed: self.shed,
+            });
+        }
+
+        None
+    }
+
+    /// Решает, передавать ли обработчику пакет `index` пачки из `nb_rx` пакетов
+    #[inline(always)]
+    pub fn admit(&mut self, index: usize, nb_rx: usize) -> bool {
+        if !self.open {
+            return true;
+        }
+
+        let admitted = match self.config.mode {
+            BreakerMode::Shed => {
+                if self.budget > 0 {
+                    self.budget -= 1;
+                    true
+                } else {
+                    false
+                }
+            }
+            BreakerMode::Conflate => index + 1 == nb_rx,
+        };
+
+        if !admitted {
+            self.shed += 1;
+        }
+        admitted
+    }
+}
@@ -0,0 +1,441 @@
+// src/packet/budget.rs
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use core_affinity::CoreId;
+use crossbeam::queue::ArrayQueue;
+
+use crate::control::snapshot::ConfigSnapshot;
+use crate::control::state::StateSource;
+use crate::cpu::housekeeping;
+use crate::packet::data::{PacketRef, PacketView};
+use crate::packet::handler::PacketHandler;
+use crate::time;
+use crate::time::tsc::rdtsc;
+use crate::{log_info, log_warn};
+
+/// Емкость очереди переноса фида на резервное ядро (пакетов)
+const OFFLOAD_QUEUE_CAPACITY: usize = 4096;
+/// Наибольшее число пакетов фида, обрабатываемых резервным ядром за проход
+const OVERFLOW_BATCH: usize = 32;
+
+/// Изоляция фида, превысившего бюджет декодирования
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverloadAction {
+    /// Обрабатывать все пакеты фида на резервном ядре
+    Offload,
+    /// Обрабатывать на резервном ядре только последний пакет (актуальное состояние)
+    Conflate,
+}
+
+impl fmt::Display for OverloadAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OverloadAction::Offload => write!(f, "offload"),
+            OverloadAction::Conflate => write!(f, "conflate"),
+        }
+    }
+}
+
+/// Бюджет процессорного времени на декодирование фида
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeedBudgetConfig {
+    /// Доля одного ядра (в процентах), при превышении которой фид изолируется
+    pub max_share_pct: u64,
+    /// Окно измерения
+    pub window: Duration,
+    pub action: OverloadAction,
+}
+
+impl FeedBudgetConfig {
+    /// Бюджет с окном 100 мс
+    pub fn new(max_share_pct: u64, action: OverloadAction) -> Self {
+        Self {
+            max_share_pct,
+            window: Duration::from_millis(100),
+            action,
+        }
+    }
+
+    /// Разбирает `<max_pct>[,offload|conflate]`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (pct, action) = spec.split_once(',').unwrap_or((spec, "offload"));
+
+        let max_share_pct = match pct.parse::<u64>() {
+            Ok(pct) if pct > 0 => pct,
+            _ => {
+                return Err(format!(
+                    "Invalid feed budget '{}': expected a positive core share in percent",
+                    pct
+                ))
+            }
+        };
+        let action = match action {
+            "offload" => OverloadAction::Offload,
+            "conflate" => OverloadAction::Conflate,
+            other => {
+                return Err(format!(
+                    "Invalid overload action '{}': expected offload or conflate",
+                    other
+                ))
+            }
+        };
+
+        Ok(Self::new(max_share_pct, action))
+    }
+}
+
+/// Счетчики бюджета фида
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BudgetStats {
+    pub packets: u64,
+    /// Средняя стоимость декодирования пакета за последнее окно (такты TSC)
+    pub cycles_per_packet: u64,
+    /// Стоимость декодирования всего потока фида за последнее окно (% ядра)
+    pub share_pct: u64,
+    pub isolated: bool,
+    /// Сколько раз фид изолировался
+    pub isolations: u64,
+    /// Пакеты, обработанные резервным ядром
+    pub offloaded: u64,
+    /// Пакеты, замененные более новыми в режиме схлопывания
+    pub conflated: u64,
+    /// Пакеты, не поместившиеся в очередь переноса
+    pub dropped: u64,
+}
+
+impl fmt::Display for BudgetStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "packets={} cycles/pkt={} share={}% isolated={} isolations={} offloaded={} conflated={} dropped={}",
+            self.packets,
+            self.cycles_per_packet,
+            self.share_pct,
+            self.isolated,
+            self.isolations,
+            self.offloaded,
+            self.conflated,
+            self.dropped
+        )
+    }
+}
+
+/// Учет стоимости декодирования фида и его изоляция при перегрузке
+///
+/// Обработчик фида (`budgeted_handler`) измеряет такты TSC каждого вызова.
+/// По итогам окна стоимость всего входящего потока (средняя стоимость пакета,
+/// умноженная на число пришедших пакетов) сравнивается с бюджетом: при
+/// превышении (например, после изменения схемы или во время всплеска) фид
+/// уходит на резервное ядро (`OverflowWorker`) целиком или в режиме
+/// схлопывания, и соседние фиды того же рабочего потока не ждут его
+/// декодирования. Фид возвращается, когда стоимость падает ниже половины
+/// бюджета. Во время переключения порядок пакетов фида не гарантируется.
+pub struct FeedBudget {
+    name: String,
+    config: FeedBudgetConfig,
+    window_ns: u64,
+    window_start_ns: AtomicU64,
+    window_arrivals: AtomicU64,
+    window_decoded: AtomicU64,
+    window_cycles: AtomicU64,
+    cycles_per_packet: AtomicU64,
+    share_pct: AtomicU64,
+    packets: AtomicU64,
+    isolated: AtomicBool,
+    /// Резервное ядро разбирает очередь фида
+    serviced: AtomicBool,
+    isolations: AtomicU64,
+    offloaded: AtomicU64,
+    conflated: AtomicU64,
+    dropped: AtomicU64,
+    overflow: ArrayQueue<PacketRef>,
+}
+
+impl FeedBudget {
+    pub fn new(name: &str, config: FeedBudgetConfig) -> Self {
+        let capacity = match config.action {
+            OverloadAction::Offload => OFFLOAD_QUEUE_CAPACITY,
+            OverloadAction::Conflate => 1,
+        };
+
+        Self {
+            name: name.to_string(),
+            config,
+            window_ns: (config.window.as_nanos() as u64).max(1),
+            window_start_ns: AtomicU64::new(0),
+            window_arrivals: AtomicU64::new(0),
+            window_decoded: AtomicU64::new(0),
+            window_cycles: AtomicU64::new(0),
+            cycles_per_packet: AtomicU64::new(0),
+            share_pct: AtomicU64::new(0),
+            packets: AtomicU64::new(0),
+            isolated: AtomicBool::new(false),
+            serviced: AtomicBool::new(false),
+            isolations: AtomicU64::new(0),
+            offloaded: AtomicU64::new(0),
+            conflated: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            overflow: ArrayQueue::new(capacity),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Фид изолирован
+    #[inline(always)]
+    pub fn is_isolated(&self) -> bool {
+        self.isolated.load(Ordering::Relaxed)
+    }
+
+    pub fn stats(&self) -> BudgetStats {
+        BudgetStats {
+            packets: self.packets.load(Ordering::Relaxed),
+            cycles_per_packet: self.cycles_per_packet.load(Ordering::Relaxed),
+            share_pct: self.share_pct.load(Ordering::Relaxed),
+            isolated: self.is_isolated(),
+            isolations: self.isolations.load(Ordering::Relaxed),
+            offloaded: self.offloaded.load(Ordering::Relaxed),
+            conflated: self.conflated.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Учитывает вызов обработчика фида
+    #[inline(always)]
+    fn record(&self, cycles: u64) {
+        self.window_decoded.fetch_add(1, Ordering::Relaxed);
+        self.window_cycles.fetch_add(cycles, Ordering::Relaxed);
+    }
+
+    /// Передает пакет резервному ядру; false - пакет нужно обработать на месте
+    fn divert(&self, packet: &PacketView) -> bool {
+        if !self.serviced.load(Ordering::Acquire) {
+            return false;
+        }
+        // Собранные датаграммы не удержать: они декодируются рабочим потоком
+        let Some(packet) = packet.retain() else {
+            return false;
+        };
+
+        match self.config.action {
+            OverloadAction::Offload => {
+                if self.overflow.push(packet).is_err() {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            OverloadAction::Conflate => {
+                if self.overflow.force_push(packet).is_some() {
+                    self.conflated.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+        true
+    }
+
+    /// Подводит итог окна, если оно истекло, и переключает изоляцию фида
+    fn roll_window(&self, now_ns: u64) {
+        let start_ns = self.window_start_ns.load(Ordering::Relaxed);
+        let elapsed = now_ns.saturating_sub(start_ns);
+        if elapsed < self.window_ns {
+            return;
+        }
+        // Итог окна подводит один поток
+        if self
+            .window_start_ns
+            .compare_exchange(start_ns, now_ns, Ordering::AcqRel, Ordering::Relaxed)
+            .is_err()
+        {
+            return;
+        }
+
+        let arrivals = self.window_arrivals.swap(0, Ordering::Relaxed);
+        let decoded = self.window_decoded.swap(0, Ordering::Relaxed);
+        let cycles = self.window_cycles.swap(0, Ordering::Relaxed);
+        if start_ns == 0 || decoded == 0 {
+            return;
+        }
+
+        // В режиме схлопывания декодируется часть пакетов: оцениваем стоимость всего потока
+        let cycles_per_packet = cycles / decoded;
+        let share_pct = (cycles_to_ns(cycles_per_packet as u128 * arrivals as u128) * 100
+            / elapsed as u128) as u64;
+        self.cycles_per_packet
+            .store(cycles_per_packet, Ordering::Relaxed);
+        self.share_pct.store(share_pct, Ordering::Relaxed);
+
+        let isolated = self.is_isolated();
+        if !isolated && share_pct >= self.config.max_share_pct {
+            self.isolated.store(true, Ordering::Relaxed);
+            self.isolations.fetch_add(1, Ordering::Relaxed);
+            log_warn!(
+                "budget",
+                "Feed decode cost exceeded budget, feed isolated",
+                feed = self.name,
+                share_pct = share_pct,
+                cycles_per_packet = cycles_per_packet,
+                action = self.config.action
+            );
+        } else if isolated && share_pct < self.config.max_share_pct / 2 {
+            self.isolated.store(false, Ordering::Relaxed);
+            log_info!(
+                "budget",
+                "Feed decode cost back within budget, feed restored",
+                feed = self.name,
+                share_pct = share_pct,
+                cycles_per_packet = cycles_per_packet
+            );
+        }
+    }
+}
+
+impl StateSource for FeedBudget {
+    fn capture(&self, prefix: &str, state: &mut ConfigSnapshot) {
+        let stats = self.stats();
+        state.set(
+            &format!("{}.max_share_pct", prefix),
+            self.config.max_share_pct,
+        );
+        state.set(&format!("{}.action", prefix), self.config.action);
+        state.set(&format!("{}.isolated", prefix), stats.isolated);
+        state.set(&format!("{}.share_pct", prefix), stats.share_pct);
+        state.set(
+            &format!("{}.cycles_per_packet", prefix),
+            stats.cycles_per_packet,
+        );
+        state.set(&format!("{}.isolations", prefix), stats.isolations);
+        state.set(&format!("{}.offloaded", prefix), stats.offloaded);
+        state.set(&format!("{}.conflated", prefix), stats.conflated);
+        state.set(&format!("{}.dropped", prefix), stats.dropped);
+    }
+}
+
+/// Переводит такты TSC в наносекунды
+fn cycles_to_ns(cycles: u128) -> u128 {
+    // Без инвариантного TSC счетчик и так идет в наносекундах
+    match time::tsc::clock().tsc_hz() {
+        0 => cycles,
+        hz => cycles * 1_000_000_000 / hz as u128,
+    }
+}
+
+/// Оборачивает обработчик фида: стоимость декодирования учитывается в бюджете,
+/// пакеты изолированного фида передаются резервному ядру
+pub fn budgeted_handler(budget: Arc<FeedBudget>, inner: PacketHandler) -> PacketHandler {
+    Arc::new(move |queue_id: u16, packet: &PacketView| {
+        budget.packets.fetch_add(1, Ordering::Relaxed);
+        budget.window_arrivals.fetch_add(1, Ordering::Relaxed);
+
+        if !(budget.is_isolated() && budget.divert(packet)) {
+            let start = rdtsc();
+            inner(queue_id, packet);
+            budget.record(rdtsc().wrapping_sub(start));
+        }
+
+        budget.roll_window(packet.rx_timestamp_ns());
+    })
+}
+
+/// Резервное ядро для изолированных фидов
+///
+/// Разбирает очереди переноса всех фидов с бюджетом. На выделенном ядре
+/// поток опрашивает очереди непрерывно, без ядра - засыпает на время простоя.
+pub struct OverflowWorker {
+    feeds: Vec<Arc<FeedBudget>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl OverflowWorker {
+    /// Запускает поток; `feeds` - бюджеты фидов и их обработчики без обертки бюджета
+    pub fn start(
+        feeds: Vec<(Arc<FeedBudget>, PacketHandler)>,
+        core_id: Option<CoreId>,
+    ) -> Result<Self, String> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let budgets: Vec<Arc<FeedBudget>> =
+            feeds.iter().map(|(budget, _)| budget.clone()).collect();
+
+        let thread = thread::Builder::new()
+            .name("hfeec-overflow".to_string())
+            .spawn(move || {
+                match core_id {
+                    Some(core_id) => {
+                        core_affinity::set_for_current(core_id);
+                    }
+                    None => housekeeping::pin_current_thread(),
+                }
+                log_info!(
+                    "budget",
+                    "Overflow worker started",
+                    core = core_id.map_or("none".to_string(), |core| core.id.to_string()),
+                    feeds = feeds.len()
+                );
+
+                while !thread_stop.load(Ordering::Relaxed) {
+                    let mut idle = true;
+                    for (budget, handler) in &feeds {
+                        for _ in 0..OVERFLOW_BATCH {
+                            let Some(packet) = budget.overflow.pop() else {
+                                break;
+                            };
+                            idle = false;
+
+                            let view = packet.view();
+                            let start = rdtsc();
+                            handler(view.queue_id(), &view);
+                            budget.record(rdtsc().wrapping_sub(start));
+                            budget.offloaded.fetch_add(1, Ordering::Relaxed);
+                        }
+                        // Фид без новых пакетов тоже должен вернуться из изоляции
+                        budget.roll_window(time::now_ns());
+                    }
+
+                    if idle {
+                        if core_id.is_some() {
+                            std::hint::spin_loop();
+                        } else {
+                            thread::sleep(Duration::from_micros(50));
+                        }
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to spawn overflow worker: {}", e))?;
+
+        for budget in &budgets {
+            budget.serviced.store(true, Ordering::Release);
+        }
+
+        Ok(Self {
+            feeds: budgets,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// Останавливает поток; удержанные пакеты освобождаются
+    pub fn stop(&mut self) {
+        for budget in &self.feeds {
+            budget.serviced.store(false, Ordering::Release);
+        }
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        for budget in &self.feeds {
+            while budget.overflow.pop().is_some() {}
+        }
+    }
+}
+
+impl Drop for OverflowWorker {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
@@ -1,23 +1,48 @@
 // src/packet/data.rs
-use crate::dpdk::ffi::RteMbuf;
+#[cfg(feature = "dpdk")]
+use crate::dpdk::ffi::{self, RteMbuf};
 
-/// Структура для хранения данных пакета
+/// Без DPDK пакеты не связаны с mbuf (указатель всегда нулевой)
+#[cfg(not(feature = "dpdk"))]
+type RteMbuf = std::ffi::c_void;
+
+/// Внутреннее представление принятого пакета
+///
+/// Указатели ссылаются на данные mbuf и действительны только до освобождения
+/// пачки, поэтому обработчики получают `PacketView`, срезы которого
+/// ограничены временем жизни пачки.
 #[repr(C, align(64))]
 pub struct PacketData {
     // High
-    pub data_ptr: *const u8,
-    pub data_len: usize,
+    pub(crate) data_ptr: *const u8,
+    /// Часть полезной нагрузки в первом сегменте mbuf
+    pub(crate) data_len: usize,
+    /// Часть полезной нагрузки в следующих сегментах цепочки (0 - нагрузка непрерывна)
+    pub(crate) chained_len: usize,
     // Mid
-    pub source_port: u16,
-    pub dest_port: u16,
-    pub queue_id: u16,
-    pub _padding: u16,
+    pub(crate) source_port: u16,
+    pub(crate) dest_port: u16,
+    pub(crate) queue_id: u16,
+    pub(crate) ether_type: u16,
+    pub(crate) port_id: u16,
+    /// Номер протокола IP (UDP, TCP)
+    pub(crate) protocol: u8,
     // Low
-    pub source_ip_ptr: *const u8,
-    pub source_ip_len: usize,
-    pub dest_ip_ptr: *const u8,
-    pub dest_ip_len: usize,
-    pub mbuf_ptr: *mut RteMbuf,
+    pub(crate) source_ip_ptr: *const u8,
+    pub(crate) source_ip_len: usize,
+    pub(crate) dest_ip_ptr: *const u8,
+    pub(crate) dest_ip_len: usize,
+    pub(crate) mbuf_ptr: *mut RteMbuf,
+    /// Начало Ethernet кадра (для MAC-адресов; при туннелировании - внешний кадр)
+    pub(crate) l2_ptr: *const u8,
+    /// TCI VLAN тега (действителен при `has_vlan`)
+    pub(crate) vlan_tci: u16,
+    pub(crate) has_vlan: bool,
+    /// Время приема пачки, в которой пришел пакет (`time::now_ns`)
+    pub(crate) rx_timestamp_ns: u64,
+    /// Идентификатор выдачи из пула (только в отладочной сборке, для поиска утечек)
+    #[cfg(debug_assertions)]
+    pub(crate) lease_id: u64,
 }
 
 impl PacketData {
@@ -25,17 +50,26 @@ impl PacketData {
         Self {
             data_ptr: std::ptr::null(),
             data_len: 0,
+            chained_len: 0,
 
             source_port: 0,
             dest_port: 0,
             queue_id: 0,
-            _padding: 0,
+            ether_type: 0,
+            port_id: 0,
+            protocol: 0,
 
             source_ip_ptr: std::ptr::null(),
             source_ip_len: 0,
             dest_ip_ptr: std::ptr::null(),
             dest_ip_len: 0,
             mbuf_ptr: std::ptr::null_mut(),
+            l2_ptr: std::ptr::null(),
+            vlan_tci: 0,
+            has_vlan: false,
+            rx_timestamp_ns: 0,
+            #[cfg(debug_assertions)]
+            lease_id: 0,
         }
     }
 
@@ -45,35 +79,430 @@ impl PacketData {
     pub fn reset(&mut self) {
         self.data_ptr = std::ptr::null();
         self.data_len = 0;
+        self.chained_len = 0;
 
         self.source_port = 0;
         self.dest_port = 0;
         self.queue_id = 0;
+        self.ether_type = 0;
+        self.port_id = 0;
+        self.protocol = 0;
 
         self.source_ip_ptr = std::ptr::null();
         self.source_ip_len = 0;
         self.dest_ip_ptr = std::ptr::null();
         self.dest_ip_len = 0;
         self.mbuf_ptr = std::ptr::null_mut();
+        self.l2_ptr = std::ptr::null();
+        self.vlan_tci = 0;
+        self.has_vlan = false;
+        self.rx_timestamp_ns = 0;
     }
 
     /// Получает исходный IP-адрес в виде среза
     #[inline(always)]
-    pub fn get_source_ip(&self) -> &[u8] {
+    pub(crate) fn get_source_ip(&self) -> &[u8] {
         unsafe { std::slice::from_raw_parts(self.source_ip_ptr, self.source_ip_len) }
     }
 
     /// Получает IP-адрес назначения в виде среза
     #[inline(always)]
-    pub fn get_dest_ip(&self) -> &[u8] {
+    pub(crate) fn get_dest_ip(&self) -> &[u8] {
         unsafe { std::slice::from_raw_parts(self.dest_ip_ptr, self.dest_ip_len) }
     }
 
+    /// Получает MAC-адрес назначения
+    #[inline(always)]
+    pub(crate) fn get_dest_mac(&self) -> Option<&[u8; 6]> {
+        if self.l2_ptr.is_null() {
+            return None;
+        }
+        unsafe { Some(&*(self.l2_ptr as *const [u8; 6])) }
+    }
+
+    /// Получает MAC-адрес источника
+    #[inline(always)]
+    pub(crate) fn get_source_mac(&self) -> Option<&[u8; 6]> {
+        if self.l2_ptr.is_null() {
+            return None;
+        }
+        unsafe { Some(&*(self.l2_ptr.add(6) as *const [u8; 6])) }
+    }
+
+    /// Получает идентификатор VLAN (12 младших бит TCI)
+    #[inline(always)]
+    pub(crate) fn get_vlan_id(&self) -> Option<u16> {
+        if self.has_vlan {
+            Some(self.vlan_tci & 0x0fff)
+        } else {
+            None
+        }
+    }
+
+    /// Получает приоритет 802.1p (3 старших бита TCI)
+    #[inline(always)]
+    pub(crate) fn get_vlan_priority(&self) -> Option<u8> {
+        if self.has_vlan {
+            Some((self.vlan_tci >> 13) as u8)
+        } else {
+            None
+        }
+    }
+
     /// Получает данные пакета в виде среза
     #[inline(always)]
-    pub fn get_data(&self) -> &[u8] {
+    pub(crate) fn get_data(&self) -> &[u8] {
         unsafe { std::slice::from_raw_parts(self.data_ptr, self.data_len) }
     }
+
+    /// Удерживает mbuf пакета (см. `PacketView::retain`)
+    #[inline(always)]
+    pub(crate) fn retain(&self) -> Option<PacketRef> {
+        if self.mbuf_ptr.is_null() {
+            return None;
+        }
+
+        // Ссылка берется на каждый сегмент цепочки: освобождение пачки
+        // уменьшает счетчик каждого сегмента, и без нее хвост цепочки
+        // вернулся бы в пул раньше первого сегмента
+        #[cfg(feature = "dpdk")]
+        unsafe {
+            ffi::dpdk_mbuf_refcnt_update(self.mbuf_ptr, 1)
+        };
+
+        Some(PacketRef {
+            raw: PacketData {
+                data_ptr: self.data_ptr,
+                data_len: self.data_len,
+                chained_len: self.chained_len,
+                source_port: self.source_port,
+                dest_port: self.dest_port,
+                queue_id: self.queue_id,
+                ether_type: self.ether_type,
+                port_id: self.port_id,
+                protocol: self.protocol,
+                source_ip_ptr: self.source_ip_ptr,
+                source_ip_len: self.source_ip_len,
+                dest_ip_ptr: self.dest_ip_ptr,
+                dest_ip_len: self.dest_ip_len,
+                mbuf_ptr: self.mbuf_ptr,
+                l2_ptr: self.l2_ptr,
+                vlan_tci: self.vlan_tci,
+                has_vlan: self.has_vlan,
+                rx_timestamp_ns: self.rx_timestamp_ns,
+                #[cfg(debug_assertions)]
+                lease_id: self.lease_id,
+            },
+        })
+    }
 }
 
 unsafe impl Send for PacketData {}
+
+/// Пакет, переданный обработчику
+///
+/// Срезы привязаны к времени жизни `'burst`: пачка mbuf освобождается после
+/// возврата из обработчика, и ссылка на данные пакета не может его пережить.
+/// Чтобы сохранить пакет дольше, его нужно удержать (`retain`).
+#[derive(Clone, Copy)]
+pub struct PacketView<'burst> {
+    raw: &'burst PacketData,
+}
+
+impl<'burst> PacketView<'burst> {
+    #[inline(always)]
+    pub(crate) fn new(raw: &'burst PacketData) -> Self {
+        Self { raw }
+    }
+
+    /// Полезная нагрузка L4
+    ///
+    /// Для пакета из цепочки mbuf (`is_chained`) - только часть в первом
+    /// сегменте; вся нагрузка доступна через `segments` и `linearize`.
+    #[inline(always)]
+    pub fn data(&self) -> &'burst [u8] {
+        self.raw.get_data()
+    }
+
+    /// Длина всей полезной нагрузки L4, включая следующие сегменты цепочки
+    #[inline(always)]
+    pub fn payload_len(&self) -> usize {
+        self.raw.data_len + self.raw.chained_len
+    }
+
+    /// Полезная нагрузка продолжается в следующих сегментах цепочки mbuf
+    ///
+    /// Так принимаются jumbo кадры больше сегмента пула и пакеты, собранные LRO.
+    #[inline(always)]
+    pub fn is_chained(&self) -> bool {
+        self.raw.chained_len > 0
+    }
+
+    /// Части полезной нагрузки по сегментам цепочки mbuf
+    ///
+    /// Для непрерывной нагрузки - один срез, совпадающий с `data`.
+    #[inline(always)]
+    pub fn segments(&self) -> PayloadSegments<'burst> {
+        PayloadSegments {
+            first: Some(self.raw.get_data()),
+            mbuf: self.raw.mbuf_ptr,
+            remaining: self.raw.chained_len,
+            _burst: std::marker::PhantomData,
+        }
+    }
+
+    /// Непрерывная полезная нагрузка
+    ///
+    /// Непрерывная нагрузка возвращается без копирования; нагрузка из
+    /// цепочки копируется в `buf` (буфер переиспользуется между пакетами).
+    pub fn linearize<'a>(&self, buf: &'a mut Vec<u8>) -> &'a [u8]
+    where
+        'burst: 'a,
+    {
+        if !self.is_chained() {
+            return self.data();
+        }
+
+        buf.clear();
+        buf.reserve(self.payload_len());
+        for segment in self.segments() {
+            buf.extend_from_slice(segment);
+        }
+        buf
+    }
+
+    /// IP-адрес источника
+    #[inline(always)]
+    pub fn source_ip(&self) -> &'burst [u8] {
+        self.raw.get_source_ip()
+    }
+
+    /// IP-адрес назначения
+    #[inline(always)]
+    pub fn dest_ip(&self) -> &'burst [u8] {
+        self.raw.get_dest_ip()
+    }
+
+    /// MAC-адрес источника (для туннелей - внешнего кадра)
+    #[inline(always)]
+    pub fn source_mac(&self) -> Option<&'burst [u8; 6]> {
+        self.raw.get_source_mac()
+    }
+
+    /// MAC-адрес назначения (для туннелей - внешнего кадра)
+    #[inline(always)]
+    pub fn dest_mac(&self) -> Option<&'burst [u8; 6]> {
+        self.raw.get_dest_mac()
+    }
+
+    #[inline(always)]
+    pub fn source_port(&self) -> u16 {
+        self.raw.source_port
+    }
+
+    #[inline(always)]
+    pub fn dest_port(&self) -> u16 {
+        self.raw.dest_port
+    }
+
+    #[inline(always)]
+    pub fn queue_id(&self) -> u16 {
+        self.raw.queue_id
+    }
+
+    #[inline(always)]
+    pub fn ether_type(&self) -> u16 {
+        self.raw.ether_type
+    }
+
+    /// Порт приема
+    #[inline(always)]
+    pub fn port_id(&self) -> u16 {
+        self.raw.port_id
+    }
+
+    /// Номер протокола IP (17 - UDP, 6 - TCP)
+    #[inline(always)]
+    pub fn protocol(&self) -> u8 {
+        self.raw.protocol
+    }
+
+    /// Идентификатор VLAN (12 младших бит TCI)
+    #[inline(always)]
+    pub fn vlan_id(&self) -> Option<u16> {
+        self.raw.get_vlan_id()
+    }
+
+    /// Приоритет 802.1p (3 старших бита TCI)
+    #[inline(always)]
+    pub fn vlan_priority(&self) -> Option<u8> {
+        self.raw.get_vlan_priority()
+    }
+
+    /// Время приема пачки (`time::now_ns`)
+    #[inline(always)]
+    pub fn rx_timestamp_ns(&self) -> u64 {
+        self.raw.rx_timestamp_ns
+    }
+
+    /// Аппаратная отметка времени приема (часы NIC, см. `DpdkConfig::with_hw_timestamps`)
+    ///
+    /// None, если отметка не включена или NIC ее не поставил, и для пакетов без mbuf.
+    #[inline(always)]
+    pub fn hw_timestamp_ns(&self) -> Option<u64> {
+        #[cfg(feature = "dpdk")]
+        {
+            let mut timestamp = 0u64;
+            let stamped = unsafe { ffi::dpdk_mbuf_rx_timestamp(self.raw.mbuf_ptr, &mut timestamp) };
+            (stamped == 1).then_some(timestamp)
+        }
+        #[cfg(not(feature = "dpdk"))]
+        None
+    }
+
+    /// Удерживает mbuf пакета после возврата из обработчика
+    ///
+    /// Увеличивает счетчик ссылок всех сегментов mbuf, и пакет (включая
+    /// `segments` и `linearize` для цепочки) остается действительным до
+    /// освобождения `PacketRef`. Пакеты без mbuf (собранные из фрагментов
+    /// датаграммы) удержать нельзя, их данные нужно скопировать.
+    #[inline(always)]
+    pub fn retain(&self) -> Option<PacketRef> {
+        self.raw.retain()
+    }
+
+    /// Ethernet кадр от начала до конца полезной нагрузки
+    ///
+    /// None для собранных датаграмм: их данные лежат не в кадре. Для пакета
+    /// из цепочки mbuf кадр заканчивается вместе с первым сегментом.
+    #[inline(always)]
+    pub fn frame(&self) -> Option<&'burst [u8]> {
+        if self.raw.mbuf_ptr.is_null() || self.raw.l2_ptr.is_null() {
+            return None;
+        }
+        unsafe {
+            let len = self.raw.data_ptr.offset_from(self.raw.l2_ptr) as usize + self.raw.data_len;
+            Some(std::slice::from_raw_parts(self.raw.l2_ptr, len))
+        }
+    }
+
+    /// Указатель на mbuf пакета (нулевой для собранных датаграмм)
+    #[inline(always)]
+    pub(crate) fn mbuf_ptr(&self) -> *mut RteMbuf {
+        self.raw.mbuf_ptr
+    }
+
+    /// Указатель на полезную нагрузку для изменения на месте (только данные mbuf)
+    ///
+    /// Выводится из указателя данных mbuf, а не из срезов пакета: запись
+    /// через указатель, полученный из `&[u8]`, недопустима.
+    #[inline(always)]
+    pub(crate) fn data_mut_ptr(&self) -> *mut u8 {
+        #[cfg(feature = "dpdk")]
+        unsafe {
+            // Кадр пакета начинается с данных первого сегмента (`fill_packet`)
+            let base = ffi::dpdk_mbuf_mtod(self.raw.mbuf_ptr) as *mut u8;
+            base.offset(self.raw.data_ptr.offset_from(self.raw.l2_ptr))
+        }
+        #[cfg(not(feature = "dpdk"))]
+        {
+            self.raw.data_ptr as *mut u8
+        }
+    }
+}
+
+/// Итератор по частям полезной нагрузки в сегментах цепочки mbuf (`PacketView::segments`)
+pub struct PayloadSegments<'burst> {
+    /// Часть в первом сегменте (еще не выдана)
+    first: Option<&'burst [u8]>,
+    /// Сегмент, выданный последним
+    mbuf: *mut RteMbuf,
+    /// Нагрузка в еще не пройденных сегментах
+    remaining: usize,
+    _burst: std::marker::PhantomData<&'burst PacketData>,
+}
+
+impl<'burst> Iterator for PayloadSegments<'burst> {
+    type Item = &'burst [u8];
+
+    fn next(&mut self) -> Option<&'burst [u8]> {
+        if let Some(first) = self.first.take() {
+            return Some(first);
+        }
+
+        #[cfg(feature = "dpdk")]
+        while self.remaining > 0 && !self.mbuf.is_null() {
+            // Сегменты цепочки живут столько же, сколько первый (до освобождения
+            // пачки или удерживающего `PacketRef`)
+            self.mbuf = unsafe { ffi::dpdk_mbuf_next(self.mbuf) };
+            if self.mbuf.is_null() {
+                break;
+            }
+
+            let segment = unsafe {
+                let data = ffi::dpdk_mbuf_mtod(self.mbuf) as *const u8;
+                let len = (ffi::dpdk_mbuf_data_len(self.mbuf) as usize).min(self.remaining);
+                std::slice::from_raw_parts(data, len)
+            };
+            if segment.is_empty() {
+                continue;
+            }
+            self.remaining -= segment.len();
+            return Some(segment);
+        }
+
+        None
+    }
+}
+
+/// Пакет, удерживаемый после возврата из обработчика (`PacketView::retain`)
+///
+/// Владеет одной ссылкой на mbuf и освобождает ее при Drop, поэтому пакет
+/// можно передать другому потоку для асинхронной обработки. Пока ссылка
+/// жива, mbuf не возвращается в пул: долгое удержание многих пакетов
+/// истощает пул очереди.
+pub struct PacketRef {
+    /// Копия описания пакета: указатели действительны, пока удерживается mbuf
+    raw: PacketData,
+}
+
+impl PacketRef {
+    /// Получает данные пакета в виде среза
+    #[inline(always)]
+    pub fn data(&self) -> &[u8] {
+        self.raw.get_data()
+    }
+
+    /// Получает Ethernet кадр от начала до конца данных
+    #[inline(always)]
+    pub fn frame(&self) -> &[u8] {
+        unsafe {
+            let len = self.raw.data_ptr.offset_from(self.raw.l2_ptr) as usize + self.raw.data_len;
+            std::slice::from_raw_parts(self.raw.l2_ptr, len)
+        }
+    }
+
+    /// Представление пакета для обработчика (например, при отложенной доставке)
+    #[inline(always)]
+    pub fn view(&self) -> PacketView<'_> {
+        PacketView::new(&self.raw)
+    }
+
+    /// Указатель на mbuf (например, для отправки без копирования)
+    pub fn mbuf_ptr(&self) -> *mut RteMbuf {
+        self.raw.mbuf_ptr
+    }
+}
+
+impl Drop for PacketRef {
+    fn drop(&mut self) {
+        // rte_pktmbuf_free снимает ссылку с каждого сегмента цепочки
+        #[cfg(feature = "dpdk")]
+        unsafe {
+            ffi::dpdk_mbuf_free(self.raw.mbuf_ptr)
+        };
+    }
+}
+
+// mbuf с увеличенным счетчиком ссылок не изменяется рабочим потоком
+unsafe impl Send for PacketRef {}
@@ -0,0 +1,569 @@
+// src/packet/decrypt.rs
+use std::fmt;
+use std::fs;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use crate::control::snapshot::ConfigSnapshot;
+use crate::control::state::StateSource;
+use crate::packet::data::PacketView;
+use crate::packet::handler::PacketHandler;
+
+const AES_BLOCK_LEN: usize = 16;
+/// Наибольшее количество раундовых ключей (AES-256: 14 раундов)
+const MAX_ROUND_KEYS: usize = 15;
+
+/// Расписание раундовых ключей AES-128 или AES-256 для шифрования блоков (AES-NI)
+#[derive(Clone)]
+pub struct AesKey {
+    round_keys: [[u8; AES_BLOCK_LEN]; MAX_ROUND_KEYS],
+    rounds: usize,
+}
+
+impl AesKey {
+    /// Разворачивает ключ длиной 16 или 32 байта
+    pub fn new(key: &[u8]) -> Result<Self, String> {
+        if !aes_ni_available() {
+            return Err("AES-NI is not available on this CPU".to_string());
+        }
+
+        let mut aes_key = Self {
+            round_keys: [[0; AES_BLOCK_LEN]; MAX_ROUND_KEYS],
+            rounds: 0,
+        };
+        match key.len() {
+            16 => {
+                aes_key.rounds = 10;
+                unsafe { aesni::expand_128(key, &mut aes_key.round_keys) };
+            }
+            32 => {
+                aes_key.rounds = 14;
+                unsafe { aesni::expand_256(key, &mut aes_key.round_keys) };
+            }
+            len => {
+                return Err(format!(
+                    "Invalid AES key length {} (expected 16 or 32)",
+                    len
+                ))
+            }
+        }
+
+        Ok(aes_key)
+    }
+
+    /// Разбирает ключ в шестнадцатеричной записи
+    pub fn from_hex(hex: &str) -> Result<Self, String> {
+        if !hex.is_ascii() || !hex.len().is_multiple_of(2) {
+            return Err("AES key is not valid hex".to_string());
+        }
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| "AES key is not valid hex".to_string())?;
+
+        Self::new(&bytes)
+    }
+
+    /// Длина ключа в битах
+    pub fn bits(&self) -> usize {
+        if self.rounds == 14 {
+            256
+        } else {
+            128
+        }
+    }
+
+    /// Применяет AES-CTR к `data` на месте (шифрование и расшифровка совпадают)
+    ///
+    /// Счетчик - весь 128-битный блок `iv`, увеличиваемый как big-endian число
+    /// (как в OpenSSL aes-*-ctr).
+    #[inline]
+    pub fn apply_ctr(&self, iv: &[u8; AES_BLOCK_LEN], data: &mut [u8]) {
+        unsafe { aesni::ctr(&self.round_keys[..=self.rounds], iv, data) }
+    }
+}
+
+impl fmt::Debug for AesKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Раундовые ключи не выводятся
+        write!(f, "AesKey(aes-{})", self.bits())
+    }
+}
+
+fn aes_ni_available() -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        is_x86_feature_detected!("aes") && is_x86_feature_detected!("sse4.1")
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        false
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod aesni {
+    use std::arch::x86_64::*;
+
+    use super::AES_BLOCK_LEN;
+
+    #[inline(always)]
+    unsafe fn store(round_key: &mut [u8; AES_BLOCK_LEN], value: __m128i) {
+        _mm_storeu_si128(round_key.as_mut_ptr() as *mut __m128i, value);
+    }
+
+    /// Сдвигает слова ключа с накоплением XOR (общая часть развертки ключа)
+    #[inline(always)]
+    unsafe fn fold(mut key: __m128i) -> __m128i {
+        key = _mm_xor_si128(key, _mm_slli_si128::<4>(key));
+        key = _mm_xor_si128(key, _mm_slli_si128::<4>(key));
+        _mm_xor_si128(key, _mm_slli_si128::<4>(key))
+    }
+
+    #[target_feature(enable = "aes,sse2")]
+    pub unsafe fn expand_128(key: &[u8], out: &mut [[u8; AES_BLOCK_LEN]]) {
+        macro_rules! round {
+            ($prev:expr, $rcon:literal) => {{
+                let assist = _mm_shuffle_epi32::<0xff>(_mm_aeskeygenassist_si128::<$rcon>($prev));
+                _mm_xor_si128(fold($prev), assist)
+            }};
+        }
+
+        let mut k = _mm_loadu_si128(key.as_ptr() as *const __m128i);
+        store(&mut out[0], k);
+        k = round!(k, 0x01);
+        store(&mut out[1], k);
+        k = round!(k, 0x02);
+        store(&mut out[2], k);
+        k = round!(k, 0x04);
+        store(&mut out[3], k);
+        k = round!(k, 0x08);
+        store(&mut out[4], k);
+        k = round!(k, 0x10);
+        store(&mut out[5], k);
+        k = round!(k, 0x20);
+        store(&mut out[6], k);
+        k = round!(k, 0x40);
+        store(&mut out[7], k);
+        k = round!(k, 0x80);
+        store(&mut out[8], k);
+        k = round!(k, 0x1b);
+        store(&mut out[9], k);
+        k = round!(k, 0x36);
+        store(&mut out[10], k);
+    }
+
+    #[target_feature(enable = "aes,sse2")]
+    pub unsafe fn expand_256(key: &[u8], out: &mut [[u8; AES_BLOCK_LEN]]) {
+        // Четные раундовые ключи получаются из предыдущего нечетного с rcon,
+        // нечетные - из предыдущего четного через SubWord без вращения
+        macro_rules! round {
+            ($even:expr, $odd:expr, $rcon:literal) => {{
+                let assist = _mm_shuffle_epi32::<0xff>(_mm_aeskeygenassist_si128::<$rcon>($odd));
+                $even = _mm_xor_si128(fold($even), assist);
+                let assist = _mm_shuffle_epi32::<0xaa>(_mm_aeskeygenassist_si128::<0>($even));
+                _mm_xor_si128(fold($odd), assist)
+            }};
+        }
+
+        let mut k0 = _mm_loadu_si128(key.as_ptr() as *const __m128i);
+        let mut k1 = _mm_loadu_si128(key.as_ptr().add(AES_BLOCK_LEN) as *const __m128i);
+        store(&mut out[0], k0);
+        store(&mut out[1], k1);
+        k1 = round!(k0, k1, 0x01);
+        store(&mut out[2], k0);
+        store(&mut out[3], k1);
+        k1 = round!(k0, k1, 0x02);
+        store(&mut out[4], k0);
+        store(&mut out[5], k1);
+        k1 = round!(k0, k1, 0x04);
+        store(&mut out[6], k0);
+        store(&mut out[7], k1);
+        k1 = round!(k0, k1, 0x08);
+        store(&mut out[8], k0);
+        store(&mut out[9], k1);
+        k1 = round!(k0, k1, 0x10);
+        store(&mut out[10], k0);
+        store(&mut out[11], k1);
+        k1 = round!(k0, k1, 0x20);
+        store(&mut out[12], k0);
+        store(&mut out[13], k1);
+        let assist = _mm_shuffle_epi32::<0xff>(_mm_aeskeygenassist_si128::<0x40>(k1));
+        k0 = _mm_xor_si128(fold(k0), assist);
+        store(&mut out[14], k0);
+    }
+
+    /// Шифрует блок счетчика раундовыми ключами `keys`
+    #[inline(always)]
+    unsafe fn encrypt(keys: &[__m128i], block: __m128i) -> __m128i {
+        let last = keys.len() - 1;
+        let mut state = _mm_xor_si128(block, keys[0]);
+        for key in &keys[1..last] {
+            state = _mm_aesenc_si128(state, *key);
+        }
+        _mm_aesenclast_si128(state, keys[last])
+    }
+
+    #[target_feature(enable = "aes,sse2,sse4.1")]
+    pub unsafe fn ctr(
+        round_keys: &[[u8; AES_BLOCK_LEN]],
+        iv: &[u8; AES_BLOCK_LEN],
+        data: &mut [u8],
+    ) {
+        let mut keys = [_mm_setzero_si128(); super::MAX_ROUND_KEYS];
+        for (key, bytes) in keys.iter_mut().zip(round_keys) {
+            *key = _mm_loadu_si128(bytes.as_ptr() as *const __m128i);
+        }
+        let keys = &keys[..round_keys.len()];
+
+        let mut counter = u128::from_be_bytes(*iv);
+        let counter_block =
+            |counter: u128| _mm_loadu_si128(counter.to_be_bytes().as_ptr() as *const __m128i);
+
+        // Четыре блока за проход: aesenc независимых блоков выполняются конвейерно
+        let mut chunks = data.chunks_exact_mut(4 * AES_BLOCK_LEN);
+        for chunk in &mut chunks {
+            let mut stream = [
+                counter_block(counter),
+                counter_block(counter.wrapping_add(1)),
+                counter_block(counter.wrapping_add(2)),
+                counter_block(counter.wrapping_add(3)),
+            ];
+            counter = counter.wrapping_add(4);
+
+            let last = keys.len() - 1;
+            for block in &mut stream {
+                *block = _mm_xor_si128(*block, keys[0]);
+            }
+            for key in &keys[1..last] {
+                for block in &mut stream {
+                    *block = _mm_aesenc_si128(*block, *key);
+                }
+            }
+            for (i, block) in stream.iter().enumerate() {
+                let ptr = chunk.as_mut_ptr().add(i * AES_BLOCK_LEN) as *mut __m128i;
+                let keystream = _mm_aesenclast_si128(*block, keys[last]);
+                _mm_storeu_si128(ptr, _mm_xor_si128(_mm_loadu_si128(ptr), keystream));
+            }
+        }
+
+        for block in chunks.into_remainder().chunks_mut(AES_BLOCK_LEN) {
+            let mut keystream = [0u8; AES_BLOCK_LEN];
+            _mm_storeu_si128(
+                keystream.as_mut_ptr() as *mut __m128i,
+                encrypt(keys, counter_block(counter)),
+            );
+            counter = counter.wrapping_add(1);
+            for (byte, key_byte) in block.iter_mut().zip(keystream) {
+                *byte ^= key_byte;
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+mod aesni {
+    use super::AES_BLOCK_LEN;
+
+    // AesKey::new отказывает без AES-NI, поэтому эти функции не вызываются
+    pub unsafe fn expand_128(_key: &[u8], _out: &mut [[u8; AES_BLOCK_LEN]]) {}
+    pub unsafe fn expand_256(_key: &[u8], _out: &mut [[u8; AES_BLOCK_LEN]]) {}
+    pub unsafe fn ctr(_keys: &[[u8; AES_BLOCK_LEN]], _iv: &[u8; AES_BLOCK_LEN], _data: &mut [u8]) {
+        unreachable!("AES-CTR requires AES-NI")
+    }
+}
+
+/// Расположение полей шифрования в полезной нагрузке UDP
+///
+/// Полезная нагрузка до `data_offset` (заголовок пакета площадки с номером,
+/// nonce и номером ключа) не шифруется, остальное - AES-CTR. Начальный
+/// счетчик - nonce, дополненный нулями до 16 байт.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CipherLayout {
+    pub nonce_offset: usize,
+    /// Длина nonce (1..=16 байт)
+    pub nonce_len: usize,
+    /// Начало зашифрованной части
+    pub data_offset: usize,
+    /// Смещение байта с номером ключа (None - используется текущий ключ)
+    pub key_id_offset: Option<usize>,
+}
+
+impl CipherLayout {
+    /// Разбирает описание вида `nonce_offset:nonce_len:data_offset[:key=<offset>]`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut parts = spec.split(':');
+        let mut field = |name: &str| {
+            parts
+                .next()
+                .and_then(|s| s.parse::<usize>().ok())
+                .ok_or_else(|| format!("Invalid {} in cipher layout '{}'", name, spec))
+        };
+
+        let mut layout = Self {
+            nonce_offset: field("nonce offset")?,
+            nonce_len: field("nonce length")?,
+            data_offset: field("data offset")?,
+            key_id_offset: None,
+        };
+
+        for part in parts {
+            match part.split_once('=') {
+                Some(("key", offset)) => {
+                    layout.key_id_offset = Some(
+                        offset
+                            .parse()
+                            .map_err(|_| format!("Invalid key id offset '{}'", offset))?,
+                    )
+                }
+                _ => return Err(format!("Invalid cipher layout option '{}'", part)),
+            }
+        }
+
+        if layout.nonce_len == 0 || layout.nonce_len > AES_BLOCK_LEN {
+            return Err(format!(
+                "Nonce length {} out of range 1..={}",
+                layout.nonce_len, AES_BLOCK_LEN
+            ));
+        }
+
+        Ok(layout)
+    }
+
+    /// Наименьшая длина полезной нагрузки, из которой читаются все поля
+    fn header_len(&self) -> usize {
+        let key_end = self.key_id_offset.map_or(0, |offset| offset + 1);
+        (self.nonce_offset + self.nonce_len)
+            .max(key_end)
+            .max(self.data_offset)
+    }
+}
+
+/// Ключи фида по номерам (0..=255)
+///
+/// Файл ключей: строки `<key_id> <hex>`, пустые строки и `#` пропускаются.
+/// Если номер ключа в пакете не передается, используется ключ последней
+/// строки: для смены ключа в файл дописывается новая строка и ключи
+/// перечитываются (`feedkeys <feed>`).
+#[derive(Debug, Default)]
+pub struct FeedKeys {
+    keys: Vec<Option<AesKey>>,
+    current: Option<u8>,
+}
+
+impl FeedKeys {
+    /// Разбирает содержимое файла ключей
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut keys = Self {
+            keys: vec![None; 256],
+            current: None,
+        };
+
+        for (line_no, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (id, hex) = line.split_once(char::is_whitespace).ok_or_else(|| {
+                format!("Key file line {}: expected '<key_id> <hex>'", line_no + 1)
+            })?;
+            let id: u8 = id
+                .parse()
+                .map_err(|_| format!("Key file line {}: invalid key id '{}'", line_no + 1, id))?;
+            let key = AesKey::from_hex(hex.trim())
+                .map_err(|e| format!("Key file line {}: {}", line_no + 1, e))?;
+
+            keys.keys[id as usize] = Some(key);
+            keys.current = Some(id);
+        }
+
+        if keys.current.is_none() {
+            return Err("Key file contains no keys".to_string());
+        }
+
+        Ok(keys)
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read key file {}: {}", path, e))?;
+        Self::parse(&contents)
+    }
+
+    #[inline(always)]
+    fn get(&self, key_id: Option<u8>) -> Option<&AesKey> {
+        self.keys.get(key_id.or(self.current)? as usize)?.as_ref()
+    }
+
+    /// Количество загруженных ключей
+    pub fn len(&self) -> usize {
+        self.keys.iter().flatten().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.current.is_none()
+    }
+
+    /// Номер ключа для пакетов без номера ключа
+    pub fn current(&self) -> Option<u8> {
+        self.current
+    }
+}
+
+/// Счетчики расшифровки
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DecryptStats {
+    pub decrypted: u64,
+    /// Пакеты короче заголовка шифрования, отброшены
+    pub short: u64,
+    /// Пакеты с номером незагруженного ключа, отброшены
+    pub no_key: u64,
+    /// Собранные из фрагментов датаграммы (не в mbuf), отброшены
+    pub unsupported: u64,
+}
+
+impl fmt::Display for DecryptStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "decrypted={} short={} no_key={} unsupported={}",
+            self.decrypted, self.short, self.no_key, self.unsupported
+        )
+    }
+}
+
+/// Расшифровка фида, общая для рабочих потоков его очередей
+pub struct FeedDecryptor {
+    layout: CipherLayout,
+    key_file: String,
+    keys: RwLock<FeedKeys>,
+    decrypted: AtomicU64,
+    short: AtomicU64,
+    no_key: AtomicU64,
+    unsupported: AtomicU64,
+}
+
+impl FeedDecryptor {
+    /// Загружает ключи фида из `key_file`
+    pub fn new(layout: CipherLayout, key_file: &str) -> Result<Self, String> {
+        Ok(Self {
+            layout,
+            key_file: key_file.to_string(),
+            keys: RwLock::new(FeedKeys::load(key_file)?),
+            decrypted: AtomicU64::new(0),
+            short: AtomicU64::new(0),
+            no_key: AtomicU64::new(0),
+            unsupported: AtomicU64::new(0),
+        })
+    }
+
+    /// Перечитывает файл ключей; при ошибке остаются прежние ключи
+    pub fn reload_keys(&self) -> Result<String, String> {
+        let keys = FeedKeys::load(&self.key_file)?;
+        let summary = format!(
+            "{} keys loaded from {}, current key {}",
+            keys.len(),
+            self.key_file,
+            keys.current().unwrap_or_default()
+        );
+
+        *self
+            .keys
+            .write()
+            .map_err(|_| "Feed key lock poisoned".to_string())? = keys;
+        Ok(summary)
+    }
+
+    /// Расшифровывает полезную нагрузку пакета на месте; false - пакет нужно отбросить
+    ///
+    /// Данные расшифровываются прямо в mbuf без копирования. Пакеты из
+    /// цепочки mbuf и собранные датаграммы не расшифровываются.
+    ///
+    /// # Safety
+    ///
+    /// Вызывающий должен иметь исключительный доступ к данным mbuf пакета:
+    /// во время вызова нет других срезов этих данных (`PacketView::data`,
+    /// `frame`, `segments`), и mbuf не удерживается (`retain`) нигде, кроме
+    /// владельца `packet`. Поэтому у фида должен быть один расшифровывающий
+    /// обработчик.
+    #[inline]
+    pub unsafe fn decrypt(&self, packet: &PacketView) -> bool {
+        let layout = &self.layout;
+        let payload = packet.data();
+        if payload.len() < layout.header_len() {
+            self.short.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        if packet.mbuf_ptr().is_null() || packet.is_chained() {
+            // Данные собранной датаграммы принадлежат таблице сборки, а
+            // нагрузка цепочки расшифровалась бы только в первом сегменте
+            self.unsupported.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+
+        let mut iv = [0u8; AES_BLOCK_LEN];
+        iv[..layout.nonce_len]
+            .copy_from_slice(&payload[layout.nonce_offset..layout.nonce_offset + layout.nonce_len]);
+        let key_id = layout.key_id_offset.map(|offset| payload[offset]);
+
+        let Ok(keys) = self.keys.read() else {
+            return false;
+        };
+        let Some(key) = keys.get(key_id) else {
+            self.no_key.fetch_add(1, Ordering::Relaxed);
+            return false;
+        };
+
+        let len = payload.len() - layout.data_offset;
+        // Исключительный доступ к данным mbuf гарантирует вызывающий
+        let data =
+            std::slice::from_raw_parts_mut(packet.data_mut_ptr().add(layout.data_offset), len);
+        key.apply_ctr(&iv, data);
+
+        self.decrypted.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    pub fn stats(&self) -> DecryptStats {
+        DecryptStats {
+            decrypted: self.decrypted.load(Ordering::Relaxed),
+            short: self.short.load(Ordering::Relaxed),
+            no_key: self.no_key.load(Ordering::Relaxed),
+            unsupported: self.unsupported.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl StateSource for FeedDecryptor {
+    fn capture(&self, prefix: &str, state: &mut ConfigSnapshot) {
+        if let Ok(keys) = self.keys.read() {
+            state.set(&format!("{}.keys", prefix), keys.len());
+            match keys.current() {
+                Some(key_id) => state.set(&format!("{}.current_key", prefix), key_id),
+                None => state.set(&format!("{}.current_key", prefix), "none"),
+            }
+        }
+
+        let stats = self.stats();
+        state.set(&format!("{}.decrypted", prefix), stats.decrypted);
+        state.set(&format!("{}.short", prefix), stats.short);
+        state.set(&format!("{}.no_key", prefix), stats.no_key);
+        state.set(&format!("{}.unsupported", prefix), stats.unsupported);
+    }
+}
+
+/// Оборачивает обработчик фида: пакет расшифровывается на месте перед разбором
+///
+/// Пакеты, которые не удалось расшифровать, обработчику не передаются.
+/// Бюджет и переупорядочивание, стоящие перед оберткой, лишь передают пакет
+/// дальше, поэтому при расшифровке он доступен только ей (см. `FeedDecryptor::decrypt`).
+pub fn decrypting_handler(decryptor: Arc<FeedDecryptor>, inner: PacketHandler) -> PacketHandler {
+    Arc::new(move |queue_id: u16, packet: &PacketView| {
+        // До вызова `inner` пакет не передан никому, кроме этой обертки
+        if unsafe { decryptor.decrypt(packet) } {
+            inner(queue_id, packet);
+        }
+    })
+}
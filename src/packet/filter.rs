@@ -0,0 +1,297 @@
+// src/packet/filter.rs
+use std::fmt;
+use std::net::Ipv4Addr;
+
+use crate::packet::data::PacketView;
+
+const IPPROTO_TCP: u8 = 6;
+const IPPROTO_UDP: u8 = 17;
+
+/// Направление адреса или порта в выражении
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Src,
+    Dst,
+    /// Источник или назначение (по умолчанию, как в tcpdump)
+    Any,
+}
+
+/// Узел разобранного выражения
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    /// Любой IPv4 пакет (до обработчиков доходит только IPv4)
+    Ip,
+    Protocol(u8),
+    Vlan(Option<u16>),
+    /// Сеть: адрес и маска (для `host` маска /32)
+    Net(Direction, u32, u32),
+    Port(Direction, u16, u16),
+    Multicast,
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+/// Фильтр приема в синтаксисе tcpdump (pcap-filter)
+///
+/// Поддерживается подмножество для IPv4 трафика, который доходит до
+/// обработчиков: `udp`, `tcp`, `ip`, `ip proto <n>`, `vlan [id]`,
+/// `multicast`, `[src|dst] host <ip>`, `[src|dst] net <ip>/<len>`,
+/// `[src|dst] port <n>`, `[src|dst] portrange <n>-<m>`, а также `and`/`&&`,
+/// `or`/`||`, `not`/`!` и скобки. Без направления подходит и источник, и
+/// назначение. Выражение компилируется в дерево один раз и вычисляется над
+/// разобранными полями пакета без обращения к кадру.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RxFilter {
+    expression: String,
+    root: Expr,
+}
+
+impl RxFilter {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let tokens = tokenize(expression);
+        if tokens.is_empty() {
+            return Err("Empty filter expression".to_string());
+        }
+
+        let mut parser = Parser { tokens, pos: 0 };
+        let root = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("Unexpected '{}' in filter expression", token));
+        }
+
+        Ok(Self {
+            expression: expression.trim().to_string(),
+            root,
+        })
+    }
+
+    /// Исходное выражение
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    #[inline]
+    pub fn matches(&self, packet: &PacketView) -> bool {
+        eval(&self.root, packet)
+    }
+}
+
+impl fmt::Display for RxFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+fn eval(expr: &Expr, packet: &PacketView) -> bool {
+    let ip = |bytes: &[u8]| -> u32 { bytes.try_into().map(u32::from_be_bytes).unwrap_or(0) };
+
+    match expr {
+        Expr::Ip => true,
+        Expr::Protocol(proto) => packet.protocol() == *proto,
+        Expr::Vlan(id) => match packet.vlan_id() {
+            Some(vlan) => id.is_none_or(|id| id == vlan),
+            None => false,
+        },
+        Expr::Net(direction, net, mask) => {
+            let in_net = |addr: u32| addr & mask == *net;
+            match direction {
+                Direction::Src => in_net(ip(packet.source_ip())),
+                Direction::Dst => in_net(ip(packet.dest_ip())),
+                Direction::Any => in_net(ip(packet.source_ip())) || in_net(ip(packet.dest_ip())),
+            }
+        }
+        Expr::Port(direction, first, last) => {
+            let in_range = |port: u16| port >= *first && port <= *last;
+            match direction {
+                Direction::Src => in_range(packet.source_port()),
+                Direction::Dst => in_range(packet.dest_port()),
+                Direction::Any => in_range(packet.source_port()) || in_range(packet.dest_port()),
+            }
+        }
+        Expr::Multicast => ip(packet.dest_ip()) >> 28 == 0xe,
+        Expr::Not(inner) => !eval(inner, packet),
+        Expr::And(left, right) => eval(left, packet) && eval(right, packet),
+        Expr::Or(left, right) => eval(left, packet) || eval(right, packet),
+    }
+}
+
+/// Разбивает выражение на слова, скобки и операторы `!`, `&&`, `||`
+fn tokenize(expression: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut chars = expression.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let operator = match c {
+            '(' | ')' | '!' => Some(c.to_string()),
+            '&' | '|' if chars.peek() == Some(&c) => {
+                chars.next();
+                Some(format!("{}{}", c, c))
+            }
+            _ => None,
+        };
+
+        if operator.is_some() || c.is_whitespace() {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            tokens.extend(operator);
+        } else {
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+
+    tokens
+}
+
+/// Рекурсивный разбор: or < and < not < примитив
+struct Parser {
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+
+    fn next(&mut self, what: &str) -> Result<&str, String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .ok_or_else(|| format!("Filter expression ends before {}", what))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_and()?;
+        while matches!(self.peek(), Some("or" | "||")) {
+            self.pos += 1;
+            left = Expr::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut left = self.parse_not()?;
+        while matches!(self.peek(), Some("and" | "&&")) {
+            self.pos += 1;
+            left = Expr::And(Box::new(left), Box::new(self.parse_not()?));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, String> {
+        if matches!(self.peek(), Some("not" | "!")) {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primitive()
+    }
+
+    fn parse_primitive(&mut self) -> Result<Expr, String> {
+        let token = self.next("a primitive")?.to_string();
+
+        let direction = match token.as_str() {
+            "src" => Some(Direction::Src),
+            "dst" => Some(Direction::Dst),
+            _ => None,
+        };
+        if let Some(direction) = direction {
+            // `src 10.0.0.1` - то же, что `src host 10.0.0.1`
+            let qualifier = self.next("a qualifier")?.to_string();
+            return match qualifier.as_str() {
+                "host" | "net" | "port" | "portrange" => {
+                    self.parse_qualified(direction, &qualifier)
+                }
+                addr => Ok(Expr::Net(direction, parse_ip(addr)?, u32::MAX)),
+            };
+        }
+
+        match token.as_str() {
+            "(" => {
+                let inner = self.parse_or()?;
+                match self.next("')'")? {
+                    ")" => Ok(inner),
+                    other => Err(format!(
+                        "Expected ')' in filter expression, got '{}'",
+                        other
+                    )),
+                }
+            }
+            "ip" if self.peek() == Some("proto") => {
+                self.pos += 1;
+                let proto = self.next("a protocol")?;
+                match proto {
+                    "udp" => Ok(Expr::Protocol(IPPROTO_UDP)),
+                    "tcp" => Ok(Expr::Protocol(IPPROTO_TCP)),
+                    _ => proto
+                        .parse()
+                        .map(Expr::Protocol)
+                        .map_err(|_| format!("Invalid protocol '{}' in filter expression", proto)),
+                }
+            }
+            "ip" => Ok(Expr::Ip),
+            "udp" => Ok(Expr::Protocol(IPPROTO_UDP)),
+            "tcp" => Ok(Expr::Protocol(IPPROTO_TCP)),
+            "multicast" => Ok(Expr::Multicast),
+            "vlan" => match self.peek().and_then(|id| id.parse::<u16>().ok()) {
+                Some(id) if id < 4096 => {
+                    self.pos += 1;
+                    Ok(Expr::Vlan(Some(id)))
+                }
+                Some(id) => Err(format!("Invalid VLAN id {} in filter expression", id)),
+                None => Ok(Expr::Vlan(None)),
+            },
+            "host" | "net" | "port" | "portrange" => self.parse_qualified(Direction::Any, &token),
+            other => Err(format!("Unknown filter primitive '{}'", other)),
+        }
+    }
+
+    fn parse_qualified(&mut self, direction: Direction, qualifier: &str) -> Result<Expr, String> {
+        let value = self.next(qualifier)?.to_string();
+
+        match qualifier {
+            "host" => Ok(Expr::Net(direction, parse_ip(&value)?, u32::MAX)),
+            "net" => {
+                let (addr, len) = value.split_once('/').unwrap_or((&value, "32"));
+                let len: u32 = len
+                    .parse()
+                    .ok()
+                    .filter(|len| *len <= 32)
+                    .ok_or_else(|| format!("Invalid prefix length in '{}'", value))?;
+                let mask = u32::MAX.checked_shl(32 - len).unwrap_or(0);
+                Ok(Expr::Net(direction, parse_ip(addr)? & mask, mask))
+            }
+            "port" => {
+                let port = parse_port(&value)?;
+                Ok(Expr::Port(direction, port, port))
+            }
+            _ => {
+                let (first, last) = value
+                    .split_once('-')
+                    .ok_or_else(|| format!("Invalid port range '{}'", value))?;
+                let (first, last) = (parse_port(first)?, parse_port(last)?);
+                Ok(Expr::Port(direction, first.min(last), first.max(last)))
+            }
+        }
+    }
+}
+
+fn parse_ip(value: &str) -> Result<u32, String> {
+    value
+        .parse::<Ipv4Addr>()
+        .map(u32::from)
+        .map_err(|_| format!("Invalid IPv4 address '{}' in filter expression", value))
+}
+
+fn parse_port(value: &str) -> Result<u16, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid port '{}' in filter expression", value))
+}
@@ -0,0 +1,345 @@
+// src/packet/gro.rs
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::control::snapshot::ConfigSnapshot;
+use crate::control::state::StateSource;
+use crate::packet::data::{PacketData, PacketView};
+use crate::packet::handler::PacketHandler;
+use crate::packet::parser::{parse_l2, ETHER_TYPE_IPV4};
+
+const IPPROTO_TCP: u8 = 6;
+
+const TCP_FLAG_FIN: u8 = 0x01;
+const TCP_FLAG_SYN: u8 = 0x02;
+const TCP_FLAG_RST: u8 = 0x04;
+const TCP_FLAG_PSH: u8 = 0x08;
+const TCP_FLAG_URG: u8 = 0x20;
+
+/// Параметры объединения сегментов TCP
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GroConfig {
+    /// Наибольший размер объединенных данных потока
+    pub max_bytes: usize,
+    /// Наибольшее удержание данных потока
+    pub max_hold: Duration,
+    /// Наибольшее число одновременно объединяемых потоков
+    pub max_flows: usize,
+}
+
+impl GroConfig {
+    pub fn new(max_bytes: usize, max_hold: Duration) -> Self {
+        Self {
+            max_bytes,
+            max_hold,
+            max_flows: 64,
+        }
+    }
+
+    /// Разбирает `[<max_bytes>[,<max_hold_us>]]` (по умолчанию 65535 байт, 100 мкс)
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut parts = spec.split(',').map(str::trim);
+        let max_bytes: usize = match parts.next() {
+            Some("") | None => 65535,
+            Some(s) => s.parse().map_err(|_| format!("Invalid GRO size '{}'", s))?,
+        };
+        let max_hold_us: u64 = match parts.next() {
+            Some(s) => s
+                .parse()
+                .map_err(|_| format!("Invalid GRO hold time '{}'", s))?,
+            None => 100,
+        };
+
+        if max_bytes == 0 {
+            return Err("GRO size must be positive".to_string());
+        }
+
+        Ok(Self::new(max_bytes, Duration::from_micros(max_hold_us)))
+    }
+}
+
+/// Счетчики объединения
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GroStats {
+    /// Принятые сегменты TCP с данными
+    pub segments: u64,
+    /// Сегменты, присоединенные к данным предыдущих
+    pub merged: u64,
+    /// Вызовы обработчика с объединенными данными
+    pub delivered: u64,
+    /// Сегменты, переданные без объединения (нет кадра, флаги, нет места в таблице)
+    pub passthrough: u64,
+}
+
+impl fmt::Display for GroStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "segments={} merged={} delivered={} passthrough={}",
+            self.segments, self.merged, self.delivered, self.passthrough
+        )
+    }
+}
+
+/// Поток TCP: адреса, порты и порт приема
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct FlowKey {
+    src_ip: [u8; 4],
+    dst_ip: [u8; 4],
+    src_port: u16,
+    dst_port: u16,
+    port_id: u16,
+}
+
+/// Накопленные данные потока
+struct GroFlow {
+    key: FlowKey,
+    queue_id: u16,
+    vlan_tci: Option<u16>,
+    /// Номер последовательности, ожидаемый следующим
+    next_seq: u32,
+    /// Время приема первого сегмента
+    first_rx_ns: u64,
+    data: Vec<u8>,
+}
+
+/// Таблица объединяемых потоков
+struct GroTable {
+    config: GroConfig,
+    flows: HashMap<FlowKey, GroFlow>,
+    /// Время приема самого старого сегмента в таблице
+    oldest_ns: u64,
+    stats: GroStats,
+}
+
+impl GroTable {
+    /// Принимает пакет; объединенные данные и необъединяемые пакеты передаются в `deliver`
+    fn offer(&mut self, packet: &PacketView, deliver: &mut dyn FnMut(&PacketView)) {
+        let now_ns = packet.rx_timestamp_ns();
+        if !self.flows.is_empty()
+            && now_ns.saturating_sub(self.oldest_ns) >= self.config.max_hold.as_nanos() as u64
+        {
+            self.expire(now_ns, deliver);
+        }
+
+        let payload = packet.data();
+        let (Some((seq, flags)), Ok(src_ip), Ok(dst_ip)) = (
+            tcp_header(packet),
+            <[u8; 4]>::try_from(packet.source_ip()),
+            <[u8; 4]>::try_from(packet.dest_ip()),
+        ) else {
+            self.stats.passthrough += 1;
+            deliver(packet);
+            return;
+        };
+        self.stats.segments += 1;
+
+        let key = FlowKey {
+            src_ip,
+            dst_ip,
+            src_port: packet.source_port(),
+            dst_port: packet.dest_port(),
+            port_id: packet.port_id(),
+        };
+
+        // Управляющие сегменты доставляются после накопленных данных потока
+        if flags & (TCP_FLAG_SYN | TCP_FLAG_RST | TCP_FLAG_URG) != 0 {
+            self.flush(&key, deliver);
+            self.stats.passthrough += 1;
+            deliver(packet);
+            return;
+        }
+
+        let appended = match self.flows.get_mut(&key) {
+            Some(flow)
+                if flow.next_seq == seq
+                    && flow.data.len() + payload.len() <= self.config.max_bytes =>
+            {
+                flow.data.extend_from_slice(payload);
+                flow.next_seq = seq.wrapping_add(payload.len() as u32);
+                self.stats.merged += 1;
+                true
+            }
+            Some(_) => {
+                // Пропуск, повтор или переполнение: накопленное уходит, сегмент начинает новые данные
+                self.flush(&key, deliver);
+                false
+            }
+            None => false,
+        };
+
+        if !appended {
+            // Одиночный сегмент с PSH или FIN доставляется без копирования
+            if flags & (TCP_FLAG_PSH | TCP_FLAG_FIN) != 0
+                || self.flows.len() >= self.config.max_flows
+            {
+                self.stats.passthrough += 1;
+                deliver(packet);
+                return;
+            }
+            if self.flows.is_empty() {
+                self.oldest_ns = now_ns;
+            }
+            let mut data = Vec::with_capacity(self.config.max_bytes);
+            data.extend_from_slice(payload);
+            self.flows.insert(
+                key,
+                GroFlow {
+                    key,
+                    queue_id: packet.queue_id(),
+                    vlan_tci: packet
+                        .vlan_id()
+                        .map(|id| id | (packet.vlan_priority().unwrap_or(0) as u16) << 13),
+                    next_seq: seq.wrapping_add(payload.len() as u32),
+                    first_rx_ns: now_ns,
+                    data,
+                },
+            );
+            return;
+        }
+
+        // Отправитель закончил запись: данные не ждут следующего сегмента
+        if flags & (TCP_FLAG_PSH | TCP_FLAG_FIN) != 0 {
+            self.flush(&key, deliver);
+        }
+    }
+
+    /// Доставляет накопленные данные потока
+    fn flush(&mut self, key: &FlowKey, deliver: &mut dyn FnMut(&PacketView)) {
+        if let Some(flow) = self.flows.remove(key) {
+            self.stats.delivered += 1;
+            deliver_flow(&flow, deliver);
+        }
+    }
+
+    /// Доставляет данные потоков, удерживаемые дольше `max_hold`
+    fn expire(&mut self, now_ns: u64, deliver: &mut dyn FnMut(&PacketView)) {
+        let max_hold_ns = self.config.max_hold.as_nanos() as u64;
+        let expired: Vec<FlowKey> = self
+            .flows
+            .values()
+            .filter(|flow| now_ns.saturating_sub(flow.first_rx_ns) >= max_hold_ns)
+            .map(|flow| flow.key)
+            .collect();
+        for key in &expired {
+            self.flush(key, deliver);
+        }
+
+        self.oldest_ns = self
+            .flows
+            .values()
+            .map(|flow| flow.first_rx_ns)
+            .min()
+            .unwrap_or(now_ns);
+    }
+}
+
+/// Передает объединенные данные потока обработчику как один пакет
+///
+/// Как и у собранной из фрагментов датаграммы, у пакета нет mbuf и кадра.
+fn deliver_flow(flow: &GroFlow, deliver: &mut dyn FnMut(&PacketView)) {
+    let mut packet = PacketData::new();
+    packet.source_port = flow.key.src_port;
+    packet.dest_port = flow.key.dst_port;
+    packet.queue_id = flow.queue_id;
+    packet.port_id = flow.key.port_id;
+    packet.protocol = IPPROTO_TCP;
+    packet.ether_type = ETHER_TYPE_IPV4;
+    packet.source_ip_ptr = flow.key.src_ip.as_ptr();
+    packet.source_ip_len = flow.key.src_ip.len();
+    packet.dest_ip_ptr = flow.key.dst_ip.as_ptr();
+    packet.dest_ip_len = flow.key.dst_ip.len();
+    packet.data_ptr = flow.data.as_ptr();
+    packet.data_len = flow.data.len();
+    packet.vlan_tci = flow.vlan_tci.unwrap_or(0);
+    packet.has_vlan = flow.vlan_tci.is_some();
+    packet.rx_timestamp_ns = flow.first_rx_ns;
+
+    deliver(&PacketView::new(&packet));
+}
+
+/// Номер последовательности и флаги TCP из кадра пакета
+///
+/// None для пакетов без кадра, не TCP, без данных, для туннельных пакетов
+/// (заголовок TCP не прилегает к данным внешнего кадра) и для цепочек mbuf
+/// (их уже объединил NIC).
+fn tcp_header(packet: &PacketView) -> Option<(u32, u8)> {
+    if packet.protocol() != IPPROTO_TCP || packet.data().is_empty() || packet.is_chained() {
+        return None;
+    }
+    let frame = packet.frame()?;
+    let (_, _, l3) = parse_l2(frame).ok()?;
+    let l4 = l3 + ((*frame.get(l3)? & 0x0f) as usize) * 4;
+    let header = frame.get(l4..l4 + 20)?;
+    if l4 + ((header[12] >> 4) as usize) * 4 != frame.len() - packet.data().len() {
+        return None;
+    }
+
+    let seq = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+    Some((seq, header[13]))
+}
+
+/// Программный GRO для потоков TCP (загрузка снимков, восстановление)
+///
+/// Сегменты потока, идущие подряд по номеру последовательности,
+/// копируются в общий буфер и передаются обработчику одним вызовом, когда
+/// отправитель ставит PSH или FIN, буфер заполнен, приходит сегмент не по
+/// порядку или данные ждут дольше `max_hold`. Время проверяется по приходу
+/// следующего пакета, как в `ReorderBuffer`. Повторы и пропуски не
+/// исправляются: это дело сборки потока после объединения. Пакеты UDP и
+/// управляющие сегменты (SYN, RST, URG) проходят без изменений.
+pub struct TcpGro {
+    table: Mutex<GroTable>,
+}
+
+impl TcpGro {
+    pub fn new(config: GroConfig) -> Self {
+        Self {
+            table: Mutex::new(GroTable {
+                config,
+                flows: HashMap::new(),
+                oldest_ns: 0,
+                stats: GroStats::default(),
+            }),
+        }
+    }
+
+    pub fn stats(&self) -> GroStats {
+        self.table
+            .lock()
+            .map(|table| table.stats)
+            .unwrap_or_default()
+    }
+}
+
+impl StateSource for TcpGro {
+    fn capture(&self, prefix: &str, state: &mut ConfigSnapshot) {
+        let Ok(table) = self.table.lock() else {
+            return;
+        };
+
+        state.set(&format!("{}.flows", prefix), table.flows.len());
+        state.set(&format!("{}.segments", prefix), table.stats.segments);
+        state.set(&format!("{}.merged", prefix), table.stats.merged);
+        state.set(&format!("{}.delivered", prefix), table.stats.delivered);
+    }
+}
+
+/// Оборачивает обработчик: сегменты TCP объединяются перед обработкой
+pub fn gro_handler(gro: Arc<TcpGro>, inner: PacketHandler) -> PacketHandler {
+    Arc::new(move |queue_id: u16, packet: &PacketView| {
+        if packet.protocol() != IPPROTO_TCP {
+            inner(queue_id, packet);
+            return;
+        }
+
+        let Ok(mut table) = gro.table.lock() else {
+            return;
+        };
+        table.offer(packet, &mut |merged: &PacketView| {
+            inner(merged.queue_id(), merged)
+        });
+    })
+}
@@ -0,0 +1,136 @@
+// src/packet/gso.rs
+use crate::packet::parser::{parse_frame, ParsedPacket};
+
+const IPPROTO_TCP: u8 = 6;
+const IPPROTO_UDP: u8 = 17;
+
+const TCP_FLAG_FIN: u8 = 0x01;
+const TCP_FLAG_PSH: u8 = 0x08;
+const TCP_FLAG_CWR: u8 = 0x80;
+
+/// Разбирает кадр для сегментации: Ethernet/IPv4 с TCP или UDP без туннеля
+pub fn parse_segmentable(frame: &[u8]) -> Result<ParsedPacket, String> {
+    let parsed =
+        parse_frame(frame, 0, 0).map_err(|e| format!("Frame cannot be segmented: {:?}", e))?;
+    match parsed.protocol {
+        IPPROTO_TCP | IPPROTO_UDP => Ok(parsed),
+        other => Err(format!("Frame cannot be segmented: protocol {}", other)),
+    }
+}
+
+/// Программная сегментация (GSO) кадра TCP или UDP по `segment_size` байт данных
+///
+/// TCP делится как при TSO: номер последовательности сдвигается, FIN и PSH
+/// остаются только у последнего сегмента, CWR - только у первого. UDP
+/// делится как при UDP GSO (UFO в DPDK): каждый сегмент - отдельная
+/// датаграмма со своим заголовком UDP, а не IP фрагмент. Идентификатор IP
+/// увеличивается на сегмент, контрольные суммы IPv4 и L4 пересчитываются
+/// (нулевая сумма UDP остается нулевой).
+///
+/// Сегменты по очереди передаются `emit` через общий буфер; Ok(false) из
+/// `emit` останавливает сегментацию (например, очередь TX заполнена).
+/// Возвращает число принятых сегментов.
+pub fn segment<F>(frame: &[u8], segment_size: usize, mut emit: F) -> Result<usize, String>
+where
+    F: FnMut(&[u8]) -> Result<bool, String>,
+{
+    if segment_size == 0 {
+        return Err("Segment size must be positive".to_string());
+    }
+    let parsed = parse_segmentable(frame)?;
+
+    let l3 = parsed.l3_offset;
+    let l4 = l3 + ((frame[l3] & 0x0f) as usize) * 4;
+    let headers = &frame[..parsed.payload_offset];
+    let payload = &frame[parsed.payload_offset..parsed.payload_offset + parsed.payload_len];
+    let ip_id = read_u16(frame, l3 + 4);
+    let tcp_seq = read_u32(frame, l4 + 4);
+    let udp_checksum = parsed.protocol == IPPROTO_UDP && read_u16(frame, l4 + 6) != 0;
+
+    let mut buf = Vec::with_capacity(headers.len() + segment_size);
+    let count = payload.len().div_ceil(segment_size);
+    let mut emitted = 0;
+
+    for (index, chunk) in payload.chunks(segment_size).enumerate() {
+        buf.clear();
+        buf.extend_from_slice(headers);
+        buf.extend_from_slice(chunk);
+
+        let ip_total_len = (buf.len() - l3) as u16;
+        buf[l3 + 2..l3 + 4].copy_from_slice(&ip_total_len.to_be_bytes());
+        buf[l3 + 4..l3 + 6].copy_from_slice(&ip_id.wrapping_add(index as u16).to_be_bytes());
+        buf[l3 + 10..l3 + 12].fill(0);
+        let ip_checksum = fold(sum_words(&buf[l3..l4]));
+        buf[l3 + 10..l3 + 12].copy_from_slice(&ip_checksum.to_be_bytes());
+
+        let l4_len = buf.len() - l4;
+        let checksum_offset = match parsed.protocol {
+            IPPROTO_TCP => {
+                let seq = tcp_seq.wrapping_add((index * segment_size) as u32);
+                buf[l4 + 4..l4 + 8].copy_from_slice(&seq.to_be_bytes());
+                if index + 1 < count {
+                    buf[l4 + 13] &= !(TCP_FLAG_FIN | TCP_FLAG_PSH);
+                }
+                if index > 0 {
+                    buf[l4 + 13] &= !TCP_FLAG_CWR;
+                }
+                Some(l4 + 16)
+            }
+            _ => {
+                buf[l4 + 4..l4 + 6].copy_from_slice(&(l4_len as u16).to_be_bytes());
+                udp_checksum.then_some(l4 + 6)
+            }
+        };
+
+        if let Some(offset) = checksum_offset {
+            buf[offset..offset + 2].fill(0);
+            let mut pseudo = [0u8; 12];
+            pseudo[..8].copy_from_slice(&buf[l3 + 12..l3 + 20]);
+            pseudo[9] = parsed.protocol;
+            pseudo[10..].copy_from_slice(&(l4_len as u16).to_be_bytes());
+            let mut sum = fold(sum_words(&pseudo) + sum_words(&buf[l4..]));
+            // Нулевая сумма UDP означает ее отсутствие (RFC 768)
+            if sum == 0 && parsed.protocol == IPPROTO_UDP {
+                sum = 0xffff;
+            }
+            buf[offset..offset + 2].copy_from_slice(&sum.to_be_bytes());
+        }
+
+        if !emit(&buf)? {
+            break;
+        }
+        emitted += 1;
+    }
+
+    Ok(emitted)
+}
+
+#[inline(always)]
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([data[offset], data[offset + 1]])
+}
+
+#[inline(always)]
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
+
+/// Сумма 16-битных слов для контрольной суммы Интернета (RFC 1071)
+fn sum_words(data: &[u8]) -> u32 {
+    data.chunks(2)
+        .map(|word| u16::from_be_bytes([word[0], *word.get(1).unwrap_or(&0)]) as u32)
+        .sum()
+}
+
+/// Сворачивает сумму слов в контрольную сумму
+fn fold(mut sum: u32) -> u16 {
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
@@ -0,0 +1,372 @@
+// src/packet/handler.rs
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use crate::metrics::counters::WorkerMetrics;
+use crate::packet::data::{PacketData, PacketView};
+use crate::packet::filter::RxFilter;
+use crate::time;
+
+const IPPROTO_UDP: u8 = 17;
+
+/// Тип обработчика пакетов
+///
+/// Пакет действителен только во время вызова; для обработки в другом потоке
+/// его нужно удержать (`PacketView::retain`).
+pub type PacketHandler = Arc<dyn Fn(u16, &PacketView) + Send + Sync + 'static>;
+
+/// Класс потока данных (например, конкретный multicast фид рыночных данных)
+///
+/// Пакет относится к классу, если это UDP пакет, его порт назначения лежит в
+/// диапазоне `dst_ports` и (если заданы) совпадают IP назначения и порт DPDK.
+/// TCP сегменты с тем же портом назначения классу не принадлежат.
+#[derive(Debug, Clone)]
+pub struct FeedClass {
+    pub name: String,
+    pub dst_ports: (u16, u16),
+    pub dst_ip: Option<[u8; 4]>,
+    pub port_id: Option<u16>,
+}
+
+impl FeedClass {
+    /// Класс по диапазону UDP портов назначения (включительно)
+    pub fn new(name: &str, first_port: u16, last_port: u16) -> Self {
+        Self {
+            name: name.to_string(),
+            dst_ports: (first_port.min(last_port), first_port.max(last_port)),
+            dst_ip: None,
+            port_id: None,
+        }
+    }
+
+    /// Класс по диапазону `<udp_port>[-<udp_port>]`
+    pub fn parse(name: &str, ports: &str) -> Result<Self, String> {
+        let (first, last) = ports.split_once('-').unwrap_or((ports, ports));
+        let port = |s: &str| {
+            s.trim()
+                .parse::<u16>()
+                .map_err(|_| format!("Invalid UDP port '{}'", s))
+        };
+
+        Ok(Self::new(name, port(first)?, port(last)?))
+    }
+
+    /// Ограничивает класс IP адресом назначения (multicast группой)
+    pub fn with_dst_ip(mut self, ip: [u8; 4]) -> Self {
+        self.dst_ip = Some(ip);
+        self
+    }
+
+    /// Ограничивает класс одним портом DPDK
+    pub fn with_port_id(mut self, port_id: u16) -> Self {
+        self.port_id = Some(port_id);
+        self
+    }
+
+    #[inline(always)]
+    pub(crate) fn matches(&self, packet: &PacketView) -> bool {
+        let (first, last) = self.dst_ports;
+        if packet.protocol() != IPPROTO_UDP
+            || packet.dest_port() < first
+            || packet.dest_port() > last
+        {
+            return false;
+        }
+
+        match self.dst_ip {
+            Some(ip) => packet.dest_ip() == ip,
+            None => true,
+        }
+    }
+}
+
+/// Реестр обработчиков пакетов
+///
+/// Порядок выбора обработчика: класс фида, затем очередь, затем порт, затем
+/// обработчик по умолчанию. Пакеты, для которых обработчик не найден,
+/// отбрасываются. Фильтр приема порта проверяется до выбора обработчика.
+#[derive(Clone, Default)]
+pub struct HandlerRegistry {
+    default: Option<PacketHandler>,
+    by_port: HashMap<u16, PacketHandler>,
+    by_queue: HashMap<(u16, u16), PacketHandler>,
+    feed_classes: Vec<(FeedClass, PacketHandler)>,
+    /// Именованные обработчики, на которые можно ссылаться во время работы
+    named: HashMap<String, PacketHandler>,
+    /// Фильтры приема портов (пакеты, не прошедшие фильтр, не доходят до обработчиков)
+    port_filters: HashMap<u16, Arc<RxFilter>>,
+}
+
+impl HandlerRegistry {
+    /// Создает реестр с обработчиком по умолчанию
+    pub fn new(default: PacketHandler) -> Self {
+        Self {
+            default: Some(default),
+            ..Self::default()
+        }
+    }
+
+    /// Создает реестр без обработчика по умолчанию
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Назначает обработчик всем очередям порта
+    pub fn with_port(mut self, port_id: u16, handler: PacketHandler) -> Self {
+        self.by_port.insert(port_id, handler);
+        self
+    }
+
+    /// Назначает обработчик одной очереди порта
+    pub fn with_queue(mut self, port_id: u16, queue_id: u16, handler: PacketHandler) -> Self {
+        self.by_queue.insert((port_id, queue_id), handler);
+        self
+    }
+
+    /// Назначает обработчик классу фида (классы проверяются в порядке добавления)
+    pub fn with_feed_class(mut self, class: FeedClass, handler: PacketHandler) -> Self {
+        self.feed_classes.push((class, handler));
+        self
+    }
+
+    /// Задает фильтр приема порта
+    pub fn with_port_filter(mut self, port_id: u16, filter: RxFilter) -> Self {
+        self.port_filters.insert(port_id, Arc::new(filter));
+        self
+    }
+
+    /// Заменяет или снимает (None) фильтр приема порта
+    pub fn set_port_filter(&mut self, port_id: u16, filter: Option<RxFilter>) {
+        match filter {
+            Some(filter) => self.port_filters.insert(port_id, Arc::new(filter)),
+            None => self.port_filters.remove(&port_id),
+        };
+    }
+
+    /// Фильтр приема порта
+    pub fn port_filter(&self, port_id: u16) -> Option<&RxFilter> {
+        self.port_filters
+            .get(&port_id)
+            .map(|filter| filter.as_ref())
+    }
+
+    /// Регистрирует именованный обработчик для последующего добавления фидов
+    pub fn with_named_handler(mut self, name: &str, handler: PacketHandler) -> Self {
+        self.named.insert(name.to_string(), handler);
+        self
+    }
+
+    /// Добавляет класс фида с именованным обработчиком
+    pub fn add_feed_class(&mut self, class: FeedClass, handler_name: &str) -> Result<(), String> {
+        let handler = self
+            .named
+            .get(handler_name)
+            .cloned()
+            .ok_or_else(|| format!("Unknown handler: {}", handler_name))?;
+
+        self.feed_classes.retain(|(c, _)| c.name != class.name);
+        self.feed_classes.push((class, handler));
+        Ok(())
+    }
+
+    /// Имена зарегистрированных классов фидов
+    pub fn feed_class_names(&self) -> Vec<&str> {
+        self.feed_classes
+            .iter()
+            .map(|(c, _)| c.name.as_str())
+            .collect()
+    }
+
+    /// Порты, для которых зарегистрированы обработчики
+    pub fn routed_ports(&self) -> Vec<u16> {
+        let mut ports: Vec<u16> = self
+            .by_port
+            .keys()
+            .copied()
+            .chain(self.by_queue.keys().map(|&(port_id, _)| port_id))
+            .chain(self.feed_classes.iter().filter_map(|(c, _)| c.port_id))
+            .collect();
+        ports.sort_unstable();
+        ports.dedup();
+        ports
+    }
+
+    /// Собирает таблицу диспетчеризации для очереди рабочего потока
+    pub fn resolve(&self, port_id: u16, queue_id: u16) -> QueueDispatch {
+        let feed_classes = self
+            .feed_classes
+            .iter()
+            .filter(|(class, _)| class.port_id.is_none_or(|id| id == port_id))
+            .cloned()
+            .collect();
+
+        let fallback = self
+            .by_queue
+            .get(&(port_id, queue_id))
+            .or_else(|| self.by_port.get(&port_id))
+            .or(self.default.as_ref())
+            .cloned();
+
+        QueueDispatch {
+            filter: self.port_filters.get(&port_id).cloned(),
+            feed_classes,
+            fallback,
+        }
+    }
+}
+
+impl From<PacketHandler> for HandlerRegistry {
+    fn from(handler: PacketHandler) -> Self {
+        Self::new(handler)
+    }
+}
+
+/// Реестр обработчиков, изменяемый во время работы
+///
+/// Рабочие потоки сравнивают номер поколения раз в пачку и заново
+/// разрешают свою таблицу диспетчеризации только после изменения реестра.
+pub struct SharedHandlers {
+    registry: RwLock<HandlerRegistry>,
+    generation: AtomicU64,
+}
+
+impl SharedHandlers {
+    pub fn new(registry: HandlerRegistry) -> Self {
+        Self {
+            registry: RwLock::new(registry),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Номер поколения реестра (увеличивается при каждом изменении)
+    #[inline(always)]
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Разрешает таблицу диспетчеризации очереди по текущему реестру
+    pub fn resolve(&self, port_id: u16, queue_id: u16) -> QueueDispatch {
+        match self.registry.read() {
+            Ok(registry) => registry.resolve(port_id, queue_id),
+            Err(_) => QueueDispatch {
+                filter: None,
+                feed_classes: Vec::new(),
+                fallback: None,
+            },
+        }
+    }
+
+    /// Изменяет реестр и публикует новое поколение
+    pub fn update<R>(
+        &self,
+        f: impl FnOnce(&mut HandlerRegistry) -> Result<R, String>,
+    ) -> Result<R, String> {
+        let mut registry = self
+            .registry
+            .write()
+            .map_err(|_| "Handler registry lock poisoned".to_string())?;
+        let result = f(&mut registry)?;
+        self.generation.fetch_add(1, Ordering::Release);
+        Ok(result)
+    }
+
+    /// Выполняет чтение реестра
+    pub fn read<R>(&self, f: impl FnOnce(&HandlerRegistry) -> R) -> Option<R> {
+        self.registry.read().ok().map(|registry| f(&registry))
+    }
+}
+
+/// Таблица диспетчеризации одной очереди (разрешается при запуске рабочего потока)
+pub struct QueueDispatch {
+    filter: Option<Arc<RxFilter>>,
+    feed_classes: Vec<(FeedClass, PacketHandler)>,
+    fallback: Option<PacketHandler>,
+}
+
+impl QueueDispatch {
+    /// Есть ли хотя бы один обработчик для очереди
+    pub fn is_routed(&self) -> bool {
+        self.fallback.is_some() || !self.feed_classes.is_empty()
+    }
+
+    /// Передает пакет подходящему обработчику
+    ///
+    /// Возвращает false, если обработчик не найден и пакет отброшен.
+    #[inline(always)]
+    pub fn dispatch(&self, queue_id: u16, packet: &PacketView) -> bool {
+        for (class, handler) in &self.feed_classes {
+            if class.matches(packet) {
+                handler(queue_id, packet);
+                return true;
+            }
+        }
+
+        match &self.fallback {
+            Some(handler) => {
+                handler(queue_id, packet);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Передает пакет обработчику, записывая задержку до вызова и время его выполнения
+    #[inline(always)]
+    pub fn dispatch_timed(&self, metrics: &WorkerMetrics, queue_id: u16, packet: &PacketData) {
+        if let Some(filter) = &self.filter {
+            if !filter.matches(&PacketView::new(packet)) {
+                metrics.counters.filtered.inc();
+                return;
+            }
+        }
+
+        let start_ns = time::now_ns();
+        metrics
+            .latency
+            .wire_to_handler
+            .record(start_ns.saturating_sub(packet.rx_timestamp_ns));
+
+        if !self.dispatch(queue_id, &PacketView::new(packet)) {
+            metrics.counters.dropped.inc();
+            return;
+        }
+
+        let elapsed_ns = time::now_ns().saturating_sub(start_ns);
+        metrics.latency.handler.record(elapsed_ns);
+        metrics.counters.handler_time_ns.add(elapsed_ns);
+        metrics.counters.processed.inc();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet(protocol: u8, dest_port: u16, dest_ip: &[u8; 4]) -> PacketData {
+        let mut raw = PacketData::new();
+        raw.protocol = protocol;
+        raw.dest_port = dest_port;
+        raw.dest_ip_ptr = dest_ip.as_ptr();
+        raw.dest_ip_len = dest_ip.len();
+        raw
+    }
+
+    #[test]
+    fn feed_class_matches_udp_ports_only() {
+        let group = [239, 1, 1, 1];
+        let class = FeedClass::new("md", 30000, 30010).with_dst_ip(group);
+
+        let udp = packet(IPPROTO_UDP, 30005, &group);
+        assert!(class.matches(&PacketView::new(&udp)));
+
+        let tcp = packet(6, 30005, &group);
+        assert!(!class.matches(&PacketView::new(&tcp)));
+
+        let outside = packet(IPPROTO_UDP, 30011, &group);
+        assert!(!class.matches(&PacketView::new(&outside)));
+
+        let other_group = packet(IPPROTO_UDP, 30005, &[239, 1, 1, 2]);
+        assert!(!class.matches(&PacketView::new(&other_group)));
+    }
+}
@@ -1,2 +1,14 @@
+pub mod arbitration;
+pub mod breaker;
+pub mod budget;
 pub mod data;
+pub mod decrypt;
+pub mod filter;
+pub mod gro;
+pub mod gso;
+pub mod handler;
+pub mod parser;
 pub mod pool;
+pub mod reassembly;
+pub mod reorder;
+pub mod tap;
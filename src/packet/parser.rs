@@ -0,0 +1,604 @@
+// src/packet/parser.rs
+// Разбор заголовков Ethernet/IPv4/TCP/UDP непосредственно над данными mbuf
+//
+// Заменяет C-функцию dpdk_extract_packet_data: разбор выполняется без FFI
+// вызова на каждый пакет и может встраиваться в цикл рабочего потока.
+
+/// Флаг декапсуляции VXLAN
+pub const EXTRACT_DECAP_VXLAN: u32 = 0x1;
+/// Флаг декапсуляции GRE
+pub const EXTRACT_DECAP_GRE: u32 = 0x2;
+
+/// Стандартный UDP порт VXLAN (IANA)
+pub const VXLAN_DEFAULT_PORT: u16 = 4789;
+
+pub const ETH_HDR_LEN: usize = 14;
+const IPV4_MIN_HDR_LEN: usize = 20;
+const UDP_HDR_LEN: usize = 8;
+const TCP_MIN_HDR_LEN: usize = 20;
+const VXLAN_HDR_LEN: usize = 8;
+
+const VLAN_TAG_LEN: usize = 4;
+
+pub const ETHER_TYPE_IPV4: u16 = 0x0800;
+pub const ETHER_TYPE_VLAN: u16 = 0x8100;
+pub const ETHER_TYPE_QINQ: u16 = 0x88a8;
+const GRE_PROTO_TEB: u16 = 0x6558;
+
+const IPPROTO_TCP: u8 = 6;
+const IPPROTO_UDP: u8 = 17;
+const IPPROTO_GRE: u8 = 47;
+
+const IPV4_MF_FLAG: u16 = 0x2000;
+const IPV4_OFFSET_MASK: u16 = 0x1fff;
+
+/// Результат разбора: смещения внутри кадра и порты
+///
+/// Смещения относятся к началу кадра, поэтому результат не удерживает
+/// заимствование данных и легко переносится в `PacketData`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParsedPacket {
+    /// EtherType после VLAN тегов внешнего кадра
+    pub ether_type: u16,
+    /// TCI самого внутреннего VLAN тега внешнего кадра (если тег есть)
+    pub vlan_tci: Option<u16>,
+    /// Смещение IPv4 заголовка (внутреннего, если пакет был декапсулирован)
+    pub l3_offset: usize,
+    pub src_port: u16,
+    pub dst_port: u16,
+    pub protocol: u8,
+    pub payload_offset: usize,
+    pub payload_len: usize,
+    /// Пакет был извлечен из VXLAN/GRE туннеля
+    pub decapsulated: bool,
+}
+
+impl ParsedPacket {
+    /// Смещение IP-адреса источника
+    #[inline(always)]
+    pub fn src_ip_offset(&self) -> usize {
+        self.l3_offset + 12
+    }
+
+    /// Смещение IP-адреса назначения
+    #[inline(always)]
+    pub fn dst_ip_offset(&self) -> usize {
+        self.l3_offset + 16
+    }
+}
+
+/// Причина, по которой пакет не может быть передан обработчику
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// Кадр короче заголовков
+    Truncated,
+    /// Не IPv4
+    NotIpv4,
+    /// IPv4 фрагмент - требует сборки
+    Fragment { l3_offset: usize },
+    /// Протокол L4, отличный от TCP/UDP
+    UnsupportedProtocol(u8),
+    /// Некорректный туннельный заголовок
+    BadTunnel,
+    /// Нет полезной нагрузки
+    NoPayload,
+}
+
+#[inline(always)]
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([data[offset], data[offset + 1]])
+}
+
+/// Разбирает Ethernet кадр
+///
+/// `flags` - комбинация `EXTRACT_DECAP_*`; декапсулируется не более одного уровня.
+#[inline]
+pub fn parse_frame(frame: &[u8], flags: u32, vxlan_port: u16) -> Result<ParsedPacket, ParseError> {
+    parse_chained(frame, frame.len(), flags, vxlan_port)
+}
+
+/// Разбирает кадр из цепочки сегментов mbuf
+///
+/// `first_segment` - данные первого сегмента, `pkt_len` - длина всего кадра.
+/// Заголовки должны целиком лежать в первом сегменте (так их раскладывают
+/// PMD при приеме jumbo кадров и LRO), полезная нагрузка может продолжаться
+/// в следующих. Границы нагрузки в `ParsedPacket` отсчитываются от начала
+/// кадра и могут выходить за первый сегмент.
+#[inline]
+pub fn parse_chained(
+    first_segment: &[u8],
+    pkt_len: usize,
+    flags: u32,
+    vxlan_port: u16,
+) -> Result<ParsedPacket, ParseError> {
+    let frame = first_segment;
+    let (ether_type, vlan_tci, mut l3) = parse_l2(frame)?;
+    if ether_type != ETHER_TYPE_IPV4 {
+        return Err(ParseError::NotIpv4);
+    }
+
+    let vxlan_port = if vxlan_port == 0 {
+        VXLAN_DEFAULT_PORT
+    } else {
+        vxlan_port
+    };
+
+    let mut decapsulated = false;
+    let l2 = L2Info {
+        ether_type,
+        vlan_tci,
+    };
+
+    loop {
+        if frame.len() < l3 + IPV4_MIN_HDR_LEN {
+            return Err(ParseError::Truncated);
+        }
+
+        // EtherType (внешний или внутренний) мог соврать
+        if frame[l3] >> 4 != 4 {
+            return Err(ParseError::NotIpv4);
+        }
+
+        let frag = read_u16(frame, l3 + 6);
+        if frag & (IPV4_MF_FLAG | IPV4_OFFSET_MASK) != 0 {
+            return Err(ParseError::Fragment { l3_offset: l3 });
+        }
+
+        let ihl = ((frame[l3] & 0x0f) as usize) * 4;
+        if ihl < IPV4_MIN_HDR_LEN {
+            return Err(ParseError::Truncated);
+        }
+
+        let protocol = frame[l3 + 9];
+        let l4 = l3 + ihl;
+        let ip_total_length = read_u16(frame, l3 + 2) as usize;
+
+        match protocol {
+            IPPROTO_GRE if !decapsulated && flags & EXTRACT_DECAP_GRE != 0 => {
+                l3 = skip_gre(frame, l4)?;
+                decapsulated = true;
+                continue;
+            }
+            IPPROTO_UDP => {
+                if frame.len() < l4 + UDP_HDR_LEN {
+                    return Err(ParseError::Truncated);
+                }
+
+                let src_port = read_u16(frame, l4);
+                let dst_port = read_u16(frame, l4 + 2);
+
+                if !decapsulated && flags & EXTRACT_DECAP_VXLAN != 0 && dst_port == vxlan_port {
+                    let vxlan = l4 + UDP_HDR_LEN;
+                    let inner_eth = vxlan + VXLAN_HDR_LEN;
+
+                    // Флаг I (валидный VNI) обязателен
+                    if frame.len() < inner_eth + ETH_HDR_LEN
+                        || frame[vxlan] & 0x08 == 0
+                        || read_u16(frame, inner_eth + 12) != ETHER_TYPE_IPV4
+                    {
+                        return Err(ParseError::BadTunnel);
+                    }
+
+                    l3 = inner_eth + ETH_HDR_LEN;
+                    decapsulated = true;
+                    continue;
+                }
+
+                return finish(
+                    frame,
+                    pkt_len,
+                    l2,
+                    l3,
+                    ip_total_length,
+                    ihl + UDP_HDR_LEN,
+                    protocol,
+                    src_port,
+                    dst_port,
+                    decapsulated,
+                );
+            }
+            IPPROTO_TCP => {
+                if frame.len() < l4 + TCP_MIN_HDR_LEN {
+                    return Err(ParseError::Truncated);
+                }
+
+                let tcp_header_len = ((frame[l4 + 12] >> 4) as usize) * 4;
+                if tcp_header_len < TCP_MIN_HDR_LEN {
+                    return Err(ParseError::Truncated);
+                }
+                return finish(
+                    frame,
+                    pkt_len,
+                    l2,
+                    l3,
+                    ip_total_length,
+                    ihl + tcp_header_len,
+                    protocol,
+                    read_u16(frame, l4),
+                    read_u16(frame, l4 + 2),
+                    decapsulated,
+                );
+            }
+            other => return Err(ParseError::UnsupportedProtocol(other)),
+        }
+    }
+}
+
+/// Сведения L2 уровня внешнего кадра
+#[derive(Clone, Copy)]
+struct L2Info {
+    ether_type: u16,
+    vlan_tci: Option<u16>,
+}
+
+/// Разбирает Ethernet заголовок с учетом до двух VLAN тегов (802.1Q / QinQ)
+///
+/// Возвращает (ether_type, vlan_tci, смещение L3 заголовка)
+#[inline(always)]
+pub fn parse_l2(frame: &[u8]) -> Result<(u16, Option<u16>, usize), ParseError> {
+    if frame.len() < ETH_HDR_LEN {
+        return Err(ParseError::Truncated);
+    }
+
+    let mut ether_type = read_u16(frame, 12);
+    let mut vlan_tci = None;
+    let mut offset = ETH_HDR_LEN;
+
+    for _ in 0..2 {
+        if ether_type != ETHER_TYPE_VLAN && ether_type != ETHER_TYPE_QINQ {
+            break;
+        }
+        if frame.len() < offset + VLAN_TAG_LEN {
+            return Err(ParseError::Truncated);
+        }
+
+        vlan_tci = Some(read_u16(frame, offset));
+        ether_type = read_u16(frame, offset + 2);
+        offset += VLAN_TAG_LEN;
+    }
+
+    Ok((ether_type, vlan_tci, offset))
+}
+
+/// Вычисляет границы полезной нагрузки по полю total_length
+#[inline(always)]
+#[allow(clippy::too_many_arguments)]
+fn finish(
+    frame: &[u8],
+    pkt_len: usize,
+    l2: L2Info,
+    l3_offset: usize,
+    ip_total_length: usize,
+    headers_len: usize,
+    protocol: u8,
+    src_port: u16,
+    dst_port: u16,
+    decapsulated: bool,
+) -> Result<ParsedPacket, ParseError> {
+    if ip_total_length <= headers_len {
+        return Err(ParseError::NoPayload);
+    }
+
+    let payload_offset = l3_offset + headers_len;
+    let payload_len = ip_total_length - headers_len;
+
+    if payload_offset > frame.len() || payload_offset + payload_len > pkt_len {
+        return Err(ParseError::Truncated);
+    }
+
+    Ok(ParsedPacket {
+        ether_type: l2.ether_type,
+        vlan_tci: l2.vlan_tci,
+        l3_offset,
+        src_port,
+        dst_port,
+        protocol,
+        payload_offset,
+        payload_len,
+        decapsulated,
+    })
+}
+
+/// Пропускает GRE заголовок и возвращает смещение внутреннего IPv4 заголовка
+#[inline]
+fn skip_gre(frame: &[u8], gre: usize) -> Result<usize, ParseError> {
+    if frame.len() < gre + 4 {
+        return Err(ParseError::BadTunnel);
+    }
+
+    let flags = read_u16(frame, gre);
+    // Поддерживается только GRE версии 0 без routing
+    if flags & 0x0007 != 0 || flags & 0x4000 != 0 {
+        return Err(ParseError::BadTunnel);
+    }
+
+    let mut header_len = 4;
+    if flags & 0x8000 != 0 {
+        header_len += 4; // Checksum + reserved
+    }
+    if flags & 0x2000 != 0 {
+        header_len += 4; // Key
+    }
+    if flags & 0x1000 != 0 {
+        header_len += 4; // Sequence number
+    }
+
+    let inner = gre + header_len;
+    match read_u16(frame, gre + 2) {
+        GRE_PROTO_TEB => {
+            if frame.len() < inner + ETH_HDR_LEN || read_u16(frame, inner + 12) != ETHER_TYPE_IPV4 {
+                return Err(ParseError::BadTunnel);
+            }
+            Ok(inner + ETH_HDR_LEN)
+        }
+        ETHER_TYPE_IPV4 => Ok(inner),
+        _ => Err(ParseError::BadTunnel),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAYLOAD: &[u8] = b"payload!";
+
+    /// VLAN теги, L3 часть, ожидаемые protocol, dst_port, vlan_tci и l3_offset
+    type FrameCase<'a> = (&'a [(u16, u16)], &'a [u8], u8, u16, Option<u16>, usize);
+
+    fn ipv4(protocol: u8, l4: &[u8]) -> Vec<u8> {
+        let total = (IPV4_MIN_HDR_LEN + l4.len()) as u16;
+        let mut hdr = vec![
+            0x45, 0, 0, 0, 0, 0, 0, 0, 64, protocol, 0, 0, 10, 0, 0, 1, 10, 0, 0, 2,
+        ];
+        hdr[2..4].copy_from_slice(&total.to_be_bytes());
+        hdr.extend_from_slice(l4);
+        hdr
+    }
+
+    fn udp(src: u16, dst: u16, payload: &[u8]) -> Vec<u8> {
+        let mut hdr = Vec::new();
+        hdr.extend_from_slice(&src.to_be_bytes());
+        hdr.extend_from_slice(&dst.to_be_bytes());
+        hdr.extend_from_slice(&((UDP_HDR_LEN + payload.len()) as u16).to_be_bytes());
+        hdr.extend_from_slice(&[0, 0]);
+        hdr.extend_from_slice(payload);
+        hdr
+    }
+
+    fn tcp(src: u16, dst: u16, data_offset: u8, payload: &[u8]) -> Vec<u8> {
+        let mut hdr = vec![0; TCP_MIN_HDR_LEN];
+        hdr[0..2].copy_from_slice(&src.to_be_bytes());
+        hdr[2..4].copy_from_slice(&dst.to_be_bytes());
+        hdr[12] = data_offset << 4;
+        hdr.extend_from_slice(payload);
+        hdr
+    }
+
+    /// Ethernet кадр с заданными VLAN тегами (TPID, TCI)
+    fn eth(tags: &[(u16, u16)], ether_type: u16, l3: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0; 12];
+        for (tpid, tci) in tags {
+            frame.extend_from_slice(&tpid.to_be_bytes());
+            frame.extend_from_slice(&tci.to_be_bytes());
+        }
+        frame.extend_from_slice(&ether_type.to_be_bytes());
+        frame.extend_from_slice(l3);
+        frame
+    }
+
+    fn vxlan(flags: u8, inner: &[u8]) -> Vec<u8> {
+        let mut hdr = vec![flags, 0, 0, 0, 0, 0, 42, 0];
+        hdr.extend_from_slice(inner);
+        udp(50000, VXLAN_DEFAULT_PORT, &hdr)
+    }
+
+    fn gre(flags: u16, proto: u16, inner: &[u8]) -> Vec<u8> {
+        let mut hdr = Vec::new();
+        hdr.extend_from_slice(&flags.to_be_bytes());
+        hdr.extend_from_slice(&proto.to_be_bytes());
+        let options = (flags & 0x8000 != 0) as usize
+            + (flags & 0x2000 != 0) as usize
+            + (flags & 0x1000 != 0) as usize;
+        hdr.resize(4 + options * 4, 0);
+        hdr.extend_from_slice(inner);
+        hdr
+    }
+
+    fn inner_udp() -> Vec<u8> {
+        ipv4(IPPROTO_UDP, &udp(1000, 2000, PAYLOAD))
+    }
+
+    fn check_payload(frame: &[u8], parsed: &ParsedPacket) {
+        assert_eq!(
+            &frame[parsed.payload_offset..parsed.payload_offset + parsed.payload_len],
+            PAYLOAD
+        );
+    }
+
+    #[test]
+    fn parses_plain_and_vlan_tagged_frames() {
+        let udp_l3 = ipv4(IPPROTO_UDP, &udp(1000, 2000, PAYLOAD));
+        let tcp_l3 = ipv4(IPPROTO_TCP, &tcp(3000, 4000, 5, PAYLOAD));
+        let cases: [FrameCase; 4] = [
+            (&[], &udp_l3, IPPROTO_UDP, 2000, None, ETH_HDR_LEN),
+            (&[], &tcp_l3, IPPROTO_TCP, 4000, None, ETH_HDR_LEN),
+            (
+                &[(ETHER_TYPE_VLAN, 100)],
+                &udp_l3,
+                IPPROTO_UDP,
+                2000,
+                Some(100),
+                ETH_HDR_LEN + 4,
+            ),
+            (
+                &[(ETHER_TYPE_QINQ, 10), (ETHER_TYPE_VLAN, 200)],
+                &tcp_l3,
+                IPPROTO_TCP,
+                4000,
+                Some(200),
+                ETH_HDR_LEN + 8,
+            ),
+        ];
+
+        for (tags, l3, protocol, dst_port, vlan_tci, l3_offset) in cases {
+            let frame = eth(tags, ETHER_TYPE_IPV4, l3);
+            let parsed = parse_frame(&frame, 0, 0).unwrap();
+            assert_eq!(parsed.protocol, protocol);
+            assert_eq!(parsed.dst_port, dst_port);
+            assert_eq!(parsed.vlan_tci, vlan_tci);
+            assert_eq!(parsed.ether_type, ETHER_TYPE_IPV4);
+            assert_eq!(parsed.l3_offset, l3_offset);
+            assert!(!parsed.decapsulated);
+            check_payload(&frame, &parsed);
+        }
+    }
+
+    #[test]
+    fn decapsulates_vxlan_only_with_valid_vni() {
+        let inner = eth(&[], ETHER_TYPE_IPV4, &inner_udp());
+
+        let frame = eth(
+            &[],
+            ETHER_TYPE_IPV4,
+            &ipv4(IPPROTO_UDP, &vxlan(0x08, &inner)),
+        );
+        let parsed = parse_frame(&frame, EXTRACT_DECAP_VXLAN, 0).unwrap();
+        assert!(parsed.decapsulated);
+        assert_eq!(parsed.dst_port, 2000);
+        assert_eq!(
+            parsed.l3_offset,
+            ETH_HDR_LEN + IPV4_MIN_HDR_LEN + UDP_HDR_LEN + VXLAN_HDR_LEN + ETH_HDR_LEN
+        );
+        check_payload(&frame, &parsed);
+
+        // Без флага декапсуляции VXLAN остается обычным UDP
+        let outer = parse_frame(&frame, 0, 0).unwrap();
+        assert!(!outer.decapsulated);
+        assert_eq!(outer.dst_port, VXLAN_DEFAULT_PORT);
+
+        let no_vni = eth(
+            &[],
+            ETHER_TYPE_IPV4,
+            &ipv4(IPPROTO_UDP, &vxlan(0x00, &inner)),
+        );
+        assert_eq!(
+            parse_frame(&no_vni, EXTRACT_DECAP_VXLAN, 0),
+            Err(ParseError::BadTunnel)
+        );
+    }
+
+    #[test]
+    fn decapsulates_gre_variants() {
+        let inner_ip = inner_udp();
+        let inner_eth = eth(&[], ETHER_TYPE_IPV4, &inner_ip);
+        let cases: [(u16, u16, &[u8]); 4] = [
+            (0x0000, ETHER_TYPE_IPV4, &inner_ip),
+            (0x2000, ETHER_TYPE_IPV4, &inner_ip),
+            (0x8000, ETHER_TYPE_IPV4, &inner_ip),
+            (0x0000, GRE_PROTO_TEB, &inner_eth),
+        ];
+
+        for (gre_flags, proto, inner) in cases {
+            let frame = eth(
+                &[],
+                ETHER_TYPE_IPV4,
+                &ipv4(IPPROTO_GRE, &gre(gre_flags, proto, inner)),
+            );
+            let parsed = parse_frame(&frame, EXTRACT_DECAP_GRE, 0).unwrap();
+            assert!(
+                parsed.decapsulated,
+                "flags {:#06x} proto {:#06x}",
+                gre_flags, proto
+            );
+            assert_eq!(parsed.dst_port, 2000);
+            check_payload(&frame, &parsed);
+        }
+
+        let routing = eth(
+            &[],
+            ETHER_TYPE_IPV4,
+            &ipv4(IPPROTO_GRE, &gre(0x4000, ETHER_TYPE_IPV4, &inner_ip)),
+        );
+        assert_eq!(
+            parse_frame(&routing, EXTRACT_DECAP_GRE, 0),
+            Err(ParseError::BadTunnel)
+        );
+
+        let plain = eth(
+            &[],
+            ETHER_TYPE_IPV4,
+            &ipv4(IPPROTO_GRE, &gre(0, ETHER_TYPE_IPV4, &inner_ip)),
+        );
+        assert_eq!(
+            parse_frame(&plain, 0, 0),
+            Err(ParseError::UnsupportedProtocol(IPPROTO_GRE))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_headers() {
+        let base = eth(&[], ETHER_TYPE_IPV4, &inner_udp());
+        let l3 = ETH_HDR_LEN;
+
+        let mut more_fragments = base.clone();
+        more_fragments[l3 + 6] = 0x20;
+        let mut offset = base.clone();
+        offset[l3 + 7] = 0x10;
+        let mut short_ihl = base.clone();
+        short_ihl[l3] = 0x44;
+        let mut ipv6 = base.clone();
+        ipv6[l3] = 0x65;
+        let mut short_total = base.clone();
+        short_total[l3 + 2..l3 + 4]
+            .copy_from_slice(&((IPV4_MIN_HDR_LEN + UDP_HDR_LEN) as u16).to_be_bytes());
+        let short_tcp = eth(
+            &[],
+            ETHER_TYPE_IPV4,
+            &ipv4(IPPROTO_TCP, &tcp(1, 2, 4, PAYLOAD)),
+        );
+        let arp = eth(&[], 0x0806, &inner_udp());
+
+        let cases: [(&[u8], ParseError); 9] = [
+            (&more_fragments, ParseError::Fragment { l3_offset: l3 }),
+            (&offset, ParseError::Fragment { l3_offset: l3 }),
+            (&short_ihl, ParseError::Truncated),
+            (&ipv6, ParseError::NotIpv4),
+            (&short_total, ParseError::NoPayload),
+            (&short_tcp, ParseError::Truncated),
+            (&arp, ParseError::NotIpv4),
+            (&base[..ETH_HDR_LEN - 1], ParseError::Truncated),
+            (&base[..l3 + IPV4_MIN_HDR_LEN + 4], ParseError::Truncated),
+        ];
+
+        for (frame, expected) in cases {
+            assert_eq!(parse_frame(frame, 0, 0), Err(expected));
+        }
+
+        // Нагрузка обрезана: total_length обещает больше, чем есть в кадре
+        assert_eq!(
+            parse_frame(&base[..base.len() - 1], 0, 0),
+            Err(ParseError::Truncated)
+        );
+    }
+
+    #[test]
+    fn chained_payload_may_extend_past_first_segment() {
+        let frame = eth(&[], ETHER_TYPE_IPV4, &inner_udp());
+        let headers = ETH_HDR_LEN + IPV4_MIN_HDR_LEN + UDP_HDR_LEN;
+        let first_segment = &frame[..headers + 2];
+
+        let parsed = parse_chained(first_segment, frame.len(), 0, 0).unwrap();
+        assert_eq!(parsed.payload_offset, headers);
+        assert_eq!(parsed.payload_len, PAYLOAD.len());
+
+        assert_eq!(
+            parse_chained(first_segment, frame.len() - 1, 0, 0),
+            Err(ParseError::Truncated)
+        );
+        // Заголовки обязаны лежать в первом сегменте
+        assert_eq!(
+            parse_chained(&frame[..headers - 1], frame.len(), 0, 0),
+            Err(ParseError::Truncated)
+        );
+    }
+}
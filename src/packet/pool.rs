@@ -2,8 +2,32 @@ use crossbeam::queue::ArrayQueue;
 use std::os::raw::c_void;
 use std::sync::Arc;
 
+#[cfg(debug_assertions)]
+use std::collections::HashMap;
+#[cfg(debug_assertions)]
+use std::panic::Location;
+#[cfg(debug_assertions)]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(debug_assertions)]
+use std::sync::Mutex;
+
+use crate::logging::counter::AnomalyCounter;
 use crate::numa::ffi::NumaAllocator;
 use crate::packet::data::PacketData;
+use crate::{log_debug, log_info, log_warn};
+
+/// Пул пуст: пакет создается вне пула (не чаще одного сообщения в секунду)
+static POOL_EMPTY: AnomalyCounter = AnomalyCounter::new(
+    "pool",
+    "Packet pool is empty, creating new packet",
+    1_000_000_000,
+);
+/// Пул переполнен: пакет не возвращен в пул
+static POOL_FULL: AnomalyCounter = AnomalyCounter::new(
+    "pool",
+    "Failed to return packet to pool (pool is full)",
+    1_000_000_000,
+);
 
 /// Пул пакетов данных с поддержкой NUMA
 pub struct PacketDataPool {
@@ -13,6 +37,57 @@ pub struct PacketDataPool {
     numa_node: Option<usize>,
     /// Информация о выделенной памяти для корректного освобождения
     allocated_memory: Option<(*mut c_void, usize)>,
+    /// Учет выданных, но не возвращенных пакетов (только в отладочной сборке)
+    #[cfg(debug_assertions)]
+    leases: LeaseTracker,
+}
+
+/// Отслеживает выданные пакеты и места их получения
+#[cfg(debug_assertions)]
+struct LeaseTracker {
+    next_id: AtomicU64,
+    outstanding: Mutex<HashMap<u64, &'static Location<'static>>>,
+}
+
+#[cfg(debug_assertions)]
+impl LeaseTracker {
+    fn new() -> Self {
+        Self {
+            next_id: AtomicU64::new(1),
+            outstanding: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn acquire(&self, location: &'static Location<'static>) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.outstanding.lock().unwrap().insert(id, location);
+        id
+    }
+
+    fn release(&self, id: u64) {
+        if id == 0 {
+            return;
+        }
+        self.outstanding.lock().unwrap().remove(&id);
+    }
+
+    /// Возвращает места получения невозвращенных пакетов с количеством
+    fn leaks(&self) -> Vec<(&'static Location<'static>, usize)> {
+        let outstanding = self.outstanding.lock().unwrap();
+        let mut by_site: HashMap<(&'static str, u32, u32), (&'static Location<'static>, usize)> =
+            HashMap::new();
+
+        for location in outstanding.values() {
+            by_site
+                .entry((location.file(), location.line(), location.column()))
+                .or_insert((location, 0))
+                .1 += 1;
+        }
+
+        let mut leaks: Vec<_> = by_site.into_values().collect();
+        leaks.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        leaks
+    }
 }
 
 impl PacketDataPool {
@@ -23,9 +98,11 @@ impl PacketDataPool {
 
         if let Some(node) = numa_node {
             if NumaAllocator::is_available() {
-                println!(
-                    "Creating packet pool with NUMA-optimized memory on node {}",
-                    node
+                log_info!(
+                    "pool",
+                    "Creating packet pool with NUMA-optimized memory",
+                    node = node,
+                    capacity = capacity
                 );
 
                 let packet_size = std::mem::size_of::<PacketData>();
@@ -53,15 +130,27 @@ impl PacketDataPool {
                         }
                     }
 
-                    println!("Successfully allocated NUMA-optimized memory for packet pool");
+                    log_info!(
+                        "pool",
+                        "Allocated NUMA-optimized memory",
+                        bytes = total_size
+                    );
                 } else {
-                    println!("Warning: Failed to allocate NUMA memory, falling back to regular allocation");
+                    log_warn!(
+                        "pool",
+                        "Failed to allocate NUMA memory, falling back to regular allocation",
+                        node = node
+                    );
                 }
             }
         }
 
         if allocated_memory.is_none() {
-            println!("Creating packet pool with regular memory allocation");
+            log_info!(
+                "pool",
+                "Creating packet pool with regular memory allocation",
+                capacity = capacity
+            );
             for _ in 0..capacity {
                 let data = PacketData::new();
                 let _ = queue.push(data);
@@ -72,26 +161,43 @@ impl PacketDataPool {
             queue,
             numa_node,
             allocated_memory,
+            #[cfg(debug_assertions)]
+            leases: LeaseTracker::new(),
         }
     }
 
     /// Получает пакет из пула
+    #[cfg_attr(debug_assertions, track_caller)]
     pub fn acquire(&self) -> PacketData {
-        match self.queue.pop() {
+        #[allow(unused_mut)]
+        let mut packet = match self.queue.pop() {
             Some(packet) => packet,
             None => {
-                println!("Warning: Packet pool is empty, creating new packet");
+                POOL_EMPTY.hit();
                 PacketData::new()
             }
+        };
+
+        #[cfg(debug_assertions)]
+        {
+            packet.lease_id = self.leases.acquire(Location::caller());
         }
+
+        packet
     }
 
     /// Возвращает пакет в пул
     pub fn release(&self, mut packet: PacketData) {
+        #[cfg(debug_assertions)]
+        {
+            self.leases.release(packet.lease_id);
+            packet.lease_id = 0;
+        }
+
         packet.reset();
 
         if self.queue.push(packet).is_err() {
-            println!("Warning: Failed to return packet to pool (pool is full)");
+            POOL_FULL.hit();
         }
     }
 
@@ -99,12 +205,39 @@ impl PacketDataPool {
     pub fn get_numa_node(&self) -> Option<usize> {
         self.numa_node
     }
+
+    /// Выводит отчет о пакетах, выданных из пула и не возвращенных в него
+    ///
+    /// В релизной сборке учет не ведется и функция возвращает 0.
+    pub fn report_leaks(&self) -> usize {
+        #[cfg(debug_assertions)]
+        {
+            let leaks = self.leases.leaks();
+            let total: usize = leaks.iter().map(|(_, count)| count).sum();
+
+            if total > 0 {
+                crate::log_error!("pool", "Packets acquired but never released", count = total);
+                for (location, count) in leaks {
+                    crate::log_error!("pool", "Leaked packets", count = count, location = location);
+                }
+            }
+
+            total
+        }
+
+        #[cfg(not(debug_assertions))]
+        {
+            0
+        }
+    }
 }
 
 impl Drop for PacketDataPool {
     fn drop(&mut self) {
+        self.report_leaks();
+
         if let Some((ptr, size)) = self.allocated_memory {
-            println!("Freeing NUMA-allocated memory for packet pool");
+            log_debug!("pool", "Freeing NUMA-allocated memory", bytes = size);
             NumaAllocator::free(ptr, size);
         }
     }
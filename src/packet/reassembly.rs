@@ -0,0 +1,279 @@
+// src/packet/reassembly.rs
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::mem::copy::copy_bytes;
+
+/// Флаг "More Fragments" в поле fragment_offset заголовка IPv4
+const IPV4_MF_FLAG: u16 = 0x2000;
+/// Маска смещения фрагмента (в единицах по 8 байт)
+const IPV4_OFFSET_MASK: u16 = 0x1fff;
+
+/// Параметры сборки фрагментированных IPv4 пакетов
+#[derive(Debug, Clone, Copy)]
+pub struct ReassemblyConfig {
+    /// Время, после которого незавершенная сборка отбрасывается
+    pub timeout: Duration,
+    /// Максимальное количество одновременно собираемых датаграмм
+    pub max_flows: usize,
+    /// Максимальный размер собранной датаграммы (без IP заголовка)
+    pub max_datagram_size: usize,
+}
+
+impl Default for ReassemblyConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_millis(100),
+            max_flows: 64,
+            max_datagram_size: 65535,
+        }
+    }
+}
+
+/// Ключ сборки: (src, dst, id, protocol)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct FragmentKey {
+    src: [u8; 4],
+    dst: [u8; 4],
+    id: u16,
+    protocol: u8,
+}
+
+/// Состояние собираемой датаграммы
+struct FragmentEntry {
+    /// Время получения первого фрагмента (`time::now_ns`)
+    first_seen_ns: u64,
+    buffer: Vec<u8>,
+    /// Полученные диапазоны [start, end) полезной нагрузки IP
+    ranges: Vec<(usize, usize)>,
+    received: usize,
+    /// Полная длина становится известна после получения последнего фрагмента
+    total_len: Option<usize>,
+}
+
+/// Собранная датаграмма (L4 заголовок + данные)
+pub struct ReassembledDatagram<'a> {
+    pub src_ip: &'a [u8],
+    pub dst_ip: &'a [u8],
+    pub protocol: u8,
+    pub l4: &'a [u8],
+}
+
+/// Результат обработки фрагмента
+pub enum FragmentResult<'a> {
+    /// Пакет не является фрагментом
+    NotFragment,
+    /// Фрагмент сохранен, датаграмма еще не собрана
+    Pending,
+    /// Фрагмент отброшен (перекрытие, превышение размера или лимита сборок)
+    Dropped,
+    /// Датаграмма собрана полностью
+    Complete(ReassembledDatagram<'a>),
+}
+
+/// Таблица сборки фрагментов, принадлежащая одному рабочему потоку
+pub struct FragmentTable {
+    config: ReassemblyConfig,
+    flows: HashMap<FragmentKey, FragmentEntry>,
+    /// Буферы освобожденных сборок для повторного использования
+    free_buffers: Vec<Vec<u8>>,
+    /// Последняя собранная датаграмма (живет до следующего вызова `process`)
+    completed_key: FragmentKey,
+    completed: Vec<u8>,
+    /// Количество сборок, отброшенных по таймауту
+    pub timed_out: u64,
+}
+
+impl FragmentTable {
+    pub fn new(config: ReassemblyConfig) -> Self {
+        Self {
+            config,
+            flows: HashMap::with_capacity(config.max_flows),
+            free_buffers: Vec::new(),
+            completed_key: FragmentKey {
+                src: [0; 4],
+                dst: [0; 4],
+                id: 0,
+                protocol: 0,
+            },
+            completed: Vec::new(),
+            timed_out: 0,
+        }
+    }
+
+    /// Проверяет, является ли IPv4 пакет фрагментом
+    #[inline(always)]
+    pub fn is_fragment(ip_packet: &[u8]) -> bool {
+        if ip_packet.len() < 20 {
+            return false;
+        }
+
+        let frag = u16::from_be_bytes([ip_packet[6], ip_packet[7]]);
+        frag & (IPV4_MF_FLAG | IPV4_OFFSET_MASK) != 0
+    }
+
+    /// Возвращает количество незавершенных сборок
+    pub fn pending(&self) -> usize {
+        self.flows.len()
+    }
+
+    /// Обрабатывает IPv4 пакет (начиная с IP заголовка)
+    ///
+    /// `now_ns` - текущее время по `time::now_ns`
+    pub fn process(&mut self, ip_packet: &[u8], now_ns: u64) -> FragmentResult<'_> {
+        if !Self::is_fragment(ip_packet) {
+            return FragmentResult::NotFragment;
+        }
+
+        let ihl = ((ip_packet[0] & 0x0f) as usize) * 4;
+        let total_length = u16::from_be_bytes([ip_packet[2], ip_packet[3]]) as usize;
+        if ihl < 20 || total_length < ihl || total_length > ip_packet.len() {
+            return FragmentResult::Dropped;
+        }
+
+        let frag = u16::from_be_bytes([ip_packet[6], ip_packet[7]]);
+        let more_fragments = frag & IPV4_MF_FLAG != 0;
+        let start = ((frag & IPV4_OFFSET_MASK) as usize) * 8;
+        let payload = &ip_packet[ihl..total_length];
+        let end = start + payload.len();
+
+        if end > self.config.max_datagram_size {
+            return FragmentResult::Dropped;
+        }
+
+        let key = FragmentKey {
+            src: [ip_packet[12], ip_packet[13], ip_packet[14], ip_packet[15]],
+            dst: [ip_packet[16], ip_packet[17], ip_packet[18], ip_packet[19]],
+            id: u16::from_be_bytes([ip_packet[4], ip_packet[5]]),
+            protocol: ip_packet[9],
+        };
+
+        if !self.flows.contains_key(&key) {
+            if self.flows.len() >= self.config.max_flows {
+                self.evict_expired(now_ns);
+                if self.flows.len() >= self.config.max_flows {
+                    return FragmentResult::Dropped;
+                }
+            }
+
+            let buffer = self.free_buffers.pop().unwrap_or_default();
+            self.flows.insert(
+                key,
+                FragmentEntry {
+                    first_seen_ns: now_ns,
+                    buffer,
+                    ranges: Vec::with_capacity(8),
+                    received: 0,
+                    total_len: None,
+                },
+            );
+        }
+
+        let entry = self.flows.get_mut(&key).unwrap();
+
+        // Перекрывающиеся фрагменты считаем атакой или ошибкой и сбрасываем сборку
+        let overlaps = entry.ranges.iter().any(|&(s, e)| start < e && s < end);
+        let conflicting_tail = match entry.total_len {
+            Some(total) => end > total || (!more_fragments && end != total),
+            None => false,
+        };
+        if overlaps || conflicting_tail {
+            let entry = self.flows.remove(&key).unwrap();
+            self.recycle(entry.buffer);
+            return FragmentResult::Dropped;
+        }
+
+        if entry.buffer.len() < end {
+            entry.buffer.resize(end, 0);
+        }
+        copy_bytes(&mut entry.buffer[start..end], payload);
+        entry.ranges.push((start, end));
+        entry.received += payload.len();
+
+        if !more_fragments {
+            entry.total_len = Some(end);
+        }
+
+        match entry.total_len {
+            Some(total) if entry.received == total => {
+                let entry = self.flows.remove(&key).unwrap();
+                let previous = std::mem::replace(&mut self.completed, entry.buffer);
+                self.recycle(previous);
+                self.completed.truncate(total);
+                self.completed_key = key;
+
+                FragmentResult::Complete(ReassembledDatagram {
+                    src_ip: &self.completed_key.src,
+                    dst_ip: &self.completed_key.dst,
+                    protocol: self.completed_key.protocol,
+                    l4: &self.completed,
+                })
+            }
+            _ => FragmentResult::Pending,
+        }
+    }
+
+    /// Удаляет сборки, не завершенные за отведенное время
+    pub fn evict_expired(&mut self, now_ns: u64) {
+        if self.flows.is_empty() {
+            return;
+        }
+
+        let timeout_ns = self.config.timeout.as_nanos() as u64;
+        let expired: Vec<FragmentKey> = self
+            .flows
+            .iter()
+            .filter(|(_, e)| now_ns.saturating_sub(e.first_seen_ns) >= timeout_ns)
+            .map(|(k, _)| *k)
+            .collect();
+
+        for key in expired {
+            if let Some(entry) = self.flows.remove(&key) {
+                self.timed_out += 1;
+                self.recycle(entry.buffer);
+            }
+        }
+    }
+
+    fn recycle(&mut self, mut buffer: Vec<u8>) {
+        if self.free_buffers.len() < self.config.max_flows {
+            buffer.clear();
+            self.free_buffers.push(buffer);
+        }
+    }
+}
+
+impl ReassembledDatagram<'_> {
+    /// Разбирает L4 заголовок и возвращает (src_port, dst_port, payload)
+    pub fn ports_and_payload(&self) -> Option<(u16, u16, &[u8])> {
+        match self.protocol {
+            // TCP
+            6 => {
+                if self.l4.len() < 20 {
+                    return None;
+                }
+                let header_len = ((self.l4[12] >> 4) as usize) * 4;
+                if header_len < 20 || header_len > self.l4.len() {
+                    return None;
+                }
+                Some((
+                    u16::from_be_bytes([self.l4[0], self.l4[1]]),
+                    u16::from_be_bytes([self.l4[2], self.l4[3]]),
+                    &self.l4[header_len..],
+                ))
+            }
+            // UDP
+            17 => {
+                if self.l4.len() < 8 {
+                    return None;
+                }
+                Some((
+                    u16::from_be_bytes([self.l4[0], self.l4[1]]),
+                    u16::from_be_bytes([self.l4[2], self.l4[3]]),
+                    &self.l4[8..],
+                ))
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,323 @@
+// src/packet/reorder.rs
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::control::snapshot::ConfigSnapshot;
+use crate::control::state::StateSource;
+use crate::metrics::line_correlation::SequenceExtractor;
+use crate::packet::data::{PacketRef, PacketView};
+use crate::packet::handler::PacketHandler;
+
+/// Наибольшее расстояние удержания: буфер рассчитан на локальные перестановки
+pub const MAX_REORDER_DISTANCE: u64 = 4096;
+
+/// Параметры переупорядочивания фида
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReorderConfig {
+    /// Наибольшее опережение ожидаемого номера, при котором пакет удерживается
+    pub max_distance: u64,
+    /// Наибольшее ожидание пропущенного номера
+    pub max_hold: Duration,
+}
+
+impl ReorderConfig {
+    pub fn new(max_distance: u64, max_hold: Duration) -> Self {
+        Self {
+            max_distance,
+            max_hold,
+        }
+    }
+
+    /// Разбирает `<max_distance>[,<max_hold_us>]` (по умолчанию 50 мкс)
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut parts = spec.split(',');
+        let max_distance: u64 = parts
+            .next()
+            .and_then(|s| s.trim().parse().ok())
+            .ok_or_else(|| format!("Invalid reorder distance in '{}'", spec))?;
+        let max_hold_us: u64 = match parts.next() {
+            Some(s) => s
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid reorder hold time '{}'", s))?,
+            None => 50,
+        };
+
+        if max_distance == 0 || max_distance > MAX_REORDER_DISTANCE {
+            return Err(format!(
+                "Reorder distance {} out of range 1..={}",
+                max_distance, MAX_REORDER_DISTANCE
+            ));
+        }
+
+        Ok(Self::new(max_distance, Duration::from_micros(max_hold_us)))
+    }
+}
+
+/// Счетчики переупорядочивания
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReorderStats {
+    /// Пакеты, пришедшие по порядку
+    pub in_order: u64,
+    /// Пакеты, доставленные из буфера после пропущенного номера
+    pub reordered: u64,
+    /// Номера, которые не дождались (пропущены по времени или расстоянию)
+    pub skipped: u64,
+    /// Пакеты с уже пройденным номером (опоздавшие и повторы), отброшены
+    pub late: u64,
+    /// Пакеты без mbuf, доставленные без удержания
+    pub unheld: u64,
+    /// Пакеты без порядкового номера
+    pub unsequenced: u64,
+}
+
+impl fmt::Display for ReorderStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "in_order={} reordered={} skipped={} late={} unheld={} unseq={}",
+            self.in_order, self.reordered, self.skipped, self.late, self.unheld, self.unsequenced
+        )
+    }
+}
+
+/// Буфер переупорядочивания одного фида
+///
+/// Пакет с ожидаемым номером доставляется сразу, пакет с номером впереди
+/// удерживается (`PacketView::retain`), пока не придут пропущенные. Ожидание
+/// ограничено расстоянием (`max_distance`) и временем (`max_hold`): после
+/// этого пропуск считается потерей и удержанные пакеты доставляются по
+/// порядку. Время проверяется по приходу следующего пакета фида, поэтому на
+/// затихшем фиде пакеты остаются в буфере до следующего пакета.
+pub struct ReorderBuffer {
+    config: ReorderConfig,
+    /// Удержанные пакеты: номер `n` хранится в ячейке `n & mask`
+    slots: Box<[Option<PacketRef>]>,
+    mask: u64,
+    next: Option<u64>,
+    held: usize,
+    /// Время приема самого старого удержанного пакета
+    oldest_held_ns: u64,
+    stats: ReorderStats,
+}
+
+impl ReorderBuffer {
+    pub fn new(config: ReorderConfig) -> Self {
+        let size = (config.max_distance as usize + 1).next_power_of_two();
+        Self {
+            config,
+            slots: (0..size).map(|_| None).collect(),
+            mask: size as u64 - 1,
+            next: None,
+            held: 0,
+            oldest_held_ns: 0,
+            stats: ReorderStats::default(),
+        }
+    }
+
+    /// Принимает пакет с номером `sequence`; пакеты по порядку передаются в `deliver`
+    pub fn offer(
+        &mut self,
+        sequence: u64,
+        packet: &PacketView,
+        deliver: &mut dyn FnMut(&PacketView),
+    ) {
+        let now_ns = packet.rx_timestamp_ns();
+        if self.held > 0
+            && now_ns.saturating_sub(self.oldest_held_ns) >= self.config.max_hold.as_nanos() as u64
+        {
+            self.skip_gap(deliver);
+        }
+
+        let next = *self.next.get_or_insert(sequence);
+        if sequence < next {
+            self.stats.late += 1;
+            return;
+        }
+
+        if sequence == next {
+            self.stats.in_order += 1;
+            deliver(packet);
+            self.next = Some(next + 1);
+            self.drain(deliver);
+            return;
+        }
+
+        if sequence - next > self.config.max_distance {
+            // Пропуск шире окна: удержанные пакеты доставляются, ожидание прекращается
+            while self.held > 0 {
+                self.skip_gap(deliver);
+            }
+            let next = self.next.unwrap_or(sequence);
+            self.stats.skipped += sequence.saturating_sub(next);
+            self.stats.in_order += 1;
+            deliver(packet);
+            self.next = Some(sequence + 1);
+            return;
+        }
+
+        let slot = &mut self.slots[(sequence & self.mask) as usize];
+        if slot.is_some() {
+            self.stats.late += 1;
+            return;
+        }
+
+        match packet.retain() {
+            Some(held) => {
+                *slot = Some(held);
+                if self.held == 0 {
+                    self.oldest_held_ns = now_ns;
+                }
+                self.held += 1;
+            }
+            None => {
+                self.stats.unheld += 1;
+                deliver(packet);
+            }
+        }
+    }
+
+    /// Отмечает пакет без порядкового номера
+    pub fn note_unsequenced(&mut self) {
+        self.stats.unsequenced += 1;
+    }
+
+    /// Доставляет удержанные пакеты, идущие подряд от ожидаемого номера
+    fn drain(&mut self, deliver: &mut dyn FnMut(&PacketView)) {
+        let Some(mut next) = self.next else {
+            return;
+        };
+
+        while self.held > 0 {
+            let Some(held) = self.slots[(next & self.mask) as usize].take() else {
+                break;
+            };
+            self.held -= 1;
+            self.stats.reordered += 1;
+            deliver(&held.view());
+            next += 1;
+        }
+        self.next = Some(next);
+    }
+
+    /// Прекращает ожидание пропущенных номеров до ближайшего удержанного пакета
+    fn skip_gap(&mut self, deliver: &mut dyn FnMut(&PacketView)) {
+        let Some(next) = self.next else {
+            return;
+        };
+
+        let Some(first_held) = (next + 1..=next + self.config.max_distance)
+            .find(|&sequence| self.slots[(sequence & self.mask) as usize].is_some())
+        else {
+            self.held = 0;
+            return;
+        };
+
+        self.stats.skipped += first_held - next;
+        self.next = Some(first_held);
+        self.drain(deliver);
+
+        if self.held > 0 {
+            self.oldest_held_ns = self
+                .slots
+                .iter()
+                .flatten()
+                .map(|held| held.view().rx_timestamp_ns())
+                .min()
+                .unwrap_or(0);
+        }
+    }
+
+    /// Следующий ожидаемый номер (None - пакетов еще не было)
+    pub fn next_sequence(&self) -> Option<u64> {
+        self.next
+    }
+
+    /// Количество удержанных пакетов
+    pub fn held(&self) -> usize {
+        self.held
+    }
+
+    pub fn stats(&self) -> ReorderStats {
+        self.stats
+    }
+}
+
+/// Переупорядочивание фида, общее для рабочих потоков его очередей
+///
+/// Пакеты фида обычно приходят в одну очередь, и блокировка не
+/// конкурирует; если фид распределен RSS по нескольким очередям, порядок
+/// между ними восстанавливается ценой ожидания блокировки.
+pub struct FeedReorder {
+    buffer: Mutex<ReorderBuffer>,
+}
+
+impl FeedReorder {
+    pub fn new(config: ReorderConfig) -> Self {
+        Self {
+            buffer: Mutex::new(ReorderBuffer::new(config)),
+        }
+    }
+
+    pub fn stats(&self) -> ReorderStats {
+        self.buffer
+            .lock()
+            .map(|buffer| buffer.stats())
+            .unwrap_or_default()
+    }
+}
+
+impl StateSource for FeedReorder {
+    fn capture(&self, prefix: &str, state: &mut ConfigSnapshot) {
+        let Ok(buffer) = self.buffer.lock() else {
+            return;
+        };
+
+        match buffer.next_sequence() {
+            Some(sequence) => state.set(&format!("{}.next_sequence", prefix), sequence),
+            None => state.set(&format!("{}.next_sequence", prefix), "none"),
+        }
+        state.set(&format!("{}.held", prefix), buffer.held());
+
+        let stats = buffer.stats();
+        state.set(&format!("{}.in_order", prefix), stats.in_order);
+        state.set(&format!("{}.reordered", prefix), stats.reordered);
+        state.set(&format!("{}.skipped", prefix), stats.skipped);
+        state.set(&format!("{}.late", prefix), stats.late);
+    }
+}
+
+/// Оборачивает обработчик фида: пакеты передаются в порядке номеров
+///
+/// `bypass` (если задан) получает каждый пакет сразу в порядке прихода, до
+/// буфера, - для потребителей, которым задержка важнее порядка. Пакеты без
+/// порядкового номера передаются без переупорядочивания.
+pub fn reordering_handler(
+    reorder: Arc<FeedReorder>,
+    extractor: SequenceExtractor,
+    inner: PacketHandler,
+    bypass: Option<PacketHandler>,
+) -> PacketHandler {
+    Arc::new(move |queue_id: u16, packet: &PacketView| {
+        if let Some(bypass) = &bypass {
+            bypass(queue_id, packet);
+        }
+
+        let Ok(mut buffer) = reorder.buffer.lock() else {
+            return;
+        };
+
+        match extractor.extract(packet.data()) {
+            Some(sequence) => {
+                buffer.offer(sequence, packet, &mut |ordered: &PacketView| {
+                    inner(ordered.queue_id(), ordered)
+                });
+            }
+            None => {
+                buffer.note_unsequenced();
+                inner(queue_id, packet);
+            }
+        }
+    })
+}
@@ -0,0 +1,144 @@
+// src/packet/tap.rs
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use crossbeam::queue::ArrayQueue;
+
+use crate::packet::data::PacketView;
+use crate::packet::handler::{FeedClass, PacketHandler};
+
+/// Наибольшая длина копируемой части кадра по умолчанию
+pub const DEFAULT_TAP_SNAPLEN: usize = 2048;
+
+/// Кадр, скопированный в кольцо зеркалирования
+pub struct TapFrame {
+    /// Время приема (`time::now_ns`)
+    pub timestamp_ns: u64,
+    /// Длина кадра до усечения
+    pub orig_len: usize,
+    pub data: Vec<u8>,
+}
+
+/// Отбор зеркалируемых пакетов
+///
+/// Разбирает `<udp_port>[-<udp_port>][,<dst_ip>]`; пустая строка и `all` -
+/// все пакеты.
+pub fn parse_tap_filter(spec: &str) -> Result<Option<FeedClass>, String> {
+    let spec = spec.trim();
+    if spec.is_empty() || spec == "all" {
+        return Ok(None);
+    }
+
+    let (ports, dst_ip) = match spec.split_once(',') {
+        Some((ports, ip)) => (ports, Some(ip)),
+        None => (spec, None),
+    };
+    let mut class = FeedClass::parse("tap", ports).map_err(|e| format!("{} in tap filter", e))?;
+    if let Some(ip) = dst_ip {
+        let addr: std::net::Ipv4Addr = ip
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid IPv4 address '{}' in tap filter", ip))?;
+        class = class.with_dst_ip(addr.octets());
+    }
+
+    Ok(Some(class))
+}
+
+/// Кольцо зеркалирования трафика для отладки (просмотр в Wireshark)
+///
+/// Пока зеркалирование не включено, обработчик только читает флаг. Во время
+/// просмотра кадры копируются в кольцо рабочими потоками; если читатель не
+/// успевает, новые кадры отбрасываются и учитываются в `dropped`.
+pub struct TapRing {
+    frames: ArrayQueue<TapFrame>,
+    active: AtomicBool,
+    filter: RwLock<Option<FeedClass>>,
+    snaplen: usize,
+    dropped: AtomicU64,
+}
+
+impl TapRing {
+    pub fn new(capacity: usize, snaplen: usize) -> Self {
+        Self {
+            frames: ArrayQueue::new(capacity.max(1)),
+            active: AtomicBool::new(false),
+            filter: RwLock::new(None),
+            snaplen,
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Включает зеркалирование пакетов, подходящих под `filter` (None - всех)
+    pub fn start(&self, filter: Option<FeedClass>) {
+        if let Ok(mut current) = self.filter.write() {
+            *current = filter;
+        }
+        self.dropped.store(0, Ordering::Relaxed);
+        self.active.store(true, Ordering::Release);
+    }
+
+    /// Выключает зеркалирование и очищает кольцо
+    pub fn stop(&self) {
+        self.active.store(false, Ordering::Release);
+        while self.frames.pop().is_some() {}
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Acquire)
+    }
+
+    /// Копирует кадр пакета в кольцо, если зеркалирование включено
+    #[inline(always)]
+    pub fn mirror(&self, packet: &PacketView) {
+        if !self.active.load(Ordering::Relaxed) {
+            return;
+        }
+        self.mirror_slow(packet);
+    }
+
+    #[cold]
+    fn mirror_slow(&self, packet: &PacketView) {
+        let Some(frame) = packet.frame() else {
+            return;
+        };
+        let matched = match self.filter.read() {
+            Ok(filter) => filter.as_ref().is_none_or(|class| class.matches(packet)),
+            Err(_) => false,
+        };
+        if !matched {
+            return;
+        }
+
+        let tap_frame = TapFrame {
+            timestamp_ns: packet.rx_timestamp_ns(),
+            orig_len: frame.len(),
+            data: frame[..frame.len().min(self.snaplen)].to_vec(),
+        };
+        if self.frames.push(tap_frame).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Забирает следующий кадр
+    pub fn pop(&self) -> Option<TapFrame> {
+        self.frames.pop()
+    }
+
+    pub fn snaplen(&self) -> usize {
+        self.snaplen
+    }
+
+    /// Кадры, не поместившиеся в кольцо с начала просмотра
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// Оборачивает обработчик: пакеты зеркалируются в кольцо перед обработкой
+pub fn tap_handler(tap: Arc<TapRing>, inner: PacketHandler) -> PacketHandler {
+    Arc::new(move |queue_id: u16, packet: &PacketView| {
+        tap.mirror(packet);
+        inner(queue_id, packet);
+    })
+}
@@ -0,0 +1,236 @@
+// src/replay/backend.rs
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use crate::backend::rx::{Backend, RxQueue, RxQueueInfo};
+use crate::replay::pcap::PcapReader;
+use crate::time;
+use crate::{log_info, log_warn};
+
+/// Темп воспроизведения записи
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// Исходные интервалы между кадрами, деленные на коэффициент
+    /// (1.0 - темп записи, 10.0 - в 10 раз быстрее)
+    Scaled(f64),
+    /// Без пауз: кадр передается, как только рабочий поток готов его принять
+    Max,
+}
+
+impl ReplaySpeed {
+    /// Разбирает `<коэффициент>` или `max`
+    pub fn parse(value: &str) -> Result<Self, String> {
+        if value == "max" {
+            return Ok(ReplaySpeed::Max);
+        }
+
+        match value.parse::<f64>() {
+            Ok(factor) if factor.is_finite() && factor > 0.0 => Ok(ReplaySpeed::Scaled(factor)),
+            _ => Err(format!(
+                "Invalid replay speed '{}': expected a positive factor or max",
+                value
+            )),
+        }
+    }
+}
+
+/// Общая шкала времени воспроизведения всех файлов
+///
+/// Кадр с отметкой `base_timestamp_ns` передается в момент `start_ns`
+/// (первый опрос любой очереди), остальные - со сдвигом по отметке, поэтому
+/// взаимный порядок кадров разных файлов сохраняется с точностью до опроса.
+struct ReplayClock {
+    base_timestamp_ns: u64,
+    start_ns: OnceLock<u64>,
+}
+
+/// Файл записи, воспроизводимый как порт с одной очередью
+struct ReplayFile {
+    port_id: u16,
+    path: String,
+    name: String,
+}
+
+/// Бэкенд воспроизведения записей pcap/pcapng для тестирования на истории
+///
+/// Кадры проходят тот же путь, что и принятые с сети: разбор, реестр
+/// обработчиков, фиды и метрики рабочих потоков. Каждый файл - порт с одной
+/// очередью; номера портов присваиваются в порядке `--replay-file`, начиная
+/// с 0. Порядок кадров внутри файла сохраняется; отметка приема - время
+/// передачи кадра, поэтому темп `Scaled(1.0)` воспроизводит исходные
+/// интервалы. Когда все файлы прочитаны, `is_finished` возвращает true.
+pub struct ReplayBackend {
+    files: Vec<ReplayFile>,
+    speed: ReplaySpeed,
+    clock: Arc<ReplayClock>,
+    remaining: Arc<AtomicUsize>,
+}
+
+impl ReplayBackend {
+    pub fn new(paths: &[String], speed: ReplaySpeed) -> Result<Self, String> {
+        if paths.is_empty() {
+            return Err("No files for replay backend (use --replay-file)".to_string());
+        }
+
+        let mut files = Vec::with_capacity(paths.len());
+        let mut base_timestamp_ns = None;
+        let mut frame = Vec::new();
+
+        for (port_id, path) in paths.iter().enumerate() {
+            // Первый кадр каждого файла задает начало общей шкалы времени
+            let mut reader = PcapReader::open(path)?;
+            if let Some(timestamp_ns) = reader.next_frame(&mut frame)? {
+                base_timestamp_ns = Some(
+                    base_timestamp_ns.map_or(timestamp_ns, |base: u64| base.min(timestamp_ns)),
+                );
+            }
+
+            let name = Path::new(path)
+                .file_name()
+                .map_or_else(|| path.clone(), |name| name.to_string_lossy().into_owned());
+
+            log_info!(
+                "replay",
+                "Replay file selected",
+                file = path,
+                port = port_id
+            );
+
+            files.push(ReplayFile {
+                port_id: port_id as u16,
+                path: path.clone(),
+                name,
+            });
+        }
+
+        Ok(Self {
+            remaining: Arc::new(AtomicUsize::new(files.len())),
+            files,
+            speed,
+            clock: Arc::new(ReplayClock {
+                base_timestamp_ns: base_timestamp_ns.unwrap_or(0),
+                start_ns: OnceLock::new(),
+            }),
+        })
+    }
+}
+
+impl Backend for ReplayBackend {
+    fn name(&self) -> &'static str {
+        "pcap"
+    }
+
+    fn rx_queues(&self) -> Vec<RxQueueInfo> {
+        self.files
+            .iter()
+            .map(|file| RxQueueInfo {
+                port_id: file.port_id,
+                queue_id: 0,
+                if_name: file.name.clone(),
+                numa_node: None,
+            })
+            .collect()
+    }
+
+    fn open_rx(&self, port_id: u16, _queue_id: u16) -> Result<Box<dyn RxQueue>, String> {
+        let file = self
+            .files
+            .iter()
+            .find(|file| file.port_id == port_id)
+            .ok_or_else(|| format!("Unknown replay port {}", port_id))?;
+
+        Ok(Box::new(ReplayQueue {
+            reader: PcapReader::open(&file.path)?,
+            path: file.path.clone(),
+            frame: Vec::with_capacity(2048),
+            pending: None,
+            speed: self.speed,
+            clock: self.clock.clone(),
+            remaining: self.remaining.clone(),
+            replayed: 0,
+            finished: false,
+        }))
+    }
+
+    fn is_finished(&self) -> bool {
+        self.remaining.load(Ordering::Acquire) == 0
+    }
+}
+
+/// Очередь воспроизведения одного файла
+struct ReplayQueue {
+    reader: PcapReader,
+    path: String,
+    frame: Vec<u8>,
+    /// Отметка кадра в `frame`, который еще не передан (ждет своего времени)
+    pending: Option<u64>,
+    speed: ReplaySpeed,
+    clock: Arc<ReplayClock>,
+    remaining: Arc<AtomicUsize>,
+    replayed: u64,
+    finished: bool,
+}
+
+impl ReplayQueue {
+    fn finish(&mut self) {
+        self.finished = true;
+        self.remaining.fetch_sub(1, Ordering::AcqRel);
+
+        log_info!(
+            "replay",
+            "Replay file finished",
+            file = self.path,
+            frames = self.replayed,
+            skipped = self.reader.skipped()
+        );
+    }
+}
+
+impl RxQueue for ReplayQueue {
+    fn rx_burst(&mut self, max: usize, on_frame: &mut dyn FnMut(&[u8])) -> usize {
+        if self.finished {
+            return 0;
+        }
+
+        let start_ns = *self.clock.start_ns.get_or_init(time::now_ns);
+        let now_ns = time::now_ns();
+        let mut received = 0;
+
+        while received < max {
+            let timestamp_ns = match self.pending {
+                Some(timestamp_ns) => timestamp_ns,
+                None => match self.reader.next_frame(&mut self.frame) {
+                    Ok(Some(timestamp_ns)) => {
+                        self.pending = Some(timestamp_ns);
+                        timestamp_ns
+                    }
+                    Ok(None) => {
+                        self.finish();
+                        break;
+                    }
+                    Err(e) => {
+                        log_warn!("replay", "Replay stopped on read error", error = e);
+                        self.finish();
+                        break;
+                    }
+                },
+            };
+
+            // Кадр ждет момента, соответствующего его отметке в записи
+            if let ReplaySpeed::Scaled(factor) = self.speed {
+                let offset_ns = timestamp_ns.saturating_sub(self.clock.base_timestamp_ns);
+                if start_ns + (offset_ns as f64 / factor) as u64 > now_ns {
+                    break;
+                }
+            }
+
+            on_frame(&self.frame);
+            self.pending = None;
+            self.replayed += 1;
+            received += 1;
+        }
+
+        received
+    }
+}
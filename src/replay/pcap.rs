@@ -0,0 +1,352 @@
+// src/replay/pcap.rs
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+
+/// Тип канального уровня: Ethernet
+const LINKTYPE_ETHERNET: u16 = 1;
+
+// Сигнатуры pcap (в порядке байт файла, прочитанные как little-endian)
+const PCAP_MAGIC_US: u32 = 0xa1b2_c3d4;
+const PCAP_MAGIC_US_SWAPPED: u32 = 0xd4c3_b2a1;
+const PCAP_MAGIC_NS: u32 = 0xa1b2_3c4d;
+const PCAP_MAGIC_NS_SWAPPED: u32 = 0x4d3c_b2a1;
+
+// Типы блоков pcapng
+const PCAPNG_SECTION_HEADER: u32 = 0x0a0d_0d0a;
+const PCAPNG_INTERFACE_DESCRIPTION: u32 = 1;
+const PCAPNG_SIMPLE_PACKET: u32 = 3;
+const PCAPNG_ENHANCED_PACKET: u32 = 6;
+const PCAPNG_BYTE_ORDER_MAGIC: u32 = 0x1a2b_3c4d;
+/// Параметр описания интерфейса: разрешение отметок времени
+const PCAPNG_OPT_IF_TSRESOL: u16 = 9;
+
+/// Наибольший размер блока или записи; больше - признак поврежденного файла
+const MAX_BLOCK_SIZE: usize = 16 * 1024 * 1024;
+
+/// Формат файла записи
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// Классический pcap; отметки времени в микро- или наносекундах
+    Pcap {
+        nanos: bool,
+    },
+    Pcapng,
+}
+
+/// Интерфейс секции pcapng
+#[derive(Debug, Clone, Copy)]
+struct PcapngInterface {
+    link_type: u16,
+    /// Единиц отметки времени в секунде
+    units_per_sec: u64,
+}
+
+/// Последовательное чтение кадров Ethernet из файла pcap или pcapng
+///
+/// Кадры других типов канального уровня пропускаются. Простые пакеты
+/// pcapng (без отметки времени) получают отметку предыдущего пакета.
+pub struct PcapReader {
+    reader: BufReader<File>,
+    path: String,
+    format: Format,
+    big_endian: bool,
+    /// Тип канального уровня классического pcap
+    link_type: u16,
+    /// Интерфейсы текущей секции pcapng
+    interfaces: Vec<PcapngInterface>,
+    last_timestamp_ns: u64,
+    block: Vec<u8>,
+    skipped: u64,
+}
+
+impl PcapReader {
+    /// Открывает файл и читает заголовок
+    pub fn open(path: &str) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+        let mut reader = Self {
+            reader: BufReader::with_capacity(1 << 20, file),
+            path: path.to_string(),
+            format: Format::Pcapng,
+            big_endian: false,
+            link_type: LINKTYPE_ETHERNET,
+            interfaces: Vec::new(),
+            last_timestamp_ns: 0,
+            block: Vec::new(),
+            skipped: 0,
+        };
+
+        let mut magic = [0u8; 4];
+        reader
+            .reader
+            .read_exact(&mut magic)
+            .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+        match u32::from_le_bytes(magic) {
+            PCAPNG_SECTION_HEADER => {
+                reader.read_section_header()?;
+            }
+            magic => {
+                let (nanos, big_endian) = match magic {
+                    PCAP_MAGIC_US => (false, false),
+                    PCAP_MAGIC_US_SWAPPED => (false, true),
+                    PCAP_MAGIC_NS => (true, false),
+                    PCAP_MAGIC_NS_SWAPPED => (true, true),
+                    _ => return Err(format!("{} is not a pcap or pcapng file", path)),
+                };
+                reader.format = Format::Pcap { nanos };
+                reader.big_endian = big_endian;
+
+                // version(4), thiszone(4), sigfigs(4), snaplen(4), network(4)
+                let mut header = [0u8; 20];
+                reader
+                    .reader
+                    .read_exact(&mut header)
+                    .map_err(|e| format!("Truncated pcap header in {}: {}", path, e))?;
+                let network = reader.u32_at(&header, 16);
+                if network > u16::MAX as u32 {
+                    return Err(format!("Invalid link type {} in {}", network, path));
+                }
+                reader.link_type = network as u16;
+                if reader.link_type != LINKTYPE_ETHERNET {
+                    return Err(format!(
+                        "{} has link type {}, only Ethernet captures can be replayed",
+                        path, reader.link_type
+                    ));
+                }
+            }
+        }
+
+        Ok(reader)
+    }
+
+    /// Читает следующий кадр Ethernet в `frame`; возвращает отметку времени
+    /// (нс от эпохи Unix) или None в конце файла
+    pub fn next_frame(&mut self, frame: &mut Vec<u8>) -> Result<Option<u64>, String> {
+        match self.format {
+            Format::Pcap { nanos } => self.next_pcap_frame(nanos, frame),
+            Format::Pcapng => self.next_pcapng_frame(frame),
+        }
+    }
+
+    /// Пропущенные записи (не Ethernet, неизвестные интерфейсы)
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    fn next_pcap_frame(&mut self, nanos: bool, frame: &mut Vec<u8>) -> Result<Option<u64>, String> {
+        let mut header = [0u8; 16];
+        if !self.read_or_eof(&mut header)? {
+            return Ok(None);
+        }
+
+        let ts_sec = self.u32_at(&header, 0) as u64;
+        let ts_frac = self.u32_at(&header, 4) as u64;
+        let caplen = self.u32_at(&header, 8) as usize;
+        if caplen > MAX_BLOCK_SIZE {
+            return Err(format!("Corrupt record length {} in {}", caplen, self.path));
+        }
+
+        frame.resize(caplen, 0);
+        self.read_exact(frame)?;
+
+        let timestamp_ns = ts_sec * 1_000_000_000 + if nanos { ts_frac } else { ts_frac * 1_000 };
+        self.last_timestamp_ns = timestamp_ns;
+        Ok(Some(timestamp_ns))
+    }
+
+    fn next_pcapng_frame(&mut self, frame: &mut Vec<u8>) -> Result<Option<u64>, String> {
+        loop {
+            let mut header = [0u8; 8];
+            if !self.read_or_eof(&mut header)? {
+                return Ok(None);
+            }
+
+            // Секция может сменить порядок байт: тип SHB симметричен
+            let block_type = self.u32_at(&header, 0);
+            if block_type == PCAPNG_SECTION_HEADER {
+                self.read_section_body(&header[4..8])?;
+                continue;
+            }
+
+            let total_len = self.u32_at(&header, 4) as usize;
+            if !(12..=MAX_BLOCK_SIZE).contains(&total_len) || !total_len.is_multiple_of(4) {
+                return Err(format!(
+                    "Corrupt pcapng block length {} in {}",
+                    total_len, self.path
+                ));
+            }
+
+            // Тело блока и завершающая копия длины
+            let mut block = std::mem::take(&mut self.block);
+            block.resize(total_len - 8, 0);
+            let read = self.read_exact(&mut block);
+            self.block = block;
+            read?;
+            let body = &self.block[..total_len - 12];
+
+            match block_type {
+                PCAPNG_INTERFACE_DESCRIPTION => {
+                    let interface = self.parse_interface(body)?;
+                    self.interfaces.push(interface);
+                }
+                PCAPNG_ENHANCED_PACKET => {
+                    if body.len() < 20 {
+                        return Err(format!("Truncated enhanced packet block in {}", self.path));
+                    }
+                    let interface_id = self.u32_at(body, 0) as usize;
+                    let ts = ((self.u32_at(body, 4) as u64) << 32) | self.u32_at(body, 8) as u64;
+                    let caplen = self.u32_at(body, 12) as usize;
+                    if 20 + caplen > body.len() {
+                        return Err(format!("Corrupt enhanced packet length in {}", self.path));
+                    }
+
+                    let interface = match self.interfaces.get(interface_id) {
+                        Some(interface) if interface.link_type == LINKTYPE_ETHERNET => *interface,
+                        _ => {
+                            self.skipped += 1;
+                            continue;
+                        }
+                    };
+
+                    let timestamp_ns =
+                        (ts as u128 * 1_000_000_000 / interface.units_per_sec as u128) as u64;
+                    frame.clear();
+                    frame.extend_from_slice(&self.block[20..20 + caplen]);
+                    self.last_timestamp_ns = timestamp_ns;
+                    return Ok(Some(timestamp_ns));
+                }
+                PCAPNG_SIMPLE_PACKET => {
+                    let ethernet = self
+                        .interfaces
+                        .first()
+                        .is_some_and(|interface| interface.link_type == LINKTYPE_ETHERNET);
+                    if body.len() < 4 || !ethernet {
+                        self.skipped += 1;
+                        continue;
+                    }
+                    // Длина данных - минимум из исходной длины и размера блока
+                    let orig_len = self.u32_at(body, 0) as usize;
+                    let caplen = orig_len.min(body.len() - 4);
+                    frame.clear();
+                    frame.extend_from_slice(&self.block[4..4 + caplen]);
+                    return Ok(Some(self.last_timestamp_ns));
+                }
+                // Статистика, разрешение имен и прочие блоки не нужны
+                _ => {}
+            }
+        }
+    }
+
+    /// Читает заголовок секции после сигнатуры, прочитанной в `open`
+    fn read_section_header(&mut self) -> Result<(), String> {
+        let mut len = [0u8; 4];
+        self.read_exact(&mut len)?;
+        self.read_section_body(&len)
+    }
+
+    /// Читает тело SHB; `len` - поле длины блока в порядке байт секции
+    fn read_section_body(&mut self, len: &[u8]) -> Result<(), String> {
+        let mut magic = [0u8; 4];
+        self.read_exact(&mut magic)?;
+        self.big_endian = match u32::from_le_bytes(magic) {
+            PCAPNG_BYTE_ORDER_MAGIC => false,
+            magic if magic.swap_bytes() == PCAPNG_BYTE_ORDER_MAGIC => true,
+            _ => return Err(format!("Invalid pcapng byte order magic in {}", self.path)),
+        };
+
+        let total_len = self.u32_at(len, 0) as usize;
+        if !(28..=MAX_BLOCK_SIZE).contains(&total_len) || !total_len.is_multiple_of(4) {
+            return Err(format!("Corrupt pcapng section header in {}", self.path));
+        }
+        // Версия, длина секции, параметры и завершающая копия длины
+        let mut rest = vec![0u8; total_len - 12];
+        self.read_exact(&mut rest)?;
+
+        // Номера интерфейсов действуют в пределах секции
+        self.interfaces.clear();
+        Ok(())
+    }
+
+    fn parse_interface(&self, body: &[u8]) -> Result<PcapngInterface, String> {
+        if body.len() < 8 {
+            return Err(format!("Truncated interface description in {}", self.path));
+        }
+
+        let mut interface = PcapngInterface {
+            link_type: self.u16_at(body, 0),
+            units_per_sec: 1_000_000,
+        };
+
+        // Параметры: код(2), длина(2), значение с выравниванием до 4 байт
+        let mut offset = 8;
+        while offset + 4 <= body.len() {
+            let code = self.u16_at(body, offset);
+            let len = self.u16_at(body, offset + 2) as usize;
+            let value = offset + 4;
+            if code == 0 || value + len > body.len() {
+                break;
+            }
+
+            if code == PCAPNG_OPT_IF_TSRESOL && len >= 1 {
+                let resolution = body[value];
+                let exponent = (resolution & 0x7f) as u32;
+                let units = if resolution & 0x80 == 0 {
+                    10u64.checked_pow(exponent)
+                } else {
+                    2u64.checked_pow(exponent)
+                };
+                interface.units_per_sec = units
+                    .filter(|&units| units > 0)
+                    .ok_or_else(|| format!("Unsupported timestamp resolution in {}", self.path))?;
+            }
+
+            offset = value + len.div_ceil(4) * 4;
+        }
+
+        Ok(interface)
+    }
+
+    /// Читает `buf` целиком; false - конец файла до первого байта
+    fn read_or_eof(&mut self, buf: &mut [u8]) -> Result<bool, String> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            match self.reader.read(&mut buf[filled..]) {
+                Ok(0) if filled == 0 => return Ok(false),
+                Ok(0) => return Err(format!("Truncated record in {}", self.path)),
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(format!("Failed to read {}: {}", self.path, e)),
+            }
+        }
+        Ok(true)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), String> {
+        self.reader
+            .read_exact(buf)
+            .map_err(|e| format!("Truncated record in {}: {}", self.path, e))
+    }
+
+    fn u16_at(&self, buf: &[u8], offset: usize) -> u16 {
+        let bytes = [buf[offset], buf[offset + 1]];
+        if self.big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        }
+    }
+
+    fn u32_at(&self, buf: &[u8], offset: usize) -> u32 {
+        let bytes = [
+            buf[offset],
+            buf[offset + 1],
+            buf[offset + 2],
+            buf[offset + 3],
+        ];
+        if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        }
+    }
+}
@@ -0,0 +1,126 @@
+// src/strategy/experiment.rs
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::strategy::rng::{fnv1a_update, FNV1A_OFFSET};
+
+/// Вариант стратегии в эксперименте
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Variant {
+    A,
+    B,
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Variant::A => write!(f, "A"),
+            Variant::B => write!(f, "B"),
+        }
+    }
+}
+
+/// Результат назначения: какой вариант обслуживает ключ и в каком эксперименте
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Assignment<'a> {
+    pub experiment: &'a str,
+    pub variant: Variant,
+}
+
+impl Assignment<'_> {
+    /// Тег для атрибуции в журнале, например `spread_v2:B`
+    pub fn tag(&self) -> String {
+        format!("{}:{}", self.experiment, self.variant)
+    }
+}
+
+/// Маршрутизатор A/B эксперимента
+///
+/// Ключ (инструмент или идентификатор потока заявок) детерминированно
+/// назначается варианту по хешу от имени эксперимента и ключа, поэтому одно и
+/// то же назначение получается на любом сервере и при любом перезапуске.
+pub struct ExperimentRouter {
+    /// Имя эксперимента (используется как соль хеша)
+    name: String,
+    /// Доля ключей, направляемых в вариант B, в базисных пунктах (0..=10000)
+    b_share_bps: u32,
+    /// Явно закрепленные назначения
+    pinned: HashMap<String, Variant>,
+    /// Счетчики маршрутизированных событий по вариантам
+    routed_a: AtomicU64,
+    routed_b: AtomicU64,
+}
+
+impl ExperimentRouter {
+    /// Создает эксперимент с долей варианта B в базисных пунктах
+    pub fn new(name: &str, b_share_bps: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            b_share_bps: b_share_bps.min(10_000),
+            pinned: HashMap::new(),
+            routed_a: AtomicU64::new(0),
+            routed_b: AtomicU64::new(0),
+        }
+    }
+
+    /// Закрепляет ключ за конкретным вариантом независимо от хеша
+    pub fn with_pinned(mut self, key: &str, variant: Variant) -> Self {
+        self.pinned.insert(key.to_string(), variant);
+        self
+    }
+
+    /// Возвращает имя эксперимента
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Определяет вариант для ключа без учета статистики
+    pub fn variant_for(&self, key: &str) -> Variant {
+        if let Some(&variant) = self.pinned.get(key) {
+            return variant;
+        }
+
+        let bucket = (bucket_hash(&self.name, key) % 10_000) as u32;
+        if bucket < self.b_share_bps {
+            Variant::B
+        } else {
+            Variant::A
+        }
+    }
+
+    /// Назначает вариант для ключа и учитывает событие в статистике
+    #[inline]
+    pub fn route(&self, key: &str) -> Assignment<'_> {
+        let variant = self.variant_for(key);
+
+        match variant {
+            Variant::A => self.routed_a.fetch_add(1, Ordering::Relaxed),
+            Variant::B => self.routed_b.fetch_add(1, Ordering::Relaxed),
+        };
+
+        Assignment {
+            experiment: &self.name,
+            variant,
+        }
+    }
+
+    /// Возвращает количество событий, направленных в варианты A и B
+    pub fn routed_counts(&self) -> (u64, u64) {
+        (
+            self.routed_a.load(Ordering::Relaxed),
+            self.routed_b.load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// FNV-1a от имени эксперимента и ключа с финальным перемешиванием
+fn bucket_hash(salt: &str, key: &str) -> u64 {
+    let mut hash = fnv1a_update(FNV1A_OFFSET, salt.as_bytes());
+    hash = fnv1a_update(hash, &[0]);
+    hash = fnv1a_update(hash, key.as_bytes());
+
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^ (hash >> 33)
+}
@@ -0,0 +1,3 @@
+pub mod experiment;
+pub mod own_orders;
+pub mod rng;
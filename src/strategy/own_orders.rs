@@ -0,0 +1,122 @@
+// src/strategy/own_orders.rs
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+use crate::control::snapshot::ConfigSnapshot;
+use crate::control::state::StateSource;
+
+/// Происхождение ликвидности в публичном фиде
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Liquidity {
+    /// Собственная заявка фирмы, отраженная в публичном фиде
+    Own(OwnOrder),
+    /// Внешняя ликвидность
+    External,
+}
+
+impl Liquidity {
+    pub fn is_own(&self) -> bool {
+        matches!(self, Liquidity::Own(_))
+    }
+}
+
+/// Сведения о собственной заявке, зарегистрированные OMS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OwnOrder {
+    /// Внутренний идентификатор заявки (client order id)
+    pub client_order_id: u64,
+    /// Идентификатор стратегии, выставившей заявку
+    pub strategy_id: u32,
+}
+
+/// Реестр собственных заявок для распознавания их эха в публичном фиде
+///
+/// OMS регистрирует биржевой идентификатор заявки после подтверждения и
+/// удаляет его после исполнения или отмены; обработчики рыночных данных
+/// сопоставляют идентификаторы заявок из фида и помечают собственную
+/// ликвидность. Для фидов без идентификаторов заявок сопоставление невозможно
+/// и все события считаются внешними.
+pub struct OwnOrderRegistry {
+    orders: RwLock<HashMap<u64, OwnOrder>>,
+    matched: AtomicU64,
+}
+
+impl OwnOrderRegistry {
+    pub fn new() -> Self {
+        Self {
+            orders: RwLock::new(HashMap::new()),
+            matched: AtomicU64::new(0),
+        }
+    }
+
+    /// Регистрирует подтвержденную биржей заявку
+    pub fn register(&self, exchange_order_id: u64, order: OwnOrder) {
+        if let Ok(mut orders) = self.orders.write() {
+            orders.insert(exchange_order_id, order);
+        }
+    }
+
+    /// Удаляет заявку после исполнения или отмены
+    pub fn remove(&self, exchange_order_id: u64) -> Option<OwnOrder> {
+        self.orders.write().ok()?.remove(&exchange_order_id)
+    }
+
+    /// Определяет происхождение заявки из публичного фида
+    #[inline]
+    pub fn classify(&self, exchange_order_id: u64) -> Liquidity {
+        let own = self
+            .orders
+            .read()
+            .ok()
+            .and_then(|orders| orders.get(&exchange_order_id).copied());
+
+        match own {
+            Some(order) => {
+                self.matched.fetch_add(1, Ordering::Relaxed);
+                Liquidity::Own(order)
+            }
+            None => Liquidity::External,
+        }
+    }
+
+    /// Количество активных собственных заявок
+    pub fn len(&self) -> usize {
+        self.orders.read().map(|orders| orders.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Количество событий фида, распознанных как собственные
+    pub fn matched(&self) -> u64 {
+        self.matched.load(Ordering::Relaxed)
+    }
+}
+
+impl StateSource for OwnOrderRegistry {
+    fn capture(&self, prefix: &str, state: &mut ConfigSnapshot) {
+        state.set(&format!("{}.matched", prefix), self.matched());
+
+        let Ok(orders) = self.orders.read() else {
+            return;
+        };
+        state.set(&format!("{}.orders", prefix), orders.len());
+        for (exchange_order_id, order) in orders.iter() {
+            state.set(
+                &format!("{}.order.{}", prefix, exchange_order_id),
+                format_args!(
+                    "client_order_id={} strategy={}",
+                    order.client_order_id, order.strategy_id
+                ),
+            );
+        }
+    }
+}
+
+impl Default for OwnOrderRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,193 @@
+// src/strategy/rng.rs
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Сервис детерминированных генераторов случайных чисел для стратегий
+///
+/// Каждая стратегия получает собственный поток, seed которого выводится из
+/// мастер-seed и имени стратегии. Выданные seed'ы запоминаются, чтобы их можно
+/// было записать в журнал и воспроизвести те же решения при повторном прогоне.
+pub struct RngService {
+    /// Мастер-seed всего процесса
+    master_seed: u64,
+    /// Выданные потоки: имя стратегии -> seed
+    issued: Mutex<HashMap<String, u64>>,
+    /// Явно заданные seed'ы (при воспроизведении из журнала)
+    overrides: HashMap<String, u64>,
+}
+
+impl RngService {
+    /// Создает сервис с указанным мастер-seed
+    pub fn new(master_seed: u64) -> Self {
+        Self {
+            master_seed,
+            issued: Mutex::new(HashMap::new()),
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// Создает сервис для воспроизведения: seed'ы берутся из ранее записанного журнала
+    pub fn from_journal(master_seed: u64, seeds: &[(String, u64)]) -> Self {
+        let mut service = Self::new(master_seed);
+        service.overrides = seeds.iter().cloned().collect();
+        service
+    }
+
+    /// Возвращает мастер-seed
+    pub fn master_seed(&self) -> u64 {
+        self.master_seed
+    }
+
+    /// Вычисляет seed для стратегии
+    pub fn seed_for(&self, strategy: &str) -> u64 {
+        if let Some(&seed) = self.overrides.get(strategy) {
+            return seed;
+        }
+
+        let mut state = self.master_seed ^ fnv1a(strategy.as_bytes());
+        splitmix64(&mut state)
+    }
+
+    /// Выдает генератор для стратегии и фиксирует его seed
+    pub fn stream(&self, strategy: &str) -> StrategyRng {
+        let seed = self.seed_for(strategy);
+
+        self.issued
+            .lock()
+            .unwrap()
+            .insert(strategy.to_string(), seed);
+
+        StrategyRng::new(seed)
+    }
+
+    /// Возвращает все выданные seed'ы в детерминированном порядке (для записи в журнал)
+    pub fn issued_seeds(&self) -> Vec<(String, u64)> {
+        let issued = self.issued.lock().unwrap();
+        let mut seeds: Vec<(String, u64)> = issued.iter().map(|(k, &v)| (k.clone(), v)).collect();
+        seeds.sort();
+        seeds
+    }
+}
+
+/// Генератор xoshiro256** с подсчетом количества выборок
+///
+/// Пара (seed, draws) полностью описывает состояние генератора, поэтому
+/// ее достаточно сохранить для точного восстановления.
+#[derive(Debug, Clone)]
+pub struct StrategyRng {
+    seed: u64,
+    state: [u64; 4],
+    draws: u64,
+}
+
+impl StrategyRng {
+    /// Создает генератор из seed
+    pub fn new(seed: u64) -> Self {
+        let mut sm = seed;
+        let state = [
+            splitmix64(&mut sm),
+            splitmix64(&mut sm),
+            splitmix64(&mut sm),
+            splitmix64(&mut sm),
+        ];
+
+        Self {
+            seed,
+            state,
+            draws: 0,
+        }
+    }
+
+    /// Восстанавливает генератор в состояние после `draws` выборок
+    pub fn restore(seed: u64, draws: u64) -> Self {
+        let mut rng = Self::new(seed);
+        for _ in 0..draws {
+            rng.next_u64();
+        }
+        rng
+    }
+
+    /// Возвращает seed генератора
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Возвращает количество выполненных выборок
+    pub fn draws(&self) -> u64 {
+        self.draws
+    }
+
+    /// Следующее 64-битное значение
+    #[inline(always)]
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+
+        self.draws += 1;
+        result
+    }
+
+    /// Равномерное значение в диапазоне [0, 1)
+    #[inline(always)]
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// Равномерное целое в диапазоне [low, high)
+    #[inline(always)]
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        if high <= low {
+            return low;
+        }
+
+        let span = high - low;
+        low + ((self.next_u64() as u128 * span as u128) >> 64) as u64
+    }
+
+    /// Добавляет к значению случайный разброс в пределах ±`max_jitter`
+    /// (например, для размера заявки или задержки отправки)
+    #[inline(always)]
+    pub fn jitter(&mut self, value: u64, max_jitter: u64) -> u64 {
+        if max_jitter == 0 {
+            return value;
+        }
+
+        let offset = self.range(0, max_jitter.saturating_mul(2).saturating_add(1));
+        value.saturating_add(offset).saturating_sub(max_jitter)
+    }
+}
+
+/// SplitMix64 - используется для развертки seed в состояние генератора
+#[inline(always)]
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Начальное значение FNV-1a
+pub(crate) const FNV1A_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// FNV-1a хеш - стабилен между версиями компилятора, в отличие от DefaultHasher
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    fnv1a_update(FNV1A_OFFSET, bytes)
+}
+
+/// Продолжает FNV-1a хеш `hash` байтами `bytes` (для хеширования составных ключей)
+pub(crate) fn fnv1a_update(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &b in bytes {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
@@ -0,0 +1,5 @@
+// src/time/mod.rs
+pub mod tsc;
+pub mod wheel;
+
+pub use tsc::now_ns;
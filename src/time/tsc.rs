@@ -0,0 +1,166 @@
+// src/time/tsc.rs
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Длительность калибровки TSC относительно CLOCK_MONOTONIC
+const CALIBRATION_PERIOD: Duration = Duration::from_millis(50);
+/// Сдвиг фиксированной точки множителя тактов в наносекунды
+const MULT_SHIFT: u32 = 32;
+
+/// Часы на основе инвариантного TSC, откалиброванные по CLOCK_MONOTONIC
+///
+/// Время считается как `base_ns + ((tsc - base_tsc) * mult) >> MULT_SHIFT`,
+/// что на горячем пути сводится к rdtsc и одному умножению.
+#[derive(Debug, Clone, Copy)]
+pub struct TscClock {
+    base_tsc: u64,
+    base_ns: u64,
+    mult: u64,
+    tsc_hz: u64,
+    invariant: bool,
+}
+
+static CLOCK: OnceLock<TscClock> = OnceLock::new();
+
+impl TscClock {
+    /// Калибрует TSC: измеряет число тактов за `CALIBRATION_PERIOD` монотонного времени
+    ///
+    /// Без инвариантного TSC (или на не-x86 платформах) часы работают через
+    /// clock_gettime(CLOCK_MONOTONIC).
+    pub fn calibrate() -> Self {
+        let invariant = has_invariant_tsc();
+        if !invariant {
+            return Self {
+                base_tsc: 0,
+                base_ns: monotonic_ns(),
+                mult: 0,
+                tsc_hz: 0,
+                invariant: false,
+            };
+        }
+
+        let (start_tsc, start_ns) = sample_pair();
+        std::thread::sleep(CALIBRATION_PERIOD);
+        let (end_tsc, end_ns) = sample_pair();
+
+        let ticks = end_tsc.wrapping_sub(start_tsc).max(1);
+        let elapsed_ns = end_ns - start_ns;
+
+        let mult = ((elapsed_ns as u128) << MULT_SHIFT) / ticks as u128;
+        let tsc_hz = (ticks as u128 * 1_000_000_000 / elapsed_ns.max(1) as u128) as u64;
+
+        Self {
+            base_tsc: end_tsc,
+            base_ns: end_ns,
+            mult: mult as u64,
+            tsc_hz,
+            invariant: true,
+        }
+    }
+
+    /// Текущее время в наносекундах (шкала CLOCK_MONOTONIC)
+    #[inline(always)]
+    pub fn now_ns(&self) -> u64 {
+        if !self.invariant {
+            return monotonic_ns();
+        }
+
+        self.ticks_to_ns(rdtsc())
+    }
+
+    /// Переводит значение счетчика TSC в наносекунды шкалы часов
+    #[inline(always)]
+    pub fn ticks_to_ns(&self, tsc: u64) -> u64 {
+        let delta = tsc.wrapping_sub(self.base_tsc);
+        self.base_ns + ((delta as u128 * self.mult as u128) >> MULT_SHIFT) as u64
+    }
+
+    /// Частота TSC в герцах (0, если используется CLOCK_MONOTONIC)
+    pub fn tsc_hz(&self) -> u64 {
+        self.tsc_hz
+    }
+
+    pub fn is_invariant(&self) -> bool {
+        self.invariant
+    }
+}
+
+/// Глобальные часы процесса; калибруются при первом обращении
+pub fn clock() -> &'static TscClock {
+    CLOCK.get_or_init(TscClock::calibrate)
+}
+
+/// Калибрует глобальные часы заранее, чтобы первое обращение на горячем пути
+/// не ждало калибровки
+pub fn init() -> &'static TscClock {
+    clock()
+}
+
+/// Текущее время в наносекундах
+#[inline(always)]
+pub fn now_ns() -> u64 {
+    clock().now_ns()
+}
+
+/// Читает счетчик TSC
+#[inline(always)]
+pub fn rdtsc() -> u64 {
+    #[cfg(target_arch = "x86_64")]
+    unsafe {
+        core::arch::x86_64::_rdtsc()
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        monotonic_ns()
+    }
+}
+
+/// Проверяет флаг инвариантного TSC (CPUID 0x80000007, EDX бит 8)
+fn has_invariant_tsc() -> bool {
+    // __cpuid стал безопасной функцией в новых версиях компилятора
+    #[cfg(target_arch = "x86_64")]
+    #[allow(unused_unsafe)]
+    unsafe {
+        use core::arch::x86_64::__cpuid;
+
+        if __cpuid(0x8000_0000).eax < 0x8000_0007 {
+            return false;
+        }
+        __cpuid(0x8000_0007).edx & (1 << 8) != 0
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        false
+    }
+}
+
+/// Снимает пару (TSC, CLOCK_MONOTONIC) с минимальным окном между чтениями
+fn sample_pair() -> (u64, u64) {
+    let mut best = (0, 0);
+    let mut best_window = u64::MAX;
+
+    for _ in 0..16 {
+        let before = rdtsc();
+        let ns = monotonic_ns();
+        let after = rdtsc();
+
+        let window = after.wrapping_sub(before);
+        if window < best_window {
+            best_window = window;
+            best = (before + window / 2, ns);
+        }
+    }
+
+    best
+}
+
+fn monotonic_ns() -> u64 {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts) };
+    ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64
+}
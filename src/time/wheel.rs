@@ -0,0 +1,481 @@
+// src/time/wheel.rs
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, TryLockError};
+use std::time::Duration;
+
+use crate::time;
+
+/// Бит номера слота на уровень (64 слота - маска занятости в одном u64)
+const SLOT_BITS: u32 = 6;
+const SLOTS: usize = 1 << SLOT_BITS;
+/// Уровней колеса: 64^6 тактов (около 19 часов при такте 1 мкс)
+const LEVELS: usize = 6;
+/// Такт колеса общей службы таймеров
+const DEFAULT_TICK_NS: u64 = 1_000;
+
+/// Идентификатор таймера (номер записи и поколение)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerId {
+    index: u32,
+    generation: u32,
+}
+
+/// Состояние записи таймера
+enum EntryState<T> {
+    Free,
+    /// Таймер в колесе
+    Armed(T),
+    /// Периодический таймер сработал, обработчик еще не вернул его (`restore`)
+    Firing,
+}
+
+struct Entry<T> {
+    generation: u32,
+    /// Срок в тактах колеса
+    deadline: u64,
+    /// Период в тактах (0 - однократный)
+    period: u64,
+    state: EntryState<T>,
+}
+
+/// Иерархическое колесо таймеров
+///
+/// Шесть уровней по 64 слота: уровень L хранит таймеры, срок которых
+/// отличается от текущего такта в битах уровня L. При достижении слота
+/// верхнего уровня его таймеры переносятся на нижние, поэтому постановка и
+/// отмена - O(1), а продвижение по пустым тактам пропускается по маскам
+/// занятости. Отмена ленивая: запись освобождается сразу, а ее след в слоте
+/// отбрасывается по поколению при обходе. Таймеры дальше диапазона колеса
+/// хранятся отдельно до перехода в их блок.
+pub struct TimerWheel<T> {
+    tick_ns: u64,
+    /// Последний обработанный такт
+    elapsed: u64,
+    entries: Vec<Entry<T>>,
+    free: Vec<u32>,
+    slots: Vec<Vec<TimerId>>,
+    occupied: [u64; LEVELS],
+    overflow: Vec<TimerId>,
+    armed: usize,
+}
+
+impl<T> TimerWheel<T> {
+    /// Создает колесо с тактом `tick_ns`, начиная с момента `now_ns`
+    pub fn new(tick_ns: u64, now_ns: u64) -> Self {
+        let tick_ns = tick_ns.max(1);
+        Self {
+            tick_ns,
+            elapsed: now_ns / tick_ns,
+            entries: Vec::new(),
+            free: Vec::new(),
+            slots: (0..LEVELS * SLOTS).map(|_| Vec::new()).collect(),
+            occupied: [0; LEVELS],
+            overflow: Vec::new(),
+            armed: 0,
+        }
+    }
+
+    /// Такт колеса, нс
+    pub fn tick_ns(&self) -> u64 {
+        self.tick_ns
+    }
+
+    /// Число взведенных таймеров
+    pub fn len(&self) -> usize {
+        self.armed
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.armed == 0
+    }
+
+    /// Ставит таймер на момент `deadline_ns`; `period_ns` > 0 - периодический
+    ///
+    /// Срок в прошлом срабатывает при следующем `advance`.
+    pub fn insert(&mut self, deadline_ns: u64, period_ns: u64, payload: T) -> TimerId {
+        let deadline = deadline_ns.div_ceil(self.tick_ns).max(self.elapsed + 1);
+        let period = if period_ns > 0 {
+            period_ns.div_ceil(self.tick_ns)
+        } else {
+            0
+        };
+
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.entries.push(Entry {
+                    generation: 0,
+                    deadline: 0,
+                    period: 0,
+                    state: EntryState::Free,
+                });
+                (self.entries.len() - 1) as u32
+            }
+        };
+
+        let entry = &mut self.entries[index as usize];
+        entry.deadline = deadline;
+        entry.period = period;
+        entry.state = EntryState::Armed(payload);
+        let id = TimerId {
+            index,
+            generation: entry.generation,
+        };
+
+        self.armed += 1;
+        self.place(id, deadline);
+        id
+    }
+
+    /// Отменяет таймер; false, если он уже сработал или отменен
+    ///
+    /// Периодический таймер, отмененный во время срабатывания, не
+    /// возвращается в колесо.
+    pub fn cancel(&mut self, id: TimerId) -> bool {
+        let Some(entry) = self.entries.get_mut(id.index as usize) else {
+            return false;
+        };
+        if entry.generation != id.generation {
+            return false;
+        }
+        let was_armed = match entry.state {
+            EntryState::Free => return false,
+            EntryState::Armed(_) => true,
+            EntryState::Firing => false,
+        };
+        if was_armed {
+            self.armed -= 1;
+        }
+        self.release(id.index);
+        true
+    }
+
+    /// Продвигает колесо до момента `now_ns` и переносит сработавшие таймеры в `expired`
+    ///
+    /// Однократные таймеры освобождаются. Периодические ждут `restore`,
+    /// который ставит их на следующий период.
+    pub fn advance(&mut self, now_ns: u64, expired: &mut Vec<(TimerId, T)>) {
+        let target = now_ns / self.tick_ns;
+
+        while self.elapsed < target {
+            match self.next_slot() {
+                Some((level, slot, deadline)) if deadline <= target => {
+                    self.elapsed = self.elapsed.max(deadline);
+                    self.drain_slot(level, slot, expired);
+                }
+                _ => {
+                    let block = LEVELS as u32 * SLOT_BITS;
+                    if target >> block != self.elapsed >> block && !self.overflow.is_empty() {
+                        // Переход в следующий блок: дальние таймеры раскладываются заново
+                        self.elapsed = ((self.elapsed >> block) + 1) << block;
+                        for id in std::mem::take(&mut self.overflow) {
+                            self.reinsert(id, expired);
+                        }
+                    } else {
+                        self.elapsed = target;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Возвращает сработавший периодический таймер в колесо; false - таймер
+    /// однократный или отменен во время срабатывания (`payload` отбрасывается)
+    pub fn restore(&mut self, id: TimerId, payload: T) -> bool {
+        let Some(entry) = self.entries.get_mut(id.index as usize) else {
+            return false;
+        };
+        if entry.generation != id.generation || !matches!(entry.state, EntryState::Firing) {
+            return false;
+        }
+
+        // Пропущенные периоды не догоняются
+        let mut deadline = entry.deadline + entry.period;
+        if deadline <= self.elapsed {
+            let missed = (self.elapsed - deadline) / entry.period + 1;
+            deadline += missed * entry.period;
+        }
+        entry.deadline = deadline;
+        entry.state = EntryState::Armed(payload);
+
+        self.armed += 1;
+        self.place(id, deadline);
+        true
+    }
+
+    /// Ближайший возможный срок срабатывания, нс (None - таймеров нет)
+    ///
+    /// Для таймеров верхних уровней возвращается начало слота, поэтому срок
+    /// может быть раньше фактического, но не позже.
+    pub fn next_deadline_ns(&self) -> Option<u64> {
+        if self.armed == 0 {
+            return None;
+        }
+        let wheel = self.next_slot().map(|(_, _, deadline)| deadline);
+        let overflow = self
+            .overflow
+            .iter()
+            .filter_map(|id| self.live_deadline(*id))
+            .min();
+        let deadline = match (wheel, overflow) {
+            (Some(a), Some(b)) => a.min(b),
+            (a, b) => a.or(b)?,
+        };
+        Some(deadline * self.tick_ns)
+    }
+
+    /// Раскладывает таймер по уровню старшего отличающегося от текущего такта бита
+    fn place(&mut self, id: TimerId, deadline: u64) {
+        let diff = deadline ^ self.elapsed;
+        let level = ((63 - diff.leading_zeros()) / SLOT_BITS) as usize;
+        if level >= LEVELS {
+            self.overflow.push(id);
+            return;
+        }
+
+        let slot = ((deadline >> (level as u32 * SLOT_BITS)) as usize) & (SLOTS - 1);
+        self.slots[level * SLOTS + slot].push(id);
+        self.occupied[level] |= 1 << slot;
+    }
+
+    /// Ближайший занятый слот: (уровень, слот, такт начала слота)
+    ///
+    /// Все таймеры нижнего уровня срабатывают раньше таймеров верхнего,
+    /// поэтому достаточно первого уровня с занятыми слотами.
+    fn next_slot(&self) -> Option<(usize, usize, u64)> {
+        for level in 0..LEVELS {
+            let shift = level as u32 * SLOT_BITS;
+            let current = ((self.elapsed >> shift) as usize) & (SLOTS - 1);
+            let pending = self.occupied[level] & (u64::MAX << current);
+            if pending == 0 {
+                continue;
+            }
+
+            let slot = pending.trailing_zeros() as usize;
+            let level_start = self.elapsed & !((1u64 << (shift + SLOT_BITS)) - 1);
+            let deadline = level_start + ((slot as u64) << shift);
+            return Some((level, slot, deadline.max(self.elapsed)));
+        }
+        None
+    }
+
+    /// Разбирает слот: наступившие таймеры срабатывают, остальные спускаются ниже
+    fn drain_slot(&mut self, level: usize, slot: usize, expired: &mut Vec<(TimerId, T)>) {
+        self.occupied[level] &= !(1 << slot);
+        let ids = std::mem::take(&mut self.slots[level * SLOTS + slot]);
+        for id in ids {
+            self.reinsert(id, expired);
+        }
+    }
+
+    fn reinsert(&mut self, id: TimerId, expired: &mut Vec<(TimerId, T)>) {
+        let Some(deadline) = self.live_deadline(id) else {
+            return;
+        };
+        if deadline > self.elapsed {
+            self.place(id, deadline);
+            return;
+        }
+
+        let entry = &mut self.entries[id.index as usize];
+        let EntryState::Armed(payload) = std::mem::replace(&mut entry.state, EntryState::Firing)
+        else {
+            return;
+        };
+        self.armed -= 1;
+        if entry.period == 0 {
+            self.release(id.index);
+        }
+        expired.push((id, payload));
+    }
+
+    /// Срок взведенного таймера (None - след отмененного)
+    fn live_deadline(&self, id: TimerId) -> Option<u64> {
+        let entry = self.entries.get(id.index as usize)?;
+        (entry.generation == id.generation && matches!(entry.state, EntryState::Armed(_)))
+            .then_some(entry.deadline)
+    }
+
+    fn release(&mut self, index: u32) {
+        let entry = &mut self.entries[index as usize];
+        entry.state = EntryState::Free;
+        entry.generation = entry.generation.wrapping_add(1);
+        self.free.push(index);
+    }
+}
+
+/// Обработчик срабатывания таймера
+type TimerCallback = Box<dyn FnMut() + Send>;
+
+/// Общая служба таймеров протоколов (heartbeat, повторы, ожидание пропусков, сессии)
+///
+/// Таймеры проверяются из простаивающих рабочих потоков и с служебного
+/// ядра; системные таймеры и сигналы не используются. Проверка до срока -
+/// чтение TSC и одна атомарная загрузка; колесо блокируется через
+/// `try_lock`, поэтому рабочий поток никогда не ждет другой поток, а
+/// обработчики выполняются вне блокировки и могут ставить и отменять таймеры.
+/// Обработчик выполняется на том потоке, который первым заметил срок,
+/// поэтому должен быть коротким.
+pub struct Timers {
+    wheel: Mutex<TimerWheel<TimerCallback>>,
+    /// Ближайший срок, нс (u64::MAX - таймеров нет)
+    next_deadline_ns: AtomicU64,
+    fired: AtomicU64,
+}
+
+impl Timers {
+    pub fn new() -> Self {
+        Self::with_tick(Duration::from_nanos(DEFAULT_TICK_NS))
+    }
+
+    /// Служба с заданным тактом колеса (точность срабатывания)
+    pub fn with_tick(tick: Duration) -> Self {
+        Self {
+            wheel: Mutex::new(TimerWheel::new(tick.as_nanos() as u64, time::now_ns())),
+            next_deadline_ns: AtomicU64::new(u64::MAX),
+            fired: AtomicU64::new(0),
+        }
+    }
+
+    /// Однократный таймер через `delay`
+    pub fn schedule(&self, delay: Duration, callback: impl FnMut() + Send + 'static) -> TimerId {
+        self.schedule_at(time::now_ns() + delay.as_nanos() as u64, callback)
+    }
+
+    /// Однократный таймер на момент `deadline_ns` (шкала `time::now_ns`)
+    pub fn schedule_at(
+        &self,
+        deadline_ns: u64,
+        callback: impl FnMut() + Send + 'static,
+    ) -> TimerId {
+        let mut wheel = self.lock();
+        let id = wheel.insert(deadline_ns, 0, Box::new(callback));
+        self.publish_deadline(&wheel);
+        id
+    }
+
+    /// Периодический таймер с периодом `period` (первое срабатывание через период)
+    pub fn schedule_every(
+        &self,
+        period: Duration,
+        callback: impl FnMut() + Send + 'static,
+    ) -> TimerId {
+        let period_ns = (period.as_nanos() as u64).max(1);
+        let mut wheel = self.lock();
+        let id = wheel.insert(time::now_ns() + period_ns, period_ns, Box::new(callback));
+        self.publish_deadline(&wheel);
+        id
+    }
+
+    /// Отменяет таймер; false, если он уже сработал или отменен
+    pub fn cancel(&self, id: TimerId) -> bool {
+        let mut wheel = self.lock();
+        let cancelled = wheel.cancel(id);
+        self.publish_deadline(&wheel);
+        cancelled
+    }
+
+    /// Переставляет однократный таймер на `delay` от текущего момента
+    /// (сдвиг таймаута сессии при каждом сообщении)
+    pub fn reschedule(
+        &self,
+        id: TimerId,
+        delay: Duration,
+        callback: impl FnMut() + Send + 'static,
+    ) -> TimerId {
+        let mut wheel = self.lock();
+        wheel.cancel(id);
+        let id = wheel.insert(
+            time::now_ns() + delay.as_nanos() as u64,
+            0,
+            Box::new(callback),
+        );
+        self.publish_deadline(&wheel);
+        id
+    }
+
+    /// Выполняет наступившие таймеры; возвращает число сработавших
+    ///
+    /// Вызывается из циклов опроса с уже снятой отметкой времени. Если
+    /// колесо занято другим потоком, проверка пропускается до следующего вызова.
+    #[inline(always)]
+    pub fn poll(&self, now_ns: u64) -> usize {
+        if now_ns < self.next_deadline_ns.load(Ordering::Acquire) {
+            return 0;
+        }
+        self.fire(now_ns)
+    }
+
+    /// Число взведенных таймеров
+    pub fn pending(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Число сработавших таймеров с запуска
+    pub fn fired(&self) -> u64 {
+        self.fired.load(Ordering::Relaxed)
+    }
+
+    #[cold]
+    fn fire(&self, now_ns: u64) -> usize {
+        let mut expired = Vec::new();
+        {
+            let mut wheel = match self.wheel.try_lock() {
+                Ok(wheel) => wheel,
+                Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+                Err(TryLockError::WouldBlock) => return 0,
+            };
+            wheel.advance(now_ns, &mut expired);
+            self.publish_deadline(&wheel);
+        }
+        if expired.is_empty() {
+            return 0;
+        }
+
+        for (_, callback) in expired.iter_mut() {
+            callback();
+        }
+        self.fired
+            .fetch_add(expired.len() as u64, Ordering::Relaxed);
+
+        let count = expired.len();
+        let mut wheel = self.lock();
+        for (id, callback) in expired {
+            wheel.restore(id, callback);
+        }
+        self.publish_deadline(&wheel);
+        count
+    }
+
+    fn lock(&self) -> MutexGuard<'_, TimerWheel<TimerCallback>> {
+        self.wheel
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn publish_deadline(&self, wheel: &TimerWheel<TimerCallback>) {
+        self.next_deadline_ns.store(
+            wheel.next_deadline_ns().unwrap_or(u64::MAX),
+            Ordering::Release,
+        );
+    }
+}
+
+impl fmt::Debug for Timers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Timers")
+            .field(
+                "next_deadline_ns",
+                &self.next_deadline_ns.load(Ordering::Relaxed),
+            )
+            .field("fired", &self.fired())
+            .finish()
+    }
+}
+
+impl Default for Timers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
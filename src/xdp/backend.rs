@@ -0,0 +1,138 @@
+// src/xdp/backend.rs
+use std::fs;
+
+use crate::backend::rx::{Backend, InterfaceSpec, RxQueue, RxQueueInfo};
+use crate::log_info;
+use crate::numa::topology::NumaTopology;
+use crate::xdp::ffi;
+use crate::xdp::program::{XdpMode, XdpProgram};
+use crate::xdp::socket::{XskConfig, XskSocket};
+
+/// Интерфейс с подключенной программой перенаправления
+struct XdpInterface {
+    port_id: u16,
+    if_name: String,
+    ifindex: u32,
+    num_queues: u16,
+    numa_node: Option<usize>,
+    program: XdpProgram,
+}
+
+/// Бэкенд приема через сокеты AF_XDP
+///
+/// Не требует привязки NIC к vfio и EAL: интерфейс остается в ядре, а
+/// программа XDP перенаправляет кадры опрашиваемых очередей в сокеты
+/// (остальной трафик идет в сетевой стек). Номера портов в реестре
+/// обработчиков присваиваются в порядке `--xdp-interface`, начиная с 0.
+/// Нужны права CAP_NET_ADMIN, CAP_BPF (или CAP_SYS_ADMIN) и Linux 5.9+.
+pub struct XdpBackend {
+    interfaces: Vec<XdpInterface>,
+    socket_config: XskConfig,
+}
+
+impl XdpBackend {
+    pub fn new(specs: &[InterfaceSpec], mode: XdpMode) -> Result<Self, String> {
+        if specs.is_empty() {
+            return Err("No interfaces for AF_XDP backend (use --xdp-interface)".to_string());
+        }
+
+        let topology = NumaTopology::new().ok();
+        let mut interfaces = Vec::with_capacity(specs.len());
+
+        for (port_id, spec) in specs.iter().enumerate() {
+            let ifindex = spec.ifindex()?;
+            let available = rx_queue_count(&spec.if_name);
+            let num_queues = match spec.num_queues {
+                Some(count) if available > 0 && count > available => {
+                    return Err(format!(
+                        "{} has {} RX queues, {} requested",
+                        spec.if_name, available, count
+                    ))
+                }
+                Some(count) => count,
+                None => available.max(1),
+            };
+
+            let program = XdpProgram::attach(&spec.if_name, ifindex, num_queues, mode)?;
+            let numa_node = topology
+                .as_ref()
+                .and_then(|topology| topology.get_nic_node(&spec.if_name));
+
+            log_info!(
+                "xdp",
+                "XDP program attached",
+                interface = spec.if_name,
+                port = port_id,
+                queues = num_queues,
+                node = numa_node.map_or("unknown".to_string(), |node| node.to_string())
+            );
+
+            interfaces.push(XdpInterface {
+                port_id: port_id as u16,
+                if_name: spec.if_name.clone(),
+                ifindex,
+                num_queues,
+                numa_node,
+                program,
+            });
+        }
+
+        let socket_config = XskConfig {
+            bind_flags: match mode {
+                XdpMode::Auto => 0,
+                XdpMode::Generic => ffi::XDP_COPY,
+                XdpMode::Native => ffi::XDP_ZEROCOPY,
+            },
+            ..XskConfig::default()
+        };
+
+        Ok(Self {
+            interfaces,
+            socket_config,
+        })
+    }
+}
+
+impl Backend for XdpBackend {
+    fn name(&self) -> &'static str {
+        "af_xdp"
+    }
+
+    fn rx_queues(&self) -> Vec<RxQueueInfo> {
+        self.interfaces
+            .iter()
+            .flat_map(|interface| {
+                (0..interface.num_queues).map(move |queue_id| RxQueueInfo {
+                    port_id: interface.port_id,
+                    queue_id,
+                    if_name: interface.if_name.clone(),
+                    numa_node: interface.numa_node,
+                })
+            })
+            .collect()
+    }
+
+    fn open_rx(&self, port_id: u16, queue_id: u16) -> Result<Box<dyn RxQueue>, String> {
+        let interface = self
+            .interfaces
+            .iter()
+            .find(|interface| interface.port_id == port_id)
+            .ok_or_else(|| format!("Unknown AF_XDP port {}", port_id))?;
+
+        let socket = XskSocket::open(interface.ifindex, queue_id, &self.socket_config)?;
+        interface.program.register_socket(queue_id, socket.fd())?;
+        Ok(Box::new(socket))
+    }
+}
+
+/// Число очередей RX интерфейса по sysfs (0, если неизвестно)
+fn rx_queue_count(if_name: &str) -> u16 {
+    fs::read_dir(format!("/sys/class/net/{}/queues", if_name))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.file_name().to_string_lossy().starts_with("rx-"))
+                .count() as u16
+        })
+        .unwrap_or(0)
+}
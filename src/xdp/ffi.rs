@@ -0,0 +1,176 @@
+// src/xdp/ffi.rs
+// Определения UAPI Linux для AF_XDP (linux/if_xdp.h) и bpf(2) (linux/bpf.h)
+//
+// Объявлены вручную: бэкенд не зависит от libbpf/libxdp и заголовков ядра.
+use std::os::raw::{c_int, c_long};
+
+pub const AF_XDP: c_int = 44;
+pub const SOL_XDP: c_int = 283;
+
+// Параметры сокета (setsockopt/getsockopt SOL_XDP)
+pub const XDP_MMAP_OFFSETS: c_int = 1;
+pub const XDP_RX_RING: c_int = 2;
+pub const XDP_UMEM_REG: c_int = 4;
+pub const XDP_UMEM_FILL_RING: c_int = 5;
+pub const XDP_UMEM_COMPLETION_RING: c_int = 6;
+pub const XDP_STATISTICS: c_int = 7;
+
+// Флаги bind
+pub const XDP_COPY: u16 = 1 << 1;
+pub const XDP_ZEROCOPY: u16 = 1 << 2;
+pub const XDP_USE_NEED_WAKEUP: u16 = 1 << 3;
+
+/// Флаг кольца: ядру нужно напомнить о новых буферах
+pub const XDP_RING_NEED_WAKEUP: u32 = 1 << 0;
+
+// Смещения mmap колец
+pub const XDP_PGOFF_RX_RING: libc::off_t = 0;
+pub const XDP_UMEM_PGOFF_FILL_RING: libc::off_t = 0x1_0000_0000;
+pub const XDP_UMEM_PGOFF_COMPLETION_RING: libc::off_t = 0x1_8000_0000;
+
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct SockaddrXdp {
+    pub sxdp_family: u16,
+    pub sxdp_flags: u16,
+    pub sxdp_ifindex: u32,
+    pub sxdp_queue_id: u32,
+    pub sxdp_shared_umem_fd: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct XdpUmemReg {
+    pub addr: u64,
+    pub len: u64,
+    pub chunk_size: u32,
+    pub headroom: u32,
+    pub flags: u32,
+    pub tx_metadata_len: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct XdpRingOffset {
+    pub producer: u64,
+    pub consumer: u64,
+    pub desc: u64,
+    pub flags: u64,
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct XdpMmapOffsets {
+    pub rx: XdpRingOffset,
+    pub tx: XdpRingOffset,
+    pub fr: XdpRingOffset,
+    pub cr: XdpRingOffset,
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct XdpDesc {
+    pub addr: u64,
+    pub len: u32,
+    pub options: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct XdpStatistics {
+    pub rx_dropped: u64,
+    pub rx_invalid_descs: u64,
+    pub tx_invalid_descs: u64,
+    pub rx_ring_full: u64,
+    pub rx_fill_ring_empty_descs: u64,
+    pub tx_ring_empty_descs: u64,
+}
+
+// Команды bpf(2)
+pub const BPF_MAP_CREATE: c_int = 0;
+pub const BPF_MAP_UPDATE_ELEM: c_int = 2;
+pub const BPF_PROG_LOAD: c_int = 5;
+pub const BPF_LINK_CREATE: c_int = 28;
+
+pub const BPF_MAP_TYPE_XSKMAP: u32 = 17;
+pub const BPF_PROG_TYPE_XDP: u32 = 6;
+pub const BPF_XDP: u32 = 37;
+
+// Флаги подключения программы XDP
+pub const XDP_FLAGS_SKB_MODE: u32 = 1 << 1;
+pub const XDP_FLAGS_DRV_MODE: u32 = 1 << 2;
+
+/// Код возврата XDP: передать пакет в сетевой стек
+pub const XDP_PASS: i32 = 2;
+/// Вспомогательная функция bpf_redirect_map
+pub const BPF_FUNC_REDIRECT_MAP: i32 = 51;
+/// Источник ld_imm64: дескриптор карты
+pub const BPF_PSEUDO_MAP_FD: u8 = 1;
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BpfInsn {
+    pub code: u8,
+    /// Младшие 4 бита - dst_reg, старшие - src_reg
+    pub regs: u8,
+    pub off: i16,
+    pub imm: i32,
+}
+
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct BpfMapCreateAttr {
+    pub map_type: u32,
+    pub key_size: u32,
+    pub value_size: u32,
+    pub max_entries: u32,
+    pub map_flags: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct BpfMapElemAttr {
+    pub map_fd: u32,
+    pub _pad: u32,
+    pub key: u64,
+    pub value: u64,
+    pub flags: u64,
+}
+
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct BpfProgLoadAttr {
+    pub prog_type: u32,
+    pub insn_cnt: u32,
+    pub insns: u64,
+    pub license: u64,
+    pub log_level: u32,
+    pub log_size: u32,
+    pub log_buf: u64,
+    pub kern_version: u32,
+    pub prog_flags: u32,
+    pub prog_name: [u8; 16],
+}
+
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct BpfLinkCreateAttr {
+    pub prog_fd: u32,
+    pub target_ifindex: u32,
+    pub attach_type: u32,
+    pub flags: u32,
+}
+
+/// Вызывает bpf(2) с атрибутами `attr`
+///
+/// # Safety
+/// `attr` должен соответствовать команде `cmd`; указатели внутри атрибутов
+/// должны быть действительны на время вызова.
+pub unsafe fn bpf<T>(cmd: c_int, attr: &mut T) -> c_long {
+    libc::syscall(
+        libc::SYS_bpf,
+        cmd,
+        attr as *mut T,
+        std::mem::size_of::<T>() as u32,
+    )
+}
@@ -0,0 +1,4 @@
+pub mod backend;
+pub mod ffi;
+pub mod program;
+pub mod socket;
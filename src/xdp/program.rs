@@ -0,0 +1,178 @@
+// src/xdp/program.rs
+use std::io;
+use std::os::raw::c_int;
+
+use crate::xdp::ffi::{self, BpfInsn};
+
+/// Режим подключения программы XDP к интерфейсу
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XdpMode {
+    /// Драйверный режим, если драйвер его поддерживает, иначе универсальный
+    Auto,
+    /// Универсальный режим (SKB): работает с любым драйвером, с копированием
+    Generic,
+    /// Драйверный режим; сокеты используют нулевое копирование
+    Native,
+}
+
+impl XdpMode {
+    /// Разбирает `auto`, `generic` или `native`
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "auto" => Ok(XdpMode::Auto),
+            "generic" => Ok(XdpMode::Generic),
+            "native" => Ok(XdpMode::Native),
+            other => Err(format!(
+                "Invalid XDP mode '{}': expected auto, generic or native",
+                other
+            )),
+        }
+    }
+}
+
+/// Программа XDP, перенаправляющая кадры очереди в сокет AF_XDP
+///
+/// Эквивалент стандартной программы libxdp:
+/// `return bpf_redirect_map(&xsks_map, ctx->rx_queue_index, XDP_PASS);`
+/// Кадры очередей без сокета передаются в сетевой стек. Программа
+/// подключается через bpf_link (Linux 5.9+) и отключается при закрытии
+/// дескриптора, в том числе при аварийном завершении процесса.
+pub struct XdpProgram {
+    map_fd: c_int,
+    prog_fd: c_int,
+    link_fd: c_int,
+    if_name: String,
+}
+
+impl XdpProgram {
+    /// Загружает программу и подключает ее к интерфейсу `ifindex`
+    pub fn attach(
+        if_name: &str,
+        ifindex: u32,
+        num_queues: u16,
+        mode: XdpMode,
+    ) -> Result<Self, String> {
+        let mut program = Self {
+            map_fd: -1,
+            prog_fd: -1,
+            link_fd: -1,
+            if_name: if_name.to_string(),
+        };
+
+        let mut map_attr = ffi::BpfMapCreateAttr {
+            map_type: ffi::BPF_MAP_TYPE_XSKMAP,
+            key_size: 4,
+            value_size: 4,
+            max_entries: num_queues.max(1) as u32,
+            map_flags: 0,
+        };
+        program.map_fd = bpf_fd(ffi::BPF_MAP_CREATE, &mut map_attr)
+            .map_err(|e| format!("Failed to create XSKMAP for {}: {}", if_name, e))?;
+
+        let insns = redirect_program(program.map_fd);
+        let license = b"GPL\0";
+        let mut log = vec![0u8; 4096];
+        let mut prog_name = [0u8; 16];
+        prog_name[..10].copy_from_slice(b"hfeec_xsks");
+        let mut prog_attr = ffi::BpfProgLoadAttr {
+            prog_type: ffi::BPF_PROG_TYPE_XDP,
+            insn_cnt: insns.len() as u32,
+            insns: insns.as_ptr() as u64,
+            license: license.as_ptr() as u64,
+            log_level: 1,
+            log_size: log.len() as u32,
+            log_buf: log.as_mut_ptr() as u64,
+            prog_name,
+            ..Default::default()
+        };
+        program.prog_fd = bpf_fd(ffi::BPF_PROG_LOAD, &mut prog_attr).map_err(|e| {
+            let verifier = String::from_utf8_lossy(&log);
+            format!(
+                "Failed to load XDP program for {}: {} {}",
+                if_name,
+                e,
+                verifier.trim_end_matches('\0').trim()
+            )
+        })?;
+
+        let mut link_attr = ffi::BpfLinkCreateAttr {
+            prog_fd: program.prog_fd as u32,
+            target_ifindex: ifindex,
+            attach_type: ffi::BPF_XDP,
+            flags: match mode {
+                XdpMode::Auto => 0,
+                XdpMode::Generic => ffi::XDP_FLAGS_SKB_MODE,
+                XdpMode::Native => ffi::XDP_FLAGS_DRV_MODE,
+            },
+        };
+        program.link_fd = bpf_fd(ffi::BPF_LINK_CREATE, &mut link_attr)
+            .map_err(|e| format!("Failed to attach XDP program to {}: {}", if_name, e))?;
+
+        Ok(program)
+    }
+
+    /// Направляет кадры очереди `queue_id` в сокет `xsk_fd`
+    pub fn register_socket(&self, queue_id: u16, xsk_fd: c_int) -> Result<(), String> {
+        let key = queue_id as u32;
+        let value = xsk_fd as u32;
+        let mut attr = ffi::BpfMapElemAttr {
+            map_fd: self.map_fd as u32,
+            key: &key as *const u32 as u64,
+            value: &value as *const u32 as u64,
+            ..Default::default()
+        };
+
+        if unsafe { ffi::bpf(ffi::BPF_MAP_UPDATE_ELEM, &mut attr) } < 0 {
+            return Err(format!(
+                "Failed to register {} queue {} socket: {}",
+                self.if_name,
+                queue_id,
+                io::Error::last_os_error()
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for XdpProgram {
+    fn drop(&mut self) {
+        for fd in [self.link_fd, self.prog_fd, self.map_fd] {
+            if fd >= 0 {
+                unsafe { libc::close(fd) };
+            }
+        }
+    }
+}
+
+/// Выполняет команду bpf(2), возвращающую дескриптор
+fn bpf_fd<T>(cmd: c_int, attr: &mut T) -> io::Result<c_int> {
+    let fd = unsafe { ffi::bpf(cmd, attr) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(fd as c_int)
+}
+
+/// Собирает инструкции программы перенаправления
+fn redirect_program(map_fd: c_int) -> [BpfInsn; 6] {
+    let insn = |code: u8, dst: u8, src: u8, off: i16, imm: i32| BpfInsn {
+        code,
+        regs: (src << 4) | dst,
+        off,
+        imm,
+    };
+
+    [
+        // r2 = ctx->rx_queue_index (struct xdp_md, смещение 16)
+        insn(0x61, 2, 1, 16, 0),
+        // r1 = &xsks_map (ld_imm64, вторая половина - старшие 32 бита)
+        insn(0x18, 1, ffi::BPF_PSEUDO_MAP_FD, 0, map_fd),
+        insn(0x00, 0, 0, 0, 0),
+        // r3 = XDP_PASS: действие, если для очереди нет сокета
+        insn(0xb7, 3, 0, 0, ffi::XDP_PASS),
+        // r0 = bpf_redirect_map(r1, r2, r3)
+        insn(0x85, 0, 0, 0, ffi::BPF_FUNC_REDIRECT_MAP),
+        // return r0
+        insn(0x95, 0, 0, 0, 0),
+    ]
+}
@@ -0,0 +1,433 @@
+// src/xdp/socket.rs
+use std::io;
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::backend::rx::RxQueue;
+use crate::xdp::ffi::{self, XdpDesc};
+
+/// Размеры области UMEM и колец сокета
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct XskConfig {
+    /// Число кадров UMEM (степень двойки); все кадры сразу отдаются в кольцо заполнения
+    pub frames: u32,
+    /// Размер кадра UMEM (2048 или 4096)
+    pub frame_size: u32,
+    /// Размер кольца RX (степень двойки)
+    pub rx_ring_size: u32,
+    /// Флаги bind: режим копирования (`XDP_COPY`/`XDP_ZEROCOPY`, 0 - выбирает ядро)
+    pub bind_flags: u16,
+}
+
+impl Default for XskConfig {
+    fn default() -> Self {
+        Self {
+            frames: 4096,
+            frame_size: 4096,
+            rx_ring_size: 2048,
+            bind_flags: 0,
+        }
+    }
+}
+
+/// Отображенное кольцо сокета AF_XDP (один производитель, один потребитель)
+struct XskRing {
+    producer: *const AtomicU32,
+    consumer: *const AtomicU32,
+    flags: *const AtomicU32,
+    desc: *mut u8,
+    mask: u32,
+    size: u32,
+    map: *mut c_void,
+    map_len: usize,
+}
+
+impl XskRing {
+    /// Отображает кольцо из `size` элементов размером `entry_size`
+    fn map(
+        fd: c_int,
+        offsets: &ffi::XdpRingOffset,
+        size: u32,
+        entry_size: usize,
+        pgoff: libc::off_t,
+    ) -> io::Result<Self> {
+        let map_len = offsets.desc as usize + size as usize * entry_size;
+        let map = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                map_len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED | libc::MAP_POPULATE,
+                fd,
+                pgoff,
+            )
+        };
+        if map == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        let base = map as *mut u8;
+        unsafe {
+            Ok(Self {
+                producer: base.add(offsets.producer as usize) as *const AtomicU32,
+                consumer: base.add(offsets.consumer as usize) as *const AtomicU32,
+                flags: base.add(offsets.flags as usize) as *const AtomicU32,
+                desc: base.add(offsets.desc as usize),
+                mask: size - 1,
+                size,
+                map,
+                map_len,
+            })
+        }
+    }
+
+    /// Элементы, готовые к чтению (для колец, где процесс - потребитель)
+    #[inline(always)]
+    fn available(&self) -> (u32, u32) {
+        let producer = unsafe { (*self.producer).load(Ordering::Acquire) };
+        let consumer = unsafe { (*self.consumer).load(Ordering::Relaxed) };
+        (consumer, producer.wrapping_sub(consumer))
+    }
+
+    #[inline(always)]
+    fn release(&self, count: u32) {
+        unsafe { (*self.consumer).fetch_add(count, Ordering::Release) };
+    }
+
+    /// Свободные элементы (для колец, где процесс - производитель)
+    #[inline(always)]
+    fn free(&self) -> (u32, u32) {
+        let producer = unsafe { (*self.producer).load(Ordering::Relaxed) };
+        let consumer = unsafe { (*self.consumer).load(Ordering::Acquire) };
+        (producer, self.size - producer.wrapping_sub(consumer))
+    }
+
+    #[inline(always)]
+    fn submit(&self, count: u32) {
+        unsafe { (*self.producer).fetch_add(count, Ordering::Release) };
+    }
+
+    #[inline(always)]
+    fn needs_wakeup(&self) -> bool {
+        unsafe { (*self.flags).load(Ordering::Relaxed) & ffi::XDP_RING_NEED_WAKEUP != 0 }
+    }
+
+    #[inline(always)]
+    fn rx_desc(&self, idx: u32) -> XdpDesc {
+        unsafe { *(self.desc as *const XdpDesc).add((idx & self.mask) as usize) }
+    }
+
+    #[inline(always)]
+    fn set_addr(&self, idx: u32, addr: u64) {
+        unsafe { *(self.desc as *mut u64).add((idx & self.mask) as usize) = addr };
+    }
+}
+
+impl Drop for XskRing {
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.map, self.map_len) };
+    }
+}
+
+/// Сокет AF_XDP одной очереди RX со своей областью UMEM
+///
+/// Кадры принимаются в UMEM без копирования в сокет и возвращаются в кольцо
+/// заполнения сразу после обработки пачки.
+pub struct XskSocket {
+    fd: c_int,
+    umem: *mut c_void,
+    umem_len: usize,
+    frame_size: u32,
+    rx: XskRing,
+    fill: XskRing,
+    // Кольцо завершения обязательно для UMEM, хотя сокет не передает кадры
+    _completion: XskRing,
+    /// Адреса кадров текущей пачки для возврата в кольцо заполнения
+    recycled: Vec<u64>,
+}
+
+// Кольца и UMEM принадлежат рабочему потоку сокета
+unsafe impl Send for XskSocket {}
+
+impl XskSocket {
+    /// Создает сокет и привязывает его к очереди `queue_id` интерфейса `ifindex`
+    pub fn open(ifindex: u32, queue_id: u16, config: &XskConfig) -> Result<Self, String> {
+        let fd = unsafe { libc::socket(ffi::AF_XDP, libc::SOCK_RAW | libc::SOCK_CLOEXEC, 0) };
+        if fd < 0 {
+            return Err(format!(
+                "Failed to create AF_XDP socket: {}",
+                io::Error::last_os_error()
+            ));
+        }
+
+        // Сокет закрывается при любой ошибке ниже
+        let guard = FdGuard(fd);
+
+        let umem_len = config.frames as usize * config.frame_size as usize;
+        let umem = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                umem_len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_POPULATE,
+                -1,
+                0,
+            )
+        };
+        if umem == libc::MAP_FAILED {
+            return Err(format!(
+                "Failed to allocate UMEM ({} bytes): {}",
+                umem_len,
+                io::Error::last_os_error()
+            ));
+        }
+        let umem_guard = MapGuard(umem, umem_len);
+
+        let reg = ffi::XdpUmemReg {
+            addr: umem as u64,
+            len: umem_len as u64,
+            chunk_size: config.frame_size,
+            headroom: 0,
+            flags: 0,
+            tx_metadata_len: 0,
+        };
+        set_option(fd, ffi::XDP_UMEM_REG, &reg, "XDP_UMEM_REG")?;
+        set_option(
+            fd,
+            ffi::XDP_UMEM_FILL_RING,
+            &config.frames,
+            "XDP_UMEM_FILL_RING",
+        )?;
+        set_option(
+            fd,
+            ffi::XDP_UMEM_COMPLETION_RING,
+            &config.rx_ring_size,
+            "XDP_UMEM_COMPLETION_RING",
+        )?;
+        set_option(fd, ffi::XDP_RX_RING, &config.rx_ring_size, "XDP_RX_RING")?;
+
+        let mut offsets = ffi::XdpMmapOffsets::default();
+        let mut len = std::mem::size_of::<ffi::XdpMmapOffsets>() as libc::socklen_t;
+        let rc = unsafe {
+            libc::getsockopt(
+                fd,
+                ffi::SOL_XDP,
+                ffi::XDP_MMAP_OFFSETS,
+                &mut offsets as *mut _ as *mut c_void,
+                &mut len,
+            )
+        };
+        if rc < 0 {
+            return Err(format!(
+                "Failed to query AF_XDP ring offsets: {}",
+                io::Error::last_os_error()
+            ));
+        }
+        // Без поля flags (Linux < 5.4) режим need_wakeup недоступен
+        if len as usize != std::mem::size_of::<ffi::XdpMmapOffsets>() {
+            return Err("AF_XDP requires Linux 5.4 or newer".to_string());
+        }
+
+        let map_err =
+            |ring: &str, e: io::Error| format!("Failed to map AF_XDP {} ring: {}", ring, e);
+        let fill = XskRing::map(
+            fd,
+            &offsets.fr,
+            config.frames,
+            std::mem::size_of::<u64>(),
+            ffi::XDP_UMEM_PGOFF_FILL_RING,
+        )
+        .map_err(|e| map_err("fill", e))?;
+        let completion = XskRing::map(
+            fd,
+            &offsets.cr,
+            config.rx_ring_size,
+            std::mem::size_of::<u64>(),
+            ffi::XDP_UMEM_PGOFF_COMPLETION_RING,
+        )
+        .map_err(|e| map_err("completion", e))?;
+        let rx = XskRing::map(
+            fd,
+            &offsets.rx,
+            config.rx_ring_size,
+            std::mem::size_of::<XdpDesc>(),
+            ffi::XDP_PGOFF_RX_RING,
+        )
+        .map_err(|e| map_err("rx", e))?;
+
+        // Отдаем ядру все кадры UMEM
+        let (producer, _) = fill.free();
+        for frame in 0..config.frames {
+            fill.set_addr(
+                producer.wrapping_add(frame),
+                frame as u64 * config.frame_size as u64,
+            );
+        }
+        fill.submit(config.frames);
+
+        let addr = ffi::SockaddrXdp {
+            sxdp_family: ffi::AF_XDP as u16,
+            sxdp_flags: config.bind_flags | ffi::XDP_USE_NEED_WAKEUP,
+            sxdp_ifindex: ifindex,
+            sxdp_queue_id: queue_id as u32,
+            sxdp_shared_umem_fd: 0,
+        };
+        let rc = unsafe {
+            libc::bind(
+                fd,
+                &addr as *const ffi::SockaddrXdp as *const libc::sockaddr,
+                std::mem::size_of::<ffi::SockaddrXdp>() as libc::socklen_t,
+            )
+        };
+        if rc < 0 {
+            return Err(format!(
+                "Failed to bind AF_XDP socket to queue {}: {}",
+                queue_id,
+                io::Error::last_os_error()
+            ));
+        }
+
+        std::mem::forget(guard);
+        std::mem::forget(umem_guard);
+        Ok(Self {
+            fd,
+            umem,
+            umem_len,
+            frame_size: config.frame_size,
+            rx,
+            fill,
+            _completion: completion,
+            recycled: Vec::with_capacity(config.rx_ring_size as usize),
+        })
+    }
+
+    pub fn fd(&self) -> c_int {
+        self.fd
+    }
+
+    /// Статистика сокета, которую ведет ядро
+    pub fn statistics(&self) -> Option<ffi::XdpStatistics> {
+        let mut stats = ffi::XdpStatistics::default();
+        let mut len = std::mem::size_of::<ffi::XdpStatistics>() as libc::socklen_t;
+        let rc = unsafe {
+            libc::getsockopt(
+                self.fd,
+                ffi::SOL_XDP,
+                ffi::XDP_STATISTICS,
+                &mut stats as *mut _ as *mut c_void,
+                &mut len,
+            )
+        };
+        (rc == 0).then_some(stats)
+    }
+
+    /// Напоминает ядру о буферах в кольце заполнения
+    #[cold]
+    fn wakeup(&self) {
+        unsafe {
+            libc::recvfrom(
+                self.fd,
+                ptr::null_mut(),
+                0,
+                libc::MSG_DONTWAIT,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            )
+        };
+    }
+}
+
+impl RxQueue for XskSocket {
+    #[inline]
+    fn rx_burst(&mut self, max: usize, on_frame: &mut dyn FnMut(&[u8])) -> usize {
+        let (consumer, available) = self.rx.available();
+        let count = available.min(max as u32);
+        if count == 0 {
+            if self.fill.needs_wakeup() {
+                self.wakeup();
+            }
+            return 0;
+        }
+
+        self.recycled.clear();
+        for i in 0..count {
+            let desc = self.rx.rx_desc(consumer.wrapping_add(i));
+            let frame = unsafe {
+                std::slice::from_raw_parts(
+                    (self.umem as *const u8).add(desc.addr as usize),
+                    desc.len as usize,
+                )
+            };
+            on_frame(frame);
+            // Адрес в дескрипторе может включать смещение внутри кадра
+            self.recycled
+                .push(desc.addr - desc.addr % self.frame_size as u64);
+        }
+        self.rx.release(count);
+
+        // Кольцо заполнения вмещает все кадры UMEM, поэтому место есть всегда
+        let (producer, _) = self.fill.free();
+        for (i, &addr) in self.recycled.iter().enumerate() {
+            self.fill.set_addr(producer.wrapping_add(i as u32), addr);
+        }
+        self.fill.submit(count);
+        if self.fill.needs_wakeup() {
+            self.wakeup();
+        }
+
+        count as usize
+    }
+
+    fn dropped(&self) -> u64 {
+        self.statistics()
+            .map_or(0, |stats| stats.rx_dropped + stats.rx_ring_full)
+    }
+}
+
+impl Drop for XskSocket {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.fd);
+            libc::munmap(self.umem, self.umem_len);
+        }
+    }
+}
+
+/// Закрывает дескриптор, если создание сокета прервано ошибкой
+struct FdGuard(c_int);
+
+impl Drop for FdGuard {
+    fn drop(&mut self) {
+        unsafe { libc::close(self.0) };
+    }
+}
+
+/// Освобождает UMEM, если создание сокета прервано ошибкой
+struct MapGuard(*mut c_void, usize);
+
+impl Drop for MapGuard {
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.0, self.1) };
+    }
+}
+
+fn set_option<T>(fd: c_int, option: c_int, value: &T, name: &str) -> Result<(), String> {
+    let rc = unsafe {
+        libc::setsockopt(
+            fd,
+            ffi::SOL_XDP,
+            option,
+            value as *const T as *const c_void,
+            std::mem::size_of::<T>() as libc::socklen_t,
+        )
+    };
+    if rc < 0 {
+        return Err(format!(
+            "Failed to set {}: {}",
+            name,
+            io::Error::last_os_error()
+        ));
+    }
+    Ok(())
+}